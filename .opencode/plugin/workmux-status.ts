@@ -1,22 +1,43 @@
 import type { Plugin } from '@opencode-ai/plugin';
 
-export const WorkmuxStatusPlugin: Plugin = async ({ $ }) => {
+// Time between heartbeats while the session is busy, well under
+// workmux's default heartbeat_timeout
+const HEARTBEAT_INTERVAL_MS = 60_000;
+
+export const WorkmuxStatusPlugin: Plugin = async ({ $, serverUrl }) => {
+  // Report the server URL so the workmux dashboard can pull detailed progress
+  const server = serverUrl ? ['--server-url', serverUrl.toString()] : [];
+
+  // Tell workmux the agent is alive during long tool calls
+  let heartbeat: ReturnType<typeof setInterval> | undefined;
+  const startHeartbeat = () => {
+    heartbeat ??= setInterval(() => {
+      $`workmux set-window-status heartbeat`.quiet().catch(() => {});
+    }, HEARTBEAT_INTERVAL_MS);
+  };
+  const stopHeartbeat = () => {
+    clearInterval(heartbeat);
+    heartbeat = undefined;
+  };
+
   return {
     event: async ({ event }) => {
       switch (event.type) {
         case 'session.status':
           if (event.properties.status.type === 'busy') {
-            await $`workmux set-window-status working`.quiet();
+            startHeartbeat();
+            await $`workmux set-window-status working ${server}`.quiet();
           }
           break;
         case 'permission.updated':
-          await $`workmux set-window-status waiting`.quiet();
+          await $`workmux set-window-status waiting ${server}`.quiet();
           break;
         case 'permission.replied':
-          await $`workmux set-window-status working`.quiet();
+          await $`workmux set-window-status working ${server}`.quiet();
           break;
         case 'session.idle':
-          await $`workmux set-window-status done`.quiet();
+          stopHeartbeat();
+          await $`workmux set-window-status done ${server}`.quiet();
           break;
       }
     },
@@ -112,6 +112,7 @@ export default defineConfig({
           { text: "Session mode", link: "/guide/session-mode" },
           { text: "direnv", link: "/guide/direnv" },
           { text: "Monorepos", link: "/guide/monorepos" },
+          { text: "Plugins", link: "/guide/plugins" },
           { text: "Worktree caveats", link: "/guide/git-worktree-caveats" },
           { text: "Nix", link: "/guide/nix" },
         ],
@@ -121,17 +122,37 @@ export default defineConfig({
         items: [
           { text: "add", link: "/reference/commands/add" },
           { text: "merge", link: "/reference/commands/merge" },
+          { text: "pr", link: "/reference/commands/pr" },
           { text: "remove", link: "/reference/commands/remove" },
+          { text: "restore-branch", link: "/reference/commands/restore-branch" },
+          { text: "archive", link: "/reference/commands/archive" },
+          { text: "reattach", link: "/reference/commands/reattach" },
           { text: "list", link: "/reference/commands/list" },
           { text: "open", link: "/reference/commands/open" },
           { text: "close", link: "/reference/commands/close" },
+          { text: "pane", link: "/reference/commands/pane" },
+          { text: "layout", link: "/reference/commands/layout" },
+          { text: "group", link: "/reference/commands/group" },
           { text: "path", link: "/reference/commands/path" },
+          { text: "grep", link: "/reference/commands/grep" },
+          { text: "move-dir", link: "/reference/commands/move-dir" },
+          { text: "lock", link: "/reference/commands/lock" },
+          { text: "convert", link: "/reference/commands/convert" },
+          { text: "meta", link: "/reference/commands/meta" },
           { text: "dashboard", link: "/reference/commands/dashboard" },
+          { text: "share", link: "/reference/commands/share" },
+          { text: "top", link: "/reference/commands/top" },
+          { text: "gc", link: "/reference/commands/gc" },
+          { text: "report-progress", link: "/reference/commands/report-progress" },
+          { text: "request-exec", link: "/reference/commands/request-exec" },
+          { text: "git-query", link: "/reference/commands/git-query" },
           { text: "init", link: "/reference/commands/init" },
           { text: "claude prune", link: "/reference/commands/claude" },
           { text: "sandbox", link: "/reference/commands/sandbox" },
           { text: "completions", link: "/reference/commands/completions" },
           { text: "docs", link: "/reference/commands/docs" },
+          { text: "doctor", link: "/reference/commands/doctor" },
+          { text: "self-update", link: "/reference/commands/self-update" },
         ],
       },
     ],
@@ -1,12 +1,13 @@
 //! Claude Code status tracking setup.
 //!
 //! Detects Claude Code via the `~/.claude/` directory.
-//! Installs hooks by merging into `~/.claude/settings.json`.
+//! Installs hooks by merging into `~/.claude/settings.json`, or with
+//! `workmux setup --project` into the repository's `.claude/settings.json`.
 
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::StatusCheck;
 
@@ -17,6 +18,9 @@ fn settings_path() -> Option<PathBuf> {
     home::home_dir().map(|h| h.join(".claude/settings.json"))
 }
 
+/// Settings files Claude Code reads in a repository, besides the global one.
+const PROJECT_SETTINGS: &[&str] = &[".claude/settings.json", ".claude/settings.local.json"];
+
 fn claude_dir() -> Option<PathBuf> {
     home::home_dir().map(|h| h.join(".claude"))
 }
@@ -37,21 +41,51 @@ pub fn detect() -> Option<&'static str> {
 /// 1. Plugin: `enabledPlugins` has a key starting with `workmux-status@`
 ///    (regardless of enabled/disabled -- user knows about it)
 /// 2. Manual hooks: `hooks` object contains a command with `workmux set-window-status`
+///
+/// Hooks in the current repository's settings count too, since Claude Code
+/// merges them with the global ones.
 pub fn check() -> Result<StatusCheck> {
+    let repo_root = crate::vcs::try_detect_vcs().and_then(|v| v.get_repo_root().ok());
+    if let Some(root) = repo_root
+        && matches!(check_project(&root)?, StatusCheck::Installed)
+    {
+        return Ok(StatusCheck::Installed);
+    }
+
     let Some(path) = settings_path() else {
         return Ok(StatusCheck::NotInstalled);
     };
+    check_file(&path, "~/.claude/settings.json")
+}
 
-    if !path.exists() {
-        return Ok(StatusCheck::NotInstalled);
+/// Check the repository-local settings files of the repository at `root`.
+pub fn check_project(root: &Path) -> Result<StatusCheck> {
+    for name in PROJECT_SETTINGS {
+        if let StatusCheck::Installed = check_file(&root.join(name), name)? {
+            return Ok(StatusCheck::Installed);
+        }
     }
+    Ok(StatusCheck::NotInstalled)
+}
 
-    let content = fs::read_to_string(&path).context("Failed to read ~/.claude/settings.json")?;
-
-    let settings: Value =
-        serde_json::from_str(&content).context("~/.claude/settings.json is not valid JSON")?;
+/// Check one settings file; `display` names it in errors.
+fn check_file(path: &Path, display: &str) -> Result<StatusCheck> {
+    match read_settings(path, display)? {
+        Some(settings) => Ok(check_settings(&settings)),
+        None => Ok(StatusCheck::NotInstalled),
+    }
+}
 
-    Ok(check_settings(&settings))
+/// Parse a settings file, or `None` when it doesn't exist.
+fn read_settings(path: &Path, display: &str) -> Result<Option<Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", display))?;
+    let settings =
+        serde_json::from_str(&content).with_context(|| format!("{} is not valid JSON", display))?;
+    Ok(Some(settings))
 }
 
 /// Check a parsed settings.json value for workmux status tracking configuration.
@@ -115,22 +149,46 @@ fn load_hooks_from_plugin() -> Result<Value> {
 pub fn install() -> Result<String> {
     let path =
         settings_path().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    install_into(&path, "~/.claude/settings.json")?;
+    Ok("Installed hooks to ~/.claude/settings.json".to_string())
+}
+
+/// Install workmux hooks into `.claude/settings.json` of the repository at
+/// `root`, merging them like [`install`].
+pub fn install_project(root: &Path) -> Result<String> {
+    let name = PROJECT_SETTINGS[0];
+    install_into(&root.join(name), name)?;
+    Ok(format!(
+        "Installed hooks to {}. Commit it (or list it in files.copy) so new worktrees get them.",
+        name
+    ))
+}
 
+/// Merge the workmux hooks into the settings file at `path`, creating it
+/// if needed.
+fn install_into(path: &Path, display: &str) -> Result<()> {
     // Read existing settings or start fresh
-    let mut settings: Value = if path.exists() {
-        let content =
-            fs::read_to_string(&path).context("Failed to read ~/.claude/settings.json")?;
-        serde_json::from_str(&content).context("~/.claude/settings.json is not valid JSON")?
-    } else {
-        // Ensure ~/.claude/ directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).context("Failed to create ~/.claude/ directory")?;
+    let mut settings = match read_settings(path, display)? {
+        Some(settings) => settings,
+        None => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory for {}", display))?;
+            }
+            Value::Object(serde_json::Map::new())
         }
-        Value::Object(serde_json::Map::new())
     };
 
-    let hooks_to_add = load_hooks_from_plugin()?;
+    merge_hooks(&mut settings, &load_hooks_from_plugin()?)?;
+
+    // Write back with pretty formatting
+    let output = serde_json::to_string_pretty(&settings)?;
+    fs::write(path, output + "\n").with_context(|| format!("Failed to write {}", display))?;
+    Ok(())
+}
 
+/// Merge hook groups into `settings.hooks`, deduplicating by value equality.
+fn merge_hooks(settings: &mut Value, hooks_to_add: &Value) -> Result<()> {
     // Ensure settings.hooks exists as an object
     let settings_obj = settings
         .as_object_mut()
@@ -145,7 +203,6 @@ pub fn install() -> Result<String> {
         .and_then(|v| v.as_object_mut())
         .ok_or_else(|| anyhow::anyhow!("settings.json hooks is not an object"))?;
 
-    // Merge each hook event, deduplicating by value equality
     let hooks_map = hooks_to_add.as_object().expect("plugin hooks is an object");
     for (event, hook_groups) in hooks_map {
         let Some(new_groups) = hook_groups.as_array() else {
@@ -165,12 +222,7 @@ pub fn install() -> Result<String> {
             existing_hooks.insert(event.clone(), hook_groups.clone());
         }
     }
-
-    // Write back with pretty formatting
-    let output = serde_json::to_string_pretty(&settings)?;
-    fs::write(&path, output + "\n").context("Failed to write ~/.claude/settings.json")?;
-
-    Ok("Installed hooks to ~/.claude/settings.json".to_string())
+    Ok(())
 }
 
 #[cfg(test)]
@@ -220,6 +272,7 @@ mod tests {
         let obj = hooks.as_object().unwrap();
         assert!(obj.contains_key("UserPromptSubmit"));
         assert!(obj.contains_key("Notification"));
+        assert!(obj.contains_key("PreToolUse"));
         assert!(obj.contains_key("PostToolUse"));
         assert!(obj.contains_key("Stop"));
     }
@@ -243,7 +296,7 @@ mod tests {
         }
 
         let hooks = settings.get("hooks").unwrap().as_object().unwrap();
-        assert_eq!(hooks.len(), 4);
+        assert_eq!(hooks.len(), 5);
     }
 
     #[test]
@@ -416,8 +469,69 @@ mod tests {
             .unwrap();
         assert_eq!(stop.len(), 2);
 
-        // All 4 events should be present
+        // All 5 events should be present
         let hooks = settings.get("hooks").unwrap().as_object().unwrap();
-        assert_eq!(hooks.len(), 4);
+        assert_eq!(hooks.len(), 5);
+    }
+
+    #[test]
+    fn install_project_merges_into_repo_settings() {
+        let repo = tempfile::tempdir().unwrap();
+        let path = repo.path().join(".claude/settings.json");
+        assert!(matches!(
+            check_project(repo.path()).unwrap(),
+            StatusCheck::NotInstalled
+        ));
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let existing = json!({
+            "permissions": { "allow": ["Bash(cargo test:*)"] },
+            "hooks": {
+                "Stop": [{
+                    "hooks": [{
+                        "type": "command",
+                        "command": "afplay /System/Library/Sounds/Glass.aiff"
+                    }]
+                }]
+            }
+        });
+        fs::write(&path, existing.to_string()).unwrap();
+
+        install_project(repo.path()).unwrap();
+        install_project(repo.path()).unwrap();
+
+        let settings: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(settings["permissions"], existing["permissions"]);
+        assert_eq!(settings["hooks"]["Stop"].as_array().unwrap().len(), 2);
+        assert!(matches!(
+            check_project(repo.path()).unwrap(),
+            StatusCheck::Installed
+        ));
+    }
+
+    #[test]
+    fn check_project_reads_local_settings() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".claude")).unwrap();
+        let hooks = json!({
+            "hooks": {
+                "Stop": [{
+                    "hooks": [{
+                        "type": "command",
+                        "command": "workmux set-window-status done"
+                    }]
+                }]
+            }
+        });
+        let local = repo.path().join(".claude/settings.local.json");
+        fs::write(&local, hooks.to_string()).unwrap();
+        assert!(matches!(
+            check_project(repo.path()).unwrap(),
+            StatusCheck::Installed
+        ));
+
+        fs::write(&local, "{").unwrap();
+        let err = check_project(repo.path()).unwrap_err().to_string();
+        assert_eq!(err, ".claude/settings.local.json is not valid JSON");
     }
 }
@@ -13,7 +13,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeSet;
 use std::fs;
 use std::io::{self, IsTerminal, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::state::schema::{self, SchemaError, StateKind};
 
 /// An agent that supports status tracking.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -92,6 +95,28 @@ pub fn install(agent: Agent) -> Result<String> {
     }
 }
 
+/// Check status tracking in the settings of the repository at `root`, for
+/// the agents that read settings from the repository.
+pub fn check_project(root: &Path) -> Vec<AgentCheck> {
+    let status = match claude::check_project(root) {
+        Ok(s) => s,
+        Err(e) => StatusCheck::Error(e.to_string()),
+    };
+    vec![AgentCheck {
+        agent: Agent::Claude,
+        reason: ".claude/settings.json",
+        status,
+    }]
+}
+
+/// Install status tracking for the given agent into the repository at `root`.
+pub fn install_project(agent: Agent, root: &Path) -> Result<String> {
+    match agent {
+        Agent::Claude => claude::install_project(root),
+        Agent::OpenCode => anyhow::bail!("the OpenCode plugin can only be installed globally"),
+    }
+}
+
 // --- State persistence (declined agents) ---
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -104,17 +129,19 @@ fn setup_state_path() -> Result<PathBuf> {
     Ok(crate::state::store::get_state_dir()?.join("workmux/setup.json"))
 }
 
-fn load_setup_state() -> SetupState {
-    let Ok(path) = setup_state_path() else {
-        return SetupState::default();
+/// Load the setup state. A missing or corrupted file is empty state; one
+/// written by a newer version of workmux is an error, so saving doesn't
+/// overwrite it.
+fn load_setup_state() -> Result<SetupState> {
+    let path = setup_state_path()?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(SetupState::default());
     };
-    if !path.exists() {
-        return SetupState::default();
+    match schema::parse(StateKind::Setup, &content) {
+        Ok(state) => Ok(state),
+        Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
+        Err(_) => Ok(SetupState::default()),
     }
-    fs::read_to_string(&path)
-        .ok()
-        .and_then(|c| serde_json::from_str(&c).ok())
-        .unwrap_or_default()
 }
 
 fn save_setup_state(state: &SetupState) -> Result<()> {
@@ -122,17 +149,17 @@ fn save_setup_state(state: &SetupState) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).context("Failed to create state directory")?;
     }
-    let content = serde_json::to_string_pretty(state)?;
+    let content = schema::to_json(StateKind::Setup, state)?;
     fs::write(&path, content + "\n")?;
     Ok(())
 }
 
 pub fn is_declined(agent: Agent) -> bool {
-    load_setup_state().declined.contains(&agent)
+    load_setup_state().is_ok_and(|state| state.declined.contains(&agent))
 }
 
 fn mark_declined(agents: &[Agent]) -> Result<()> {
-    let mut state = load_setup_state();
+    let mut state = load_setup_state()?;
     for agent in agents {
         state.declined.insert(*agent);
     }
@@ -141,18 +168,23 @@ fn mark_declined(agents: &[Agent]) -> Result<()> {
 
 // --- Shared prompt UI ---
 
-/// Print the status tracking description with a mock tmux status bar.
+/// Print the status tracking description with a mock tmux status bar, using
+/// the configured status icons.
 /// `prefix` is printed before each line (e.g. "│ " for the wizard, "" for the command).
 pub(crate) fn print_description(prefix: &str) {
+    let icons = Config::load(None)
+        .map(|config| config.status_icons)
+        .unwrap_or_default();
+    let (working, waiting, done) = (icons.working(), icons.waiting(), icons.done());
     println!("{prefix}  Status tracking shows agent activity in your tmux window list:");
     println!("{prefix}");
     println!(
-        "{prefix}    {}  2:user-auth 🤖  3:refactor 💬  {}",
+        "{prefix}    {}  2:user-auth {working}  3:refactor {waiting}  {}",
         style("1:main*").reverse(),
-        style("4:dark-mode ✅").dim(),
+        style(format!("4:dark-mode {done}")).dim(),
     );
     println!("{prefix}");
-    println!("{prefix}  🤖 = working  💬 = waiting for input  ✅ = done");
+    println!("{prefix}  {working} = working  {waiting} = waiting for input  {done} = done");
     println!(
         "{prefix}  {}",
         style("https://workmux.raine.dev/guide/status-tracking").dim()
@@ -0,0 +1,93 @@
+//! Terminal escape sequences shared by command output.
+//!
+//! OSC 8 hyperlinks make worktree paths and PR numbers clickable, and OSC 2
+//! sets the terminal (or tmux pane) title. Terminals that don't understand a
+//! sequence ignore it, but pipes and `TERM=dumb` must only ever see plain
+//! text, so callers check `supports_escapes` first.
+
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Whether `stream` is a terminal that escape sequences can be written to.
+pub fn supports_escapes(stream: &impl IsTerminal) -> bool {
+    stream.is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// `text` as an OSC 8 hyperlink to `url`.
+///
+/// Only the visible text counts towards the width in tables, which strip
+/// escape sequences when measuring.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!(
+        "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+        strip_controls(url),
+        text
+    )
+}
+
+/// `text` linked to `url` when `enabled`, else `text` unchanged.
+pub fn link_if(enabled: bool, url: Option<&str>, text: String) -> String {
+    match url {
+        Some(url) if enabled => hyperlink(url, &text),
+        _ => text,
+    }
+}
+
+/// `file://` URL of an absolute path, percent-encoding everything but
+/// unreserved characters and `/`.
+pub fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+/// OSC 2 sequence setting the terminal title to `title`.
+pub fn title(title: &str) -> String {
+    format!("\x1b]2;{}\x07", strip_controls(title))
+}
+
+/// Drop control characters, which would end the sequence early.
+fn strip_controls(s: &str) -> String {
+    s.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperlinks_wrap_the_text() {
+        assert_eq!(
+            hyperlink("https://example.com/pull/7", "#7"),
+            "\x1b]8;;https://example.com/pull/7\x1b\\#7\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            link_if(false, Some("https://example.com"), "x".to_string()),
+            "x"
+        );
+        assert_eq!(link_if(true, None, "x".to_string()), "x");
+        assert_eq!(
+            strip_ansi_escapes::strip_str(hyperlink("https://example.com", "#7")),
+            "#7"
+        );
+    }
+
+    #[test]
+    fn file_urls_are_percent_encoded() {
+        assert_eq!(
+            file_url(Path::new("/home/me/my repo/wt-ü")),
+            "file:///home/me/my%20repo/wt-%C3%BC"
+        );
+    }
+
+    #[test]
+    fn titles_drop_control_characters() {
+        assert_eq!(title("feat\x07\x1b]x"), "\x1b]2;feat]x\x07");
+    }
+}
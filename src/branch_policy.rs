@@ -0,0 +1,270 @@
+//! Branch naming policy (`branch_policy:` in config).
+//!
+//! Teams often require names like `feat/PROJ-123-login-form`. A policy has a
+//! `pattern` new branches must match and a `template` that builds such a
+//! name from the short one passed to `workmux add` or generated by
+//! `--auto-name`. `{ticket}` comes from the configured ticket source, which
+//! is consulted at most once per command.
+
+use anyhow::{Context, Result, anyhow, bail};
+use regex::Regex;
+use std::cell::OnceCell;
+use std::io::{IsTerminal, Write};
+
+use crate::cmd::Cmd;
+use crate::config::{BranchPolicyConfig, TicketSource};
+
+/// Placeholders allowed in `branch_policy.template`.
+const PLACEHOLDERS: &[&str] = &["type", "ticket", "name"];
+
+/// Hint appended to policy errors.
+const ESCAPE_HINT: &str = "Pass --no-policy to skip the branch policy.";
+
+/// A validated branch policy.
+#[derive(Debug)]
+pub struct BranchPolicy<'a> {
+    pattern: Option<Regex>,
+    template: Option<&'a str>,
+    kind: &'a str,
+    ticket_source: Option<&'a TicketSource>,
+    ticket: OnceCell<String>,
+}
+
+impl<'a> BranchPolicy<'a> {
+    /// Build the policy from config. `None` when neither a pattern nor a
+    /// template is configured.
+    pub fn from_config(config: &'a BranchPolicyConfig) -> Result<Option<Self>> {
+        if config.pattern.is_none() && config.template.is_none() {
+            return Ok(None);
+        }
+        let pattern = config
+            .pattern
+            .as_deref()
+            .map(|p| {
+                Regex::new(p).with_context(|| format!("Invalid branch_policy.pattern '{}'", p))
+            })
+            .transpose()?;
+        if let Some(template) = config.template.as_deref() {
+            for placeholder in placeholders(template) {
+                if !PLACEHOLDERS.contains(&placeholder) {
+                    bail!(
+                        "Unknown placeholder '{{{}}}' in branch_policy.template (expected {})",
+                        placeholder,
+                        PLACEHOLDERS
+                            .iter()
+                            .map(|p| format!("{{{}}}", p))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                }
+            }
+        }
+        Ok(Some(Self {
+            pattern,
+            template: config.template.as_deref(),
+            kind: config.kind(),
+            ticket_source: config.ticket.as_ref(),
+            ticket: OnceCell::new(),
+        }))
+    }
+
+    /// The branch name to create for `name`: `name` itself when it matches
+    /// the pattern, otherwise the template applied to it. Without a pattern
+    /// the template is always applied. Fails when the result doesn't match
+    /// the pattern.
+    pub fn apply(&self, name: &str) -> Result<String> {
+        if self.pattern.as_ref().is_some_and(|p| p.is_match(name)) {
+            return Ok(name.to_string());
+        }
+        let Some(template) = self.template else {
+            bail!(
+                "Branch name '{}' does not match branch_policy.pattern '{}'.\n{}",
+                name,
+                self.pattern_str(),
+                ESCAPE_HINT
+            );
+        };
+        let ticket = if placeholders(template).any(|p| p == "ticket") {
+            self.ticket()?
+        } else {
+            ""
+        };
+        let branch = render(template, self.kind, ticket, name);
+        if !self.matches(&branch) {
+            bail!(
+                "Branch name '{}' (from branch_policy.template '{}') does not match \
+                 branch_policy.pattern '{}'.\n{}",
+                branch,
+                template,
+                self.pattern_str(),
+                ESCAPE_HINT
+            );
+        }
+        Ok(branch)
+    }
+
+    fn matches(&self, branch: &str) -> bool {
+        self.pattern.as_ref().is_none_or(|p| p.is_match(branch))
+    }
+
+    fn pattern_str(&self) -> &str {
+        self.pattern.as_ref().map_or("", Regex::as_str)
+    }
+
+    /// The ticket ID, resolved from the source on first use.
+    fn ticket(&self) -> Result<&str> {
+        if let Some(ticket) = self.ticket.get() {
+            return Ok(ticket);
+        }
+        let source = self.ticket_source.ok_or_else(|| {
+            anyhow!(
+                "branch_policy.template uses {{ticket}} but no branch_policy.ticket source is \
+                 configured in the global config.\n{}",
+                ESCAPE_HINT
+            )
+        })?;
+        let ticket = resolve_ticket(source)?;
+        Ok(self.ticket.get_or_init(|| ticket))
+    }
+}
+
+/// Names of the `{placeholder}`s in `template`.
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|s| s.split_once('}'))
+        .map(|(p, _)| p)
+}
+
+fn render(template: &str, kind: &str, ticket: &str, name: &str) -> String {
+    template
+        .replace("{type}", kind)
+        .replace("{ticket}", ticket)
+        .replace("{name}", name)
+}
+
+fn resolve_ticket(source: &TicketSource) -> Result<String> {
+    let ticket = match source {
+        TicketSource::Env { env } => std::env::var(env).map_err(|_| {
+            anyhow!(
+                "branch_policy.ticket: ${} is not set. Set it to the ticket ID.\n{}",
+                env,
+                ESCAPE_HINT
+            )
+        })?,
+        TicketSource::Command { command } => Cmd::new("sh")
+            .args(&["-c", command])
+            .run_and_capture_stdout()
+            .with_context(|| format!("branch_policy.ticket: command '{}' failed", command))?
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string(),
+        TicketSource::Prompt { prompt } => {
+            if !std::io::stdin().is_terminal() {
+                bail!(
+                    "branch_policy.ticket asks for '{}' but stdin is not a terminal.\n{}",
+                    prompt,
+                    ESCAPE_HINT
+                );
+            }
+            eprint!("{}: ", prompt);
+            std::io::stderr().flush()?;
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            input
+        }
+    };
+    let ticket = ticket.trim();
+    if ticket.is_empty() {
+        bail!(
+            "branch_policy.ticket: got an empty ticket ID.\n{}",
+            ESCAPE_HINT
+        );
+    }
+    Ok(ticket.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy_config(pattern: Option<&str>, template: Option<&str>) -> BranchPolicyConfig {
+        BranchPolicyConfig {
+            pattern: pattern.map(str::to_string),
+            template: template.map(str::to_string),
+            kind: None,
+            ticket: Some(TicketSource::Command {
+                command: "echo ' PROJ-123 '; echo ignored".to_string(),
+            }),
+        }
+    }
+
+    const PATTERN: &str = r"^(feat|fix)/[A-Z]+-\d+-[a-z0-9-]+$";
+    const TEMPLATE: &str = "{type}/{ticket}-{name}";
+
+    #[test]
+    fn no_policy_without_pattern_or_template() {
+        assert!(
+            BranchPolicy::from_config(&BranchPolicyConfig::default())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn conforming_names_are_kept() {
+        let config = policy_config(Some(PATTERN), Some(TEMPLATE));
+        let policy = BranchPolicy::from_config(&config).unwrap().unwrap();
+        assert_eq!(policy.apply("fix/ABC-9-crash").unwrap(), "fix/ABC-9-crash");
+    }
+
+    #[test]
+    fn template_builds_names_that_do_not_match() {
+        let config = policy_config(Some(PATTERN), Some(TEMPLATE));
+        let policy = BranchPolicy::from_config(&config).unwrap().unwrap();
+        assert_eq!(
+            policy.apply("login-form").unwrap(),
+            "feat/PROJ-123-login-form"
+        );
+    }
+
+    #[test]
+    fn names_failing_the_pattern_are_rejected() {
+        let config = policy_config(Some(PATTERN), None);
+        let policy = BranchPolicy::from_config(&config).unwrap().unwrap();
+        let err = policy.apply("login-form").unwrap_err().to_string();
+        assert!(err.contains("does not match branch_policy.pattern"));
+        assert!(err.contains("--no-policy"));
+
+        let config = policy_config(Some(PATTERN), Some(TEMPLATE));
+        let policy = BranchPolicy::from_config(&config).unwrap().unwrap();
+        assert!(policy.apply("Login Form").is_err());
+    }
+
+    #[test]
+    fn bad_config_is_rejected() {
+        assert!(BranchPolicy::from_config(&policy_config(Some("(unclosed"), None)).is_err());
+        let err = BranchPolicy::from_config(&policy_config(None, Some("{kind}/{name}")))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("{kind}"), "{}", err);
+    }
+
+    #[test]
+    fn missing_ticket_source_is_reported() {
+        let config = BranchPolicyConfig {
+            template: Some(TEMPLATE.to_string()),
+            ..BranchPolicyConfig::default()
+        };
+        let policy = BranchPolicy::from_config(&config).unwrap().unwrap();
+        assert!(
+            policy
+                .apply("login")
+                .unwrap_err()
+                .to_string()
+                .contains("no branch_policy.ticket source")
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use crate::command::args::{MultiArgs, PromptArgs, RescueArgs, SetupFlags};
-use crate::{claude, command, config, nerdfont, vcs};
+use crate::sandbox::git_query::GitQuery;
+use crate::workflow::MergeRemoteSync;
+use crate::workflow::pr::PrCreateOptions;
+use crate::{claude, command, config, nerdfont, progress, prompt, vcs};
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
@@ -172,6 +175,15 @@ impl clap::builder::TypedValueParser for GitBranchParser {
 #[command(about = "An opinionated workflow tool that orchestrates git worktrees and tmux")]
 #[command(after_help = "Run 'workmux docs' for detailed documentation.")]
 struct Cli {
+    /// Run as if workmux was started in <PATH> instead of the current directory
+    #[arg(long = "repo", value_name = "PATH", global = true)]
+    repo: Option<std::path::PathBuf>,
+
+    /// Apply the named entry from `profiles` in the config (overrides
+    /// WORKMUX_PROFILE)
+    #[arg(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -193,7 +205,8 @@ enum Commands {
         #[arg(short = 'A', long = "auto-name", conflicts_with = "pr")]
         auto_name: bool,
 
-        /// Base branch/commit/tag to branch from (defaults to current branch)
+        /// Base branch/commit/tag to branch from (defaults to current branch).
+        /// Remote branches that haven't been fetched yet are fetched automatically.
         #[arg(long)]
         base: Option<String>,
 
@@ -201,6 +214,15 @@ enum Commands {
         #[arg(long)]
         name: Option<String>,
 
+        /// Skip the configured branch_policy (for local experiments)
+        #[arg(long)]
+        no_policy: bool,
+
+        /// Apply a diff, `git format-patch` mailbox or git bundle to the new
+        /// worktree before hooks and the agent run
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["pr", "with_changes"])]
+        apply: Option<std::path::PathBuf>,
+
         #[command(flatten)]
         prompt: PromptArgs,
 
@@ -211,7 +233,7 @@ enum Commands {
         rescue: RescueArgs,
 
         #[command(flatten)]
-        multi: MultiArgs,
+        multi: Box<MultiArgs>,
 
         /// Block until the created tmux window is closed
         #[arg(short = 'W', long)]
@@ -220,12 +242,17 @@ enum Commands {
         /// Create the window in its own tmux session (useful for session-per-project workflows)
         #[arg(short = 's', long)]
         session: bool,
+
+        /// Report progress as JSON lines on stdout
+        #[arg(long)]
+        json: bool,
     },
 
     /// Open a tmux window for an existing worktree
     Open {
-        /// Worktree name (directory name, visible in tmux window). Optional with --new.
-        #[arg(value_parser = WorktreeHandleParser::new(), required_unless_present = "new")]
+        /// Worktree name (directory name, visible in tmux window). Omit to pick
+        /// branches without a worktree to open.
+        #[arg(value_parser = WorktreeHandleParser::new())]
         name: Option<String>,
 
         /// Re-run post-create hooks (e.g., pnpm install)
@@ -240,6 +267,11 @@ enum Commands {
         #[arg(long, short = 'n')]
         new: bool,
 
+        /// Pull request to open: switches to its worktree, or checks out its
+        /// head branch (from the fork for PRs from forks) into a new one
+        #[arg(long, conflicts_with_all = ["name", "new"])]
+        pr: Option<u32>,
+
         #[command(flatten)]
         prompt: PromptArgs,
     },
@@ -288,19 +320,78 @@ enum Commands {
         /// Show a system notification on successful merge
         #[arg(long)]
         notification: bool,
+
+        /// Push the target branch to its remote after merging
+        #[arg(long)]
+        push: bool,
+
+        /// Delete the merged branch on its remote
+        #[arg(long)]
+        delete_remote: bool,
+
+        /// Force-push (with lease) the rebased branch before merging it
+        #[arg(long)]
+        push_rebased: bool,
+
+        /// What to do when the merge has conflicts (defaults to merge_on_conflict from config)
+        #[arg(long, value_enum, value_name = "STRATEGY")]
+        on_conflict: Option<config::ConflictStrategy>,
+
+        /// Continue a merge that was interrupted, from the last completed step
+        #[arg(long, conflicts_with = "abort")]
+        resume: bool,
+
+        /// Roll back a merge that was interrupted
+        #[arg(long)]
+        abort: bool,
+
+        /// Report progress as JSON lines on stdout
+        #[arg(long)]
+        json: bool,
+
+        /// Rebase onto the upstream remote's default branch (or --into) and
+        /// push to your fork instead of merging locally; keeps the worktree
+        #[arg(long, conflicts_with_all = [
+            "ignore_uncommitted", "rebase", "squash", "keep", "no_verify", "no_hooks",
+            "notification", "push", "delete_remote", "push_rebased", "on_conflict", "resume",
+            "abort",
+        ])]
+        to_upstream: bool,
+    },
+
+    /// Open pull requests for worktree branches
+    Pr {
+        #[command(subcommand)]
+        command: PrCommands,
     },
 
     /// Remove a worktree, tmux window, and branch without merging
     #[command(visible_alias = "rm")]
     Remove {
-        /// Worktree names (defaults to current directory name if empty)
+        /// Worktree names or glob patterns like 'experiment-*' (defaults to current directory name if empty)
         #[arg(value_parser = WorktreeHandleParser::new(), conflicts_with_all = ["gone", "all"], num_args = 0..)]
         names: Vec<String>,
 
         /// Remove worktrees whose upstream remote branch has been deleted (e.g., after PR merge)
-        #[arg(long, conflicts_with = "all")]
+        #[arg(long, conflicts_with_all = ["all", "merged", "status", "older_than", "dry_run"])]
         gone: bool,
 
+        /// Only remove worktrees whose branch is merged into its base
+        #[arg(long)]
+        merged: bool,
+
+        /// Only remove worktrees whose agents all have this status
+        #[arg(long, value_enum)]
+        status: Option<command::remove::StatusFilter>,
+
+        /// Only remove worktrees whose latest commit is older than this (e.g. 12h, 14d, 2w)
+        #[arg(long, value_parser = command::remove::parse_age)]
+        older_than: Option<u64>,
+
+        /// List what would be removed without removing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
         /// Remove all worktrees (except the main worktree)
         #[arg(long)]
         all: bool,
@@ -312,6 +403,10 @@ enum Commands {
         /// Keep the local branch (only remove worktree and tmux window)
         #[arg(short = 'k', long)]
         keep_branch: bool,
+
+        /// Save a snapshot (files, prompt, pane logs, diff vs base) to the archive directory first
+        #[arg(long)]
+        archive: bool,
     },
 
     /// List all worktrees
@@ -326,6 +421,51 @@ enum Commands {
         filter: Vec<String>,
     },
 
+    /// Recover a window whose worktree was deleted outside workmux
+    Reattach {
+        /// Window, session or worktree handle (see 'workmux doctor')
+        name: String,
+
+        /// Close the window instead of recreating the worktree
+        #[arg(long, conflicts_with = "branch")]
+        close: bool,
+
+        /// Branch to recreate the worktree from (default: found from the handle)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+
+    /// List or extract worktree archives made by 'remove --archive'
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommands,
+    },
+
+    /// Recreate a deleted branch from its backup, or list backups
+    #[command(name = "restore-branch")]
+    RestoreBranch {
+        /// Branch to restore (lists backups when omitted)
+        name: Option<String>,
+    },
+
+    /// Search all worktrees for a pattern, grouped by worktree with agent status
+    Grep {
+        /// Regular expression to search for
+        pattern: String,
+
+        /// Match case-insensitively
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+
+        /// Treat the pattern as a literal string
+        #[arg(short = 'F', long)]
+        fixed_strings: bool,
+
+        /// Only search worktrees with a running agent
+        #[arg(long)]
+        active: bool,
+    },
+
     /// Get the filesystem path of a worktree
     Path {
         /// Worktree name (directory name)
@@ -333,6 +473,75 @@ enum Commands {
         name: String,
     },
 
+    /// Lock a worktree so it isn't pruned or removed, e.g. while its drive is unplugged
+    Lock {
+        /// Worktree name or branch (defaults to current directory)
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: Option<String>,
+
+        /// Why the worktree is locked, shown by 'list' and when removal is refused
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Unlock a worktree locked with 'workmux lock'
+    Unlock {
+        /// Worktree name or branch (defaults to current directory)
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: Option<String>,
+    },
+
+    /// Move a worktree directory to a new location (e.g. another disk)
+    #[command(name = "move-dir")]
+    MoveDir {
+        /// Worktree name (directory name) or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        /// New parent directory, or the full new worktree path
+        destination: std::path::PathBuf,
+
+        /// Don't close and reopen the worktree's window or restart panes
+        #[arg(long)]
+        no_restart: bool,
+    },
+
+    /// Move a worktree's window into its own session, or its session into a window
+    Convert {
+        /// Worktree name (directory name) or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        /// What the worktree should become
+        #[arg(long, value_enum)]
+        to: command::convert::ConvertTarget,
+    },
+
+    /// Open, close and check on named groups of worktrees together
+    Group {
+        #[command(subcommand)]
+        command: GroupCommands,
+    },
+
+    /// Add panes to a worktree's open window
+    Pane {
+        #[command(subcommand)]
+        command: PaneCommands,
+    },
+
+    /// Inspect pane layouts
+    Layout {
+        #[command(subcommand)]
+        command: LayoutCommands,
+    },
+
+    /// Inspect and edit a worktree's metadata (mode, base, note, tags)
+    #[command(visible_alias = "metadata")]
+    Meta {
+        #[command(subcommand)]
+        command: MetaCommands,
+    },
+
     /// Send a prompt or instruction to a running agent
     Send {
         /// Worktree name
@@ -346,6 +555,22 @@ enum Commands {
         /// Read prompt from file
         #[arg(short, long, conflicts_with = "text")]
         file: Option<String>,
+
+        /// Allow sending to an agent in the main worktree when protect_main is enabled
+        #[arg(long)]
+        allow_main: bool,
+
+        /// Copy a file into the worktree and reference it in the message (repeatable)
+        #[arg(long = "attach", value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+        attach: Vec<std::path::PathBuf>,
+
+        /// Deliver prompts queued while the worktree's agents weren't ready
+        #[arg(long, conflicts_with_all = ["text", "file", "attach"])]
+        flush: bool,
+
+        /// Keep retrying queued prompts with backoff (used in the background)
+        #[arg(long, hide = true, requires = "flush")]
+        retry: bool,
     },
 
     /// Capture terminal output from a running agent
@@ -416,18 +641,100 @@ enum Commands {
         timeout: Option<u64>,
     },
 
-    /// Generate example .workmux.yaml configuration file
-    Init,
+    /// Report the current agent's task progress to the dashboard
+    ReportProgress {
+        /// Steps completed so far
+        #[arg(required_unless_present = "clear")]
+        step: Option<u32>,
+
+        /// Total number of steps
+        #[arg(required_unless_present = "clear")]
+        total: Option<u32>,
+
+        /// What the agent is working on
+        message: Option<String>,
+
+        /// Remove the reported progress
+        #[arg(long, conflicts_with_all = ["step", "total", "message"])]
+        clear: bool,
+    },
+
+    /// Run a command on the host once the user approves it in the dashboard
+    /// (inside a sandbox, for commands not in host_commands)
+    RequestExec {
+        /// Command name and arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        args: Vec<String>,
+    },
+
+    /// Read-only git queries about the repository, answered by the host
+    /// inside a sandbox
+    GitQuery {
+        #[command(subcommand)]
+        query: GitQueryCommands,
+    },
+
+    /// Generate a starter .workmux.yaml for the current project
+    Init {
+        /// Only write the detected settings, without comments or the reference
+        /// of all other options
+        #[arg(long)]
+        minimal: bool,
+
+        /// Enable an agent sandbox with the given backend
+        #[arg(long, value_enum, value_name = "BACKEND")]
+        with_sandbox: Option<command::init::InitSandbox>,
+    },
 
     /// Set up agent status tracking hooks
-    Setup,
+    Setup {
+        /// Also pull the container sandbox images in the background
+        #[arg(long)]
+        sandbox: bool,
+
+        /// Install the hooks into this repository's agent settings
+        /// (.claude/settings.json) instead of the global ones
+        #[arg(long)]
+        project: bool,
+
+        /// Install key bindings for the dashboard, quick add and jumping to
+        /// waiting agents into the tmux, WezTerm or kitty config
+        #[arg(long, conflicts_with_all = ["sandbox", "project"])]
+        keys: bool,
+
+        /// Print the key bindings instead of installing them
+        #[arg(long, requires = "keys")]
+        print: bool,
+
+        /// Remove the key bindings installed by --keys
+        #[arg(long, requires = "keys", conflicts_with = "print")]
+        remove: bool,
+    },
 
     /// Show detailed documentation (renders README.md)
     Docs,
 
+    /// Check repository setup and window names shared with other projects
+    Doctor {
+        /// Fix detected problems (renames this project's windows to a qualified prefix)
+        #[arg(long)]
+        fix: bool,
+    },
+
     /// Show the changelog (what's new in each version)
     Changelog,
 
+    /// Update workmux to the latest release
+    SelfUpdate {
+        /// Release channel to update from
+        #[arg(long, value_enum, default_value_t)]
+        channel: command::self_update::Channel,
+
+        /// Only check whether an update is available
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Show a TUI dashboard of all active workmux agents across all sessions
     Dashboard {
         /// Preview pane size as percentage (10-90). Larger = more preview, less table.
@@ -437,6 +744,60 @@ enum Commands {
         /// Open diff view directly for the current worktree
         #[arg(long, short = 'd')]
         diff: bool,
+
+        /// Allow input to agents in the main worktree when protect_main is enabled
+        #[arg(long)]
+        allow_main: bool,
+
+        /// Print agent status as one JSON line per interval instead of opening the dashboard
+        #[arg(long, conflicts_with_all = ["preview_size", "diff", "allow_main"])]
+        watch: bool,
+
+        /// Seconds between --watch updates
+        #[arg(long, default_value_t = 2, requires = "watch")]
+        interval: u64,
+    },
+
+    /// Show live CPU and memory usage of each agent's processes
+    Top {
+        /// Seconds between updates
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+
+        /// Print one measurement and exit
+        #[arg(long)]
+        once: bool,
+
+        /// Print one measurement as JSON and exit
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete caches, logs, state, sandboxes and prompt files workmux no longer uses
+    Gc {
+        /// List what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long, conflicts_with = "dry_run")]
+        yes: bool,
+    },
+
+    /// Share a read-only view of the dashboard over a Unix socket
+    Share {
+        /// Socket path (default: a temporary file)
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+
+        /// Seconds between updates
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+
+        /// Command to run while sharing (socket path in $WORKMUX_SHARE_SOCKET);
+        /// sharing stops when it exits
+        #[arg(long)]
+        exec: Option<String>,
     },
 
     /// Manage global configuration
@@ -456,6 +817,14 @@ enum Commands {
     SetWindowStatus {
         #[arg(value_enum)]
         command: command::set_window_status::SetWindowStatusCommand,
+
+        /// URL of the agent's HTTP server, for pulling detailed progress
+        #[arg(long)]
+        server_url: Option<String>,
+
+        /// Clear this pane's status unless it is "working" (focus hooks)
+        #[arg(long, value_name = "PANE_ID")]
+        pane: Option<String>,
     },
 
     /// Set the base branch for the current worktree (used after rebasing)
@@ -474,14 +843,42 @@ enum Commands {
         run_dir: std::path::PathBuf,
     },
 
+    /// Wait for pane readiness checks (used by pane `depends_on`)
+    #[command(hide = true, name = "_pane-wait")]
+    PaneWait {
+        /// TCP port on localhost that must accept connections
+        #[arg(long)]
+        port: Vec<u16>,
+
+        /// File that must exist
+        #[arg(long)]
+        file: Vec<std::path::PathBuf>,
+
+        /// Shell command that must exit successfully
+        #[arg(long)]
+        command: Vec<String>,
+
+        /// Seconds to wait before giving up
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+
     /// Switch to the agent that most recently completed its task
     #[command(hide = true, name = "last-done")]
     LastDone,
 
+    /// Switch to the agent that has been waiting for input the longest
+    #[command(hide = true, name = "last-waiting")]
+    LastWaiting,
+
     /// Switch to the last visited agent (toggle between two)
     #[command(hide = true, name = "last-agent")]
     LastAgent,
 
+    /// Check that the local state files are readable (used by self-update)
+    #[command(hide = true, name = "_check-state")]
+    CheckState,
+
     /// Execute a command on the host (used by guest shims)
     #[command(hide = true, name = "host-exec")]
     HostExec {
@@ -508,6 +905,10 @@ enum Commands {
     /// Output git branches for shell completion (internal use)
     #[command(hide = true, name = "_complete-git-branches")]
     CompleteGitBranches,
+
+    /// Output base branches (local and remote) for shell completion (internal use)
+    #[command(hide = true, name = "_complete-base-branches")]
+    CompleteBaseBranches,
 }
 
 #[derive(Subcommand)]
@@ -516,12 +917,230 @@ enum ClaudeCommands {
     Prune,
 }
 
+#[derive(Subcommand)]
+enum PrCommands {
+    /// Push a worktree's branch and open a pull request, titled and described
+    /// from the prompt its agent got
+    Create {
+        /// Worktree name or branch (defaults to current directory)
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: Option<String>,
+
+        /// Branch to merge into (defaults to main_branch from config)
+        #[arg(long, value_parser = GitBranchParser::new())]
+        base: Option<String>,
+
+        /// Title (defaults to the first line of the prompt, or the branch name)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Open the pull request as a draft
+        #[arg(long)]
+        draft: bool,
+
+        /// Report progress as JSON lines on stdout
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum GroupCommands {
+    /// Add worktrees to a group, creating the group if needed
+    Add {
+        /// Group name
+        name: String,
+
+        /// Worktree names (handles) to add
+        #[arg(required = true, value_parser = WorktreeHandleParser::new())]
+        worktrees: Vec<String>,
+    },
+
+    /// Remove worktrees from a group, or the whole group if none are given
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// Group name
+        name: String,
+
+        /// Worktree names (handles) to remove
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        worktrees: Vec<String>,
+    },
+
+    /// List groups and their worktrees
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// Open a window for every worktree in the group, in the background
+    Open {
+        /// Group name
+        name: String,
+    },
+
+    /// Close the windows of every worktree in the group (worktrees are kept)
+    Close {
+        /// Group name
+        name: String,
+    },
+
+    /// Show the window and agent status of every worktree in the group
+    Status {
+        /// Group name
+        name: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// Show a worktree's metadata
+    Show {
+        /// Worktree name (handle) or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Set a metadata key, after checking the value
+    Set {
+        /// Worktree name (handle) or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        #[arg(value_enum)]
+        key: command::meta::MetaKey,
+
+        value: String,
+    },
+
+    /// Remove a metadata key
+    Unset {
+        /// Worktree name (handle) or branch
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: String,
+
+        #[arg(value_enum)]
+        key: command::meta::MetaKey,
+    },
+}
+
+#[derive(Subcommand)]
+enum PaneCommands {
+    /// Split a pane from the config, or an ad-hoc command, into the window
+    Add {
+        /// Worktree name (defaults to current directory)
+        #[arg(value_parser = WorktreeHandleParser::new())]
+        name: Option<String>,
+
+        /// 0-based index of the configured pane to add
+        #[arg(long, conflicts_with = "cmd", required_unless_present = "cmd")]
+        pane: Option<usize>,
+
+        /// Take --pane from a named layout instead of `panes`
+        #[arg(short = 'l', long, requires = "pane")]
+        layout: Option<String>,
+
+        /// Command to run in the new pane
+        #[arg(long)]
+        cmd: Option<String>,
+
+        /// Split direction (defaults to the pane's configured split, else horizontal)
+        #[arg(long, value_enum)]
+        split: Option<config::SplitDirection>,
+
+        /// Size of the new pane in lines or cells
+        #[arg(long, conflicts_with = "percentage")]
+        size: Option<u16>,
+
+        /// Size of the new pane as a percentage of the split pane
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=99))]
+        percentage: Option<u8>,
+
+        /// Pane id to split (defaults to the current pane, else the window's first)
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Switch to the new pane
+        #[arg(long)]
+        focus: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum LayoutCommands {
+    /// Draw the panes a layout creates, sized to fit, without creating anything
+    Preview {
+        /// Named layout from `layouts` (defaults to `panes`, or `windows`)
+        layout: Option<String>,
+
+        /// Window width in columns (defaults to the terminal's)
+        #[arg(long)]
+        width: Option<u16>,
+
+        /// Window height in lines (defaults to the terminal's)
+        #[arg(long)]
+        height: Option<u16>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveCommands {
+    /// List the project's archives, newest first
+    #[command(visible_alias = "ls")]
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Unpack an archive into a new directory
+    Extract {
+        /// Worktree handle (its newest archive), archive file name, or path
+        name: String,
+
+        /// Directory to unpack into (default: ./<handle>-<timestamp>)
+        #[arg(long)]
+        dest: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitQueryCommands {
+    /// Commits of the worktree's base branch, newest first
+    BaseLog {
+        /// Number of commits to list (at most 200)
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: u32,
+    },
+
+    /// Files changed by the worktree's commits against its base branch
+    DiffStat,
+
+    /// Content of a file at a ref
+    Show {
+        /// Branch, tag or commit
+        git_ref: String,
+
+        /// Path of the file, relative to the repository root
+        path: String,
+    },
+}
+
 /// Check if the command should show the nerdfont setup prompt.
 /// Only commands that display icons should trigger the prompt.
 fn should_prompt_nerdfont(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Add { .. } | Commands::Init | Commands::Dashboard { .. } | Commands::List { .. }
+        Commands::Add { .. }
+            | Commands::Init { .. }
+            | Commands::Dashboard { watch: false, .. }
+            | Commands::List { .. }
     )
 }
 
@@ -530,14 +1149,55 @@ fn should_prompt_nerdfont(cmd: &Commands) -> bool {
 fn should_prompt_status_setup(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Add { .. } | Commands::Init | Commands::Dashboard { .. } | Commands::List { .. }
+        Commands::Add { .. }
+            | Commands::Init { .. }
+            | Commands::Dashboard { watch: false, .. }
+            | Commands::List { .. }
     )
 }
 
+/// Command-line arguments as logged: inline prompt text (`-p`/`--prompt`,
+/// `send`'s text) goes through `prompt::log_repr`, so `redact_prompts` keeps it
+/// out of the log.
+fn logged_args(args: impl Iterator<Item = String>, cmd: &Commands, redact: bool) -> Vec<String> {
+    let prompts: Vec<&str> = match cmd {
+        Commands::Add { prompt, .. } | Commands::Open { prompt, .. } => {
+            prompt.prompt.as_deref().into_iter().collect()
+        }
+        Commands::Send { text, .. } => text.as_deref().into_iter().collect(),
+        _ => Vec::new(),
+    };
+    args.map(|arg| {
+        // The prompt is its own argument, or glued to its flag (`--prompt=...`)
+        match prompts
+            .iter()
+            .find(|p| !p.is_empty() && (arg == **p || arg.starts_with('-') && arg.ends_with(**p)))
+        {
+            Some(p) => format!(
+                "{}{}",
+                &arg[..arg.len() - p.len()],
+                prompt::log_repr(p, redact)
+            ),
+            None => arg,
+        }
+    })
+    .collect()
+}
+
 // --- Public Entry Point ---
 pub fn run() -> Result<()> {
     let cli = Cli::parse();
 
+    // Like `git -C`: everything below (VCS detection, config, workflow
+    // context) resolves paths from the current directory
+    if let Some(repo) = &cli.repo {
+        std::env::set_current_dir(repo)
+            .with_context(|| format!("Cannot change to '{}'", repo.display()))?;
+    }
+    if let Some(profile) = cli.profile.clone() {
+        config::set_profile(profile);
+    }
+
     // Always initialize nerdfont setting for prefix consistency across commands.
     // Only prompt interactively for commands that display icons.
     // If config fails to load, skip the nerdfont wizard -- it will be shown on
@@ -559,6 +1219,11 @@ pub fn run() -> Result<()> {
     };
     nerdfont::init(Some(nerdfont_enabled), has_pua);
 
+    tracing::info!(
+        args = ?logged_args(std::env::args(), &cli.command, cfg.redact_prompts()),
+        "workmux start"
+    );
+
     // Check agent status tracking setup after nerdfont.
     // Uses a separate gate to avoid double-prompting when running `workmux setup`.
     if config_ok
@@ -575,32 +1240,46 @@ pub fn run() -> Result<()> {
             auto_name,
             base,
             name,
+            no_policy,
+            apply,
             prompt,
             setup,
             rescue,
             multi,
             wait,
             session,
-        } => command::add::run(
-            branch_name.as_deref(),
-            pr,
-            auto_name,
-            base.as_deref(),
-            name,
-            prompt,
-            setup,
-            rescue,
-            multi,
-            wait,
-            session,
-        ),
+            json,
+        } => {
+            if json {
+                progress::set_mode(progress::Mode::Json);
+            }
+            command::add::run(
+                branch_name.as_deref(),
+                pr,
+                auto_name,
+                base.as_deref(),
+                name,
+                no_policy,
+                apply.as_deref(),
+                prompt,
+                setup,
+                rescue,
+                *multi,
+                wait,
+                session,
+            )
+        }
         Commands::Open {
             name,
             run_hooks,
             force_files,
             new,
+            pr,
             prompt,
-        } => command::open::run(name.as_deref(), run_hooks, force_files, new, prompt),
+        } => match pr {
+            Some(pr) => command::open::run_pr(pr, run_hooks, force_files, prompt),
+            None => command::open::run(name.as_deref(), run_hooks, force_files, new, prompt),
+        },
         Commands::Close { name } => command::close::run(name.as_deref()),
         Commands::Merge {
             name,
@@ -612,29 +1291,110 @@ pub fn run() -> Result<()> {
             no_verify,
             no_hooks,
             notification,
-        } => command::merge::run(
-            name.as_deref(),
-            into.as_deref(),
-            ignore_uncommitted,
-            rebase,
-            squash,
-            keep,
-            no_verify,
-            no_hooks,
-            notification,
-        ),
+            push,
+            delete_remote,
+            push_rebased,
+            on_conflict,
+            resume,
+            abort,
+            json,
+            to_upstream,
+        } => {
+            if json {
+                progress::set_mode(progress::Mode::Json);
+            }
+            if to_upstream {
+                return command::merge::run_to_upstream(name.as_deref(), into.as_deref());
+            }
+            command::merge::run(
+                name.as_deref(),
+                into.as_deref(),
+                ignore_uncommitted,
+                rebase,
+                squash,
+                keep,
+                no_verify,
+                no_hooks,
+                notification,
+                MergeRemoteSync {
+                    push,
+                    delete_remote,
+                    push_rebased,
+                },
+                on_conflict,
+                resume,
+                abort,
+            )
+        }
         Commands::Remove {
             names,
             gone,
             all,
             force,
             keep_branch,
-        } => command::remove::run(names, gone, all, force, keep_branch),
+            archive,
+            merged,
+            status,
+            older_than,
+            dry_run,
+        } => {
+            let options = command::remove::RemoveOptions {
+                force,
+                keep_branch,
+                archive,
+                dry_run,
+                filters: command::remove::RemoveFilters {
+                    merged,
+                    status: status.map(Into::into),
+                    older_than,
+                },
+            };
+            command::remove::run(names, gone, all, options)
+        }
         Commands::List { pr, filter } => command::list::run(pr, &filter),
+        Commands::Reattach {
+            name,
+            close,
+            branch,
+        } => command::reattach::run(&name, close, branch.as_deref()),
+        Commands::Archive { command } => match command {
+            ArchiveCommands::List { json } => command::archive::run_list(json),
+            ArchiveCommands::Extract { name, dest } => {
+                command::archive::run_extract(&name, dest.as_deref())
+            }
+        },
+        Commands::RestoreBranch { name } => command::restore_branch::run(name.as_deref()),
+        Commands::Grep {
+            pattern,
+            ignore_case,
+            fixed_strings,
+            active,
+        } => command::grep::run(&pattern, ignore_case, fixed_strings, active),
         Commands::Path { name } => command::path::run(&name),
-        Commands::Send { name, text, file } => {
-            command::send::run(&name, text.as_deref(), file.as_deref())
+        Commands::Lock { name, reason } => {
+            command::lock::run_lock(name.as_deref(), reason.as_deref())
         }
+        Commands::Unlock { name } => command::lock::run_unlock(name.as_deref()),
+        Commands::MoveDir {
+            name,
+            destination,
+            no_restart,
+        } => command::move_dir::run(&name, &destination, no_restart),
+        Commands::Convert { name, to } => command::convert::run(&name, to),
+        Commands::Send {
+            name,
+            flush: true,
+            retry,
+            ..
+        } => command::send::run_flush(&name, retry),
+        Commands::Send {
+            name,
+            text,
+            file,
+            allow_main,
+            attach,
+            ..
+        } => command::send::run(&name, text.as_deref(), file.as_deref(), &attach, allow_main),
         Commands::Capture { name, lines } => command::capture::run(&name, lines),
         Commands::Status {
             worktrees,
@@ -654,21 +1414,159 @@ pub fn run() -> Result<()> {
             keep,
             timeout,
         } => command::run::run(&name, command, background, keep, timeout),
+        Commands::ReportProgress {
+            step,
+            total,
+            message,
+            clear,
+        } => command::report_progress::run(step, total, message, clear),
+        Commands::RequestExec { args } => {
+            let (command, cmd_args) = args
+                .split_first()
+                .ok_or_else(|| anyhow::anyhow!("request-exec requires a command name"))?;
+            let code = command::host_exec::request(command, cmd_args)?;
+            std::process::exit(code);
+        }
+        Commands::GitQuery { query } => command::git_query::run(match query {
+            GitQueryCommands::BaseLog { limit } => GitQuery::BaseLog { limit: Some(limit) },
+            GitQueryCommands::DiffStat => GitQuery::DiffStat,
+            GitQueryCommands::Show { git_ref, path } => GitQuery::Show { git_ref, path },
+        }),
         Commands::Exec { run_dir } => command::exec::run(&run_dir),
-        Commands::Init => crate::config::Config::init(),
-        Commands::Setup => command::setup::run(),
+        Commands::PaneWait {
+            port,
+            file,
+            command,
+            timeout,
+        } => command::pane_wait::run(&port, &file, &command, timeout),
+        Commands::Init {
+            minimal,
+            with_sandbox,
+        } => command::init::run(minimal, with_sandbox),
+        Commands::Setup {
+            sandbox,
+            project,
+            keys,
+            print,
+            remove,
+        } => {
+            if keys {
+                command::setup_keys::run(print, remove)
+            } else {
+                command::setup::run(sandbox, project)
+            }
+        }
         Commands::Docs => command::docs::run(),
+        Commands::Doctor { fix } => command::doctor::run(fix),
         Commands::Changelog => command::changelog::run(),
-        Commands::Dashboard { preview_size, diff } => command::dashboard::run(preview_size, diff),
+        Commands::SelfUpdate { channel, check } => command::self_update::run(channel, check),
+        Commands::Dashboard {
+            preview_size,
+            diff,
+            allow_main,
+            watch,
+            interval,
+        } => {
+            if watch {
+                command::dashboard::watch::run(interval)
+            } else {
+                command::dashboard::run(preview_size, diff, allow_main)
+            }
+        }
+        Commands::Top {
+            interval,
+            once,
+            json,
+        } => command::top::run(interval, once, json),
+        Commands::Gc { dry_run, yes } => command::gc::run(dry_run, yes),
+        Commands::Share {
+            socket,
+            interval,
+            exec,
+        } => command::dashboard::share::run(socket, interval, exec.as_deref()),
         Commands::Config(args) => command::config::run(args),
         Commands::Claude { command } => match command {
             ClaudeCommands::Prune => prune_claude_config(),
         },
+        Commands::Pr { command } => match command {
+            PrCommands::Create {
+                name,
+                base,
+                title,
+                draft,
+                json,
+            } => {
+                if json {
+                    progress::set_mode(progress::Mode::Json);
+                }
+                command::pr::run_create(
+                    name.as_deref(),
+                    PrCreateOptions {
+                        base: base.as_deref(),
+                        title: title.as_deref(),
+                        draft,
+                    },
+                )
+            }
+        },
+        Commands::Group { command } => match command {
+            GroupCommands::Add { name, worktrees } => command::group::run_add(&name, &worktrees),
+            GroupCommands::Remove { name, worktrees } => {
+                command::group::run_remove(&name, &worktrees)
+            }
+            GroupCommands::List => command::group::run_list(),
+            GroupCommands::Open { name } => command::group::run_open(&name),
+            GroupCommands::Close { name } => command::group::run_close(&name),
+            GroupCommands::Status { name, json } => command::group::run_status(&name, json),
+        },
+        Commands::Pane { command } => match command {
+            PaneCommands::Add {
+                name,
+                pane,
+                layout,
+                cmd,
+                split,
+                size,
+                percentage,
+                target,
+                focus,
+            } => command::pane::run_add(
+                name.as_deref(),
+                command::pane::PaneAddArgs {
+                    pane,
+                    layout,
+                    cmd,
+                    split,
+                    size,
+                    percentage,
+                    target,
+                    focus,
+                },
+            ),
+        },
+        Commands::Layout { command } => match command {
+            LayoutCommands::Preview {
+                layout,
+                width,
+                height,
+            } => command::layout::run_preview(layout.as_deref(), width, height),
+        },
+        Commands::Meta { command } => match command {
+            MetaCommands::Show { name, json } => command::meta::run_show(&name, json),
+            MetaCommands::Set { name, key, value } => command::meta::run_set(&name, key, &value),
+            MetaCommands::Unset { name, key } => command::meta::run_unset(&name, key),
+        },
         Commands::Sandbox(args) => command::sandbox::run(args),
-        Commands::SetWindowStatus { command } => command::set_window_status::run(command),
+        Commands::SetWindowStatus {
+            command,
+            server_url,
+            pane,
+        } => command::set_window_status::run(command, server_url, pane),
         Commands::SetBase { base } => command::set_base::run(&base),
         Commands::LastDone => command::last_done::run(),
+        Commands::LastWaiting => command::last_done::run_waiting(),
         Commands::LastAgent => command::last_agent::run(),
+        Commands::CheckState => command::self_update::run_check_state(),
         Commands::HostExec { args } => {
             let (command, cmd_args) = args
                 .split_first()
@@ -698,6 +1596,15 @@ pub fn run() -> Result<()> {
             }
             Ok(())
         }
+        Commands::CompleteBaseBranches => {
+            // Fail silently on completion; don't disrupt the user's shell.
+            if let Some(vcs) = vcs::try_detect_vcs() {
+                for branch in vcs.list_base_branches().unwrap_or_default() {
+                    println!("{branch}");
+                }
+            }
+            Ok(())
+        }
     }
 }
 
@@ -738,3 +1645,40 @@ fn print_bash_dynamic_completion() {
 fn print_fish_dynamic_completion() {
     print!("{}", include_str!("scripts/completions/fish_dynamic.fish"));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        // Catches clashing flags, e.g. global options reusing a subcommand's short flag
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn logged_args_redact_inline_prompts() {
+        let logged = |argv: &[&str], redact: bool| {
+            let cli = Cli::try_parse_from(argv).unwrap();
+            let args = argv.iter().map(|a| a.to_string());
+            logged_args(args, &cli.command, redact)
+        };
+
+        assert_eq!(
+            logged(&["workmux", "add", "fix", "-p", "secret plan"], true),
+            ["workmux", "add", "fix", "-p", "<redacted, 11 bytes>"]
+        );
+        assert_eq!(
+            logged(&["workmux", "add", "fix", "--prompt=secret plan"], true),
+            ["workmux", "add", "fix", "--prompt=<redacted, 11 bytes>"]
+        );
+        assert_eq!(
+            logged(&["workmux", "send", "fix", "secret plan"], true),
+            ["workmux", "send", "fix", "<redacted, 11 bytes>"]
+        );
+        assert_eq!(
+            logged(&["workmux", "send", "fix", "secret plan"], false),
+            ["workmux", "send", "fix", "secret plan"]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result, anyhow};
+use std::io::Write;
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 use tracing::{debug, trace};
 
 /// A builder for executing shell commands with unified error handling
@@ -8,6 +9,7 @@ pub struct Cmd<'a> {
     command: &'a str,
     args: Vec<&'a str>,
     workdir: Option<&'a Path>,
+    stdin: Option<&'a str>,
 }
 
 impl<'a> Cmd<'a> {
@@ -17,6 +19,7 @@ impl<'a> Cmd<'a> {
             command,
             args: Vec::new(),
             workdir: None,
+            stdin: None,
         }
     }
 
@@ -38,6 +41,15 @@ impl<'a> Cmd<'a> {
         self
     }
 
+    /// Pipe `input` to the command's stdin.
+    ///
+    /// Use this for user content such as prompts: unlike arguments, stdin is
+    /// not visible in `ps` and is never written to the log.
+    pub fn stdin(mut self, input: &'a str) -> Self {
+        self.stdin = Some(input);
+        self
+    }
+
     /// Execute the command and return the output
     /// Returns an error if the command fails (non-zero exit code)
     pub fn run(self) -> Result<Output> {
@@ -45,18 +57,23 @@ impl<'a> Cmd<'a> {
             command,
             args,
             workdir,
+            stdin,
         } = self;
         let workdir_display = workdir.map(|p| p.display().to_string());
+        let stdin_len = stdin.map(str::len);
 
-        trace!(command, args = ?args, workdir = ?workdir_display, "cmd:run start");
+        trace!(command, args = ?args, workdir = ?workdir_display, stdin_len, "cmd:run start");
 
         let mut cmd = Command::new(command);
         if let Some(dir) = workdir {
             cmd.current_dir(dir);
         }
-        let output = cmd.args(&args).output().with_context(|| {
-            format!("Failed to execute command: {} {}", command, args.join(" "))
-        })?;
+        cmd.args(&args);
+        let output = match stdin {
+            Some(input) => output_with_stdin(&mut cmd, input),
+            None => cmd.output().map_err(anyhow::Error::from),
+        }
+        .with_context(|| format!("Failed to execute command: {} {}", command, args.join(" ")))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -91,6 +108,7 @@ impl<'a> Cmd<'a> {
             command,
             args,
             workdir,
+            stdin: _,
         } = self;
         let workdir_display = workdir.map(|p| p.display().to_string());
         trace!(command, args = ?args, workdir = ?workdir_display, "cmd:check start");
@@ -109,6 +127,19 @@ impl<'a> Cmd<'a> {
     }
 }
 
+/// Run `cmd` to completion with `input` written to its stdin.
+fn output_with_stdin(cmd: &mut Command, input: &str) -> Result<Output> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(input.as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
 /// Helper to create a shell command with additional environment variables
 pub fn shell_command_with_env(
     command: &str,
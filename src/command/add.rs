@@ -1,8 +1,10 @@
-use crate::config::MuxMode;
+use crate::branch_policy::BranchPolicy;
+use crate::config::{FocusPolicy, MuxMode};
 use crate::multiplexer::handle::mode_label;
-use crate::multiplexer::{MuxHandle, create_backend, detect_backend, util::prefixed};
+use crate::multiplexer::{Multiplexer, MuxHandle, create_backend, detect_backend, util::prefixed};
+use crate::progress;
 use crate::prompt::{Prompt, PromptDocument, foreach_from_frontmatter};
-use crate::spinner;
+use crate::state::events::{Event, WorktreeReady};
 use crate::template::{
     TemplateEnv, WorktreeSpec, create_template_env, generate_worktree_specs, parse_foreach_matrix,
     render_prompt_body, validate_template_variables,
@@ -10,11 +12,12 @@ use crate::template::{
 use crate::workflow::SetupOptions;
 use crate::workflow::pr::detect_remote_branch;
 use crate::workflow::prompt_loader::{PromptLoadArgs, load_prompt, parse_prompt_with_frontmatter};
-use crate::{config, vcs, workflow};
+use crate::{config, state, vcs, workflow};
 use anyhow::{Context, Result, anyhow, bail};
 use serde_json::Value;
 use std::collections::BTreeMap;
 use std::io::{IsTerminal, Read};
+use std::path::Path;
 
 // Re-export the arg types that are used by the CLI
 pub use super::args::{MultiArgs, PromptArgs, RescueArgs, SetupFlags};
@@ -41,10 +44,10 @@ fn generate_branch_name_with_spinner(
         .as_ref()
         .and_then(|c| c.system_prompt.as_deref());
 
-    let generated = spinner::with_spinner("Generating branch name", || {
+    let generated = progress::step("Generating branch name", || {
         crate::llm::generate_branch_name(prompt_text, model, system_prompt)
     })?;
-    println!("  Branch: {}", generated);
+    progress::info(format!("  Branch: {}", generated));
 
     Ok(generated)
 }
@@ -103,6 +106,54 @@ fn check_preconditions() -> Result<()> {
     Err(anyhow!(errors.join("\n")))
 }
 
+/// Whether the window (or session) `add` creates should take focus under
+/// `policy`.
+///
+/// From a popup, such as the dashboard's, the client is never switched to
+/// another session: that would close the popup under the running TUI.
+fn should_focus(policy: FocusPolicy, mode: MuxMode, mux: &dyn Multiplexer) -> bool {
+    if mode == MuxMode::Session && mux.in_popup() {
+        return false;
+    }
+    match policy {
+        FocusPolicy::Always => true,
+        FocusPolicy::Never => false,
+        FocusPolicy::IfIdle => !current_window_busy(mux),
+    }
+}
+
+/// Whether an agent in the window the client is looking at is working.
+/// Unknown counts as idle.
+fn current_window_busy(mux: &dyn Multiplexer) -> bool {
+    let Some(active) = mux.active_pane_id() else {
+        return false;
+    };
+    let Some(window) = mux
+        .get_all_live_pane_info()
+        .ok()
+        .and_then(|panes| panes.get(&active).cloned())
+    else {
+        return false;
+    };
+    let agents = state::StateStore::new()
+        .and_then(|store| store.load_reconciled_agents(mux))
+        .unwrap_or_default();
+    any_working_in(&agents, window.session.as_deref(), window.window.as_deref())
+}
+
+/// Whether any agent in `session`'s `window` is working.
+fn any_working_in(
+    agents: &[crate::multiplexer::AgentPane],
+    session: Option<&str>,
+    window: Option<&str>,
+) -> bool {
+    agents.iter().any(|agent| {
+        agent.status == Some(crate::multiplexer::AgentStatus::Working)
+            && session.is_none_or(|s| agent.session == s)
+            && window.is_some_and(|w| agent.window_name == w)
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     branch_name: Option<&str>,
@@ -110,6 +161,8 @@ pub fn run(
     auto_name: bool,
     base: Option<&str>,
     name: Option<String>,
+    no_policy: bool,
+    apply: Option<&Path>,
     prompt_args: PromptArgs,
     setup: SetupFlags,
     rescue: RescueArgs,
@@ -129,6 +182,8 @@ pub fn run(
             base,
             pr,
             name.as_deref(),
+            no_policy,
+            apply,
             wait,
             session,
         );
@@ -137,11 +192,39 @@ pub fn run(
     // Ensure preconditions are met (git repo and multiplexer session)
     check_preconditions()?;
 
-    // Extract sandbox override before consuming setup flags
-    let sandbox_override = setup.sandbox;
-
     // Load config early to determine mode (CLI flag overrides config)
     let initial_config = config::Config::load(multi.agent.first().map(|s| s.as_str()))?;
+
+    // Report repository setup problems (no commits, unknown default branch,
+    // no remotes) before anything is created
+    let vcs = vcs::detect_vcs()?;
+    workflow::preflight::ensure_ready(vcs.as_ref(), &initial_config, pr.is_some())?;
+
+    // Validate the base up front so typos fail before any LLM call or
+    // worktree creation
+    if let Some(base) = base {
+        workflow::ensure_base_exists(vcs.as_ref(), &progress::Terminal, base)?;
+    }
+    if let Some(layout) = &setup.layout {
+        initial_config.clone().apply_layout(layout)?;
+    }
+    if let Some(patch) = apply
+        && !patch.is_file()
+    {
+        bail!("Patch file '{}' not found", patch.display());
+    }
+    for tag in &setup.tags {
+        crate::plugin::validate_tag(tag)?;
+    }
+    // Branch naming rules apply to every branch this command creates
+    let policy = if no_policy {
+        None
+    } else {
+        BranchPolicy::from_config(&initial_config.branch_policy)?
+    };
+
+    // Extract sandbox override before consuming setup flags
+    let sandbox_override = setup.sandbox;
     let mode = if session {
         MuxMode::Session
     } else {
@@ -150,9 +233,27 @@ pub fn run(
 
     // Construct setup options from flags
     let mut options = SetupOptions::new(!setup.no_hooks, !setup.no_file_ops, !setup.no_pane_cmds);
-    options.focus_window = !setup.background;
+    options.focus_window = !setup.background
+        && should_focus(
+            initial_config.focus_new_window(),
+            mode,
+            create_backend(detect_backend()).as_ref(),
+        );
     options.open_if_exists = setup.open_if_exists;
+    options.on_collision = setup
+        .on_collision
+        .unwrap_or_else(|| initial_config.on_collision());
     options.mode = mode;
+    options.guardrails = !prompt_args.no_guardrails;
+    // Check attachments up front, so a missing file fails before anything is created
+    options.attachments = prompt_args
+        .attach
+        .iter()
+        .map(|path| {
+            path.canonicalize()
+                .with_context(|| format!("Attachment '{}' not found", path.display()))
+        })
+        .collect::<Result<_>>()?;
 
     // If using --auto-name and config has auto_name.background = true, run in background
     if auto_name && options.focus_window {
@@ -262,8 +363,12 @@ pub fn run(
         if sandbox_override {
             rescue_config.sandbox.enabled = Some(true);
         }
+        if let Some(layout) = &setup.layout {
+            rescue_config.apply_layout(layout)?;
+        }
         let mux = create_backend(detect_backend());
         let rescue_context = workflow::WorkflowContext::new(rescue_config, mux, rescue_location)?;
+        let branch_name = &apply_branch_policy(policy.as_ref(), vcs.as_ref(), branch_name, None)?;
         // Derive handle for rescue flow (uses config for naming strategy/prefix)
         let handle =
             crate::naming::derive_handle(branch_name, name.as_deref(), &rescue_context.config)?;
@@ -275,6 +380,7 @@ pub fn run(
             options.clone(),
             wait,
         )? {
+            tag_worktree(rescue_context.vcs.as_ref(), &handle, &setup.tags)?;
             return Ok(());
         }
     }
@@ -290,6 +396,12 @@ pub fn run(
         })?
     };
 
+    if prompt_template.is_none() && !options.attachments.is_empty() {
+        bail!(
+            "--attach needs a prompt to reference the files in (--prompt, --prompt-file or --prompt-editor)"
+        );
+    }
+
     // Parse prompt document to extract frontmatter (if applicable)
     let prompt_doc = if let Some(ref prompt_src) = prompt_template {
         // Account for implicit editor usage triggered by auto_name
@@ -378,10 +490,50 @@ pub fn run(
         deferred_auto_name,
         max_concurrent: multi.max_concurrent,
         sandbox_override,
+        layout: setup.layout.as_deref(),
+        tags: &setup.tags,
+        policy: policy.as_ref(),
+        vcs: vcs.as_ref(),
+        apply,
     };
     plan.execute()
 }
 
+/// The name of the branch to create for `branch_name` under the branch
+/// policy. Existing branches and remote refs keep their names.
+fn apply_branch_policy(
+    policy: Option<&BranchPolicy>,
+    vcs: &dyn vcs::Vcs,
+    branch_name: &str,
+    remote_branch: Option<&str>,
+) -> Result<String> {
+    let Some(policy) = policy else {
+        return Ok(branch_name.to_string());
+    };
+    if remote_branch.is_some() || vcs.branch_exists(branch_name)? {
+        return Ok(branch_name.to_string());
+    }
+    let branch = policy.apply(branch_name)?;
+    if branch != branch_name {
+        progress::info(format!("  Branch: {} (branch_policy)", branch));
+    }
+    Ok(branch)
+}
+
+/// Add `tags` to the worktree's tags (shown by `workmux list`).
+fn tag_worktree(vcs: &dyn vcs::Vcs, handle: &str, tags: &[String]) -> Result<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let mut stored = vcs
+        .get_workspace_meta(handle, crate::plugin::TAGS_META_KEY)
+        .unwrap_or_default();
+    for tag in tags {
+        stored = crate::plugin::add_tag(Some(&stored), tag);
+    }
+    vcs.set_workspace_meta(handle, crate::plugin::TAGS_META_KEY, &stored)
+}
+
 /// Handle the rescue flow (--with-changes).
 /// Returns Ok(true) if rescue flow was handled, Ok(false) if normal flow should continue.
 fn handle_rescue_flow(
@@ -409,11 +561,16 @@ fn handle_rescue_flow(
     )
     .context("Failed to move uncommitted changes")?;
 
-    println!(
-        "✓ Moved uncommitted changes to new worktree for branch '{}'\n  Worktree: {}\n  Original worktree is now clean",
-        result.branch_name,
-        result.worktree_path.display()
-    );
+    progress::success(format!(
+        "Moved uncommitted changes to new worktree for branch '{}'",
+        result.branch_name
+    ));
+    progress::info(format!("  Worktree: {}", result.worktree_path.display()));
+    progress::info("  Original worktree is now clean");
+    progress::result(&serde_json::json!({
+        "branch": result.branch_name,
+        "worktree": result.worktree_path,
+    }));
 
     if wait {
         MuxHandle::new(context.mux.as_ref(), mode, &context.prefix, handle).wait_until_closed()?;
@@ -493,6 +650,9 @@ fn determine_foreach_matrix(
 /// Polling interval for checking window status in worker pool mode
 const WORKER_POOL_POLL_MS: u64 = 250;
 
+/// Lines of agent output included in the summary of a background worktree
+const SUMMARY_OUTPUT_LINES: usize = 5;
+
 /// Encapsulates all parameters needed for worktree creation.
 struct CreationPlan<'a> {
     specs: &'a [WorktreeSpec],
@@ -506,6 +666,11 @@ struct CreationPlan<'a> {
     deferred_auto_name: bool,
     max_concurrent: Option<u32>,
     sandbox_override: bool,
+    layout: Option<&'a str>,
+    tags: &'a [String],
+    policy: Option<&'a BranchPolicy<'a>>,
+    vcs: &'a dyn vcs::Vcs,
+    apply: Option<&'a Path>,
 }
 
 impl<'a> CreationPlan<'a> {
@@ -516,7 +681,10 @@ impl<'a> CreationPlan<'a> {
 
     fn create_worktrees(&self) -> Result<()> {
         if self.specs.len() > 1 {
-            println!("Preparing to create {} worktrees...", self.specs.len());
+            progress::info(format!(
+                "Preparing to create {} worktrees...",
+                self.specs.len()
+            ));
         }
 
         // Create backend once for all specs
@@ -526,6 +694,8 @@ impl<'a> CreationPlan<'a> {
         let mut created_targets = Vec::new();
         // Track currently active targets for --max-concurrent
         let mut active_targets: Vec<String> = Vec::new();
+        // Handles created without switching to them, for the ready notification
+        let mut ready_in_background: Vec<String> = Vec::new();
         let mode = self.options.mode;
 
         for (i, spec) in self.specs.iter().enumerate() {
@@ -555,6 +725,9 @@ impl<'a> CreationPlan<'a> {
             if self.sandbox_override {
                 config.sandbox.enabled = Some(true);
             }
+            if let Some(layout) = self.layout {
+                config.apply_layout(layout)?;
+            }
 
             // Render prompt first (needed for deferred auto-name)
             let rendered_prompt = if let Some(doc) = self.prompt_doc {
@@ -565,6 +738,14 @@ impl<'a> CreationPlan<'a> {
             } else {
                 None
             };
+            if let Some(prompt) = &rendered_prompt {
+                crate::prompt::ensure_size(prompt, config.max_prompt_kb())?;
+                tracing::debug!(
+                    spec = i,
+                    prompt = %crate::prompt::log_repr(prompt, config.redact_prompts()),
+                    "add:rendered prompt"
+                );
+            }
 
             // If auto-name was deferred, run it now using the rendered prompt
             let final_branch_name = if self.deferred_auto_name {
@@ -572,14 +753,20 @@ impl<'a> CreationPlan<'a> {
             } else {
                 spec.branch_name.clone()
             };
+            let final_branch_name = apply_branch_policy(
+                self.policy,
+                self.vcs,
+                &final_branch_name,
+                self.remote_branch,
+            )?;
 
             if self.specs.len() > 1 {
-                println!(
+                progress::info(format!(
                     "\n--- [{}/{}] Creating worktree: {} ---",
                     i + 1,
                     self.specs.len(),
                     final_branch_name
-                );
+                ));
             }
 
             // Derive handle from branch name, optional explicit name, and config
@@ -587,24 +774,31 @@ impl<'a> CreationPlan<'a> {
             let handle =
                 crate::naming::derive_handle(&final_branch_name, self.explicit_name, &config)?;
 
-            let prompt_for_spec = rendered_prompt.map(Prompt::Inline);
+            // Keep system instructions and context files alongside the task so
+            // the agent command can pass them separately
+            let prompt_for_spec = match (rendered_prompt, self.prompt_doc) {
+                (Some(body), Some(doc)) => {
+                    let system = doc
+                        .meta
+                        .system
+                        .as_deref()
+                        .map(|system| render_prompt_body(system, self.env, &spec.template_context))
+                        .transpose()
+                        .context("Failed to render prompt system instructions")?;
+                    Some(Prompt::Inline(crate::prompt::with_structure(
+                        system.as_deref(),
+                        doc.meta.context.as_deref(),
+                        &body,
+                    )?))
+                }
+                (body, _) => body.map(Prompt::Inline),
+            };
 
             super::announce_hooks(&config, Some(&self.options), super::HookPhase::PostCreate);
 
             // Create a WorkflowContext for this spec's config (reuse shared mux)
-            let context = workflow::WorkflowContext::new(config, mux.clone(), config_location)?;
-
-            // Calculate window name for tracking
-            let full_window_name = prefixed(&context.prefix, &handle);
-
-            if self.wait {
-                created_targets.push(full_window_name.clone());
-            }
-
-            // Track for concurrency control
-            if self.max_concurrent.is_some() {
-                active_targets.push(full_window_name);
-            }
+            let mut context = workflow::WorkflowContext::new(config, mux.clone(), config_location)?;
+            super::announce_prefix_qualification(&mut context, &handle, self.options.mode)?;
 
             let result = workflow::create(
                 &context,
@@ -616,6 +810,7 @@ impl<'a> CreationPlan<'a> {
                     prompt: prompt_for_spec.as_ref(),
                     options: self.options.clone(),
                     agent: spec.agent.as_deref(),
+                    apply: self.apply,
                 },
             )
             .with_context(|| {
@@ -625,19 +820,67 @@ impl<'a> CreationPlan<'a> {
                 )
             })?;
 
+            tag_worktree(context.vcs.as_ref(), &handle, self.tags)?;
+
+            // Calculate window name for tracking (a collision may have
+            // suffixed it)
+            let full_window_name = prefixed(&context.prefix, &result.handle);
+
+            if self.wait {
+                created_targets.push(full_window_name.clone());
+            }
+
+            // Track for concurrency control
+            if self.max_concurrent.is_some() {
+                active_targets.push(full_window_name.clone());
+            }
+
             if result.post_create_hooks_run > 0 {
-                println!("✓ Setup complete");
+                progress::success("Setup complete");
             }
 
-            println!(
-                "✓ Successfully created worktree and tmux {} for '{}'",
-                mode_label(mode),
-                result.branch_name
-            );
+            if result.did_switch {
+                progress::success(format!(
+                    "Switched to existing tmux {} for '{}'",
+                    mode_label(mode),
+                    result.branch_name
+                ));
+            } else {
+                progress::success(format!(
+                    "Successfully created worktree and tmux {} for '{}'",
+                    mode_label(mode),
+                    result.branch_name
+                ));
+            }
             if let Some(ref base) = result.base_branch {
-                println!("  Base: {}", base);
+                progress::info(format!("  Base: {}", base));
+            }
+            progress::info(format!("  Worktree: {}", result.worktree_path.display()));
+            let summary = serde_json::json!({
+                "branch": result.branch_name,
+                "handle": result.handle,
+                "window": full_window_name,
+                "worktree": result.worktree_path,
+                "base": result.base_branch,
+                "switched": result.did_switch,
+            });
+
+            if !self.options.focus_window && !result.did_switch {
+                let ready = background_summary(mux.as_ref(), &handle, full_window_name, result);
+                print_background_summary(&ready);
+                if let Err(e) = state::events::append(&Event::WorktreeReady(ready)) {
+                    tracing::warn!(error = %e, "add:failed to record worktree_ready event");
+                }
+                ready_in_background.push(handle);
             }
-            println!("  Worktree: {}", result.worktree_path.display());
+            progress::result(&summary);
+        }
+
+        if !ready_in_background.is_empty() {
+            crate::util::show_notification(&match ready_in_background.as_slice() {
+                [handle] => format!("'{}' is ready", handle),
+                handles => format!("{} worktrees are ready", handles.len()),
+            });
         }
 
         if self.wait && !created_targets.is_empty() {
@@ -655,10 +898,63 @@ impl<'a> CreationPlan<'a> {
     }
 }
 
+/// Summarize a worktree created in the background.
+fn background_summary(
+    mux: &dyn Multiplexer,
+    handle: &str,
+    window: String,
+    result: workflow::types::CreateResult,
+) -> WorktreeReady {
+    let agent_output = result
+        .focus_pane_id
+        .as_deref()
+        .and_then(|pane_id| mux.capture_pane(pane_id, 200))
+        .map(|content| {
+            strip_ansi_escapes::strip_str(&content)
+                .lines()
+                .map(str::trim_end)
+                .filter(|line| !line.trim().is_empty())
+                .take(SUMMARY_OUTPUT_LINES)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    WorktreeReady {
+        handle: handle.to_string(),
+        branch: result.branch_name,
+        window,
+        worktree_path: result.worktree_path,
+        sandbox_vm: result.sandbox_vm,
+        post_create_hooks: result.post_create_hooks_run,
+        agent_output,
+    }
+}
+
+fn print_background_summary(ready: &WorktreeReady) {
+    progress::info(format!("  Ready in background: {}", ready.window));
+    if let Some(vm) = &ready.sandbox_vm {
+        progress::info(format!("  Sandbox VM: {}", vm));
+    }
+    if ready.post_create_hooks > 0 {
+        progress::info(format!(
+            "  Hooks: {} post-create hook(s) succeeded",
+            ready.post_create_hooks
+        ));
+    }
+    if !ready.agent_output.is_empty() {
+        progress::info("  Agent output:");
+        for line in &ready.agent_output {
+            progress::info(format!("    {}", line));
+        }
+    }
+}
+
 /// Route `workmux add` through SpawnAgent RPC when running inside a sandbox.
 ///
 /// Only a subset of `add` flags are supported over RPC. Unsupported flags
 /// are explicitly rejected with a clear error rather than silently ignored.
+/// The host checks the agent, layout and base against its `sandbox.spawn`
+/// policy.
 #[allow(clippy::too_many_arguments)]
 fn run_add_via_rpc(
     branch_name: Option<&str>,
@@ -670,6 +966,8 @@ fn run_add_via_rpc(
     base: Option<&str>,
     pr: Option<u32>,
     name: Option<&str>,
+    no_policy: bool,
+    apply: Option<&Path>,
     wait: bool,
     session: bool,
 ) -> Result<()> {
@@ -677,23 +975,36 @@ fn run_add_via_rpc(
     use crate::workflow::prompt_loader::{PromptLoadArgs, load_prompt};
 
     // --- Validate: reject unsupported flags explicitly ---
-    if base.is_some() {
-        bail!("--base is not supported from inside a sandbox");
-    }
     if pr.is_some() {
         bail!("--pr is not supported from inside a sandbox");
     }
     if name.is_some() {
         bail!("--name is not supported from inside a sandbox");
     }
+    if no_policy {
+        bail!("--no-policy is not supported from inside a sandbox (host enforces branch_policy)");
+    }
+    if apply.is_some() {
+        bail!("--apply is not supported from inside a sandbox");
+    }
+    if prompt_args.no_guardrails {
+        bail!(
+            "--no-guardrails is not supported from inside a sandbox (host applies its prompt guardrails)"
+        );
+    }
+    if !prompt_args.attach.is_empty() {
+        bail!("--attach is not supported from inside a sandbox");
+    }
     if wait {
         bail!("--wait is not supported from inside a sandbox");
     }
     if rescue.with_changes {
         bail!("--with-changes is not supported from inside a sandbox");
     }
-    if !multi.agent.is_empty() {
-        bail!("--agent is not supported from inside a sandbox (uses host config)");
+    if multi.agent.len() > 1 {
+        bail!(
+            "Only one --agent is supported from inside a sandbox. Call workmux add once per agent instead."
+        );
     }
     if multi.count.is_some() {
         bail!(
@@ -708,6 +1019,11 @@ fn run_add_via_rpc(
             "--session is not supported from inside a sandbox (host controls mode via its config)"
         );
     }
+    if setup.on_collision.is_some() {
+        bail!(
+            "--on-collision is not supported from inside a sandbox (host controls it via its config)"
+        );
+    }
 
     // --- Resolve prompt via existing loader (handles -p, -P, -e) ---
     let prompt_content = load_prompt(&PromptLoadArgs {
@@ -739,12 +1055,16 @@ fn run_add_via_rpc(
         prompt: prompt_text.unwrap_or_default(),
         branch_name: rpc_branch.clone(),
         background: if setup.background { Some(true) } else { None },
+        base: base.map(str::to_string),
+        layout: setup.layout.clone(),
+        tags: setup.tags.clone(),
+        agent: multi.agent.first().cloned(),
     })?;
 
     match resp {
         RpcResponse::Ok => {
             let display_name = rpc_branch.as_deref().unwrap_or("(auto-named)");
-            println!("✓ Spawned agent: {}", display_name);
+            progress::success(format!("Spawned agent: {}", display_name));
             Ok(())
         }
         RpcResponse::Error { message } => {
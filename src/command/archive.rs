@@ -0,0 +1,86 @@
+//! `workmux archive`: list and unpack snapshots made by `remove --archive`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::command::gc::format_size;
+use crate::command::sandbox::format_duration_since;
+use crate::config;
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::workflow::WorkflowContext;
+use crate::workflow::archive::{self, Manifest};
+
+#[derive(Serialize)]
+struct ArchiveEntry {
+    path: PathBuf,
+    size: u64,
+    #[serde(flatten)]
+    manifest: Option<Manifest>,
+}
+
+fn archive_dir() -> Result<PathBuf> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+    archive::archive_dir(&context.config, &context.main_worktree_root)
+}
+
+pub fn run_list(json: bool) -> Result<()> {
+    let dir = archive_dir()?;
+    let entries: Vec<ArchiveEntry> = archive::list_archives(&dir)
+        .into_iter()
+        .map(|a| ArchiveEntry {
+            size: a.path.metadata().map(|m| m.len()).unwrap_or(0),
+            manifest: archive::read_manifest(&a.path).ok(),
+            path: a.path,
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No archives in {}", dir.display());
+        return Ok(());
+    }
+    for entry in &entries {
+        let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+        match &entry.manifest {
+            Some(m) => println!(
+                "{}  {}  branch '{}', {} files, {}",
+                name,
+                format_duration_since(UNIX_EPOCH + Duration::from_secs(m.created)),
+                m.branch,
+                m.files,
+                format_size(entry.size)
+            ),
+            None => println!("{}  (unreadable manifest)", name),
+        }
+    }
+    Ok(())
+}
+
+pub fn run_extract(name: &str, dest: Option<&Path>) -> Result<()> {
+    let path = archive::find_archive(&archive_dir()?, name)?;
+    let dest = match dest {
+        Some(dest) => dest.to_path_buf(),
+        None => {
+            let file = path.file_name().unwrap_or_default().to_string_lossy();
+            let stem = file.strip_suffix(".tar.gz").unwrap_or(&file);
+            PathBuf::from(stem.replace('@', "-"))
+        }
+    };
+    archive::extract(&path, &dest)?;
+    println!("✓ Extracted {} to {}", path.display(), dest.display());
+    if dest.join("diff.patch").is_file() {
+        println!(
+            "  Re-apply the changes with: git apply {}",
+            dest.join("diff.patch").display()
+        );
+    }
+    Ok(())
+}
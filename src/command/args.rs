@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
-#[derive(clap::Args, Debug)]
+use crate::config::WindowCollision;
+
+#[derive(clap::Args, Debug, Clone, Default)]
 pub struct PromptArgs {
     /// Inline prompt text to store in the new worktree
     #[arg(short = 'p', long, conflicts_with_all = ["prompt_file", "prompt_editor"])]
@@ -18,9 +20,17 @@ pub struct PromptArgs {
     /// Open $EDITOR to write the prompt
     #[arg(short = 'e', long = "prompt-editor", conflicts_with_all = ["prompt", "prompt_file"])]
     pub prompt_editor: bool,
+
+    /// Don't wrap the prompt in the configured prompt_prefix/prompt_suffix
+    #[arg(long)]
+    pub no_guardrails: bool,
+
+    /// Copy a file into the worktree and reference it in the prompt (repeatable)
+    #[arg(long = "attach", value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    pub attach: Vec<PathBuf>,
 }
 
-#[derive(clap::Args, Debug)]
+#[derive(clap::Args, Debug, Default)]
 pub struct SetupFlags {
     /// Skip running post-create hooks
     #[arg(short = 'H', long)]
@@ -34,8 +44,9 @@ pub struct SetupFlags {
     #[arg(short = 'C', long)]
     pub no_pane_cmds: bool,
 
-    /// Create tmux window in the background (do not switch to it)
-    #[arg(short = 'b', long = "background")]
+    /// Create tmux window in the background (do not switch to it).
+    /// Overrides `focus_new_window` from config
+    #[arg(short = 'b', long = "background", visible_alias = "no-switch")]
     pub background: bool,
 
     /// Open existing worktree if it exists instead of failing (like tmux new -A)
@@ -45,8 +56,23 @@ pub struct SetupFlags {
     /// Enable sandbox mode even when disabled in config
     #[arg(short = 'S', long)]
     pub sandbox: bool,
+
+    /// Use a named pane layout from the `layouts` config instead of `panes`
+    #[arg(short = 'l', long)]
+    pub layout: Option<String>,
+
+    /// Tag the new worktree (repeatable). Tags are shown by `workmux list`
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// What to do when a window with the target name already exists
+    /// (overrides `on_collision` from config)
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub on_collision: Option<WindowCollision>,
 }
 
+const DEFAULT_BRANCH_TEMPLATE: &str = r#"{{ base_name }}{% if agent %}-{{ agent | slugify }}{% endif %}{% for key in foreach_vars %}-{{ foreach_vars[key] | slugify }}{% endfor %}{% if num %}-{{ num }}{% endif %}"#;
+
 #[derive(clap::Args, Debug)]
 pub struct MultiArgs {
     /// The agent(s) to use. Creates one worktree per agent if -n is not specified.
@@ -71,10 +97,7 @@ pub struct MultiArgs {
 
     /// Template for branch names in multi-worktree modes.
     /// Variables: {{ base_name }}, {{ agent }}, {{ num }}, {{ foreach_vars }}.
-    #[arg(
-        long,
-        default_value = r#"{{ base_name }}{% if agent %}-{{ agent | slugify }}{% endif %}{% for key in foreach_vars %}-{{ foreach_vars[key] | slugify }}{% endfor %}{% if num %}-{{ num }}{% endif %}"#
-    )]
+    #[arg(long, default_value = DEFAULT_BRANCH_TEMPLATE)]
     pub branch_template: String,
 
     /// Maximum number of worktrees to run concurrently.
@@ -83,7 +106,19 @@ pub struct MultiArgs {
     pub max_concurrent: Option<u32>,
 }
 
-#[derive(clap::Args, Debug)]
+impl Default for MultiArgs {
+    fn default() -> Self {
+        Self {
+            agent: Vec::new(),
+            count: None,
+            foreach: None,
+            branch_template: DEFAULT_BRANCH_TEMPLATE.to_string(),
+            max_concurrent: None,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Default)]
 pub struct RescueArgs {
     /// Move uncommitted changes from the current worktree to the new worktree
     #[arg(short = 'w', long, conflicts_with_all = ["count", "foreach"])]
@@ -6,7 +6,6 @@ use anyhow::{Context, Result, anyhow};
 pub fn run(name: Option<&str>) -> Result<()> {
     let config = config::Config::load(None)?;
     let mux = create_backend(detect_backend());
-    let prefix = config.window_prefix();
 
     // Resolve the handle first to determine target mode
     let resolved_handle = match name {
@@ -16,6 +15,7 @@ pub fn run(name: Option<&str>) -> Result<()> {
 
     // Determine if this worktree was created as a session or window
     let vcs = vcs::detect_vcs()?;
+    let prefix = &config.window_prefix_for(vcs.as_ref());
     let mode = vcs.get_workspace_mode(&resolved_handle);
 
     // When no name is provided, prefer the current window/session name
@@ -0,0 +1,222 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::ValueEnum;
+
+use crate::config::{self, MuxMode};
+use crate::multiplexer::handle::mode_label;
+use crate::multiplexer::util::prefixed;
+use crate::multiplexer::{LivePaneInfo, create_backend, detect_backend};
+use crate::state::{self, StateStore};
+use crate::util::canon_or_self;
+use crate::workflow::WorkflowContext;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ConvertTarget {
+    /// A window in another session
+    Window,
+    /// A session of its own
+    Session,
+}
+
+impl From<ConvertTarget> for MuxMode {
+    fn from(target: ConvertTarget) -> Self {
+        match target {
+            ConvertTarget::Window => MuxMode::Window,
+            ConvertTarget::Session => MuxMode::Session,
+        }
+    }
+}
+
+pub fn run(name: &str, to: ConvertTarget) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+
+    let (path, _branch) = context.vcs.find_workspace(name).with_context(|| {
+        format!(
+            "No workspace found with name '{}'. Use 'workmux list' to see available workspaces.",
+            name
+        )
+    })?;
+    if canon_or_self(&path) == canon_or_self(&context.main_worktree_root) {
+        bail!("Cannot convert the main worktree");
+    }
+    let handle = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid worktree path: no directory name"))?
+        .to_string_lossy()
+        .to_string();
+
+    let from = context.vcs.get_workspace_mode(&handle);
+    let to = MuxMode::from(to);
+    if from == to {
+        println!("'{}' is already a {}", handle, mode_label(to));
+        return Ok(());
+    }
+    if !context.mux.is_running().unwrap_or(false) {
+        bail!("{} is not running", context.mux.name());
+    }
+
+    let full_name = prefixed(&context.prefix, &handle);
+    let panes = context.mux.get_all_live_pane_info()?;
+
+    match to {
+        MuxMode::Session => {
+            let pane_id = window_pane(&panes, &full_name).ok_or_else(|| {
+                anyhow!(
+                    "No open window '{}'. Open it first with 'workmux open {}'",
+                    full_name,
+                    handle
+                )
+            })?;
+            if context.mux.session_exists(&full_name)? {
+                bail!("A session named '{}' already exists", full_name);
+            }
+
+            context
+                .mux
+                .move_window_to_new_session(&pane_id, &full_name, &path)
+                .context("Failed to move window into a new session")?;
+            context.vcs.set_workspace_meta(&handle, "mode", "session")?;
+
+            // Seed the session status rollup from agents already in the window
+            let has_agents = StateStore::new()
+                .and_then(|store| store.list_all_agents())
+                .is_ok_and(|agents| {
+                    agents.iter().any(|agent| {
+                        panes
+                            .get(&agent.pane_key.pane_id)
+                            .and_then(|p| p.window.as_deref())
+                            == Some(full_name.as_str())
+                    })
+                });
+            if has_agents {
+                state::refresh_session_status(context.mux.as_ref(), &pane_id, &context.config);
+            }
+
+            if context.mux.current_pane_id().is_some() {
+                let _ = context.mux.switch_to_session(&context.prefix, &handle);
+            }
+        }
+        MuxMode::Window => {
+            let (pane_id, window_count) = session_pane(&panes, &full_name).ok_or_else(|| {
+                anyhow!(
+                    "No open session '{}'. Open it first with 'workmux open {}'",
+                    full_name,
+                    handle
+                )
+            })?;
+            if window_count > 1 {
+                bail!(
+                    "Session '{}' has {} windows; only single-window sessions can become a window",
+                    full_name,
+                    window_count
+                );
+            }
+
+            // Join the session the client is in, or the one it came from when
+            // running inside the worktree's own session
+            let current = context.mux.current_session();
+            let target = current
+                .clone()
+                .filter(|s| *s != full_name)
+                .or_else(|| context.mux.last_session().filter(|s| *s != full_name))
+                .ok_or_else(|| {
+                    anyhow!("Run 'workmux convert' from the session the window should join")
+                })?;
+            if context.mux.window_exists_by_full_name(&full_name)? {
+                bail!("A window named '{}' already exists", full_name);
+            }
+
+            // The session closes once its last window moves out; switch away
+            // first so the client isn't detached
+            if current.as_deref() == Some(full_name.as_str()) {
+                context.mux.switch_to_session("", &target)?;
+            }
+            context
+                .mux
+                .move_window_to_session(&pane_id, &target)
+                .with_context(|| format!("Failed to move window into session '{}'", target))?;
+            context.mux.rename_window(&pane_id, &full_name)?;
+            context.vcs.set_workspace_meta(&handle, "mode", "window")?;
+
+            if let Ok(store) = StateStore::new() {
+                store.delete_session(context.mux.name(), &context.mux.instance_id(), &full_name)?;
+            }
+            if context.mux.current_pane_id().is_some() {
+                let _ = context.mux.switch_to_pane(&pane_id);
+            }
+        }
+    }
+
+    println!("✓ Converted '{}' to a {}", handle, mode_label(to));
+    Ok(())
+}
+
+/// A pane in the window named `full_name`.
+fn window_pane(panes: &HashMap<String, LivePaneInfo>, full_name: &str) -> Option<String> {
+    panes
+        .iter()
+        .filter(|(_, info)| info.window.as_deref() == Some(full_name))
+        .map(|(pane_id, _)| pane_id.clone())
+        .min()
+}
+
+/// A pane in the session named `full_name`, with the session's window count.
+fn session_pane(panes: &HashMap<String, LivePaneInfo>, full_name: &str) -> Option<(String, usize)> {
+    let in_session: Vec<(&String, &LivePaneInfo)> = panes
+        .iter()
+        .filter(|(_, info)| info.session.as_deref() == Some(full_name))
+        .collect();
+    let windows: BTreeSet<Option<&str>> = in_session
+        .iter()
+        .map(|(_, info)| info.window.as_deref())
+        .collect();
+    let pane_id = in_session.iter().map(|(pane_id, _)| *pane_id).min()?;
+    Some((pane_id.clone(), windows.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn pane(session: &str, window: &str) -> LivePaneInfo {
+        LivePaneInfo {
+            pid: 1,
+            current_command: "zsh".to_string(),
+            working_dir: PathBuf::from("/code/api__worktrees/feature"),
+            title: None,
+            session: Some(session.to_string()),
+            window: Some(window.to_string()),
+            process: None,
+        }
+    }
+
+    #[test]
+    fn window_pane_finds_pane_in_named_window() {
+        let panes = HashMap::from([
+            ("%3".to_string(), pane("main", "wm-feature")),
+            ("%2".to_string(), pane("main", "wm-feature")),
+            ("%1".to_string(), pane("main", "wm-other")),
+        ]);
+        assert_eq!(window_pane(&panes, "wm-feature").as_deref(), Some("%2"));
+        assert_eq!(window_pane(&panes, "wm-missing"), None);
+    }
+
+    #[test]
+    fn session_pane_counts_windows() {
+        let panes = HashMap::from([
+            ("%1".to_string(), pane("wm-feature", "editor")),
+            ("%2".to_string(), pane("wm-feature", "editor")),
+            ("%3".to_string(), pane("wm-feature", "tests")),
+            ("%4".to_string(), pane("main", "wm-feature")),
+        ]);
+        assert_eq!(
+            session_pane(&panes, "wm-feature"),
+            Some(("%1".to_string(), 2))
+        );
+        assert_eq!(session_pane(&panes, "wm-missing"), None);
+    }
+}
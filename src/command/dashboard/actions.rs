@@ -1,6 +1,8 @@
 //! Action enum and dispatcher for dashboard key handling.
 
 use super::app::{App, ViewMode};
+use super::comment::Edit;
+use super::confirm::PendingAction;
 use super::diff_ops::DiffOps;
 
 /// All possible actions in the dashboard.
@@ -21,6 +23,9 @@ pub enum Action {
     // Dashboard commands
     CycleSortMode,
     ToggleStaleFilter,
+    TogglePin,
+    AcknowledgeReminders,
+    ReloadConfig,
     EnterInputMode,
     ExitInputMode,
     ScrollPreviewUp,
@@ -30,9 +35,15 @@ pub enum Action {
     LoadWipDiff,
     SendCommitDashboard,
     TriggerMergeDashboard,
+    OpenFileManager,
+    OpenScratchShell,
+    SaveEvidence,
+    ReviewExecRequest,
+    ShowHistory,
 
     // Input mode
     SendKey(String),
+    SendPaste(String),
 
     // Diff view navigation
     CloseDiff,
@@ -42,6 +53,7 @@ pub enum Action {
     ScrollPageDown,
     ToggleDiffType,
     EnterPatchMode,
+    ToggleBlame,
     SendCommitDiff,
     TriggerMergeDiff,
 
@@ -55,17 +67,23 @@ pub enum Action {
     NextHunk,
     ExitPatchMode,
 
-    // Comment input
-    CancelComment,
+    // Comment editor
+    CloseComment,
+    DiscardComment,
     SendComment,
-    DeleteChar,
-    AppendChar(char),
+    EditComment(Edit),
+    EditCommentExternally,
+
+    // Confirmation modal
+    Confirm,
+    DenyConfirm,
+    CancelConfirm,
 }
 
 /// Apply an action to the app state.
 /// Returns true if preview should be refreshed immediately.
 pub fn apply_action(app: &mut App, action: Action) -> bool {
-    match action {
+    let refresh = match action {
         // Global
         Action::ShowHelp => {
             app.show_help = true;
@@ -111,8 +129,22 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             app.toggle_stale_filter();
             false
         }
+        Action::TogglePin => {
+            app.toggle_pin_selected();
+            false
+        }
+        Action::AcknowledgeReminders => {
+            app.acknowledge_reminders();
+            false
+        }
+        Action::ReloadConfig => {
+            app.reload_config();
+            false
+        }
         Action::EnterInputMode => {
-            if app.table_state.selected().is_some() && !app.agents.is_empty() {
+            if let Some(path) = app.selected_agent_path()
+                && app.allow_input_to(&path)
+            {
                 app.input_mode = true;
             }
             false
@@ -146,7 +178,27 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             false
         }
         Action::TriggerMergeDashboard => {
-            app.trigger_merge_for_selected();
+            app.request_confirmation(PendingAction::MergeSelected);
+            false
+        }
+        Action::OpenFileManager => {
+            app.open_selected_in_file_manager();
+            false
+        }
+        Action::OpenScratchShell => {
+            app.open_scratch_shell();
+            false
+        }
+        Action::SaveEvidence => {
+            app.save_evidence();
+            false
+        }
+        Action::ReviewExecRequest => {
+            app.review_exec_request();
+            false
+        }
+        Action::ShowHistory => {
+            app.show_history();
             false
         }
 
@@ -156,6 +208,11 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             app.refresh_preview();
             true // Signal that preview was refreshed
         }
+        Action::SendPaste(text) => {
+            app.paste_to_selected(&text);
+            app.refresh_preview();
+            true
+        }
 
         // Diff view
         Action::CloseDiff => {
@@ -199,12 +256,16 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             app.enter_patch_mode();
             false
         }
+        Action::ToggleBlame => {
+            app.toggle_blame();
+            false
+        }
         Action::SendCommitDiff => {
             app.send_commit_to_agent();
             false
         }
         Action::TriggerMergeDiff => {
-            app.trigger_merge();
+            app.request_confirmation(PendingAction::MergeDiff);
             false
         }
 
@@ -226,9 +287,7 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             false
         }
         Action::StartComment => {
-            if let ViewMode::Diff(ref mut diff) = app.view_mode {
-                diff.comment_input = Some(String::new());
-            }
+            app.start_comment();
             false
         }
         Action::PrevHunk => {
@@ -244,32 +303,48 @@ pub fn apply_action(app: &mut App, action: Action) -> bool {
             false
         }
 
-        // Comment input
-        Action::CancelComment => {
-            if let ViewMode::Diff(ref mut diff) = app.view_mode {
-                diff.comment_input = None;
-            }
+        // Comment editor
+        Action::CloseComment => {
+            app.close_comment();
+            false
+        }
+        Action::DiscardComment => {
+            app.discard_comment();
             false
         }
         Action::SendComment => {
             app.send_hunk_comment();
             false
         }
-        Action::DeleteChar => {
-            if let ViewMode::Diff(ref mut diff) = app.view_mode
-                && let Some(ref mut input) = diff.comment_input
-            {
-                input.pop();
+        Action::EditComment(edit) => {
+            app.edit_comment(edit);
+            false
+        }
+        Action::EditCommentExternally => {
+            // Needs the terminal, so the event loop runs the editor
+            app.external_comment_edit = true;
+            false
+        }
+
+        // Confirmation modal
+        Action::Confirm => {
+            if let Some(confirm) = app.confirm.take() {
+                app.run_pending(confirm.action);
             }
             false
         }
-        Action::AppendChar(c) => {
-            if let ViewMode::Diff(ref mut diff) = app.view_mode
-                && let Some(ref mut input) = diff.comment_input
-            {
-                input.push(c);
+        Action::DenyConfirm => {
+            if let Some(confirm) = app.confirm.take() {
+                app.deny_pending(confirm.action);
             }
             false
         }
-    }
+        Action::CancelConfirm => {
+            app.confirm = None;
+            false
+        }
+    };
+    // Keep an open blame panel on the file or hunk under the cursor
+    app.refresh_blame();
+    refresh
 }
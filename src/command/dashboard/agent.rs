@@ -1,6 +1,13 @@
 //! Pure helper functions for agent data extraction and formatting.
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::multiplexer::{AgentPane, AgentStatus};
+use crate::workflow::match_agents_to_worktree;
+
+/// A worktree group's name and the worktree paths of its members.
+pub type GroupPaths = (String, Vec<PathBuf>);
 
 /// Extract the worktree name from a window or session name.
 /// Checks window_name first (window mode), then session_name (session mode).
@@ -10,12 +17,15 @@ pub fn extract_worktree_name(
     window_name: &str,
     window_prefix: &str,
 ) -> (String, bool) {
+    // Qualified names (`wm-api/fix-bug`) put the project between the prefix
+    // and the handle, which never contains a slash
+    let handle = |stripped: &str| stripped.rsplit('/').next().unwrap_or(stripped).to_string();
     if let Some(stripped) = window_name.strip_prefix(window_prefix) {
         // Window mode: worktree name is in the window name
-        (stripped.to_string(), false)
+        (handle(stripped), false)
     } else if let Some(stripped) = session_name.strip_prefix(window_prefix) {
         // Session mode: worktree name is in the session name
-        (stripped.to_string(), false)
+        (handle(stripped), false)
     } else {
         // Non-workmux agent - running in main worktree
         ("main".to_string(), true)
@@ -53,6 +63,28 @@ pub fn extract_project_name(path: &Path) -> String {
         .unwrap_or_else(|| path.to_string_lossy().to_string())
 }
 
+/// Lowercase name of a status, as shown to viewers and in JSON output.
+pub fn status_name(status: AgentStatus) -> &'static str {
+    status.as_str()
+}
+
+/// What the agent is doing: its reported task progress, then its current
+/// tool or last message, then the pane title.
+pub fn activity(agent: &AgentPane) -> Option<String> {
+    let reported = agent.task_progress.as_ref().map(|p| match &p.message {
+        Some(message) => format!("{}/{} {}", p.step, p.total, message),
+        None => format!("{}/{}", p.step, p.total),
+    });
+    reported
+        .or_else(|| {
+            agent
+                .progress
+                .as_ref()
+                .and_then(|p| p.tool.clone().or_else(|| p.last_message.clone()))
+        })
+        .or_else(|| agent.pane_title.clone())
+}
+
 /// Check if an agent is stale based on its status timestamp.
 pub fn is_stale(status_ts: Option<u64>, stale_threshold_secs: u64, now_secs: u64) -> bool {
     status_ts
@@ -73,6 +105,65 @@ pub fn format_duration(secs: u64) -> String {
     format!("{:02}:{:02}:{:02}", hours, mins, secs)
 }
 
+/// Compute worst-of status rollups for session-mode sessions.
+///
+/// Only sessions named with the workmux prefix (and whose agents are not in
+/// prefixed windows) that contain more than one agent get a rollup; a single
+/// agent's own status already tells the whole story.
+pub fn compute_session_rollups(
+    agents: &[AgentPane],
+    window_prefix: &str,
+) -> HashMap<String, AgentStatus> {
+    let mut by_session: HashMap<&str, Vec<Option<AgentStatus>>> = HashMap::new();
+    for agent in agents {
+        if agent.session.starts_with(window_prefix) && !agent.window_name.starts_with(window_prefix)
+        {
+            by_session
+                .entry(agent.session.as_str())
+                .or_default()
+                .push(agent.status);
+        }
+    }
+
+    by_session
+        .into_iter()
+        .filter(|(_, statuses)| statuses.len() > 1)
+        .filter_map(|(session, statuses)| {
+            AgentStatus::rollup(statuses.into_iter().flatten())
+                .map(|status| (session.to_string(), status))
+        })
+        .collect()
+}
+
+/// Compute worst-of status rollups for worktree groups, keyed by group name.
+pub fn compute_group_rollups(
+    agents: &[AgentPane],
+    groups: &[GroupPaths],
+) -> HashMap<String, AgentStatus> {
+    groups
+        .iter()
+        .filter_map(|(name, paths)| {
+            let statuses = paths
+                .iter()
+                .flat_map(|path| match_agents_to_worktree(agents, path))
+                .filter_map(|agent| agent.status);
+            AgentStatus::rollup(statuses).map(|status| (name.clone(), status))
+        })
+        .collect()
+}
+
+/// Name of the first group containing the agent's worktree.
+pub fn group_of<'a>(agent: &AgentPane, groups: &'a [GroupPaths]) -> Option<&'a str> {
+    groups
+        .iter()
+        .find(|(_, paths)| {
+            paths
+                .iter()
+                .any(|path| !match_agents_to_worktree(std::slice::from_ref(agent), path).is_empty())
+        })
+        .map(|(name, _)| name.as_str())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +191,16 @@ mod tests {
         assert!(!is_main);
     }
 
+    #[test]
+    fn test_extract_worktree_name_qualified() {
+        let (name, is_main) = extract_worktree_name("main-session", "wm-api/fix-bug", "wm-");
+        assert_eq!(name, "fix-bug");
+        assert!(!is_main);
+        let (name, is_main) = extract_worktree_name("wm-web/feature-auth", "zsh", "wm-");
+        assert_eq!(name, "feature-auth");
+        assert!(!is_main);
+    }
+
     #[test]
     fn test_extract_worktree_name_main() {
         let (name, is_main) = extract_worktree_name("other-session", "some-window", "workmux:");
@@ -173,4 +274,60 @@ mod tests {
         assert_eq!(format_duration(61), "00:01:01");
         assert_eq!(format_duration(3661), "01:01:01");
     }
+
+    fn agent_in(session: &str, window: &str, status: AgentStatus) -> AgentPane {
+        AgentPane {
+            session: session.to_string(),
+            window_name: window.to_string(),
+            pane_id: "%1".to_string(),
+            path: PathBuf::from("/tmp"),
+            pane_title: None,
+            status: Some(status),
+            status_ts: None,
+            progress: None,
+            task_progress: None,
+            denied_exec: None,
+            net_activity: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_session_rollups_worst_of() {
+        let agents = vec![
+            agent_in("wm-feature", "claude", AgentStatus::Working),
+            agent_in("wm-feature", "codex", AgentStatus::Waiting),
+            agent_in("wm-solo", "claude", AgentStatus::Done),
+            agent_in("main", "wm-other", AgentStatus::Waiting),
+            agent_in("main", "wm-other", AgentStatus::Working),
+        ];
+        let rollups = compute_session_rollups(&agents, "wm-");
+        assert_eq!(rollups.len(), 1);
+        assert_eq!(rollups.get("wm-feature"), Some(&AgentStatus::Waiting));
+    }
+
+    #[test]
+    fn test_compute_group_rollups_across_worktrees() {
+        let at = |path: &str, status| AgentPane {
+            path: PathBuf::from(path),
+            ..agent_in("main", "wm-x", status)
+        };
+        let agents = vec![
+            at("/repo__worktrees/frontend", AgentStatus::Done),
+            at("/repo__worktrees/backend/src", AgentStatus::Waiting),
+            at("/repo__worktrees/docs", AgentStatus::Working),
+        ];
+        let groups = vec![(
+            "stack".to_string(),
+            vec![
+                PathBuf::from("/repo__worktrees/frontend"),
+                PathBuf::from("/repo__worktrees/backend"),
+            ],
+        )];
+
+        let rollups = compute_group_rollups(&agents, &groups);
+        assert_eq!(rollups.get("stack"), Some(&AgentStatus::Waiting));
+        assert_eq!(group_of(&agents[1], &groups), Some("stack"));
+        assert_eq!(group_of(&agents[2], &groups), None);
+    }
 }
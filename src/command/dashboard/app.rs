@@ -3,34 +3,49 @@
 use anyhow::Result;
 use ratatui::style::Color;
 use ratatui::widgets::TableState;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, mpsc};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::config::Config;
+use crate::ansi;
+use crate::config::{Config, SplitDirection};
+use crate::filesystem;
 use crate::vcs::{self, VcsStatus};
 use crate::github::PrSummary;
-use crate::multiplexer::{AgentPane, AgentStatus, Multiplexer};
-use crate::state::StateStore;
+use crate::multiplexer::{AgentPane, AgentStatus, HandshakeSettings, Multiplexer, handshake};
+use crate::state::{ExecRequest, PaneKey, StateStore, StatusChange};
+use crate::workflow::{evidence, orphans};
 
 use super::ui::theme::ThemePalette;
 
 const PR_FETCH_INTERVAL: Duration = Duration::from_secs(30);
+const PROGRESS_FETCH_INTERVAL: Duration = Duration::from_secs(2);
+/// Share of the agent's pane a scratch shell takes
+const SCRATCH_SHELL_PERCENTAGE: u8 = 30;
+/// Git status of worktrees on network filesystems is slow to compute, so it's
+/// fetched less often than for local ones
+const NETWORK_GIT_FETCH_INTERVAL: Duration = Duration::from_secs(30);
+/// With `watch_files`, watched worktrees are still checked this often, for
+/// changes that don't touch their files (fetches, branch updates)
+const WATCHED_GIT_FETCH_INTERVAL: Duration = Duration::from_secs(60);
 
 use super::agent;
+use super::ansi::strip_ansi_escapes;
+use super::confirm::{Confirmation, PendingAction};
 use super::diff::DiffView;
+use super::diff_ops::DiffOps;
+use super::fs_watch::WorktreeWatcher;
+use super::preview::PreviewBuffer;
+use super::reminder::{self, ReminderSettings, Reminders};
 use super::settings::{
-    load_hide_stale, load_last_pane_id, load_preview_size, save_hide_stale, save_last_pane_id,
-    save_preview_size,
+    load_hide_stale, load_last_pane_id, load_pinned_panes, load_preview_size, save_hide_stale,
+    save_last_pane_id, save_pinned_panes, save_preview_size,
 };
 use super::sort::SortMode;
 use super::spinner::SPINNER_FRAMES;
 
-/// Number of lines to capture from the agent's terminal for preview (scrollable history)
-pub const PREVIEW_LINES: u16 = 200;
-
 /// Current view mode of the dashboard
 #[derive(Debug, Default, PartialEq)]
 pub enum ViewMode {
@@ -39,6 +54,13 @@ pub enum ViewMode {
     Diff(Box<DiffView>),
 }
 
+/// Status changes of the agent a history popup was opened for.
+pub struct HistoryPopup {
+    /// Worktree name of the agent
+    pub name: String,
+    pub history: Vec<StatusChange>,
+}
+
 /// App state for the TUI
 pub struct App {
     /// The multiplexer backend
@@ -60,6 +82,8 @@ pub struct App {
     pub preview: Option<String>,
     /// Track which pane_id the preview was captured from (to detect selection changes)
     preview_pane_id: Option<String>,
+    /// Scrollback captured so far for the preview
+    preview_buffer: PreviewBuffer,
     /// Input mode: keystrokes are sent directly to the selected agent's pane
     pub input_mode: bool,
     /// Manual scroll offset for the preview (None = auto-scroll to bottom)
@@ -76,8 +100,16 @@ pub struct App {
     git_tx: mpsc::Sender<(PathBuf, VcsStatus)>,
     /// Last time git status was fetched (to throttle background fetches)
     last_git_fetch: std::time::Instant,
+    /// Last time git status was fetched for worktrees on network filesystems
+    last_network_git_fetch: std::time::Instant,
     /// Flag to track if a git fetch is in progress (prevents thread pile-up)
     pub is_git_fetching: Arc<AtomicBool>,
+    /// Watches worktrees for file changes (`watch_files`)
+    fs_watcher: Option<WorktreeWatcher>,
+    /// Worktrees whose files changed, waiting for a git status fetch
+    dirty_worktrees: HashSet<PathBuf>,
+    /// Last time git status was fetched for watched worktrees too
+    last_watched_git_fetch: std::time::Instant,
     /// PR info indexed by repo root, then branch name
     pr_statuses: HashMap<PathBuf, HashMap<String, PrSummary>>,
     /// Channel for PR status updates (repo_root, prs)
@@ -87,25 +119,62 @@ pub struct App {
     last_pr_fetch: std::time::Instant,
     /// Flag to prevent concurrent PR fetches
     is_pr_fetching: Arc<AtomicBool>,
+    /// Last time agent progress was pulled from OpenCode servers
+    last_progress_fetch: std::time::Instant,
+    /// Flag to prevent concurrent progress fetches
+    is_progress_fetching: Arc<AtomicBool>,
     /// Cache of repo roots for agent paths
     repo_roots: HashMap<PathBuf, PathBuf>,
     /// Frame counter for spinner animation (increments each tick)
     pub spinner_frame: u8,
     /// Whether to hide stale agents from the list
     pub hide_stale: bool,
+    /// Agents kept at the top of the list (and visible) whatever the sort
+    /// mode or stale filter, by pane_id
+    pub pinned: BTreeSet<String>,
+    /// Key the pins are stored under: `<backend>:<instance>`
+    pin_scope: String,
     /// Whether to show the help overlay
     pub show_help: bool,
+    /// Status history of an agent, shown over the dashboard
+    pub history: Option<HistoryPopup>,
+    /// Destructive action waiting for the user to confirm it
+    pub confirm: Option<Confirmation>,
     /// Preview pane size as percentage (1-90). Higher = larger preview.
     pub preview_size: u8,
     /// Last jumped-to pane_id for quick toggle (cached from settings)
     last_pane_id: Option<String>,
     /// Color palette based on the configured theme
     pub palette: ThemePalette,
+    /// Agents whose worktree was deleted outside workmux, by pane_id
+    pub orphaned: HashSet<String>,
+    /// Agents whose worktree is locked with `workmux lock`, by pane_id
+    pub locked: HashSet<String>,
+    /// Host commands sandboxed agents asked to run, by pane_id
+    pub exec_requests: HashMap<String, ExecRequest>,
+    /// Worst-of status per session-mode session with more than one agent
+    pub session_rollups: HashMap<String, AgentStatus>,
+    /// Worktree groups of the current repository, with member paths
+    groups: Vec<agent::GroupPaths>,
+    /// Worst-of status per worktree group
+    group_rollups: HashMap<String, AgentStatus>,
+    /// Bypass the protect_main guard (from --allow-main)
+    pub allow_main: bool,
+    /// One-off message shown in the footer until the next key press
+    pub notice: Option<String>,
+    /// The open comment should be edited in $EDITOR (run by the event loop,
+    /// which owns the terminal)
+    pub external_comment_edit: bool,
+    /// Reminders about agents left waiting
+    pub reminders: Reminders,
+    /// Config files being watched, with their last seen modification times
+    config_files: Vec<(PathBuf, Option<SystemTime>)>,
 }
 
 impl App {
     pub fn new(mux: Arc<dyn Multiplexer>) -> Result<Self> {
-        let config = Config::load(None)?;
+        let (config, location) = Config::load_with_location(None)?;
+        let config_files = watched_config_files(location.map(|l| l.config_path));
         let (git_tx, git_rx) = mpsc::channel();
         let (pr_tx, pr_rx) = mpsc::channel();
 
@@ -130,6 +199,15 @@ impl App {
         let pr_statuses = crate::github::load_pr_cache();
         let hide_stale = load_hide_stale();
         let last_pane_id = load_last_pane_id();
+        let pin_scope = format!("{}:{}", mux.name(), mux.instance_id());
+        let pinned = load_pinned_panes(&pin_scope);
+        let fs_watcher = if config.dashboard.watch_files() {
+            WorktreeWatcher::new()
+                .inspect_err(|e| tracing::warn!(error = %e, "dashboard:file watcher unavailable"))
+                .ok()
+        } else {
+            None
+        };
 
         let mut app = Self {
             mux,
@@ -145,6 +223,7 @@ impl App {
             view_mode: ViewMode::default(),
             preview: None,
             preview_pane_id: None,
+            preview_buffer: PreviewBuffer::default(),
             input_mode: false,
             preview_scroll: None,
             preview_line_count: 0,
@@ -154,20 +233,41 @@ impl App {
             git_tx,
             // Set to past to trigger immediate fetch on first refresh
             last_git_fetch: std::time::Instant::now() - Duration::from_secs(60),
+            last_network_git_fetch: std::time::Instant::now() - NETWORK_GIT_FETCH_INTERVAL,
             is_git_fetching: Arc::new(AtomicBool::new(false)),
+            fs_watcher,
+            dirty_worktrees: HashSet::new(),
+            last_watched_git_fetch: std::time::Instant::now() - WATCHED_GIT_FETCH_INTERVAL,
             pr_statuses,
             pr_rx,
             pr_tx,
             // Set to past to trigger immediate fetch on first refresh
             last_pr_fetch: std::time::Instant::now() - PR_FETCH_INTERVAL,
             is_pr_fetching: Arc::new(AtomicBool::new(false)),
+            last_progress_fetch: std::time::Instant::now() - PROGRESS_FETCH_INTERVAL,
+            is_progress_fetching: Arc::new(AtomicBool::new(false)),
             repo_roots: HashMap::new(),
             spinner_frame: 0,
             hide_stale,
+            pinned,
+            pin_scope,
             show_help: false,
+            history: None,
+            confirm: None,
             preview_size,
             last_pane_id,
             palette,
+            orphaned: HashSet::new(),
+            locked: HashSet::new(),
+            exec_requests: HashMap::new(),
+            session_rollups: HashMap::new(),
+            groups: Vec::new(),
+            group_rollups: HashMap::new(),
+            allow_main: false,
+            notice: None,
+            external_comment_edit: false,
+            reminders: Reminders::default(),
+            config_files,
         };
 
         app.refresh();
@@ -185,12 +285,75 @@ impl App {
     }
 
     pub fn refresh(&mut self) {
+        // Pick up edits to the global or project config
+        if self
+            .config_files
+            .iter()
+            .any(|(path, mtime)| modified_time(path) != *mtime)
+        {
+            self.reload_config();
+        }
+
         // Load agents from StateStore with reconciliation against live pane state
         self.agents = StateStore::new()
             .and_then(|store| store.load_reconciled_agents(self.mux.as_ref()))
             .unwrap_or_default();
 
+        // Host command requests of this multiplexer's panes still waiting
+        // for a decision
+        let (backend, instance) = (self.mux.name(), self.mux.instance_id());
+        self.exec_requests = StateStore::new()
+            .and_then(|store| store.list_exec_requests())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| {
+                r.pane_key.backend == backend
+                    && r.pane_key.instance == instance
+                    && r.approved.is_none()
+            })
+            .map(|r| (r.pane_key.pane_id.clone(), r))
+            .collect();
+        // Drop a review whose request was answered or withdrawn meanwhile
+        if let Some(Confirmation {
+            action: PendingAction::ApproveExec(id),
+            ..
+        }) = &self.confirm
+            && !self.exec_requests.values().any(|r| r.id == *id)
+        {
+            self.confirm = None;
+        }
+
+        // Forget pins of panes that are gone
+        if !self.agents.is_empty() {
+            let before = self.pinned.len();
+            let agents = &self.agents;
+            self.pinned
+                .retain(|pane_id| agents.iter().any(|a| a.pane_id == *pane_id));
+            if self.pinned.len() != before {
+                save_pinned_panes(&self.pin_scope, &self.pinned);
+            }
+        }
+
+        self.orphaned = self
+            .agents
+            .iter()
+            .filter(|a| orphans::is_missing_dir(&a.path))
+            .map(|a| a.pane_id.clone())
+            .collect();
+        self.locked = self
+            .agents
+            .iter()
+            .filter(|a| crate::git::worktree_lock_reason(&a.path).is_some())
+            .map(|a| a.pane_id.clone())
+            .collect();
+
         self.sort_agents();
+        // Before the stale filter, which would hide agents waiting for long
+        self.check_reminders();
+        self.session_rollups =
+            agent::compute_session_rollups(&self.agents, self.config.window_prefix());
+        self.groups = load_groups(&self.config);
+        self.group_rollups = agent::compute_group_rollups(&self.agents, &self.groups);
 
         // Cache repo roots for new agents (parallel execution)
         let paths_to_resolve: Vec<PathBuf> = self
@@ -230,10 +393,11 @@ impl App {
                 .map(|d| d.as_secs())
                 .unwrap_or(0);
             self.agents.retain(|agent| {
-                agent
-                    .status_ts
-                    .map(|ts| now.saturating_sub(ts) <= threshold)
-                    .unwrap_or(true) // Keep agents without timestamp
+                self.pinned.contains(&agent.pane_id)
+                    || agent
+                        .status_ts
+                        .map(|ts| now.saturating_sub(ts) <= threshold)
+                        .unwrap_or(true) // Keep agents without timestamp
             });
         }
 
@@ -242,10 +406,39 @@ impl App {
             self.git_statuses.insert(path, status);
         }
 
-        // Trigger background git status fetch every 5 seconds
+        if let Some(watcher) = &mut self.fs_watcher {
+            watcher.sync(self.agents.iter().map(|a| &a.path));
+        }
+
+        // Trigger background git status fetch every 5 seconds, including
+        // worktrees on network filesystems every 30 seconds. Worktrees the
+        // file watcher covers are fetched when they change, and once a minute
         if self.last_git_fetch.elapsed() >= Duration::from_secs(5) {
             self.last_git_fetch = std::time::Instant::now();
-            self.spawn_git_status_fetch();
+            let include_network =
+                self.last_network_git_fetch.elapsed() >= NETWORK_GIT_FETCH_INTERVAL;
+            let include_watched =
+                self.last_watched_git_fetch.elapsed() >= WATCHED_GIT_FETCH_INTERVAL;
+            let paths: Vec<PathBuf> = self
+                .agents
+                .iter()
+                .map(|a| a.path.clone())
+                .filter(|path| {
+                    include_watched
+                        || !self
+                            .fs_watcher
+                            .as_ref()
+                            .is_some_and(|w| w.is_watching(path))
+                })
+                .collect();
+            if self.spawn_git_status_fetch(paths, include_network) {
+                if include_network {
+                    self.last_network_git_fetch = self.last_git_fetch;
+                }
+                if include_watched {
+                    self.last_watched_git_fetch = self.last_git_fetch;
+                }
+            }
         }
 
         // Consume any pending PR status updates
@@ -259,6 +452,14 @@ impl App {
             self.spawn_pr_status_fetch();
         }
 
+        // Pull agent progress into the StateStore; picked up on the next refresh
+        if self.config.dashboard.opencode_server()
+            && self.last_progress_fetch.elapsed() >= PROGRESS_FETCH_INTERVAL
+        {
+            self.last_progress_fetch = std::time::Instant::now();
+            self.spawn_progress_fetch();
+        }
+
         // Restore selection by pane_id to follow the item across reorders
         if let Some(ref pane_id) = self.selected_pane_id {
             // Find the new index of the previously selected item
@@ -298,20 +499,65 @@ impl App {
         self.update_preview();
     }
 
-    /// Spawn a background thread to fetch git status for all agent worktrees
-    fn spawn_git_status_fetch(&self) {
+    /// Reload config and re-resolve everything derived from it (icons are
+    /// looked up from config at render time, the palette is cached here).
+    ///
+    /// Selection, scroll and preview size are left untouched. On a parse
+    /// error the previous config stays active and the error is shown.
+    pub fn reload_config(&mut self) {
+        match Config::load_with_location(None) {
+            Ok((config, location)) => {
+                self.palette = ThemePalette::from_theme(config.theme);
+                self.session_rollups =
+                    agent::compute_session_rollups(&self.agents, config.window_prefix());
+                self.config = config;
+                self.notice = Some("Config reloaded".to_string());
+                self.config_files = watched_config_files(location.map(|l| l.config_path));
+            }
+            Err(e) => {
+                self.notice = Some(format!("Config reload failed: {}", e));
+                // Don't retry until the files change again
+                for (path, mtime) in &mut self.config_files {
+                    *mtime = modified_time(path);
+                }
+            }
+        }
+    }
+
+    /// Fetch the git status of worktrees whose files changed, and show any
+    /// fetched statuses. Called every tick, so changes show up right away.
+    pub fn check_file_changes(&mut self) {
+        let Some(watcher) = &self.fs_watcher else {
+            return;
+        };
+        self.dirty_worktrees.extend(watcher.changed());
+        // Changes during a fetch wait for the next one
+        if !self.dirty_worktrees.is_empty() {
+            let paths: Vec<PathBuf> = self.dirty_worktrees.iter().cloned().collect();
+            if self.spawn_git_status_fetch(paths, true) {
+                self.dirty_worktrees.clear();
+            }
+        }
+        while let Ok((path, status)) = self.git_rx.try_recv() {
+            self.git_statuses.insert(path, status);
+        }
+    }
+
+    /// Spawn a background thread to fetch git status for `paths`.
+    /// Worktrees on network filesystems are skipped unless `include_network`.
+    /// Returns whether a fetch was started.
+    fn spawn_git_status_fetch(&self, paths: Vec<PathBuf>, include_network: bool) -> bool {
         // Skip if a fetch is already in progress (prevents thread pile-up)
         if self
             .is_git_fetching
             .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
             .is_err()
         {
-            return;
+            return false;
         }
 
         let tx = self.git_tx.clone();
         let is_fetching = self.is_git_fetching.clone();
-        let agent_paths: Vec<PathBuf> = self.agents.iter().map(|a| a.path.clone()).collect();
 
         std::thread::spawn(move || {
             // Reset flag when thread completes (even on panic)
@@ -323,7 +569,10 @@ impl App {
             }
             let _reset = ResetFlag(is_fetching);
 
-            for path in agent_paths {
+            for path in paths {
+                if !include_network && filesystem::is_network(&path) {
+                    continue;
+                }
                 let status = vcs::try_detect_vcs()
                     .map(|v| v.get_status(&path))
                     .unwrap_or_default();
@@ -331,6 +580,7 @@ impl App {
                 let _ = tx.send((path, status));
             }
         });
+        true
     }
 
     /// Spawn a background thread to fetch PR status for all repos
@@ -374,7 +624,7 @@ impl App {
             let _reset = ResetFlag(is_fetching);
 
             for repo_root in repo_roots {
-                match crate::github::list_prs_in_repo(&repo_root) {
+                match crate::forge::Forge::for_repo(&repo_root).list_prs() {
                     Ok(prs) => {
                         let _ = tx.send((repo_root, prs));
                     }
@@ -386,6 +636,58 @@ impl App {
         });
     }
 
+    /// Spawn a background thread to pull progress from agents' OpenCode servers
+    fn spawn_progress_fetch(&self) {
+        if self
+            .is_progress_fetching
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let is_fetching = self.is_progress_fetching.clone();
+        let pane_ids: std::collections::HashSet<String> =
+            self.agents.iter().map(|a| a.pane_id.clone()).collect();
+        let backend = self.mux.name().to_string();
+        let instance = self.mux.instance_id();
+
+        std::thread::spawn(move || {
+            struct ResetFlag(Arc<AtomicBool>);
+            impl Drop for ResetFlag {
+                fn drop(&mut self) {
+                    self.0.store(false, Ordering::SeqCst);
+                }
+            }
+            let _reset = ResetFlag(is_fetching);
+
+            let Ok(store) = StateStore::new() else {
+                return;
+            };
+            let agents = store.list_all_agents().unwrap_or_default();
+            for state in agents {
+                let key = &state.pane_key;
+                if key.backend != backend
+                    || key.instance != instance
+                    || !pane_ids.contains(&key.pane_id)
+                {
+                    continue;
+                }
+                let Some(url) = state.server_url else {
+                    continue;
+                };
+                match crate::opencode::fetch_progress(&url) {
+                    Ok(progress) => {
+                        let _ = store.update_agent(key, |s| s.progress = Some(progress));
+                    }
+                    Err(e) => {
+                        tracing::debug!("Failed to fetch OpenCode progress from {}: {}", url, e);
+                    }
+                }
+            }
+        });
+    }
+
     /// Update the preview for the currently selected agent.
     /// Only fetches if the selection has changed or preview is stale.
     pub fn update_preview(&mut self) {
@@ -397,21 +699,35 @@ impl App {
 
         // Only fetch if selection changed
         if current_pane_id != self.preview_pane_id {
-            self.preview_pane_id = current_pane_id.clone();
-            self.preview = current_pane_id
-                .as_ref()
-                .and_then(|pane_id| self.mux.capture_pane(pane_id, PREVIEW_LINES));
+            self.preview_pane_id = current_pane_id;
+            self.preview_buffer = PreviewBuffer::default();
             // Reset scroll position when selection changes
             self.preview_scroll = None;
+            self.refresh_preview();
         }
     }
 
-    /// Force refresh the preview (used on periodic refresh)
+    /// Force refresh the preview (used on periodic refresh).
+    /// Only output added since the last refresh is captured.
     pub fn refresh_preview(&mut self) {
-        self.preview = self
-            .preview_pane_id
-            .as_ref()
-            .and_then(|pane_id| self.mux.capture_pane(pane_id, PREVIEW_LINES));
+        let capture = self.preview_pane_id.as_ref().and_then(|pane_id| {
+            self.mux.capture_pane_incremental(
+                pane_id,
+                self.preview_buffer.history_size(),
+                self.preview_buffer.depth(),
+            )
+        });
+        let Some(capture) = capture else {
+            self.preview_buffer = PreviewBuffer::default();
+            self.preview = None;
+            return;
+        };
+        let dropped = self.preview_buffer.apply(capture);
+        // Keep a manual scroll position on the same content as old lines drop off
+        if let Some(scroll) = self.preview_scroll.as_mut() {
+            *scroll = scroll.saturating_sub(u16::try_from(dropped).unwrap_or(u16::MAX));
+        }
+        self.preview = Some(self.preview_buffer.render());
     }
 
     /// Parse pane_id to a number for proper ordering.
@@ -448,6 +764,7 @@ impl App {
             match agent.status {
                 Some(AgentStatus::Waiting) => 0, // Waiting: needs input
                 Some(AgentStatus::Done) => 1,    // Done: needs review
+                Some(AgentStatus::Unknown) => 1, // Stopped reporting: check on it
                 Some(AgentStatus::Working) => 2, // Working: no action needed
                 None => 3,                       // Unknown/other: lowest priority
             }
@@ -486,6 +803,10 @@ impl App {
                 self.agents.sort_by_cached_key(pane_num);
             }
         }
+
+        // Pinned agents go first, in the order of the sort mode (stable sort)
+        let pinned = &self.pinned;
+        self.agents.sort_by_key(|a| !pinned.contains(&a.pane_id));
     }
 
     /// Cycle to the next sort mode, re-sort, and persist to tmux
@@ -495,6 +816,23 @@ impl App {
         self.sort_agents();
     }
 
+    /// Pin or unpin the selected agent, and persist the pins
+    pub fn toggle_pin_selected(&mut self) {
+        let Some(pane_id) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.agents.get(idx))
+            .map(|a| a.pane_id.clone())
+        else {
+            return;
+        };
+        if !self.pinned.remove(&pane_id) {
+            self.pinned.insert(pane_id);
+        }
+        save_pinned_panes(&self.pin_scope, &self.pinned);
+        self.refresh();
+    }
+
     /// Toggle hiding stale agents
     pub fn toggle_stale_filter(&mut self) {
         self.hide_stale = !self.hide_stale;
@@ -564,6 +902,7 @@ impl App {
         }
 
         self.should_jump = true;
+        self.set_terminal_title(target_pane_id);
 
         // Only update last_pane_id if:
         // 1. We actually moved to a different pane
@@ -577,6 +916,27 @@ impl App {
         }
     }
 
+    /// Set the terminal title to the handle of the agent in `pane_id`, so
+    /// the terminal's tab shows the worktree that was jumped to.
+    fn set_terminal_title(&self, pane_id: &str) {
+        use std::io::Write;
+
+        if !self.config.dashboard.set_title() || !ansi::supports_escapes(&std::io::stdout()) {
+            return;
+        }
+        let Some(handle) = self
+            .agents
+            .iter()
+            .find(|a| a.pane_id == pane_id)
+            .and_then(|a| a.path.file_name())
+        else {
+            return;
+        };
+        let mut stdout = std::io::stdout();
+        let _ = write!(stdout, "{}", ansi::title(&handle.to_string_lossy()));
+        let _ = stdout.flush();
+    }
+
     pub fn jump_to_selected(&mut self) {
         if let Some(selected) = self.table_state.selected()
             && let Some(agent) = self.agents.get(selected)
@@ -625,6 +985,124 @@ impl App {
         }
     }
 
+    /// Open the selected agent's worktree in the system file manager.
+    pub fn open_selected_in_file_manager(&mut self) {
+        let Some(path) = self.selected_agent_path() else {
+            return;
+        };
+        let opener = if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        let spawned = std::process::Command::new(opener)
+            .arg(&path)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+        self.notice = Some(match spawned {
+            Ok(_) => format!("Opened {} in the file manager", path.display()),
+            Err(e) => format!("Could not run {}: {}", opener, e),
+        });
+    }
+
+    /// Save the last lines of an agent's pane (e.g. a test run) as merge
+    /// evidence in its worktree: the agent whose diff is open, or else the
+    /// selected one.
+    pub fn save_evidence(&mut self) {
+        let target = match &self.view_mode {
+            ViewMode::Diff(diff) => Some((diff.pane_id.clone(), diff.worktree_path.clone())),
+            ViewMode::Dashboard => self
+                .table_state
+                .selected()
+                .and_then(|idx| self.agents.get(idx))
+                .map(|agent| (agent.pane_id.clone(), agent.path.clone())),
+        };
+        let Some((pane_id, path)) = target else {
+            return;
+        };
+        let worktree = self.repo_roots.get(&path).cloned().unwrap_or(path);
+        let lines = self.config.dashboard.evidence_lines();
+        let Some(output) = self.mux.capture_pane(&pane_id, lines) else {
+            self.notice = Some("Could not read the agent's output".to_string());
+            return;
+        };
+        let source = worktree
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let output = strip_ansi_escapes(&output);
+        let Some(block) = evidence::format_block(&source, &output, lines.into()) else {
+            self.notice = Some("The agent's pane is empty".to_string());
+            return;
+        };
+        self.notice = Some(match evidence::append(&worktree, &block) {
+            Ok(()) => format!(
+                "Saved the last {} lines of output as evidence for the squash commit",
+                lines
+            ),
+            Err(e) => format!("Could not save evidence: {:#}", e),
+        });
+    }
+
+    /// Split a shell pane below the selected agent, in its working
+    /// directory, and jump to it.
+    pub fn open_scratch_shell(&mut self) {
+        let Some(agent) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.agents.get(idx))
+        else {
+            return;
+        };
+        let (pane_id, path) = (agent.pane_id.clone(), agent.path.clone());
+        match self.mux.split_pane(
+            &pane_id,
+            &SplitDirection::Vertical,
+            &path,
+            None,
+            Some(SCRATCH_SHELL_PERCENTAGE),
+            None,
+        ) {
+            Ok(shell_pane) => self.switch_to_pane_and_track(&shell_pane),
+            Err(e) => self.notice = Some(format!("Could not open a shell: {}", e)),
+        }
+    }
+
+    /// Working directory of the currently selected agent
+    pub fn selected_agent_path(&self) -> Option<PathBuf> {
+        self.table_state
+            .selected()
+            .and_then(|idx| self.agents.get(idx))
+            .map(|agent| agent.path.clone())
+    }
+
+    /// Check whether input may be sent to an agent in `path`.
+    ///
+    /// With protect_main enabled, agents in the main worktree are refused
+    /// (unless --allow-main was passed) and a footer notice explains why.
+    pub fn allow_input_to(&mut self, path: &std::path::Path) -> bool {
+        let Some(vcs) = vcs::try_detect_vcs() else {
+            return true;
+        };
+        match crate::workflow::ensure_not_protected_main(
+            &self.config,
+            vcs.as_ref(),
+            path,
+            self.allow_main,
+        ) {
+            Ok(()) => true,
+            Err(_) => {
+                self.notice = Some(
+                    "Main worktree is protected (protect_main); restart with --allow-main"
+                        .to_string(),
+                );
+                false
+            }
+        }
+    }
+
     /// Send a key to the selected agent's pane
     pub fn send_key_to_selected(&self, key: &str) {
         if let Some(selected) = self.table_state.selected()
@@ -634,12 +1112,175 @@ impl App {
         }
     }
 
-    /// Scroll preview up (toward older content). Returns the amount to scroll by.
+    /// Paste text into the selected agent's pane as a bracketed paste, so
+    /// newlines don't submit it line by line
+    pub fn paste_to_selected(&mut self, text: &str) {
+        if let Some(selected) = self.table_state.selected()
+            && let Some(agent) = self.agents.get(selected)
+            && let Err(e) = self.mux.paste(&agent.pane_id, text)
+        {
+            self.notice = Some(format!("Paste failed: {}", e));
+        }
+    }
+
+    /// Send a line of input to an agent pane, surfacing verification failures
+    /// Ask the user to confirm `action` before running it.
+    ///
+    /// Runs it right away when confirmations are disabled, or when input to
+    /// the agent is refused anyway, so the refusal notice shows immediately.
+    pub fn request_confirmation(&mut self, action: PendingAction) {
+        let path = match action {
+            PendingAction::MergeSelected => self.selected_agent_path(),
+            PendingAction::MergeDiff => match &self.view_mode {
+                ViewMode::Diff(diff) => Some(diff.worktree_path.clone()),
+                ViewMode::Dashboard => None,
+            },
+            // Approvals are opened by review_exec_request and never skipped
+            PendingAction::ApproveExec(_) => return,
+        };
+        match path {
+            Some(path) if self.config.dashboard.confirm() && self.allow_input_to(&path) => {
+                self.confirm = Some(self.merge_confirmation(&path, action));
+            }
+            _ => self.run_pending(action),
+        }
+    }
+
+    /// Run an action the user confirmed.
+    pub fn run_pending(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::MergeSelected => self.trigger_merge_for_selected(),
+            PendingAction::MergeDiff => self.trigger_merge(),
+            PendingAction::ApproveExec(id) => self.decide_exec_request(id, true),
+        }
+    }
+
+    /// Answer "no" to an action that can be refused; others are just
+    /// cancelled.
+    pub fn deny_pending(&mut self, action: PendingAction) {
+        if let PendingAction::ApproveExec(id) = action {
+            self.decide_exec_request(id, false);
+        }
+    }
+
+    /// Open the host command request of the selected agent for approval.
+    /// Open the status history of the selected agent.
+    pub fn show_history(&mut self) {
+        let Some(agent) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.agents.get(idx))
+        else {
+            return;
+        };
+        let key = PaneKey {
+            backend: self.mux.name().to_string(),
+            instance: self.mux.instance_id(),
+            pane_id: agent.pane_id.clone(),
+        };
+        let history = StateStore::new()
+            .and_then(|store| store.get_agent(&key))
+            .ok()
+            .flatten()
+            .map(|state| state.history)
+            .unwrap_or_default();
+        if history.is_empty() {
+            self.notice = Some("No status changes recorded for this agent yet".to_string());
+            return;
+        }
+        let (name, _) = self.extract_worktree_name(agent);
+        self.history = Some(HistoryPopup { name, history });
+    }
+
+    pub fn review_exec_request(&mut self) {
+        let Some(agent) = self
+            .table_state
+            .selected()
+            .and_then(|idx| self.agents.get(idx))
+        else {
+            return;
+        };
+        let Some(request) = self.exec_requests.get(&agent.pane_id) else {
+            self.notice = Some("No host command waiting for approval".to_string());
+            return;
+        };
+        let (name, _) = self.extract_worktree_name(agent);
+        self.confirm = Some(Confirmation::exec_request(request, &name));
+    }
+
+    /// Record the user's decision on host command request `id`.
+    fn decide_exec_request(&mut self, id: u64, approved: bool) {
+        let Some(request) = self.exec_requests.values().find(|r| r.id == id) else {
+            return;
+        };
+        let result = StateStore::new()
+            .and_then(|store| store.decide_exec_request(&request.pane_key, id, approved));
+        match result {
+            Ok(true) => {
+                let pane_id = request.pane_key.pane_id.clone();
+                self.exec_requests.remove(&pane_id);
+            }
+            Ok(false) => {
+                self.notice = Some("The request was withdrawn".to_string());
+            }
+            Err(e) => {
+                self.notice = Some(format!("Failed to answer the request: {}", e));
+            }
+        }
+    }
+
+    /// Describe the merge of the worktree at `path`: branch, target and
+    /// uncommitted files.
+    fn merge_confirmation(&self, path: &std::path::Path, action: PendingAction) -> Confirmation {
+        let status = self.git_statuses.get(path);
+        let branch = status
+            .and_then(|s| s.branch.clone())
+            .or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let target = status
+            .map(|s| s.base_branch.clone())
+            .filter(|base| !base.is_empty());
+        Confirmation::merge(
+            action,
+            &branch,
+            target.as_deref(),
+            super::diff::count_changed_files(path),
+            self.config.dashboard.merge(),
+        )
+    }
+
+    pub fn send_agent_input(&mut self, pane_id: &str, text: &str) {
+        let settings = HandshakeSettings::from_config(&self.config.handshake);
+        let submit =
+            handshake::SubmitSettings::for_agent(&self.config, self.config.agent.as_deref());
+        if let Err(e) = handshake::send_agent_input(
+            self.mux.as_ref(),
+            pane_id,
+            text,
+            self.config.agent.as_deref(),
+            &settings,
+            &submit,
+        ) {
+            self.notice = Some(e.to_string());
+        }
+    }
+
+    /// Scroll preview up (toward older content).
+    /// Reaching the top loads more of the pane's history above the current view.
     pub fn scroll_preview_up(&mut self, visible_height: u16, total_lines: u16) {
         let max_scroll = total_lines.saturating_sub(visible_height);
         let current = self.preview_scroll.unwrap_or(max_scroll);
         let half_page = visible_height / 2;
-        self.preview_scroll = Some(current.saturating_sub(half_page));
+        let mut added = 0;
+
+        if current <= half_page && self.preview_buffer.can_deepen() {
+            let before = self.preview_buffer.history_len();
+            self.preview_buffer.deepen();
+            self.refresh_preview();
+            added = self.preview_buffer.history_len().saturating_sub(before);
+        }
+        let new_scroll = (current as usize + added).saturating_sub(half_page as usize);
+        self.preview_scroll = Some(u16::try_from(new_scroll).unwrap_or(u16::MAX));
     }
 
     /// Scroll preview down (toward newer content).
@@ -656,6 +1297,52 @@ impl App {
         }
     }
 
+    /// Remind about agents waiting past `dashboard.remind_after`: a footer
+    /// notice, then a desktop notification and the bell as reminders repeat.
+    fn check_reminders(&mut self) {
+        let Some(settings) = ReminderSettings::from_config(&self.config.dashboard) else {
+            return;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let due = self.reminders.check(&self.agents, &settings, now);
+        let Some(escalation) = due.iter().map(|d| d.count).max() else {
+            return;
+        };
+
+        let names: Vec<String> = due
+            .iter()
+            .filter_map(|d| self.agents.iter().find(|a| a.pane_id == d.pane_id))
+            .map(|agent| self.extract_worktree_name(agent).0)
+            .collect();
+        let message = match (names.as_slice(), due.as_slice()) {
+            ([name], [due]) => format!(
+                "{} has been waiting for {}",
+                name,
+                agent::format_duration(due.waiting_secs)
+            ),
+            _ => format!("{} agents are waiting: {}", names.len(), names.join(", ")),
+        };
+        self.notice = Some(format!("Reminder: {} (a: acknowledge)", message));
+        if escalation >= 2 && settings.notify {
+            crate::util::show_notification(&message);
+        }
+        if escalation >= 3 && settings.bell {
+            reminder::ring_bell();
+        }
+    }
+
+    /// Stop reminding about the agents reminded about so far.
+    pub fn acknowledge_reminders(&mut self) {
+        self.notice = Some(match self.reminders.acknowledge_all() {
+            0 => "No reminders to acknowledge".to_string(),
+            1 => "Reminder acknowledged".to_string(),
+            n => format!("{} reminders acknowledged", n),
+        });
+    }
+
     pub fn format_duration(&self, secs: u64) -> String {
         agent::format_duration(secs)
     }
@@ -686,6 +1373,7 @@ impl App {
                 (self.config.status_icons.waiting(), Color::Magenta, false)
             }
             Some(AgentStatus::Done) => (self.config.status_icons.done(), Color::Green, false),
+            Some(AgentStatus::Unknown) => (self.config.status_icons.unknown(), Color::Red, false),
             None => ("", self.palette.text, false),
         };
 
@@ -693,8 +1381,9 @@ impl App {
         if is_stale {
             let display_text = format!("{} \u{f051b}", icon);
             (display_text, self.palette.dimmed)
-        } else if is_working {
-            // Add animated spinner when agent is working
+        } else if is_working && !agent.process.as_ref().is_some_and(|p| p.is_idle()) {
+            // Add animated spinner when agent is working, unless its process
+            // shows it idle at its prompt
             let spinner = SPINNER_FRAMES[self.spinner_frame as usize];
             let display_text = format!("{} {}", icon, spinner);
             (display_text, base_color)
@@ -703,6 +1392,39 @@ impl App {
         }
     }
 
+    /// Rollup icon for the agent's session, if it is a session-mode session
+    /// shared by several agents.
+    pub fn session_rollup_icon(&self, agent: &AgentPane) -> Option<&str> {
+        self.session_rollups
+            .get(&agent.session)
+            .map(|status| match status {
+                AgentStatus::Working => self.config.status_icons.working(),
+                AgentStatus::Waiting => self.config.status_icons.waiting(),
+                AgentStatus::Done => self.config.status_icons.done(),
+                AgentStatus::Unknown => self.config.status_icons.unknown(),
+            })
+    }
+
+    /// Group label for the agent's worktree with the group's rollup icon,
+    /// e.g. "stack 💬".
+    pub fn group_label(&self, agent: &AgentPane) -> Option<String> {
+        let name = agent::group_of(agent, &self.groups)?;
+        let label = match self.group_rollups.get(name) {
+            Some(AgentStatus::Working) => {
+                format!("{} {}", name, self.config.status_icons.working())
+            }
+            Some(AgentStatus::Waiting) => {
+                format!("{} {}", name, self.config.status_icons.waiting())
+            }
+            Some(AgentStatus::Done) => format!("{} {}", name, self.config.status_icons.done()),
+            Some(AgentStatus::Unknown) => {
+                format!("{} {}", name, self.config.status_icons.unknown())
+            }
+            None => name.to_string(),
+        };
+        Some(label)
+    }
+
     /// Extract the worktree name from an agent.
     /// Returns (worktree_name, is_main) where is_main indicates if this is the main worktree.
     pub fn extract_worktree_name(&self, agent_pane: &AgentPane) -> (String, bool) {
@@ -746,3 +1468,38 @@ impl App {
         &self.pr_statuses
     }
 }
+
+/// Global and project config files to watch for live reload.
+fn watched_config_files(project: Option<PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+    crate::config::global_config_path()
+        .into_iter()
+        .chain(project)
+        .map(|path| {
+            let mtime = modified_time(&path);
+            (path, mtime)
+        })
+        .collect()
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Worktree groups of the repository the dashboard was opened in, with the
+/// paths of members that have a worktree.
+fn load_groups(config: &Config) -> Vec<agent::GroupPaths> {
+    let Some(vcs) = vcs::try_detect_vcs() else {
+        return Vec::new();
+    };
+    crate::workflow::group::list(config, vcs.as_ref())
+        .into_iter()
+        .map(|group| {
+            let paths = crate::workflow::group::resolve_members(vcs.as_ref(), &group)
+                .into_iter()
+                .filter_map(|member| member.path)
+                .collect();
+            (group.name, paths)
+        })
+        .filter(|(_, paths): &agent::GroupPaths| !paths.is_empty())
+        .collect()
+}
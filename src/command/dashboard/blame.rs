@@ -0,0 +1,301 @@
+//! Blame and history side panel for the diff view.
+//!
+//! Shows who last touched the lines a diff changes and which commits changed
+//! them recently, plus commits to the file that landed on the base branch but
+//! aren't in the worktree's branch yet. Lines are blamed on the old side of
+//! the diff, so they show the human work the change is about to replace.
+
+use std::path::Path;
+use std::process::Command;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Commits listed per history section.
+const HISTORY_LIMIT: usize = 10;
+
+/// Line ranges blamed at most, so huge diffs stay fast.
+const MAX_RANGES: usize = 20;
+
+/// What the panel describes: a file, and the hunk in patch mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameTarget {
+    pub filename: String,
+    /// Old-side `(start, count)` of the hunk, or None for the whole file's
+    /// changes
+    pub hunk: Option<(usize, usize)>,
+}
+
+/// Loaded contents of the side panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlamePanel {
+    pub target: BlameTarget,
+    pub lines: Vec<Line<'static>>,
+}
+
+/// Where a diff's old side comes from.
+pub struct BlameSource<'a> {
+    /// Diff argument whose old side is blamed (`HEAD` or `base...HEAD`)
+    pub diff_arg: &'a str,
+    /// Base branch of the worktree, for commits not in the branch yet
+    pub base: &'a str,
+}
+
+/// Parse the old-side range of a hunk header: "@@ -10,5 +12,7 @@" ->
+/// Some((10, 5)). A missing count means one line.
+pub fn parse_old_range(header: &str) -> Option<(usize, usize)> {
+    let meta = header.split("@@").nth(1)?;
+    let old = meta.split_whitespace().find_map(|p| p.strip_prefix('-'))?;
+    let (start, count) = match old.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (old.parse().ok()?, 1),
+    };
+    Some((start, count))
+}
+
+/// Lines to blame for an old-side hunk range. Pure insertions (count 0)
+/// blame the line they follow; insertions at the top of a file have nothing
+/// to blame.
+fn blame_range((start, count): (usize, usize)) -> Option<(usize, usize)> {
+    match (start, count) {
+        (0, _) => None,
+        (start, 0) => Some((start, 1)),
+        range => Some(range),
+    }
+}
+
+fn git(worktree: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .arg("--no-pager")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Error running git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Old-side ranges of every hunk the diff has in `filename`.
+fn file_hunk_ranges(worktree: &Path, diff_arg: &str, filename: &str) -> Vec<(usize, usize)> {
+    git(worktree, &["diff", "-U0", diff_arg, "--", filename])
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| line.starts_with("@@"))
+        .filter_map(parse_old_range)
+        .collect()
+}
+
+/// Revision holding the diff's old side.
+fn old_revision(worktree: &Path, diff_arg: &str) -> Option<String> {
+    match diff_arg.split_once("...") {
+        Some((base, head)) => git(worktree, &["merge-base", base, head])
+            .ok()
+            .map(|rev| rev.trim().to_string()),
+        None => Some(diff_arg.to_string()),
+    }
+}
+
+/// One line of `git blame --date=short` output.
+#[derive(Debug, PartialEq)]
+struct BlameLine {
+    commit: String,
+    author: String,
+    date: String,
+    line_no: usize,
+    content: String,
+}
+
+/// Parse `git blame --date=short` output:
+/// `<commit> [<file>] (<author> <date> <line>) <content>`.
+fn parse_blame(output: &str) -> Vec<BlameLine> {
+    let re = regex::Regex::new(r"^\^?([0-9a-f]+) .*?\((.*?) +(\d{4}-\d{2}-\d{2}) +(\d+)\) ?(.*)$")
+        .expect("valid regex");
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = re.captures(line)?;
+            Some(BlameLine {
+                commit: caps[1].chars().take(8).collect(),
+                author: caps[2].trim().to_string(),
+                date: caps[3].to_string(),
+                line_no: caps[4].parse().ok()?,
+                content: caps[5].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn heading(text: String) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn dimmed(text: String) -> Line<'static> {
+    Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))
+}
+
+/// Commits from `git log --format=%h%x09%ad%x09%an%x09%s`.
+fn commit_lines(log: &str) -> Vec<Line<'static>> {
+    log.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let (hash, date, author, subject) = (
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+            );
+            Some(Line::from(vec![
+                Span::styled(format!("{} ", hash), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{} ", date), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{} ", author), Style::default().fg(Color::Green)),
+                Span::raw(subject.to_string()),
+            ]))
+        })
+        .collect()
+}
+
+/// Recent commits selected by `args`, newest first.
+fn log(worktree: &Path, args: &[&str]) -> Result<Vec<Line<'static>>, String> {
+    let limit = HISTORY_LIMIT.to_string();
+    let mut log_args = vec![
+        "log",
+        "-n",
+        &limit,
+        "--no-patch",
+        "--date=short",
+        "--format=%h%x09%ad%x09%an%x09%s",
+    ];
+    log_args.extend(args);
+    git(worktree, &log_args).map(|log| commit_lines(&log))
+}
+
+/// Run git for the panel and build its contents.
+pub fn load(worktree: &Path, target: BlameTarget, source: &BlameSource) -> BlamePanel {
+    let mut lines = Vec::new();
+
+    let ranges: Vec<(usize, usize)> = match target.hunk {
+        Some(hunk) => vec![hunk],
+        None => file_hunk_ranges(worktree, source.diff_arg, &target.filename),
+    }
+    .into_iter()
+    .filter_map(blame_range)
+    .take(MAX_RANGES)
+    .collect();
+    let rev = old_revision(worktree, source.diff_arg);
+
+    // Commits on the base that the branch doesn't have yet
+    let not_merged = format!("HEAD..{}", source.base);
+    let upstream = log(worktree, &[&not_merged, "--", &target.filename]).unwrap_or_default();
+    if !upstream.is_empty() {
+        lines.push(heading(format!("On {}, not in this branch", source.base)));
+        lines.extend(upstream);
+        lines.push(Line::default());
+    }
+
+    let (Some(rev), false) = (rev, ranges.is_empty()) else {
+        lines.push(dimmed("New lines: no earlier history".to_string()));
+        return BlamePanel { target, lines };
+    };
+    let line_ranges: Vec<String> = ranges
+        .iter()
+        .map(|(start, count)| format!("{},+{}", start, count))
+        .collect();
+
+    let history_ranges: Vec<String> = line_ranges
+        .iter()
+        .map(|range| format!("-L{}:{}", range, target.filename))
+        .collect();
+    let mut history_args: Vec<&str> = history_ranges.iter().map(String::as_str).collect();
+    history_args.push(&rev);
+    lines.push(heading("Recent commits to these lines".to_string()));
+    match log(worktree, &history_args) {
+        Ok(commits) => lines.extend(commits),
+        Err(e) => lines.push(dimmed(e)),
+    }
+    lines.push(Line::default());
+
+    let mut blame_args = vec!["blame", "--date=short"];
+    for range in &line_ranges {
+        blame_args.extend(["-L", range]);
+    }
+    blame_args.extend([rev.as_str(), "--", &target.filename]);
+    lines.push(heading("Blame".to_string()));
+    match git(worktree, &blame_args) {
+        Ok(output) => {
+            let mut previous_line = None;
+            for blame in parse_blame(&output) {
+                // Separate ranges that aren't adjacent
+                if previous_line.is_some_and(|n| n + 1 != blame.line_no) {
+                    lines.push(dimmed("  ...".to_string()));
+                }
+                previous_line = Some(blame.line_no);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", blame.commit),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{} {} ", blame.date, blame.author),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        format!("{:>4} ", blame.line_no),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(blame.content),
+                ]));
+            }
+        }
+        Err(e) => lines.push(dimmed(e)),
+    }
+
+    BlamePanel { target, lines }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_range_is_parsed_from_hunk_headers() {
+        assert_eq!(
+            parse_old_range("@@ -10,5 +12,7 @@ fn main()"),
+            Some((10, 5))
+        );
+        assert_eq!(parse_old_range("@@ -3 +3 @@"), Some((3, 1)));
+        assert_eq!(parse_old_range("@@ -0,0 +1,4 @@"), Some((0, 0)));
+        assert_eq!(parse_old_range("not a header"), None);
+    }
+
+    #[test]
+    fn insertions_blame_the_line_they_follow() {
+        assert_eq!(blame_range((10, 5)), Some((10, 5)));
+        assert_eq!(blame_range((7, 0)), Some((7, 1)));
+        assert_eq!(blame_range((0, 0)), None);
+    }
+
+    #[test]
+    fn blame_output_is_parsed() {
+        let output = "\
+^6aae344 (Jane Doe   2026-01-02  1) mod cli;
+86a09485 src/old.rs (A (B) C 2026-03-04 12)     let x = 1;
+";
+        let lines = parse_blame(output);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].commit, "6aae344");
+        assert_eq!(lines[0].author, "Jane Doe");
+        assert_eq!(lines[0].line_no, 1);
+        assert_eq!(lines[0].content, "mod cli;");
+        assert_eq!(lines[1].author, "A (B) C");
+        assert_eq!(lines[1].date, "2026-03-04");
+        assert_eq!(lines[1].content, "    let x = 1;");
+    }
+}
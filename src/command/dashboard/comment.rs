@@ -0,0 +1,273 @@
+//! Multi-line editor for comments sent to an agent from patch mode.
+//!
+//! Drafts are saved per hunk as they are typed (see `CommentDrafts`), so
+//! closing the editor with Esc, moving to another hunk or quitting the
+//! dashboard keeps the text. Sending the comment or clearing it drops the
+//! draft. Longer comments can be written in `$VISUAL`/`$EDITOR`.
+
+use anyhow::{Context, Result};
+use edit::Builder;
+
+/// An editing operation on the comment text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Insert(char),
+    /// Insert text as-is, e.g. from a bracketed paste
+    Paste(String),
+    Newline,
+    Backspace,
+    Delete,
+    /// Delete from the cursor back to the start of its line
+    DeleteToLineStart,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+}
+
+/// Text and cursor of the comment being written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentEditor {
+    /// Draft key of the hunk the comment is about
+    pub draft_key: String,
+    /// Shown in the editor's title, e.g. `src/lib.rs:12`
+    pub location: String,
+    /// Problem to show below the text (failed save or editor handoff)
+    pub error: Option<String>,
+    lines: Vec<String>,
+    row: usize,
+    /// Cursor column, in chars
+    col: usize,
+}
+
+impl CommentEditor {
+    /// Open the editor on `text` (a saved draft, or empty) with the cursor at
+    /// the end.
+    pub fn new(draft_key: String, location: String, text: &str) -> Self {
+        let mut editor = Self {
+            draft_key,
+            location,
+            error: None,
+            lines: Vec::new(),
+            row: 0,
+            col: 0,
+        };
+        editor.set_text(text);
+        editor
+    }
+
+    /// Replace the text, moving the cursor to the end.
+    pub fn set_text(&mut self, text: &str) {
+        let text = normalize_newlines(text);
+        self.lines = text.split('\n').map(String::from).collect();
+        self.row = self.lines.len() - 1;
+        self.col = self.lines[self.row].chars().count();
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.lines.iter().all(|line| line.trim().is_empty())
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Cursor position as (line, column in chars).
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// Apply `edit`. Returns whether the text changed.
+    pub fn apply(&mut self, edit: Edit) -> bool {
+        match edit {
+            Edit::Insert(c) => {
+                let at = self.byte_col();
+                self.lines[self.row].insert(at, c);
+                self.col += 1;
+            }
+            Edit::Paste(text) => {
+                let text = normalize_newlines(&text);
+                for (i, part) in text.split('\n').enumerate() {
+                    if i > 0 {
+                        self.apply(Edit::Newline);
+                    }
+                    let at = self.byte_col();
+                    self.lines[self.row].insert_str(at, part);
+                    self.col += part.chars().count();
+                }
+                return !text.is_empty();
+            }
+            Edit::Newline => {
+                let at = self.byte_col();
+                let rest = self.lines[self.row].split_off(at);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            Edit::Backspace => {
+                if self.col > 0 {
+                    self.col -= 1;
+                    let at = self.byte_col();
+                    self.lines[self.row].remove(at);
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.lines[self.row].chars().count();
+                    self.lines[self.row].push_str(&line);
+                } else {
+                    return false;
+                }
+            }
+            Edit::Delete => {
+                if self.col < self.lines[self.row].chars().count() {
+                    let at = self.byte_col();
+                    self.lines[self.row].remove(at);
+                } else if self.row + 1 < self.lines.len() {
+                    let next = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&next);
+                } else {
+                    return false;
+                }
+            }
+            Edit::DeleteToLineStart => {
+                if self.col == 0 {
+                    return false;
+                }
+                let at = self.byte_col();
+                self.lines[self.row].replace_range(..at, "");
+                self.col = 0;
+            }
+            Edit::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.lines[self.row].chars().count();
+                }
+                return false;
+            }
+            Edit::Right => {
+                if self.col < self.lines[self.row].chars().count() {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+                return false;
+            }
+            Edit::Up => {
+                if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.col.min(self.lines[self.row].chars().count());
+                }
+                return false;
+            }
+            Edit::Down => {
+                if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = self.col.min(self.lines[self.row].chars().count());
+                }
+                return false;
+            }
+            Edit::Home => {
+                self.col = 0;
+                return false;
+            }
+            Edit::End => {
+                self.col = self.lines[self.row].chars().count();
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Byte offset of the cursor in its line.
+    fn byte_col(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(i, _)| i)
+    }
+}
+
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Let the user edit `text` in `$VISUAL`/`$EDITOR`. The caller suspends the
+/// TUI around this.
+pub fn edit_externally(text: &str) -> Result<String> {
+    let mut builder = Builder::new();
+    builder.prefix("workmux-comment-").suffix(".md");
+    let edited = edit::edit_with_builder(text, &builder).context("Failed to run the editor")?;
+    // Editors add a final newline
+    Ok(edited.trim_end_matches(['\n', '\r']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str) -> CommentEditor {
+        CommentEditor::new("key".to_string(), "src/lib.rs:1".to_string(), text)
+    }
+
+    #[test]
+    fn typing_and_newlines_edit_at_the_cursor() {
+        let mut e = editor("");
+        for c in "héllo".chars() {
+            e.apply(Edit::Insert(c));
+        }
+        e.apply(Edit::Left);
+        e.apply(Edit::Left);
+        e.apply(Edit::Newline);
+        assert_eq!(e.text(), "hél\nlo");
+        assert_eq!(e.cursor(), (1, 0));
+
+        e.apply(Edit::Backspace);
+        assert_eq!(e.text(), "héllo");
+        assert_eq!(e.cursor(), (0, 3));
+        e.apply(Edit::Delete);
+        assert_eq!(e.text(), "hélo");
+        e.apply(Edit::DeleteToLineStart);
+        assert_eq!(e.text(), "o");
+    }
+
+    #[test]
+    fn cursor_moves_between_lines() {
+        let mut e = editor("a long line\nshort");
+        assert_eq!(e.cursor(), (1, 5));
+        e.apply(Edit::Up);
+        assert_eq!(e.cursor(), (0, 5));
+        e.apply(Edit::End);
+        e.apply(Edit::Down);
+        assert_eq!(e.cursor(), (1, 5));
+        e.apply(Edit::Home);
+        e.apply(Edit::Left);
+        assert_eq!(e.cursor(), (0, 11));
+        e.apply(Edit::Right);
+        assert_eq!(e.cursor(), (1, 0));
+        // Joining lines at the end of one
+        e.apply(Edit::Up);
+        e.apply(Edit::End);
+        e.apply(Edit::Delete);
+        assert_eq!(e.text(), "a long lineshort");
+    }
+
+    #[test]
+    fn pastes_keep_their_lines() {
+        let mut e = editor("ab");
+        e.apply(Edit::Left);
+        assert!(e.apply(Edit::Paste("1\r\n2".to_string())));
+        assert_eq!(e.text(), "a1\n2b");
+        assert_eq!(e.cursor(), (1, 1));
+        assert!(!editor(" \n ").apply(Edit::Left));
+        assert!(editor(" \n ").is_blank());
+    }
+}
@@ -0,0 +1,142 @@
+//! Confirmation prompts for destructive dashboard actions.
+
+use crate::state::ExecRequest;
+
+/// Action that runs once the user confirms it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingAction {
+    /// Send the merge command to the selected agent
+    MergeSelected,
+    /// Send the merge command to the agent whose diff is open
+    MergeDiff,
+    /// Run the host command a sandboxed agent asked for, by request id.
+    /// Can also be denied.
+    ApproveExec(u64),
+}
+
+/// A confirmation modal waiting for the user's answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirmation {
+    pub title: String,
+    /// Label/value rows describing what the action affects
+    pub details: Vec<(&'static str, String)>,
+    /// Highlighted line below the details, e.g. about uncommitted changes
+    pub warning: Option<String>,
+    pub action: PendingAction,
+}
+
+impl Confirmation {
+    /// Confirmation for sending the merge command to an agent.
+    pub fn merge(
+        action: PendingAction,
+        branch: &str,
+        target: Option<&str>,
+        changed_files: usize,
+        command: &str,
+    ) -> Self {
+        let mut details = vec![("Branch", branch.to_string())];
+        if let Some(target) = target {
+            details.push(("Into", target.to_string()));
+        }
+        details.push(("Sends", command.to_string()));
+
+        let warning = (changed_files > 0).then(|| {
+            format!(
+                "{} uncommitted file{} in the worktree",
+                changed_files,
+                if changed_files == 1 { "" } else { "s" }
+            )
+        });
+
+        Self {
+            title: format!("Merge {}?", branch),
+            details,
+            warning,
+            action,
+        }
+    }
+
+    /// Confirmation for a host command a sandboxed agent asked to run.
+    pub fn exec_request(request: &ExecRequest, agent: &str) -> Self {
+        Self {
+            title: "Run on the host?".to_string(),
+            details: vec![
+                ("Agent", agent.to_string()),
+                ("Command", request.command_line()),
+                ("In", request.worktree.display().to_string()),
+            ],
+            warning: Some("Runs once, outside the sandbox".to_string()),
+            action: PendingAction::ApproveExec(request.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_confirmation_lists_context() {
+        let confirm = Confirmation::merge(
+            PendingAction::MergeSelected,
+            "feature",
+            Some("main"),
+            0,
+            "!workmux merge",
+        );
+        assert_eq!(confirm.title, "Merge feature?");
+        assert_eq!(
+            confirm.details,
+            [
+                ("Branch", "feature".to_string()),
+                ("Into", "main".to_string()),
+                ("Sends", "!workmux merge".to_string()),
+            ]
+        );
+        assert_eq!(confirm.warning, None);
+    }
+
+    #[test]
+    fn merge_confirmation_warns_about_uncommitted_files() {
+        let one = Confirmation::merge(PendingAction::MergeDiff, "fix", None, 1, "m");
+        assert_eq!(
+            one.warning.as_deref(),
+            Some("1 uncommitted file in the worktree")
+        );
+        assert_eq!(one.details.len(), 2, "no target row when it is unknown");
+
+        let many = Confirmation::merge(PendingAction::MergeDiff, "fix", None, 3, "m");
+        assert_eq!(
+            many.warning.as_deref(),
+            Some("3 uncommitted files in the worktree")
+        );
+    }
+
+    #[test]
+    fn exec_request_confirmation_shows_the_exact_command() {
+        let request = ExecRequest {
+            pane_key: crate::state::PaneKey {
+                backend: "tmux".to_string(),
+                instance: "default".to_string(),
+                pane_id: "%3".to_string(),
+            },
+            worktree: "/src/app__worktrees/login".into(),
+            id: 42,
+            command: "gh".to_string(),
+            args: vec![
+                "pr".to_string(),
+                "create".to_string(),
+                "--title".to_string(),
+                "Fix login".to_string(),
+            ],
+            requested_ts: 1,
+            approved: None,
+        };
+        let confirm = Confirmation::exec_request(&request, "login");
+        assert_eq!(confirm.action, PendingAction::ApproveExec(42));
+        assert_eq!(
+            confirm.details[1],
+            ("Command", "gh pr create --title 'Fix login'".to_string())
+        );
+    }
+}
@@ -1,9 +1,14 @@
 //! Diff domain types and helper functions.
 
 use ratatui::text::Line;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::state::CommentDrafts;
 
 use super::ansi::{parse_ansi_to_lines, strip_ansi_escapes};
+use super::blame::{BlamePanel, BlameTarget, parse_old_range};
+use super::comment::CommentEditor;
 
 /// A file entry in the diff, used for the sidebar file list
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +45,31 @@ pub struct DiffHunk {
 }
 
 impl DiffHunk {
+    /// Key of this hunk's comment draft: the file and a digest of the hunk's
+    /// lines. The `@@` header is left out, so the draft survives edits above
+    /// the hunk that only shift its line numbers.
+    pub fn draft_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        for line in self.hunk_body.lines().skip(1) {
+            hasher.update(line.as_bytes());
+            hasher.update(b"\n");
+        }
+        let digest: String = hasher.finalize()[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        format!("{}:{}", self.filename, digest)
+    }
+
+    /// `file:line` of the hunk's first new line, as sent to the agent.
+    pub fn location(&self) -> String {
+        // e.g. "@@ -10,5 +12,7 @@" -> 12
+        let line_num = parse_hunk_header(&self.hunk_body)
+            .map(|(_, new_start)| new_start)
+            .unwrap_or(1);
+        format!("{}:{}", self.filename, line_num)
+    }
+
     /// Attempt to split this hunk into smaller hunks if there are context lines between changes.
     /// Returns None if the hunk cannot be split.
     pub fn split(&self) -> Option<Vec<DiffHunk>> {
@@ -225,13 +255,61 @@ pub struct DiffView {
     pub hunks_processed: usize,
     /// Stack of staged hunks for undo functionality
     pub staged_hunks: Vec<DiffHunk>,
-    /// Comment input buffer (Some = comment mode active)
-    pub comment_input: Option<String>,
+    /// Comment editor (Some = comment mode active)
+    pub comment_input: Option<CommentEditor>,
+    /// Unsent comments on this worktree's hunks (loaded in patch mode)
+    pub comment_drafts: CommentDrafts,
     /// List of files in the diff for the sidebar
     pub file_list: Vec<FileEntry>,
+    /// Blame and history side panel, replacing the file list while open
+    pub blame: Option<BlamePanel>,
 }
 
 impl DiffView {
+    /// Index in `file_list` of the file under the cursor: the current hunk's
+    /// file in patch mode, otherwise the file at the top of the viewport.
+    pub fn current_file_index(&self) -> Option<usize> {
+        if self.file_list.is_empty() {
+            return None;
+        }
+
+        // In patch mode, use the current hunk's filename
+        if self.patch_mode && !self.hunks.is_empty() {
+            let current_filename = &self.hunks[self.current_hunk].filename;
+            return self
+                .file_list
+                .iter()
+                .position(|f| &f.filename == current_filename);
+        }
+
+        // Find the last file whose start_line is <= current scroll position
+        let mut current_idx = 0;
+        for (idx, file) in self.file_list.iter().enumerate() {
+            if file.start_line <= self.scroll {
+                current_idx = idx;
+            } else {
+                break;
+            }
+        }
+        Some(current_idx)
+    }
+
+    /// What the blame panel should show for the cursor position.
+    pub fn blame_target(&self) -> Option<BlameTarget> {
+        if self.patch_mode {
+            let hunk = self.hunks.get(self.current_hunk)?;
+            return Some(BlameTarget {
+                filename: hunk.filename.clone(),
+                hunk: Some(parse_old_range(hunk.hunk_body.lines().next()?)?),
+            });
+        }
+        let file = &self.file_list[self.current_file_index()?];
+        Some(BlameTarget {
+            filename: file.filename.clone(),
+            hunk: None,
+        })
+    }
+
     pub fn scroll_up(&mut self) {
         self.scroll = self.scroll.saturating_sub(1);
     }
@@ -713,6 +791,23 @@ pub fn get_diff_content(
     ))
 }
 
+/// Count files with uncommitted changes, untracked files included
+pub fn count_changed_files(path: &Path) -> usize {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("status")
+        .arg("--porcelain")
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
 /// Generate diff output for untracked files (new files not yet staged)
 pub fn get_untracked_files_diff(path: &PathBuf) -> Result<String, String> {
     // Get list of untracked files
@@ -873,6 +968,31 @@ mod tests {
         assert_eq!(hunks.len(), 2);
     }
 
+    #[test]
+    fn test_draft_key_ignores_line_numbers() {
+        let hunk = DiffHunk {
+            file_header: "diff --git a/test.rs b/test.rs".to_string(),
+            hunk_body: "@@ -1,3 +1,4 @@\n+line1\n context".to_string(),
+            filename: "test.rs".to_string(),
+            lines_added: 1,
+            lines_removed: 0,
+            rendered_content: String::new(),
+            parsed_lines: vec![],
+        };
+        let shifted = DiffHunk {
+            hunk_body: "@@ -11,3 +11,4 @@\n+line1\n context".to_string(),
+            ..hunk.clone()
+        };
+        let changed = DiffHunk {
+            hunk_body: "@@ -1,3 +1,4 @@\n+line2\n context".to_string(),
+            ..hunk.clone()
+        };
+        assert_eq!(hunk.draft_key(), shifted.draft_key());
+        assert_ne!(hunk.draft_key(), changed.draft_key());
+        assert!(hunk.draft_key().starts_with("test.rs:"));
+        assert_eq!(shifted.location(), "test.rs:11");
+    }
+
     #[test]
     fn test_map_file_offsets() {
         use ratatui::text::Line;
@@ -7,12 +7,18 @@
 //! - Sending commands to agents (commit, merge)
 
 use std::io::Write;
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::state::{CommentDrafts, StateStore};
 
 use super::ansi::parse_ansi_to_lines;
 use super::app::{App, ViewMode};
+use super::blame::{self, BlamePanel, BlameSource};
+use super::comment::{CommentEditor, Edit};
 use super::diff::{
     DiffView, extract_file_list, get_diff_content, get_file_list_numstat, map_file_offsets,
-    parse_hunk_header,
 };
 
 /// Extension trait for diff and patch mode operations on App.
@@ -25,10 +31,17 @@ pub trait DiffOps {
     fn stage_and_next(&mut self);
     fn skip_hunk(&mut self);
     fn undo_staged_hunk(&mut self);
+    fn start_comment(&mut self);
+    fn edit_comment(&mut self, edit: Edit);
+    fn set_comment_text(&mut self, text: &str);
+    fn close_comment(&mut self);
+    fn discard_comment(&mut self);
     fn send_hunk_comment(&mut self);
     fn split_current_hunk(&mut self) -> bool;
     fn load_diff(&mut self, branch_diff: bool);
     fn close_diff(&mut self);
+    fn toggle_blame(&mut self);
+    fn refresh_blame(&mut self);
     fn send_commit_to_agent(&mut self);
     fn trigger_merge(&mut self);
     fn send_commit_to_selected(&mut self);
@@ -64,6 +77,7 @@ fn reload_unstaged_diff(app: &mut App) {
             let parsed_lines = parse_ansi_to_lines(&content);
             let mut file_list = extract_file_list(&hunks);
             map_file_offsets(&mut file_list, &parsed_lines);
+            let comment_drafts = load_comment_drafts(&path);
 
             app.view_mode = ViewMode::Diff(Box::new(DiffView {
                 content,
@@ -84,7 +98,9 @@ fn reload_unstaged_diff(app: &mut App) {
                 hunks_processed: 0,
                 staged_hunks: Vec::new(),
                 comment_input: None,
+                comment_drafts,
                 file_list,
+                blame: None,
             }));
         }
         Err(e) => {
@@ -108,12 +124,71 @@ fn reload_unstaged_diff(app: &mut App) {
                 hunks_processed: 0,
                 staged_hunks: Vec::new(),
                 comment_input: None,
+                comment_drafts: CommentDrafts::default(),
                 file_list: Vec::new(),
+                blame: None,
             }));
         }
     }
 }
 
+/// Unsent comments on the hunks of the worktree at `path`.
+fn load_comment_drafts(path: &Path) -> CommentDrafts {
+    StateStore::new()
+        .and_then(|store| store.load_comment_drafts(path))
+        .unwrap_or_else(|e| {
+            warn!(error = %e, "dashboard:failed to load comment drafts");
+            CommentDrafts {
+                worktree: path.to_path_buf(),
+                ..Default::default()
+            }
+        })
+}
+
+/// Save the open comment as the current hunk's draft, or drop the draft once
+/// the comment is blank. A failure is shown in the editor.
+fn save_comment_draft(diff: &mut DiffView) {
+    let Some(editor) = diff.comment_input.as_mut() else {
+        return;
+    };
+    if editor.is_blank() {
+        diff.comment_drafts.drafts.remove(&editor.draft_key);
+    } else {
+        diff.comment_drafts
+            .drafts
+            .insert(editor.draft_key.clone(), editor.text());
+    }
+    editor.error = StateStore::new()
+        .and_then(|store| store.save_comment_drafts(&diff.comment_drafts))
+        .err()
+        .map(|e| format!("Draft not saved: {:#}", e));
+}
+
+/// Base branch of a worktree from its git status, falling back to "main".
+fn diff_base(app: &App, path: &Path) -> String {
+    app.git_statuses
+        .get(path)
+        .map(|s| s.base_branch.as_str())
+        .filter(|b| !b.is_empty())
+        .unwrap_or("main")
+        .to_string()
+}
+
+/// Load the blame panel for the cursor position of `diff`.
+fn load_blame(diff: &DiffView, base: &str) -> Option<BlamePanel> {
+    let target = diff.blame_target()?;
+    let diff_arg = if diff.is_branch_diff {
+        format!("{}...HEAD", base)
+    } else {
+        "HEAD".to_string()
+    };
+    let source = BlameSource {
+        diff_arg: &diff_arg,
+        base,
+    };
+    Some(blame::load(&diff.worktree_path, target, &source))
+}
+
 impl DiffOps for App {
     /// Stage a single hunk using git apply --cached
     fn stage_hunk(&mut self) -> Result<(), String> {
@@ -319,6 +394,62 @@ impl DiffOps for App {
         }
     }
 
+    /// Open the comment editor on the current hunk, with its draft if any
+    fn start_comment(&mut self) {
+        let ViewMode::Diff(ref mut diff) = self.view_mode else {
+            return;
+        };
+        let Some(hunk) = diff.hunks.get(diff.current_hunk) else {
+            return;
+        };
+        let key = hunk.draft_key();
+        let draft = diff
+            .comment_drafts
+            .drafts
+            .get(&key)
+            .map(String::as_str)
+            .unwrap_or("");
+        diff.comment_input = Some(CommentEditor::new(key, hunk.location(), draft));
+    }
+
+    /// Edit the open comment, saving the draft when the text changed
+    fn edit_comment(&mut self, edit: Edit) {
+        if let ViewMode::Diff(ref mut diff) = self.view_mode
+            && let Some(ref mut editor) = diff.comment_input
+            && editor.apply(edit)
+        {
+            save_comment_draft(diff);
+        }
+    }
+
+    /// Replace the open comment's text (after editing it in $EDITOR)
+    fn set_comment_text(&mut self, text: &str) {
+        if let ViewMode::Diff(ref mut diff) = self.view_mode
+            && let Some(ref mut editor) = diff.comment_input
+        {
+            editor.set_text(text);
+            save_comment_draft(diff);
+        }
+    }
+
+    /// Close the comment editor, keeping the draft
+    fn close_comment(&mut self) {
+        if let ViewMode::Diff(ref mut diff) = self.view_mode {
+            diff.comment_input = None;
+        }
+    }
+
+    /// Close the comment editor and drop the draft
+    fn discard_comment(&mut self) {
+        if let ViewMode::Diff(ref mut diff) = self.view_mode
+            && let Some(ref mut editor) = diff.comment_input
+        {
+            editor.set_text("");
+            save_comment_draft(diff);
+            diff.comment_input = None;
+        }
+    }
+
     /// Send a comment about the current hunk to the agent
     fn send_hunk_comment(&mut self) {
         let ViewMode::Diff(ref mut diff) = self.view_mode else {
@@ -329,17 +460,21 @@ impl DiffOps for App {
             return;
         }
 
-        let comment = match diff.comment_input.take() {
-            Some(c) if !c.trim().is_empty() => c,
+        let comment = match diff.comment_input {
+            Some(ref editor) if !editor.is_blank() => editor.text(),
             _ => return,
         };
 
-        let hunk = &diff.hunks[diff.current_hunk];
+        let worktree_path = diff.worktree_path.clone();
+        if !self.allow_input_to(&worktree_path) {
+            return;
+        }
+        // Re-borrow: the guard above needs &mut self
+        let ViewMode::Diff(ref mut diff) = self.view_mode else {
+            return;
+        };
 
-        // Extract line number from hunk header (e.g., "@@ -10,5 +12,7 @@" -> 12)
-        let line_num = parse_hunk_header(&hunk.hunk_body)
-            .map(|(_, new_start)| new_start)
-            .unwrap_or(1);
+        let hunk = &diff.hunks[diff.current_hunk];
 
         // Determine safe code fence (use more backticks if content contains ```)
         let mut fence = "```".to_string();
@@ -349,14 +484,31 @@ impl DiffOps for App {
 
         // Format the message with file path, line number, hunk content, and comment
         let message = format!(
-            "{}:{}\n\n{}diff\n{}\n{}\n\n{}",
-            hunk.filename, line_num, fence, hunk.hunk_body, fence, comment
+            "{}\n\n{}diff\n{}\n{}\n\n{}",
+            hunk.location(),
+            fence,
+            hunk.hunk_body,
+            fence,
+            comment
         );
 
         // Use paste_multiline to properly handle newlines in the message
-        let _ = self.mux.paste_multiline(&diff.pane_id, &message);
+        if let Err(e) = self.mux.paste_multiline(&diff.pane_id, &message) {
+            // Keep the editor open so the comment isn't lost
+            if let Some(ref mut editor) = diff.comment_input {
+                editor.error = Some(format!("Could not send the comment: {:#}", e));
+            }
+            return;
+        }
         // Send an additional Enter to submit the comment to the agent
         let _ = self.mux.send_key(&diff.pane_id, "Enter");
+
+        // Sent; the draft is no longer needed
+        if let Some(ref mut editor) = diff.comment_input {
+            editor.set_text("");
+        }
+        save_comment_draft(diff);
+        diff.comment_input = None;
     }
 
     /// Split the current hunk into smaller hunks if possible
@@ -402,13 +554,7 @@ impl DiffOps for App {
         let worktree_name = self.extract_worktree_name(agent).0;
 
         let (diff_arg, title) = if branch_diff {
-            // Get the base branch from git status if available, fallback to "main"
-            let base = self
-                .git_statuses
-                .get(path)
-                .map(|s| s.base_branch.as_str())
-                .filter(|b| !b.is_empty())
-                .unwrap_or("main");
+            let base = diff_base(self, path);
             (
                 format!("{}...HEAD", base),
                 format!("Review: {} \u{2192} {}", worktree_name, base),
@@ -464,7 +610,9 @@ impl DiffOps for App {
                     hunks_processed: 0,
                     staged_hunks: Vec::new(),
                     comment_input: None,
+                    comment_drafts: CommentDrafts::default(),
                     file_list,
+                    blame: None,
                 }));
             }
             Err(e) => {
@@ -489,7 +637,9 @@ impl DiffOps for App {
                     hunks_processed: 0,
                     staged_hunks: Vec::new(),
                     comment_input: None,
+                    comment_drafts: CommentDrafts::default(),
                     file_list: Vec::new(),
+                    blame: None,
                 }));
             }
         }
@@ -500,53 +650,90 @@ impl DiffOps for App {
         self.view_mode = ViewMode::Dashboard;
     }
 
+    /// Open or close the blame and history panel for the cursor position
+    fn toggle_blame(&mut self) {
+        let ViewMode::Diff(ref diff) = self.view_mode else {
+            return;
+        };
+        let blame = if diff.blame.is_some() {
+            None
+        } else {
+            load_blame(diff, &diff_base(self, &diff.worktree_path))
+        };
+        if let ViewMode::Diff(ref mut diff) = self.view_mode {
+            diff.blame = blame;
+        }
+    }
+
+    /// Reload an open blame panel once the cursor moved to another file or
+    /// hunk
+    fn refresh_blame(&mut self) {
+        let ViewMode::Diff(ref diff) = self.view_mode else {
+            return;
+        };
+        let Some(ref blame) = diff.blame else {
+            return;
+        };
+        if diff.blame_target().as_ref() == Some(&blame.target) {
+            return;
+        }
+        let blame = load_blame(diff, &diff_base(self, &diff.worktree_path));
+        if let ViewMode::Diff(ref mut diff) = self.view_mode {
+            diff.blame = blame;
+        }
+    }
+
     /// Send commit action to the agent pane and close diff modal
     fn send_commit_to_agent(&mut self) {
-        if let ViewMode::Diff(diff) = &self.view_mode {
-            let _ = self.mux.send_keys_to_agent(
-                &diff.pane_id,
-                self.config.dashboard.commit(),
-                self.config.agent.as_deref(),
-            );
+        let target = match &self.view_mode {
+            ViewMode::Diff(diff) => Some((diff.pane_id.clone(), diff.worktree_path.clone())),
+            _ => None,
+        };
+        if let Some((pane_id, path)) = target
+            && self.allow_input_to(&path)
+        {
+            let text = self.config.dashboard.commit().to_string();
+            self.send_agent_input(&pane_id, &text);
         }
         self.close_diff();
     }
 
     /// Send merge action to the agent pane and close diff modal
     fn trigger_merge(&mut self) {
-        if let ViewMode::Diff(diff) = &self.view_mode {
-            let _ = self.mux.send_keys_to_agent(
-                &diff.pane_id,
-                self.config.dashboard.merge(),
-                self.config.agent.as_deref(),
-            );
+        let target = match &self.view_mode {
+            ViewMode::Diff(diff) => Some((diff.pane_id.clone(), diff.worktree_path.clone())),
+            _ => None,
+        };
+        if let Some((pane_id, path)) = target
+            && self.allow_input_to(&path)
+        {
+            let text = self.config.dashboard.merge().to_string();
+            self.send_agent_input(&pane_id, &text);
         }
         self.close_diff();
     }
 
     /// Send commit action to the currently selected agent's pane (from dashboard view)
     fn send_commit_to_selected(&mut self) {
-        if let Some(selected) = self.table_state.selected()
-            && let Some(agent) = self.agents.get(selected)
+        if let Some(path) = self.selected_agent_path()
+            && self.allow_input_to(&path)
+            && let Some(selected) = self.table_state.selected()
+            && let Some(pane_id) = self.agents.get(selected).map(|a| a.pane_id.clone())
         {
-            let _ = self.mux.send_keys_to_agent(
-                &agent.pane_id,
-                self.config.dashboard.commit(),
-                self.config.agent.as_deref(),
-            );
+            let text = self.config.dashboard.commit().to_string();
+            self.send_agent_input(&pane_id, &text);
         }
     }
 
     /// Send merge action to the currently selected agent's pane (from dashboard view)
     fn trigger_merge_for_selected(&mut self) {
-        if let Some(selected) = self.table_state.selected()
-            && let Some(agent) = self.agents.get(selected)
+        if let Some(path) = self.selected_agent_path()
+            && self.allow_input_to(&path)
+            && let Some(selected) = self.table_state.selected()
+            && let Some(pane_id) = self.agents.get(selected).map(|a| a.pane_id.clone())
         {
-            let _ = self.mux.send_keys_to_agent(
-                &agent.pane_id,
-                self.config.dashboard.merge(),
-                self.config.agent.as_deref(),
-            );
+            let text = self.config.dashboard.merge().to_string();
+            self.send_agent_input(&pane_id, &text);
         }
     }
 }
@@ -0,0 +1,198 @@
+//! File watcher marking worktrees dirty as soon as their files change.
+//!
+//! With `dashboard.watch_files`, the dashboard re-checks a worktree's git
+//! status right after a change instead of polling every worktree every few
+//! seconds. Worktrees that can't be watched (network filesystems, or when the
+//! OS runs out of watches) are polled as before.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::{debug, warn};
+
+use crate::filesystem;
+use crate::git::linked_git_dir;
+
+/// A directory being watched on behalf of a worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Root {
+    dir: PathBuf,
+    worktree: PathBuf,
+    /// Whether `dir` is the `logs` directory of the worktree's git directory,
+    /// where `HEAD` moves on commits, resets and checkouts
+    git_logs: bool,
+}
+
+pub struct WorktreeWatcher {
+    watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<notify::Event>>,
+    /// Watched worktrees, with the directories watched for each
+    watched: HashMap<PathBuf, Vec<Root>>,
+    /// Worktrees that couldn't be watched; they are polled instead
+    unwatchable: HashSet<PathBuf>,
+}
+
+impl WorktreeWatcher {
+    pub fn new() -> Result<Self> {
+        let (tx, rx) = channel();
+        let watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+        Ok(Self {
+            watcher,
+            rx,
+            watched: HashMap::new(),
+            unwatchable: HashSet::new(),
+        })
+    }
+
+    /// Watch `worktrees` and stop watching any others.
+    pub fn sync<'a>(&mut self, worktrees: impl IntoIterator<Item = &'a PathBuf>) {
+        let wanted: HashSet<&PathBuf> = worktrees.into_iter().collect();
+        let gone: Vec<PathBuf> = self
+            .watched
+            .keys()
+            .filter(|w| !wanted.contains(w))
+            .cloned()
+            .collect();
+        for worktree in gone {
+            for root in self.watched.remove(&worktree).unwrap_or_default() {
+                let _ = self.watcher.unwatch(&root.dir);
+            }
+        }
+        for worktree in wanted {
+            if self.watched.contains_key(worktree) || self.unwatchable.contains(worktree) {
+                continue;
+            }
+            if filesystem::is_network(worktree) {
+                self.unwatchable.insert(worktree.clone());
+                continue;
+            }
+            match self.watch(worktree) {
+                Ok(roots) => {
+                    self.watched.insert(worktree.clone(), roots);
+                }
+                Err(e) => {
+                    warn!(worktree = %worktree.display(), error = %e, "fs_watch:polling instead");
+                    self.unwatchable.insert(worktree.clone());
+                }
+            }
+        }
+    }
+
+    fn watch(&mut self, worktree: &Path) -> Result<Vec<Root>> {
+        self.watcher
+            .watch(worktree, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", worktree.display()))?;
+        let mut roots = vec![Root {
+            dir: worktree.to_path_buf(),
+            worktree: worktree.to_path_buf(),
+            git_logs: false,
+        }];
+        // A linked worktree's git directory lives in the main repository;
+        // watch where its HEAD log is written so commits show up too
+        if let Some(logs) = linked_git_dir(worktree).map(|dir| dir.join("logs"))
+            && logs.is_dir()
+        {
+            match self.watcher.watch(&logs, RecursiveMode::NonRecursive) {
+                Ok(()) => roots.push(Root {
+                    dir: logs,
+                    worktree: worktree.to_path_buf(),
+                    git_logs: true,
+                }),
+                Err(e) => debug!(dir = %logs.display(), error = %e, "fs_watch:no git logs watch"),
+            }
+        }
+        Ok(roots)
+    }
+
+    /// Whether changes to `worktree` are picked up by the watcher.
+    pub fn is_watching(&self, worktree: &Path) -> bool {
+        self.watched.contains_key(worktree)
+    }
+
+    /// Worktrees whose files changed since the last call. Never blocks.
+    pub fn changed(&self) -> HashSet<PathBuf> {
+        let roots: Vec<&Root> = self.watched.values().flatten().collect();
+        let mut changed = HashSet::new();
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                Ok(event) => {
+                    if !matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        continue;
+                    }
+                    changed.extend(event.paths.iter().filter_map(|p| affected(&roots, p)));
+                }
+                // Events may have been lost, so assume everything changed
+                Err(_) => changed.extend(self.watched.keys().cloned()),
+            }
+        }
+        changed
+    }
+}
+
+/// The worktree whose git status a change at `path` can affect. Changes
+/// inside `.git` only count when HEAD moves, since `git status` itself
+/// rewrites the index.
+fn affected(roots: &[&Root], path: &Path) -> Option<PathBuf> {
+    // The innermost root wins, for worktrees nested in the main worktree
+    let root = roots
+        .iter()
+        .filter(|r| path.starts_with(&r.dir))
+        .max_by_key(|r| r.dir.components().count())?;
+    let rel = path.strip_prefix(&root.dir).ok()?;
+    let relevant = if root.git_logs {
+        rel == Path::new("HEAD")
+    } else if rel.starts_with(".git") {
+        rel == Path::new(".git/logs/HEAD")
+    } else {
+        true
+    };
+    relevant.then(|| root.worktree.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(dir: &str, worktree: &str, git_logs: bool) -> Root {
+        Root {
+            dir: PathBuf::from(dir),
+            worktree: PathBuf::from(worktree),
+            git_logs,
+        }
+    }
+
+    #[test]
+    fn changes_map_to_the_innermost_worktree() {
+        let main = root("/repo", "/repo", false);
+        let nested = root("/repo/.worktrees/feat", "/repo/.worktrees/feat", false);
+        let logs = root(
+            "/repo/.git/worktrees/feat/logs",
+            "/repo/.worktrees/feat",
+            true,
+        );
+        let roots = vec![&main, &nested, &logs];
+        let at = |p: &str| affected(&roots, Path::new(p)).map(|w| w.display().to_string());
+
+        assert_eq!(at("/repo/src/main.rs").as_deref(), Some("/repo"));
+        assert_eq!(
+            at("/repo/.worktrees/feat/src/lib.rs").as_deref(),
+            Some("/repo/.worktrees/feat")
+        );
+        assert_eq!(at("/elsewhere/file"), None);
+
+        // Inside .git only HEAD moving counts
+        assert_eq!(at("/repo/.git/index"), None);
+        assert_eq!(at("/repo/.git/logs/HEAD").as_deref(), Some("/repo"));
+        assert_eq!(
+            at("/repo/.git/worktrees/feat/logs/HEAD").as_deref(),
+            Some("/repo/.worktrees/feat")
+        );
+        assert_eq!(at("/repo/.git/worktrees/feat/logs/other"), None);
+    }
+}
@@ -3,6 +3,7 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use super::actions::Action;
+use super::comment::Edit;
 
 /// Context for key handling - determines which keymap is active.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,6 +13,7 @@ pub enum Context {
     DiffNormal,
     Patch,
     Comment,
+    Confirm,
 }
 
 /// Map a key event to an action for the given context.
@@ -22,10 +24,22 @@ pub fn action_for_key(ctx: Context, key: KeyEvent) -> Option<Action> {
         Context::DiffNormal => diff_normal_key(key),
         Context::Patch => patch_key(key),
         Context::Comment => comment_key(key),
+        Context::Confirm => confirm_key(key),
     }
 }
 
+/// Whether `key` toggles input mode. Ctrl+] is rarely bound by agents or
+/// shells, so every other key, Esc included, can be forwarded. Terminals
+/// without the kitty keyboard protocol report it as Ctrl+5.
+fn is_input_toggle(key: KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char(']') | KeyCode::Char('5'))
+}
+
 fn dashboard_normal_key(key: KeyEvent) -> Option<Action> {
+    if is_input_toggle(key) {
+        return Some(Action::EnterInputMode);
+    }
     match key.code {
         KeyCode::Char('?') => Some(Action::ShowHelp),
         KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
@@ -41,6 +55,9 @@ fn dashboard_normal_key(key: KeyEvent) -> Option<Action> {
         KeyCode::Char('p') => Some(Action::PeekSelected),
         KeyCode::Char('s') => Some(Action::CycleSortMode),
         KeyCode::Char('f') => Some(Action::ToggleStaleFilter),
+        KeyCode::Char('!') => Some(Action::TogglePin),
+        KeyCode::Char('a') => Some(Action::AcknowledgeReminders),
+        KeyCode::Char('r') => Some(Action::ReloadConfig),
         KeyCode::Char('i') => Some(Action::EnterInputMode),
         KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             Some(Action::ScrollPreviewUp)
@@ -53,24 +70,62 @@ fn dashboard_normal_key(key: KeyEvent) -> Option<Action> {
         KeyCode::Char('d') => Some(Action::LoadWipDiff),
         KeyCode::Char('c') => Some(Action::SendCommitDashboard),
         KeyCode::Char('m') => Some(Action::TriggerMergeDashboard),
+        KeyCode::Char('o') => Some(Action::OpenFileManager),
+        KeyCode::Char('t') => Some(Action::OpenScratchShell),
+        KeyCode::Char('e') => Some(Action::SaveEvidence),
+        KeyCode::Char('x') => Some(Action::ReviewExecRequest),
+        KeyCode::Char('h') => Some(Action::ShowHistory),
         KeyCode::Char(c @ '1'..='9') => Some(Action::JumpToIndex((c as u8 - b'1') as usize)),
         _ => None,
     }
 }
 
 fn dashboard_input_key(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        KeyCode::Esc => Some(Action::ExitInputMode),
-        KeyCode::Enter => Some(Action::SendKey("Enter".to_string())),
-        KeyCode::Backspace => Some(Action::SendKey("BSpace".to_string())),
-        KeyCode::Tab => Some(Action::SendKey("Tab".to_string())),
-        KeyCode::Up => Some(Action::SendKey("Up".to_string())),
-        KeyCode::Down => Some(Action::SendKey("Down".to_string())),
-        KeyCode::Left => Some(Action::SendKey("Left".to_string())),
-        KeyCode::Right => Some(Action::SendKey("Right".to_string())),
-        KeyCode::Char(c) => Some(Action::SendKey(c.to_string())),
-        _ => None,
+    if is_input_toggle(key) {
+        return Some(Action::ExitInputMode);
     }
+    tmux_key_name(key).map(Action::SendKey)
+}
+
+/// The tmux name of a key forwarded to an agent, with `C-`, `M-` and `S-`
+/// prefixes for its modifiers.
+fn tmux_key_name(key: KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Backspace => "BSpace".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BTab".to_string(),
+        KeyCode::Esc => "Escape".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PPage".to_string(),
+        KeyCode::PageDown => "NPage".to_string(),
+        KeyCode::Insert => "IC".to_string(),
+        KeyCode::Delete => "DC".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        _ => return None,
+    };
+    let mut prefixed = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        prefixed.push_str("C-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        prefixed.push_str("M-");
+    }
+    // Shift is already part of the character ('A', '!') and of BackTab
+    if key.modifiers.contains(KeyModifiers::SHIFT)
+        && !matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab)
+    {
+        prefixed.push_str("S-");
+    }
+    prefixed.push_str(&name);
+    Some(prefixed)
 }
 
 fn diff_normal_key(key: KeyEvent) -> Option<Action> {
@@ -90,6 +145,8 @@ fn diff_normal_key(key: KeyEvent) -> Option<Action> {
         }
         KeyCode::Tab => Some(Action::ToggleDiffType),
         KeyCode::Char('a') => Some(Action::EnterPatchMode),
+        KeyCode::Char('b') => Some(Action::ToggleBlame),
+        KeyCode::Char('e') => Some(Action::SaveEvidence),
         KeyCode::Char('c') => Some(Action::SendCommitDiff),
         KeyCode::Char('m') => Some(Action::TriggerMergeDiff),
         _ => None,
@@ -111,6 +168,8 @@ fn patch_key(key: KeyEvent) -> Option<Action> {
         KeyCode::Char('u') => Some(Action::UndoStagedHunk),
         KeyCode::Char('s') => Some(Action::SplitHunk),
         KeyCode::Char('o') => Some(Action::StartComment),
+        KeyCode::Char('b') => Some(Action::ToggleBlame),
+        KeyCode::Char('e') => Some(Action::SaveEvidence),
         KeyCode::Char('k') | KeyCode::Up => Some(Action::PrevHunk),
         KeyCode::Char('j') | KeyCode::Down => Some(Action::NextHunk),
         KeyCode::Char('c') => Some(Action::SendCommitDiff),
@@ -121,11 +180,47 @@ fn patch_key(key: KeyEvent) -> Option<Action> {
 }
 
 fn comment_key(key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let edit = match key.code {
+        KeyCode::Esc => return Some(Action::CloseComment),
+        // Terminals only report modified Enter with the kitty keyboard
+        // protocol, so Ctrl+J is the newline that always works
+        KeyCode::Enter
+            if key
+                .modifiers
+                .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+        {
+            Edit::Newline
+        }
+        KeyCode::Enter => return Some(Action::SendComment),
+        KeyCode::Char('c') if ctrl => return Some(Action::Quit),
+        KeyCode::Char('g') if ctrl => return Some(Action::EditCommentExternally),
+        KeyCode::Char('x') if ctrl => return Some(Action::DiscardComment),
+        KeyCode::Char('j') if ctrl => Edit::Newline,
+        KeyCode::Char('a') if ctrl => Edit::Home,
+        KeyCode::Char('e') if ctrl => Edit::End,
+        KeyCode::Char('u') if ctrl => Edit::DeleteToLineStart,
+        KeyCode::Char(_) if ctrl => return None,
+        KeyCode::Char(c) => Edit::Insert(c),
+        KeyCode::Backspace => Edit::Backspace,
+        KeyCode::Delete => Edit::Delete,
+        KeyCode::Left => Edit::Left,
+        KeyCode::Right => Edit::Right,
+        KeyCode::Up => Edit::Up,
+        KeyCode::Down => Edit::Down,
+        KeyCode::Home => Edit::Home,
+        KeyCode::End => Edit::End,
+        _ => return None,
+    };
+    Some(Action::EditComment(edit))
+}
+
+fn confirm_key(key: KeyEvent) -> Option<Action> {
     match key.code {
-        KeyCode::Esc => Some(Action::CancelComment),
-        KeyCode::Enter => Some(Action::SendComment),
-        KeyCode::Backspace => Some(Action::DeleteChar),
-        KeyCode::Char(c) => Some(Action::AppendChar(c)),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Char('y') | KeyCode::Enter => Some(Action::Confirm),
+        KeyCode::Char('d') => Some(Action::DenyConfirm),
+        KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => Some(Action::CancelConfirm),
         _ => None,
     }
 }
@@ -142,15 +237,27 @@ pub fn help_rows(ctx: Context) -> Vec<(&'static str, &'static str)> {
             ("p", "Peek agent (keep popup)"),
             ("s", "Cycle sort mode"),
             ("f", "Toggle stale filter"),
-            ("i", "Enter input mode"),
+            ("!", "Pin/unpin agent to the top"),
+            ("a", "Acknowledge reminders"),
+            ("r", "Reload config"),
+            ("i/Ctrl+]", "Enter input mode"),
             ("Ctrl+u/d", "Scroll preview"),
             ("+/-", "Resize preview"),
             ("d", "View diff"),
             ("c", "Commit changes"),
             ("m", "Merge branch"),
+            ("o", "Open worktree in file manager"),
+            ("t", "Open shell below agent"),
+            ("e", "Save output as merge evidence"),
+            ("x", "Review host command request"),
+            ("h", "Status history of agent"),
             ("1-9", "Quick jump"),
         ],
-        Context::DashboardInput => vec![("Esc", "Exit input mode"), ("<keys>", "Send to agent")],
+        Context::DashboardInput => vec![
+            ("Ctrl+]", "Exit input mode"),
+            ("<keys>", "Send to agent, Esc and Ctrl keys included"),
+            ("<paste>", "Paste into agent"),
+        ],
         Context::DiffNormal => vec![
             ("?", "Show help"),
             ("q/Esc", "Close diff"),
@@ -158,6 +265,8 @@ pub fn help_rows(ctx: Context) -> Vec<(&'static str, &'static str)> {
             ("Ctrl+d/u", "Scroll page"),
             ("Tab", "Toggle WIP/Review"),
             ("a", "Enter patch mode (WIP only)"),
+            ("b", "Toggle blame and history"),
+            ("e", "Save agent output as evidence"),
             ("c", "Commit changes"),
             ("m", "Merge branch"),
         ],
@@ -168,6 +277,8 @@ pub fn help_rows(ctx: Context) -> Vec<(&'static str, &'static str)> {
             ("u", "Undo last staged"),
             ("s", "Split hunk"),
             ("o", "Add comment"),
+            ("b", "Toggle blame and history"),
+            ("e", "Save agent output as evidence"),
             ("j/k", "Next/prev hunk"),
             ("Ctrl+d/u", "Scroll hunk"),
             ("c", "Commit changes"),
@@ -175,10 +286,20 @@ pub fn help_rows(ctx: Context) -> Vec<(&'static str, &'static str)> {
             ("q/Esc", "Exit patch mode"),
         ],
         Context::Comment => vec![
-            ("Esc", "Cancel"),
             ("Enter", "Send comment"),
+            ("Alt+Enter/Ctrl+j", "New line"),
+            ("Ctrl+g", "Edit in $EDITOR"),
+            ("Esc", "Close (draft is kept)"),
+            ("Ctrl+x", "Discard draft"),
+            ("Ctrl+a/e", "Start/end of line"),
+            ("Ctrl+u", "Delete to start of line"),
             ("<type>", "Input text"),
         ],
+        Context::Confirm => vec![
+            ("y/Enter", "Confirm"),
+            ("d", "Deny host command"),
+            ("n/q/Esc", "Cancel"),
+        ],
     }
 }
 
@@ -193,6 +314,7 @@ mod tests {
         assert!(!help_rows(Context::DiffNormal).is_empty());
         assert!(!help_rows(Context::Patch).is_empty());
         assert!(!help_rows(Context::Comment).is_empty());
+        assert!(!help_rows(Context::Confirm).is_empty());
     }
 
     #[test]
@@ -203,6 +325,7 @@ mod tests {
             Context::DiffNormal,
             Context::Patch,
             Context::Comment,
+            Context::Confirm,
         ] {
             let rows = help_rows(ctx);
             let keys: Vec<_> = rows.iter().map(|(k, _)| *k).collect();
@@ -236,6 +359,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dashboard_quick_action_keys() {
+        let o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+        let t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE);
+        let e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE);
+
+        assert_eq!(
+            action_for_key(Context::DashboardNormal, o),
+            Some(Action::OpenFileManager)
+        );
+        assert_eq!(
+            action_for_key(Context::DashboardNormal, t),
+            Some(Action::OpenScratchShell)
+        );
+        for ctx in [
+            Context::DashboardNormal,
+            Context::DiffNormal,
+            Context::Patch,
+        ] {
+            assert_eq!(action_for_key(ctx, e), Some(Action::SaveEvidence));
+        }
+
+        // Shift is reported along with '!' on some terminals
+        let bang = KeyEvent::new(KeyCode::Char('!'), KeyModifiers::SHIFT);
+        assert_eq!(
+            action_for_key(Context::DashboardNormal, bang),
+            Some(Action::TogglePin)
+        );
+    }
+
+    #[test]
+    fn test_input_mode_forwards_modified_keys() {
+        let key = |code, modifiers| {
+            action_for_key(Context::DashboardInput, KeyEvent::new(code, modifiers))
+        };
+        let sent = |name: &str| Some(Action::SendKey(name.to_string()));
+        assert_eq!(key(KeyCode::Esc, KeyModifiers::NONE), sent("Escape"));
+        assert_eq!(key(KeyCode::Char('c'), KeyModifiers::CONTROL), sent("C-c"));
+        assert_eq!(key(KeyCode::Char('A'), KeyModifiers::SHIFT), sent("A"));
+        assert_eq!(key(KeyCode::Char('x'), KeyModifiers::ALT), sent("M-x"));
+        assert_eq!(
+            key(KeyCode::Char(' '), KeyModifiers::CONTROL),
+            sent("C-Space")
+        );
+        assert_eq!(key(KeyCode::BackTab, KeyModifiers::SHIFT), sent("BTab"));
+        assert_eq!(
+            key(KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+            sent("C-S-Up")
+        );
+        assert_eq!(key(KeyCode::F(5), KeyModifiers::NONE), sent("F5"));
+    }
+
+    #[test]
+    fn test_input_mode_toggle() {
+        for code in [KeyCode::Char(']'), KeyCode::Char('5')] {
+            let toggle = KeyEvent::new(code, KeyModifiers::CONTROL);
+            assert_eq!(
+                action_for_key(Context::DashboardNormal, toggle),
+                Some(Action::EnterInputMode)
+            );
+            assert_eq!(
+                action_for_key(Context::DashboardInput, toggle),
+                Some(Action::ExitInputMode)
+            );
+        }
+        // Plain 5 still jumps to the fifth agent
+        assert_eq!(
+            action_for_key(
+                Context::DashboardNormal,
+                KeyEvent::new(KeyCode::Char('5'), KeyModifiers::NONE)
+            ),
+            Some(Action::JumpToIndex(4))
+        );
+    }
+
     #[test]
     fn test_diff_close_keys() {
         let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
@@ -251,6 +449,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_confirm_keys() {
+        let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE);
+        let m = KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+
+        assert_eq!(action_for_key(Context::Confirm, y), Some(Action::Confirm));
+        assert_eq!(
+            action_for_key(Context::Confirm, d),
+            Some(Action::DenyConfirm)
+        );
+        assert_eq!(
+            action_for_key(Context::Confirm, esc),
+            Some(Action::CancelConfirm)
+        );
+        assert_eq!(action_for_key(Context::Confirm, m), None);
+    }
+
     #[test]
     fn test_patch_stage_key() {
         let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
@@ -259,4 +476,27 @@ mod tests {
             Some(Action::StageAndNext)
         );
     }
+
+    #[test]
+    fn test_comment_keys() {
+        let key =
+            |code, modifiers| action_for_key(Context::Comment, KeyEvent::new(code, modifiers));
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::NONE),
+            Some(Action::SendComment)
+        );
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::ALT),
+            Some(Action::EditComment(Edit::Newline))
+        );
+        assert_eq!(
+            key(KeyCode::Char('j'), KeyModifiers::CONTROL),
+            Some(Action::EditComment(Edit::Newline))
+        );
+        assert_eq!(
+            key(KeyCode::Char('j'), KeyModifiers::NONE),
+            Some(Action::EditComment(Edit::Insert('j')))
+        );
+        assert_eq!(key(KeyCode::Char('q'), KeyModifiers::CONTROL), None);
+    }
 }
@@ -12,32 +12,47 @@
 //! - `actions`: Action enum and dispatcher for all dashboard actions
 //! - `agent`: Pure helper functions for agent data extraction
 //! - `ansi`: ANSI escape sequence parsing and stripping
+//! - `blame`: Blame and history side panel for the diff view
+//! - `comment`: Multi-line comment editor and per-hunk drafts for patch mode
 //! - `diff`: Diff domain types and helper functions
 //! - `keymap`: Key-to-action mapping per context with help text
+//! - `reminder`: Reminders for agents left waiting
 //! - `settings`: Tmux-persisted dashboard settings
+//! - `share`: Read-only view served over a Unix socket (`workmux share`)
 //! - `sort`: Sort mode enum and tmux persistence
 //! - `spinner`: Spinner animation constants
 //! - `ui/`: TUI rendering modules
 //!   - `dashboard`: Table, preview, and footer
-//!   - `diff`: Normal diff, patch mode, file list
+//!   - `diff`: Normal diff, patch mode, file list, blame panel
 //!   - `format`: Git status formatting
 //!   - `help`: Help overlay
 
 mod actions;
-mod agent;
+pub mod agent;
 mod ansi;
 mod app;
+mod blame;
+mod comment;
+mod confirm;
 mod diff;
 mod diff_ops;
+mod fs_watch;
 mod keymap;
+mod preview;
+mod reminder;
 mod settings;
+pub mod share;
 mod sort;
 mod spinner;
 mod ui;
+pub mod watch;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseEventKind},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -48,6 +63,7 @@ use std::time::Duration;
 use crate::github;
 use crate::vcs;
 use crate::multiplexer::{create_backend, detect_backend};
+use crate::state::StateStore;
 
 use self::actions::apply_action;
 use self::app::{App, ViewMode};
@@ -58,6 +74,9 @@ use self::ui::ui;
 
 /// Determine the current keymap context based on app state.
 fn get_context(app: &App) -> Context {
+    if app.confirm.is_some() {
+        return Context::Confirm;
+    }
     match &app.view_mode {
         ViewMode::Dashboard => {
             if app.input_mode {
@@ -106,7 +125,7 @@ fn handle_mouse_event(app: &mut App, kind: MouseEventKind) {
     }
 }
 
-pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
+pub fn run(cli_preview_size: Option<u8>, open_diff: bool, allow_main: bool) -> Result<()> {
     let mux = create_backend(detect_backend());
 
     // Check if multiplexer is running
@@ -118,13 +137,20 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
     // Create app state
     let mut app = App::new(mux)?;
 
+    app.allow_main = allow_main;
+
     // CLI preview size overrides config/tmux if provided
     if let Some(size) = cli_preview_size {
         app.preview_size = size;
@@ -142,7 +168,17 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
     // Main loop
     let tick_rate = Duration::from_millis(250);
     let mut last_tick = std::time::Instant::now();
-    let refresh_interval = Duration::from_secs(2);
+    // Status changes arrive through the state watcher; polling still picks
+    // up panes that closed without updating their state
+    let state_watcher = StateStore::new().and_then(|store| store.watch());
+    if let Err(e) = &state_watcher {
+        tracing::warn!(error = %e, "dashboard:state watcher unavailable, polling instead");
+    }
+    let refresh_interval = if state_watcher.is_ok() {
+        Duration::from_secs(5)
+    } else {
+        Duration::from_secs(2)
+    };
     let mut last_refresh = std::time::Instant::now();
     // Preview refreshes more frequently than the agent list
     // Use a faster refresh rate when in input mode for responsive typing feedback
@@ -173,17 +209,30 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
                 continue;
             }
 
+            if let Event::Paste(text) = event {
+                if !app.show_help && app.history.is_none() {
+                    handle_paste(&mut app, text);
+                }
+                continue;
+            }
+
             // Handle key events
             let Event::Key(key) = event else { continue };
             if key.kind != KeyEventKind::Press {
                 continue;
             }
 
+            // Any key dismisses a pending notice
+            app.notice = None;
+
             // Help overlay handling - close on any key if open
             if app.show_help {
                 app.show_help = false;
                 continue;
             }
+            if app.history.take().is_some() {
+                continue;
+            }
 
             // Get current context and map key to action
             let ctx = get_context(&app);
@@ -205,16 +254,25 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
                     last_preview_refresh = std::time::Instant::now();
                 }
             }
+
+            if std::mem::take(&mut app.external_comment_edit) {
+                edit_comment_externally(&mut terminal, &mut app)?;
+            }
         }
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = std::time::Instant::now();
             // Advance spinner animation frame (wrap at frame count to avoid skip artifact)
             app.spinner_frame = (app.spinner_frame + 1) % SPINNER_FRAME_COUNT;
+            app.check_file_changes();
         }
 
-        // Auto-refresh agent list every 2 seconds
-        if last_refresh.elapsed() >= refresh_interval {
+        // Refresh the agent list when another process changed agent state,
+        // and on the fallback interval
+        let state_changed = state_watcher
+            .as_ref()
+            .is_ok_and(|watcher| watcher.has_changed());
+        if state_changed || last_refresh.elapsed() >= refresh_interval {
             app.refresh();
             last_refresh = std::time::Instant::now();
         }
@@ -244,9 +302,80 @@ pub fn run(cli_preview_size: Option<u8>, open_diff: bool) -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
     Ok(())
 }
+
+/// Handle pasted text: the comment editor takes it whole, elsewhere it is
+/// handled like the keys it was typed with.
+fn handle_paste(app: &mut App, text: String) {
+    let ctx = get_context(app);
+    if ctx == Context::Comment {
+        apply_action(
+            app,
+            actions::Action::EditComment(comment::Edit::Paste(text)),
+        );
+        return;
+    }
+    if ctx == Context::DashboardInput {
+        apply_action(app, actions::Action::SendPaste(text));
+        return;
+    }
+    for c in text.chars() {
+        let code = match c {
+            '\r' | '\n' => KeyCode::Enter,
+            '\t' => KeyCode::Tab,
+            c => KeyCode::Char(c),
+        };
+        if let Some(action) = action_for_key(ctx, KeyEvent::from(code)) {
+            apply_action(app, action);
+        }
+    }
+}
+
+/// Suspend the TUI and edit the open comment in $EDITOR.
+fn edit_comment_externally(
+    terminal: &mut ratatui::Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> Result<()> {
+    let ViewMode::Diff(ref diff) = app.view_mode else {
+        return Ok(());
+    };
+    let Some(ref editor) = diff.comment_input else {
+        return Ok(());
+    };
+    let text = editor.text();
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+    let edited = comment::edit_externally(&text);
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+
+    match edited {
+        Ok(text) => app.set_comment_text(&text),
+        Err(e) => {
+            if let ViewMode::Diff(ref mut diff) = app.view_mode
+                && let Some(ref mut editor) = diff.comment_input
+            {
+                editor.error = Some(format!("{:#}", e));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! Scrollback cache for the dashboard's live preview.
+//!
+//! The preview is refreshed every few hundred milliseconds, so only the
+//! scrollback added since the last refresh is captured and appended here.
+//! Older history is loaded on demand when the user scrolls to the top.
+
+use std::collections::VecDeque;
+
+use crate::multiplexer::PaneCapture;
+
+/// Scrollback lines kept for the preview until the user scrolls up for more
+const PREVIEW_LINES: u16 = 200;
+
+/// Scrollback lines added each time the user scrolls past the top
+const PREVIEW_LINES_STEP: u16 = 1000;
+
+/// Upper bound on the scrollback kept for the preview
+const PREVIEW_LINES_MAX: u16 = 10_000;
+
+/// Cached scrollback and screen of the selected agent's pane.
+#[derive(Debug)]
+pub struct PreviewBuffer {
+    history: VecDeque<String>,
+    screen: Vec<String>,
+    /// History size reported by the last capture, to request only what's new
+    history_size: Option<usize>,
+    /// How many scrollback lines to keep
+    depth: u16,
+}
+
+impl Default for PreviewBuffer {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::new(),
+            screen: Vec::new(),
+            history_size: None,
+            depth: PREVIEW_LINES,
+        }
+    }
+}
+
+impl PreviewBuffer {
+    /// `since` argument for the next incremental capture.
+    pub fn history_size(&self) -> Option<usize> {
+        self.history_size
+    }
+
+    /// Number of scrollback lines to request on a full capture.
+    pub fn depth(&self) -> u16 {
+        self.depth
+    }
+
+    /// Number of cached scrollback lines.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Merge a capture into the cache. Returns how many lines were dropped
+    /// from the top, so a scroll offset can be kept on the same content.
+    pub fn apply(&mut self, capture: PaneCapture) -> usize {
+        if capture.reset {
+            self.history.clear();
+        }
+        self.history.extend(capture.history);
+        self.screen = capture.screen;
+        self.history_size = capture.history_size;
+
+        let excess = self.history.len().saturating_sub(self.history_limit());
+        self.history.drain(..excess);
+        if capture.reset { 0 } else { excess }
+    }
+
+    /// Scrollback lines to keep. Backends that can't separate scrollback from
+    /// the screen return everything as screen, so count it against the depth.
+    fn history_limit(&self) -> usize {
+        if self.history_size.is_some() {
+            self.depth as usize
+        } else {
+            (self.depth as usize).saturating_sub(self.screen.len())
+        }
+    }
+
+    /// Whether the pane may have more history than is cached.
+    pub fn can_deepen(&self) -> bool {
+        self.depth < PREVIEW_LINES_MAX
+            && self.history.len() + self.screen.len() >= self.depth as usize
+    }
+
+    /// Request more history and force the next capture to be a full one.
+    pub fn deepen(&mut self) {
+        self.depth = self
+            .depth
+            .saturating_add(PREVIEW_LINES_STEP)
+            .min(PREVIEW_LINES_MAX);
+        self.history_size = None;
+    }
+
+    /// Preview text: cached scrollback followed by the screen.
+    pub fn render(&self) -> String {
+        let mut lines: Vec<&str> = self.history.iter().map(String::as_str).collect();
+        lines.extend(self.screen.iter().map(String::as_str));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("line {}", i)).collect()
+    }
+
+    fn capture(history: Vec<String>, reset: bool, history_size: usize) -> PaneCapture {
+        PaneCapture {
+            history,
+            screen: vec!["$ ".to_string()],
+            reset,
+            history_size: Some(history_size),
+        }
+    }
+
+    #[test]
+    fn incremental_captures_append_to_history() {
+        let mut buffer = PreviewBuffer::default();
+        assert_eq!(buffer.apply(capture(lines(0..3), true, 3)), 0);
+        assert_eq!(buffer.apply(capture(lines(3..5), false, 5)), 0);
+        assert_eq!(buffer.history_size(), Some(5));
+        assert_eq!(
+            buffer.render(),
+            "line 0\nline 1\nline 2\nline 3\nline 4\n$ "
+        );
+    }
+
+    #[test]
+    fn history_is_trimmed_to_depth() {
+        let mut buffer = PreviewBuffer::default();
+        let depth = PREVIEW_LINES as usize;
+        buffer.apply(capture(lines(0..depth), true, depth));
+        assert_eq!(
+            buffer.apply(capture(lines(depth..depth + 5), false, depth + 5)),
+            5
+        );
+        assert_eq!(buffer.history_len(), depth);
+        assert!(buffer.render().starts_with("line 5\n"));
+
+        // A reset replaces the history, so nothing shifts relative to it
+        assert_eq!(
+            buffer.apply(capture(lines(0..depth + 5), true, depth + 5)),
+            0
+        );
+        assert_eq!(buffer.history_len(), depth);
+    }
+
+    #[test]
+    fn deepen_forces_full_capture_until_history_runs_out() {
+        let mut buffer = PreviewBuffer::default();
+        buffer.apply(capture(lines(0..PREVIEW_LINES as usize), true, 5000));
+        assert!(buffer.can_deepen());
+
+        buffer.deepen();
+        assert_eq!(buffer.depth(), PREVIEW_LINES + PREVIEW_LINES_STEP);
+        assert_eq!(buffer.history_size(), None);
+
+        // The pane had less history than requested
+        buffer.apply(capture(lines(0..600), true, 600));
+        assert!(!buffer.can_deepen());
+    }
+
+    #[test]
+    fn screen_only_captures_count_against_depth() {
+        let mut buffer = PreviewBuffer::default();
+        buffer.apply(PaneCapture {
+            screen: lines(0..PREVIEW_LINES as usize),
+            reset: true,
+            ..Default::default()
+        });
+        assert_eq!(buffer.history_len(), 0);
+        assert!(buffer.can_deepen());
+    }
+}
@@ -0,0 +1,243 @@
+//! Reminders for agents left waiting for input.
+//!
+//! Once an agent has been `waiting` for `dashboard.remind_after` minutes the
+//! dashboard reminds you, then again every `dashboard.remind_every` minutes
+//! until the reminder is acknowledged or the agent's status changes. Each
+//! repeat escalates: the first reminder only shows in the dashboard, the
+//! second also raises a desktop notification, and later ones ring the bell.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::config::DashboardConfig;
+use crate::multiplexer::{AgentPane, AgentStatus};
+
+/// When and how to remind, from the dashboard config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReminderSettings {
+    /// Seconds of waiting before the first reminder
+    pub after_secs: u64,
+    /// Seconds between repeats
+    pub every_secs: u64,
+    /// Raise a desktop notification from the second reminder on
+    pub notify: bool,
+    /// Ring the terminal bell from the third reminder on
+    pub bell: bool,
+}
+
+impl ReminderSettings {
+    /// `None` when reminders are off (`remind_after` unset or 0).
+    pub fn from_config(config: &DashboardConfig) -> Option<Self> {
+        let after = config.remind_after().filter(|&minutes| minutes > 0)?;
+        Some(Self {
+            after_secs: after * 60,
+            every_secs: config.remind_every().unwrap_or(after).max(1) * 60,
+            notify: config.remind_notify(),
+            bell: config.remind_bell(),
+        })
+    }
+}
+
+/// A reminder that fell due.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Due {
+    pub pane_id: String,
+    /// 1 for the first reminder, then one more per repeat
+    pub count: u32,
+    pub waiting_secs: u64,
+}
+
+/// Reminder state of one waiting agent.
+#[derive(Debug)]
+struct Reminder {
+    /// Status timestamp the reminders are for; a new one starts over
+    status_ts: u64,
+    sent: u32,
+    acknowledged: bool,
+}
+
+/// Reminders of the agents the dashboard shows, by pane id.
+#[derive(Debug, Default)]
+pub struct Reminders {
+    by_pane: HashMap<String, Reminder>,
+}
+
+impl Reminders {
+    /// Reminders falling due at `now` for the waiting `agents`.
+    ///
+    /// A dashboard opened on an agent that has been waiting for hours sends
+    /// a single reminder, already escalated.
+    pub fn check(
+        &mut self,
+        agents: &[AgentPane],
+        settings: &ReminderSettings,
+        now: u64,
+    ) -> Vec<Due> {
+        let mut due = Vec::new();
+        let mut waiting = HashMap::new();
+        for agent in agents {
+            let (Some(AgentStatus::Waiting), Some(status_ts)) = (agent.status, agent.status_ts)
+            else {
+                continue;
+            };
+            let mut reminder = self
+                .by_pane
+                .remove(&agent.pane_id)
+                .filter(|r| r.status_ts == status_ts)
+                .unwrap_or(Reminder {
+                    status_ts,
+                    sent: 0,
+                    acknowledged: false,
+                });
+            let waiting_secs = now.saturating_sub(status_ts);
+            let count = reminders_due(waiting_secs, settings);
+            if count > reminder.sent && !reminder.acknowledged {
+                reminder.sent = count;
+                due.push(Due {
+                    pane_id: agent.pane_id.clone(),
+                    count,
+                    waiting_secs,
+                });
+            }
+            waiting.insert(agent.pane_id.clone(), reminder);
+        }
+        // Agents that stopped waiting (or are gone) start over next time
+        self.by_pane = waiting;
+        due
+    }
+
+    /// Whether the agent has been reminded about and not acknowledged yet.
+    pub fn is_active(&self, pane_id: &str) -> bool {
+        self.by_pane
+            .get(pane_id)
+            .is_some_and(|r| r.sent > 0 && !r.acknowledged)
+    }
+
+    /// Stop reminding about every agent reminded about so far, until its
+    /// status changes. Returns how many were acknowledged.
+    pub fn acknowledge_all(&mut self) -> usize {
+        let mut count = 0;
+        for reminder in self.by_pane.values_mut() {
+            if reminder.sent > 0 && !reminder.acknowledged {
+                reminder.acknowledged = true;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Number of reminders due after waiting `waiting_secs`.
+fn reminders_due(waiting_secs: u64, settings: &ReminderSettings) -> u32 {
+    if waiting_secs < settings.after_secs {
+        return 0;
+    }
+    let repeats = (waiting_secs - settings.after_secs) / settings.every_secs;
+    u32::try_from(repeats).unwrap_or(u32::MAX).saturating_add(1)
+}
+
+/// Ring the terminal bell. tmux flags the dashboard's window in the status
+/// line until it is visited.
+pub fn ring_bell() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn settings() -> ReminderSettings {
+        ReminderSettings {
+            after_secs: 600,
+            every_secs: 300,
+            notify: true,
+            bell: false,
+        }
+    }
+
+    fn agent(pane_id: &str, status: AgentStatus, status_ts: u64) -> AgentPane {
+        AgentPane {
+            session: "main".to_string(),
+            window_name: "wm-x".to_string(),
+            pane_id: pane_id.to_string(),
+            path: PathBuf::from("/tmp/wt"),
+            pane_title: None,
+            status: Some(status),
+            status_ts: Some(status_ts),
+            progress: None,
+            task_progress: None,
+            denied_exec: None,
+            net_activity: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn reminders_repeat_until_acknowledged() {
+        let mut reminders = Reminders::default();
+        let agents = [agent("%1", AgentStatus::Waiting, 1000)];
+
+        assert!(reminders.check(&agents, &settings(), 1599).is_empty());
+        let due = reminders.check(&agents, &settings(), 1600);
+        assert_eq!(
+            due,
+            [Due {
+                pane_id: "%1".to_string(),
+                count: 1,
+                waiting_secs: 600
+            }]
+        );
+        assert!(reminders.is_active("%1"));
+        // Nothing new until the next repeat
+        assert!(reminders.check(&agents, &settings(), 1899).is_empty());
+        assert_eq!(reminders.check(&agents, &settings(), 1900)[0].count, 2);
+
+        assert_eq!(reminders.acknowledge_all(), 1);
+        assert!(!reminders.is_active("%1"));
+        assert!(reminders.check(&agents, &settings(), 5000).is_empty());
+    }
+
+    #[test]
+    fn a_new_status_starts_over() {
+        let mut reminders = Reminders::default();
+        let waiting = [agent("%1", AgentStatus::Waiting, 1000)];
+        assert_eq!(reminders.check(&waiting, &settings(), 1600).len(), 1);
+        reminders.acknowledge_all();
+
+        // Working agents are never reminded about, and forget acknowledgements
+        let working = [agent("%1", AgentStatus::Working, 2000)];
+        assert!(reminders.check(&working, &settings(), 9000).is_empty());
+        assert!(!reminders.is_active("%1"));
+
+        let waiting = [agent("%1", AgentStatus::Waiting, 3000)];
+        assert_eq!(reminders.check(&waiting, &settings(), 3600)[0].count, 1);
+    }
+
+    #[test]
+    fn long_waits_send_one_escalated_reminder() {
+        let mut reminders = Reminders::default();
+        let agents = [agent("%1", AgentStatus::Waiting, 0)];
+        let due = reminders.check(&agents, &settings(), 600 + 3 * 300 + 10);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].count, 4);
+    }
+
+    #[test]
+    fn settings_come_from_the_dashboard_config() {
+        assert_eq!(
+            ReminderSettings::from_config(&DashboardConfig::default()),
+            None
+        );
+        let config = DashboardConfig {
+            remind_after: Some(10),
+            ..DashboardConfig::default()
+        };
+        let settings = ReminderSettings::from_config(&config).unwrap();
+        assert_eq!((settings.after_secs, settings.every_secs), (600, 600));
+        assert!(settings.notify);
+        assert!(!settings.bell);
+    }
+}
@@ -1,5 +1,7 @@
 //! Dashboard settings persistence using StateStore.
 
+use std::collections::BTreeSet;
+
 use crate::state::StateStore;
 
 /// Load hide_stale filter state from StateStore.
@@ -59,3 +61,29 @@ pub fn save_last_pane_id(pane_id: &str) {
         let _ = store.save_settings(&settings);
     }
 }
+
+/// Load the pinned agent pane_ids of a multiplexer instance from StateStore.
+pub fn load_pinned_panes(instance: &str) -> BTreeSet<String> {
+    StateStore::new()
+        .ok()
+        .and_then(|store| store.load_settings().ok())
+        .and_then(|mut s| s.pinned_panes.remove(instance))
+        .map(|panes| panes.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Save the pinned agent pane_ids of a multiplexer instance to StateStore.
+pub fn save_pinned_panes(instance: &str, panes: &BTreeSet<String>) {
+    if let Ok(store) = StateStore::new()
+        && let Ok(mut settings) = store.load_settings()
+    {
+        if panes.is_empty() {
+            settings.pinned_panes.remove(instance);
+        } else {
+            settings
+                .pinned_panes
+                .insert(instance.to_string(), panes.iter().cloned().collect());
+        }
+        let _ = store.save_settings(&settings);
+    }
+}
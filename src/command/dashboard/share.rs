@@ -0,0 +1,260 @@
+//! Read-only view of the dashboard served over a Unix socket.
+//!
+//! `workmux share` renders the agent table to plain text and streams it to
+//! every client connected to the socket. Clients never get a terminal or an
+//! input channel, so a reviewer can watch agents (e.g. `nc -U <socket>` over
+//! SSH with a forced command) without any access to the machine itself.
+
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use tabled::{
+    Table, Tabled,
+    settings::{Padding, Style, object::Columns},
+};
+
+use super::agent::{
+    activity, elapsed_secs, extract_project_name, extract_worktree_name, format_duration,
+    status_name,
+};
+use crate::config::Config;
+use crate::multiplexer::{AgentPane, create_backend, detect_backend};
+use crate::state::StateStore;
+
+/// Environment variable holding the socket path for `--exec` commands.
+const SOCKET_ENV: &str = "WORKMUX_SHARE_SOCKET";
+
+#[derive(Tabled)]
+struct ShareRow {
+    #[tabled(rename = "PROJECT")]
+    project: String,
+    #[tabled(rename = "WORKTREE")]
+    worktree: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "ELAPSED")]
+    elapsed: String,
+    #[tabled(rename = "ACTIVITY")]
+    activity: String,
+}
+
+pub fn run(socket: Option<PathBuf>, interval_secs: u64, exec: Option<&str>) -> Result<()> {
+    let mux = create_backend(detect_backend());
+    if !mux.is_running().unwrap_or(false) {
+        bail!("No {} server running", mux.name());
+    }
+    let config = Config::load(None).unwrap_or_default();
+    let interval = Duration::from_secs(interval_secs.max(1));
+
+    let socket = socket.unwrap_or_else(|| {
+        std::env::temp_dir().join(format!("workmux-share-{}.sock", std::process::id()))
+    });
+    remove_stale_socket(&socket)?;
+    let listener = UnixListener::bind(&socket)
+        .with_context(|| format!("Failed to bind {}", socket.display()))?;
+    // Only the owner can connect; remote viewers come in through SSH as the owner
+    fs::set_permissions(&socket, fs::Permissions::from_mode(0o600))?;
+
+    let cleanup_path = socket.clone();
+    let _ = ctrlc::set_handler(move || {
+        let _ = fs::remove_file(&cleanup_path);
+        std::process::exit(0);
+    });
+
+    let frame = Arc::new(Mutex::new(String::new()));
+    {
+        let frame = Arc::clone(&frame);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let frame = Arc::clone(&frame);
+                thread::spawn(move || serve_client(stream, &frame, interval));
+            }
+        });
+    }
+
+    println!("Sharing a read-only dashboard on {}", socket.display());
+    println!("  Watch locally:  nc -U {}", socket.display());
+    println!(
+        "  Watch remotely: ssh -t <you>@<host> nc -U {}",
+        socket.display()
+    );
+    let mut child = match exec {
+        Some(cmd) => {
+            println!("  Running: {}", cmd);
+            Some(
+                Command::new("sh")
+                    .args(["-c", cmd])
+                    .env(SOCKET_ENV, &socket)
+                    .spawn()
+                    .context("Failed to start --exec command")?,
+            )
+        }
+        None => {
+            println!("Press Ctrl-C to stop sharing");
+            None
+        }
+    };
+
+    loop {
+        let agents = StateStore::new()
+            .and_then(|store| store.load_reconciled_agents(mux.as_ref()))
+            .unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        *frame.lock().unwrap() = render_frame(&agents, config.window_prefix(), now);
+
+        if let Some(child) = child.as_mut()
+            && child.try_wait()?.is_some()
+        {
+            break;
+        }
+        thread::sleep(interval);
+    }
+
+    let _ = fs::remove_file(&socket);
+    println!("Stopped sharing");
+    Ok(())
+}
+
+/// Remove a socket left behind by a share that didn't shut down cleanly.
+fn remove_stale_socket(socket: &Path) -> Result<()> {
+    if !socket.exists() {
+        return Ok(());
+    }
+    if UnixStream::connect(socket).is_ok() {
+        bail!("{} is already being shared", socket.display());
+    }
+    fs::remove_file(socket).with_context(|| format!("Failed to remove {}", socket.display()))
+}
+
+/// Send the latest frame until the client disconnects. Input is never read.
+fn serve_client(mut stream: UnixStream, frame: &Mutex<String>, interval: Duration) {
+    loop {
+        let current = frame.lock().unwrap().clone();
+        if stream.write_all(current.as_bytes()).is_err() {
+            return;
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Render the agent table as a full-screen redraw.
+fn render_frame(agents: &[AgentPane], window_prefix: &str, now: u64) -> String {
+    let mut rows: Vec<ShareRow> = agents
+        .iter()
+        .map(|agent| {
+            let (worktree, _) =
+                extract_worktree_name(&agent.session, &agent.window_name, window_prefix);
+            let activity = activity(agent).unwrap_or_else(|| "-".to_string());
+            ShareRow {
+                project: extract_project_name(&agent.path),
+                worktree,
+                status: agent.status.map_or("-", status_name).to_string(),
+                elapsed: elapsed_secs(agent.status_ts, now)
+                    .map(format_duration)
+                    .unwrap_or_else(|| "-".to_string()),
+                activity,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.project, &a.worktree).cmp(&(&b.project, &b.worktree)));
+
+    let body = if rows.is_empty() {
+        "No active agents".to_string()
+    } else {
+        Table::new(rows)
+            .with(Style::blank())
+            .modify(Columns::new(..), Padding::new(0, 1, 0, 0))
+            .to_string()
+    };
+
+    // Home the cursor and clear each line as it is overwritten, so viewers
+    // don't see a blank screen between updates
+    let mut out = String::from("\x1b[H");
+    for line in std::iter::once("workmux (read-only)")
+        .chain(std::iter::once(""))
+        .chain(body.lines())
+    {
+        out.push_str(line);
+        out.push_str("\x1b[K\r\n");
+    }
+    out.push_str("\x1b[J");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multiplexer::{AgentProgress, AgentStatus};
+
+    fn agent(window: &str, path: &str, status: AgentStatus) -> AgentPane {
+        AgentPane {
+            session: "main".to_string(),
+            window_name: window.to_string(),
+            pane_id: "%1".to_string(),
+            path: PathBuf::from(path),
+            pane_title: Some("Fix login".to_string()),
+            status: Some(status),
+            status_ts: Some(1_000),
+            progress: None,
+            task_progress: None,
+            denied_exec: None,
+            net_activity: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn render_frame_lists_agents_sorted() {
+        let mut busy = agent("wm-auth", "/code/web__worktrees/auth", AgentStatus::Working);
+        busy.progress = Some(AgentProgress {
+            tool: Some("bash: cargo test".to_string()),
+            ..Default::default()
+        });
+        let agents = vec![
+            busy,
+            agent("wm-api", "/code/api__worktrees/api", AgentStatus::Done),
+        ];
+        let frame = render_frame(&agents, "wm-", 1_065);
+        let text = String::from_utf8(strip_ansi_escapes::strip(&frame)).unwrap();
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+
+        assert_eq!(lines[0], "workmux (read-only)");
+        assert!(lines[2].starts_with("PROJECT"));
+        assert!(lines[3].starts_with("api"));
+        assert!(lines[3].contains("done"));
+        assert!(lines[3].contains("Fix login"));
+        assert!(lines[4].starts_with("web"));
+        assert!(lines[4].contains("00:01:05"));
+        assert!(lines[4].contains("bash: cargo test"));
+    }
+
+    #[test]
+    fn render_frame_without_agents() {
+        let frame = render_frame(&[], "wm-", 0);
+        assert!(frame.contains("No active agents"));
+    }
+
+    #[test]
+    fn remove_stale_socket_clears_dead_socket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("share.sock");
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        remove_stale_socket(&path).unwrap();
+        assert!(!path.exists());
+
+        let _live = UnixListener::bind(&path).unwrap();
+        assert!(remove_stale_socket(&path).is_err());
+    }
+}
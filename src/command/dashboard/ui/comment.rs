@@ -0,0 +1,92 @@
+//! Comment editor overlay rendering.
+
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::super::comment::CommentEditor;
+use super::theme::ThemePalette;
+
+/// Render the comment editor centered over the patch view.
+pub fn render_comment_editor(f: &mut Frame, editor: &CommentEditor, palette: &ThemePalette) {
+    let area = f.area();
+    let width = (area.width * 7 / 10).max(50).min(area.width);
+    let extra_rows = u16::from(editor.error.is_some());
+    // At least five lines of text, growing with the comment
+    let text_rows = (editor.lines().len() as u16).clamp(5, area.height.saturating_sub(6).max(1));
+    let height = (text_rows + extra_rows + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::bordered()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(Style::default().fg(palette.help_border))
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("Comment on {}", editor.location),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+        ]))
+        .title_bottom(Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Enter", Style::default().fg(Color::Yellow)),
+            Span::styled(" send · ", Style::default().fg(palette.help_muted)),
+            Span::styled("Alt+Enter", Style::default().fg(Color::Yellow)),
+            Span::styled(" new line · ", Style::default().fg(palette.help_muted)),
+            Span::styled("Ctrl+g", Style::default().fg(Color::Yellow)),
+            Span::styled(" $EDITOR · ", Style::default().fg(palette.help_muted)),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::styled(" close ", Style::default().fg(palette.help_muted)),
+        ]));
+    let inner = block.inner(popup_area);
+
+    // Scroll so the cursor stays visible
+    let (row, col) = editor.cursor();
+    let visible_rows = usize::from(text_rows.min(inner.height));
+    let top = row.saturating_sub(visible_rows.saturating_sub(1));
+    let left = col.saturating_sub(usize::from(inner.width.saturating_sub(1)));
+
+    let mut lines: Vec<Line> = if editor.text().is_empty() {
+        vec![Line::from(Span::styled(
+            "Type your comment...",
+            Style::default().fg(palette.dimmed),
+        ))]
+    } else {
+        editor
+            .lines()
+            .iter()
+            .skip(top)
+            .take(visible_rows)
+            .map(|line| {
+                let visible: String = line.chars().skip(left).collect();
+                Line::from(Span::styled(visible, Style::default().fg(palette.text)))
+            })
+            .collect()
+    };
+    if let Some(error) = &editor.error {
+        lines.resize(visible_rows, Line::from(""));
+        lines.push(Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+    f.set_cursor_position(Position::new(
+        inner.x + (col - left) as u16,
+        inner.y + (row - top) as u16,
+    ));
+}
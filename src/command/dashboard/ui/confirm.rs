@@ -0,0 +1,101 @@
+//! Confirmation modal rendering.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::super::confirm::{Confirmation, PendingAction};
+use super::theme::ThemePalette;
+
+/// Render a confirmation modal centered over the current view.
+pub fn render_confirm(f: &mut Frame, confirm: &Confirmation, palette: &ThemePalette) {
+    let label_width = confirm
+        .details
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+
+    // Empty line at top for padding
+    let mut lines = vec![Line::from("")];
+    lines.extend(confirm.details.iter().map(|(label, value)| {
+        Line::from(vec![
+            Span::styled(
+                format!(" {:>width$}", label, width = label_width),
+                Style::default().fg(palette.dimmed),
+            ),
+            Span::styled(" · ", Style::default().fg(palette.help_muted)),
+            Span::styled(value.as_str(), Style::default().fg(palette.text)),
+        ])
+    }));
+    if let Some(warning) = &confirm.warning {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" {}", warning),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let title_width = confirm.title.len() as u16 + 2;
+    // +4 for borders and right padding
+    let width = content_width.max(title_width).max(34) + 4;
+    let height = lines.len() as u16 + 3; // +3 for borders and empty line at bottom
+
+    let area = f.area();
+    let popup_area = Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+
+    let block = Block::bordered()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(Style::default().fg(palette.help_border))
+        .title(Line::from(vec![
+            Span::styled(" ", Style::default()),
+            Span::styled(
+                confirm.title.as_str(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" ", Style::default()),
+        ]))
+        .title_bottom(Line::from(hints(confirm.action, palette)));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+/// Key hints below the modal. Host command requests can also be denied;
+/// cancelling leaves them waiting.
+fn hints(action: PendingAction, palette: &ThemePalette) -> Vec<Span<'static>> {
+    let key = Style::default().fg(Color::Yellow);
+    let muted = Style::default().fg(palette.help_muted);
+    match action {
+        PendingAction::ApproveExec(_) => vec![
+            Span::styled(" ", Style::default()),
+            Span::styled("y/Enter", key),
+            Span::styled(" approve · ", muted),
+            Span::styled("d", key),
+            Span::styled(" deny · ", muted),
+            Span::styled("n/Esc", key),
+            Span::styled(" later ", muted),
+        ],
+        PendingAction::MergeSelected | PendingAction::MergeDiff => vec![
+            Span::styled(" ", Style::default()),
+            Span::styled("y/Enter", key),
+            Span::styled(" confirm · ", muted),
+            Span::styled("n/Esc", key),
+            Span::styled(" cancel ", muted),
+        ],
+    }
+}
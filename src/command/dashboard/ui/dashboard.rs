@@ -10,10 +10,18 @@ use ratatui::{
 };
 use std::collections::{BTreeMap, HashSet};
 
+use crate::multiplexer::AgentStatus;
+
 use super::super::app::App;
 use super::super::spinner::SPINNER_FRAMES;
 use super::format::{format_git_status, format_pr_status};
 
+/// Cells in the Progress column's bar
+const PROGRESS_BAR_WIDTH: usize = 5;
+
+/// Characters of a host command request shown next to the worktree name
+const EXEC_REQUEST_CHARS: usize = 24;
+
 /// Render the dashboard view (table + preview + footer).
 pub fn render_dashboard(f: &mut Frame, app: &mut App) {
     let area = f.area();
@@ -35,7 +43,12 @@ pub fn render_dashboard(f: &mut Frame, app: &mut App) {
     render_preview(f, app, chunks[1]);
 
     // Footer - show different help based on mode
-    let footer_text = if app.input_mode {
+    let footer_text = if let Some(ref notice) = app.notice {
+        Paragraph::new(Line::from(Span::styled(
+            format!("  {}", notice),
+            Style::default().fg(Color::Yellow),
+        )))
+    } else if app.input_mode {
         Paragraph::new(Line::from(vec![
             Span::styled(
                 "  INPUT MODE",
@@ -43,8 +56,8 @@ pub fn render_dashboard(f: &mut Frame, app: &mut App) {
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" - Type to send keys to agent  "),
-            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" - keys, Ctrl/Alt combos and paste go to the agent  "),
+            Span::styled("[Ctrl+]]", Style::default().fg(Color::Yellow)),
             Span::raw(" exit"),
         ]))
     } else {
@@ -95,6 +108,17 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     // Check if we should show the PR column (only when at least one agent has a PR)
     let show_pr_column = app.has_any_pr();
     let show_check_counts = app.config.dashboard.show_check_counts();
+    // Only when at least one agent reported task progress
+    let show_progress_column = app.agents.iter().any(|a| a.task_progress.is_some());
+    // Only when a sandbox is reporting network activity
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let show_net_column = app
+        .agents
+        .iter()
+        .any(|a| a.net_activity.as_ref().is_some_and(|n| !n.is_stale(now)));
 
     // Check if git data is being refreshed
     let is_git_fetching = app
@@ -135,6 +159,14 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         header_cells.push(Cell::from(pr_header));
     }
 
+    if show_progress_column {
+        header_cells.push(Cell::from("Progress").style(header_style));
+    }
+
+    if show_net_column {
+        header_cells.push(Cell::from("Net").style(header_style));
+    }
+
     header_cells.extend(vec![
         Cell::from("Status").style(header_style),
         Cell::from("Time").style(header_style),
@@ -198,17 +230,117 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
                     agent.path == *cwd
                 }
             });
-            let worktree_display = format!("{}{}", worktree_name, pane_suffix);
+            // Session mode with several agents: show the session's worst-of status
+            let rollup_suffix = app
+                .session_rollup_icon(agent)
+                .map(|icon| format!(" ({})", icon))
+                .unwrap_or_default();
+            // Member of a worktree group: show the group and its worst-of status
+            let group_suffix = app
+                .group_label(agent)
+                .map(|label| format!(" ({})", label))
+                .unwrap_or_default();
+            // Sandboxed agent tried to run a host command it isn't allowed to
+            let denied_suffix = agent
+                .denied_exec
+                .as_ref()
+                .map(|denied| match denied.count {
+                    1 => format!(" [⚠ {}]", denied.command),
+                    count => format!(" [⚠ {} ×{}]", denied.command, count),
+                })
+                .unwrap_or_default();
+            // Sandboxed agent waiting for approval of a host command (`x`)
+            let request_suffix = app
+                .exec_requests
+                .get(&agent.pane_id)
+                .map(|request| {
+                    let line = request.command_line();
+                    if line.chars().count() > EXEC_REQUEST_CHARS {
+                        let cut: String = line.chars().take(EXEC_REQUEST_CHARS - 1).collect();
+                        format!(" [? {}…]", cut)
+                    } else {
+                        format!(" [? {}]", line)
+                    }
+                })
+                .unwrap_or_default();
+            // Worktree deleted outside workmux (recover with `workmux reattach`)
+            let orphan_suffix = if app.orphaned.contains(&agent.pane_id) {
+                " [worktree deleted]"
+            } else {
+                ""
+            };
+            let locked_suffix = if app.locked.contains(&agent.pane_id) {
+                " [locked]"
+            } else {
+                ""
+            };
+            let pin_prefix = if app.pinned.contains(&agent.pane_id) {
+                "📌 "
+            } else {
+                ""
+            };
+            let worktree_display = format!(
+                "{}{}{}{}{}{}{}{}{}",
+                pin_prefix,
+                worktree_name,
+                pane_suffix,
+                rollup_suffix,
+                group_suffix,
+                denied_suffix,
+                request_suffix,
+                locked_suffix,
+                orphan_suffix
+            );
             let title = agent
                 .pane_title
                 .as_ref()
                 .map(|t| t.strip_prefix("... ").unwrap_or(t).to_string())
                 .unwrap_or_default();
+            // "Currently doing X", shown ahead of the title. A message the
+            // agent reported itself wins over one pulled from its server,
+            // which wins over the command its process is busy running.
+            let activity = agent
+                .task_progress
+                .as_ref()
+                .and_then(|p| p.message.clone())
+                .or_else(|| agent.progress.as_ref().and_then(|p| p.summary()))
+                .or_else(|| {
+                    let child = agent.process.as_ref()?.child.as_ref()?;
+                    (agent.status == Some(AgentStatus::Working))
+                        .then(|| format!("Running {}", child))
+                });
+            let task_progress = agent
+                .task_progress
+                .as_ref()
+                .map(|p| p.bar(PROGRESS_BAR_WIDTH));
+            // Recent transfer rates, and tunnels opened through the proxy
+            let net = agent
+                .net_activity
+                .as_ref()
+                .filter(|n| !n.is_stale(now))
+                .map(|n| {
+                    let mut label = format!(
+                        "{} {}/s",
+                        n.sparkline(),
+                        crate::command::gc::format_size(n.rate())
+                    );
+                    if let Some(connections) = n.connections.last() {
+                        label.push_str(&format!(" {} conn", connections));
+                    }
+                    label
+                });
             let (status_text, status_color) = app.get_status_display(agent);
             let duration = app
                 .get_elapsed(agent)
                 .map(|d| app.format_duration(d))
                 .unwrap_or_else(|| "-".to_string());
+            // Waiting past the reminder threshold and not acknowledged yet
+            let duration_style = if app.reminders.is_active(&agent.pane_id) {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let duration = Span::styled(duration, duration_style);
 
             // Get git status for this worktree (may be None if not yet fetched)
             let git_status = app.git_statuses.get(&agent.path);
@@ -234,6 +366,9 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
                 status_color,
                 duration,
                 title,
+                activity,
+                task_progress,
+                net,
             )
         })
         .collect();
@@ -241,7 +376,7 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     // Calculate max project name width (with padding, capped)
     let max_project_width = row_data
         .iter()
-        .map(|(_, project, _, _, _, _, _, _, _, _, _)| project.len())
+        .map(|(_, project, _, _, _, _, _, _, _, _, _, _, _, _)| project.len())
         .max()
         .unwrap_or(5)
         .clamp(5, 20) // min 5, max 20
@@ -251,7 +386,7 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     // Use at least 8 to fit the "Worktree" header
     let max_worktree_width = row_data
         .iter()
-        .map(|(_, _, worktree_display, _, _, _, _, _, _, _, _)| worktree_display.len())
+        .map(|(_, _, worktree_display, _, _, _, _, _, _, _, _, _, _, _)| worktree_display.len())
         .max()
         .unwrap_or(8)
         .max(8) // min 8 (header width)
@@ -261,7 +396,7 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     // Use chars().count() instead of len() because Nerd Font icons are multi-byte
     let max_git_width = row_data
         .iter()
-        .map(|(_, _, _, _, _, git_spans, _, _, _, _, _)| {
+        .map(|(_, _, _, _, _, git_spans, _, _, _, _, _, _, _, _)| {
             git_spans
                 .iter()
                 .map(|(text, _)| text.chars().count())
@@ -276,7 +411,7 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     let max_pr_width = if show_pr_column {
         row_data
             .iter()
-            .filter_map(|(_, _, _, _, _, _, pr_spans, _, _, _, _)| pr_spans.as_ref())
+            .filter_map(|(_, _, _, _, _, _, pr_spans, _, _, _, _, _, _, _)| pr_spans.as_ref())
             .map(|spans| {
                 spans
                     .iter()
@@ -291,6 +426,65 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         0
     };
 
+    // Calculate max task progress width (bar plus step count)
+    let max_progress_width = row_data
+        .iter()
+        .filter_map(|(_, _, _, _, _, _, _, _, _, _, _, _, task_progress, _)| task_progress.as_ref())
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(8)
+        .max(8) // min 8 (header width)
+        + 1;
+
+    // Calculate max network activity width (sparkline, rate and connections)
+    let max_net_width = row_data
+        .iter()
+        .filter_map(|(_, _, _, _, _, _, _, _, _, _, _, _, _, net)| net.as_ref())
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(3)
+        .clamp(3, 30)
+        + 1;
+
+    // Build column constraints conditionally based on whether PR column is shown
+    let mut constraints = vec![
+        Constraint::Length(2),                         // #: jump key
+        Constraint::Length(max_project_width as u16),  // Project: auto-sized
+        Constraint::Length(max_worktree_width as u16), // Worktree: auto-sized
+        Constraint::Length(max_git_width as u16),      // Git: auto-sized
+    ];
+
+    if show_pr_column {
+        constraints.push(Constraint::Length(max_pr_width as u16)); // PR: auto-sized
+    }
+
+    if show_progress_column {
+        constraints.push(Constraint::Length(max_progress_width as u16)); // Progress: auto-sized
+    }
+
+    if show_net_column {
+        constraints.push(Constraint::Length(max_net_width as u16)); // Net: auto-sized
+    }
+
+    constraints.extend(vec![
+        Constraint::Length(8),  // Status: fixed (icons)
+        Constraint::Length(10), // Time: HH:MM:SS + padding
+        Constraint::Fill(1),    // Title: takes remaining space
+    ]);
+
+    // Width left for the title: the table minus the highlight symbol, the
+    // other columns and the spaces between columns
+    let fixed_width: u16 = constraints
+        .iter()
+        .map(|c| match c {
+            Constraint::Length(width) => *width,
+            _ => 0,
+        })
+        .sum();
+    let title_width =
+        area.width
+            .saturating_sub(2 + fixed_width + constraints.len() as u16 - 1) as usize;
+
     let rows: Vec<Row> = row_data
         .into_iter()
         .map(
@@ -306,6 +500,9 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
                 status_color,
                 duration,
                 title,
+                activity,
+                task_progress,
+                net,
             )| {
                 let worktree_style = if is_current {
                     Style::default().fg(app.palette.current_worktree_fg)
@@ -340,10 +537,21 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
                     cells.push(Cell::from(pr_line));
                 }
 
+                if show_progress_column {
+                    cells.push(
+                        Cell::from(task_progress.unwrap_or_default())
+                            .style(Style::default().fg(Color::Cyan)),
+                    );
+                }
+
+                if show_net_column {
+                    cells.push(Cell::from(net.unwrap_or_default()));
+                }
+
                 cells.extend(vec![
                     Cell::from(status_text).style(Style::default().fg(status_color)),
-                    Cell::from(duration),
-                    Cell::from(title),
+                    Cell::from(Line::from(duration)),
+                    Cell::from(title_line(activity, title, app.palette.dimmed, title_width)),
                 ]);
 
                 let row = Row::new(cells);
@@ -357,24 +565,6 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
         )
         .collect();
 
-    // Build column constraints conditionally based on whether PR column is shown
-    let mut constraints = vec![
-        Constraint::Length(2),                         // #: jump key
-        Constraint::Length(max_project_width as u16),  // Project: auto-sized
-        Constraint::Length(max_worktree_width as u16), // Worktree: auto-sized
-        Constraint::Length(max_git_width as u16),      // Git: auto-sized
-    ];
-
-    if show_pr_column {
-        constraints.push(Constraint::Length(max_pr_width as u16)); // PR: auto-sized
-    }
-
-    constraints.extend(vec![
-        Constraint::Length(8),  // Status: fixed (icons)
-        Constraint::Length(10), // Time: HH:MM:SS + padding
-        Constraint::Fill(1),    // Title: takes remaining space
-    ]);
-
     let table = Table::new(rows, constraints)
         .header(header)
         .block(Block::default())
@@ -384,6 +574,45 @@ fn render_table(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(table, area, &mut app.table_state);
 }
 
+/// Title cell content: the agent's current activity (if known) followed by
+/// the dimmed pane title, or just the title. Text past `width` is cut with
+/// an ellipsis, the title before the activity.
+fn title_line(
+    activity: Option<String>,
+    title: String,
+    dimmed: Color,
+    width: usize,
+) -> Line<'static> {
+    match activity {
+        Some(activity) if title.is_empty() => Line::from(truncate(&activity, width)),
+        Some(activity) => {
+            let activity = truncate(&activity, width);
+            let rest = width.saturating_sub(activity.chars().count() + 2);
+            let mut spans = vec![Span::raw(activity)];
+            if rest > 0 {
+                spans.push(Span::styled(
+                    format!("  {}", truncate(&title, rest)),
+                    Style::default().fg(dimmed),
+                ));
+            }
+            Line::from(spans)
+        }
+        None => Line::from(truncate(&title, width)),
+    }
+}
+
+/// `text` cut to `width` characters, ending in an ellipsis when shortened.
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
 fn render_preview(f: &mut Frame, app: &mut App, area: Rect) {
     // Get info about the selected agent for the title
     let selected_agent = app
@@ -396,7 +625,7 @@ fn render_preview(f: &mut Frame, app: &mut App, area: Rect) {
             .map(|a| app.extract_worktree_name(a).0)
             .unwrap_or_default();
         (
-            format!(" INPUT: {} ", worktree_name),
+            format!(" INPUT: {} (Ctrl+] to exit) ", worktree_name),
             Style::default()
                 .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
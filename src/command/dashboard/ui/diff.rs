@@ -8,6 +8,7 @@ use ratatui::{
     widgets::{Block, List, ListItem, Paragraph},
 };
 
+use super::super::blame::BlamePanel;
 use super::super::diff::DiffView;
 use super::theme::ThemePalette;
 
@@ -22,75 +23,65 @@ pub fn render_diff_view(f: &mut Frame, diff: &mut DiffView, palette: &ThemePalet
     ])
     .split(area);
 
-    // Split content: File List (Right) + Diff (Left)
-    // Only show file list if there are files to display
-    let has_files = !diff.file_list.is_empty();
-    let content_chunks = if has_files {
+    // Split content: Sidebar (Right) + Diff (Left)
+    // The sidebar is the blame panel when open, otherwise the file list if
+    // there are files to display
+    let sidebar_width = if diff.blame.is_some() {
+        Some(Constraint::Percentage(45))
+    } else if !diff.file_list.is_empty() {
+        Some(Constraint::Percentage(25))
+    } else {
+        None
+    };
+    let content_chunks = if let Some(width) = sidebar_width {
         Layout::horizontal([
-            Constraint::Min(40),        // Diff content (takes remaining space)
-            Constraint::Percentage(25), // File list sidebar
+            Constraint::Min(40), // Diff content (takes remaining space)
+            width,
         ])
         .split(chunks[0])
     } else {
-        // No files - use full width for diff
+        // No sidebar - use full width for diff
         Layout::horizontal([Constraint::Percentage(100)]).split(chunks[0])
     };
 
     let diff_area = content_chunks[0];
-    let file_list_area = if has_files {
-        Some(content_chunks[1])
-    } else {
-        None
-    };
+    let sidebar_area = sidebar_width.map(|_| content_chunks[1]);
 
     // Update viewport height for scroll calculations (subtract 2 for borders)
     diff.viewport_height = diff_area.height.saturating_sub(2);
 
     if diff.patch_mode {
-        // Patch mode with optional file list sidebar
-        render_patch_mode(f, diff, diff_area, chunks[1], palette);
-        if let Some(file_area) = file_list_area {
-            render_file_list(f, diff, file_area, palette);
-        }
+        render_patch_mode(f, diff, diff_area, chunks[1]);
     } else {
-        // Normal diff mode with optional file list
         render_normal_diff(f, diff, diff_area, chunks[1], palette);
-        if let Some(file_area) = file_list_area {
-            render_file_list(f, diff, file_area, palette);
+    }
+    if let Some(sidebar_area) = sidebar_area {
+        if let Some(ref blame) = diff.blame {
+            render_blame_panel(f, blame, sidebar_area, palette);
+        } else {
+            render_file_list(f, diff, sidebar_area, palette);
         }
     }
 }
 
-/// Determine which file is currently visible based on scroll position or current hunk.
-fn get_current_file_index(diff: &DiffView) -> Option<usize> {
-    if diff.file_list.is_empty() {
-        return None;
-    }
-
-    // In patch mode, use the current hunk's filename
-    if diff.patch_mode && !diff.hunks.is_empty() {
-        let current_filename = &diff.hunks[diff.current_hunk].filename;
-        return diff
-            .file_list
-            .iter()
-            .position(|f| &f.filename == current_filename);
-    }
+/// Render the blame and history panel for the file or hunk under the cursor.
+fn render_blame_panel(f: &mut Frame, blame: &BlamePanel, area: Rect, palette: &ThemePalette) {
+    let title = match blame.target.hunk {
+        Some((start, _)) => format!(" Blame: {}:{} ", blame.target.filename, start),
+        None => format!(" Blame: {} ", blame.target.filename),
+    };
+    let block = Block::bordered()
+        .title(title)
+        .title_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(palette.dimmed));
 
-    // Find the last file whose start_line is <= current scroll position
-    let mut current_idx = 0;
-    for (idx, file) in diff.file_list.iter().enumerate() {
-        if file.start_line <= diff.scroll {
-            current_idx = idx;
-        } else {
-            break;
-        }
-    }
-    Some(current_idx)
+    let paragraph = Paragraph::new(Text::from(blame.lines.clone())).block(block);
+    f.render_widget(paragraph, area);
 }
 
 /// Render the file list sidebar (full paths, directory dimmed, left-truncate if needed).
 fn render_file_list(f: &mut Frame, diff: &DiffView, area: Rect, palette: &ThemePalette) {
-    let current_file_idx = get_current_file_index(diff);
+    let current_file_idx = diff.current_file_index();
 
     let block = Block::bordered()
         .title(format!(" Files ({}) ", diff.file_list.len()))
@@ -308,6 +299,8 @@ fn render_normal_diff(
     footer_spans.extend(vec![
         Span::styled("[j/k]", Style::default().fg(Color::Cyan)),
         Span::raw(" scroll  "),
+        Span::styled("[b]", Style::default().fg(Color::Magenta)),
+        Span::raw(" blame  "),
         Span::styled("[c]", Style::default().fg(Color::Green)),
         Span::raw(" commit  "),
         Span::styled("[m]", Style::default().fg(Color::Yellow)),
@@ -321,13 +314,7 @@ fn render_normal_diff(
 }
 
 /// Render patch mode (hunk-by-hunk staging like git add -p).
-fn render_patch_mode(
-    f: &mut Frame,
-    diff: &DiffView,
-    content_area: Rect,
-    footer_area: Rect,
-    palette: &ThemePalette,
-) {
+fn render_patch_mode(f: &mut Frame, diff: &DiffView, content_area: Rect, footer_area: Rect) {
     let hunk = &diff.hunks[diff.current_hunk];
 
     // Title shows filename and hunk progress
@@ -387,33 +374,24 @@ fn render_patch_mode(
 
     f.render_widget(paragraph, content_area);
 
-    // Footer: show comment input if in comment mode, otherwise show keybindings
-    if let Some(ref input) = diff.comment_input {
-        // Comment input mode - hints on left stay fixed, input on right
-        let mut spans = vec![
-            Span::styled("  [Enter]", Style::default().fg(Color::Green)),
-            Span::raw(" send  "),
-            Span::styled("[Esc]", Style::default().fg(Color::Red)),
-            Span::raw(" cancel  "),
-            Span::styled("| ", Style::default().fg(palette.dimmed)),
-        ];
-
-        if input.is_empty() {
-            // Show cursor then placeholder when empty
-            spans.push(Span::styled("|", Style::default().fg(palette.text)));
-            spans.push(Span::styled(
-                "Type your comment...",
-                Style::default().fg(palette.dimmed),
-            ));
-        } else {
-            spans.push(Span::raw(input));
-            spans.push(Span::styled("|", Style::default().fg(palette.text)));
-        }
-
-        let footer = Paragraph::new(Line::from(spans));
+    // Footer: comment editor keys while it is open (it renders as an
+    // overlay), otherwise patch mode keybindings
+    if diff.comment_input.is_some() {
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled("  [Ctrl+x]", Style::default().fg(Color::Red)),
+            Span::raw(" discard draft  "),
+            Span::styled("[Ctrl+a/e]", Style::default().fg(Color::Cyan)),
+            Span::raw(" line start/end  "),
+            Span::styled("[Ctrl+u]", Style::default().fg(Color::Cyan)),
+            Span::raw(" delete to line start"),
+        ]));
         f.render_widget(footer, footer_area);
     } else {
         // Normal patch mode keybindings
+        let has_draft = diff
+            .hunks
+            .get(diff.current_hunk)
+            .is_some_and(|hunk| diff.comment_drafts.drafts.contains_key(&hunk.draft_key()));
         let mut footer_spans = vec![
             Span::raw("  "),
             Span::styled("[y]", Style::default().fg(Color::Green)),
@@ -432,9 +410,15 @@ fn render_patch_mode(
             Span::styled("[s]", Style::default().fg(Color::Yellow)),
             Span::raw(" split  "),
             Span::styled("[o]", Style::default().fg(Color::Cyan)),
-            Span::raw(" comment  "),
+            Span::raw(if has_draft {
+                " comment (draft)  "
+            } else {
+                " comment  "
+            }),
             Span::styled("[j/k]", Style::default().fg(Color::Cyan)),
             Span::raw(" nav  "),
+            Span::styled("[b]", Style::default().fg(Color::Magenta)),
+            Span::raw(" blame  "),
             Span::styled("[q]", Style::default().fg(Color::Cyan)),
             Span::raw(" quit"),
         ]);
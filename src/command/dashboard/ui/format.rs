@@ -2,15 +2,17 @@
 
 use ratatui::style::{Color, Modifier, Style};
 
-use crate::vcs::VcsStatus;
 use crate::github::{CheckState, PrSummary};
 use crate::nerdfont;
+use crate::vcs::VcsStatus;
 
 use super::super::spinner::SPINNER_FRAMES;
 use super::theme::ThemePalette;
 
 /// Format git status for the Git column: base branch, diff stats, then indicators
 /// Format: "→branch +N -M 󰏫 +X -Y 󰀪 ↑A ↓B"
+/// Branches without an upstream get a no-upstream marker and dimmed ahead/behind
+/// counts, which are then relative to the base branch
 /// When there are uncommitted changes that differ from total, branch totals are dimmed
 pub fn format_git_status(
     status: Option<&VcsStatus>,
@@ -119,14 +121,32 @@ pub fn format_git_status(
             spans.push((icons.conflict.to_string(), Style::default().fg(Color::Red)));
         }
 
-        // Ahead/behind upstream
+        // Ahead/behind upstream. Without an upstream the counts are against the
+        // base branch, so mark them and dim them to avoid reading them as unpushed.
+        let vs_base = status.compares_to_base();
+        let count_modifier = if vs_base {
+            Modifier::DIM
+        } else {
+            Modifier::empty()
+        };
+        if vs_base {
+            if !spans.is_empty() {
+                spans.push((" ".to_string(), Style::default()));
+            }
+            spans.push((
+                icons.no_upstream.to_string(),
+                Style::default().fg(palette.dimmed),
+            ));
+        }
         if status.ahead > 0 {
             if !spans.is_empty() {
                 spans.push((" ".to_string(), Style::default()));
             }
             spans.push((
                 format!("↑{}", status.ahead),
-                Style::default().fg(Color::Blue),
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(count_modifier),
             ));
         }
         if status.behind > 0 {
@@ -135,7 +155,9 @@ pub fn format_git_status(
             }
             spans.push((
                 format!("↓{}", status.behind),
-                Style::default().fg(Color::Yellow),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(count_modifier),
             ));
         }
 
@@ -13,6 +13,9 @@ use super::super::keymap::{Context, help_rows};
 
 /// Determine the current keymap context for help display.
 fn get_help_context(app: &App) -> Context {
+    if app.confirm.is_some() {
+        return Context::Confirm;
+    }
     match &app.view_mode {
         ViewMode::Dashboard => {
             if app.input_mode {
@@ -43,6 +46,7 @@ fn context_title(ctx: Context) -> &'static str {
         Context::DiffNormal => "Diff View",
         Context::Patch => "Patch Mode",
         Context::Comment => "Comment",
+        Context::Confirm => "Confirm",
     }
 }
 
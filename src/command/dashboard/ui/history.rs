@@ -0,0 +1,169 @@
+//! Status history popup: a timeline of an agent's statuses with totals.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::super::app::HistoryPopup;
+use super::theme::ThemePalette;
+use crate::multiplexer::AgentStatus;
+use crate::state::history::{self, Period};
+use crate::util::format_elapsed_secs;
+
+fn status_color(status: AgentStatus) -> Color {
+    match status {
+        AgentStatus::Working => Color::Cyan,
+        AgentStatus::Waiting => Color::Magenta,
+        AgentStatus::Done => Color::Green,
+        AgentStatus::Unknown => Color::Red,
+    }
+}
+
+/// Status of each of `width` columns of a bar spanning `periods`, so each
+/// status gets a share of the bar matching the time spent in it.
+fn bar_columns(periods: &[Period], now: u64, width: usize) -> Vec<AgentStatus> {
+    let Some(first) = periods.first() else {
+        return Vec::new();
+    };
+    let total = now.saturating_sub(first.start).max(1);
+    (0..width as u64)
+        .map(|col| {
+            let at = first.start + total * col / width as u64;
+            periods
+                .iter()
+                .rev()
+                .find(|p| p.start <= at)
+                .unwrap_or(first)
+                .status
+        })
+        .collect()
+}
+
+/// Render the status history of one agent.
+pub fn render_history(f: &mut Frame, popup: &HistoryPopup, palette: &ThemePalette) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let periods = history::periods(&popup.history, now);
+    let summary = history::summarize(&popup.history, now);
+
+    let area = f.area();
+    let width = 56.min(area.width);
+    let inner_width = width.saturating_sub(4) as usize;
+    let muted = Style::default().fg(palette.help_muted);
+    let text = Style::default().fg(palette.text);
+
+    // Bar, in runs of the same status
+    let mut bar: Vec<Span> = vec![Span::raw(" ")];
+    let columns = bar_columns(&periods, now, inner_width);
+    let mut start = 0;
+    while start < columns.len() {
+        let status = columns[start];
+        let len = columns[start..]
+            .iter()
+            .take_while(|s| **s == status)
+            .count();
+        bar.push(Span::styled(
+            "█".repeat(len),
+            Style::default().fg(status_color(status)),
+        ));
+        start += len;
+    }
+
+    let mut totals: Vec<Span> = vec![Span::raw(" ")];
+    for (status, secs) in [
+        (AgentStatus::Working, summary.working_secs),
+        (AgentStatus::Waiting, summary.waiting_secs),
+        (AgentStatus::Done, summary.done_secs),
+        (AgentStatus::Unknown, summary.unknown_secs),
+    ] {
+        if !periods.iter().any(|p| p.status == status) {
+            continue;
+        }
+        if totals.len() > 1 {
+            totals.push(Span::styled(" · ", muted));
+        }
+        totals.push(Span::styled(
+            status.as_str(),
+            Style::default().fg(status_color(status)),
+        ));
+        totals.push(Span::styled(
+            format!(" {}", format_elapsed_secs(secs)),
+            text,
+        ));
+    }
+
+    let mut facts = vec![format!("{} changes", popup.history.len())];
+    if summary.waits > 0 {
+        facts.push(format!("waited for input {}×", summary.waits));
+    }
+    if let Some(secs) = summary.first_response_secs {
+        facts.push(format!(
+            "first response after {}",
+            format_elapsed_secs(secs)
+        ));
+    }
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(bar),
+        Line::from(""),
+        Line::from(totals),
+        Line::from(Span::styled(format!(" {}", facts.join(" · ")), muted)),
+        Line::from(""),
+    ];
+
+    // Latest changes that fit inside the borders, oldest first
+    let room = (area.height.saturating_sub(2) as usize).saturating_sub(lines.len());
+    for period in periods.iter().skip(periods.len().saturating_sub(room)) {
+        let ago = format!(
+            "{} ago",
+            format_elapsed_secs(now.saturating_sub(period.start))
+        );
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:>9}  ", ago), muted),
+            Span::styled(
+                format!("{:<8}", period.status.as_str()),
+                Style::default().fg(status_color(period.status)),
+            ),
+            Span::styled(format!(" {}", format_elapsed_secs(period.secs)), text),
+        ]));
+    }
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::bordered()
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(Style::default().fg(palette.help_border))
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("History · {}", popup.name),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+        ]))
+        .title_bottom(Line::from(vec![
+            Span::raw(" "),
+            Span::styled("any key", Style::default().fg(palette.dimmed)),
+            Span::styled(" to close ", muted),
+        ]));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -1,25 +1,45 @@
 //! TUI rendering logic for the dashboard.
 
+mod comment;
+mod confirm;
 mod dashboard;
 mod diff;
 mod format;
 mod help;
+mod history;
 pub mod theme;
 
 use ratatui::Frame;
 
 use super::app::{App, ViewMode};
 
+pub use self::comment::render_comment_editor;
+pub use self::confirm::render_confirm;
 pub use self::dashboard::render_dashboard;
 pub use self::diff::render_diff_view;
 pub use self::help::render_help;
+pub use self::history::render_history;
 
 /// Main UI entry point - renders the appropriate view based on app state.
 pub fn ui(f: &mut Frame, app: &mut App) {
     // Render either dashboard or diff view based on view mode
     match &mut app.view_mode {
         ViewMode::Dashboard => render_dashboard(f, app),
-        ViewMode::Diff(diff_view) => render_diff_view(f, diff_view, &app.palette),
+        ViewMode::Diff(diff_view) => {
+            render_diff_view(f, diff_view, &app.palette);
+            if let Some(editor) = &diff_view.comment_input {
+                render_comment_editor(f, editor, &app.palette);
+            }
+        }
+    }
+
+    // Confirmation modal sits above either view
+    if let Some(confirm) = &app.confirm {
+        render_confirm(f, confirm, &app.palette);
+    }
+
+    if let Some(history) = &app.history {
+        render_history(f, history, &app.palette);
     }
 
     // Render help overlay on top if active
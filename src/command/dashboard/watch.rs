@@ -0,0 +1,231 @@
+//! Headless dashboard streaming agent status as newline-delimited JSON.
+//!
+//! `workmux dashboard --watch` prints one JSON object per interval with every
+//! agent the dashboard shows, its status, elapsed time and the git stats of
+//! its worktree. Each line stands on its own, so status bars (waybar,
+//! polybar) and custom UIs can read the latest one without keeping state.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::agent::{
+    activity, elapsed_secs, extract_project_name, extract_worktree_name, status_name,
+};
+use crate::config::Config;
+use crate::filesystem;
+use crate::multiplexer::{AgentPane, AgentStatus, create_backend, detect_backend};
+use crate::state::StateStore;
+use crate::vcs::{self, VcsStatus};
+
+#[derive(Debug, Serialize)]
+struct Snapshot {
+    /// UNIX seconds the snapshot was taken
+    ts: u64,
+    counts: Counts,
+    agents: Vec<AgentEntry>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Counts {
+    working: usize,
+    waiting: usize,
+    done: usize,
+    unknown: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct AgentEntry {
+    project: String,
+    worktree: String,
+    path: PathBuf,
+    pane_id: String,
+    status: Option<&'static str>,
+    elapsed_secs: Option<u64>,
+    title: Option<String>,
+    activity: Option<String>,
+    /// Missing for worktrees on network filesystems and outside a repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git: Option<GitStats>,
+}
+
+#[derive(Debug, Serialize)]
+struct GitStats {
+    branch: Option<String>,
+    base_branch: String,
+    ahead: usize,
+    behind: usize,
+    dirty: bool,
+    conflict: bool,
+    lines_added: usize,
+    lines_removed: usize,
+    uncommitted_added: usize,
+    uncommitted_removed: usize,
+}
+
+impl From<&VcsStatus> for GitStats {
+    fn from(status: &VcsStatus) -> Self {
+        Self {
+            branch: status.branch.clone(),
+            base_branch: status.base_branch.clone(),
+            ahead: status.ahead,
+            behind: status.behind,
+            dirty: status.is_dirty,
+            conflict: status.has_conflict,
+            lines_added: status.lines_added,
+            lines_removed: status.lines_removed,
+            uncommitted_added: status.uncommitted_added,
+            uncommitted_removed: status.uncommitted_removed,
+        }
+    }
+}
+
+/// Print a snapshot every `interval_secs` until stdout is closed.
+pub fn run(interval_secs: u64) -> Result<()> {
+    let mux = create_backend(detect_backend());
+    let config = Config::load(None).unwrap_or_default();
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let vcs = vcs::try_detect_vcs();
+
+    loop {
+        // A stopped multiplexer shows up as no agents rather than ending the
+        // stream, so a status bar started before it keeps working
+        let agents = StateStore::new()
+            .and_then(|store| store.load_reconciled_agents(mux.as_ref()))
+            .unwrap_or_default();
+
+        let mut git: HashMap<PathBuf, VcsStatus> = HashMap::new();
+        if let Some(vcs) = &vcs {
+            for agent in &agents {
+                if !git.contains_key(&agent.path) && !filesystem::is_network(&agent.path) {
+                    git.insert(agent.path.clone(), vcs.get_status(&agent.path));
+                }
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let snapshot = snapshot(&agents, &git, config.window_prefix(), now);
+
+        // The reader going away (status bar reloaded) ends the stream quietly
+        let mut stdout = io::stdout().lock();
+        if writeln!(stdout, "{}", serde_json::to_string(&snapshot)?).is_err()
+            || stdout.flush().is_err()
+        {
+            return Ok(());
+        }
+        drop(stdout);
+        thread::sleep(interval);
+    }
+}
+
+fn snapshot(
+    agents: &[AgentPane],
+    git: &HashMap<PathBuf, VcsStatus>,
+    window_prefix: &str,
+    now: u64,
+) -> Snapshot {
+    let mut counts = Counts::default();
+    let mut entries: Vec<AgentEntry> = agents
+        .iter()
+        .map(|agent| {
+            match agent.status {
+                Some(AgentStatus::Working) => counts.working += 1,
+                Some(AgentStatus::Waiting) => counts.waiting += 1,
+                Some(AgentStatus::Done) => counts.done += 1,
+                Some(AgentStatus::Unknown) => counts.unknown += 1,
+                None => {}
+            }
+            let (worktree, _) =
+                extract_worktree_name(&agent.session, &agent.window_name, window_prefix);
+            AgentEntry {
+                project: extract_project_name(&agent.path),
+                worktree,
+                path: agent.path.clone(),
+                pane_id: agent.pane_id.clone(),
+                status: agent.status.map(status_name),
+                elapsed_secs: elapsed_secs(agent.status_ts, now),
+                title: agent.pane_title.clone(),
+                activity: activity(agent),
+                git: git.get(&agent.path).map(GitStats::from),
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| (&a.project, &a.worktree).cmp(&(&b.project, &b.worktree)));
+    Snapshot {
+        ts: now,
+        counts,
+        agents: entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(window: &str, path: &str, status: AgentStatus) -> AgentPane {
+        AgentPane {
+            session: "main".to_string(),
+            window_name: window.to_string(),
+            pane_id: "%1".to_string(),
+            path: PathBuf::from(path),
+            pane_title: Some("Fix login".to_string()),
+            status: Some(status),
+            status_ts: Some(1_000),
+            progress: None,
+            task_progress: None,
+            denied_exec: None,
+            net_activity: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_counts_and_sorts_agents_with_git_stats() {
+        let agents = vec![
+            agent("wm-auth", "/code/web__worktrees/auth", AgentStatus::Working),
+            agent("wm-api", "/code/api__worktrees/api", AgentStatus::Waiting),
+            agent("wm-docs", "/code/web__worktrees/docs", AgentStatus::Working),
+        ];
+        let git = HashMap::from([(
+            PathBuf::from("/code/web__worktrees/auth"),
+            VcsStatus {
+                ahead: 2,
+                lines_added: 40,
+                is_dirty: true,
+                branch: Some("auth".to_string()),
+                base_branch: "main".to_string(),
+                ..Default::default()
+            },
+        )]);
+        let snapshot = snapshot(&agents, &git, "wm-", 1_065);
+        assert_eq!(
+            snapshot.counts,
+            Counts {
+                working: 2,
+                waiting: 1,
+                ..Default::default()
+            }
+        );
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["ts"], 1_065);
+        let agents = json["agents"].as_array().unwrap();
+        let worktrees: Vec<_> = agents.iter().map(|a| a["worktree"].clone()).collect();
+        assert_eq!(worktrees, ["api", "auth", "docs"]);
+        assert_eq!(agents[0]["status"], "waiting");
+        assert_eq!(agents[0]["elapsed_secs"], 65);
+        assert_eq!(agents[0]["activity"], "Fix login");
+        assert!(agents[0].get("git").is_none());
+        assert_eq!(agents[1]["git"]["ahead"], 2);
+        assert_eq!(agents[1]["git"]["dirty"], true);
+        assert_eq!(agents[1]["git"]["branch"], "auth");
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::config::{self, MuxMode};
+use crate::multiplexer::capabilities;
+use crate::multiplexer::handle::mode_label;
+use crate::multiplexer::util::prefixed;
+use crate::multiplexer::{LivePaneInfo, create_backend, detect_backend};
+use crate::util::canon_or_self;
+use crate::vcs;
+use crate::workflow::{WorkflowContext, orphans, preflight};
+
+/// A window or session of this project that needs a new name.
+struct Rename {
+    mode: MuxMode,
+    old: String,
+    new: String,
+    /// A pane inside the window (windows are renamed via a pane since names
+    /// may be shared with another project's window)
+    pane_id: String,
+}
+
+pub fn run(fix: bool) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+
+    // Repository setup problems that would make `workmux add` fail
+    let issues = preflight::check(vcs::detect_vcs()?.as_ref(), &config, false);
+    if !issues.is_empty() {
+        println!("Repository:\n{}", preflight::format_issues(&issues));
+    }
+    if issues
+        .iter()
+        .any(|i| i.severity == preflight::Severity::Error)
+    {
+        return Ok(());
+    }
+
+    // Flags the installed agent doesn't accept
+    let agent_issues = capabilities::lint(&config);
+    if !agent_issues.is_empty() {
+        println!("Agent:");
+        for issue in &agent_issues {
+            println!("  ! {}", issue);
+        }
+    }
+
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+
+    if !context.mux.is_running().unwrap_or(false) {
+        println!("{} is not running; nothing to check", context.mux.name());
+        return Ok(());
+    }
+
+    let project = config::project_name(&context.main_worktree_root);
+    let qualified = config::unqualified_prefix(&context.prefix, &project).is_some();
+    let base_prefix = config::unqualified_prefix(&context.prefix, &project)
+        .unwrap_or(&context.prefix)
+        .to_string();
+    let new_prefix = config::qualified_prefix(&base_prefix, &project);
+    println!(
+        "Window prefix: '{}' (project '{}')",
+        context.prefix, project
+    );
+
+    let status_format = context.config.status_format.unwrap_or(true);
+    for issue in context.mux.status_format_issues(status_format) {
+        println!("! {}", issue);
+    }
+
+    let roots = context.project_roots();
+    let panes = context.mux.get_all_live_pane_info()?;
+    let modes = context.vcs.get_all_workspace_modes();
+    let handles: Vec<String> = context
+        .vcs
+        .list_workspaces()?
+        .iter()
+        .filter_map(|(path, _)| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .collect();
+
+    // Names shared with windows of other projects
+    let mut collisions = Vec::new();
+    let mut renames = Vec::new();
+    for handle in &handles {
+        let mode = modes.get(handle).copied().unwrap_or(MuxMode::Window);
+        let old = prefixed(&base_prefix, handle);
+        for (name, pane_id) in own_targets(&panes, &roots, mode, &old) {
+            let suffix = &name[old.len()..];
+            renames.push(Rename {
+                mode,
+                new: format!("{}{}", prefixed(&new_prefix, handle), suffix),
+                old: name,
+                pane_id,
+            });
+        }
+        if !qualified && used_by_other_project(&context, &panes, &roots, mode, &old) {
+            collisions.push(old);
+        }
+    }
+
+    // Windows left behind by a worktree deleted outside workmux
+    let orphans = orphans::find(&context, &panes)?;
+    for orphan in &orphans {
+        println!(
+            "! {} '{}' runs in deleted worktree {}; run 'workmux reattach {}'",
+            mode_label(orphan.mode),
+            orphan.name,
+            orphan.path.display(),
+            orphan.handle
+        );
+    }
+
+    for name in &collisions {
+        println!("! '{}' is also used by another project", name);
+    }
+    if qualified {
+        for rename in &renames {
+            println!("! '{}' still uses the unqualified prefix", rename.old);
+        }
+    }
+
+    let needs_migration = !collisions.is_empty() || (qualified && !renames.is_empty());
+    if !needs_migration {
+        if orphans.is_empty() {
+            println!("✓ No problems found");
+        }
+        return Ok(());
+    }
+    if !fix {
+        println!(
+            "Run 'workmux doctor --fix' to name this project's windows '{}<name>'",
+            new_prefix
+        );
+        return Ok(());
+    }
+
+    if !qualified {
+        context
+            .vcs
+            .set_repo_meta(config::QUALIFY_PREFIX_META, "true")?;
+    }
+    for rename in &renames {
+        match rename.mode {
+            MuxMode::Window => context.mux.rename_window(&rename.pane_id, &rename.new)?,
+            MuxMode::Session => context.mux.rename_session(&rename.old, &rename.new)?,
+        }
+        println!("✓ Renamed '{}' to '{}'", rename.old, rename.new);
+    }
+    println!(
+        "✓ This project's windows are now named '{}<name>'",
+        new_prefix
+    );
+    Ok(())
+}
+
+/// Windows (or sessions) named `full_name` or `full_name-N` that contain a
+/// pane inside this project, with one such pane per name.
+fn own_targets(
+    panes: &HashMap<String, LivePaneInfo>,
+    roots: &[PathBuf],
+    mode: MuxMode,
+    full_name: &str,
+) -> Vec<(String, String)> {
+    let mut targets: Vec<(String, String)> = panes
+        .iter()
+        .filter(|(_, info)| in_project(&info.working_dir, roots))
+        .filter_map(|(pane_id, info)| {
+            let name = target_name(info, mode)?;
+            matches_handle_name(name, full_name).then(|| (name.to_string(), pane_id.clone()))
+        })
+        .collect();
+    targets.sort();
+    targets.dedup_by(|a, b| a.0 == b.0);
+    targets
+}
+
+/// Whether a pane of another repository sits in a target named `full_name`.
+fn used_by_other_project(
+    context: &WorkflowContext,
+    panes: &HashMap<String, LivePaneInfo>,
+    roots: &[PathBuf],
+    mode: MuxMode,
+    full_name: &str,
+) -> bool {
+    panes.values().any(|info| {
+        target_name(info, mode) == Some(full_name)
+            && !in_project(&info.working_dir, roots)
+            && context
+                .vcs
+                .get_repo_root_for(&info.working_dir)
+                .is_ok_and(|root| !in_project(&root, roots))
+    })
+}
+
+fn target_name(info: &LivePaneInfo, mode: MuxMode) -> Option<&str> {
+    match mode {
+        MuxMode::Window => info.window.as_deref(),
+        MuxMode::Session => info.session.as_deref(),
+    }
+}
+
+fn in_project(dir: &Path, roots: &[PathBuf]) -> bool {
+    let dir = canon_or_self(dir);
+    roots.iter().any(|root| dir.starts_with(root))
+}
+
+/// Whether `name` is `full_name` or a numbered duplicate (`full_name-2`).
+fn matches_handle_name(name: &str, full_name: &str) -> bool {
+    name == full_name
+        || name
+            .strip_prefix(full_name)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_handle_name_accepts_numbered_duplicates() {
+        assert!(matches_handle_name("wm-feature", "wm-feature"));
+        assert!(matches_handle_name("wm-feature-2", "wm-feature"));
+        assert!(!matches_handle_name("wm-feature-x", "wm-feature"));
+        assert!(!matches_handle_name("wm-feature-", "wm-feature"));
+        assert!(!matches_handle_name("wm-feat", "wm-feature"));
+    }
+
+    #[test]
+    fn own_targets_only_includes_project_panes() {
+        let pane = |dir: &str, window: &str| LivePaneInfo {
+            pid: 1,
+            current_command: "zsh".to_string(),
+            working_dir: PathBuf::from(dir),
+            title: None,
+            session: Some("main".to_string()),
+            window: Some(window.to_string()),
+            process: None,
+        };
+        let panes = HashMap::from([
+            (
+                "%1".to_string(),
+                pane("/code/api__worktrees/feature", "wm-feature"),
+            ),
+            (
+                "%2".to_string(),
+                pane("/code/web__worktrees/feature", "wm-feature"),
+            ),
+            (
+                "%3".to_string(),
+                pane("/code/api__worktrees/feature/src", "wm-feature-2"),
+            ),
+        ]);
+        let roots = vec![
+            PathBuf::from("/code/api"),
+            PathBuf::from("/code/api__worktrees/feature"),
+        ];
+        let targets = own_targets(&panes, &roots, MuxMode::Window, "wm-feature");
+        assert_eq!(
+            targets,
+            vec![
+                ("wm-feature".to_string(), "%1".to_string()),
+                ("wm-feature-2".to_string(), "%3".to_string()),
+            ]
+        );
+    }
+}
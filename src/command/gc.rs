@@ -0,0 +1,618 @@
+//! `workmux gc`: delete what workmux leaves behind once it is no longer used.
+//!
+//! Covers status cache entries of worktrees that were deleted, pane
+//! transcripts, agent state of panes that are gone, stopped `wm-*` VMs and
+//! containers, and prompt files in the temp directory. Files and sandboxes
+//! are kept for the retention windows in the `gc` config section.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use tracing::debug;
+
+use crate::config::{Config, GcConfig, SandboxRuntime};
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::sandbox::lima::LimaInstance;
+use crate::state::{PaneKey, StateStore};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Name prefix of prompt files written to the temp directory.
+const PROMPT_FILE_PREFIX: &str = "workmux-prompt-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Category {
+    StatusCache,
+    Logs,
+    AgentState,
+    Sandboxes,
+    Prompts,
+}
+
+impl Category {
+    fn title(self) -> &'static str {
+        match self {
+            Category::StatusCache => "Status cache entries of deleted worktrees",
+            Category::Logs => "Pane transcripts",
+            Category::AgentState => "Agent state of closed panes",
+            Category::Sandboxes => "Stopped sandboxes",
+            Category::Prompts => "Prompt files",
+        }
+    }
+}
+
+/// How an item is deleted.
+#[derive(Debug, PartialEq)]
+enum Removal {
+    Path(PathBuf),
+    StatusEntry(PathBuf),
+    PrEntry(PathBuf),
+    Agent(PaneKey),
+    Vm(String),
+    Container { runtime: &'static str, name: String },
+}
+
+/// Something gc would delete.
+#[derive(Debug)]
+struct Item {
+    category: Category,
+    description: String,
+    /// Disk space freed, when known
+    bytes: Option<u64>,
+    removal: Removal,
+}
+
+pub fn run(dry_run: bool, yes: bool) -> Result<()> {
+    let config = Config::load(None)?;
+    let now = now_secs();
+
+    let mut items = status_cache_items();
+    match crate::workflow::pane_logs_root() {
+        Ok(root) => items.extend(log_items(&root, config.gc.logs_days(), now)),
+        Err(e) => debug!(error = %e, "gc:no state directory"),
+    }
+    items.extend(agent_items());
+    items.extend(sandbox_items(&config, &config.gc, now));
+    items.extend(prompt_items(
+        &std::env::temp_dir(),
+        config.gc.prompts_days(),
+        now,
+    ));
+    items.sort_by_key(|a| a.category);
+
+    if items.is_empty() {
+        println!("Nothing to clean up");
+        return Ok(());
+    }
+    print_items(&items);
+
+    let total: u64 = items.iter().filter_map(|item| item.bytes).sum();
+    if dry_run {
+        println!(
+            "\nWould free {}. Run without --dry-run to delete.",
+            format_size(total)
+        );
+        return Ok(());
+    }
+
+    // Confirm deletion unless --yes
+    if !yes {
+        print!(
+            "\nDelete these {} item(s), freeing {}? [y/N] ",
+            items.len(),
+            format_size(total)
+        );
+        io::stdout().flush().context("Failed to flush stdout")?;
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read input")?;
+
+        if input.trim().to_lowercase() != "y" {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let mut freed = 0;
+    let mut removed = 0;
+    let mut failed = Vec::new();
+    let mut status_entries = HashSet::new();
+    let mut pr_entries = HashSet::new();
+    let store = StateStore::new().ok();
+    for item in items {
+        let result = match item.removal {
+            Removal::Path(ref path) if path.is_dir() => {
+                fs::remove_dir_all(path).map_err(Into::into)
+            }
+            Removal::Path(ref path) => fs::remove_file(path).map_err(Into::into),
+            Removal::StatusEntry(path) => {
+                status_entries.insert(path);
+                Ok(())
+            }
+            Removal::PrEntry(path) => {
+                pr_entries.insert(path);
+                Ok(())
+            }
+            Removal::Agent(ref key) => match &store {
+                Some(store) => store.delete_agent(key),
+                None => Err(anyhow::anyhow!("State directory unavailable")),
+            },
+            Removal::Vm(ref name) => super::sandbox::delete_vm(name),
+            Removal::Container { runtime, ref name } => remove_container(runtime, name),
+        };
+        match result {
+            Ok(()) => {
+                removed += 1;
+                freed += item.bytes.unwrap_or(0);
+            }
+            Err(e) => failed.push((item.description, e)),
+        }
+    }
+
+    if !status_entries.is_empty() {
+        let mut statuses = crate::git::load_status_cache();
+        statuses.retain(|path, _| !status_entries.contains(path));
+        crate::git::save_status_cache(&statuses);
+    }
+    if !pr_entries.is_empty() {
+        let mut statuses = crate::github::load_pr_cache();
+        statuses.retain(|path, _| !pr_entries.contains(path));
+        crate::github::save_pr_cache(&statuses);
+    }
+    if let Ok(root) = crate::workflow::pane_logs_root() {
+        remove_empty_dirs(&root);
+    }
+
+    println!(
+        "\n✓ Removed {} item(s), freed {}",
+        removed,
+        format_size(freed)
+    );
+    if !failed.is_empty() {
+        eprintln!("\nFailed to remove {} item(s):", failed.len());
+        for (description, error) in &failed {
+            eprintln!("  - {}: {}", description, error);
+        }
+        anyhow::bail!("Some items could not be removed");
+    }
+    Ok(())
+}
+
+fn print_items(items: &[Item]) {
+    let mut current = None;
+    for item in items {
+        if current != Some(item.category) {
+            current = Some(item.category);
+            let in_category: Vec<&Item> = items
+                .iter()
+                .filter(|i| i.category == item.category)
+                .collect();
+            let bytes: u64 = in_category.iter().filter_map(|i| i.bytes).sum();
+            if current != items.first().map(|i| i.category) {
+                println!();
+            }
+            match bytes {
+                0 => println!("{} ({})", item.category.title(), in_category.len()),
+                bytes => println!(
+                    "{} ({}, {})",
+                    item.category.title(),
+                    in_category.len(),
+                    format_size(bytes)
+                ),
+            }
+        }
+        match item.bytes {
+            Some(bytes) => println!("  {:>6}  {}", format_size(bytes), item.description),
+            None => println!("  {:>6}  {}", "", item.description),
+        }
+    }
+}
+
+/// Entries of the git and PR status caches whose worktree no longer exists.
+fn status_cache_items() -> Vec<Item> {
+    let mut items: Vec<Item> = crate::git::load_status_cache()
+        .into_iter()
+        .filter(|(path, _)| !path.exists())
+        .map(|(path, status)| Item {
+            category: Category::StatusCache,
+            description: format!("{} (git status)", path.display()),
+            bytes: serde_json::to_string(&status).ok().map(|s| s.len() as u64),
+            removal: Removal::StatusEntry(path),
+        })
+        .collect();
+    items.extend(
+        crate::github::load_pr_cache()
+            .into_iter()
+            .filter(|(path, _)| !path.exists())
+            .map(|(path, prs)| Item {
+                category: Category::StatusCache,
+                description: format!("{} (pull requests)", path.display()),
+                bytes: serde_json::to_string(&prs).ok().map(|s| s.len() as u64),
+                removal: Removal::PrEntry(path),
+            }),
+    );
+    items.sort_by(|a, b| a.description.cmp(&b.description));
+    items
+}
+
+/// Transcript directories (`<root>/<project>/<handle>`) nothing was written
+/// to for `keep_days`.
+fn log_items(root: &Path, keep_days: u64, now: u64) -> Vec<Item> {
+    let cutoff = now.saturating_sub(keep_days * SECS_PER_DAY);
+    let mut items = Vec::new();
+    for project in read_dir_sorted(root) {
+        for dir in read_dir_sorted(&project) {
+            if !dir.is_dir() || newest_mtime(&dir) >= cutoff {
+                continue;
+            }
+            let name = dir.strip_prefix(root).unwrap_or(&dir);
+            items.push(Item {
+                category: Category::Logs,
+                description: format!(
+                    "{} (last written {} days ago)",
+                    name.display(),
+                    now.saturating_sub(newest_mtime(&dir)) / SECS_PER_DAY
+                ),
+                bytes: Some(dir_size(&dir)),
+                removal: Removal::Path(dir),
+            });
+        }
+    }
+    items
+}
+
+/// Agent state files whose pane is gone or no longer runs the agent. Only
+/// the running multiplexer's panes can be checked.
+fn agent_items() -> Vec<Item> {
+    let mux = create_backend(detect_backend());
+    if !mux.is_running().unwrap_or(false) {
+        return Vec::new();
+    }
+    let stale = StateStore::new().and_then(|store| store.stale_agents(mux.as_ref()));
+    match stale {
+        Ok(agents) => agents
+            .into_iter()
+            .map(|agent| Item {
+                category: Category::AgentState,
+                description: format!(
+                    "{} pane {} in {}",
+                    agent.pane_key.backend,
+                    agent.pane_key.pane_id,
+                    agent.workdir.display()
+                ),
+                bytes: None,
+                removal: Removal::Agent(agent.pane_key),
+            })
+            .collect(),
+        Err(e) => {
+            debug!(error = %e, "gc:failed to check agent state");
+            Vec::new()
+        }
+    }
+}
+
+/// Stopped `wm-*` Lima VMs and containers unused for `sandbox_days`.
+fn sandbox_items(config: &Config, gc: &GcConfig, now: u64) -> Vec<Item> {
+    let cutoff = now.saturating_sub(gc.sandbox_days() * SECS_PER_DAY);
+    let mut items = Vec::new();
+
+    if LimaInstance::is_lima_available() {
+        match super::sandbox::list_workmux_vms() {
+            Ok(vms) => {
+                for vm in vms {
+                    let last_used = vm.last_accessed.or(vm.created).map(secs_since_epoch);
+                    if vm.status != "Stopped" || last_used.is_none_or(|t| t >= cutoff) {
+                        continue;
+                    }
+                    items.push(Item {
+                        category: Category::Sandboxes,
+                        description: format!(
+                            "Lima VM {} (last used {} days ago)",
+                            vm.name,
+                            now.saturating_sub(last_used.unwrap_or(now)) / SECS_PER_DAY
+                        ),
+                        bytes: Some(dir_size(&vm.dir)),
+                        removal: Removal::Vm(vm.name),
+                    });
+                }
+            }
+            Err(e) => debug!(error = %e, "gc:failed to list Lima VMs"),
+        }
+    }
+
+    let runtime = match config.sandbox.runtime() {
+        SandboxRuntime::Podman => "podman",
+        SandboxRuntime::Docker => "docker",
+    };
+    if which::which(runtime).is_ok() {
+        for (name, finished) in stopped_containers(runtime) {
+            if finished >= cutoff {
+                continue;
+            }
+            items.push(Item {
+                category: Category::Sandboxes,
+                description: format!(
+                    "{} container {} (stopped {} days ago)",
+                    runtime,
+                    name,
+                    now.saturating_sub(finished) / SECS_PER_DAY
+                ),
+                bytes: None,
+                removal: Removal::Container { runtime, name },
+            });
+        }
+    }
+    items
+}
+
+/// Exited `wm-*` containers with the time they stopped.
+fn stopped_containers(runtime: &str) -> Vec<(String, u64)> {
+    let Ok(output) = Command::new(runtime)
+        .args([
+            "ps",
+            "-a",
+            "--filter",
+            "name=^wm-",
+            "--filter",
+            "status=exited",
+            "--format",
+            "{{.Names}}",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| name.starts_with("wm-"))
+        .filter_map(|name| {
+            let output = Command::new(runtime)
+                .args(["inspect", "--format", "{{.State.FinishedAt}}", name])
+                .output()
+                .ok()?;
+            let finished = parse_timestamp(String::from_utf8_lossy(&output.stdout).trim())?;
+            Some((name.to_string(), finished))
+        })
+        .collect()
+}
+
+fn remove_container(runtime: &str, name: &str) -> Result<()> {
+    let output = Command::new(runtime)
+        .args(["rm", name])
+        .output()
+        .with_context(|| format!("Failed to execute {} rm", runtime))?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Prompt files in `dir` older than `keep_days`.
+fn prompt_items(dir: &Path, keep_days: u64, now: u64) -> Vec<Item> {
+    let cutoff = now.saturating_sub(keep_days * SECS_PER_DAY);
+    read_dir_sorted(dir)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(PROMPT_FILE_PREFIX))
+                && path.is_file()
+                && mtime(path) < cutoff
+        })
+        .map(|path| Item {
+            category: Category::Prompts,
+            description: path.display().to_string(),
+            bytes: fs::metadata(&path).ok().map(|meta| meta.len()),
+            removal: Removal::Path(path),
+        })
+        .collect()
+}
+
+/// Remove empty project directories left under the transcripts root.
+fn remove_empty_dirs(root: &Path) {
+    for dir in read_dir_sorted(root) {
+        // Fails on directories that still have transcripts
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+fn read_dir_sorted(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn secs_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn now_secs() -> u64 {
+    secs_since_epoch(SystemTime::now())
+}
+
+fn mtime(path: &Path) -> u64 {
+    fs::symlink_metadata(path)
+        .and_then(|meta| meta.modified())
+        .map(secs_since_epoch)
+        .unwrap_or(0)
+}
+
+/// Latest modification time of `dir` and everything in it.
+fn newest_mtime(dir: &Path) -> u64 {
+    read_dir_sorted(dir)
+        .iter()
+        .map(|path| {
+            if path.is_dir() && !path.is_symlink() {
+                newest_mtime(path)
+            } else {
+                mtime(path)
+            }
+        })
+        .fold(mtime(dir), u64::max)
+}
+
+/// Total size of the files under `path`, without following symlinks.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    read_dir_sorted(path).iter().map(|p| dir_size(p)).sum()
+}
+
+/// Format a byte count as a short human-readable size.
+pub(crate) fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    if bytes >= GB {
+        format!("{:.1}G", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1}M", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{}K", bytes / KB)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+/// Seconds since the epoch of a container runtime timestamp: RFC 3339 from
+/// docker (`2026-01-02T03:04:05.123Z`) or Go's default format from podman
+/// (`2026-01-02 03:04:05.123 +0000 UTC`).
+fn parse_timestamp(s: &str) -> Option<u64> {
+    let date = s.get(..10)?;
+    let time = s.get(11..19)?;
+    let mut date_parts = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (
+        date_parts.next()??,
+        date_parts.next()??,
+        date_parts.next()??,
+    );
+    let mut time_parts = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (
+        time_parts.next()??,
+        time_parts.next()??,
+        time_parts.next()??,
+    );
+
+    // Skip fractional seconds, then read the UTC offset if there is one
+    let rest = s[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let rest = rest.trim_start();
+    let offset = match rest.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let digits: String = rest[1..]
+                .chars()
+                .filter(char::is_ascii_digit)
+                .take(4)
+                .collect();
+            let hours: i64 = digits.get(..2)?.parse().ok()?;
+            let minutes: i64 = digits.get(2..4)?.parse().ok()?;
+            let offset = hours * 3600 + minutes * 60;
+            if sign == '-' { -offset } else { offset }
+        }
+        _ => 0,
+    };
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * SECS_PER_DAY as i64 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs).ok()
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn container_timestamps_are_parsed() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_timestamp("2026-01-02T03:04:05.123456789Z"),
+            Some(1_767_323_045)
+        );
+        assert_eq!(
+            parse_timestamp("2026-01-02 05:04:05.123 +0200 CEST"),
+            Some(1_767_323_045)
+        );
+        assert_eq!(
+            parse_timestamp("2026-01-01T22:04:05-05:00"),
+            Some(1_767_323_045)
+        );
+        // Never finished
+        assert_eq!(parse_timestamp("0001-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("garbage"), None);
+    }
+
+    #[test]
+    fn old_logs_and_prompts_are_collected() {
+        let temp = TempDir::new().unwrap();
+        let logs = temp.path().join("logs");
+        fs::create_dir_all(logs.join("app/feature")).unwrap();
+        fs::write(logs.join("app/feature/pane-0.log"), "output").unwrap();
+        let tmp = temp.path().join("tmp");
+        fs::create_dir_all(&tmp).unwrap();
+        fs::write(tmp.join("workmux-prompt-feature.md"), "prompt").unwrap();
+        fs::write(tmp.join("other.md"), "not ours").unwrap();
+
+        // Everything was just written: nothing is old enough
+        let now = now_secs();
+        assert!(log_items(&logs, 14, now).is_empty());
+        assert!(prompt_items(&tmp, 7, now).is_empty());
+
+        let later = now + 30 * SECS_PER_DAY;
+        let items = log_items(&logs, 14, later);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].bytes, Some(6));
+        assert_eq!(items[0].removal, Removal::Path(logs.join("app/feature")));
+        assert!(
+            items[0]
+                .description
+                .starts_with("app/feature (last written 30")
+        );
+
+        let items = prompt_items(&tmp, 7, later);
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].removal,
+            Removal::Path(tmp.join("workmux-prompt-feature.md"))
+        );
+    }
+
+    #[test]
+    fn sizes_are_formatted() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(2048), "2K");
+        assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5M");
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0G");
+    }
+}
@@ -0,0 +1,29 @@
+//! `workmux git-query`: read-only git information about the repository.
+//! Inside a sandbox guest the host answers, so agents can see their base
+//! branch and other refs without access to the host repository.
+
+use anyhow::{Result, bail};
+
+use crate::sandbox::git_query::{self, GitQuery};
+
+pub fn run(query: GitQuery) -> Result<()> {
+    let output = if crate::sandbox::guest::is_sandbox_guest() {
+        run_via_rpc(query)?
+    } else {
+        git_query::run(&query, &std::env::current_dir()?)?
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// Ask the host supervisor to answer the query.
+fn run_via_rpc(query: GitQuery) -> Result<String> {
+    use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
+
+    let mut client = RpcClient::from_env()?;
+    match client.call(&RpcRequest::GitQuery { query })? {
+        RpcResponse::QueryOutput { data } => Ok(data),
+        RpcResponse::Error { message } => bail!("{}", message),
+        other => bail!("Unexpected RPC response: {:?}", other),
+    }
+}
@@ -0,0 +1,179 @@
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use console::style;
+
+use crate::command::list::format_agent_status;
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::util::canon_or_self;
+use crate::{config, vcs, workflow};
+
+/// Tool used to search a worktree. Both skip gitignored files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Searcher {
+    Ripgrep,
+    GitGrep,
+}
+
+impl Searcher {
+    fn detect() -> Self {
+        if which::which("rg").is_ok() {
+            Self::Ripgrep
+        } else {
+            Self::GitGrep
+        }
+    }
+
+    /// Program and arguments that print `path:line:text` for each match.
+    fn command(
+        self,
+        pattern: &str,
+        ignore_case: bool,
+        fixed_strings: bool,
+        color: bool,
+    ) -> (&'static str, Vec<String>) {
+        let color = if color {
+            "--color=always"
+        } else {
+            "--color=never"
+        };
+        let mut args: Vec<String> = match self {
+            Self::Ripgrep => vec!["--no-heading", "--line-number", color],
+            // --untracked still skips ignored files
+            Self::GitGrep => vec!["grep", "--line-number", "-I", "--untracked", "-E", color],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+        if ignore_case {
+            args.push("--ignore-case".to_string());
+        }
+        if fixed_strings {
+            args.push("--fixed-strings".to_string());
+        }
+        args.push("-e".to_string());
+        args.push(pattern.to_string());
+        let program = match self {
+            Self::Ripgrep => "rg",
+            Self::GitGrep => "git",
+        };
+        (program, args)
+    }
+}
+
+pub fn run(pattern: &str, ignore_case: bool, fixed_strings: bool, active: bool) -> Result<()> {
+    let config = config::Config::load(None)?;
+    let mux = create_backend(detect_backend());
+    let vcs = vcs::detect_vcs()?;
+    let main_root = canon_or_self(&vcs.get_main_workspace_root()?);
+    let worktrees = workflow::list(&config, mux.as_ref(), vcs.as_ref(), false, &[])?;
+
+    let searcher = Searcher::detect();
+    let use_color = std::io::stdout().is_terminal();
+    let (program, args) = searcher.command(pattern, ignore_case, fixed_strings, use_color);
+
+    let mut searched = 0;
+    let mut matched = 0;
+    for wt in worktrees {
+        if canon_or_self(&wt.path) == main_root {
+            continue;
+        }
+        let has_agents = wt
+            .agent_status
+            .as_ref()
+            .is_some_and(|s| !s.statuses.is_empty());
+        if active && !has_agents {
+            continue;
+        }
+        searched += 1;
+
+        let Some(output) = search(program, &args, &wt.path)? else {
+            continue;
+        };
+        matched += 1;
+
+        let handle = wt
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let status = format_agent_status(wt.agent_status.as_ref(), &config, use_color);
+        if matched > 1 {
+            println!();
+        }
+        println!(
+            "{} {} {}",
+            style(handle).bold(),
+            style(format!("({})", wt.branch)).dim(),
+            status
+        );
+        for line in output.lines() {
+            println!("  {}", line);
+        }
+    }
+
+    if searched == 0 {
+        if active {
+            println!("No worktrees with active agents");
+        } else {
+            println!("No worktrees found");
+        }
+    } else if matched == 0 {
+        println!("No matches in {} worktree(s)", searched);
+    }
+    Ok(())
+}
+
+/// Run the search in `dir`. Returns None when nothing matched.
+fn search(program: &str, args: &[String], dir: &Path) -> Result<Option<String>> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned())),
+        Some(1) => Ok(None),
+        _ => bail!(
+            "{} failed in {}: {}",
+            program,
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ripgrep_command() {
+        let (program, args) = Searcher::Ripgrep.command("TODO", true, false, false);
+        assert_eq!(program, "rg");
+        assert_eq!(
+            args,
+            [
+                "--no-heading",
+                "--line-number",
+                "--color=never",
+                "--ignore-case",
+                "-e",
+                "TODO"
+            ]
+        );
+    }
+
+    #[test]
+    fn git_grep_command_includes_untracked_files() {
+        let (program, args) = Searcher::GitGrep.command("-v", false, true, true);
+        assert_eq!(program, "git");
+        assert_eq!(args[0], "grep");
+        assert!(args.contains(&"--untracked".to_string()));
+        assert!(args.contains(&"--color=always".to_string()));
+        // The pattern follows -e so it can't be read as an option
+        assert_eq!(&args[args.len() - 3..], ["--fixed-strings", "-e", "-v"]);
+    }
+}
@@ -0,0 +1,268 @@
+//! Commands for named groups of worktrees.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+use tabled::{
+    Table, Tabled,
+    settings::{Padding, Style, object::Columns},
+};
+
+use crate::multiplexer::{AgentStatus, MuxHandle, create_backend, detect_backend};
+use crate::state::StateStore;
+use crate::workflow::group::{self, Member};
+use crate::workflow::{SetupOptions, WorkflowContext};
+use crate::{config, progress, vcs, workflow};
+
+/// Handle a member resolves to: its worktree directory name.
+fn member_handle(member: &Member) -> Option<String> {
+    member
+        .path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+pub fn run_add(name: &str, handles: &[String]) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    for handle in handles {
+        if vcs.find_workspace(handle).is_err() {
+            progress::warn(format!(
+                "no worktree named '{}' yet; it joins the group once created",
+                handle
+            ));
+        }
+    }
+    let added = group::add_members(vcs.as_ref(), name, handles)?;
+    if added.is_empty() {
+        println!("Group '{}' already has these worktrees", name);
+    } else {
+        println!("✓ Added {} to group '{}'", added.join(", "), name);
+    }
+    Ok(())
+}
+
+pub fn run_remove(name: &str, handles: &[String]) -> Result<()> {
+    let config = config::Config::load(None)?;
+    let vcs = vcs::detect_vcs()?;
+    group::remove_members(&config, vcs.as_ref(), name, handles)?;
+    if handles.is_empty() {
+        println!("✓ Removed group '{}'", name);
+    } else {
+        println!("✓ Removed {} from group '{}'", handles.join(", "), name);
+    }
+    Ok(())
+}
+
+pub fn run_list() -> Result<()> {
+    let config = config::Config::load(None)?;
+    let vcs = vcs::detect_vcs()?;
+    let groups = group::list(&config, vcs.as_ref());
+    if groups.is_empty() {
+        println!("No groups defined");
+        return Ok(());
+    }
+    for group in groups {
+        println!("{}: {}", group.name, group.members.join(", "));
+    }
+    Ok(())
+}
+
+/// Open a window for every member that has a worktree and no open window.
+/// Windows are opened in the background.
+pub fn run_open(name: &str) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let mut context = WorkflowContext::new(config, mux, config_location)?;
+    let group = group::find(&context.config, context.vcs.as_ref(), name)?;
+
+    let mut failed = Vec::new();
+    for member in group::resolve_members(context.vcs.as_ref(), &group) {
+        let Some(handle) = member_handle(&member) else {
+            progress::warn(format!("no worktree for '{}'; skipping", member.handle));
+            continue;
+        };
+        let mode = context.vcs.get_workspace_mode(&handle);
+        super::announce_prefix_qualification(&mut context, &handle, mode)?;
+        if MuxHandle::new(context.mux.as_ref(), mode, &context.prefix, &handle).exists()? {
+            progress::info(format!("'{}' is already open", handle));
+            continue;
+        }
+
+        let mut options = SetupOptions::new(false, false, true);
+        options.mode = mode;
+        options.focus_window = false;
+        match workflow::open(&handle, &context, options, false) {
+            Ok(_) => progress::success(format!("Opened '{}'", handle)),
+            Err(e) => {
+                progress::warn(format!("failed to open '{}': {:#}", handle, e));
+                failed.push(handle);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "Could not open {} of group '{}'",
+            failed.join(", "),
+            group.name
+        );
+    }
+    Ok(())
+}
+
+/// Close the window of every member that has one. Worktrees are kept.
+pub fn run_close(name: &str) -> Result<()> {
+    let config = config::Config::load(None)?;
+    let mux = create_backend(detect_backend());
+    let vcs = vcs::detect_vcs()?;
+    let group = group::find(&config, vcs.as_ref(), name)?;
+    let prefix = &config.window_prefix_for(vcs.as_ref());
+
+    // Close the window this runs in last, so the rest get closed first
+    let mut open = Vec::new();
+    for member in group::resolve_members(vcs.as_ref(), &group) {
+        let Some(handle) = member_handle(&member) else {
+            continue;
+        };
+        let target = MuxHandle::new(
+            mux.as_ref(),
+            vcs.get_workspace_mode(&handle),
+            prefix,
+            &handle,
+        );
+        if target.exists()? {
+            let is_current = target.current_name()?.as_deref() == Some(target.full_name().as_str());
+            open.push((is_current, handle));
+        }
+    }
+    if open.is_empty() {
+        println!("No worktrees in group '{}' are open", group.name);
+        return Ok(());
+    }
+    open.sort_by_key(|(is_current, _)| *is_current);
+
+    for (_, handle) in open {
+        super::close::run(Some(&handle))?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct MemberStatus {
+    worktree: String,
+    exists: bool,
+    open: bool,
+    status: Option<String>,
+    agents: usize,
+}
+
+#[derive(Serialize)]
+struct GroupStatus {
+    group: String,
+    status: Option<String>,
+    members: Vec<MemberStatus>,
+}
+
+#[derive(Tabled)]
+struct MemberRow {
+    #[tabled(rename = "WORKTREE")]
+    worktree: String,
+    #[tabled(rename = "WINDOW")]
+    window: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "AGENTS")]
+    agents: String,
+}
+
+fn status_name(status: AgentStatus) -> String {
+    match status {
+        AgentStatus::Working => "working",
+        AgentStatus::Waiting => "waiting",
+        AgentStatus::Done => "done",
+        AgentStatus::Unknown => "unknown",
+    }
+    .to_string()
+}
+
+/// Show each member's window and agent status, and the group's worst-of status.
+pub fn run_status(name: &str, json: bool) -> Result<()> {
+    let config = config::Config::load(None)?;
+    let mux = create_backend(detect_backend());
+    let vcs = vcs::detect_vcs()?;
+    let group = group::find(&config, vcs.as_ref(), name)?;
+    let prefix = &config.window_prefix_for(vcs.as_ref());
+
+    let agents = if mux.is_running().unwrap_or(false) {
+        StateStore::new().and_then(|store| store.load_reconciled_agents(mux.as_ref()))?
+    } else {
+        Vec::new()
+    };
+
+    let mut all_statuses = Vec::new();
+    let mut members = Vec::new();
+    for member in group::resolve_members(vcs.as_ref(), &group) {
+        let Some(path) = &member.path else {
+            members.push(MemberStatus {
+                worktree: member.handle,
+                exists: false,
+                open: false,
+                status: None,
+                agents: 0,
+            });
+            continue;
+        };
+        let handle = member_handle(&member).unwrap_or(member.handle.clone());
+        let mode = vcs.get_workspace_mode(&handle);
+        let open = MuxHandle::new(mux.as_ref(), mode, prefix, &handle)
+            .exists()
+            .unwrap_or(false);
+        let matching = workflow::match_agents_to_worktree(&agents, path);
+        let statuses: Vec<AgentStatus> = matching.iter().filter_map(|a| a.status).collect();
+        all_statuses.extend(statuses.iter().copied());
+        members.push(MemberStatus {
+            worktree: handle,
+            exists: true,
+            open,
+            status: AgentStatus::rollup(statuses).map(status_name),
+            agents: matching.len(),
+        });
+    }
+    let status = GroupStatus {
+        group: group.name,
+        status: AgentStatus::rollup(all_statuses).map(status_name),
+        members,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    let rows: Vec<MemberRow> = status
+        .members
+        .iter()
+        .map(|m| MemberRow {
+            worktree: m.worktree.clone(),
+            window: match (m.exists, m.open) {
+                (false, _) => "no worktree",
+                (true, true) => "open",
+                (true, false) => "closed",
+            }
+            .to_string(),
+            status: m.status.clone().unwrap_or_else(|| "-".to_string()),
+            agents: m.agents.to_string(),
+        })
+        .collect();
+    let mut table = Table::new(rows);
+    table
+        .with(Style::blank())
+        .modify(Columns::new(..), Padding::new(0, 1, 0, 0));
+    println!("{}", table);
+    println!(
+        "\nGroup '{}': {}",
+        status.group,
+        status.status.as_deref().unwrap_or("no agent status")
+    );
+    Ok(())
+}
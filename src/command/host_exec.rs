@@ -1,7 +1,9 @@
 //! Execute a command on the host via RPC.
 //!
 //! Used by guest shims to transparently proxy toolchain commands
-//! (just, cargo, npm, etc.) to the host machine.
+//! (just, cargo, npm, etc.) to the host machine, and by
+//! `workmux request-exec` to run a command outside the allowlist once the
+//! user approves it.
 
 use anyhow::{Result, bail};
 use std::io::Write;
@@ -15,16 +17,31 @@ pub fn run(command: &str, args: &[String]) -> Result<i32> {
         bail!("host-exec only works inside a sandbox guest (WM_SANDBOX_GUEST=1)");
     }
 
-    let mut client = RpcClient::from_env()?;
+    execute(RpcRequest::Exec {
+        command: command.to_string(),
+        args: args.to_vec(),
+    })
+}
 
-    // Send exec request
-    let request = RpcRequest::Exec {
+/// Ask the user to approve a command in the dashboard, then run it on the
+/// host like [`run`]. Returns the remote process exit code, or 126 when the
+/// command wasn't approved.
+pub fn request(command: &str, args: &[String]) -> Result<i32> {
+    if !crate::sandbox::guest::is_sandbox_guest() {
+        bail!("request-exec only works inside a sandbox guest (WM_SANDBOX_GUEST=1)");
+    }
+
+    execute(RpcRequest::RequestExec {
         command: command.to_string(),
         args: args.to_vec(),
-    };
+    })
+}
+
+/// Send an exec request and stream responses until ExecExit.
+fn execute(request: RpcRequest) -> Result<i32> {
+    let mut client = RpcClient::from_env()?;
     client.send(&request)?;
 
-    // Stream responses until ExecExit
     let mut stdout = std::io::stdout().lock();
     let mut stderr = std::io::stderr().lock();
 
@@ -42,6 +59,9 @@ pub fn run(command: &str, args: &[String]) -> Result<i32> {
             RpcResponse::ExecExit { code } => {
                 return Ok(code);
             }
+            RpcResponse::Output { message } => {
+                writeln!(stderr, "{}", message)?;
+            }
             RpcResponse::Error { message } => {
                 bail!("Host exec failed: {}", message);
             }
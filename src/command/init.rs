@@ -0,0 +1,354 @@
+//! `workmux init`: generate a starter `.workmux.yaml` for the current
+//! directory based on what the project looks like.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+
+use crate::config::Config;
+
+const CONFIG_FILE: &str = ".workmux.yaml";
+
+/// Sandbox backend to enable in the generated config.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum InitSandbox {
+    /// Lima VM
+    Lima,
+    /// Docker/Podman container
+    Container,
+}
+
+impl InitSandbox {
+    fn name(self) -> &'static str {
+        match self {
+            InitSandbox::Lima => "lima",
+            InitSandbox::Container => "container",
+        }
+    }
+}
+
+/// Main language of the project, with the tool that manages dependencies.
+#[derive(Debug, Clone, PartialEq)]
+enum Language {
+    Rust,
+    Node { manager: &'static str },
+    Python { manager: &'static str },
+    Go,
+}
+
+/// Dev environment definition that commands should run inside.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Toolchain {
+    Devbox,
+    Flake,
+}
+
+/// What `init` found out about the project.
+#[derive(Debug, Default)]
+struct Project {
+    language: Option<Language>,
+    toolchain: Option<Toolchain>,
+    /// Installs dependencies in a new worktree
+    install_command: Option<String>,
+    /// Starts a development server
+    dev_command: Option<String>,
+    test_command: Option<String>,
+    has_env_file: bool,
+}
+
+/// Whether a justfile or Makefile has a recipe/target named `name`.
+fn has_recipe(contents: &str, name: &str) -> bool {
+    contents.lines().any(|line| {
+        line.strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with(':') || rest.starts_with(" :"))
+    })
+}
+
+fn package_script(package_json: &serde_json::Value, name: &str) -> bool {
+    package_json["scripts"][name].is_string()
+}
+
+fn detect(dir: &Path) -> Project {
+    let exists = |name: &str| dir.join(name).exists();
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap_or_default();
+    let mut project = Project {
+        has_env_file: exists(".env"),
+        ..Default::default()
+    };
+
+    if exists("devbox.json") {
+        project.toolchain = Some(Toolchain::Devbox);
+    } else if exists("flake.nix") {
+        project.toolchain = Some(Toolchain::Flake);
+    }
+
+    if exists("Cargo.toml") {
+        project.language = Some(Language::Rust);
+        project.test_command = Some("cargo test".to_string());
+    } else if exists("package.json") {
+        let manager = if exists("pnpm-lock.yaml") {
+            "pnpm"
+        } else if exists("yarn.lock") {
+            "yarn"
+        } else if exists("bun.lock") || exists("bun.lockb") {
+            "bun"
+        } else {
+            "npm"
+        };
+        let package_json: serde_json::Value =
+            serde_json::from_str(&read("package.json")).unwrap_or_default();
+        if package_script(&package_json, "dev") {
+            project.dev_command = Some(format!("{} run dev", manager));
+        }
+        if package_script(&package_json, "test") {
+            project.test_command = Some(format!("{} test", manager));
+        }
+        project.install_command = Some(format!("{} install", manager));
+        project.language = Some(Language::Node { manager });
+    } else if exists("pyproject.toml") || exists("requirements.txt") {
+        let manager = if exists("uv.lock") {
+            "uv"
+        } else if exists("poetry.lock") {
+            "poetry"
+        } else {
+            "pip"
+        };
+        let uses_pytest = exists("pytest.ini")
+            || read("pyproject.toml").contains("pytest")
+            || read("requirements.txt").contains("pytest");
+        if uses_pytest {
+            project.test_command = Some(match manager {
+                "pip" => "pytest".to_string(),
+                _ => format!("{} run pytest", manager),
+            });
+        }
+        // Plain pip would install into whatever environment is active
+        project.install_command = match manager {
+            "uv" => Some("uv sync".to_string()),
+            "poetry" => Some("poetry install".to_string()),
+            _ => None,
+        };
+        project.language = Some(Language::Python { manager });
+    } else if exists("go.mod") {
+        project.language = Some(Language::Go);
+        project.install_command = Some("go mod download".to_string());
+        project.test_command = Some("go test ./...".to_string());
+    }
+
+    // A task runner recipe is what the project itself uses to run tests
+    let justfile = read("justfile") + &read("Justfile");
+    if has_recipe(&justfile, "test") {
+        project.test_command = Some("just test".to_string());
+    } else if has_recipe(&read("Makefile"), "test") {
+        project.test_command = Some("make test".to_string());
+    }
+
+    project
+}
+
+impl Project {
+    fn description(&self) -> Option<String> {
+        let language = match self.language.as_ref()? {
+            Language::Rust => "Rust (cargo)".to_string(),
+            Language::Node { manager } => format!("Node.js ({})", manager),
+            Language::Python { manager } => format!("Python ({})", manager),
+            Language::Go => "Go".to_string(),
+        };
+        Some(match self.toolchain {
+            Some(Toolchain::Devbox) => format!("{} with devbox.json", language),
+            Some(Toolchain::Flake) => format!("{} with flake.nix", language),
+            None => language,
+        })
+    }
+
+    /// Wrap a command so it runs inside the project's dev environment.
+    fn in_toolchain(&self, command: &str) -> String {
+        match self.toolchain {
+            Some(Toolchain::Devbox) => format!("devbox run -- {}", command),
+            Some(Toolchain::Flake) => format!("nix develop -c {}", command),
+            None => command.to_string(),
+        }
+    }
+}
+
+/// Render the generated config. `minimal` leaves out explanations and the
+/// reference of all other options.
+fn render(project: &Project, minimal: bool, sandbox: Option<InitSandbox>) -> String {
+    let mut out = String::from("# workmux project configuration\n");
+    if let Some(description) = project.description() {
+        out.push_str(&format!("# Detected: {}\n", description));
+    }
+    if !minimal {
+        out.push_str("# For global settings, edit ~/.config/workmux/config.yaml\n");
+    }
+    let comment = |out: &mut String, text: &str| {
+        if !minimal {
+            out.push_str(&format!("\n# {}\n", text));
+        }
+    };
+
+    comment(
+        &mut out,
+        "Window layout: the agent, with a dev server or a shell below it.",
+    );
+    out.push_str("panes:\n  - command: <agent>\n    focus: true\n");
+    match &project.dev_command {
+        Some(dev) => out.push_str(&format!(
+            "  - command: {}\n    split: horizontal\n",
+            project.in_toolchain(dev)
+        )),
+        None => out.push_str("  - split: horizontal\n"),
+    }
+
+    if let Some(install) = &project.install_command {
+        comment(
+            &mut out,
+            "Install dependencies before the window opens. Runs in the new worktree.",
+        );
+        out.push_str(&format!(
+            "post_create:\n  - {}\n",
+            project.in_toolchain(install)
+        ));
+    }
+
+    if let Some(test) = &project.test_command {
+        comment(&mut out, "Run the tests before `workmux merge`.");
+        out.push_str(&format!("pre_merge:\n  - {}\n", project.in_toolchain(test)));
+    }
+
+    if project.has_env_file {
+        comment(&mut out, "Copy gitignored files into new worktrees.");
+        out.push_str("files:\n  copy:\n    - .env\n");
+    }
+
+    if let Some(backend) = sandbox {
+        comment(
+            &mut out,
+            "Run agents in a sandbox. Credentials and images are configured in\n\
+             # the global config; see `workmux docs` for the sandbox guide.",
+        );
+        out.push_str(&format!(
+            "sandbox:\n  enabled: true\n  backend: {}\n",
+            backend.name()
+        ));
+        if !minimal {
+            if project.toolchain.is_some() {
+                out.push_str(
+                    "  # Commands in the sandbox run inside the detected dev environment.\n  \
+                     # toolchain: auto\n",
+                );
+            }
+            if backend == InitSandbox::Lima {
+                out.push_str("  # lima:\n  #   isolation: project\n");
+            }
+        }
+    }
+
+    if !minimal {
+        out.push_str(
+            "\n# All options below are commented out - uncomment to override defaults.\n\n",
+        );
+        out.push_str(Config::example_config());
+    }
+    out
+}
+
+pub fn run(minimal: bool, sandbox: Option<InitSandbox>) -> Result<()> {
+    let config_path = Path::new(CONFIG_FILE);
+    if config_path.exists() {
+        bail!(
+            "{} already exists. Remove it first if you want to regenerate it.",
+            CONFIG_FILE
+        );
+    }
+
+    let project = detect(&std::env::current_dir()?);
+    fs::write(config_path, render(&project, minimal, sandbox))?;
+
+    println!("✓ Created {}", CONFIG_FILE);
+    if let Some(description) = project.description() {
+        println!("  Detected {}", description);
+    }
+    println!("\nThis file provides project-specific overrides.");
+    println!("For global settings, edit ~/.config/workmux/config.yaml");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> Project {
+        let tmp = tempfile::tempdir().unwrap();
+        for (name, contents) in files {
+            fs::write(tmp.path().join(name), contents).unwrap();
+        }
+        detect(tmp.path())
+    }
+
+    fn parse(yaml: &str) -> Config {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn detects_node_package_manager_and_scripts() {
+        let project = project(&[
+            (
+                "package.json",
+                r#"{"scripts": {"dev": "vite", "test": "vitest"}}"#,
+            ),
+            ("pnpm-lock.yaml", ""),
+            (".env", "KEY=1"),
+        ]);
+        assert_eq!(project.language, Some(Language::Node { manager: "pnpm" }));
+        assert_eq!(project.install_command.as_deref(), Some("pnpm install"));
+        assert_eq!(project.dev_command.as_deref(), Some("pnpm run dev"));
+        assert_eq!(project.test_command.as_deref(), Some("pnpm test"));
+        assert!(project.has_env_file);
+    }
+
+    #[test]
+    fn task_runner_recipe_overrides_language_test_command() {
+        let project = project(&[
+            ("Cargo.toml", ""),
+            (
+                "justfile",
+                "build:\n    cargo build\ntest:\n    cargo nextest run\n",
+            ),
+        ]);
+        assert_eq!(project.test_command.as_deref(), Some("just test"));
+        assert_eq!(project.install_command, None);
+    }
+
+    #[test]
+    fn toolchain_wraps_commands() {
+        let project = project(&[("go.mod", ""), ("devbox.json", "{}")]);
+        let yaml = render(&project, true, None);
+        assert!(yaml.contains("post_create:\n  - devbox run -- go mod download\n"));
+        assert!(yaml.contains("pre_merge:\n  - devbox run -- go test ./...\n"));
+        assert_eq!(
+            project.description().as_deref(),
+            Some("Go with devbox.json")
+        );
+    }
+
+    #[test]
+    fn generated_config_is_valid() {
+        let node = project(&[
+            ("package.json", r#"{"scripts": {"dev": "next dev"}}"#),
+            (".env", ""),
+        ]);
+        let config = parse(&render(&node, false, Some(InitSandbox::Lima)));
+        assert_eq!(config.post_create.unwrap()[0].command(), "npm install");
+        assert!(config.sandbox.is_enabled());
+        assert_eq!(config.panes.unwrap().len(), 2);
+
+        let minimal = render(&Project::default(), true, Some(InitSandbox::Container));
+        assert!(!minimal.contains("All options below"));
+        let config = parse(&minimal);
+        assert!(config.pre_merge.is_none());
+        assert!(config.sandbox.is_enabled());
+    }
+}
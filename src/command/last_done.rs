@@ -10,6 +10,22 @@ use crate::state::StateStore;
 /// one with the most recent timestamp. Cycles through completed agents on
 /// repeated invocations.
 pub fn run() -> Result<()> {
+    cycle(AgentStatus::Done)
+}
+
+/// Switch to the agent that has been waiting for input the longest.
+/// Cycles through waiting agents on repeated invocations.
+pub fn run_waiting() -> Result<()> {
+    cycle(AgentStatus::Waiting)
+}
+
+/// Switch to the next agent with `status`: done agents most recent first,
+/// waiting agents longest waiting first.
+fn cycle(status: AgentStatus) -> Result<()> {
+    let label = match status {
+        AgentStatus::Waiting => "waiting",
+        _ => "completed",
+    };
     let mux = create_backend(detect_backend());
     let store = StateStore::new()?;
 
@@ -17,46 +33,49 @@ pub fn run() -> Result<()> {
     // This avoids O(n) tmux queries. Dead panes are handled during switch.
     let agents = store.list_all_agents()?;
 
-    // Filter to done agents for current backend/instance
+    // Filter to agents with the status for current backend/instance
     let backend_name = mux.name();
     let instance_id = mux.instance_id();
-    let mut done_agents: Vec<_> = agents
+    let mut matching: Vec<_> = agents
         .into_iter()
         .filter(|a| {
-            a.status == Some(AgentStatus::Done)
+            a.status == Some(status)
                 && a.pane_key.backend == backend_name
                 && a.pane_key.instance == instance_id
         })
         .collect();
 
-    debug!(count = done_agents.len(), "done agents");
+    debug!(count = matching.len(), status = label, "agents");
 
-    if done_agents.is_empty() {
-        println!("No completed agents found");
+    if matching.is_empty() {
+        println!("No {} agents found", label);
         return Ok(());
     }
 
-    // Sort by timestamp descending (most recent first)
-    done_agents.sort_by(|a, b| b.status_ts.cmp(&a.status_ts));
+    if status == AgentStatus::Waiting {
+        // Longest waiting first
+        matching.sort_by_key(|a| a.status_ts);
+    } else {
+        // Sort by timestamp descending (most recent first)
+        matching.sort_by(|a, b| b.status_ts.cmp(&a.status_ts));
+    }
 
     // Get current pane to determine where we are in the cycle
     // Use active_pane_id() instead of current_pane_id() - env var is stale in run-shell
     let current_pane = mux.active_pane_id();
-    let current_idx = current_pane.as_ref().and_then(|current| {
-        done_agents
-            .iter()
-            .position(|a| &a.pane_key.pane_id == current)
-    });
+    let current_idx = current_pane
+        .as_ref()
+        .and_then(|current| matching.iter().position(|a| &a.pane_key.pane_id == current));
 
     let start_idx = match current_idx {
-        Some(idx) => (idx + 1) % done_agents.len(),
+        Some(idx) => (idx + 1) % matching.len(),
         None => 0,
     };
 
     // Try to switch, skipping dead panes
-    for i in 0..done_agents.len() {
-        let idx = (start_idx + i) % done_agents.len();
-        let pane_id = &done_agents[idx].pane_key.pane_id;
+    for i in 0..matching.len() {
+        let idx = (start_idx + i) % matching.len();
+        let pane_id = &matching[idx].pane_key.pane_id;
 
         if mux.switch_to_pane(pane_id).is_ok() {
             return Ok(());
@@ -64,6 +83,6 @@ pub fn run() -> Result<()> {
         debug!(pane_id, "pane dead, trying next");
     }
 
-    println!("No active completed agents found");
+    println!("No active {} agents found", label);
     Ok(())
 }
@@ -0,0 +1,86 @@
+//! `workmux layout preview`: draw a pane layout before creating anything.
+
+use anyhow::{Context, Result};
+
+use crate::config::{Config, PaneConfig};
+use crate::multiplexer::layout::{self, MinSize};
+
+/// Size used when the terminal's can't be read.
+const FALLBACK_SIZE: (u16, u16) = (120, 30);
+
+pub fn run_preview(name: Option<&str>, width: Option<u16>, height: Option<u16>) -> Result<()> {
+    let mut config = Config::load(None)?;
+    if let Some(name) = name {
+        config.apply_layout(name)?;
+    }
+
+    // Leave room for the frame and the lines printed around it
+    let (columns, rows) = crossterm::terminal::size().unwrap_or(FALLBACK_SIZE);
+    let width = width.unwrap_or(columns.saturating_sub(2));
+    let height = height.unwrap_or(rows.saturating_sub(4));
+
+    let windows: Vec<(Option<&str>, Vec<PaneConfig>)> = match &config.windows {
+        Some(windows) => windows
+            .iter()
+            .map(|w| {
+                let panes = w
+                    .panes
+                    .clone()
+                    .unwrap_or_else(|| vec![PaneConfig::default()]);
+                (w.name.as_deref(), panes)
+            })
+            .collect(),
+        None => vec![(None, config.panes.clone().unwrap_or_default())],
+    };
+
+    let min = MinSize::from_config(&config);
+    for (i, (window, panes)) in windows.iter().enumerate() {
+        if config.windows.is_some() {
+            println!("Window {}: {}", i, window.unwrap_or("unnamed"));
+        }
+        if panes.is_empty() {
+            println!("No panes configured");
+            continue;
+        }
+        let fitted = layout::fit(panes, width, height, min)
+            .with_context(|| format!("The layout doesn't fit in {}x{}", width, height))?;
+        let labels: Vec<Vec<String>> = fitted
+            .panes
+            .iter()
+            .zip(&fitted.rects)
+            .enumerate()
+            .map(|(i, (pane, rect))| label(i, pane, rect))
+            .collect();
+        println!("{}", layout::render(&fitted.rects, &labels, width, height));
+        for &i in &fitted.resized {
+            let pane = &fitted.panes[i];
+            println!(
+                "Pane {} resized to {} {} to keep panes at least {}x{}",
+                i,
+                pane.size.unwrap_or_default(),
+                pane.split.as_ref().map_or("cells", layout::unit),
+                min.width,
+                min.height
+            );
+        }
+        if !fitted.cramped.is_empty() {
+            println!(
+                "Panes {:?} stay below {}x{}: the window is too small",
+                fitted.cramped, min.width, min.height
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Lines shown inside pane `index`.
+fn label(index: usize, pane: &PaneConfig, rect: &layout::Rect) -> Vec<String> {
+    let mut lines = vec![
+        format!("{}: {}", index, pane.command.as_deref().unwrap_or("shell")),
+        format!("{}x{}", rect.width, rect.height),
+    ];
+    if pane.focus {
+        lines.push("focus".to_string());
+    }
+    lines
+}
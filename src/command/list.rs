@@ -3,7 +3,7 @@ use std::io::IsTerminal;
 use crate::config;
 use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
 use crate::workflow::types::AgentStatusSummary;
-use crate::{nerdfont, workflow};
+use crate::{ansi, nerdfont, plugin, workflow};
 use anyhow::Result;
 use pathdiff::diff_paths;
 use tabled::{
@@ -25,9 +25,12 @@ struct WorktreeRow {
     unmerged_status: String,
     #[tabled(rename = "PATH")]
     path_str: String,
+    #[tabled(rename = "NOTES")]
+    notes: String,
 }
 
-fn format_pr_status(pr_info: Option<crate::github::PrSummary>) -> String {
+/// PR number and state icon, the number linked to the PR when `links`.
+fn format_pr_status(pr_info: Option<crate::github::PrSummary>, links: bool) -> String {
     pr_info
         .map(|pr| {
             let icons = nerdfont::pr_icons();
@@ -39,29 +42,49 @@ fn format_pr_status(pr_info: Option<crate::github::PrSummary>) -> String {
                 "CLOSED" => (icons.closed, "\x1b[31m"),             // red
                 _ => (icons.open, "\x1b[32m"),
             };
-            format!("#{} {}{}\x1b[0m", pr.number, color, icon)
+            let number = ansi::link_if(links, pr.url.as_deref(), format!("#{}", pr.number));
+            format!("{} {}{}\x1b[0m", number, color, icon)
         })
         .unwrap_or_else(|| "-".to_string())
 }
 
 /// Format a single agent status as either an icon (TTY) or text label (piped).
+/// Lock state, then tags and note set by plugins, e.g.
+/// `locked (usb drive) [ci,review] needs rebase`.
+fn format_notes(locked: Option<&str>, tags: Option<&str>, note: Option<&str>) -> String {
+    let tags = tags.map(plugin::split_tags).unwrap_or_default();
+    let mut parts = Vec::new();
+    if let Some(reason) = locked {
+        parts.push(format!("locked{}", crate::vcs::describe_lock(reason)));
+    }
+    if !tags.is_empty() {
+        parts.push(format!("[{}]", tags.join(",")));
+    }
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        parts.push(note.to_string());
+    }
+    parts.join(" ")
+}
+
 fn format_status_label(status: AgentStatus, config: &config::Config, use_icons: bool) -> String {
     if use_icons {
         match status {
             AgentStatus::Working => config.status_icons.working().to_string(),
             AgentStatus::Waiting => config.status_icons.waiting().to_string(),
             AgentStatus::Done => config.status_icons.done().to_string(),
+            AgentStatus::Unknown => config.status_icons.unknown().to_string(),
         }
     } else {
         match status {
             AgentStatus::Working => "working".to_string(),
             AgentStatus::Waiting => "waiting".to_string(),
             AgentStatus::Done => "done".to_string(),
+            AgentStatus::Unknown => "unknown".to_string(),
         }
     }
 }
 
-fn format_agent_status(
+pub(crate) fn format_agent_status(
     summary: Option<&AgentStatusSummary>,
     config: &config::Config,
     use_icons: bool,
@@ -74,39 +97,43 @@ fn format_agent_status(
     let total = summary.statuses.len();
     if total == 1 {
         format_status_label(summary.statuses[0], config, use_icons)
+    } else if let Some(rollup) = summary.session_status {
+        // Session mode: lead with the worst-of rollup, then the breakdown
+        format!(
+            "{} ({})",
+            format_status_label(rollup, config, use_icons),
+            format_status_breakdown(&summary.statuses, config, use_icons)
+        )
     } else {
-        // Multiple agents: show breakdown
-        let working = summary
-            .statuses
-            .iter()
-            .filter(|s| matches!(s, AgentStatus::Working))
-            .count();
-        let waiting = summary
-            .statuses
-            .iter()
-            .filter(|s| matches!(s, AgentStatus::Waiting))
-            .count();
-        let done = summary
-            .statuses
-            .iter()
-            .filter(|s| matches!(s, AgentStatus::Done))
-            .count();
-
-        let mut parts = Vec::new();
-        if working > 0 {
-            let label = format_status_label(AgentStatus::Working, config, use_icons);
-            parts.push(format!("{}{}", working, label));
-        }
-        if waiting > 0 {
-            let label = format_status_label(AgentStatus::Waiting, config, use_icons);
-            parts.push(format!("{}{}", waiting, label));
-        }
-        if done > 0 {
-            let label = format_status_label(AgentStatus::Done, config, use_icons);
-            parts.push(format!("{}{}", done, label));
+        format_status_breakdown(&summary.statuses, config, use_icons)
+    }
+}
+
+/// Format per-status agent counts, e.g. "2🤖 1💬".
+fn format_status_breakdown(
+    statuses: &[AgentStatus],
+    config: &config::Config,
+    use_icons: bool,
+) -> String {
+    let count = |status: AgentStatus| statuses.iter().filter(|s| **s == status).count();
+
+    let mut parts = Vec::new();
+    for status in [
+        AgentStatus::Working,
+        AgentStatus::Waiting,
+        AgentStatus::Done,
+        AgentStatus::Unknown,
+    ] {
+        let n = count(status);
+        if n > 0 {
+            parts.push(format!(
+                "{}{}",
+                n,
+                format_status_label(status, config, use_icons)
+            ));
         }
-        parts.join(" ")
     }
+    parts.join(" ")
 }
 
 pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
@@ -122,6 +149,7 @@ pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
 
     // Use icons when outputting to a terminal, text labels when piped (for agents)
     let use_icons = std::io::stdout().is_terminal();
+    let links = config.hyperlinks() && ansi::supports_escapes(&std::io::stdout());
     let current_dir = std::env::current_dir()?;
 
     let display_data: Vec<WorktreeRow> = worktrees
@@ -137,10 +165,24 @@ pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
                     }
                 })
                 .unwrap_or_else(|| wt.path.display().to_string());
+            let path_str = ansi::link_if(links, Some(&ansi::file_url(&wt.path)), path_str);
+
+            let handle = wt
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let notes = format_notes(
+                wt.locked.as_deref(),
+                vcs.get_workspace_meta(&handle, plugin::TAGS_META_KEY)
+                    .as_deref(),
+                vcs.get_workspace_meta(&handle, plugin::NOTE_META_KEY)
+                    .as_deref(),
+            );
 
             WorktreeRow {
                 branch: wt.branch,
-                pr_status: format_pr_status(wt.pr_info),
+                pr_status: format_pr_status(wt.pr_info, links),
                 agent_status: format_agent_status(wt.agent_status.as_ref(), &config, use_icons),
                 mux_status: if wt.has_mux_window {
                     "✓".to_string()
@@ -153,14 +195,22 @@ pub fn run(show_pr: bool, filter: &[String]) -> Result<()> {
                     "-".to_string()
                 },
                 path_str,
+                notes,
             }
         })
         .collect();
+    let has_notes = display_data.iter().any(|row| !row.notes.is_empty());
 
     let mut table = Table::new(display_data);
     table
         .with(Style::blank())
-        .modify(Columns::new(0..6), Padding::new(0, 1, 0, 0));
+        .modify(Columns::new(0..7), Padding::new(0, 1, 0, 0));
+
+    // Hide NOTES column when no worktree is locked or annotated by a plugin (column 6).
+    // Removed before the PR column so the index is still valid.
+    if !has_notes {
+        table.with(Remove::column(Columns::new(6..7)));
+    }
 
     // Hide PR column if --pr flag not used (column 1)
     if !show_pr {
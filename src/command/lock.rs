@@ -0,0 +1,49 @@
+use anyhow::{Context, Result, bail};
+use console::style;
+
+use crate::vcs;
+
+pub fn run_lock(name: Option<&str>, reason: Option<&str>) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    let path = find(vcs.as_ref(), name)?;
+    if vcs.is_main_workspace(&path)? {
+        bail!("Cannot lock the main worktree");
+    }
+    if let Some(current) = vcs.list_locked_workspaces().get(&path) {
+        bail!(
+            "Worktree '{}' is already locked{}",
+            path.display(),
+            vcs::describe_lock(current)
+        );
+    }
+    vcs.lock_workspace(&path, reason)?;
+    println!(
+        "  {} Locked {}: it won't be pruned or removed until unlocked",
+        style("✓").green(),
+        path.display()
+    );
+    Ok(())
+}
+
+pub fn run_unlock(name: Option<&str>) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    let path = find(vcs.as_ref(), name)?;
+    if !vcs.list_locked_workspaces().contains_key(&path) {
+        bail!("Worktree '{}' is not locked", path.display());
+    }
+    vcs.unlock_workspace(&path)?;
+    println!("  {} Unlocked {}", style("✓").green(), path.display());
+    Ok(())
+}
+
+fn find(vcs: &dyn vcs::Vcs, name: Option<&str>) -> Result<std::path::PathBuf> {
+    let name = super::resolve_name(name)?;
+    // Smart resolution: try handle first, then branch name
+    let (path, _branch) = vcs.find_workspace(&name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    Ok(path)
+}
@@ -1,8 +1,12 @@
-use crate::config::MergeStrategy;
+use crate::config::{ConflictStrategy, MergeStrategy};
+use crate::forge::Forge;
 use crate::multiplexer::{create_backend, detect_backend};
-use crate::workflow::WorkflowContext;
-use crate::{config, workflow};
-use anyhow::{Context, Result};
+use crate::state::MergeCheckpoint;
+use crate::workflow::merge_checkpoint;
+use crate::workflow::{MergeRemoteSync, WorkflowContext};
+use crate::{ansi, config, progress, workflow};
+use anyhow::{Context, Result, bail};
+use std::io::{self, IsTerminal, Write};
 
 #[allow(clippy::too_many_arguments)]
 pub fn run(
@@ -15,9 +19,16 @@ pub fn run(
     no_verify: bool,
     no_hooks: bool,
     notification: bool,
+    remote_sync: MergeRemoteSync,
+    on_conflict: Option<ConflictStrategy>,
+    mut resume: bool,
+    abort: bool,
 ) -> Result<()> {
     // Inside a sandbox guest, route through RPC to the host supervisor
     if crate::sandbox::guest::is_sandbox_guest() {
+        if resume || abort {
+            bail!("Interrupted merges can only be resumed or aborted on the host");
+        }
         let name_to_merge = super::resolve_name(name)?;
         return run_via_rpc(
             &name_to_merge,
@@ -29,6 +40,8 @@ pub fn run(
             no_verify,
             no_hooks,
             notification,
+            remote_sync,
+            on_conflict,
         );
     }
 
@@ -46,6 +59,18 @@ pub fn run(
         }
     }
 
+    if remote_sync.push_rebased && !rebase {
+        progress::info("Note: --push-rebased only applies to the rebase strategy");
+    }
+
+    // Config enables remote sync for every merge; CLI flags only add to it
+    let remote_sync = MergeRemoteSync {
+        push: remote_sync.push || config.merge_push(),
+        delete_remote: remote_sync.delete_remote || config.merge_delete_remote(),
+        push_rebased: remote_sync.push_rebased || config.merge_push_rebased(),
+    };
+    let on_conflict = on_conflict.unwrap_or_else(|| config.merge_on_conflict());
+
     // Resolve name from argument or current directory
     // Note: Must be done BEFORE creating WorkflowContext (which may change CWD)
     let name_to_merge = super::resolve_name(name)?;
@@ -53,6 +78,25 @@ pub fn run(
     let mux = create_backend(detect_backend());
     let context = WorkflowContext::new(config, mux, None)?;
 
+    // A merge that died midway is resumed or rolled back, never run over
+    if let Some(cp) = merge_checkpoint::find_interrupted(&context, &name_to_merge)? {
+        resume = match (resume, abort) {
+            (true, _) => true,
+            (_, true) => false,
+            _ => ask_resume(&cp)?.ok_or_else(|| merge_checkpoint::interrupted_error(&cp))?,
+        };
+        if !resume {
+            merge_checkpoint::abort(&context, &cp)?;
+            progress::success(format!(
+                "Rolled back the interrupted merge of '{}' into '{}'",
+                cp.branch, cp.target
+            ));
+            return Ok(());
+        }
+    } else if abort {
+        bail!("No interrupted merge of '{}' to abort", name_to_merge);
+    }
+
     let skip_hooks = no_verify || no_hooks;
 
     // Announce pre-merge hooks if any (unless hooks are skipped)
@@ -75,32 +119,127 @@ pub fn run(
         no_verify,
         no_hooks,
         notification,
+        remote_sync,
+        on_conflict,
+        resume,
         &context,
     )
     .context("Failed to merge worktree")?;
 
     if result.had_staged_changes {
-        println!("✓ Committed staged changes");
+        progress::success("Committed staged changes");
     }
 
-    println!(
+    progress::info(format!(
         "Merging '{}' into '{}'...",
         result.branch_merged, result.main_branch
-    );
-    println!("✓ Merged '{}'", result.branch_merged);
+    ));
+    progress::success(format!("Merged '{}'", result.branch_merged));
+
+    let links = context.config.hyperlinks()
+        && progress::mode() != progress::Mode::Json
+        && ansi::supports_escapes(&std::io::stdout());
+
+    // Pushing the merge closes the branch's PR; point at it
+    let pr = if remote_sync.push || remote_sync.push_rebased {
+        Forge::current()
+            .list_prs()
+            .ok()
+            .and_then(|mut prs| prs.remove(&result.branch_merged))
+    } else {
+        None
+    };
+    if let Some(pr) = &pr {
+        progress::info(format!(
+            "Pull request: {}",
+            ansi::link_if(
+                links,
+                pr.url.as_deref(),
+                format!("#{} {}", pr.number, pr.title)
+            )
+        ));
+    }
 
     if keep {
-        println!("Worktree, window, and branch kept");
+        let path = result.worktree_path.display().to_string();
+        progress::info(format!(
+            "Worktree, window, and branch kept at {}",
+            ansi::link_if(links, Some(&ansi::file_url(&result.worktree_path)), path)
+        ));
     } else {
-        println!(
-            "✓ Successfully merged and cleaned up '{}'",
+        progress::success(format!(
+            "Successfully merged and cleaned up '{}'",
             result.branch_merged
-        );
+        ));
+    }
+    progress::result(&serde_json::json!({
+        "branch": result.branch_merged,
+        "into": result.main_branch,
+        "committed_staged_changes": result.had_staged_changes,
+        "kept": keep,
+        "pr_url": pr.and_then(|pr| pr.url),
+    }));
+
+    Ok(())
+}
+
+/// `workmux merge --to-upstream`: rebase onto the canonical repository and
+/// push to the fork instead of merging locally.
+pub fn run_to_upstream(name: Option<&str>, into_branch: Option<&str>) -> Result<()> {
+    if crate::sandbox::guest::is_sandbox_guest() {
+        bail!("--to-upstream is only available on the host");
     }
+    let config = config::Config::load(None)?;
+    let name_to_merge = super::resolve_name(name)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, None)?;
+
+    let result = workflow::merge_to_upstream(&name_to_merge, into_branch, &context)
+        .context("Failed to rebase onto upstream")?;
 
+    progress::success(format!(
+        "Pushed '{}' to '{}', rebased onto '{}/{}'",
+        result.branch, result.fork, result.upstream, result.target
+    ));
+    progress::info(format!(
+        "Open a pull request against '{}' on {}; the worktree is kept at {}",
+        result.target,
+        result.upstream,
+        result.worktree_path.display()
+    ));
+    progress::result(&serde_json::json!({
+        "branch": result.branch,
+        "upstream": result.upstream,
+        "into": result.target,
+        "fork": result.fork,
+        "kept": true,
+    }));
     Ok(())
 }
 
+/// Ask whether to resume (`Some(true)`) or roll back (`Some(false)`) an
+/// interrupted merge. `None` when cancelled or there is no one to ask.
+fn ask_resume(checkpoint: &MergeCheckpoint) -> Result<Option<bool>> {
+    if !io::stdin().is_terminal() || progress::mode() == progress::Mode::Json {
+        return Ok(None);
+    }
+    println!(
+        "A previous merge of '{}' into '{}' was interrupted {}.",
+        checkpoint.branch,
+        checkpoint.target,
+        checkpoint.step.describe()
+    );
+    print!("[r]esume, [a]bort, or [c]ancel? ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "r" | "resume" => Some(true),
+        "a" | "abort" => Some(false),
+        _ => None,
+    })
+}
+
 /// Run merge via RPC when inside a sandbox guest.
 #[allow(clippy::too_many_arguments)]
 fn run_via_rpc(
@@ -113,9 +252,10 @@ fn run_via_rpc(
     no_verify: bool,
     no_hooks: bool,
     notification: bool,
+    remote_sync: MergeRemoteSync,
+    on_conflict: Option<ConflictStrategy>,
 ) -> Result<()> {
     use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
-    use std::io::Write;
 
     let mut client = RpcClient::from_env()?;
     client.send(&RpcRequest::Merge {
@@ -128,6 +268,10 @@ fn run_via_rpc(
         no_verify,
         no_hooks,
         notification,
+        push: remote_sync.push,
+        delete_remote: remote_sync.delete_remote,
+        push_rebased: remote_sync.push_rebased,
+        on_conflict,
     })?;
 
     // Read streaming responses until we get a terminal Ok or Error
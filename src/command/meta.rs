@@ -0,0 +1,215 @@
+//! Inspect and repair the metadata workmux keeps per worktree.
+//!
+//! The metadata lives in git config (jj repo config for jj): the window or
+//! session mode, the note and tags, the base branch (stored per branch) and,
+//! for jj, the workspace path. Git knows where its worktrees are, so `path`
+//! is only shown there; use `workmux move-dir` to relocate one.
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+
+use crate::plugin::{self, NOTE_META_KEY, TAGS_META_KEY};
+use crate::vcs::{self, Vcs};
+
+/// Metadata keys `workmux meta` knows how to validate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MetaKey {
+    /// `window` or `session`
+    Mode,
+    /// Branch the worktree's branch merges into
+    Base,
+    /// Directory of the workspace (jj only)
+    Path,
+    /// Free-form note shown by `workmux list`
+    Note,
+    /// Comma-separated tags shown by `workmux list`
+    Tags,
+}
+
+impl MetaKey {
+    fn name(self) -> &'static str {
+        match self {
+            MetaKey::Mode => "mode",
+            MetaKey::Base => "base",
+            MetaKey::Path => "path",
+            MetaKey::Note => NOTE_META_KEY,
+            MetaKey::Tags => TAGS_META_KEY,
+        }
+    }
+}
+
+/// A worktree's metadata, as shown by `workmux meta show`.
+#[derive(Debug, Serialize)]
+struct WorktreeMeta {
+    handle: String,
+    branch: String,
+    path: String,
+    /// Stored mode; `None` means the default, window
+    mode: Option<String>,
+    base: Option<String>,
+    note: Option<String>,
+    tags: Vec<String>,
+}
+
+pub fn run_show(name: &str, json: bool) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    let (handle, branch, path) = resolve(vcs.as_ref(), name)?;
+    let meta = WorktreeMeta {
+        mode: vcs.get_workspace_meta(&handle, MetaKey::Mode.name()),
+        base: vcs.get_branch_base(&branch).ok(),
+        note: vcs.get_workspace_meta(&handle, NOTE_META_KEY),
+        tags: vcs
+            .get_workspace_meta(&handle, TAGS_META_KEY)
+            .map(|tags| {
+                plugin::split_tags(&tags)
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        handle,
+        branch,
+        path,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&meta)?);
+        return Ok(());
+    }
+    let unset = || "-".to_string();
+    let rows = [
+        ("handle", meta.handle),
+        ("branch", meta.branch),
+        ("path", meta.path),
+        (
+            "mode",
+            meta.mode.unwrap_or_else(|| "window (default)".to_string()),
+        ),
+        ("base", meta.base.unwrap_or_else(unset)),
+        ("note", meta.note.unwrap_or_else(unset)),
+        (
+            "tags",
+            if meta.tags.is_empty() {
+                unset()
+            } else {
+                meta.tags.join(", ")
+            },
+        ),
+    ];
+    for (key, value) in rows {
+        println!("{:<7} {}", key, value);
+    }
+    Ok(())
+}
+
+pub fn run_set(name: &str, key: MetaKey, value: &str) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    let (handle, branch, _) = resolve(vcs.as_ref(), name)?;
+    let value = normalize(key, value)?;
+    match key {
+        MetaKey::Base => {
+            if !vcs.branch_exists(&value)? {
+                bail!("Base reference '{}' does not exist", value);
+            }
+            if value == branch {
+                bail!("Cannot set the base of '{}' to itself", branch);
+            }
+            vcs.set_branch_base(&branch, &value)?;
+        }
+        MetaKey::Path => {
+            ensure_stored_path(vcs.as_ref())?;
+            let path = std::path::Path::new(&value);
+            if !path.is_absolute() || !path.is_dir() {
+                bail!("'{}' is not an absolute path to a directory", value);
+            }
+            vcs.set_workspace_meta(&handle, key.name(), &value)?;
+        }
+        _ => vcs.set_workspace_meta(&handle, key.name(), &value)?,
+    }
+    println!("✓ Set {} of '{}' to '{}'", key.name(), handle, value);
+    Ok(())
+}
+
+pub fn run_unset(name: &str, key: MetaKey) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    let (handle, branch, _) = resolve(vcs.as_ref(), name)?;
+    match key {
+        MetaKey::Base => vcs.unset_branch_base(&branch)?,
+        MetaKey::Path => bail!(
+            "The path of a worktree can't be unset; set it to the workspace's directory instead"
+        ),
+        _ => vcs.unset_workspace_meta(&handle, key.name())?,
+    }
+    println!("✓ Unset {} of '{}'", key.name(), handle);
+    Ok(())
+}
+
+/// Handle, branch and path of the worktree `name` (handle or branch).
+fn resolve(vcs: &dyn Vcs, name: &str) -> Result<(String, String, String)> {
+    let (path, branch) = vcs
+        .find_workspace(name)
+        .with_context(|| format!("No worktree found with name '{}'", name))?;
+    let handle = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow!("Invalid worktree path: {}", path.display()))?;
+    Ok((handle, branch, path.display().to_string()))
+}
+
+/// Only jj stores workspace paths; git tracks its worktrees itself.
+fn ensure_stored_path(vcs: &dyn Vcs) -> Result<()> {
+    if vcs.name() != "jj" {
+        bail!("git tracks where its worktrees are; move one with `workmux move-dir` instead");
+    }
+    Ok(())
+}
+
+/// Check `value` for `key` and return it as it is stored.
+fn normalize(key: MetaKey, value: &str) -> Result<String> {
+    let value = value.trim();
+    match key {
+        MetaKey::Mode => match value {
+            "window" | "session" => Ok(value.to_string()),
+            _ => bail!("Invalid mode '{}': use 'window' or 'session'", value),
+        },
+        MetaKey::Tags => {
+            let mut tags = String::new();
+            for tag in plugin::split_tags(value) {
+                plugin::validate_tag(tag)?;
+                tags = plugin::add_tag(Some(&tags), tag);
+            }
+            if tags.is_empty() {
+                bail!("No tags given; use `workmux meta unset <name> tags` to clear them");
+            }
+            Ok(tags)
+        }
+        MetaKey::Note if value.contains('\n') => bail!("Notes must be a single line"),
+        _ if value.is_empty() => bail!("Value must not be empty"),
+        _ => Ok(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_validated_per_key() {
+        assert_eq!(normalize(MetaKey::Mode, "session").unwrap(), "session");
+        assert!(normalize(MetaKey::Mode, "tab").is_err());
+
+        assert_eq!(
+            normalize(MetaKey::Tags, "infra, urgent,infra").unwrap(),
+            "infra,urgent"
+        );
+        assert!(normalize(MetaKey::Tags, "has space").is_err());
+        assert!(normalize(MetaKey::Tags, " , ").is_err());
+
+        assert_eq!(
+            normalize(MetaKey::Note, " waiting on review ").unwrap(),
+            "waiting on review"
+        );
+        assert!(normalize(MetaKey::Note, "two\nlines").is_err());
+        assert!(normalize(MetaKey::Base, "").is_err());
+    }
+}
@@ -1,33 +1,56 @@
 pub mod add;
+pub mod archive;
 pub mod args;
 pub mod capture;
 pub mod changelog;
 pub mod close;
 pub mod config;
+pub mod convert;
 pub mod dashboard;
 pub mod docs;
+pub mod doctor;
 pub mod exec;
+pub mod gc;
+pub mod git_query;
+pub mod grep;
+pub mod group;
 pub mod host_exec;
+pub mod init;
 pub mod last_agent;
 pub mod last_done;
+pub mod layout;
 pub mod list;
+pub mod lock;
 pub mod merge;
+pub mod meta;
+pub mod move_dir;
 pub mod open;
+pub mod pane;
+pub mod pane_wait;
 pub mod path;
+pub mod pr;
+pub mod reattach;
 pub mod remove;
+pub mod report_progress;
+pub mod restore_branch;
 pub mod run;
 pub mod sandbox;
 pub mod sandbox_run;
+pub mod self_update;
 pub mod send;
 pub mod set_base;
 pub mod set_window_status;
 pub mod setup;
+pub mod setup_keys;
 pub mod status;
+pub mod top;
 pub mod wait;
 
 use anyhow::{Context, Result, anyhow};
 
-use crate::{config::Config, workflow::SetupOptions};
+use crate::config::{Config, MuxMode};
+use crate::progress;
+use crate::workflow::{SetupOptions, WorkflowContext};
 
 /// Represents the different phases where hooks can be executed
 pub enum HookPhase {
@@ -42,10 +65,11 @@ pub fn announce_hooks(config: &Config, options: Option<&SetupOptions>, phase: Ho
     match phase {
         HookPhase::PostCreate => {
             let should_run = options.is_some_and(|opts| opts.run_hooks)
-                && config.post_create.as_ref().is_some_and(|v| !v.is_empty());
+                && (config.post_create.as_ref().is_some_and(|v| !v.is_empty())
+                    || config.git_hooks.install());
 
             if should_run {
-                println!("Running setup commands...");
+                progress::info("Running setup commands...");
             }
             should_run
         }
@@ -53,7 +77,7 @@ pub fn announce_hooks(config: &Config, options: Option<&SetupOptions>, phase: Ho
             let should_run = config.pre_merge.as_ref().is_some_and(|v| !v.is_empty());
 
             if should_run {
-                println!("Running pre-merge commands...");
+                progress::info("Running pre-merge commands...");
             }
             should_run
         }
@@ -61,21 +85,37 @@ pub fn announce_hooks(config: &Config, options: Option<&SetupOptions>, phase: Ho
             let should_run = config.pre_remove.as_ref().is_some_and(|v| !v.is_empty());
 
             if should_run {
-                println!("Running pre-remove commands...");
+                progress::info("Running pre-remove commands...");
             }
             should_run
         }
     }
 }
 
+/// Qualify the context's window prefix if `handle` collides with another
+/// project's window or session, telling the user about the new names.
+pub fn announce_prefix_qualification(
+    context: &mut WorkflowContext,
+    handle: &str,
+    mode: MuxMode,
+) -> Result<()> {
+    if let Some(collision) = context.qualify_prefix_on_collision(handle, mode)? {
+        progress::info(format!(
+            "'{}' belongs to another project; this project's windows are now named '{}<name>'",
+            collision, context.prefix
+        ));
+    }
+    Ok(())
+}
+
 /// Resolve name from argument or current worktree directory.
 ///
-/// When no argument is provided, extracts the worktree name from the current directory.
-/// If the user is in a subdirectory of a worktree, provides a helpful error message.
+/// When no argument (or `.`) is provided, extracts the worktree name from the current
+/// directory. If the user is in a subdirectory of a worktree, provides a helpful error message.
 pub fn resolve_name(arg: Option<&str>) -> Result<String> {
     match arg {
-        Some(name) => Ok(name.to_string()),
-        None => {
+        Some(name) if name != "." => Ok(name.to_string()),
+        _ => {
             let cwd = std::env::current_dir().context("Failed to get current directory")?;
             resolve_name_from_path(&cwd)
         }
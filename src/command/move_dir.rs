@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::config::{self, SandboxBackend};
+use crate::multiplexer::{MuxHandle, create_backend, detect_backend};
+use crate::state::StateStore;
+use crate::util::canon_or_self;
+use crate::workflow::{self, SetupOptions, WorkflowContext};
+use crate::{sandbox, spinner};
+
+pub fn run(name: &str, destination: &Path, no_restart: bool) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+
+    // Smart resolution: try handle first, then branch name
+    let (old_path, _branch) = context.vcs.find_workspace(name).with_context(|| {
+        format!(
+            "No workspace found with name '{}'. Use 'workmux list' to see available workspaces.",
+            name
+        )
+    })?;
+    let old_path = canon_or_self(&old_path);
+
+    if old_path == canon_or_self(&context.main_worktree_root) {
+        bail!("Cannot move the main worktree");
+    }
+
+    let handle = old_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid worktree path: no directory name"))?
+        .to_string_lossy()
+        .to_string();
+
+    let new_path = resolve_destination(destination, &handle)?;
+
+    // The shell running this command would be left in a deleted directory
+    let cwd = canon_or_self(&std::env::current_dir()?);
+    if cwd.starts_with(&old_path) {
+        bail!(
+            "Cannot move the worktree you are in. Run 'workmux move-dir' from outside {}",
+            old_path.display()
+        );
+    }
+
+    // Close the worktree's window/session so no process holds the old directory.
+    // It is reopened at the new location once the move is complete.
+    let mode = context.vcs.get_workspace_mode(&handle);
+    let target = MuxHandle::new(context.mux.as_ref(), mode, &context.prefix, &handle);
+    let mux_running = context.mux.is_running().unwrap_or(false);
+    let reopen = !no_restart && mux_running && target.exists()?;
+
+    // Panes outside the worktree's own window/session that sit in the worktree
+    // (e.g. a shell the user cd'd into it) are respawned in place.
+    let full_name = target.full_name();
+    let stray_panes: Vec<(String, PathBuf)> = if !no_restart && mux_running {
+        context
+            .mux
+            .get_all_live_pane_info()?
+            .into_iter()
+            .filter(|(_, info)| {
+                let owner = if target.is_session() {
+                    info.session.as_deref()
+                } else {
+                    info.window.as_deref()
+                };
+                !(reopen && owner == Some(full_name.as_str()))
+            })
+            .filter_map(|(pane_id, info)| {
+                let rel = canon_or_self(&info.working_dir)
+                    .strip_prefix(&old_path)
+                    .ok()?
+                    .to_path_buf();
+                Some((pane_id, new_path.join(rel)))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if reopen {
+        sandbox::stop_containers_for_handle(&handle, &context.config.sandbox);
+        MuxHandle::kill_full(context.mux.as_ref(), mode, &full_name)
+            .context("Failed to close target")?;
+        target.wait_until_closed()?;
+    }
+
+    let msg = format!("Moving worktree to {}", new_path.display());
+    spinner::with_spinner(&msg, || context.vcs.move_workspace(&old_path, &new_path))?;
+
+    if let Ok(store) = StateStore::new() {
+        store.relocate_workdirs(&old_path, &new_path)?;
+    }
+
+    // Lima VMs mount host directories at boot; make sure the new location is
+    // visible to the VM that will serve this worktree.
+    let sandbox_config = &context.config.sandbox;
+    if sandbox_config.is_enabled() && sandbox_config.backend() == SandboxBackend::Lima {
+        let vm_name = sandbox::lima::instance_name(
+            &new_path,
+            sandbox_config.lima.isolation(),
+            &context.config,
+        )?;
+        if sandbox::lima::ensure_vm_mounts_path(&vm_name, &new_path)? {
+            println!(
+                "  Added {} to Lima VM '{}' mounts",
+                new_path.display(),
+                vm_name
+            );
+        }
+    }
+
+    for (pane_id, cwd) in &stray_panes {
+        context
+            .mux
+            .respawn_pane(pane_id, cwd, None)
+            .with_context(|| format!("Failed to restart pane {}", pane_id))?;
+    }
+
+    if reopen {
+        let mut options = SetupOptions::new(false, false, true);
+        options.mode = mode;
+        options.focus_window = false;
+        workflow::open(&handle, &context, options, false)
+            .context("Failed to reopen worktree environment")?;
+    }
+
+    println!("✓ Moved worktree '{}' to {}", handle, new_path.display());
+    Ok(())
+}
+
+/// Resolve the destination path for a worktree move.
+///
+/// An existing directory is treated as the new parent; otherwise the path is
+/// the new worktree location itself. Workspace metadata is keyed by directory
+/// name, so the final component must stay the same.
+fn resolve_destination(destination: &Path, handle: &str) -> Result<PathBuf> {
+    let destination = std::path::absolute(destination)
+        .with_context(|| format!("Invalid destination {}", destination.display()))?;
+    let new_path = if destination.is_dir() {
+        destination.join(handle)
+    } else {
+        destination
+    };
+
+    if new_path.exists() {
+        bail!("Destination {} already exists", new_path.display());
+    }
+    if new_path.file_name().and_then(|n| n.to_str()) != Some(handle) {
+        bail!(
+            "Destination directory must be named '{}' (renaming is not supported); \
+             pass its parent directory instead",
+            handle
+        );
+    }
+    Ok(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_destination_into_existing_parent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let resolved = resolve_destination(tmp.path(), "feature").unwrap();
+        assert_eq!(resolved, tmp.path().join("feature"));
+    }
+
+    #[test]
+    fn resolve_destination_explicit_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let explicit = tmp.path().join("disk2/feature");
+        assert_eq!(resolve_destination(&explicit, "feature").unwrap(), explicit);
+    }
+
+    #[test]
+    fn resolve_destination_rejects_rename_and_existing() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(resolve_destination(&tmp.path().join("other"), "feature").is_err());
+
+        std::fs::create_dir(tmp.path().join("feature")).unwrap();
+        assert!(resolve_destination(tmp.path(), "feature").is_err());
+    }
+}
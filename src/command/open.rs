@@ -1,10 +1,23 @@
-use crate::command::args::PromptArgs;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::command::args::{MultiArgs, PromptArgs, RescueArgs, SetupFlags};
 use crate::config::MuxMode;
+use crate::forge::Forge;
 use crate::multiplexer::{create_backend, detect_backend};
+use crate::prompt::Guardrails;
+use crate::vcs::{BranchSummary, Vcs};
 use crate::workflow::prompt_loader::{PromptLoadArgs, load_prompt};
 use crate::workflow::{SetupOptions, WorkflowContext};
-use crate::{config, workflow};
-use anyhow::{Context, Result, bail};
+use crate::{config, vcs, workflow};
+use anyhow::{Context, Result, anyhow, bail};
+use console::style;
+
+/// A branch without a worktree, offered by the picker.
+struct BranchChoice {
+    name: String,
+    summary: Option<BranchSummary>,
+}
 
 pub fn run(
     name: Option<&str>,
@@ -19,15 +32,20 @@ pub fn run(
         (None, true) => super::resolve_name(None).context(
             "Could not infer current worktree. Run inside a worktree or provide a name.",
         )?,
-        (None, false) => bail!("Worktree name is required unless --new is provided"),
+        (None, false) => return open_picked_branches(prompt_args),
     };
 
     let (config, config_location) = config::Config::load_with_location(None)?;
     let mux = create_backend(detect_backend());
-    let context = WorkflowContext::new(config, mux, config_location)?;
+    let mut context = WorkflowContext::new(config, mux, config_location)?;
 
     // Determine the target mode from stored metadata
     let stored_mode = context.vcs.get_workspace_mode(&resolved_name);
+    if let Ok((path, _)) = context.vcs.find_workspace(&resolved_name)
+        && let Some(handle) = path.file_name().map(|n| n.to_string_lossy().into_owned())
+    {
+        super::announce_prefix_qualification(&mut context, &handle, stored_mode)?;
+    }
     let target_type = match stored_mode {
         MuxMode::Session => "session",
         MuxMode::Window => "window",
@@ -39,13 +57,17 @@ pub fn run(
         prompt_inline: prompt_args.prompt.as_deref(),
         prompt_file: prompt_args.prompt_file.as_ref(),
     })?;
+    if prompt.is_none() && !prompt_args.attach.is_empty() {
+        bail!(
+            "--attach needs a prompt to reference the files in (--prompt, --prompt-file or --prompt-editor)"
+        );
+    }
 
-    // Write prompt to temp file if provided
+    // Write prompt to the worktree's .workmux/ directory if provided, or to a temp file
+    // if the worktree can't be found yet (open reports that error itself).
     // Use unique filename with timestamp to prevent race condition when opening multiple duplicates
-    // Note: We use None for working_dir here because we don't know the worktree path yet
-    // (open resolves it later). The temp dir approach works fine for open since sandbox
-    // wrapping only happens during initial create, not open.
     let prompt_file_path = if let Some(ref p) = prompt {
+        crate::prompt::ensure_size(&p.read_content()?, context.config.max_prompt_kb())?;
         let unique_name = format!(
             "{}-{}",
             resolved_name,
@@ -54,7 +76,31 @@ pub fn run(
                 .unwrap_or_default()
                 .as_millis()
         );
-        Some(crate::workflow::write_prompt_file(None, &unique_name, p)?)
+        let worktree_path = context
+            .vcs
+            .find_workspace(&resolved_name)
+            .ok()
+            .map(|(path, _)| path);
+        let guardrails = if prompt_args.no_guardrails {
+            Guardrails::default()
+        } else {
+            Guardrails::from_config(&context.config)
+        };
+        let p = match &worktree_path {
+            Some(path) => crate::workflow::attach_files(
+                path,
+                p,
+                &prompt_args.attach,
+                context.config.agent.as_deref(),
+            )?,
+            None => p.clone(),
+        };
+        Some(crate::workflow::write_prompt_file(
+            worktree_path.as_deref(),
+            &unique_name,
+            &p,
+            guardrails,
+        )?)
     } else {
         None
     };
@@ -98,3 +144,230 @@ pub fn run(
 
     Ok(())
 }
+
+/// `workmux open --pr <number>`: switch to the worktree of a pull request's
+/// head branch, or check the branch out into a new worktree as
+/// `workmux add --pr` does, fetching it from the fork for PRs from forks.
+pub fn run_pr(
+    number: u32,
+    run_hooks: bool,
+    force_files: bool,
+    prompt_args: PromptArgs,
+) -> Result<()> {
+    let vcs = vcs::detect_vcs()?;
+    // Best effort: when the lookup fails, `add --pr` reports the real error
+    let head = Forge::current()
+        .list_prs()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(_, pr)| pr.number == number)
+        .map(|(branch, _)| branch);
+    let existing = head
+        .and_then(|branch| vcs.find_workspace(&branch).ok())
+        .filter(|(path, _)| !vcs.is_main_workspace(path).unwrap_or(true));
+    if let Some((path, _)) = existing {
+        let handle = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("Invalid worktree path '{}'", path.display()))?;
+        return run(Some(&handle), run_hooks, force_files, false, prompt_args);
+    }
+    super::add::run(
+        None,
+        Some(number),
+        false,
+        None,
+        None,
+        false,
+        None,
+        prompt_args,
+        SetupFlags::default(),
+        RescueArgs::default(),
+        MultiArgs::default(),
+        false,
+        false,
+    )
+}
+
+/// Let the user pick branches that have no worktree yet, then create a
+/// worktree and window for each, as `workmux add <branch>` would.
+///
+/// The first branch is focused; the rest are created in the background.
+fn open_picked_branches(prompt_args: PromptArgs) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        bail!("Worktree name is required when not running in a terminal");
+    }
+    let config = config::Config::load(None)?;
+    let vcs = vcs::detect_vcs()?;
+    let main_branch = match config.main_branch.clone() {
+        Some(branch) => branch,
+        None => vcs.get_default_branch()?,
+    };
+    let base = vcs
+        .get_merge_base(&main_branch)
+        .unwrap_or_else(|_| main_branch.clone());
+
+    let choices = branch_choices(vcs.as_ref(), &main_branch, &base)?;
+    if choices.is_empty() {
+        println!("No branches without a worktree");
+        return Ok(());
+    }
+
+    println!("Branches without a worktree (compared with {}):", base);
+    print_choices(&choices);
+    print!("Open which branches? (e.g. 1 3 5-7, all; empty to cancel): ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let selected = parse_selection(&input, choices.len())?;
+    if selected.is_empty() {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    for (i, index) in selected.iter().enumerate() {
+        let branch = &choices[*index].name;
+        if selected.len() > 1 {
+            println!(
+                "\n--- [{}/{}] Opening branch: {} ---",
+                i + 1,
+                selected.len(),
+                branch
+            );
+        }
+        super::add::run(
+            Some(branch),
+            None,
+            false,
+            None,
+            None,
+            true,
+            None,
+            prompt_args.clone(),
+            SetupFlags {
+                background: i > 0,
+                ..Default::default()
+            },
+            RescueArgs::default(),
+            MultiArgs::default(),
+            false,
+            false,
+        )
+        .with_context(|| format!("Failed to open branch '{}'", branch))?;
+    }
+    Ok(())
+}
+
+/// Branches that could get a worktree, most recently committed first.
+///
+/// Remote branches are left out when a local branch of the same name exists,
+/// since checking them out would collide with it.
+fn branch_choices(vcs: &dyn Vcs, main_branch: &str, base: &str) -> Result<Vec<BranchChoice>> {
+    let mut choices: Vec<BranchChoice> = vcs
+        .list_checkout_branches()?
+        .into_iter()
+        .filter(|name| match vcs.split_remote_ref(name) {
+            Some((_, local)) => local != main_branch && !vcs.branch_exists(&local).unwrap_or(false),
+            None => name != main_branch,
+        })
+        .map(|name| BranchChoice {
+            summary: vcs.get_branch_summary(&name, base).ok(),
+            name,
+        })
+        .collect();
+    choices
+        .sort_by_key(|c| std::cmp::Reverse(c.summary.as_ref().map_or(0, |s| s.last_commit_time)));
+    Ok(choices)
+}
+
+fn print_choices(choices: &[BranchChoice]) {
+    let name_width = choices.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for (i, choice) in choices.iter().enumerate() {
+        let (counts, age, subject) = match &choice.summary {
+            Some(s) => (
+                format!("↑{} ↓{}", s.ahead, s.behind),
+                super::sandbox::format_duration_since(
+                    UNIX_EPOCH + Duration::from_secs(s.last_commit_time),
+                ),
+                s.subject.as_str(),
+            ),
+            None => ("-".to_string(), "-".to_string(), ""),
+        };
+        println!(
+            "  {:>3}  {:<name_width$}  {:<10}  {}  {}",
+            i + 1,
+            choice.name,
+            counts,
+            style(format!("{:<14}", age)).dim(),
+            subject,
+            name_width = name_width
+        );
+    }
+}
+
+/// Parse a selection like `1 3 5-7` or `all` into indices into a list of
+/// `count` items, in the order given and without duplicates.
+fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let number = |s: &str| -> Result<usize> {
+        let n: usize = s
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("'{}' is not a number", s))?;
+        if n == 0 || n > count {
+            bail!("{} is out of range (1-{})", n, count);
+        }
+        Ok(n - 1)
+    };
+
+    let mut selected = Vec::new();
+    for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+        let range = match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (number(start)?, number(end)?);
+                if start > end {
+                    bail!("'{}' is not a valid range", part);
+                }
+                start..=end
+            }
+            None => {
+                let n = number(part)?;
+                n..=n
+            }
+        };
+        for index in range {
+            if !selected.contains(&index) {
+                selected.push(index);
+            }
+        }
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_selection_numbers_and_ranges() {
+        assert_eq!(parse_selection("1 3 5-7\n", 8).unwrap(), [0, 2, 4, 5, 6]);
+        assert_eq!(parse_selection("2,1, 2", 3).unwrap(), [1, 0]);
+        assert_eq!(parse_selection("all", 3).unwrap(), [0, 1, 2]);
+        assert!(parse_selection("  \n", 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_selection_rejects_bad_input() {
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("1-x", 3).is_err());
+        assert!(parse_selection("3-1", 3).is_err());
+        assert!(parse_selection("feature", 3).is_err());
+    }
+}
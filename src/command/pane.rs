@@ -0,0 +1,184 @@
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::config::{self, PaneConfig, SplitDirection};
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::workflow::{self, WorkflowContext};
+
+/// Where the pane for `workmux pane add` comes from, plus overrides.
+pub struct PaneAddArgs {
+    /// 0-based index into the configured panes
+    pub pane: Option<usize>,
+    /// Layout to take `pane` from instead of `panes`
+    pub layout: Option<String>,
+    /// Ad-hoc command to run instead of a configured pane
+    pub cmd: Option<String>,
+    pub split: Option<SplitDirection>,
+    pub size: Option<u16>,
+    pub percentage: Option<u8>,
+    /// Pane id to split
+    pub target: Option<String>,
+    pub focus: bool,
+}
+
+pub fn run_add(name: Option<&str>, args: PaneAddArgs) -> Result<()> {
+    let name = super::resolve_name(name)
+        .context("Could not infer current worktree. Run inside a worktree or provide a name.")?;
+
+    let (mut config, config_location) = config::Config::load_with_location(None)?;
+    if let Some(layout) = args.layout.as_deref() {
+        config.apply_layout(layout)?;
+    }
+    let pane = pane_config(config.panes.as_deref().unwrap_or_default(), &args)?;
+
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+    let added =
+        workflow::pane::add_pane(&name, &context, pane, args.target.as_deref(), args.focus)?;
+
+    println!("✓ Added pane {} to {}", added.pane_id, added.target_name);
+    Ok(())
+}
+
+/// The pane to add: configured pane `args.pane` or an ad-hoc one running
+/// `args.cmd`, with the command-line overrides applied.
+fn pane_config(configured: &[PaneConfig], args: &PaneAddArgs) -> Result<PaneConfig> {
+    let mut pane = match (args.pane, args.cmd.as_deref()) {
+        (Some(index), _) => {
+            let pane = configured.get(index).ok_or_else(|| {
+                anyhow!(
+                    "No pane {} in the configuration ({} configured)",
+                    index,
+                    configured.len()
+                )
+            })?;
+            // The window's panes stand in for the rest of the layout, so
+            // only the indices of `depends_on` still apply
+            PaneConfig {
+                focus: false,
+                target: None,
+                ..pane.clone()
+            }
+        }
+        (None, Some(cmd)) => PaneConfig {
+            command: Some(cmd.to_string()),
+            ..PaneConfig::default()
+        },
+        (None, None) => bail!("Pass --pane <index> or --cmd <command>"),
+    };
+
+    if let Some(split) = args.split.clone() {
+        pane.split = Some(split);
+    }
+    if args.size.is_some() || args.percentage.is_some() {
+        pane.size = args.size;
+        pane.percentage = args.percentage;
+    }
+    if pane.split.is_none() {
+        pane.split = Some(SplitDirection::Horizontal);
+    }
+    Ok(pane)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> PaneAddArgs {
+        PaneAddArgs {
+            pane: None,
+            layout: None,
+            cmd: None,
+            split: None,
+            size: None,
+            percentage: None,
+            target: None,
+            focus: false,
+        }
+    }
+
+    #[test]
+    fn configured_pane_keeps_its_settings_under_overrides() {
+        let configured = vec![
+            PaneConfig {
+                command: Some("<agent>".to_string()),
+                focus: true,
+                ..PaneConfig::default()
+            },
+            PaneConfig {
+                command: Some("npm run dev".to_string()),
+                split: Some(SplitDirection::Vertical),
+                percentage: Some(30),
+                target: Some(0),
+                depends_on: Some(vec![0]),
+                ..PaneConfig::default()
+            },
+        ];
+
+        let pane = pane_config(
+            &configured,
+            &PaneAddArgs {
+                pane: Some(1),
+                ..args()
+            },
+        )
+        .unwrap();
+        assert_eq!(pane.command.as_deref(), Some("npm run dev"));
+        assert_eq!(pane.split, Some(SplitDirection::Vertical));
+        assert_eq!(pane.percentage, Some(30));
+        assert_eq!(pane.depends_on, Some(vec![0]));
+        assert_eq!(pane.target, None);
+
+        let pane = pane_config(
+            &configured,
+            &PaneAddArgs {
+                pane: Some(1),
+                split: Some(SplitDirection::Horizontal),
+                size: Some(20),
+                ..args()
+            },
+        )
+        .unwrap();
+        assert_eq!(pane.split, Some(SplitDirection::Horizontal));
+        assert_eq!((pane.size, pane.percentage), (Some(20), None));
+
+        // The first pane has no split of its own
+        let pane = pane_config(
+            &configured,
+            &PaneAddArgs {
+                pane: Some(0),
+                ..args()
+            },
+        )
+        .unwrap();
+        assert_eq!(pane.split, Some(SplitDirection::Horizontal));
+        assert!(!pane.focus);
+
+        assert!(
+            pane_config(
+                &configured,
+                &PaneAddArgs {
+                    pane: Some(2),
+                    ..args()
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn ad_hoc_pane_runs_the_command() {
+        let pane = pane_config(
+            &[],
+            &PaneAddArgs {
+                cmd: Some("just watch".to_string()),
+                split: Some(SplitDirection::Vertical),
+                ..args()
+            },
+        )
+        .unwrap();
+        assert_eq!(pane.command.as_deref(), Some("just watch"));
+        assert_eq!(pane.split, Some(SplitDirection::Vertical));
+
+        assert!(pane_config(&[], &args()).is_err());
+    }
+}
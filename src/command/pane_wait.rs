@@ -0,0 +1,104 @@
+//! Hidden `_pane-wait` subcommand for pane startup ordering.
+//!
+//! Pane commands with `depends_on` are prefixed with this command so they
+//! only start once the readiness checks of their dependencies pass.
+
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub fn run(ports: &[u16], files: &[PathBuf], commands: &[String], timeout: u64) -> Result<()> {
+    let start = Instant::now();
+    let deadline = Duration::from_secs(timeout);
+    let mut announced = false;
+
+    loop {
+        let pending = pending_checks(ports, files, commands);
+        if pending.is_empty() {
+            return Ok(());
+        }
+        if start.elapsed() >= deadline {
+            bail!(
+                "Timed out after {}s waiting for {}",
+                timeout,
+                pending.join(", ")
+            );
+        }
+        if !announced {
+            eprintln!("Waiting for {}...", pending.join(", "));
+            announced = true;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Descriptions of the checks that don't pass yet.
+fn pending_checks(ports: &[u16], files: &[PathBuf], commands: &[String]) -> Vec<String> {
+    let ports = ports
+        .iter()
+        .filter(|&&port| !port_open(port))
+        .map(|port| format!("port {}", port));
+    let files = files
+        .iter()
+        .filter(|file| !file.exists())
+        .map(|file| format!("file {}", file.display()));
+    let commands = commands
+        .iter()
+        .filter(|command| !command_succeeds(command))
+        .map(|command| format!("`{}`", command));
+    ports.chain(files).chain(commands).collect()
+}
+
+fn port_open(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok()
+}
+
+fn command_succeeds(command: &str) -> bool {
+    Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn pending_checks_reports_unmet_conditions() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let open_port = listener.local_addr().unwrap().port();
+        let tmp = tempfile::tempdir().unwrap();
+        let ready = tmp.path().join("ready");
+        std::fs::write(&ready, "").unwrap();
+        let missing = tmp.path().join("missing");
+
+        let pending = pending_checks(
+            &[open_port],
+            &[ready, missing.clone()],
+            &["true".to_string(), "false".to_string()],
+        );
+        assert_eq!(
+            pending,
+            vec![format!("file {}", missing.display()), "`false`".to_string()]
+        );
+    }
+
+    #[test]
+    fn run_times_out_when_check_never_passes() {
+        let err = run(&[], &[], &["false".to_string()], 0).unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::{Context, Result, bail};
+
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::workflow::WorkflowContext;
+use crate::workflow::pr::PrCreateOptions;
+use crate::{config, progress, workflow};
+
+/// `workmux pr create`: push a worktree's branch and open a pull request
+/// described from the prompt its agent got.
+pub fn run_create(name: Option<&str>, options: PrCreateOptions) -> Result<()> {
+    if crate::sandbox::guest::is_sandbox_guest() {
+        bail!("'pr create' is only available on the host");
+    }
+    let config = config::Config::load(None)?;
+    let name = super::resolve_name(name)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, None)?;
+
+    let pr = workflow::pr::create_pull_request(&name, &options, &context)
+        .context("Failed to create pull request")?;
+
+    if pr.existing {
+        progress::success(format!(
+            "Pushed '{}' to '{}'; it already has a pull request: {}",
+            pr.branch, pr.remote, pr.url
+        ));
+    } else {
+        progress::success(format!(
+            "Opened pull request for '{}' into '{}': {}",
+            pr.branch, pr.base, pr.url
+        ));
+    }
+    progress::result(&serde_json::json!({
+        "branch": pr.branch,
+        "remote": pr.remote,
+        "into": pr.base,
+        "url": pr.url,
+        "existing": pr.existing,
+    }));
+    Ok(())
+}
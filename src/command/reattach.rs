@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::config::{self, MuxMode};
+use crate::multiplexer::handle::{MuxHandle, mode_label};
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::workflow::orphans::{self, OrphanTarget};
+use crate::workflow::{self, SetupOptions, WorkflowContext};
+
+/// Recover the windows of a worktree deleted outside workmux: recreate the
+/// worktree from its branch and reopen it, or just close the windows.
+pub fn run(name: &str, close: bool, branch: Option<&str>) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+    context.ensure_mux_running()?;
+
+    let panes = context.mux.get_all_live_pane_info()?;
+    let all = orphans::find(&context, &panes)?;
+    let handle = all
+        .iter()
+        .find(|o| o.name == name || o.handle == name)
+        .map(|o| o.handle.clone())
+        .ok_or_else(|| {
+            anyhow!(
+                "No window of a deleted worktree matches '{}'. \
+                 Run 'workmux doctor' to list them.",
+                name
+            )
+        })?;
+    let targets: Vec<&OrphanTarget> = all.iter().filter(|o| o.handle == handle).collect();
+    let path = targets[0].path.clone();
+    let mode = targets[0].mode;
+
+    // Drop the VCS bookkeeping of the deleted worktree
+    context
+        .vcs
+        .prune_workspaces(&context.shared_dir)
+        .context("Failed to prune workspaces")?;
+
+    if close {
+        let current = close_targets(&context, &targets)?;
+        context.vcs.remove_workspace_meta(&handle)?;
+        return close_current(&context, current);
+    }
+
+    let branch = match branch {
+        Some(branch) => branch.to_string(),
+        None => orphans::branch_for(&context, &handle)?.ok_or_else(|| {
+            anyhow!(
+                "Could not find the branch of worktree '{}'. \
+                 Pass --branch <name>, or --close to close its {}.",
+                handle,
+                mode_label(mode)
+            )
+        })?,
+    };
+    if !context.vcs.branch_exists(&branch)? {
+        bail!("Branch '{}' does not exist", branch);
+    }
+
+    context
+        .vcs
+        .create_workspace(&path, &branch, false, None, false)
+        .with_context(|| format!("Failed to recreate worktree '{}'", path.display()))?;
+    workflow::artifacts::ensure_dir(&path)?;
+    println!(
+        "✓ Recreated worktree '{}' from branch '{}'",
+        path.display(),
+        branch
+    );
+
+    // The old targets hold the name the reopened one needs
+    let current = close_targets(&context, &targets)?;
+    let options = SetupOptions {
+        mode,
+        ..SetupOptions::new(true, true, true)
+    };
+    workflow::open(&handle, &context, options, false)?;
+    println!("✓ Reopened {} for '{}'", mode_label(mode), handle);
+    close_current(&context, current)
+}
+
+/// Close the orphaned targets, except the one this command runs in: that
+/// one is renamed out of the way and returned, to be closed once the
+/// command is done.
+fn close_targets(
+    context: &WorkflowContext,
+    targets: &[&OrphanTarget],
+) -> Result<Option<(MuxMode, String)>> {
+    let mux = context.mux.as_ref();
+    let current_pane = context.mux.current_pane_id();
+    let mut current = None;
+    for target in targets {
+        let pane = current_pane
+            .as_ref()
+            .filter(|pane| target.pane_ids.contains(pane));
+        if let Some(pane) = pane {
+            let renamed = format!("{}-deleted", target.name);
+            match target.mode {
+                MuxMode::Window => mux.rename_window(pane, &renamed)?,
+                MuxMode::Session => mux.rename_session(&target.name, &renamed)?,
+            }
+            current = Some((target.mode, renamed));
+            continue;
+        }
+        MuxHandle::kill_full(mux, target.mode, &target.name)
+            .with_context(|| format!("Failed to close '{}'", target.name))?;
+        println!("✓ Closed {} '{}'", mode_label(target.mode), target.name);
+    }
+    Ok(current)
+}
+
+/// Close the target this command runs in after a short delay, so the
+/// command can finish first.
+fn close_current(context: &WorkflowContext, current: Option<(MuxMode, String)>) -> Result<()> {
+    if let Some((mode, name)) = current {
+        let delay = Duration::from_millis(100);
+        MuxHandle::schedule_close_full(context.mux.as_ref(), mode, &name, delay)?;
+    }
+    Ok(())
+}
@@ -1,30 +1,105 @@
-use crate::multiplexer::{create_backend, detect_backend};
+use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
+use crate::state::StateStore;
 use crate::workflow::WorkflowContext;
 use crate::{config, spinner, vcs, workflow};
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
+use clap::ValueEnum;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum StatusFilter {
+    Working,
+    Waiting,
+    Done,
+}
+
+impl From<StatusFilter> for AgentStatus {
+    fn from(status: StatusFilter) -> Self {
+        match status {
+            StatusFilter::Working => AgentStatus::Working,
+            StatusFilter::Waiting => AgentStatus::Waiting,
+            StatusFilter::Done => AgentStatus::Done,
+        }
+    }
+}
+
+/// Filters that narrow down which worktrees a batch removal applies to.
+#[derive(Debug, Default)]
+pub struct RemoveFilters {
+    /// Only worktrees whose branch is merged into its base
+    pub merged: bool,
+    /// Only worktrees whose agents all have this status
+    pub status: Option<AgentStatus>,
+    /// Only worktrees whose latest commit is at least this many seconds old
+    pub older_than: Option<u64>,
+}
+
+impl RemoveFilters {
+    fn is_empty(&self) -> bool {
+        !self.merged && self.status.is_none() && self.older_than.is_none()
+    }
+}
+
+/// How worktrees are removed, whichever way they were selected.
+#[derive(Debug, Default)]
+pub struct RemoveOptions {
+    /// Skip confirmation and remove worktrees with uncommitted changes
+    pub force: bool,
+    pub keep_branch: bool,
+    /// Save an archive of each worktree before removing it
+    pub archive: bool,
+    /// Only list what would be removed
+    pub dry_run: bool,
+    pub filters: RemoveFilters,
+}
+
+pub fn run(names: Vec<String>, gone: bool, all: bool, options: RemoveOptions) -> Result<()> {
+    // Patterns, filters and dry runs go through the batch flow, which lists
+    // everything up front and asks once
+    if !options.filters.is_empty() || options.dry_run || names.iter().any(|n| is_pattern(n)) {
+        // Only --all selects every worktree; otherwise an empty list means the
+        // current one, as in a plain remove
+        let patterns = if all {
+            Vec::new()
+        } else if names.is_empty() {
+            vec![super::resolve_name(None)?]
+        } else {
+            names
+                .iter()
+                .map(|n| {
+                    if is_pattern(n) {
+                        Ok(n.clone())
+                    } else {
+                        super::resolve_name(Some(n))
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        return run_batch(patterns, &options);
+    }
+
+    let RemoveOptions {
+        force,
+        keep_branch,
+        archive,
+        ..
+    } = options;
 
-pub fn run(
-    names: Vec<String>,
-    gone: bool,
-    all: bool,
-    force: bool,
-    keep_branch: bool,
-) -> Result<()> {
     if all {
-        return run_all(force, keep_branch);
+        return run_all(force, keep_branch, archive);
     }
 
     if gone {
-        return run_gone(force, keep_branch);
+        return run_gone(force, keep_branch, archive);
     }
 
-    run_specified(names, force, keep_branch)
+    run_specified(names, force, keep_branch, archive)
 }
 
 /// Remove specific worktrees provided by user (or current if empty)
-fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<()> {
+fn run_specified(names: Vec<String>, force: bool, keep_branch: bool, archive: bool) -> Result<()> {
     // Normalize all inputs (handles "." and other special cases)
     let resolved_names: Vec<String> = if names.is_empty() {
         vec![super::resolve_name(None)?]
@@ -36,6 +111,7 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
     };
 
     let vcs = vcs::detect_vcs()?;
+    let config = config::Config::load(None)?;
 
     // 2. Resolve all targets and validate they exist
     let mut candidates: Vec<(String, PathBuf, String)> = Vec::new();
@@ -62,8 +138,8 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
         let mut failed: Vec<(String, String)> = Vec::new();
 
         for (handle, _, _) in candidates {
-            if let Err(e) = remove_worktree(&handle, true, keep_branch) {
-                failed.push((handle, e.to_string()));
+            if let Err(e) = remove_worktree(&handle, true, keep_branch, archive) {
+                failed.push((handle, format!("{:#}", e)));
             }
         }
 
@@ -79,11 +155,19 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
     }
 
     // 4. Safety checks: categorize candidates
+    let mut locked_worktrees = vcs.list_locked_workspaces();
+    let mut locked: Vec<(String, String)> = Vec::new(); // (handle, reason)
     let mut uncommitted: Vec<String> = Vec::new();
     let mut unmerged: Vec<(String, String, String)> = Vec::new(); // (handle, branch, base)
     let mut safe: Vec<String> = Vec::new();
 
     for (handle, path, branch) in candidates {
+        // Check locked (blocking)
+        if let Some(reason) = locked_worktrees.remove(&path) {
+            locked.push((handle, reason));
+            continue;
+        }
+
         // Check uncommitted (blocking)
         if path.exists() && vcs.has_uncommitted_changes(&path).unwrap_or(false) {
             uncommitted.push(handle);
@@ -91,7 +175,7 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
         }
 
         // Check unmerged (promptable), only if we're deleting the branch
-        if !keep_branch && let Some(base) = is_unmerged(vcs.as_ref(), &branch)? {
+        if !keep_branch && let Some(base) = is_unmerged(vcs.as_ref(), &config, &branch)? {
             unmerged.push((handle, branch, base));
             continue;
         }
@@ -99,7 +183,17 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
         safe.push(handle);
     }
 
-    // 5. Handle blocking issues (uncommitted changes)
+    // 5. Handle blocking issues (locked worktrees, uncommitted changes)
+    if !locked.is_empty() {
+        eprintln!("The following worktrees are locked:");
+        for (handle, reason) in &locked {
+            eprintln!("  - {}{}", handle, vcs::describe_lock(reason));
+        }
+        return Err(anyhow!(
+            "Cannot remove locked worktrees. Unlock them with 'workmux unlock' or use --force."
+        ));
+    }
+
     if !uncommitted.is_empty() {
         eprintln!("The following worktrees have uncommitted changes:");
         for handle in &uncommitted {
@@ -139,19 +233,254 @@ fn run_specified(names: Vec<String>, force: bool, keep_branch: bool) -> Result<(
     // 7. Execute removal
     for handle in safe {
         // force=true because we already checked/prompted
-        remove_worktree(&handle, true, keep_branch)?;
+        remove_worktree(&handle, true, keep_branch, archive)?;
+    }
+
+    Ok(())
+}
+
+/// Whether a name contains glob syntax rather than naming one worktree.
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?', '['])
+}
+
+/// Parse an age like `30m`, `12h`, `14d` or `2w` into seconds.
+pub fn parse_age(age: &str) -> Result<u64, String> {
+    let age = age.trim();
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (number, unit) = age.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{}' (expected e.g. 12h, 14d, 2w)", age))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(format!("invalid age unit in '{}' (use s/m/h/d/w)", age)),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("age '{}' is too large", age))
+}
+
+/// Remove worktrees selected by names, glob patterns and filters, after
+/// showing the full list and asking once. No patterns selects every worktree.
+fn run_batch(patterns: Vec<String>, options: &RemoveOptions) -> Result<()> {
+    let RemoveOptions {
+        force,
+        keep_branch,
+        archive,
+        dry_run,
+        ref filters,
+    } = *options;
+    let vcs = vcs::detect_vcs()?;
+    let config = config::Config::load(None)?;
+    let worktrees = vcs.list_workspaces()?;
+    let main_branch = vcs.get_default_branch()?;
+    let main_worktree_root = vcs.get_main_workspace_root()?;
+
+    let globs = patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).with_context(|| format!("Invalid pattern '{}'", p)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let agents = match filters.status {
+        Some(_) => {
+            let mux = create_backend(detect_backend());
+            StateStore::new().and_then(|store| store.load_reconciled_agents(mux.as_ref()))?
+        }
+        None => Vec::new(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let locked = vcs.list_locked_workspaces();
+    let mut to_remove: Vec<(String, String, bool)> = Vec::new(); // (handle, branch, unmerged)
+    let mut skipped_locked: Vec<String> = Vec::new();
+    let mut skipped_uncommitted: Vec<String> = Vec::new();
+
+    for (path, branch) in worktrees {
+        // Skip main branch/worktree and detached HEAD
+        if branch == main_branch || branch == "(detached)" || path == main_worktree_root {
+            continue;
+        }
+
+        let handle = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&branch)
+            .to_string();
+
+        let selected = globs.is_empty()
+            || globs
+                .iter()
+                .any(|g| g.matches(&handle) || g.matches(&branch));
+        if !selected {
+            continue;
+        }
+
+        if let Some(status) = filters.status {
+            let matching = workflow::match_agents_to_worktree(&agents, &path);
+            if matching.is_empty() || matching.iter().any(|a| a.status != Some(status)) {
+                continue;
+            }
+        }
+
+        if let Some(age) = filters.older_than {
+            let old_enough = vcs
+                .get_last_commit_time(&branch)
+                .is_ok_and(|ts| now.saturating_sub(ts) >= age);
+            if !old_enough {
+                continue;
+            }
+        }
+
+        // A branch whose merge state can't be determined counts as unmerged
+        let unmerged = !matches!(is_unmerged(vcs.as_ref(), &config, &branch), Ok(None));
+        if filters.merged && unmerged {
+            continue;
+        }
+
+        if !force && let Some(reason) = locked.get(&path) {
+            skipped_locked.push(format!("{}{}", handle, vcs::describe_lock(reason)));
+            continue;
+        }
+
+        if !force && path.exists() && vcs.has_uncommitted_changes(&path).unwrap_or(false) {
+            skipped_uncommitted.push(handle);
+            continue;
+        }
+
+        to_remove.push((handle, branch, unmerged));
+    }
+
+    // A plain name that matches nothing is most likely a typo
+    if to_remove.is_empty() && skipped_uncommitted.is_empty() && skipped_locked.is_empty() {
+        if let Some(name) = patterns.iter().find(|p| !is_pattern(p))
+            && filters.is_empty()
+        {
+            bail!("No workspace found with name '{}'", name);
+        }
+        println!("No worktrees match.");
+        return Ok(());
+    }
+
+    if !to_remove.is_empty() {
+        println!("The following worktrees will be removed:");
+        for (handle, branch, unmerged) in &to_remove {
+            let label = if handle == branch {
+                handle.clone()
+            } else {
+                format!("{} ({})", handle, branch)
+            };
+            if *unmerged && !keep_branch {
+                println!("  - {} [unmerged commits]", label);
+            } else {
+                println!("  - {}", label);
+            }
+        }
+    }
+
+    print_skipped_locked(&skipped_locked);
+
+    if !skipped_uncommitted.is_empty() {
+        println!(
+            "\nSkipping {} worktree(s) with uncommitted changes:",
+            skipped_uncommitted.len()
+        );
+        for handle in &skipped_uncommitted {
+            println!("  - {}", handle);
+        }
+    }
+
+    if dry_run {
+        println!("\nDry run: nothing was removed.");
+        return Ok(());
+    }
+    if to_remove.is_empty() {
+        println!("\nUse --force to remove these anyway.");
+        return Ok(());
+    }
+
+    if !force {
+        print!(
+            "\nAre you sure you want to remove {} worktree(s)? [y/N] ",
+            to_remove.len()
+        );
+        io::stdout().flush().context("Failed to flush stdout")?;
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read user input")?;
+
+        if input.trim().to_lowercase() != "y" {
+            println!("Aborted.");
+            return Ok(());
+        }
+
+        // Deleting unmerged branches needs its own confirmation, as in a plain remove
+        if !keep_branch && to_remove.iter().any(|(_, _, unmerged)| *unmerged) {
+            println!("\nThe following branches have commits not merged into their base:");
+            for (_, branch, _) in to_remove.iter().filter(|(_, _, unmerged)| *unmerged) {
+                println!("  - {}", branch);
+            }
+            print!("Delete these branches too? [y/N] ");
+            io::stdout().flush().context("Failed to flush stdout")?;
+
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .context("Failed to read user input")?;
+
+            if input.trim().to_lowercase() != "y" {
+                to_remove.retain(|(_, _, unmerged)| !unmerged);
+                println!("Skipping worktrees with unmerged commits.");
+                if to_remove.is_empty() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    let mut success_count = 0;
+    let mut failed: Vec<(String, String)> = Vec::new();
+
+    for (handle, _, _) in to_remove {
+        match remove_worktree(&handle, true, keep_branch, archive) {
+            Ok(()) => success_count += 1,
+            Err(e) => failed.push((handle, format!("{:#}", e))),
+        }
+    }
+
+    if success_count > 0 {
+        println!("\n✓ Successfully removed {} worktree(s)", success_count);
+    }
+
+    if !failed.is_empty() {
+        eprintln!("\nFailed to remove {} worktree(s):", failed.len());
+        for (handle, error) in &failed {
+            eprintln!("  - {}: {}", handle, error);
+        }
+        return Err(anyhow!("Some worktrees could not be removed"));
     }
 
     Ok(())
 }
 
 /// Check if a branch has unmerged commits. Returns Some(base) if unmerged, None otherwise.
-fn is_unmerged(vcs: &dyn vcs::Vcs, branch: &str) -> Result<Option<String>> {
+fn is_unmerged(
+    vcs: &dyn vcs::Vcs,
+    config: &config::Config,
+    branch: &str,
+) -> Result<Option<String>> {
     let main_branch = vcs.get_default_branch().unwrap_or_else(|_| "main".to_string());
 
-    let base = vcs.get_branch_base(branch)
-        .ok()
-        .unwrap_or_else(|| main_branch.clone());
+    let base = workflow::branch_base(vcs, config, branch, &main_branch);
 
     let base_commit = match vcs.get_merge_base(&base) {
         Ok(b) => b,
@@ -173,13 +502,16 @@ fn is_unmerged(vcs: &dyn vcs::Vcs, branch: &str) -> Result<Option<String>> {
 }
 
 /// Remove all managed worktrees (except main)
-fn run_all(force: bool, keep_branch: bool) -> Result<()> {
+fn run_all(force: bool, keep_branch: bool, archive: bool) -> Result<()> {
     let vcs = vcs::detect_vcs()?;
+    let config = config::Config::load(None)?;
     let worktrees = vcs.list_workspaces()?;
     let main_branch = vcs.get_default_branch()?;
     let main_worktree_root = vcs.get_main_workspace_root()?;
 
+    let locked = vcs.list_locked_workspaces();
     let mut to_remove: Vec<(PathBuf, String, String)> = Vec::new();
+    let mut skipped_locked: Vec<String> = Vec::new();
     let mut skipped_uncommitted: Vec<String> = Vec::new();
     let mut skipped_unmerged: Vec<String> = Vec::new();
 
@@ -194,6 +526,12 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
             continue;
         }
 
+        // Locked worktrees are only removed with --force
+        if !force && let Some(reason) = locked.get(&path) {
+            skipped_locked.push(format!("{}{}", branch, vcs::describe_lock(reason)));
+            continue;
+        }
+
         // Check for uncommitted changes
         if !force && path.exists() && vcs.has_uncommitted_changes(&path).unwrap_or(false) {
             skipped_uncommitted.push(branch);
@@ -202,9 +540,7 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
 
         // Check for unmerged commits (only when deleting the branch)
         if !force && !keep_branch {
-            let base = vcs.get_branch_base(&branch)
-                .ok()
-                .unwrap_or_else(|| main_branch.clone());
+            let base = workflow::branch_base(vcs.as_ref(), &config, &branch, &main_branch);
             if let Ok(merge_base) = vcs.get_merge_base(&base)
                 && let Ok(unmerged_branches) = vcs.get_unmerged_branches(&merge_base)
                 && unmerged_branches.contains(&branch)
@@ -223,13 +559,23 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
         to_remove.push((path, branch, handle));
     }
 
-    if to_remove.is_empty() && skipped_uncommitted.is_empty() && skipped_unmerged.is_empty() {
+    if to_remove.is_empty()
+        && skipped_locked.is_empty()
+        && skipped_uncommitted.is_empty()
+        && skipped_unmerged.is_empty()
+    {
         println!("No worktrees to remove.");
         return Ok(());
     }
 
     if to_remove.is_empty() {
         println!("No removable worktrees found.");
+        if !skipped_locked.is_empty() {
+            println!("\nSkipped {} locked worktree(s):", skipped_locked.len());
+            for branch in &skipped_locked {
+                println!("  - {}", branch);
+            }
+        }
         if !skipped_uncommitted.is_empty() {
             println!(
                 "\nSkipped {} worktree(s) with uncommitted changes:",
@@ -258,6 +604,8 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
         println!("  - {}", branch);
     }
 
+    print_skipped_locked(&skipped_locked);
+
     if !skipped_uncommitted.is_empty() {
         println!(
             "\nSkipping {} worktree(s) with uncommitted changes:",
@@ -302,9 +650,9 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
     let mut failed: Vec<(String, String)> = Vec::new();
 
     for (_, branch, handle) in to_remove {
-        match remove_worktree(&handle, true, keep_branch) {
+        match remove_worktree(&handle, true, keep_branch, archive) {
             Ok(()) => success_count += 1,
-            Err(e) => failed.push((branch, e.to_string())),
+            Err(e) => failed.push((branch, format!("{:#}", e))),
         }
     }
 
@@ -324,7 +672,7 @@ fn run_all(force: bool, keep_branch: bool) -> Result<()> {
 }
 
 /// Remove worktrees whose upstream remote branch has been deleted
-fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
+fn run_gone(force: bool, keep_branch: bool, archive: bool) -> Result<()> {
     let vcs = vcs::detect_vcs()?;
 
     // Fetch with prune to update remote-tracking refs
@@ -338,7 +686,9 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
     let gone_branches = vcs.get_gone_branches().unwrap_or_default();
 
     // Find worktrees whose upstream is gone
+    let locked = vcs.list_locked_workspaces();
     let mut to_remove: Vec<(PathBuf, String, String)> = Vec::new();
+    let mut skipped_locked: Vec<String> = Vec::new();
     let mut skipped_uncommitted: Vec<String> = Vec::new();
 
     for (path, branch) in worktrees {
@@ -357,6 +707,12 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
             continue;
         }
 
+        // Locked worktrees are only removed with --force
+        if !force && let Some(reason) = locked.get(&path) {
+            skipped_locked.push(format!("{}{}", branch, vcs::describe_lock(reason)));
+            continue;
+        }
+
         // Check for uncommitted changes
         if !force && path.exists() && vcs.has_uncommitted_changes(&path).unwrap_or(false) {
             skipped_uncommitted.push(branch);
@@ -372,13 +728,19 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
         to_remove.push((path, branch, handle));
     }
 
-    if to_remove.is_empty() && skipped_uncommitted.is_empty() {
+    if to_remove.is_empty() && skipped_locked.is_empty() && skipped_uncommitted.is_empty() {
         println!("No worktrees with gone upstreams found.");
         return Ok(());
     }
 
     if to_remove.is_empty() {
         println!("No worktrees to remove.");
+        if !skipped_locked.is_empty() {
+            println!("\nSkipped {} locked worktree(s):", skipped_locked.len());
+            for branch in &skipped_locked {
+                println!("  - {}", branch);
+            }
+        }
         if !skipped_uncommitted.is_empty() {
             println!(
                 "\nSkipped {} worktree(s) with uncommitted changes:",
@@ -398,6 +760,8 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
         println!("  - {}", branch);
     }
 
+    print_skipped_locked(&skipped_locked);
+
     if !skipped_uncommitted.is_empty() {
         println!(
             "\nSkipping {} worktree(s) with uncommitted changes:",
@@ -432,9 +796,9 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
     let mut failed: Vec<(String, String)> = Vec::new();
 
     for (_, branch, handle) in to_remove {
-        match remove_worktree(&handle, true, keep_branch) {
+        match remove_worktree(&handle, true, keep_branch, archive) {
             Ok(()) => success_count += 1,
-            Err(e) => failed.push((branch, e.to_string())),
+            Err(e) => failed.push((branch, format!("{:#}", e))),
         }
     }
 
@@ -453,15 +817,26 @@ fn run_gone(force: bool, keep_branch: bool) -> Result<()> {
     Ok(())
 }
 
+/// Report the locked worktrees a batch removal left in place.
+fn print_skipped_locked(locked: &[String]) {
+    if locked.is_empty() {
+        return;
+    }
+    println!("\nSkipping {} locked worktree(s):", locked.len());
+    for name in locked {
+        println!("  - {}", name);
+    }
+}
+
 /// Execute the actual worktree removal
-fn remove_worktree(handle: &str, force: bool, keep_branch: bool) -> Result<()> {
+fn remove_worktree(handle: &str, force: bool, keep_branch: bool, archive: bool) -> Result<()> {
     let config = config::Config::load(None)?;
     let mux = create_backend(detect_backend());
     let context = WorkflowContext::new(config, mux, None)?;
 
     super::announce_hooks(&context.config, None, super::HookPhase::PreRemove);
 
-    let result = workflow::remove(handle, force, keep_branch, &context)
+    let result = workflow::remove(handle, force, keep_branch, archive, &context)
         .context("Failed to remove worktree")?;
 
     if keep_branch {
@@ -478,3 +853,34 @@ fn remove_worktree(handle: &str, force: bool, keep_branch: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_age_units() {
+        assert_eq!(parse_age("90s"), Ok(90));
+        assert_eq!(parse_age("30m"), Ok(1800));
+        assert_eq!(parse_age("12h"), Ok(43_200));
+        assert_eq!(parse_age("14d"), Ok(1_209_600));
+        assert_eq!(parse_age("2w"), Ok(1_209_600));
+    }
+
+    #[test]
+    fn parse_age_rejects_invalid() {
+        assert!(parse_age("14").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3y").is_err());
+        assert!(parse_age("-1d").is_err());
+        assert!(parse_age("99999999999999999w").is_err());
+    }
+
+    #[test]
+    fn is_pattern_detects_glob_syntax() {
+        assert!(is_pattern("experiment-*"));
+        assert!(is_pattern("fix-?"));
+        assert!(is_pattern("[ab]-test"));
+        assert!(!is_pattern("feature-auth"));
+    }
+}
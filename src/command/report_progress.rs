@@ -0,0 +1,70 @@
+//! `workmux report-progress`: let the agent in the current pane report how
+//! far along its task is, for the dashboard's Progress column.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, bail};
+use tracing::warn;
+
+use crate::multiplexer::{TaskProgress, create_backend, detect_backend};
+
+pub fn run(
+    step: Option<u32>,
+    total: Option<u32>,
+    message: Option<String>,
+    clear: bool,
+) -> Result<()> {
+    let progress = if clear {
+        None
+    } else {
+        let (Some(step), Some(total)) = (step, total) else {
+            bail!("Both <STEP> and <TOTAL> are required unless --clear is given");
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Some(TaskProgress::new(step, total, message.as_deref(), now)?)
+    };
+
+    // Inside a sandbox guest, route through RPC to the host supervisor
+    if crate::sandbox::guest::is_sandbox_guest() {
+        return run_via_rpc(progress);
+    }
+
+    let mux = create_backend(detect_backend());
+    // Fail silently if not in a multiplexer session, like status hooks
+    let Some(pane_id) = mux.current_pane_id() else {
+        return Ok(());
+    };
+    crate::state::set_agent_task_progress(&*mux, &pane_id, progress);
+    Ok(())
+}
+
+/// Send the report via RPC when running inside a sandbox guest.
+fn run_via_rpc(progress: Option<TaskProgress>) -> Result<()> {
+    use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
+
+    // A total of 0 clears the progress
+    let request = match progress {
+        Some(progress) => RpcRequest::ReportProgress {
+            step: progress.step,
+            total: progress.total,
+            message: progress.message,
+        },
+        None => RpcRequest::ReportProgress {
+            step: 0,
+            total: 0,
+            message: None,
+        },
+    };
+
+    let mut client = RpcClient::from_env()?;
+    match client.call(&request)? {
+        RpcResponse::Error { message } => {
+            warn!(error = %message, "RPC ReportProgress failed");
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
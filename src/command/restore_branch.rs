@@ -0,0 +1,48 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use crate::command::sandbox::format_duration_since;
+use crate::config;
+use crate::multiplexer::{create_backend, detect_backend};
+use crate::workflow::WorkflowContext;
+use crate::workflow::branch_backup;
+
+pub fn run(name: Option<&str>) -> Result<()> {
+    let (config, config_location) = config::Config::load_with_location(None)?;
+    let mux = create_backend(detect_backend());
+    let context = WorkflowContext::new(config, mux, config_location)?;
+
+    let Some(branch) = name else {
+        let backups =
+            branch_backup::list_backups(&branch_backup::backup_dir(&context.main_worktree_root)?);
+        if backups.is_empty() {
+            println!("No branch backups");
+            if !context.config.branch_backup.enabled() {
+                println!("Set 'branch_backup.enabled: true' to back up branches before deletion");
+            }
+            return Ok(());
+        }
+        let width = backups.iter().map(|b| b.branch.len()).max().unwrap_or(0);
+        for backup in backups {
+            let saved = UNIX_EPOCH + Duration::from_secs(backup.created);
+            println!(
+                "{:width$}  {}",
+                backup.branch,
+                format_duration_since(saved),
+                width = width
+            );
+        }
+        return Ok(());
+    };
+
+    let backup = branch_backup::restore_branch(&context, branch)?;
+    let saved = UNIX_EPOCH + Duration::from_secs(backup.created);
+    println!(
+        "✓ Restored branch '{}' from backup saved {}",
+        branch,
+        format_duration_since(saved)
+    );
+    println!("  Open it with: workmux add {}", branch);
+    Ok(())
+}
@@ -21,6 +21,7 @@ use crate::sandbox::lima::{LimaInstance, parse_lima_instances};
 Container commands:
   build            Build the sandbox container image locally
   pull             Pull the latest sandbox image from the container registry
+  preheat          Pull the images agents will need ahead of time
   init-dockerfile  Export customizable Dockerfile templates
 
 Lima commands:
@@ -46,6 +47,17 @@ pub enum SandboxCommand {
     Build,
     /// Pull the latest sandbox image from the container registry.
     Pull,
+    /// Pull the sandbox images agents will need ahead of time, and report
+    /// their size.
+    Preheat {
+        /// Agent whose image to pull (repeatable; defaults to the configured agent)
+        #[arg(long)]
+        agent: Vec<String>,
+
+        /// Pull in a detached process, logging to the state directory
+        #[arg(long)]
+        background: bool,
+    },
     /// Export customizable Dockerfile templates for building your own sandbox image.
     InitDockerfile {
         /// Overwrite existing Dockerfiles
@@ -147,6 +159,7 @@ pub fn run(args: SandboxArgs) -> Result<()> {
     match args.command {
         SandboxCommand::Build => run_build(),
         SandboxCommand::Pull => run_pull(),
+        SandboxCommand::Preheat { agent, background } => run_preheat(&agent, background),
         SandboxCommand::InitDockerfile { force } => run_init_dockerfile(force),
         SandboxCommand::Run {
             worktree,
@@ -202,6 +215,38 @@ fn run_pull() -> Result<()> {
     Ok(())
 }
 
+fn run_preheat(agents: &[String], background: bool) -> Result<()> {
+    let config = Config::load(None)?;
+    if config.sandbox.backend() == crate::config::SandboxBackend::Lima {
+        bail!(
+            "Preheating only applies to the container backend; Lima downloads its image when the first VM is created"
+        );
+    }
+    let agents = sandbox::preheat::agents_to_preheat(&config, agents);
+
+    if background {
+        let log = sandbox::preheat::spawn_in_background(&agents)?;
+        println!(
+            "Preheating sandbox images in the background (log: {})",
+            log.display()
+        );
+        return Ok(());
+    }
+
+    let images = sandbox::preheat::preheat(&config.sandbox, &agents)?;
+    println!();
+    for image in &images {
+        let size = image
+            .size_bytes
+            .map_or_else(|| "unknown size".to_string(), super::gc::format_size);
+        let state = if image.pulled { "pulled" } else { "present" };
+        println!("  {} ({}, {})", image.image, state, size);
+    }
+    let total: u64 = images.iter().filter_map(|i| i.size_bytes).sum();
+    println!("Sandbox image cache: {}", super::gc::format_size(total));
+    Ok(())
+}
+
 fn run_init_dockerfile(force: bool) -> Result<()> {
     use console::style;
 
@@ -597,19 +642,19 @@ fn install_dev_container(binary_path: &Path, image_name: &str, config: &Config)
     Ok(true)
 }
 
+/// A workmux Lima VM (`wm-*`).
 #[derive(Debug)]
-struct VmInfo {
-    name: String,
-    status: String,
-    created: Option<SystemTime>,
-    last_accessed: Option<SystemTime>,
+pub(crate) struct VmInfo {
+    pub name: String,
+    pub status: String,
+    /// Instance directory holding the VM's disk
+    pub dir: PathBuf,
+    pub created: Option<SystemTime>,
+    pub last_accessed: Option<SystemTime>,
 }
 
-fn run_prune(force: bool) -> Result<()> {
-    if !LimaInstance::is_lima_available() {
-        bail!("limactl is not installed or not in PATH");
-    }
-
+/// List the Lima VMs workmux created.
+pub(crate) fn list_workmux_vms() -> Result<Vec<VmInfo>> {
     let output = Command::new("limactl")
         .arg("list")
         .arg("--json")
@@ -653,11 +698,44 @@ fn run_prune(force: bool) -> Result<()> {
         vm_infos.push(VmInfo {
             name: instance.name,
             status: instance.status,
+            dir: vm_dir,
             created,
             last_accessed,
         });
     }
 
+    Ok(vm_infos)
+}
+
+/// Delete a Lima VM and its workmux state directory.
+pub(crate) fn delete_vm(name: &str) -> Result<()> {
+    let output = Command::new("limactl")
+        .arg("delete")
+        .arg(name)
+        .arg("--force")
+        .output()
+        .context("Failed to execute limactl delete")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    // Clean up per-VM state directory
+    if let Ok(state_dir) = lima::mounts::lima_state_dir_path(name)
+        && state_dir.exists()
+        && let Err(e) = std::fs::remove_dir_all(&state_dir)
+    {
+        tracing::warn!(vm = %name, error = %e, "failed to clean up state dir");
+    }
+    Ok(())
+}
+
+fn run_prune(force: bool) -> Result<()> {
+    if !LimaInstance::is_lima_available() {
+        bail!("limactl is not installed or not in PATH");
+    }
+
+    let vm_infos = list_workmux_vms()?;
+
     if vm_infos.is_empty() {
         println!("No workmux Lima VMs found.");
         return Ok(());
@@ -702,29 +780,10 @@ fn run_prune(force: bool) -> Result<()> {
         print!("  Deleting {}... ", vm.name);
         io::stdout().flush().ok();
 
-        let result = Command::new("limactl")
-            .arg("delete")
-            .arg(&vm.name)
-            .arg("--force")
-            .output();
-
-        match result {
-            Ok(output) if output.status.success() => {
+        match delete_vm(&vm.name) {
+            Ok(()) => {
                 println!("done");
                 deleted_count += 1;
-
-                // Clean up per-VM state directory
-                if let Ok(state_dir) = lima::mounts::lima_state_dir_path(&vm.name)
-                    && state_dir.exists()
-                    && let Err(e) = std::fs::remove_dir_all(&state_dir)
-                {
-                    tracing::warn!(vm = %vm.name, error = %e, "failed to clean up state dir");
-                }
-            }
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                println!("failed");
-                failed.push((vm.name, stderr.trim().to_string()));
             }
             Err(e) => {
                 println!("failed");
@@ -751,7 +810,7 @@ fn run_prune(force: bool) -> Result<()> {
 }
 
 /// Format duration since a timestamp as human-readable string.
-fn format_duration_since(time: SystemTime) -> String {
+pub(crate) fn format_duration_since(time: SystemTime) -> String {
     let now = SystemTime::now();
 
     let duration = match now.duration_since(time) {
@@ -9,15 +9,18 @@ use std::process::Command;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use crate::config::{Config, SandboxBackend, SandboxRuntime};
 use crate::multiplexer;
 use crate::sandbox::build_docker_run_args;
 use crate::sandbox::ensure_sandbox_config_dirs;
 use crate::sandbox::lima;
+use crate::sandbox::net_stats;
 use crate::sandbox::network_proxy::NetworkProxy;
 use crate::sandbox::rpc::{RpcContext, RpcServer, generate_token};
+use crate::sandbox::secrets::{self, Secret};
+use crate::sandbox::shared_env;
 use crate::sandbox::shims;
 use crate::sandbox::toolchain;
 use crate::state::StateStore;
@@ -59,6 +62,16 @@ impl Drop for ContainerGuard {
     }
 }
 
+/// Guard that removes the Lima secrets env file when dropped.
+/// The guest deletes it after sourcing; this covers launch failures.
+struct SecretsFileGuard(PathBuf);
+
+impl Drop for SecretsFileGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
 /// Run the sandbox supervisor.
 ///
 /// Detects the sandbox backend from config and dispatches to the
@@ -70,18 +83,31 @@ pub fn run(worktree: PathBuf, worktree_root: Option<PathBuf>, command: Vec<Strin
 
     let config = Config::load(None)?;
     let worktree = worktree.canonicalize().unwrap_or_else(|_| worktree.clone());
+    let wt_root = worktree_root
+        .map(|p| p.canonicalize().unwrap_or(p))
+        .unwrap_or_else(|| worktree.clone());
+
+    // Regenerate the env shared with host panes so both sides see the
+    // current config
+    let shared_env = shared_env::sync(&config.sandbox, &wt_root)?
+        .map(|env| env.vars)
+        .unwrap_or_default();
 
     match config.sandbox.backend() {
-        SandboxBackend::Lima => run_lima(&config, &worktree, &command),
+        SandboxBackend::Lima => run_lima(&config, &worktree, &shared_env, &command),
         SandboxBackend::Container => {
-            let wt_root = worktree_root
-                .map(|p| p.canonicalize().unwrap_or(p))
-                .unwrap_or_else(|| worktree.clone());
-            run_container(&config, &worktree, &wt_root, &command)
+            run_container(&config, &worktree, &wt_root, &shared_env, &command)
         }
     }
 }
 
+/// Pane the supervisor runs in, or an empty string outside a multiplexer.
+fn current_pane_id() -> String {
+    multiplexer::create_backend(multiplexer::detect_backend())
+        .current_pane_id()
+        .unwrap_or_default()
+}
+
 /// Start RPC server and return (server, port, token, context).
 /// Shared setup between Lima and Container backends.
 fn start_rpc(
@@ -89,6 +115,9 @@ fn start_rpc(
     allowed_commands: HashSet<String>,
     detected_toolchain: toolchain::DetectedToolchain,
     allow_unsandboxed_host_exec: bool,
+    notify_denied: bool,
+    redact_prompts: bool,
+    secrets: &[Secret],
 ) -> Result<(RpcServer, u16, String, Arc<RpcContext>)> {
     let rpc_server = RpcServer::bind()?;
     let rpc_port = rpc_server.port();
@@ -106,11 +135,29 @@ fn start_rpc(
         allowed_commands,
         detected_toolchain,
         allow_unsandboxed_host_exec,
+        audit_denied: true,
+        notify_denied,
+        redact_values: secrets.iter().map(|s| s.value.clone()).collect(),
+        redact_prompts,
     });
 
     Ok((rpc_server, rpc_port, rpc_token, ctx))
 }
 
+/// Resolve configured secrets on the host. Returns an empty list when none
+/// are configured.
+fn resolve_secrets(config: &Config) -> Result<Vec<Secret>> {
+    let Some(configured) = config.sandbox.secrets() else {
+        return Ok(Vec::new());
+    };
+    let resolved = secrets::resolve_all(configured)?;
+    info!(
+        names = ?resolved.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        "resolved sandbox secrets"
+    );
+    Ok(resolved)
+}
+
 /// Extract git `user.name` and `user.email` from the host's git config and
 /// return `GIT_CONFIG_*` environment variable pairs to inject into the sandbox.
 ///
@@ -147,7 +194,12 @@ fn git_user_config_envs(worktree_dir: &Path) -> Vec<(String, String)> {
     envs
 }
 
-fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32> {
+fn run_lima(
+    config: &Config,
+    worktree: &Path,
+    shared_env: &BTreeMap<String, String>,
+    command: &[String],
+) -> Result<i32> {
     info!(worktree = %worktree.display(), "sandbox supervisor starting (lima)");
 
     // Ensure Lima VM is running
@@ -162,6 +214,8 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
         tracing::warn!(vm_name = %vm_name, error = %e, "failed to seed ~/.claude.json; continuing");
     }
 
+    let secrets = resolve_secrets(config)?;
+
     // Detect toolchain for both agent wrapping and host-exec
     let detected = toolchain::resolve_toolchain(&config.sandbox.toolchain(), worktree);
     if detected != toolchain::DetectedToolchain::None {
@@ -181,6 +235,9 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
         allowed_commands,
         detected.clone(),
         config.sandbox.allow_unsandboxed_host_exec(),
+        config.sandbox.notify_denied(),
+        config.redact_prompts(),
+        &secrets,
     )?;
     let _rpc_handle = rpc_server.spawn(ctx);
 
@@ -216,11 +273,30 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
         env_exports.push(format!("{}='{}'", key, crate::shell::shell_escape(&val)));
     }
 
-    let exports: String = env_exports
+    for (key, val) in shared_env {
+        env_exports.push(format!("{}='{}'", key, crate::shell::shell_escape(val)));
+    }
+
+    let mut exports: String = env_exports
         .iter()
         .map(|e| format!("export {e}"))
         .collect::<Vec<_>>()
         .join("; ");
+
+    // Secrets go through a private file in the mounted state dir rather than
+    // the limactl command line, where they would be visible in `ps`.
+    let _secrets_guard = if secrets.is_empty() {
+        None
+    } else {
+        let host_path = secrets::write_env_file(&state_dir, &secrets)?;
+        let file_name = host_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let guest_path = format!("$HOME/.workmux-state/{file_name}");
+        exports.push_str(&format!(r#"; . "{guest_path}"; rm -f "{guest_path}""#));
+        Some(SecretsFileGuard(host_path))
+    };
     let user_command = command.join(" ");
 
     let final_command = toolchain::wrap_command(&user_command, &detected);
@@ -232,6 +308,14 @@ fn run_lima(config: &Config, worktree: &Path, command: &[String]) -> Result<i32>
 
     debug!(vm = %vm_name, command = %user_command, "spawning limactl shell");
 
+    net_stats::spawn_sampler(
+        net_stats::Source::Lima {
+            vm: vm_name.clone(),
+        },
+        current_pane_id(),
+        None,
+    );
+
     let status = lima_cmd
         .status()
         .context("Failed to execute limactl shell")?;
@@ -245,6 +329,7 @@ fn run_container(
     config: &Config,
     pane_cwd: &Path,
     worktree_root: &Path,
+    shared_env: &BTreeMap<String, String>,
     command: &[String],
 ) -> Result<i32> {
     info!(
@@ -265,6 +350,8 @@ fn run_container(
     // Ensure sandbox config dirs exist before building container args
     ensure_sandbox_config_dirs()?;
 
+    let secrets = resolve_secrets(config)?;
+
     // Merge built-in host commands (e.g. afplay) with user-configured ones
     let host_commands = shims::effective_host_commands(config.sandbox.host_commands());
     let allowed_commands: HashSet<String> = host_commands.iter().cloned().collect();
@@ -297,6 +384,9 @@ fn run_container(
         allowed_commands,
         detected.clone(),
         config.sandbox.allow_unsandboxed_host_exec(),
+        config.sandbox.notify_denied(),
+        config.redact_prompts(),
+        &secrets,
     )?;
     let _rpc_handle = rpc_server.spawn(ctx);
 
@@ -307,9 +397,10 @@ fn run_container(
         let proxy = NetworkProxy::bind(allowed)?;
         let proxy_port = proxy.port();
         let proxy_token = proxy.token().to_string();
+        let tunnels = proxy.tunnels();
         let handle = proxy.spawn();
         info!(port = proxy_port, "network proxy started");
-        Some((proxy_port, proxy_token, handle))
+        Some((proxy_port, proxy_token, handle, tunnels))
     } else {
         None
     };
@@ -348,7 +439,7 @@ fn run_container(
         ("WM_RPC_TOKEN".into(), rpc_token.clone()),
     ];
 
-    if let Some((proxy_port, ref proxy_token, _)) = proxy {
+    if let Some((proxy_port, ref proxy_token, _, _)) = proxy {
         let proxy_url = format!("http://workmux:{}@{}:{}", proxy_token, rpc_host, proxy_port);
         let no_proxy = format!("localhost,127.0.0.1,{}", rpc_host);
 
@@ -367,6 +458,9 @@ fn run_container(
     // Inject host git user config (user.name, user.email) for commits
     owned_envs.extend(git_user_config_envs(worktree_root));
 
+    // Same values the host panes of the worktree source
+    owned_envs.extend(shared_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+
     // Borrow owned envs for call site
     let env_refs: Vec<(&str, &str)> = owned_envs
         .iter()
@@ -392,12 +486,32 @@ fn run_container(
     docker_args.insert(1, "--name".to_string());
     docker_args.insert(2, container_name.clone());
 
+    // Secrets are passed by name only; the runtime client reads the values
+    // from its own environment so they never appear in the argument list.
+    for secret in &secrets {
+        docker_args.insert(3, secret.name.clone());
+        docker_args.insert(3, "--env".to_string());
+    }
+
     let redacted_args: Vec<_> = docker_args.iter().map(|a| redact_env_arg(a)).collect();
     debug!(runtime = runtime_bin, container = %container_name, args = ?redacted_args, "spawning container");
 
     // Background freshness check (non-blocking)
     let freshness_image = config.sandbox.resolved_image(agent);
-    crate::sandbox::freshness::check_in_background(freshness_image, config.sandbox.runtime());
+    crate::sandbox::freshness::check_in_background(
+        freshness_image,
+        config.sandbox.runtime(),
+        config.sandbox.prefetch().then_some(agent),
+    );
+
+    net_stats::spawn_sampler(
+        net_stats::Source::Container {
+            runtime: runtime_bin,
+            name: container_name.clone(),
+        },
+        current_pane_id(),
+        proxy.as_ref().map(|(_, _, _, tunnels)| Arc::clone(tunnels)),
+    );
 
     // Create guard to stop container on exit (panic, SIGTERM, etc.)
     let _guard = ContainerGuard {
@@ -408,6 +522,7 @@ fn run_container(
 
     let status = Command::new(runtime_bin)
         .args(&docker_args)
+        .envs(secrets.iter().map(|s| (&s.name, &s.value)))
         .status()
         .with_context(|| format!("Failed to execute {} run", runtime_bin))?;
 
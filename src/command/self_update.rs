@@ -0,0 +1,391 @@
+//! `workmux self-update`: replace the running binary with the newest release
+//! from GitHub.
+//!
+//! Releases are the archives `.github/workflows/release.yml` publishes, one
+//! per platform with a `.sha256` checksum next to it. The new binary is
+//! checked against the local state files before it replaces the old one, so
+//! an update can't leave agents in a state the installed version can't read.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::ValueEnum;
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::cmd::Cmd;
+use crate::state::StateStore;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/raine/workmux/releases?per_page=30";
+
+/// Which releases to update to.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Channel {
+    /// Tagged releases
+    #[default]
+    Stable,
+    /// Pre-releases as well as tagged releases
+    Nightly,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    fn version(&self) -> Option<Version> {
+        Version::parse(self.tag_name.trim_start_matches('v')).ok()
+    }
+
+    fn asset(&self, name: &str) -> Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| anyhow!("Release {} has no {} artifact", self.tag_name, name))
+    }
+}
+
+/// The newest release on `channel`, with its version.
+fn select_release(releases: Vec<Release>, channel: Channel) -> Option<(Version, Release)> {
+    releases
+        .into_iter()
+        .filter(|r| !r.draft && (channel == Channel::Nightly || !r.prerelease))
+        .filter_map(|r| Some((r.version()?, r)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+}
+
+/// Name of the release archive for this platform, without extension.
+fn platform_artifact(os: &str, arch: &str) -> Result<String> {
+    let os = match os {
+        "macos" => "darwin",
+        "linux" => "linux",
+        other => bail!("No pre-built binaries for {}", other),
+    };
+    let arch = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => bail!("No pre-built binaries for {}", other),
+    };
+    Ok(format!("workmux-{}-{}", os, arch))
+}
+
+/// The package manager that owns `exe`, with the command that updates it.
+fn package_manager(exe: &Path) -> Option<(&'static str, &'static str)> {
+    let path = exe.to_string_lossy();
+    if path.contains("/Cellar/") || path.contains("/homebrew/") || path.contains("/linuxbrew/") {
+        Some(("Homebrew", "brew upgrade workmux"))
+    } else if path.contains("/.cargo/bin/") {
+        Some(("Cargo", "cargo install workmux"))
+    } else if path.starts_with("/nix/store/") {
+        Some(("Nix", "nix profile upgrade workmux"))
+    } else {
+        None
+    }
+}
+
+/// The checksum for `file_name` in `shasum -a 256` output.
+fn parse_checksum(contents: &str, file_name: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let hash = fields.next()?;
+        let name = fields.next()?.trim_start_matches('*');
+        (name == file_name && hash.len() == 64).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn curl(url: &str, output: Option<&Path>) -> Result<Vec<u8>> {
+    let mut args = vec![
+        "-fsSL",
+        "--retry",
+        "3",
+        "--connect-timeout",
+        "10",
+        "--max-time",
+        "300",
+        "-H",
+        "Accept: application/vnd.github+json",
+    ];
+    let output_path = output.map(|p| p.to_string_lossy().into_owned());
+    if let Some(path) = &output_path {
+        args.extend(["-o", path.as_str()]);
+    }
+    args.push(url);
+    Ok(Cmd::new("curl").args(&args).run()?.stdout)
+}
+
+fn fetch_releases() -> Result<Vec<Release>> {
+    let body = curl(RELEASES_URL, None).context("Failed to fetch releases from GitHub")?;
+    serde_json::from_slice(&body).context("Failed to parse GitHub releases")
+}
+
+/// Download the release archive into `dir`, verify its checksum and extract
+/// the binary. Returns the path of the extracted binary.
+fn download(release: &Release, artifact: &str, dir: &Path) -> Result<PathBuf> {
+    let archive_name = format!("{}.tar.gz", artifact);
+    let checksum_name = format!("{}.sha256", artifact);
+    let archive = release.asset(&archive_name)?;
+    let checksum = release.asset(&checksum_name)?;
+
+    let archive_path = dir.join(&archive_name);
+    curl(&archive.browser_download_url, Some(&archive_path))
+        .with_context(|| format!("Failed to download {}", archive_name))?;
+    let checksums = curl(&checksum.browser_download_url, None)
+        .with_context(|| format!("Failed to download {}", checksum_name))?;
+
+    let expected = parse_checksum(&String::from_utf8_lossy(&checksums), &archive_name)
+        .ok_or_else(|| anyhow!("{} has no checksum for {}", checksum_name, archive_name))?;
+    let actual = sha256_hex(&fs::read(&archive_path)?);
+    if actual != expected {
+        bail!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            archive_name,
+            expected,
+            actual
+        );
+    }
+
+    let extract_dir = dir.join("extract");
+    fs::create_dir_all(&extract_dir)?;
+    Cmd::new("tar")
+        .args(&["-xzf", &archive_path.to_string_lossy()])
+        .workdir(&extract_dir)
+        .run()
+        .context("Failed to extract release archive")?;
+    let binary = extract_dir.join("workmux");
+    if !binary.is_file() {
+        bail!("Release archive does not contain a workmux binary");
+    }
+    Ok(binary)
+}
+
+/// Run the new binary's state check, so a release that can't read the
+/// current state files is never installed.
+fn check_compatibility(binary: &Path) -> Result<()> {
+    let output = std::process::Command::new(binary)
+        .arg("_check-state")
+        .output()
+        .context("Failed to run the new binary")?;
+    if !output.status.success() {
+        bail!(
+            "The new version can't read the current state files; not updating.\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Replace `exe` with `binary`. The new binary is copied next to `exe` first,
+/// so the final rename stays on one filesystem and is atomic.
+fn replace_binary(binary: &Path, exe: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = exe
+        .parent()
+        .ok_or_else(|| anyhow!("Cannot determine directory of {}", exe.display()))?;
+    let staged = dir.join(format!(".workmux.update.{}", std::process::id()));
+    let result = fs::copy(binary, &staged)
+        .and_then(|_| fs::set_permissions(&staged, fs::Permissions::from_mode(0o755)))
+        .and_then(|_| fs::rename(&staged, exe));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&staged);
+        return Err(e).with_context(|| format!("Failed to replace {}", exe.display()));
+    }
+    Ok(())
+}
+
+pub fn run(channel: Channel, check: bool) -> Result<()> {
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let exe = std::env::current_exe()?.canonicalize()?;
+    let managed = package_manager(&exe);
+    if let Some((manager, command)) = managed
+        && !check
+    {
+        bail!(
+            "workmux was installed with {}; update it with '{}'",
+            manager,
+            command
+        );
+    }
+
+    let releases = crate::spinner::with_spinner("Checking for updates", fetch_releases)?;
+    let Some((latest, release)) = select_release(releases, channel) else {
+        bail!("No {:?} releases found", channel);
+    };
+    if latest <= current {
+        println!("workmux v{} is up to date", current);
+        return Ok(());
+    }
+    if check {
+        println!("Update available: v{} -> v{}", current, latest);
+        if let Some((_, command)) = managed {
+            println!("Run '{}' to update", command);
+        } else {
+            println!("Run 'workmux self-update' to update");
+        }
+        return Ok(());
+    }
+
+    let artifact = platform_artifact(std::env::consts::OS, std::env::consts::ARCH)?;
+    let tmp = tempfile::tempdir()?;
+    let binary = crate::spinner::with_spinner(&format!("Downloading v{}", latest), || {
+        download(&release, &artifact, tmp.path())
+    })?;
+    check_compatibility(&binary)?;
+    replace_binary(&binary, &exe)?;
+
+    let installed = Cmd::new(&exe.to_string_lossy())
+        .arg("--version")
+        .run_and_capture_stdout()
+        .context("Updated binary failed to run")?;
+    println!("✓ Updated to {} ({})", installed, exe.display());
+    Ok(())
+}
+
+/// Exit with an error if any state file doesn't parse with this version.
+/// Run by `self-update` on a downloaded binary before installing it.
+pub fn run_check_state() -> Result<()> {
+    // Migrating here would leave the files unreadable for the installed
+    // version if the update doesn't go through
+    let failures = StateStore::without_migration()?.incompatible_files()?;
+    if failures.is_empty() {
+        return Ok(());
+    }
+    for (path, error) in &failures {
+        eprintln!("{}: {}", path.display(), error);
+    }
+    bail!("{} state file(s) can't be read", failures.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            draft: false,
+            prerelease,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn channel_selects_newest_release() {
+        let releases = || {
+            vec![
+                release("v0.2.0-nightly.3", true),
+                release("v0.1.121", false),
+                release("not-a-version", false),
+                release("v0.1.120", false),
+            ]
+        };
+        let (stable, _) = select_release(releases(), Channel::Stable).unwrap();
+        assert_eq!(stable, Version::parse("0.1.121").unwrap());
+        let (nightly, _) = select_release(releases(), Channel::Nightly).unwrap();
+        assert_eq!(nightly, Version::parse("0.2.0-nightly.3").unwrap());
+    }
+
+    #[test]
+    fn platform_artifact_names_match_release_workflow() {
+        assert_eq!(
+            platform_artifact("macos", "aarch64").unwrap(),
+            "workmux-darwin-arm64"
+        );
+        assert_eq!(
+            platform_artifact("linux", "x86_64").unwrap(),
+            "workmux-linux-amd64"
+        );
+        assert!(platform_artifact("windows", "x86_64").is_err());
+    }
+
+    #[test]
+    fn checksum_is_read_from_shasum_output() {
+        let hash = "a".repeat(64);
+        let contents = format!("{}  workmux-linux-amd64.tar.gz\n", hash);
+        assert_eq!(
+            parse_checksum(&contents, "workmux-linux-amd64.tar.gz"),
+            Some(hash)
+        );
+        assert_eq!(
+            parse_checksum(&contents, "workmux-linux-arm64.tar.gz"),
+            None
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn downloaded_binary_is_verified_and_swapped_in() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir(dir.join("pkg")).unwrap();
+        fs::write(dir.join("pkg/workmux"), "#!/bin/sh\necho new\n").unwrap();
+        let archive = dir.join("workmux-linux-amd64.tar.gz");
+        Cmd::new("tar")
+            .args(&["-czf", &archive.to_string_lossy(), "workmux"])
+            .workdir(&dir.join("pkg"))
+            .run()
+            .unwrap();
+        let hash = sha256_hex(&fs::read(&archive).unwrap());
+        let checksum = dir.join("workmux-linux-amd64.sha256");
+        fs::write(&checksum, format!("{}  workmux-linux-amd64.tar.gz\n", hash)).unwrap();
+
+        let asset = |path: &Path| Asset {
+            name: path.file_name().unwrap().to_string_lossy().into_owned(),
+            browser_download_url: format!("file://{}", path.display()),
+        };
+        let mut release = release("v9.9.9", false);
+        release.assets = vec![asset(&archive), asset(&checksum)];
+
+        let work = dir.join("work");
+        fs::create_dir(&work).unwrap();
+        let binary = download(&release, "workmux-linux-amd64", &work).unwrap();
+        let exe = dir.join("workmux");
+        fs::write(&exe, "old").unwrap();
+        replace_binary(&binary, &exe).unwrap();
+        let output = std::process::Command::new(&exe).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "new\n");
+
+        // A tampered checksum stops the update
+        fs::write(
+            &checksum,
+            format!("{}  workmux-linux-amd64.tar.gz\n", "0".repeat(64)),
+        )
+        .unwrap();
+        let err = download(&release, "workmux-linux-amd64", &work).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn package_manager_installs_are_detected() {
+        let brew = Path::new("/opt/homebrew/Cellar/workmux/0.1.120/bin/workmux");
+        assert_eq!(package_manager(brew).unwrap().0, "Homebrew");
+        let cargo = Path::new("/home/me/.cargo/bin/workmux");
+        assert_eq!(package_manager(cargo).unwrap().0, "Cargo");
+        assert_eq!(package_manager(Path::new("/usr/local/bin/workmux")), None);
+    }
+}
@@ -1,15 +1,27 @@
 use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
 
 use anyhow::{Result, anyhow};
+use tracing::debug;
 
 use crate::config;
-use crate::multiplexer::{create_backend, detect_backend};
-use crate::workflow;
+use crate::multiplexer::{HandshakeSettings, create_backend, detect_backend, handshake};
+use crate::state::pending;
+use crate::{prompt, workflow};
 
-pub fn run(name: &str, text: Option<&str>, file: Option<&str>) -> Result<()> {
+pub fn run(
+    name: &str,
+    text: Option<&str>,
+    file: Option<&str>,
+    attach: &[PathBuf],
+    allow_main: bool,
+) -> Result<()> {
     let cfg = config::Config::load(None).unwrap_or_default();
     let mux = create_backend(detect_backend());
     let vcs = crate::vcs::detect_vcs()?;
+    // Check the guard before looking up agents so the refusal is explicit
+    let (path, _branch) = vcs.find_workspace(name)?;
+    workflow::ensure_not_protected_main(&cfg, vcs.as_ref(), &path, allow_main)?;
     let (_path, agent) = workflow::resolve_worktree_agent(name, mux.as_ref(), vcs.as_ref())?;
 
     // Determine content: positional arg > --file > stdin
@@ -17,6 +29,9 @@ pub fn run(name: &str, text: Option<&str>, file: Option<&str>) -> Result<()> {
         t.to_string()
     } else if let Some(f) = file {
         std::fs::read_to_string(f)?
+    } else if !attach.is_empty() && std::io::stdin().is_terminal() {
+        // Attachments alone are enough to send
+        String::new()
     } else {
         // Guard: don't block on interactive TTY
         if std::io::stdin().is_terminal() {
@@ -29,20 +44,83 @@ pub fn run(name: &str, text: Option<&str>, file: Option<&str>) -> Result<()> {
         buf
     };
 
+    let content = if attach.is_empty() {
+        content
+    } else {
+        let paths = workflow::artifacts::attach(&path, attach)?;
+        prompt::with_attachments(&content, &paths, cfg.agent.as_deref())
+    };
+
     // Strip trailing newline
     let content = content.trim_end_matches('\n');
 
     if content.is_empty() {
         return Err(anyhow!("No content to send"));
     }
+    prompt::ensure_size(content, cfg.max_prompt_kb())?;
+    debug!(
+        pane_id = %agent.pane_id,
+        content = %prompt::log_repr(content, cfg.redact_prompts()),
+        "send:content"
+    );
 
-    // Single-line: use send_keys_to_agent (handles Claude's ! prefix delay)
-    // Multi-line: use paste_multiline (already sends Enter in both backends)
+    // Single-line: use send_agent_input (handles Claude's ! prefix delay and
+    // optional input verification)
+    // Multi-line: paste, in chunks if configured
+    // Both submit with the agent's submit keys
+    let submit = handshake::SubmitSettings::for_agent(&cfg, cfg.agent.as_deref());
     if content.contains('\n') {
-        mux.paste_multiline(&agent.pane_id, content)?;
+        handshake::paste_agent_input(mux.as_ref(), &agent.pane_id, content, &submit)?;
     } else {
-        mux.send_keys_to_agent(&agent.pane_id, content, cfg.agent.as_deref())?;
+        let settings = HandshakeSettings::from_config(&cfg.handshake);
+        handshake::send_agent_input(
+            mux.as_ref(),
+            &agent.pane_id,
+            content,
+            cfg.agent.as_deref(),
+            &settings,
+            &submit,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Deliver the prompts queued for the agents of worktree `name`. With
+/// `retry`, keep retrying them with backoff instead of trying once.
+pub fn run_flush(name: &str, retry: bool) -> Result<()> {
+    let cfg = config::Config::load(None).unwrap_or_default();
+    let mux = create_backend(detect_backend());
+    let vcs = crate::vcs::detect_vcs()?;
+    let (path, _branch) = vcs.find_workspace(name)?;
+
+    if retry {
+        return pending::retry_until_delivered(mux.as_ref(), &path, &cfg);
     }
 
+    let flushed = pending::flush(mux.as_ref(), &path, &cfg, true)?;
+    if flushed.delivered == 0 && flushed.waiting.is_empty() {
+        println!("No queued prompts for '{}'", name);
+        return Ok(());
+    }
+    if flushed.delivered > 0 {
+        println!(
+            "✓ Delivered {} queued prompt(s) to '{}'",
+            flushed.delivered, name
+        );
+    }
+    for waiting in &flushed.waiting {
+        println!(
+            "! Prompt for pane {} is still queued: {}",
+            waiting.pane_key.pane_id, waiting.last_error
+        );
+    }
+    if !flushed.waiting.is_empty() {
+        return Err(anyhow!(
+            "{} prompt(s) for '{}' could not be delivered",
+            flushed.waiting.len(),
+            name
+        ));
+    }
     Ok(())
 }
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::ValueEnum;
 use tracing::warn;
 
@@ -15,11 +15,27 @@ pub enum SetWindowStatusCommand {
     Done,
     /// Clear the status
     Clear,
+    /// Record that the agent is alive without changing its status
+    Heartbeat,
 }
 
-pub fn run(cmd: SetWindowStatusCommand) -> Result<()> {
+/// Set or clear the status of the agent in the current pane, or with `pane`
+/// (focus hooks, which run outside the pane) clear the status of that pane
+/// if the user hasn't seen it yet.
+pub fn run(
+    cmd: SetWindowStatusCommand,
+    server_url: Option<String>,
+    pane: Option<String>,
+) -> Result<()> {
+    if pane.is_some() && !matches!(cmd, SetWindowStatusCommand::Clear) {
+        bail!("--pane only works with 'clear'");
+    }
+
     // Inside a sandbox guest, route through RPC to the host supervisor
     if crate::sandbox::guest::is_sandbox_guest() {
+        if pane.is_some() {
+            bail!("--pane is not supported from inside a sandbox");
+        }
         return run_via_rpc(cmd);
     }
 
@@ -27,14 +43,23 @@ pub fn run(cmd: SetWindowStatusCommand) -> Result<()> {
     let mux = create_backend(detect_backend());
 
     // Fail silently if not in a multiplexer session
-    let Some(pane_id) = mux.current_pane_id() else {
+    let Some(pane_id) = pane.clone().or_else(|| mux.current_pane_id()) else {
         return Ok(());
     };
 
     match cmd {
         SetWindowStatusCommand::Clear => {
-            // Clear icon only - state file cleanup is handled by reconciliation
-            mux.clear_status(&pane_id)?;
+            let from_focus = pane.is_some();
+            let cleared = crate::state::clear_agent_status(&*mux, &pane_id, from_focus);
+            if cleared || !from_focus {
+                mux.clear_status(&pane_id)?;
+            }
+            if cleared {
+                crate::state::refresh_session_status(&*mux, &pane_id, &config);
+            }
+        }
+        SetWindowStatusCommand::Heartbeat => {
+            crate::state::record_heartbeat(&*mux, &pane_id);
         }
         SetWindowStatusCommand::Working
         | SetWindowStatusCommand::Waiting
@@ -49,7 +74,9 @@ pub fn run(cmd: SetWindowStatusCommand) -> Result<()> {
                 SetWindowStatusCommand::Done => {
                     (AgentStatus::Done, config.status_icons.done(), true)
                 }
-                SetWindowStatusCommand::Clear => unreachable!(),
+                SetWindowStatusCommand::Clear | SetWindowStatusCommand::Heartbeat => {
+                    unreachable!()
+                }
             };
 
             // Ensure the status format is applied so the icon actually shows up
@@ -61,7 +88,19 @@ pub fn run(cmd: SetWindowStatusCommand) -> Result<()> {
             mux.set_status(&pane_id, icon, auto_clear)?;
 
             // Persist to state store so the dashboard sees this agent
-            crate::state::persist_agent_update(&*mux, &pane_id, Some(status), None);
+            let status_changed =
+                crate::state::persist_agent_update(&*mux, &pane_id, Some(status), None);
+
+            // Remember where the agent's server lives so the dashboard can poll it
+            if let Some(url) = server_url {
+                crate::state::set_agent_server_url(&*mux, &pane_id, url);
+            }
+
+            // Roll the new status up to the session in session mode
+            crate::state::refresh_session_status(&*mux, &pane_id, &config);
+
+            // Pause or resume agents by priority
+            crate::state::preempt::apply(&*mux, &pane_id, status_changed, &config);
         }
     }
 
@@ -73,21 +112,26 @@ fn run_via_rpc(cmd: SetWindowStatusCommand) -> Result<()> {
     use crate::sandbox::rpc::{RpcClient, RpcRequest, RpcResponse};
 
     let status = match cmd {
-        SetWindowStatusCommand::Working => "working",
-        SetWindowStatusCommand::Waiting => "waiting",
-        SetWindowStatusCommand::Done => "done",
-        SetWindowStatusCommand::Clear => "clear",
+        SetWindowStatusCommand::Working => Some("working"),
+        SetWindowStatusCommand::Waiting => Some("waiting"),
+        SetWindowStatusCommand::Done => Some("done"),
+        SetWindowStatusCommand::Clear => Some("clear"),
+        SetWindowStatusCommand::Heartbeat => None,
     };
 
     let mut client = RpcClient::from_env()?;
-    let response = client.call(&RpcRequest::SetStatus {
-        status: status.to_string(),
-    })?;
+    let request = match status {
+        Some(status) => RpcRequest::SetStatus {
+            status: status.to_string(),
+        },
+        None => RpcRequest::Heartbeat,
+    };
+    let response = client.call(&request)?;
 
     match response {
         RpcResponse::Ok => Ok(()),
         RpcResponse::Error { message } => {
-            warn!(error = %message, "RPC SetStatus failed");
+            warn!(error = %message, "RPC status update failed");
             Ok(()) // Fail silently like the host path does
         }
         _ => Ok(()),
@@ -1,15 +1,36 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use console::style;
 use std::io::{self, IsTerminal, Write};
 
 use crate::agent_setup::{self, StatusCheck};
+use crate::config::{Config, SandboxBackend};
+
+pub fn run(sandbox: bool, project: bool) -> Result<()> {
+    if sandbox || Config::load(None).is_ok_and(|c| c.sandbox.is_enabled() && c.sandbox.prefetch()) {
+        preheat_sandbox();
+        if !io::stdin().is_terminal() {
+            return Ok(());
+        }
+    }
 
-pub fn run() -> Result<()> {
     if !io::stdin().is_terminal() {
         anyhow::bail!("workmux setup requires an interactive terminal");
     }
 
-    let checks = agent_setup::check_all();
+    // With --project, hooks go into the repository's own agent settings
+    let project_root = if project {
+        let root = crate::vcs::try_detect_vcs()
+            .and_then(|v| v.get_repo_root().ok())
+            .context("workmux setup --project must be run inside a repository")?;
+        Some(root)
+    } else {
+        None
+    };
+
+    let checks = match &project_root {
+        Some(root) => agent_setup::check_project(root),
+        None => agent_setup::check_all(),
+    };
 
     if checks.is_empty() {
         println!(
@@ -63,7 +84,11 @@ pub fn run() -> Result<()> {
     if confirm_install()? {
         let mut any_failed = false;
         for check in &needs_setup {
-            match agent_setup::install(check.agent) {
+            let result = match &project_root {
+                Some(root) => agent_setup::install_project(check.agent, root),
+                None => agent_setup::install(check.agent),
+            };
+            match result {
                 Ok(msg) => println!("  {} {}", style("✓").green(), msg),
                 Err(e) => {
                     println!("  {} {}: {}", style("✗").red(), check.agent.name(), e);
@@ -82,6 +107,33 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
+/// Start pulling the container sandbox images in the background, so the
+/// first sandboxed agent doesn't wait for them.
+fn preheat_sandbox() {
+    let config = Config::load(None).unwrap_or_default();
+    if config.sandbox.backend() == SandboxBackend::Lima {
+        println!(
+            "{}",
+            style("Lima downloads its image when the first VM is created; nothing to preheat.")
+                .dim()
+        );
+        return;
+    }
+    let agents = crate::sandbox::preheat::agents_to_preheat(&config, &[]);
+    match crate::sandbox::preheat::spawn_in_background(&agents) {
+        Ok(log) => println!(
+            "  {} Preheating sandbox images in the background ({})",
+            style("•").dim(),
+            style(log.display()).dim()
+        ),
+        Err(e) => println!(
+            "  {} Could not preheat sandbox images: {}",
+            style("✗").red(),
+            e
+        ),
+    }
+}
+
 fn confirm_install() -> Result<bool> {
     let prompt = format!(
         "  Install status tracking hooks? {}{}{} ",
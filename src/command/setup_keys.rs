@@ -0,0 +1,312 @@
+//! `workmux setup --keys`: key bindings to open the dashboard, add a
+//! worktree and jump to waiting agents.
+//!
+//! The bindings go into the multiplexer's config file between marker
+//! comments, so installing again replaces them in place and `--remove` takes
+//! them out without touching the rest of the file.
+
+use anyhow::{Context, Result, bail};
+use console::style;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::multiplexer::{BackendType, detect_backend};
+
+const BEGIN: &str = ">>> workmux keys >>>";
+const END: &str = "<<< workmux keys <<<";
+
+/// Asks for a branch name, then opens the prompt editor for `workmux add`.
+/// Kitty expands `$` in launch arguments, so no shell variables.
+const QUICK_ADD: &str = "printf 'Branch: ' && head -n 1 | xargs -o -I {} workmux add {} -e";
+
+pub fn run(print: bool, remove: bool) -> Result<()> {
+    let backend = detect_backend();
+    let path = config_path(backend)?;
+
+    if print {
+        println!("{}", block(backend, "config"));
+        println!();
+        println!("Add these lines to {}", path.display());
+        print_keys(backend);
+        return Ok(());
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    if remove {
+        match remove_block(&content, comment(backend)) {
+            Some(updated) => {
+                write(&path, &updated)?;
+                println!(
+                    "  {} Removed the workmux key bindings from {}",
+                    style("✓").green(),
+                    path.display()
+                );
+            }
+            None => println!("No workmux key bindings in {}", path.display()),
+        }
+        return Ok(());
+    }
+
+    if backend == BackendType::WezTerm && content.is_empty() {
+        bail!(
+            "No WezTerm config at {}. Run 'workmux setup --keys --print' and add the bindings to \
+             your config",
+            path.display()
+        );
+    }
+    let updated = install_block(&content, backend).with_context(|| {
+        format!(
+            "Can't install into {}; run 'workmux setup --keys --print' to add the bindings by hand",
+            path.display()
+        )
+    })?;
+    if updated == content {
+        println!(
+            "The workmux key bindings in {} are up to date",
+            path.display()
+        );
+    } else {
+        write(&path, &updated)?;
+        println!(
+            "  {} Installed the workmux key bindings into {}",
+            style("✓").green(),
+            path.display()
+        );
+    }
+    print_keys(backend);
+    match backend {
+        BackendType::Tmux => println!(
+            "Reload the config with: tmux source-file {}",
+            path.display()
+        ),
+        BackendType::Kitty => println!("Reload kitty's config (ctrl+shift+f5) to use them"),
+        BackendType::WezTerm => println!("WezTerm picks them up automatically"),
+    }
+    Ok(())
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn print_keys(backend: BackendType) {
+    let (dashboard, add, waiting) = match backend {
+        BackendType::Tmux => ("prefix C-s", "prefix A", "prefix W"),
+        BackendType::WezTerm | BackendType::Kitty => ("ctrl+alt+d", "ctrl+alt+a", "ctrl+alt+w"),
+    };
+    println!();
+    for (key, action) in [
+        (dashboard, "open the dashboard"),
+        (add, "add a worktree, asking for the branch and prompt"),
+        (
+            waiting,
+            "jump to the agent waiting longest (again for the next)",
+        ),
+    ] {
+        println!("  {:<12} {}", style(key).bold(), action);
+    }
+    println!();
+}
+
+/// The multiplexer's config file, whether it exists or not.
+fn config_path(backend: BackendType) -> Result<PathBuf> {
+    let home = home::home_dir().context("Could not determine home directory")?;
+    let xdg = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+    Ok(match backend {
+        BackendType::Tmux => {
+            let dotfile = home.join(".tmux.conf");
+            let xdg_file = xdg.join("tmux/tmux.conf");
+            if !dotfile.exists() && xdg_file.exists() {
+                xdg_file
+            } else {
+                dotfile
+            }
+        }
+        BackendType::Kitty => std::env::var_os("KITTY_CONFIG_DIRECTORY")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| xdg.join("kitty"))
+            .join("kitty.conf"),
+        BackendType::WezTerm => match std::env::var_os("WEZTERM_CONFIG_FILE") {
+            Some(file) => PathBuf::from(file),
+            None if home.join(".wezterm.lua").exists() => home.join(".wezterm.lua"),
+            None => xdg.join("wezterm/wezterm.lua"),
+        },
+    })
+}
+
+fn comment(backend: BackendType) -> &'static str {
+    match backend {
+        BackendType::WezTerm => "--",
+        BackendType::Tmux | BackendType::Kitty => "#",
+    }
+}
+
+/// The bindings between marker comments. `config` is the variable WezTerm's
+/// config is built in.
+fn block(backend: BackendType, config: &str) -> String {
+    let bindings = match backend {
+        BackendType::Tmux => [
+            r#"bind C-s display-popup -h 30 -w 100 -E "workmux dashboard""#.to_string(),
+            r#"bind A command-prompt -p "Branch:" "display-popup -w 80% -h 60% -d '#{pane_current_path}' -E 'workmux add %% -e'""#.to_string(),
+            r#"bind W run-shell "workmux last-waiting""#.to_string(),
+        ]
+        .join("\n"),
+        BackendType::Kitty => [
+            "map ctrl+alt+d launch --type=overlay --cwd=current workmux dashboard".to_string(),
+            format!(
+                "map ctrl+alt+a launch --type=overlay --cwd=current sh -c \"{}\"",
+                QUICK_ADD
+            ),
+            "map ctrl+alt+w launch --type=background --env WORKMUX_BACKEND=kitty workmux last-waiting"
+                .to_string(),
+        ]
+        .join("\n"),
+        BackendType::WezTerm => format!(
+            r#"do
+    local wezterm = require('wezterm')
+    local act = wezterm.action
+    {config}.keys = {config}.keys or {{}}
+    for _, binding in ipairs({{
+        {{ key = 'd', mods = 'CTRL|ALT', action = act.SpawnCommandInNewTab({{ args = {{ 'workmux', 'dashboard' }} }}) }},
+        {{ key = 'a', mods = 'CTRL|ALT', action = act.SplitPane({{ direction = 'Down', command = {{ args = {{ 'sh', '-c', "{QUICK_ADD}" }} }} }}) }},
+        {{ key = 'w', mods = 'CTRL|ALT', action = wezterm.action_callback(function()
+            wezterm.background_child_process({{ 'env', 'WORKMUX_BACKEND=wezterm', 'workmux', 'last-waiting' }})
+        end) }},
+    }}) do
+        table.insert({config}.keys, binding)
+    end
+end"#
+        ),
+    };
+    let comment = comment(backend);
+    format!("{comment} {BEGIN}\n{bindings}\n{comment} {END}")
+}
+
+/// Line range of the marked block in `lines`, if there is one.
+fn find_block(lines: &[&str], comment: &str) -> Option<(usize, usize)> {
+    let begin = format!("{} {}", comment, BEGIN);
+    let end = format!("{} {}", comment, END);
+    let start = lines.iter().position(|l| l.trim() == begin)?;
+    let len = lines[start..].iter().position(|l| l.trim() == end)?;
+    Some((start, start + len))
+}
+
+/// `content` with the bindings installed: replacing the marked block if
+/// there is one, otherwise appended, or for WezTerm inserted before the
+/// config is returned.
+fn install_block(content: &str, backend: BackendType) -> Result<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let comment = comment(backend);
+    let config = match backend {
+        BackendType::WezTerm => returned_config(&lines)
+            .context("Can't find the 'return config' line of the WezTerm config")?,
+        BackendType::Tmux | BackendType::Kitty => "config".to_string(),
+    };
+    let block = block(backend, &config);
+
+    if let Some((start, end)) = find_block(&lines, comment) {
+        lines.splice(start..=end, block.lines());
+    } else if backend == BackendType::WezTerm {
+        let at = lines
+            .iter()
+            .rposition(|l| l.trim_start().starts_with("return "))
+            .unwrap_or(lines.len());
+        lines.splice(at..at, block.lines().chain([""]));
+    } else {
+        if !lines.is_empty() {
+            lines.push("");
+        }
+        lines.extend(block.lines());
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Name of the variable the WezTerm config returns, e.g. `config`.
+fn returned_config(lines: &[&str]) -> Option<String> {
+    let name = lines
+        .iter()
+        .rev()
+        .find_map(|l| l.trim().strip_prefix("return "))?
+        .trim()
+        .trim_end_matches(';');
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| name.to_string())
+}
+
+/// `content` without the marked block and the blank line installing added,
+/// or `None` when there is no block.
+fn remove_block(content: &str, comment: &str) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    let (start, end) = find_block(&lines, comment)?;
+    lines.drain(start..=end);
+    if lines.get(start).is_some_and(|l| l.trim().is_empty()) {
+        lines.remove(start);
+    } else if start == lines.len() && start > 0 && lines[start - 1].trim().is_empty() {
+        lines.remove(start - 1);
+    }
+    if lines.is_empty() {
+        return Some(String::new());
+    }
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tmux_bindings_are_appended_once_and_removed() {
+        let original = "set -g mouse on\n";
+        let installed = install_block(original, BackendType::Tmux).unwrap();
+        assert!(installed.starts_with("set -g mouse on\n\n# >>> workmux keys >>>\n"));
+        assert!(installed.contains("bind W run-shell \"workmux last-waiting\"\n"));
+        assert!(installed.ends_with("# <<< workmux keys <<<\n"));
+
+        // Installing again changes nothing, and an outdated block is replaced
+        assert_eq!(
+            install_block(&installed, BackendType::Tmux).unwrap(),
+            installed
+        );
+        let outdated = installed.replace("last-waiting", "last-done");
+        assert_eq!(
+            install_block(&outdated, BackendType::Tmux).unwrap(),
+            installed
+        );
+
+        assert_eq!(remove_block(&installed, "#").as_deref(), Some(original));
+        assert_eq!(remove_block(original, "#"), None);
+
+        let fresh = install_block("", BackendType::Kitty).unwrap();
+        assert!(fresh.starts_with("# >>> workmux keys >>>\nmap ctrl+alt+d"));
+        assert_eq!(remove_block(&fresh, "#").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn wezterm_bindings_go_before_the_config_is_returned() {
+        let original =
+            "local wezterm = require 'wezterm'\nlocal c = wezterm.config_builder()\n\nreturn c\n";
+        let installed = install_block(original, BackendType::WezTerm).unwrap();
+        assert!(installed.contains("\n    c.keys = c.keys or {}\n"));
+        assert!(installed.ends_with("end\n-- <<< workmux keys <<<\n\nreturn c\n"));
+        assert_eq!(
+            install_block(&installed, BackendType::WezTerm).unwrap(),
+            installed
+        );
+        assert_eq!(remove_block(&installed, "--").as_deref(), Some(original));
+
+        let err = install_block("return {}\n", BackendType::WezTerm).unwrap_err();
+        assert!(err.to_string().contains("return config"));
+    }
+}
@@ -7,6 +7,7 @@ use tabled::{
     settings::{Padding, Style, object::Columns},
 };
 
+use crate::config;
 use crate::multiplexer::{AgentStatus, create_backend, detect_backend};
 use crate::vcs;
 use crate::state::StateStore;
@@ -72,6 +73,7 @@ fn status_label(status: Option<AgentStatus>) -> String {
         Some(AgentStatus::Working) => "working".to_string(),
         Some(AgentStatus::Waiting) => "waiting".to_string(),
         Some(AgentStatus::Done) => "done".to_string(),
+        Some(AgentStatus::Unknown) => "unknown".to_string(),
         None => "-".to_string(),
     }
 }
@@ -102,14 +104,20 @@ pub fn run(worktrees: &[String], json: bool, show_git: bool) -> Result<()> {
     } else {
         None
     };
-    let unmerged_branches = if show_git {
-        main_branch
-            .as_deref()
-            .and_then(|main| vcs.get_merge_base(main).ok())
-            .and_then(|base| vcs.get_unmerged_branches(&base).ok())
-            .unwrap_or_default()
-    } else {
-        std::collections::HashSet::new()
+    let unmerged_branches = match main_branch.as_deref() {
+        Some(main) => {
+            let config = config::Config::load(None)?;
+            workflow::unmerged_branches(
+                vcs.as_ref(),
+                &config,
+                all_worktrees
+                    .iter()
+                    .map(|(_, branch)| branch.as_str())
+                    .filter(|branch| *branch != main),
+                main,
+            )
+        }
+        None => std::collections::HashSet::new(),
     };
 
     let now = SystemTime::now()
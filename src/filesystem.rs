@@ -0,0 +1,117 @@
+//! Filesystem type detection for worktree roots.
+//!
+//! Worktrees on network mounts (NFS, SMB) are slow to stat and can't be
+//! mounted into a Lima VM with aggressive caching without serving stale
+//! files. Filesystems like FAT lack symlinks and executable bits, which
+//! breaks git hooks and symlinked files. Callers use [`detect`] to adjust
+//! their behavior or warn.
+
+use std::path::Path;
+
+/// What kind of filesystem a path lives on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsKind {
+    Local,
+    /// A network filesystem, with its name (e.g. "nfs", "smbfs")
+    Network(String),
+    /// A local filesystem that lacks features worktrees rely on
+    Unsupported(String),
+}
+
+impl FsKind {
+    pub fn is_network(&self) -> bool {
+        matches!(self, FsKind::Network(_))
+    }
+}
+
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "nfs", "nfs4", "smb", "smbfs", "smb2", "cifs", "afpfs", "webdav", "afs", "9p",
+];
+
+const UNSUPPORTED_FILESYSTEMS: &[&str] = &["msdos", "vfat", "exfat"];
+
+/// Classify a filesystem by its name.
+fn classify_name(name: &str) -> FsKind {
+    let name = name.to_ascii_lowercase();
+    if NETWORK_FILESYSTEMS.contains(&name.as_str()) {
+        FsKind::Network(name)
+    } else if UNSUPPORTED_FILESYSTEMS.contains(&name.as_str()) {
+        FsKind::Unsupported(name)
+    } else {
+        FsKind::Local
+    }
+}
+
+/// Name of a Linux filesystem from its `statfs` magic number, for the
+/// filesystems that aren't plain local ones.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn magic_name(magic: i64) -> Option<&'static str> {
+    match magic {
+        0x6969 => Some("nfs"),
+        0x517B => Some("smb"),
+        0xFF53_4D42 => Some("cifs"),
+        0xFE53_4D42 => Some("smb2"),
+        0x5346_414F => Some("afs"),
+        0x0102_1997 => Some("9p"),
+        0x4D44 => Some("msdos"),
+        0x2011_BAB0 => Some("exfat"),
+        _ => None,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn statfs_kind(stat: &nix::sys::statfs::Statfs) -> FsKind {
+    #[allow(clippy::unnecessary_cast)]
+    let magic = stat.filesystem_type().0 as i64;
+    magic_name(magic)
+        .map(classify_name)
+        .unwrap_or(FsKind::Local)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn statfs_kind(stat: &nix::sys::statfs::Statfs) -> FsKind {
+    classify_name(stat.filesystem_type_name())
+}
+
+/// Detect the filesystem `path` lives on. Paths that don't exist yet are
+/// checked at their nearest existing ancestor. Returns `None` if nothing
+/// could be checked.
+pub fn detect(path: &Path) -> Option<FsKind> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let stat = nix::sys::statfs::statfs(existing).ok()?;
+    Some(statfs_kind(&stat))
+}
+
+/// Whether `path` is on a network filesystem. `false` if unknown.
+pub fn is_network(path: &Path) -> bool {
+    detect(path).is_some_and(|kind| kind.is_network())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_filesystem_names() {
+        assert_eq!(classify_name("apfs"), FsKind::Local);
+        assert_eq!(classify_name("smbfs"), FsKind::Network("smbfs".into()));
+        assert_eq!(classify_name("NFS"), FsKind::Network("nfs".into()));
+        assert_eq!(classify_name("exfat"), FsKind::Unsupported("exfat".into()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn classifies_linux_magic_numbers() {
+        assert_eq!(magic_name(0x6969), Some("nfs"));
+        assert_eq!(magic_name(0xFF53_4D42), Some("cifs"));
+        // ext4
+        assert_eq!(magic_name(0xEF53), None);
+    }
+
+    #[test]
+    fn detects_missing_paths_at_existing_ancestor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = tmp.path().join("not/created/yet");
+        assert!(detect(&missing).is_some());
+    }
+}
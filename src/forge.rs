@@ -0,0 +1,816 @@
+//! Code hosts ("forges") serving the repository: GitHub, GitLab and Gitea.
+//!
+//! Fork remotes and pull requests follow each host's conventions. GitLab
+//! namespaces nest (`group/subgroup/repo`), so the owner of a repository is
+//! everything before its name, and pull requests are merge requests handled
+//! with the `glab` CLI. Gitea (and Forgejo) is driven through its REST API
+//! with curl, authenticated by `GITEA_TOKEN` when it is set. GitHub keeps
+//! using `gh` through [`crate::github`].
+//!
+//! The provider is the `provider` config option, or is guessed from the
+//! origin host. PR states are normalized to GitHub's (`OPEN`, `MERGED`,
+//! `CLOSED`) so callers can treat every host alike.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::cmd::Cmd;
+use crate::config::{Config, ForgeProvider};
+use crate::github::{self, Author, PrDetails, PrSummary, RepositoryOwner};
+
+/// Environment variable holding the token for Gitea API calls.
+pub const GITEA_TOKEN_ENV: &str = "GITEA_TOKEN";
+
+/// A git remote URL, split into the parts fork URLs are built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl<'a> {
+    /// `https`, `http`, `ssh` or `git`; `ssh` for scp-like URLs
+    pub scheme: &'a str,
+    pub user: Option<&'a str>,
+    pub host: &'a str,
+    pub port: Option<&'a str>,
+    /// Repository path without surrounding slashes or the `.git` suffix
+    pub path: &'a str,
+    /// Written as `[user@]host:path` rather than with a scheme
+    scp_like: bool,
+}
+
+impl<'a> RemoteUrl<'a> {
+    /// Parse `scheme://[user@]host[:port]/path` or scp-like
+    /// `[user@]host:path`. Local paths and `file://` URLs give None.
+    pub fn parse(url: &'a str) -> Option<Self> {
+        let url = url.trim();
+        let (scheme, authority, path, scp_like) = match url.split_once("://") {
+            Some((scheme, rest)) => {
+                if !matches!(scheme, "https" | "http" | "ssh" | "git") {
+                    return None;
+                }
+                let (authority, path) = rest.split_once('/')?;
+                (scheme, authority, path, false)
+            }
+            None => {
+                let (authority, path) = url.split_once(':')?;
+                if authority.contains('/') {
+                    return None;
+                }
+                ("ssh", authority, path, true)
+            }
+        };
+
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host)) => (Some(user), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host_port.split_once(':') {
+            Some((host, port)) if !scp_like => (host, Some(port)),
+            _ => (host_port, None),
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if host.is_empty() || path.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            scheme,
+            user,
+            host,
+            port,
+            path,
+            scp_like,
+        })
+    }
+
+    /// Owner of the repository: the GitLab namespace (which may nest), or
+    /// the first path segment elsewhere.
+    pub fn owner(&self, provider: ForgeProvider) -> Option<&'a str> {
+        let owner = match provider {
+            ForgeProvider::Gitlab => self.path.rsplit_once('/')?.0,
+            ForgeProvider::Github | ForgeProvider::Gitea => self.path.split_once('/')?.0,
+        };
+        Some(owner).filter(|owner| !owner.is_empty())
+    }
+
+    /// Name of the repository.
+    pub fn repo(&self, provider: ForgeProvider) -> Option<&'a str> {
+        let repo = match provider {
+            ForgeProvider::Gitlab => self.path.rsplit_once('/')?.1,
+            ForgeProvider::Github | ForgeProvider::Gitea => self.path.split('/').nth(1)?,
+        };
+        Some(repo).filter(|repo| !repo.is_empty())
+    }
+
+    /// URL of the same repository under another owner, keeping the scheme,
+    /// host and port. Users are kept for SSH only, so credentials embedded
+    /// in an HTTPS origin aren't copied to another owner's remote.
+    pub fn with_owner(&self, provider: ForgeProvider, owner: &str) -> Option<String> {
+        let repo = self.repo(provider)?;
+        if self.scp_like {
+            let user = self.user.map(|u| format!("{}@", u)).unwrap_or_default();
+            return Some(format!("{}{}:{}/{}.git", user, self.host, owner, repo));
+        }
+        let user = match (self.scheme, self.user) {
+            ("ssh", Some(user)) => format!("{}@", user),
+            _ => String::new(),
+        };
+        let port = self.port.map(|p| format!(":{}", p)).unwrap_or_default();
+        Some(format!(
+            "{}://{}{}{}/{}/{}.git",
+            self.scheme, user, self.host, port, owner, repo
+        ))
+    }
+
+    /// Base URL of the host's web UI and API: HTTPS on the same host for
+    /// SSH remotes.
+    fn web_base(&self) -> String {
+        match (self.scheme, self.port) {
+            ("https" | "http", Some(port)) => format!("{}://{}:{}", self.scheme, self.host, port),
+            ("https" | "http", None) => format!("{}://{}", self.scheme, self.host),
+            _ => format!("https://{}", self.host),
+        }
+    }
+}
+
+/// Guess the provider from a remote's host name.
+pub fn detect_provider(host: &str) -> ForgeProvider {
+    let host = host.to_ascii_lowercase();
+    if host.contains("gitlab") {
+        ForgeProvider::Gitlab
+    } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+        ForgeProvider::Gitea
+    } else {
+        ForgeProvider::Github
+    }
+}
+
+/// Provider of the repository in `dir`: the configured one, else guessed
+/// from the host of its origin URL.
+pub fn resolve_provider(dir: &Path, origin_url: Option<&str>) -> ForgeProvider {
+    Config::provider_for(dir)
+        .or_else(|| {
+            origin_url
+                .and_then(RemoteUrl::parse)
+                .map(|url| detect_provider(url.host))
+        })
+        .unwrap_or_default()
+}
+
+/// URL for `fork_owner`'s copy of the repository at `origin_url`.
+pub fn fork_url(provider: ForgeProvider, origin_url: &str, fork_owner: &str) -> Result<String> {
+    RemoteUrl::parse(origin_url)
+        .and_then(|url| url.with_owner(provider, fork_owner))
+        .ok_or_else(|| {
+            anyhow!(
+                "Failed to parse origin URL for fork remote construction: {}",
+                origin_url
+            )
+        })
+}
+
+/// A pull (or merge) request to open.
+pub struct NewPullRequest<'a> {
+    /// Branch with the changes
+    pub head: &'a str,
+    /// Owner of the fork the branch was pushed to, None for origin
+    pub head_owner: Option<&'a str>,
+    /// Branch to merge into
+    pub base: &'a str,
+    pub title: &'a str,
+    pub body: &'a str,
+    pub draft: bool,
+}
+
+/// The code host of one repository.
+pub struct Forge {
+    pub provider: ForgeProvider,
+    origin_url: Option<String>,
+    /// Repository to run CLIs in, None for the current directory
+    dir: Option<PathBuf>,
+}
+
+impl Forge {
+    /// Forge of the repository in the current directory.
+    pub fn current() -> Self {
+        let origin_url = crate::git::get_remote_url("origin").ok();
+        let cwd = std::env::current_dir().unwrap_or_default();
+        Self {
+            provider: resolve_provider(&cwd, origin_url.as_deref()),
+            origin_url,
+            dir: None,
+        }
+    }
+
+    /// Forge of the repository at `repo_root`.
+    pub fn for_repo(repo_root: &Path) -> Self {
+        let origin_url = Cmd::new("git")
+            .workdir(repo_root)
+            .args(&["config", "--get", "remote.origin.url"])
+            .run_and_capture_stdout()
+            .ok();
+        Self {
+            provider: resolve_provider(repo_root, origin_url.as_deref()),
+            origin_url,
+            dir: Some(repo_root.to_path_buf()),
+        }
+    }
+
+    fn origin(&self) -> Result<RemoteUrl<'_>> {
+        let url = self
+            .origin_url
+            .as_deref()
+            .ok_or_else(|| anyhow!("The repository has no origin remote"))?;
+        RemoteUrl::parse(url).ok_or_else(|| anyhow!("Could not parse origin URL: {}", url))
+    }
+
+    /// Owner and name of the origin repository.
+    fn origin_repo(&self) -> Result<(&str, &str)> {
+        let origin = self.origin()?;
+        let url = self.origin_url.as_deref().unwrap_or_default();
+        match (origin.owner(self.provider), origin.repo(self.provider)) {
+            (Some(owner), Some(repo)) => Ok((owner, repo)),
+            _ => Err(anyhow!(
+                "Could not parse repository owner from origin URL: {}",
+                url
+            )),
+        }
+    }
+
+    fn cmd<'a>(&'a self, program: &'a str) -> Cmd<'a> {
+        match &self.dir {
+            Some(dir) => Cmd::new(program).workdir(dir),
+            None => Cmd::new(program),
+        }
+    }
+
+    /// Details of pull request `number`.
+    pub fn get_pr_details(&self, number: u32) -> Result<PrDetails> {
+        match self.provider {
+            ForgeProvider::Github => github::get_pr_details(number),
+            ForgeProvider::Gitlab => self.gitlab_mr_details(number),
+            ForgeProvider::Gitea => {
+                let (owner, repo) = self.origin_repo()?;
+                let pr: GiteaPull =
+                    self.gitea_get(&format!("repos/{}/{}/pulls/{}", owner, repo, number))?;
+                Ok(pr.into_details())
+            }
+        }
+    }
+
+    /// The pull request for `owner`'s branch `branch`, if there is one.
+    /// Lookup failures count as no pull request.
+    pub fn find_pr_by_head_ref(&self, owner: &str, branch: &str) -> Result<Option<PrSummary>> {
+        match self.provider {
+            ForgeProvider::Github => github::find_pr_by_head_ref(owner, branch),
+            ForgeProvider::Gitlab => {
+                let Ok(mrs) = self.gitlab_list(&["--source-branch", branch], "100") else {
+                    debug!(
+                        owner,
+                        branch, "forge:glab mr list failed, treating as no MR found"
+                    );
+                    return Ok(None);
+                };
+                Ok(mrs
+                    .into_iter()
+                    .filter(|mr| mr.source_branch == branch)
+                    .find(|mr| {
+                        self.gitlab_source_owner(mr)
+                            .is_ok_and(|source| source.eq_ignore_ascii_case(owner))
+                    })
+                    .map(GitlabMr::into_summary))
+            }
+            ForgeProvider::Gitea => {
+                let Ok(pulls) = self.gitea_pulls() else {
+                    debug!(
+                        owner,
+                        branch, "forge:gitea pull list failed, treating as no PR found"
+                    );
+                    return Ok(None);
+                };
+                Ok(pulls
+                    .into_iter()
+                    .find(|pr| {
+                        pr.head.branch == branch
+                            && pr
+                                .head_owner()
+                                .is_some_and(|login| login.eq_ignore_ascii_case(owner))
+                    })
+                    .map(GiteaPull::into_summary))
+            }
+        }
+    }
+
+    /// Pull requests of the repository by head branch. Returns an empty map
+    /// when they can't be fetched.
+    pub fn list_prs(&self) -> Result<HashMap<String, PrSummary>> {
+        match self.provider {
+            ForgeProvider::Github => match &self.dir {
+                Some(dir) => github::list_prs_in_repo(dir),
+                None => github::list_prs(),
+            },
+            ForgeProvider::Gitlab => {
+                let mrs = self
+                    .gitlab_list(&[], "100")
+                    .inspect_err(|e| debug!(error = %e, "forge:glab mr list failed"))
+                    .unwrap_or_default();
+                Ok(mrs
+                    .into_iter()
+                    .map(|mr| (mr.source_branch.clone(), mr.into_summary()))
+                    .collect())
+            }
+            ForgeProvider::Gitea => {
+                let pulls = self
+                    .gitea_pulls()
+                    .inspect_err(|e| debug!(error = %e, "forge:gitea pull list failed"))
+                    .unwrap_or_default();
+                Ok(pulls
+                    .into_iter()
+                    .map(|pr| (pr.head.branch.clone(), pr.into_summary()))
+                    .collect())
+            }
+        }
+    }
+
+    /// Open a pull request. Returns its URL.
+    pub fn create_pull_request(&self, request: &NewPullRequest) -> Result<String> {
+        match self.provider {
+            ForgeProvider::Github => {
+                let head = match request.head_owner {
+                    Some(owner) => format!("{}:{}", owner, request.head),
+                    None => request.head.to_string(),
+                };
+                let mut args = vec![
+                    "pr",
+                    "create",
+                    "--head",
+                    &head,
+                    "--base",
+                    request.base,
+                    "--title",
+                    request.title,
+                    "--body-file",
+                    "-",
+                ];
+                if request.draft {
+                    args.push("--draft");
+                }
+                let output = self
+                    .cmd("gh")
+                    .args(&args)
+                    .stdin(request.body)
+                    .run_and_capture_stdout()
+                    .context("Failed to create pull request with gh")?;
+                last_url(&output)
+            }
+            ForgeProvider::Gitlab => {
+                let head_repo = match request.head_owner {
+                    Some(owner) => {
+                        let (_, repo) = self.origin_repo()?;
+                        Some(format!("{}/{}", owner, repo))
+                    }
+                    None => None,
+                };
+                let mut args = vec![
+                    "mr",
+                    "create",
+                    "--source-branch",
+                    request.head,
+                    "--target-branch",
+                    request.base,
+                    "--title",
+                    request.title,
+                    "--description",
+                    request.body,
+                    "--yes",
+                ];
+                if let Some(head_repo) = &head_repo {
+                    args.extend(["--head", head_repo]);
+                }
+                if request.draft {
+                    args.push("--draft");
+                }
+                let output = self
+                    .cmd("glab")
+                    .args(&args)
+                    .run_and_capture_stdout()
+                    .context("Failed to create merge request with glab")?;
+                last_url(&output)
+            }
+            ForgeProvider::Gitea => {
+                let (owner, repo) = self.origin_repo()?;
+                let head = match request.head_owner {
+                    Some(owner) => format!("{}:{}", owner, request.head),
+                    None => request.head.to_string(),
+                };
+                // Gitea marks drafts by a title prefix
+                let title = if request.draft {
+                    format!("WIP: {}", request.title)
+                } else {
+                    request.title.to_string()
+                };
+                let body = serde_json::json!({
+                    "head": head,
+                    "base": request.base,
+                    "title": title,
+                    "body": request.body,
+                });
+                let created: GiteaPull = self
+                    .gitea_api(
+                        "POST",
+                        &format!("repos/{}/{}/pulls", owner, repo),
+                        Some(&body.to_string()),
+                    )
+                    .and_then(|bytes| {
+                        serde_json::from_slice(&bytes).context("Failed to parse Gitea response")
+                    })
+                    .context("Failed to create pull request through the Gitea API")?;
+                Ok(created.html_url)
+            }
+        }
+    }
+
+    // ── GitLab ───────────────────────────────────────────────────────
+
+    fn gitlab_mr_details(&self, number: u32) -> Result<PrDetails> {
+        let number = number.to_string();
+        let output = self
+            .cmd("glab")
+            .args(&["mr", "view", &number, "--output", "json"])
+            .run()
+            .map_err(|e| {
+                if is_not_found(&e) {
+                    anyhow!(
+                        "GitLab CLI (glab) is required for --pr. Install from https://gitlab.com/gitlab-org/cli"
+                    )
+                } else {
+                    e.context(format!("Failed to fetch MR !{}", number))
+                }
+            })?;
+        let mr: GitlabMr =
+            serde_json::from_slice(&output.stdout).context("Failed to parse glab JSON output")?;
+        let owner = self.gitlab_source_owner(&mr)?;
+        Ok(PrDetails {
+            head_ref_name: mr.source_branch,
+            head_repository_owner: RepositoryOwner { login: owner },
+            state: gitlab_state(&mr.state).to_string(),
+            is_draft: mr.draft,
+            title: mr.title,
+            author: Author {
+                login: mr.author.username,
+            },
+        })
+    }
+
+    /// Merge requests in any state, with extra `glab mr list` arguments.
+    fn gitlab_list(&self, extra: &[&str], per_page: &str) -> Result<Vec<GitlabMr>> {
+        let mut args = vec![
+            "mr",
+            "list",
+            "--all",
+            "--per-page",
+            per_page,
+            "--output",
+            "json",
+        ];
+        args.extend(extra);
+        let output = self.cmd("glab").args(&args).run()?;
+        serde_json::from_slice(&output.stdout).context("Failed to parse glab JSON output")
+    }
+
+    /// Namespace of the project a merge request's branch lives in.
+    fn gitlab_source_owner(&self, mr: &GitlabMr) -> Result<String> {
+        if mr.source_project_id == mr.target_project_id {
+            let (owner, _) = self.origin_repo()?;
+            return Ok(owner.to_string());
+        }
+        let endpoint = format!("projects/{}", mr.source_project_id);
+        let output = self.cmd("glab").args(&["api", &endpoint]).run()?;
+        let project: GitlabProject =
+            serde_json::from_slice(&output.stdout).context("Failed to parse glab api output")?;
+        Ok(project.namespace.full_path)
+    }
+
+    // ── Gitea ────────────────────────────────────────────────────────
+
+    fn gitea_pulls(&self) -> Result<Vec<GiteaPull>> {
+        let (owner, repo) = self.origin_repo()?;
+        self.gitea_get(&format!(
+            "repos/{}/{}/pulls?state=all&limit=50",
+            owner, repo
+        ))
+    }
+
+    fn gitea_get<T: serde::de::DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let bytes = self.gitea_api("GET", endpoint, None)?;
+        serde_json::from_slice(&bytes).context("Failed to parse Gitea response")
+    }
+
+    /// Call the Gitea API. The token and request body go to curl on stdin,
+    /// so neither shows up in `ps` or the log.
+    fn gitea_api(&self, method: &str, endpoint: &str, body: Option<&str>) -> Result<Vec<u8>> {
+        let url = format!("{}/api/v1/{}", self.origin()?.web_base(), endpoint);
+        let mut config = String::new();
+        if let Ok(token) = std::env::var(GITEA_TOKEN_ENV)
+            && !token.is_empty()
+        {
+            config.push_str(&format!(
+                "header = \"Authorization: token {}\"\n",
+                curl_quote(&token)
+            ));
+        }
+        if let Some(body) = body {
+            config.push_str("header = \"Content-Type: application/json\"\n");
+            config.push_str(&format!("data-binary = \"{}\"\n", curl_quote(body)));
+        }
+        let args = [
+            "-fsSL",
+            "--connect-timeout",
+            "10",
+            "--max-time",
+            "60",
+            "-H",
+            "Accept: application/json",
+            "-X",
+            method,
+            "--config",
+            "-",
+            &url,
+        ];
+        let output = Cmd::new("curl")
+            .args(&args)
+            .stdin(&config)
+            .run()
+            .with_context(|| {
+                format!(
+                    "Gitea API request failed (set {} for private repositories)",
+                    GITEA_TOKEN_ENV
+                )
+            })?;
+        Ok(output.stdout)
+    }
+}
+
+/// Whether running a command failed because the program isn't installed.
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+    })
+}
+
+/// The last URL a CLI printed, which is the one of the created request.
+fn last_url(output: &str) -> Result<String> {
+    output
+        .split_whitespace()
+        .rev()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(String::from)
+        .ok_or_else(|| anyhow!("No pull request URL in output: {}", output.trim()))
+}
+
+/// Escape a value for a double-quoted string in a curl config file.
+pub(crate) fn curl_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// GitHub's state for a GitLab merge request state.
+fn gitlab_state(state: &str) -> &'static str {
+    match state {
+        "merged" => "MERGED",
+        "opened" => "OPEN",
+        _ => "CLOSED",
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabMr {
+    iid: u32,
+    title: String,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    source_branch: String,
+    source_project_id: u64,
+    target_project_id: u64,
+    author: GitlabUser,
+    #[serde(default)]
+    web_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabProject {
+    namespace: GitlabNamespace,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabNamespace {
+    full_path: String,
+}
+
+impl GitlabMr {
+    fn into_summary(self) -> PrSummary {
+        PrSummary {
+            number: self.iid,
+            state: gitlab_state(&self.state).to_string(),
+            title: self.title,
+            is_draft: self.draft,
+            checks: None,
+            url: self.web_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaPull {
+    number: u32,
+    title: String,
+    /// `open` or `closed`; merged requests are closed with `merged` set
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    html_url: String,
+    user: GiteaUser,
+    head: GiteaBranch,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaBranch {
+    #[serde(rename = "ref")]
+    branch: String,
+    /// None once the fork has been deleted
+    repo: Option<GiteaRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaRepo {
+    owner: GiteaUser,
+}
+
+impl GiteaPull {
+    fn head_owner(&self) -> Option<&str> {
+        self.head
+            .repo
+            .as_ref()
+            .map(|repo| repo.owner.login.as_str())
+    }
+
+    fn state(&self) -> &'static str {
+        match (self.state.as_str(), self.merged) {
+            (_, true) => "MERGED",
+            ("open", false) => "OPEN",
+            _ => "CLOSED",
+        }
+    }
+
+    /// Drafts are flagged by newer Gitea versions, and by a `WIP:` title
+    /// prefix on all of them.
+    fn is_draft(&self) -> bool {
+        self.draft || self.title.starts_with("WIP:") || self.title.starts_with("[WIP]")
+    }
+
+    fn into_summary(self) -> PrSummary {
+        PrSummary {
+            number: self.number,
+            state: self.state().to_string(),
+            is_draft: self.is_draft(),
+            title: self.title,
+            checks: None,
+            url: Some(self.html_url).filter(|url| !url.is_empty()),
+        }
+    }
+
+    fn into_details(self) -> PrDetails {
+        let owner = self.head_owner().unwrap_or(&self.user.login).to_string();
+        PrDetails {
+            state: self.state().to_string(),
+            is_draft: self.is_draft(),
+            head_ref_name: self.head.branch,
+            head_repository_owner: RepositoryOwner { login: owner },
+            title: self.title,
+            author: Author {
+                login: self.user.login,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_urls_are_parsed() {
+        let url = RemoteUrl::parse("ssh://git@gitea.example.com:2222/team/app.git").unwrap();
+        assert_eq!(url.scheme, "ssh");
+        assert_eq!(url.user, Some("git"));
+        assert_eq!(url.host, "gitea.example.com");
+        assert_eq!(url.port, Some("2222"));
+        assert_eq!(url.path, "team/app");
+
+        let url = RemoteUrl::parse("git@gitlab.com:group/sub/app.git").unwrap();
+        assert_eq!(url.host, "gitlab.com");
+        assert_eq!(url.port, None);
+        assert_eq!(url.path, "group/sub/app");
+
+        assert_eq!(RemoteUrl::parse("/local/path/to/repo"), None);
+        assert_eq!(RemoteUrl::parse("file:///local/path/to/repo"), None);
+        assert_eq!(RemoteUrl::parse("not-a-valid-url"), None);
+    }
+
+    #[test]
+    fn gitlab_owner_is_the_whole_namespace() {
+        let url = RemoteUrl::parse("https://gitlab.com/group/sub/app.git").unwrap();
+        assert_eq!(url.owner(ForgeProvider::Gitlab), Some("group/sub"));
+        assert_eq!(url.repo(ForgeProvider::Gitlab), Some("app"));
+        assert_eq!(url.owner(ForgeProvider::Github), Some("group"));
+        assert_eq!(url.repo(ForgeProvider::Github), Some("sub"));
+    }
+
+    #[test]
+    fn fork_urls_keep_scheme_host_and_port() {
+        assert_eq!(
+            fork_url(
+                ForgeProvider::Gitlab,
+                "git@gitlab.com:group/sub/app.git",
+                "alice"
+            )
+            .unwrap(),
+            "git@gitlab.com:alice/app.git"
+        );
+        assert_eq!(
+            fork_url(
+                ForgeProvider::Gitea,
+                "ssh://git@gitea.example.com:2222/team/app.git",
+                "bob"
+            )
+            .unwrap(),
+            "ssh://git@gitea.example.com:2222/bob/app.git"
+        );
+        assert_eq!(
+            fork_url(
+                ForgeProvider::Github,
+                "https://token@github.com/owner/repo",
+                "carol"
+            )
+            .unwrap(),
+            "https://github.com/carol/repo.git"
+        );
+        assert!(fork_url(ForgeProvider::Github, "/local/repo", "carol").is_err());
+    }
+
+    #[test]
+    fn provider_is_detected_from_host() {
+        assert_eq!(detect_provider("github.com"), ForgeProvider::Github);
+        assert_eq!(
+            detect_provider("github.corp.internal"),
+            ForgeProvider::Github
+        );
+        assert_eq!(detect_provider("gitlab.com"), ForgeProvider::Gitlab);
+        assert_eq!(detect_provider("GitLab.example.org"), ForgeProvider::Gitlab);
+        assert_eq!(detect_provider("codeberg.org"), ForgeProvider::Gitea);
+        assert_eq!(detect_provider("gitea.example.com"), ForgeProvider::Gitea);
+        assert_eq!(detect_provider("forgejo.example.com"), ForgeProvider::Gitea);
+    }
+
+    #[test]
+    fn gitea_pulls_map_to_github_states() {
+        let pr: GiteaPull = serde_json::from_str(
+            r#"{"number": 7, "title": "WIP: Add x", "state": "closed", "merged": true,
+                "html_url": "https://gitea.example.com/team/app/pulls/7",
+                "user": {"login": "bob"},
+                "head": {"ref": "add-x", "repo": {"owner": {"login": "bob"}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(pr.state(), "MERGED");
+        assert!(pr.is_draft());
+        let details = pr.into_details();
+        assert_eq!(details.head_ref_name, "add-x");
+        assert_eq!(details.head_repository_owner.login, "bob");
+        assert!(details.is_fork("team"));
+
+        assert_eq!(gitlab_state("opened"), "OPEN");
+        assert_eq!(gitlab_state("locked"), "CLOSED");
+    }
+
+    #[test]
+    fn curl_config_values_are_escaped() {
+        assert_eq!(curl_quote(r#"{"a":"b\n"}"#), r#"{\"a\":\"b\\n\"}"#);
+    }
+}
@@ -6,7 +6,7 @@ use tracing::debug;
 use crate::cmd::Cmd;
 
 use super::repo::has_commits;
-use super::{ForkBranchSpec, RemoteBranchSpec};
+use super::{BranchSummary, ForkBranchSpec, RemoteBranchSpec};
 
 /// Get the default branch (main or master)
 pub fn get_default_branch() -> Result<String> {
@@ -59,6 +59,14 @@ pub fn branch_exists(branch_name: &str) -> Result<bool> {
     branch_exists_in(branch_name, None)
 }
 
+/// Full commit id a revision resolves to
+pub fn resolve_commit(rev: &str) -> Result<String> {
+    Cmd::new("git")
+        .args(&["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+        .run_and_capture_stdout()
+        .with_context(|| format!("Failed to resolve '{}'", rev))
+}
+
 /// Check if a branch exists in a specific workdir
 pub fn branch_exists_in(branch_name: &str, workdir: Option<&Path>) -> Result<bool> {
     let cmd = Cmd::new("git").args(&["rev-parse", "--verify", "--quiet", branch_name]);
@@ -117,6 +125,27 @@ pub fn get_current_branch() -> Result<String> {
         .run_and_capture_stdout()
 }
 
+/// List all local and remote-tracking branches, including those checked out
+/// in worktrees. Used for base branch completion and suggestions.
+pub fn list_all_branches() -> Result<Vec<String>> {
+    let output = Cmd::new("git")
+        .args(&[
+            "for-each-ref",
+            "--format=%(refname:short)",
+            "refs/heads/",
+            "refs/remotes/",
+        ])
+        .run_and_capture_stdout()
+        .context("Failed to list git branches")?;
+
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != "HEAD" && !s.ends_with("/HEAD"))
+        .map(String::from)
+        .collect())
+}
+
 /// List all checkout-able branches (local and remote) for shell completion.
 /// Excludes branches that are already checked out in existing worktrees.
 pub fn list_checkout_branches() -> Result<Vec<String>> {
@@ -162,6 +191,71 @@ pub fn delete_branch_in(branch_name: &str, force: bool, git_common_dir: &Path) -
     Ok(())
 }
 
+/// Write the commits on a local branch that aren't on `base` to a bundle file.
+///
+/// Returns false without writing anything if the branch has no such commits.
+/// If `base` doesn't resolve, the whole branch is bundled.
+pub fn bundle_branch_in(
+    branch_name: &str,
+    base: &str,
+    path: &Path,
+    git_common_dir: &Path,
+) -> Result<bool> {
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    let mut revs = vec![branch_ref.clone()];
+    if Cmd::new("git")
+        .workdir(git_common_dir)
+        .args(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", base),
+        ])
+        .run_as_check()?
+    {
+        revs.push(format!("^{}", base));
+    }
+
+    let mut count_args = vec!["rev-list", "--count"];
+    count_args.extend(revs.iter().map(String::as_str));
+    let count: u64 = Cmd::new("git")
+        .workdir(git_common_dir)
+        .args(&count_args)
+        .run_and_capture_stdout()?
+        .trim()
+        .parse()
+        .context("Failed to count branch commits")?;
+    if count == 0 {
+        return Ok(false);
+    }
+
+    let path = path.to_string_lossy();
+    let mut bundle_args = vec!["bundle", "create", "--quiet", path.as_ref()];
+    bundle_args.extend(revs.iter().map(String::as_str));
+    Cmd::new("git")
+        .workdir(git_common_dir)
+        .args(&bundle_args)
+        .run()
+        .with_context(|| format!("Failed to bundle branch '{}'", branch_name))?;
+    Ok(true)
+}
+
+/// Create a local branch from the branch of the same name in a bundle file.
+pub fn restore_branch_bundle_in(
+    path: &Path,
+    branch_name: &str,
+    git_common_dir: &Path,
+) -> Result<()> {
+    let path = path.to_string_lossy();
+    let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch_name);
+    Cmd::new("git")
+        .workdir(git_common_dir)
+        .args(&["fetch", "--quiet", path.as_ref(), &refspec])
+        .run()
+        .with_context(|| format!("Failed to restore branch '{}' from bundle", branch_name))?;
+    Ok(())
+}
+
 /// Get the base branch for merge checks, preferring local branch over remote
 pub fn get_merge_base(main_branch: &str) -> Result<String> {
     // Check if the local branch exists first.
@@ -231,6 +325,57 @@ pub fn get_gone_branches() -> Result<HashSet<String>> {
     Ok(gone)
 }
 
+/// Get the committer timestamp (Unix seconds) of a branch's latest commit
+pub fn get_last_commit_time(branch: &str) -> Result<u64> {
+    let output = Cmd::new("git")
+        .args(&[
+            "log",
+            "-1",
+            "--format=%ct",
+            &format!("refs/heads/{}", branch),
+            "--",
+        ])
+        .run_and_capture_stdout()?;
+    output
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid commit time for branch '{}'", branch))
+}
+
+/// Summarize a local or remote branch against `base`.
+pub fn get_branch_summary(branch: &str, base: &str) -> Result<BranchSummary> {
+    let log = Cmd::new("git")
+        .args(&["log", "-1", "--format=%ct%x09%s", branch, "--"])
+        .run_and_capture_stdout()
+        .with_context(|| format!("Failed to read last commit of '{}'", branch))?;
+    let (time, subject) = log.split_once('\t').unwrap_or((log.as_str(), ""));
+    let counts = Cmd::new("git")
+        .args(&[
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("{}...{}", base, branch),
+        ])
+        .run_and_capture_stdout()
+        .with_context(|| format!("Failed to compare '{}' with '{}'", branch, base))?;
+    let (behind, ahead) = parse_left_right_count(&counts)
+        .ok_or_else(|| anyhow!("Unexpected rev-list output: {}", counts))?;
+    Ok(BranchSummary {
+        ahead,
+        behind,
+        last_commit_time: time.trim().parse().unwrap_or(0),
+        subject: subject.trim().to_string(),
+    })
+}
+
+/// Parse `git rev-list --left-right --count` output into (left, right).
+pub(super) fn parse_left_right_count(output: &str) -> Option<(usize, usize)> {
+    let mut parts = output.split_whitespace();
+    let left = parts.next()?.parse().ok()?;
+    let right = parts.next()?.parse().ok()?;
+    Some((left, right))
+}
+
 /// Unset the upstream tracking for a branch
 pub fn unset_branch_upstream(branch_name: &str) -> Result<()> {
     if !branch_has_upstream(branch_name)? {
@@ -276,6 +421,20 @@ pub fn set_branch_base(branch: &str, base: &str) -> Result<()> {
     Ok(())
 }
 
+/// Forget the base branch/commit recorded for a branch.
+/// No-op if none is recorded.
+pub fn unset_branch_base(branch: &str) -> Result<()> {
+    let config_key = format!("branch.{}.workmux-base", branch);
+    if get_branch_base(branch).is_err() {
+        return Ok(());
+    }
+    Cmd::new("git")
+        .args(&["config", "--local", "--unset-all", &config_key])
+        .run()
+        .context("Failed to unset workmux-base config")?;
+    Ok(())
+}
+
 /// Retrieve the base branch/commit that a branch was created from
 pub fn get_branch_base(branch: &str) -> Result<String> {
     get_branch_base_in(branch, None)
@@ -304,6 +463,13 @@ pub fn get_branch_base_in(branch: &str, workdir: Option<&Path>) -> Result<String
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_left_right_count() {
+        assert_eq!(parse_left_right_count("3\t12\n"), Some((3, 12)));
+        assert_eq!(parse_left_right_count(""), None);
+        assert_eq!(parse_left_right_count("x 1"), None);
+    }
+
     #[test]
     fn test_parse_fork_branch_spec_valid() {
         let spec = parse_fork_branch_spec("someuser:feature-branch").unwrap();
@@ -0,0 +1,143 @@
+//! The repository's own git hooks (husky, pre-commit, lefthook).
+//!
+//! Workmux runs plain `git merge`/`commit`/`push`, so any hooks the repo
+//! installs fire for those too. Fresh worktrees often lack the hooks'
+//! dependencies (e.g. `node_modules`), which makes the hooks fail for reasons
+//! unrelated to the change being merged.
+
+use std::path::{Path, PathBuf};
+
+use crate::cmd::Cmd;
+
+/// Hooks that can run while `git merge` creates the merge commit.
+pub const MERGE_HOOKS: &[&str] = &["pre-merge-commit", "prepare-commit-msg", "commit-msg"];
+
+/// Hooks that can run during `git commit`.
+pub const COMMIT_HOOKS: &[&str] = &["pre-commit", "prepare-commit-msg", "commit-msg"];
+
+/// A repository hook rejected a git operation workmux ran.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "The repository's git hooks ({}) rejected the {op}.{}\n\n\
+    Fix the hook (fresh worktrees may be missing its dependencies, see `git_hooks.install`), \
+    or add '{op}' to `git_hooks.skip` to run it with --no-verify.",
+    hooks.join(", "),
+    if output.is_empty() { String::new() } else { format!("\n\n{}", output) }
+)]
+pub struct GitHookFailed {
+    /// Operation name as used in `git_hooks.skip`
+    pub op: &'static str,
+    pub hooks: Vec<String>,
+    /// What git and the hooks printed
+    pub output: String,
+}
+
+/// Directory git runs hooks from for `worktree`, honoring `core.hooksPath`.
+fn hooks_dir(worktree: &Path) -> Option<PathBuf> {
+    let dir = Cmd::new("git")
+        .workdir(worktree)
+        .args(&["rev-parse", "--git-path", "hooks"])
+        .run_and_capture_stdout()
+        .ok()?;
+    Some(worktree.join(dir))
+}
+
+/// Which of `names` are installed as executable hooks for `worktree`.
+pub fn installed_hooks(worktree: &Path, names: &[&str]) -> Vec<String> {
+    let Some(dir) = hooks_dir(worktree) else {
+        return Vec::new();
+    };
+    names
+        .iter()
+        .filter(|name| is_executable(&dir.join(name)))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Commands that install the dependencies of the hook managers `root` uses.
+///
+/// Detects pre-commit (`.pre-commit-config.yaml`), lefthook (`lefthook.yml`)
+/// and husky (`.husky/` next to a `package.json`, installed with the package
+/// manager whose lockfile is present).
+pub fn hook_install_commands(root: &Path) -> Vec<String> {
+    let mut commands = Vec::new();
+    if root.join(".pre-commit-config.yaml").is_file() {
+        commands.push("pre-commit install --install-hooks".to_string());
+    }
+    if root.join("lefthook.yml").is_file() || root.join("lefthook.yaml").is_file() {
+        commands.push("lefthook install".to_string());
+    }
+    if root.join(".husky").is_dir() && root.join("package.json").is_file() {
+        let install = if root.join("pnpm-lock.yaml").is_file() {
+            "pnpm install --frozen-lockfile"
+        } else if root.join("yarn.lock").is_file() {
+            "yarn install --frozen-lockfile"
+        } else if root.join("bun.lock").is_file() || root.join("bun.lockb").is_file() {
+            "bun install --frozen-lockfile"
+        } else if root.join("package-lock.json").is_file() {
+            "npm ci"
+        } else {
+            "npm install"
+        };
+        commands.push(install.to_string());
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn install_commands_follow_detected_managers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        assert!(hook_install_commands(root).is_empty());
+
+        fs::create_dir(root.join(".husky")).unwrap();
+        assert!(
+            hook_install_commands(root).is_empty(),
+            "husky without package.json"
+        );
+
+        fs::write(root.join("package.json"), "{}").unwrap();
+        assert_eq!(hook_install_commands(root), ["npm install"]);
+
+        fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        fs::write(root.join(".pre-commit-config.yaml"), "repos: []").unwrap();
+        assert_eq!(
+            hook_install_commands(root),
+            [
+                "pre-commit install --install-hooks",
+                "pnpm install --frozen-lockfile"
+            ]
+        );
+    }
+
+    #[test]
+    fn hook_failure_message_names_hooks_and_op() {
+        let err = GitHookFailed {
+            op: "merge",
+            hooks: vec!["pre-merge-commit".to_string(), "commit-msg".to_string()],
+            output: "lint failed".to_string(),
+        };
+        let msg = err.to_string();
+        assert!(msg.starts_with(
+            "The repository's git hooks (pre-merge-commit, commit-msg) rejected the merge."
+        ));
+        assert!(msg.contains("\n\nlint failed\n\n"));
+        assert!(msg.contains("add 'merge' to `git_hooks.skip`"));
+    }
+}
@@ -4,29 +4,132 @@ use std::process::Command;
 
 use crate::cmd::Cmd;
 
-/// Commit staged changes in a worktree using the user's editor
-pub fn commit_with_editor(worktree_path: &Path) -> Result<()> {
-    let status = Command::new("git")
-        .current_dir(worktree_path)
-        .arg("commit")
-        .status()
-        .context("Failed to run git commit")?;
+use super::hooks::{COMMIT_HOOKS, GitHookFailed, MERGE_HOOKS, installed_hooks};
+
+/// Commit staged changes in a worktree using the user's editor.
+/// With `no_verify`, the repository's commit hooks are skipped.
+pub fn commit_with_editor(worktree_path: &Path, no_verify: bool) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(worktree_path).arg("commit");
+    if no_verify {
+        cmd.arg("--no-verify");
+    }
+    let status = cmd.status().context("Failed to run git commit")?;
 
     if !status.success() {
+        // The commit is interactive, so git's output went to the terminal.
+        // An aborted message and a failing hook look the same from here.
+        let hooks = if no_verify {
+            Vec::new()
+        } else {
+            installed_hooks(worktree_path, COMMIT_HOOKS)
+        };
+        if !hooks.is_empty() {
+            return Err(anyhow!(
+                "Commit was aborted or failed. The repository's git hooks ({}) ran; \
+                add 'commit' to `git_hooks.skip` to run it with --no-verify.",
+                hooks.join(", ")
+            ));
+        }
         return Err(anyhow!("Commit was aborted or failed"));
     }
 
     Ok(())
 }
 
-/// Merge a branch into the current branch in a specific worktree
-pub fn merge_in_worktree(worktree_path: &Path, branch_name: &str) -> Result<()> {
+/// Append `text` to the message git offers for the commit that concludes
+/// a squash merge (`SQUASH_MSG`).
+pub fn append_squash_message(worktree_path: &Path, text: &str) -> Result<()> {
+    let msg_path = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&["rev-parse", "--git-path", "SQUASH_MSG"])
+        .run_and_capture_stdout()
+        .context("Failed to locate SQUASH_MSG")?;
+    let msg_path = worktree_path.join(msg_path.trim());
+    let mut message = std::fs::read_to_string(&msg_path).unwrap_or_default();
+    if !message.is_empty() && !message.ends_with("\n\n") {
+        message.push('\n');
+    }
+    message.push_str(text);
+    std::fs::write(&msg_path, message)
+        .with_context(|| format!("Failed to write '{}'", msg_path.display()))
+}
+
+/// Merge a branch into the current branch in a specific worktree.
+/// With `no_verify`, the repository's merge commit hooks are skipped.
+///
+/// Returns [`GitHookFailed`] when the merge itself succeeded but a hook
+/// refused the merge commit, so callers can tell it apart from conflicts.
+pub fn merge_in_worktree(worktree_path: &Path, branch_name: &str, no_verify: bool) -> Result<()> {
+    let mut args = vec!["merge"];
+    if no_verify {
+        args.push("--no-verify");
+    }
+    args.push(branch_name);
+    let output = Command::new("git")
+        .current_dir(worktree_path)
+        .args(&args)
+        .output()
+        .context("Failed to run git merge")?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let printed = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .trim()
+    .to_string();
+    if merge_blocked_by_hook(worktree_path) {
+        return Err(GitHookFailed {
+            op: "merge",
+            hooks: installed_hooks(worktree_path, MERGE_HOOKS),
+            output: printed,
+        }
+        .into());
+    }
+    Err(anyhow!(
+        "Command failed: git {}\n{}",
+        args.join(" "),
+        printed
+    ))
+    .context("Failed to merge")
+}
+
+/// A merge left MERGE_HEAD without conflicts: git merged cleanly but did not
+/// create the commit, which only happens when a hook refuses it.
+fn merge_blocked_by_hook(worktree_path: &Path) -> bool {
+    let merging = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&["rev-parse", "-q", "--verify", "MERGE_HEAD"])
+        .run_as_check()
+        .unwrap_or(false);
+    if !merging {
+        return false;
+    }
     Cmd::new("git")
         .workdir(worktree_path)
-        .args(&["merge", branch_name])
-        .run()
-        .context("Failed to merge")?;
-    Ok(())
+        .args(&["diff", "--name-only", "--diff-filter=U"])
+        .run_and_capture_stdout()
+        .is_ok_and(|unmerged| unmerged.is_empty())
+}
+
+/// Whether a worktree has a merge in progress: MERGE_HEAD is set or the index
+/// still holds unmerged paths
+pub fn merge_in_progress(worktree_path: &Path) -> bool {
+    let merging = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&["rev-parse", "-q", "--verify", "MERGE_HEAD"])
+        .run_as_check()
+        .unwrap_or(false);
+    merging
+        || Cmd::new("git")
+            .workdir(worktree_path)
+            .args(&["diff", "--name-only", "--diff-filter=U"])
+            .run_and_capture_stdout()
+            .is_ok_and(|unmerged| !unmerged.is_empty())
 }
 
 /// Rebase the current branch in a worktree onto a base branch
@@ -111,6 +214,47 @@ pub fn reset_hard(worktree_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Whether a worktree holds nothing but an unfinished merge of `branch`: a
+/// merge waiting for its commit (`MERGE_HEAD` at the branch), or a squash
+/// merge of it that was never committed and hasn't been edited since.
+///
+/// Only then can `reset_hard` roll the merge back without losing other work.
+pub fn holds_merge_of(worktree_path: &Path, branch: &str) -> Result<bool> {
+    let git = |args: &[&str]| {
+        Cmd::new("git")
+            .workdir(worktree_path)
+            .args(args)
+            .run_and_capture_stdout()
+            .map(|out| out.trim().to_string())
+    };
+    let branch_head = git(&["rev-parse", "--verify", &format!("{}^{{commit}}", branch)])?;
+    if let Ok(merge_head) = git(&["rev-parse", "-q", "--verify", "MERGE_HEAD"]) {
+        return Ok(merge_head == branch_head);
+    }
+
+    // A squash merge leaves no MERGE_HEAD, only the message listing the
+    // squashed commits
+    let msg_path = worktree_path.join(git(&["rev-parse", "--git-path", "SQUASH_MSG"])?);
+    let Ok(message) = std::fs::read_to_string(&msg_path) else {
+        return Ok(false);
+    };
+    let squashes_branch = message
+        .lines()
+        .any(|line| line.strip_prefix("commit ") == Some(branch_head.as_str()));
+    if !squashes_branch || super::has_unstaged_changes(worktree_path)? {
+        return Ok(false);
+    }
+    // The staged tree must be exactly the squash result, so staged edits
+    // made since are not thrown away. Fails on conflicts, which are left alone.
+    let (Ok(merged), Ok(staged)) = (
+        git(&["merge-tree", "--write-tree", "HEAD", &branch_head]),
+        git(&["write-tree"]),
+    ) else {
+        return Ok(false);
+    };
+    Ok(merged.lines().next() == Some(staged.as_str()))
+}
+
 /// Abort a merge in progress in a specific worktree
 pub fn abort_merge_in_worktree(worktree_path: &Path) -> Result<()> {
     Cmd::new("git")
@@ -120,3 +264,141 @@ pub fn abort_merge_in_worktree(worktree_path: &Path) -> Result<()> {
         .context("Failed to abort merge. The worktree may not be in a merging state.")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn merge_refused_by_hook_is_reported_as_hook_failure() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["config", "user.name", "t"]);
+        git(root, &["config", "user.email", "t@t"]);
+        git(root, &["commit", "--allow-empty", "-m", "init"]);
+        git(root, &["checkout", "-b", "feature"]);
+        fs::write(root.join("feature.txt"), "x").unwrap();
+        git(root, &["add", "feature.txt"]);
+        git(root, &["commit", "-m", "feature"]);
+        git(root, &["checkout", "main"]);
+        git(root, &["commit", "--allow-empty", "-m", "main moved"]);
+
+        let hook = root.join(".git/hooks/pre-merge-commit");
+        fs::write(&hook, "#!/bin/sh\necho 'lint failed' >&2\nexit 1\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = merge_in_worktree(root, "feature", false).unwrap_err();
+        let hook_err = err.downcast_ref::<GitHookFailed>().expect("hook failure");
+        assert_eq!(hook_err.op, "merge");
+        assert_eq!(hook_err.hooks, ["pre-merge-commit"]);
+        assert!(hook_err.output.contains("lint failed"));
+
+        abort_merge_in_worktree(root).unwrap();
+        merge_in_worktree(root, "feature", true).unwrap();
+        assert!(root.join("feature.txt").exists());
+    }
+
+    #[test]
+    fn squash_message_gets_appended_text() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["config", "user.name", "t"]);
+        git(root, &["config", "user.email", "t@t"]);
+        git(root, &["commit", "--allow-empty", "-m", "init"]);
+        git(root, &["checkout", "-b", "feature"]);
+        fs::write(root.join("feature.txt"), "x").unwrap();
+        git(root, &["add", "feature.txt"]);
+        git(root, &["commit", "-m", "feature"]);
+        git(root, &["checkout", "main"]);
+
+        merge_squash_in_worktree(root, "feature").unwrap();
+        append_squash_message(root, "Output of tests:\n\n    ok\n").unwrap();
+        let message = fs::read_to_string(root.join(".git/SQUASH_MSG")).unwrap();
+        assert!(message.starts_with("Squashed commit of the following:"));
+        assert!(message.ends_with("\n\nOutput of tests:\n\n    ok\n"));
+    }
+
+    #[test]
+    fn only_an_untouched_interrupted_merge_is_held() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["config", "user.name", "t"]);
+        git(root, &["config", "user.email", "t@t"]);
+        fs::write(root.join("main.txt"), "main\n").unwrap();
+        git(root, &["add", "main.txt"]);
+        git(root, &["commit", "-m", "init"]);
+        git(root, &["checkout", "-b", "feature"]);
+        fs::write(root.join("feature.txt"), "x").unwrap();
+        git(root, &["add", "feature.txt"]);
+        git(root, &["commit", "-m", "feature"]);
+        git(root, &["checkout", "main"]);
+        assert!(!holds_merge_of(root, "feature").unwrap());
+
+        // Squash merged but not committed
+        merge_squash_in_worktree(root, "feature").unwrap();
+        assert!(holds_merge_of(root, "feature").unwrap());
+
+        // Edits made since, staged or not, must survive
+        fs::write(root.join("main.txt"), "edited\n").unwrap();
+        assert!(!holds_merge_of(root, "feature").unwrap());
+        git(root, &["add", "main.txt"]);
+        assert!(!holds_merge_of(root, "feature").unwrap());
+        reset_hard(root).unwrap();
+
+        // Plain edits with no merge at all
+        fs::write(root.join("main.txt"), "edited\n").unwrap();
+        assert!(!holds_merge_of(root, "feature").unwrap());
+        reset_hard(root).unwrap();
+
+        // A merge waiting for its commit
+        git(root, &["merge", "--no-ff", "--no-commit", "feature"]);
+        assert!(holds_merge_of(root, "feature").unwrap());
+        assert!(!holds_merge_of(root, "main").unwrap());
+    }
+
+    #[test]
+    fn only_a_conflicted_merge_is_in_progress() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["config", "user.name", "t"]);
+        git(root, &["config", "user.email", "t@t"]);
+        fs::write(root.join("file.txt"), "base\n").unwrap();
+        git(root, &["add", "file.txt"]);
+        git(root, &["commit", "-m", "init"]);
+        git(root, &["checkout", "-b", "feature"]);
+        fs::write(root.join("file.txt"), "feature\n").unwrap();
+        git(root, &["commit", "-am", "feature"]);
+        git(root, &["checkout", "main"]);
+        fs::write(root.join("file.txt"), "main\n").unwrap();
+        git(root, &["commit", "-am", "main"]);
+
+        // Refused over local edits: nothing is left in progress
+        fs::write(root.join("file.txt"), "edited\n").unwrap();
+        assert!(merge_in_worktree(root, "feature", false).is_err());
+        assert!(!merge_in_progress(root));
+        reset_hard(root).unwrap();
+
+        merge_in_worktree(root, "feature", false).unwrap_err();
+        assert!(merge_in_progress(root));
+        abort_merge_in_worktree(root).unwrap();
+        assert!(!merge_in_progress(root));
+    }
+}
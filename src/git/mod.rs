@@ -1,6 +1,8 @@
 mod branch;
 mod cache;
+mod hooks;
 mod merge;
+mod patch;
 mod remote;
 mod repo;
 mod status;
@@ -9,7 +11,9 @@ mod worktree;
 
 pub use branch::*;
 pub use cache::*;
+pub use hooks::*;
 pub use merge::*;
+pub use patch::*;
 pub use remote::*;
 pub use repo::*;
 pub use status::*;
@@ -0,0 +1,233 @@
+//! Applying a patch file to a new worktree (`workmux add --apply`).
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::io::Read;
+use std::path::Path;
+
+use crate::cmd::Cmd;
+
+/// Kind of file passed to `--apply`, detected from its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchFormat {
+    /// Plain diff, applied to the working tree and left uncommitted
+    Diff,
+    /// `git format-patch` mailbox, applied as commits with `git am`
+    Mbox,
+    /// `git bundle`, whose commits the new branch is fast-forwarded to
+    Bundle,
+}
+
+impl PatchFormat {
+    pub fn detect(head: &[u8]) -> Self {
+        if head.starts_with(b"# v2 git bundle") || head.starts_with(b"# v3 git bundle") {
+            PatchFormat::Bundle
+        } else if head.starts_with(b"From ") {
+            PatchFormat::Mbox
+        } else {
+            PatchFormat::Diff
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PatchFormat::Diff => "diff",
+            PatchFormat::Mbox => "mailbox",
+            PatchFormat::Bundle => "bundle",
+        }
+    }
+}
+
+/// Detect the format of the patch file at `path`.
+pub fn detect_patch_format(path: &Path) -> Result<PatchFormat> {
+    let mut head = [0u8; 64];
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open patch file '{}'", path.display()))?;
+    let n = file
+        .read(&mut head)
+        .with_context(|| format!("Failed to read patch file '{}'", path.display()))?;
+    Ok(PatchFormat::detect(&head[..n]))
+}
+
+/// Apply the patch file at `patch` to the worktree at `worktree_path`.
+pub fn apply_patch_file(worktree_path: &Path, patch: &Path) -> Result<PatchFormat> {
+    let patch = std::path::absolute(patch)
+        .with_context(|| format!("Invalid patch path '{}'", patch.display()))?;
+    let patch_str = patch.to_string_lossy();
+    let format = detect_patch_format(&patch)?;
+    match format {
+        PatchFormat::Diff => {
+            Cmd::new("git")
+                .workdir(worktree_path)
+                .args(&["apply", "--whitespace=nowarn", &patch_str])
+                .run()
+                .context("Failed to apply the diff")?;
+        }
+        PatchFormat::Mbox => {
+            let applied = Cmd::new("git")
+                .workdir(worktree_path)
+                .args(&["am", "--3way", &patch_str])
+                .run();
+            if let Err(e) = applied {
+                let _ = Cmd::new("git")
+                    .workdir(worktree_path)
+                    .args(&["am", "--abort"])
+                    .run();
+                return Err(e).context("Failed to apply the patches with git am");
+            }
+        }
+        PatchFormat::Bundle => {
+            let heads = Cmd::new("git")
+                .workdir(worktree_path)
+                .args(&["bundle", "list-heads", &patch_str])
+                .run_and_capture_stdout()
+                .context("Failed to read the bundle")?;
+            let (commit, head) = bundle_head(&heads)?;
+            Cmd::new("git")
+                .workdir(worktree_path)
+                .args(&["fetch", "--no-tags", &patch_str, head])
+                .run()
+                .context("Failed to fetch from the bundle")?;
+            Cmd::new("git")
+                .workdir(worktree_path)
+                .args(&["merge", "--ff-only", commit])
+                .run()
+                .map_err(|_| {
+                    anyhow!(
+                        "The bundle's commits ({}) don't build on the new branch. \
+                         Use --base with the branch the bundle was made from.",
+                        head
+                    )
+                })?;
+        }
+    }
+    Ok(format)
+}
+
+/// The commit and ref name to take from a bundle: its only branch, or
+/// its `HEAD` when it has several.
+fn bundle_head(list_heads: &str) -> Result<(&str, &str)> {
+    let heads: Vec<(&str, &str)> = list_heads
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .collect();
+    match heads.as_slice() {
+        [] => bail!("The bundle contains no refs"),
+        [only] => Ok(*only),
+        _ => heads
+            .iter()
+            .find(|(_, name)| *name == "HEAD")
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "The bundle contains several branches ({}) and no HEAD; \
+                     create it with a single branch",
+                    heads
+                        .iter()
+                        .map(|(_, name)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// A repository on `main` with one commit, and the patch files of a
+    /// follow-up commit on `feature`.
+    fn repo_with_patches() -> tempfile::TempDir {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("repo");
+        fs::create_dir(&root).unwrap();
+        git(&root, &["init", "-b", "main"]);
+        git(&root, &["config", "user.name", "t"]);
+        git(&root, &["config", "user.email", "t@t"]);
+        fs::write(root.join("a.txt"), "one\n").unwrap();
+        git(&root, &["add", "a.txt"]);
+        git(&root, &["commit", "-m", "init"]);
+        git(&root, &["checkout", "-b", "feature"]);
+        fs::write(root.join("a.txt"), "one\ntwo\n").unwrap();
+        git(&root, &["commit", "-am", "add two"]);
+
+        let diff = git(&root, &["diff", "main", "feature"]);
+        fs::write(temp.path().join("change.diff"), diff).unwrap();
+        let mbox = git(&root, &["format-patch", "--stdout", "main..feature"]);
+        fs::write(temp.path().join("change.mbox"), mbox).unwrap();
+        let bundle = temp.path().join("change.bundle");
+        git(
+            &root,
+            &[
+                "bundle",
+                "create",
+                &bundle.to_string_lossy(),
+                "main..feature",
+            ],
+        );
+        git(&root, &["checkout", "main"]);
+        temp
+    }
+
+    #[test]
+    fn formats_are_detected() {
+        let temp = repo_with_patches();
+        let format = |name: &str| detect_patch_format(&temp.path().join(name)).unwrap();
+        assert_eq!(format("change.diff"), PatchFormat::Diff);
+        assert_eq!(format("change.mbox"), PatchFormat::Mbox);
+        assert_eq!(format("change.bundle"), PatchFormat::Bundle);
+    }
+
+    #[test]
+    fn each_format_applies() {
+        let temp = repo_with_patches();
+        let root = temp.path().join("repo");
+        for name in ["change.diff", "change.mbox", "change.bundle"] {
+            git(&root, &["checkout", "-B", "target", "main"]);
+            apply_patch_file(&root, &temp.path().join(name)).unwrap();
+            assert_eq!(
+                fs::read_to_string(root.join("a.txt")).unwrap(),
+                "one\ntwo\n",
+                "{}",
+                name
+            );
+            git(&root, &["reset", "--hard"]);
+        }
+        // Mailboxes and bundles bring the commit along
+        assert_eq!(git(&root, &["log", "-1", "--format=%s"]).trim(), "add two");
+    }
+
+    #[test]
+    fn conflicting_diff_fails() {
+        let temp = repo_with_patches();
+        let root = temp.path().join("repo");
+        fs::write(root.join("a.txt"), "other\n").unwrap();
+        git(&root, &["commit", "-am", "diverge"]);
+        assert!(apply_patch_file(&root, &temp.path().join("change.diff")).is_err());
+    }
+
+    #[test]
+    fn bundle_head_prefers_the_only_branch_then_head() {
+        assert_eq!(
+            bundle_head("abc refs/heads/feature\n").unwrap(),
+            ("abc", "refs/heads/feature")
+        );
+        assert_eq!(
+            bundle_head("abc refs/heads/a\ndef HEAD\n").unwrap(),
+            ("def", "HEAD")
+        );
+        assert!(bundle_head("abc refs/heads/a\ndef refs/heads/b\n").is_err());
+        assert!(bundle_head("").is_err());
+    }
+}
@@ -1,9 +1,9 @@
 use anyhow::{Context, Result, anyhow};
-use git_url_parse::GitUrl;
-use git_url_parse::types::provider::GenericProvider;
 use tracing::info;
 
 use crate::cmd::Cmd;
+use crate::config::ForgeProvider;
+use crate::forge::{self, RemoteUrl};
 
 /// Return a list of configured git remotes
 pub fn list_remotes() -> Result<Vec<String>> {
@@ -34,6 +34,19 @@ pub fn fetch_remote(remote: &str) -> Result<()> {
     Ok(())
 }
 
+/// Fetch a single branch from the given remote.
+///
+/// Uses an explicit refspec so the remote-tracking ref is created even when
+/// the remote's configured fetch refspec is narrowed (e.g. single-branch clones).
+pub fn fetch_remote_branch(remote: &str, branch: &str) -> Result<()> {
+    let refspec = format!("refs/heads/{0}:refs/remotes/{1}/{0}", branch, remote);
+    Cmd::new("git")
+        .args(&["fetch", remote, &refspec])
+        .run()
+        .with_context(|| format!("Failed to fetch '{}' from remote '{}'", branch, remote))?;
+    Ok(())
+}
+
 /// Fetch from remote with prune to update remote-tracking refs
 pub fn fetch_prune() -> Result<()> {
     Cmd::new("git")
@@ -43,6 +56,98 @@ pub fn fetch_prune() -> Result<()> {
     Ok(())
 }
 
+/// Get the remote a branch tracks (`branch.<name>.remote`), if any
+pub fn get_branch_remote(branch: &str) -> Option<String> {
+    Cmd::new("git")
+        .args(&["config", "--get", &format!("branch.{}.remote", branch)])
+        .run_and_capture_stdout()
+        .ok()
+        .filter(|remote| !remote.is_empty() && remote != ".")
+}
+
+/// Push a local branch to the given remote.
+///
+/// With `force_with_lease`, the push only overwrites the remote branch if it
+/// still points at our remote-tracking ref (used after rebasing). With
+/// `no_verify`, the repository's pre-push hook is skipped.
+pub fn push_branch(
+    remote: &str,
+    branch: &str,
+    force_with_lease: bool,
+    no_verify: bool,
+) -> Result<()> {
+    let mut args = vec!["push"];
+    if force_with_lease {
+        args.push("--force-with-lease");
+    }
+    if no_verify {
+        args.push("--no-verify");
+    }
+    args.extend([remote, branch]);
+    Cmd::new("git")
+        .args(&args)
+        .run()
+        .with_context(|| format!("Failed to push '{}' to remote '{}'", branch, remote))?;
+    Ok(())
+}
+
+/// Delete a branch on the given remote
+pub fn delete_remote_branch(remote: &str, branch: &str) -> Result<()> {
+    Cmd::new("git")
+        .args(&["push", remote, "--delete", branch])
+        .run()
+        .with_context(|| {
+            format!(
+                "Failed to delete branch '{}' on remote '{}'",
+                branch, remote
+            )
+        })?;
+    Ok(())
+}
+
+/// Make `branch` track the branch of the same name on `remote`
+pub fn set_branch_upstream(branch: &str, remote: &str) -> Result<()> {
+    Cmd::new("git")
+        .args(&[
+            "branch",
+            &format!("--set-upstream-to={}/{}", remote, branch),
+            branch,
+        ])
+        .run()
+        .with_context(|| format!("Failed to make '{}' track '{}/{}'", branch, remote, branch))?;
+    Ok(())
+}
+
+/// Default branch of a remote: its `HEAD` as of the last fetch, otherwise
+/// asked from the remote itself.
+pub fn get_remote_default_branch(remote: &str) -> Result<String> {
+    let prefix = format!("refs/remotes/{}/", remote);
+    if let Ok(ref_name) = Cmd::new("git")
+        .args(&["symbolic-ref", &format!("{}HEAD", prefix)])
+        .run_and_capture_stdout()
+        && let Some(branch) = ref_name.strip_prefix(&prefix)
+    {
+        return Ok(branch.to_string());
+    }
+    let output = Cmd::new("git")
+        .args(&["ls-remote", "--symref", remote, "HEAD"])
+        .run_and_capture_stdout()
+        .with_context(|| format!("Failed to query remote '{}'", remote))?;
+    parse_symref_head(&output)
+        .ok_or_else(|| anyhow!("Could not determine the default branch of '{}'", remote))
+}
+
+/// Branch name from `git ls-remote --symref <remote> HEAD` output, whose
+/// first line is `ref: refs/heads/<branch>\tHEAD`.
+fn parse_symref_head(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let branch = line
+            .strip_prefix("ref: refs/heads/")?
+            .strip_suffix("HEAD")?;
+        Some(branch.trim_end().to_string())
+    })
+}
+
 /// Add a git remote if it doesn't exist
 pub fn add_remote(name: &str, url: &str) -> Result<()> {
     Cmd::new("git")
@@ -74,41 +179,20 @@ pub fn get_remote_url(remote: &str) -> Result<String> {
 
 /// Ensure a remote exists for a specific fork owner.
 /// Returns the name of the remote (e.g., "origin" or "fork-username").
-/// If the remote needs to be created, it constructs the URL based on the origin URL's scheme.
+/// If the remote needs to be created, its URL is built from the origin URL,
+/// keeping its scheme, host and port, following the provider's conventions.
 pub fn ensure_fork_remote(fork_owner: &str) -> Result<String> {
+    let origin_url = get_remote_url("origin")?;
+    let provider = current_provider(&origin_url);
+
     // If the fork owner is the same as the origin owner, just use origin
-    let current_owner = get_repo_owner().unwrap_or_default();
+    let current_owner = parse_owner_from_git_url(&origin_url, provider).unwrap_or_default();
     if !current_owner.is_empty() && fork_owner == current_owner {
         return Ok("origin".to_string());
     }
 
-    let remote_name = format!("fork-{}", fork_owner);
-
-    // Construct fork URL based on origin URL format, preserving host and protocol
-    let origin_url = get_remote_url("origin")?;
-    let parsed_url = GitUrl::parse(&origin_url).with_context(|| {
-        format!(
-            "Failed to parse origin URL for fork remote construction: {}",
-            origin_url
-        )
-    })?;
-
-    let host = parsed_url.host().unwrap_or("github.com");
-    let scheme = parsed_url.scheme().unwrap_or("ssh");
-
-    let provider: GenericProvider = parsed_url
-        .provider_info()
-        .with_context(|| "Failed to extract provider info from origin URL")?;
-    let repo_name = provider.repo();
-
-    let fork_url = match scheme {
-        "https" => format!("https://{}/{}/{}.git", host, fork_owner, repo_name),
-        "http" => format!("http://{}/{}/{}.git", host, fork_owner, repo_name),
-        _ => {
-            // SSH or other schemes
-            format!("git@{}:{}/{}.git", host, fork_owner, repo_name)
-        }
-    };
+    let remote_name = format!("fork-{}", fork_owner.replace('/', "-"));
+    let fork_url = forge::fork_url(provider, &origin_url, fork_owner)?;
 
     // Check if remote exists and update URL if needed
     if remote_exists(&remote_name)? {
@@ -127,42 +211,46 @@ pub fn ensure_fork_remote(fork_owner: &str) -> Result<String> {
     Ok(remote_name)
 }
 
+/// Provider of the repository in the current directory with origin `origin_url`
+fn current_provider(origin_url: &str) -> ForgeProvider {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    forge::resolve_provider(&cwd, Some(origin_url))
+}
+
 /// Parse the repository owner from a git remote URL
-/// Supports both HTTPS and SSH formats for github.com and GitHub Enterprise domains
-fn parse_owner_from_git_url(url: &str) -> Option<&str> {
-    if let Some(https_part) = url
-        .strip_prefix("https://")
-        .or_else(|| url.strip_prefix("http://"))
-    {
-        // HTTPS format: https://github.com/owner/repo.git or https://github.enterprise.com/owner/repo.git
-        https_part.split('/').nth(1)
-    } else if url.starts_with("git@") {
-        // SSH format: git@github.com:owner/repo.git or git@github.enterprise.com:owner/repo.git
-        url.split(':')
-            .nth(1)
-            .and_then(|path| path.split('/').next())
-    } else {
-        None
-    }
+/// Supports HTTPS and SSH formats on any host. GitLab owners are the full
+/// namespace (`group/subgroup`).
+fn parse_owner_from_git_url(url: &str, provider: ForgeProvider) -> Option<&str> {
+    RemoteUrl::parse(url)?.owner(provider)
 }
 
 /// Get the repository owner from the origin remote URL
 pub fn get_repo_owner() -> Result<String> {
     let url = get_remote_url("origin")?;
 
-    parse_owner_from_git_url(&url)
+    parse_owner_from_git_url(&url, current_provider(&url))
         .ok_or_else(|| anyhow!("Could not parse repository owner from origin URL: {}", url))
         .map(|s| s.to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_owner_from_git_url;
+    use super::{parse_owner_from_git_url, parse_symref_head};
+    use crate::config::ForgeProvider;
+
+    #[test]
+    fn test_parse_symref_head() {
+        let output = "ref: refs/heads/main\tHEAD\n3f2a9c1d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f\tHEAD";
+        assert_eq!(parse_symref_head(output).as_deref(), Some("main"));
+        let output = "ref: refs/heads/release/2.x\tHEAD\n";
+        assert_eq!(parse_symref_head(output).as_deref(), Some("release/2.x"));
+        assert_eq!(parse_symref_head("3f2a9c1d\tHEAD"), None);
+    }
 
     #[test]
     fn test_parse_repo_owner_https_github_com() {
         assert_eq!(
-            parse_owner_from_git_url("https://github.com/owner/repo.git"),
+            parse_owner_from_git_url("https://github.com/owner/repo.git", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -170,7 +258,7 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_https_github_com_no_git_suffix() {
         assert_eq!(
-            parse_owner_from_git_url("https://github.com/owner/repo"),
+            parse_owner_from_git_url("https://github.com/owner/repo", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -178,7 +266,7 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_http_github_com() {
         assert_eq!(
-            parse_owner_from_git_url("http://github.com/owner/repo.git"),
+            parse_owner_from_git_url("http://github.com/owner/repo.git", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -186,7 +274,7 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_ssh_github_com() {
         assert_eq!(
-            parse_owner_from_git_url("git@github.com:owner/repo.git"),
+            parse_owner_from_git_url("git@github.com:owner/repo.git", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -194,7 +282,7 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_ssh_github_com_no_git_suffix() {
         assert_eq!(
-            parse_owner_from_git_url("git@github.com:owner/repo"),
+            parse_owner_from_git_url("git@github.com:owner/repo", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -202,7 +290,10 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_https_github_enterprise() {
         assert_eq!(
-            parse_owner_from_git_url("https://github.enterprise.com/owner/repo.git"),
+            parse_owner_from_git_url(
+                "https://github.enterprise.com/owner/repo.git",
+                ForgeProvider::Github
+            ),
             Some("owner")
         );
     }
@@ -210,7 +301,10 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_ssh_github_enterprise() {
         assert_eq!(
-            parse_owner_from_git_url("git@github.enterprise.net:org/project.git"),
+            parse_owner_from_git_url(
+                "git@github.enterprise.net:org/project.git",
+                ForgeProvider::Github
+            ),
             Some("org")
         );
     }
@@ -218,7 +312,10 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_https_github_enterprise_subdomain() {
         assert_eq!(
-            parse_owner_from_git_url("https://github.company.internal/team/project.git"),
+            parse_owner_from_git_url(
+                "https://github.company.internal/team/project.git",
+                ForgeProvider::Github
+            ),
             Some("team")
         );
     }
@@ -226,7 +323,10 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_with_nested_path() {
         assert_eq!(
-            parse_owner_from_git_url("https://github.com/owner/repo/subpath"),
+            parse_owner_from_git_url(
+                "https://github.com/owner/repo/subpath",
+                ForgeProvider::Github
+            ),
             Some("owner")
         );
     }
@@ -234,23 +334,40 @@ mod tests {
     #[test]
     fn test_parse_repo_owner_ssh_with_nested_path() {
         assert_eq!(
-            parse_owner_from_git_url("git@github.com:owner/repo/subpath"),
+            parse_owner_from_git_url("git@github.com:owner/repo/subpath", ForgeProvider::Github),
             Some("owner")
         );
     }
 
+    #[test]
+    fn test_parse_repo_owner_gitlab_nested_namespace() {
+        assert_eq!(
+            parse_owner_from_git_url("git@gitlab.com:group/sub/repo.git", ForgeProvider::Gitlab),
+            Some("group/sub")
+        );
+    }
+
     #[test]
     fn test_parse_repo_owner_invalid_format() {
-        assert_eq!(parse_owner_from_git_url("not-a-valid-url"), None);
+        assert_eq!(
+            parse_owner_from_git_url("not-a-valid-url", ForgeProvider::Github),
+            None
+        );
     }
 
     #[test]
     fn test_parse_repo_owner_local_path() {
-        assert_eq!(parse_owner_from_git_url("/local/path/to/repo"), None);
+        assert_eq!(
+            parse_owner_from_git_url("/local/path/to/repo", ForgeProvider::Github),
+            None
+        );
     }
 
     #[test]
     fn test_parse_repo_owner_file_protocol() {
-        assert_eq!(parse_owner_from_git_url("file:///local/path/to/repo"), None);
+        assert_eq!(
+            parse_owner_from_git_url("file:///local/path/to/repo", ForgeProvider::Github),
+            None
+        );
     }
 }
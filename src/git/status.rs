@@ -6,7 +6,7 @@ use std::process::Command;
 use crate::cmd::Cmd;
 
 use super::GitStatus;
-use super::branch::{get_branch_base_in, get_default_branch_in};
+use super::branch::{get_branch_base_in, get_default_branch_in, parse_left_right_count};
 
 /// Check if the worktree has uncommitted changes
 pub fn has_uncommitted_changes(worktree_path: &Path) -> Result<bool> {
@@ -332,6 +332,24 @@ pub fn get_git_status(worktree_path: &Path) -> GitStatus {
         matches!(status, Ok(s) if s.code() == Some(1))
     };
 
+    // Without an upstream, porcelain reports nothing; count against the base instead
+    let (ahead, behind) = if has_upstream {
+        (ahead, behind)
+    } else {
+        Cmd::new("git")
+            .workdir(worktree_path)
+            .args(&[
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("HEAD...{}", base_ref),
+            ])
+            .run_and_capture_stdout()
+            .ok()
+            .and_then(|output| parse_left_right_count(&output))
+            .unwrap_or((0, 0))
+    };
+
     // Get diff stats (lines added/removed vs base)
     let diff_stats = get_diff_stats(worktree_path, &base_ref);
 
@@ -353,7 +371,39 @@ pub fn get_git_status(worktree_path: &Path) -> GitStatus {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_porcelain_v2_status;
+    use super::{get_git_status, parse_porcelain_v2_status};
+    use std::path::Path;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_git_status_without_upstream_counts_against_base() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["commit", "--allow-empty", "-m", "init"]);
+        git(root, &["checkout", "-b", "feature"]);
+        git(root, &["commit", "--allow-empty", "-m", "one"]);
+        git(root, &["commit", "--allow-empty", "-m", "two"]);
+        git(root, &["checkout", "main"]);
+        git(root, &["commit", "--allow-empty", "-m", "main moved"]);
+        git(root, &["checkout", "feature"]);
+
+        let status = get_git_status(root);
+        assert_eq!(status.base_branch, "main");
+        assert!(!status.has_upstream);
+        assert!(status.compares_to_base());
+        assert_eq!((status.ahead, status.behind), (2, 1));
+    }
 
     #[test]
     fn test_parse_porcelain_v2_clean_repo() {
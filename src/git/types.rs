@@ -17,12 +17,25 @@ pub struct ForkBranchSpec {
 #[error("Worktree not found: {0}")]
 pub struct WorktreeNotFound(pub String);
 
+/// Where a branch stands relative to a base branch, for branch pickers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchSummary {
+    /// Commits on the branch that aren't on the base
+    pub ahead: usize,
+    /// Commits on the base that aren't on the branch
+    pub behind: usize,
+    /// Committer time of the branch tip (UNIX seconds)
+    pub last_commit_time: u64,
+    /// First line of the tip commit's message
+    pub subject: String,
+}
+
 /// Git status information for a worktree
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GitStatus {
-    /// Commits ahead of upstream
+    /// Commits ahead of upstream (of the base branch when there is no upstream)
     pub ahead: usize,
-    /// Commits behind upstream
+    /// Commits behind upstream (the base branch when there is no upstream)
     pub behind: usize,
     /// Branch has conflicts when merging with base
     pub has_conflict: bool,
@@ -51,3 +64,15 @@ pub struct GitStatus {
     #[serde(default)]
     pub has_upstream: bool,
 }
+
+impl GitStatus {
+    /// Whether ahead/behind count against the base branch because the branch
+    /// has no upstream.
+    pub fn compares_to_base(&self) -> bool {
+        !self.has_upstream
+            && self
+                .branch
+                .as_deref()
+                .is_some_and(|branch| branch != self.base_branch)
+    }
+}
@@ -1,4 +1,6 @@
 use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::cmd::Cmd;
@@ -149,6 +151,85 @@ pub fn list_worktrees() -> Result<Vec<(PathBuf, String)>> {
     parse_worktree_list_porcelain(&list)
 }
 
+/// Parse the locked worktrees out of `git worktree list --porcelain`, with
+/// the reason each was locked for (empty when none was given)
+pub(super) fn parse_locked_worktrees(output: &str) -> HashMap<PathBuf, String> {
+    let mut locked = HashMap::new();
+    for block in output.trim().split("\n\n") {
+        let mut path: Option<PathBuf> = None;
+        let mut reason: Option<String> = None;
+
+        for line in block.lines() {
+            if let Some(p) = line.strip_prefix("worktree ") {
+                path = Some(PathBuf::from(p));
+            } else if line == "locked" {
+                reason = Some(String::new());
+            } else if let Some(r) = line.strip_prefix("locked ") {
+                reason = Some(r.to_string());
+            }
+        }
+
+        if let (Some(p), Some(r)) = (path, reason) {
+            locked.insert(p, r);
+        }
+    }
+    locked
+}
+
+/// Locked worktrees with the reason they were locked for. Git reports them
+/// even while their directory is missing, e.g. on an unplugged drive.
+pub fn list_locked_worktrees() -> Result<HashMap<PathBuf, String>> {
+    let list = Cmd::new("git")
+        .args(&["worktree", "list", "--porcelain"])
+        .run_and_capture_stdout()
+        .context("Failed to list worktrees")?;
+    Ok(parse_locked_worktrees(&list))
+}
+
+/// Lock a worktree so `git worktree prune` keeps it while it is unreachable
+pub fn lock_worktree(worktree: &Path, reason: Option<&str>) -> Result<()> {
+    let path = worktree.to_string_lossy();
+    let mut args = vec!["worktree", "lock"];
+    if let Some(reason) = reason {
+        args.extend(["--reason", reason]);
+    }
+    args.push(&path);
+    Cmd::new("git")
+        .args(&args)
+        .run()
+        .with_context(|| format!("Failed to lock worktree '{}'", worktree.display()))?;
+    Ok(())
+}
+
+/// Unlock a worktree locked with `lock_worktree`
+pub fn unlock_worktree(worktree: &Path) -> Result<()> {
+    Cmd::new("git")
+        .args(&["worktree", "unlock", &worktree.to_string_lossy()])
+        .run()
+        .with_context(|| format!("Failed to unlock worktree '{}'", worktree.display()))?;
+    Ok(())
+}
+
+/// Git directory of a linked worktree, from its `.git` file.
+pub fn linked_git_dir(worktree: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(worktree.join(".git")).ok()?;
+    let dir = PathBuf::from(content.strip_prefix("gitdir:")?.trim());
+    Some(if dir.is_relative() {
+        worktree.join(dir)
+    } else {
+        dir
+    })
+}
+
+/// Why the linked worktree containing `path` is locked (empty when no reason
+/// was given), read from its git directory without running git. `None` when
+/// it isn't locked.
+pub fn worktree_lock_reason(path: &Path) -> Option<String> {
+    let worktree = path.ancestors().find(|dir| dir.join(".git").exists())?;
+    let reason = fs::read_to_string(linked_git_dir(worktree)?.join("locked")).ok()?;
+    Some(reason.trim().to_string())
+}
+
 /// Store per-worktree metadata in git config.
 pub fn set_worktree_meta(handle: &str, key: &str, value: &str) -> Result<()> {
     Cmd::new("git")
@@ -178,6 +259,43 @@ pub fn get_worktree_meta(handle: &str, key: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+/// Remove one key of per-worktree metadata from git config.
+/// No-op if the key doesn't exist.
+pub fn unset_worktree_meta(handle: &str, key: &str) -> Result<()> {
+    if get_worktree_meta(handle, key).is_none() {
+        return Ok(());
+    }
+    Cmd::new("git")
+        .args(&[
+            "config",
+            "--local",
+            "--unset-all",
+            &format!("workmux.worktree.{}.{}", handle, key),
+        ])
+        .run()
+        .with_context(|| format!("Failed to unset worktree metadata {}.{}", handle, key))?;
+    Ok(())
+}
+
+/// Store repository-wide workmux metadata in git config.
+pub fn set_repo_meta(key: &str, value: &str) -> Result<()> {
+    Cmd::new("git")
+        .args(&["config", "--local", &format!("workmux.{}", key), value])
+        .run()
+        .with_context(|| format!("Failed to set repository metadata {}", key))?;
+    Ok(())
+}
+
+/// Retrieve repository-wide workmux metadata from git config.
+/// Returns None if the key doesn't exist.
+pub fn get_repo_meta(key: &str) -> Option<String> {
+    Cmd::new("git")
+        .args(&["config", "--local", "--get", &format!("workmux.{}", key)])
+        .run_and_capture_stdout()
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
 /// Determine the tmux mode for a worktree from git metadata.
 /// Falls back to Window mode if no metadata is found (backward compatibility).
 pub fn get_worktree_mode(handle: &str) -> MuxMode {
@@ -237,6 +355,39 @@ pub fn remove_worktree_meta(handle: &str) -> Result<()> {
     Ok(())
 }
 
+/// Move a linked worktree to a new location.
+///
+/// Uses `git worktree move` when both paths share a filesystem. Git can only
+/// rename, so across filesystems the directory is copied instead and the
+/// administrative links are fixed up with `git worktree repair`.
+pub fn move_worktree(from: &Path, to: &Path) -> Result<()> {
+    let from_str = from
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid worktree path"))?;
+    let to_str = to
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid worktree path"))?;
+
+    if crate::util::same_filesystem(from, to) {
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        Cmd::new("git")
+            .args(&["worktree", "move", from_str, to_str])
+            .run()
+            .context("Failed to move worktree")?;
+        return Ok(());
+    }
+
+    crate::util::move_dir(from, to)?;
+    Cmd::new("git")
+        .args(&["worktree", "repair", to_str])
+        .run()
+        .context("Failed to repair worktree links after move")?;
+    Ok(())
+}
+
 /// Get the main worktree root directory (not a linked worktree)
 ///
 /// For bare repositories with linked worktrees, this returns the bare repo path.
@@ -288,3 +439,77 @@ pub fn get_main_worktree_root() -> Result<PathBuf> {
         Err(anyhow!("No main worktree found"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_locked_worktrees_with_and_without_reason() {
+        let output = "\
+worktree /repo
+HEAD 1111111111111111111111111111111111111111
+branch refs/heads/main
+
+worktree /media/usb/repo__worktrees/feat
+HEAD 2222222222222222222222222222222222222222
+branch refs/heads/feat
+locked on the USB drive
+
+worktree /repo__worktrees/spike
+HEAD 3333333333333333333333333333333333333333
+branch refs/heads/spike
+locked
+
+worktree /repo__worktrees/fix
+HEAD 4444444444444444444444444444444444444444
+branch refs/heads/fix
+prunable gitdir file points to non-existent location
+";
+        let locked = parse_locked_worktrees(output);
+        assert_eq!(locked.len(), 2);
+        assert_eq!(
+            locked[Path::new("/media/usb/repo__worktrees/feat")],
+            "on the USB drive"
+        );
+        assert_eq!(locked[Path::new("/repo__worktrees/spike")], "");
+    }
+
+    #[test]
+    fn linked_git_dir_reads_the_git_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp.path().join(".git"),
+            "gitdir: /repo/.git/worktrees/feat\n",
+        )
+        .unwrap();
+        assert_eq!(
+            linked_git_dir(temp.path()),
+            Some(PathBuf::from("/repo/.git/worktrees/feat"))
+        );
+        fs::remove_file(temp.path().join(".git")).unwrap();
+        fs::create_dir(temp.path().join(".git")).unwrap();
+        assert_eq!(linked_git_dir(temp.path()), None);
+    }
+
+    #[test]
+    fn worktree_lock_reason_reads_the_git_dir() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git_dir = temp.path().join("repo/.git/worktrees/feat");
+        let worktree = temp.path().join("feat");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::create_dir_all(worktree.join("src")).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", git_dir.display()),
+        )
+        .unwrap();
+        assert_eq!(worktree_lock_reason(&worktree), None);
+
+        fs::write(git_dir.join("locked"), "portable drive\n").unwrap();
+        assert_eq!(
+            worktree_lock_reason(&worktree.join("src")).as_deref(),
+            Some("portable drive")
+        );
+    }
+}
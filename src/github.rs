@@ -56,6 +56,9 @@ pub struct PrSummary {
     /// Aggregated check status (None if no checks configured)
     #[serde(default)]
     pub checks: Option<CheckState>,
+    /// Web page of the PR (None when the forge didn't report it)
+    #[serde(default)]
+    pub url: Option<String>,
 }
 
 /// Handles both CheckRun (status/conclusion) and StatusContext (state) from GitHub API
@@ -125,6 +128,8 @@ struct PrListResult {
     pub is_draft: bool,
     #[serde(rename = "headRepositoryOwner")]
     pub head_repository_owner: RepositoryOwner,
+    #[serde(default)]
+    pub url: Option<String>,
 }
 
 /// Find a PR by its head ref (e.g., "owner:branch" format).
@@ -141,7 +146,7 @@ pub fn find_pr_by_head_ref(owner: &str, branch: &str) -> Result<Option<PrSummary
             "--state",
             "all", // Include closed/merged PRs
             "--json",
-            "number,title,state,isDraft,headRepositoryOwner",
+            "number,title,state,isDraft,headRepositoryOwner,url",
             "--limit",
             "50", // Get enough results to handle common branch names
         ])
@@ -184,6 +189,7 @@ pub fn find_pr_by_head_ref(owner: &str, branch: &str) -> Result<Option<PrSummary
         state: pr.state,
         is_draft: pr.is_draft,
         checks: None,
+        url: pr.url,
     }))
 }
 
@@ -244,6 +250,8 @@ struct PrBatchItem {
     head_ref_name: String,
     #[serde(rename = "statusCheckRollup", default)]
     status_check_rollup: Vec<CheckRollupItem>,
+    #[serde(default)]
+    url: Option<String>,
 }
 
 /// Fetch all PRs for the current repository.
@@ -255,7 +263,7 @@ pub fn list_prs() -> Result<HashMap<String, PrSummary>> {
             "--state",
             "all",
             "--json",
-            "number,title,state,isDraft,headRefName,statusCheckRollup",
+            "number,title,state,isDraft,headRefName,statusCheckRollup,url",
             "--limit",
             "200",
         ])
@@ -293,6 +301,7 @@ pub fn list_prs() -> Result<HashMap<String, PrSummary>> {
                     state: pr.state,
                     is_draft: pr.is_draft,
                     checks: aggregate_checks(&pr.status_check_rollup),
+                    url: pr.url,
                 },
             )
         })
@@ -311,7 +320,7 @@ pub fn list_prs_in_repo(repo_root: &Path) -> Result<HashMap<String, PrSummary>>
             "--state",
             "all",
             "--json",
-            "number,title,state,isDraft,headRefName,statusCheckRollup",
+            "number,title,state,isDraft,headRefName,statusCheckRollup,url",
             "--limit",
             "200",
         ])
@@ -343,6 +352,7 @@ pub fn list_prs_in_repo(repo_root: &Path) -> Result<HashMap<String, PrSummary>>
                     state: pr.state,
                     is_draft: pr.is_draft,
                     checks: aggregate_checks(&pr.status_check_rollup),
+                    url: pr.url,
                 },
             )
         })
@@ -0,0 +1,88 @@
+//! Core workmux functionality, usable without the `workmux` CLI.
+//!
+//! The binary is a thin command-line layer over this crate. External tools
+//! (editor plugins, alternative UIs) can depend on it to drive the same
+//! workflows without shelling out to `workmux`.
+//!
+//! # Stable API
+//!
+//! - [`config`]: loading and merging global and project configuration
+//! - [`vcs`]: the [`vcs::Vcs`] trait with git and jj backends
+//! - [`multiplexer`]: the [`multiplexer::Multiplexer`] trait with tmux, WezTerm
+//!   and kitty backends
+//! - [`state`]: the filesystem-backed agent state store
+//! - [`workflow`]: high-level operations (create, open, merge, remove, list)
+//! - [`progress`]: the [`progress::Reporter`] workflows report progress through
+//!
+//! ```no_run
+//! use workmux_core::config::Config;
+//! use workmux_core::multiplexer::{create_backend, detect_backend};
+//! use workmux_core::{vcs, workflow};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = Config::load(None)?;
+//! let mux = create_backend(detect_backend());
+//! let vcs = vcs::detect_vcs()?;
+//! for worktree in workflow::list(&config, mux.as_ref(), vcs.as_ref(), false, &[])? {
+//!     println!("{} -> {}", worktree.branch, worktree.path.display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Workflows that change worktrees take a [`workflow::WorkflowContext`].
+//! `WorkflowContext::with_backends` builds one on your own [`vcs::Vcs`] and
+//! [`multiplexer::Multiplexer`] implementations, and its `progress` field
+//! decides where progress messages go.
+
+pub mod config;
+pub mod multiplexer;
+pub mod progress;
+pub mod state;
+pub mod vcs;
+pub mod workflow;
+
+mod agent_setup;
+mod ansi;
+mod branch_policy;
+mod claude;
+mod cli;
+mod cmd;
+mod command;
+mod filesystem;
+mod forge;
+mod git;
+mod github;
+mod llm;
+mod logger;
+mod markdown;
+mod naming;
+mod nerdfont;
+mod notify;
+mod opencode;
+mod plugin;
+mod prompt;
+mod sandbox;
+mod shell;
+mod spinner;
+mod template;
+mod util;
+
+use tracing::{error, info};
+
+/// Run the `workmux` command line. Used by the binary; not part of the API.
+#[doc(hidden)]
+pub fn run_cli() -> anyhow::Result<()> {
+    logger::init()?;
+
+    match cli::run() {
+        Ok(result) => {
+            info!("workmux finished successfully");
+            Ok(result)
+        }
+        Err(err) => {
+            error!(error = ?err, "workmux failed");
+            Err(err)
+        }
+    }
+}
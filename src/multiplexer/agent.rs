@@ -5,6 +5,7 @@
 //! implementing this trait.
 
 use std::path::Path;
+use std::time::Duration;
 
 /// Describes agent-specific behaviors for command rewriting and status handling.
 pub trait AgentProfile: Send + Sync {
@@ -43,6 +44,49 @@ pub trait AgentProfile: Send + Sync {
     fn prompt_argument(&self, prompt_path: &str) -> String {
         format!("-- \"$(cat {})\"", prompt_path)
     }
+
+    /// Format the argument passing system instructions from a file.
+    ///
+    /// Returns `None` for agents without such an option; they receive the
+    /// system instructions combined with the task in a single prompt.
+    fn system_prompt_argument(&self, _system_path: &str) -> Option<String> {
+        None
+    }
+
+    /// Keys that interrupt the agent's current turn, used to pause it while
+    /// a higher-priority agent works. Agents without keys are never paused.
+    fn pause_keys(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Input that makes an interrupted agent pick up where it left off.
+    fn resume_input(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Keys that submit typed or pasted input (tmux key names).
+    fn submit_keys(&self) -> &'static [&'static str] {
+        &["Enter"]
+    }
+
+    /// How long to wait after typing or pasting input before submitting it.
+    fn submit_delay(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Text the agent shows while it waits for a login, before it takes
+    /// input. Prompts are queued while one of these is on screen.
+    fn login_screens(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Reference to a file in the worktree, as written in a prompt.
+    ///
+    /// Agents that read files mentioned as `@path` get that syntax; others
+    /// get the plain path.
+    fn file_reference(&self, path: &str) -> String {
+        path.to_string()
+    }
 }
 
 // === Built-in Profiles ===
@@ -65,6 +109,26 @@ impl AgentProfile for ClaudeProfile {
     fn skip_permissions_flag(&self) -> Option<&'static str> {
         Some("--dangerously-skip-permissions")
     }
+
+    fn system_prompt_argument(&self, system_path: &str) -> Option<String> {
+        Some(format!("--append-system-prompt \"$(cat {})\"", system_path))
+    }
+
+    fn pause_keys(&self) -> &'static [&'static str] {
+        &["Escape"]
+    }
+
+    fn resume_input(&self) -> Option<&'static str> {
+        Some("continue")
+    }
+
+    fn login_screens(&self) -> &'static [&'static str] {
+        &["Select login method", "Paste code here if prompted"]
+    }
+
+    fn file_reference(&self, path: &str) -> String {
+        format!("@{}", path)
+    }
 }
 
 pub struct GeminiProfile;
@@ -81,6 +145,22 @@ impl AgentProfile for GeminiProfile {
     fn prompt_argument(&self, prompt_path: &str) -> String {
         format!("-i \"$(cat {})\"", prompt_path)
     }
+
+    fn pause_keys(&self) -> &'static [&'static str] {
+        &["Escape"]
+    }
+
+    fn resume_input(&self) -> Option<&'static str> {
+        Some("continue")
+    }
+
+    fn login_screens(&self) -> &'static [&'static str] {
+        &["How would you like to authenticate", "Waiting for auth"]
+    }
+
+    fn file_reference(&self, path: &str) -> String {
+        format!("@{}", path)
+    }
 }
 
 pub struct OpenCodeProfile;
@@ -97,6 +177,25 @@ impl AgentProfile for OpenCodeProfile {
     fn prompt_argument(&self, prompt_path: &str) -> String {
         format!("--prompt \"$(cat {})\"", prompt_path)
     }
+
+    /// OpenCode asks for a second Escape before interrupting
+    fn pause_keys(&self) -> &'static [&'static str] {
+        &["Escape", "Escape"]
+    }
+
+    fn resume_input(&self) -> Option<&'static str> {
+        Some("continue")
+    }
+
+    /// OpenCode drops an Enter that arrives while it is still taking in a
+    /// long paste
+    fn submit_delay(&self) -> Duration {
+        Duration::from_millis(500)
+    }
+
+    fn file_reference(&self, path: &str) -> String {
+        format!("@{}", path)
+    }
 }
 
 pub struct CodexProfile;
@@ -109,6 +208,18 @@ impl AgentProfile for CodexProfile {
     fn skip_permissions_flag(&self) -> Option<&'static str> {
         Some("--yolo")
     }
+
+    fn pause_keys(&self) -> &'static [&'static str] {
+        &["Escape"]
+    }
+
+    fn resume_input(&self) -> Option<&'static str> {
+        Some("continue")
+    }
+
+    fn login_screens(&self) -> &'static [&'static str] {
+        &["Sign in with ChatGPT"]
+    }
 }
 
 pub struct DefaultProfile;
@@ -195,6 +306,11 @@ mod tests {
             profile.skip_permissions_flag(),
             Some("--dangerously-skip-permissions")
         );
+        assert_eq!(
+            profile.system_prompt_argument("SYSTEM.md").as_deref(),
+            Some("--append-system-prompt \"$(cat SYSTEM.md)\"")
+        );
+        assert_eq!(profile.file_reference("spec.pdf"), "@spec.pdf");
     }
 
     #[test]
@@ -220,6 +336,8 @@ mod tests {
             profile.prompt_argument("PROMPT.md"),
             "--prompt \"$(cat PROMPT.md)\""
         );
+        assert_eq!(profile.submit_keys(), ["Enter"]);
+        assert_eq!(profile.submit_delay(), Duration::from_millis(500));
     }
 
     #[test]
@@ -233,6 +351,7 @@ mod tests {
             "-- \"$(cat PROMPT.md)\""
         );
         assert_eq!(profile.skip_permissions_flag(), Some("--yolo"));
+        assert_eq!(profile.file_reference("spec.pdf"), "spec.pdf");
     }
 
     #[test]
@@ -245,6 +364,8 @@ mod tests {
             profile.prompt_argument("PROMPT.md"),
             "-- \"$(cat PROMPT.md)\""
         );
+        assert!(profile.pause_keys().is_empty());
+        assert_eq!(profile.resume_input(), None);
     }
 
     // === resolve_profile tests ===
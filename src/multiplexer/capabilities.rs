@@ -0,0 +1,371 @@
+//! Detection of the command-line flags an installed agent accepts.
+//!
+//! Agent CLIs add and drop flags between versions, so a flag workmux injects
+//! (`--dangerously-skip-permissions`, `--append-system-prompt`) or one the
+//! config passes may not exist in the version on PATH. The agent is run once
+//! with `--version` and `--help`, and the long flags its help lists are
+//! cached in the state directory. Entries are keyed by the executable's path
+//! and remember its size and modification time, so an upgrade probes again.
+//!
+//! Anything that can't be probed (executable not found, no flags in the help)
+//! counts as unknown, and commands are left as they are.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use wait_timeout::ChildExt;
+
+use crate::config::{self, Config};
+use crate::state::schema::{self, StateKind};
+use crate::state::store::get_state_dir;
+
+/// How long `--version` or `--help` may take before the probe gives up.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a probe found out about an agent executable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentCapabilities {
+    /// First line of `--version` output
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Long flags listed by `--help`
+    #[serde(default)]
+    pub flags: BTreeSet<String>,
+}
+
+impl AgentCapabilities {
+    /// Whether the agent accepts `flag` (`--name` or `--name=value`), or None
+    /// when its help listed no flags to check against.
+    pub fn supports(&self, flag: &str) -> Option<bool> {
+        if self.flags.is_empty() {
+            return None;
+        }
+        let name = flag.split_once('=').map_or(flag, |(name, _)| name);
+        Some(self.flags.contains(name))
+    }
+
+    /// Long flags in `command` that the agent doesn't accept. Arguments after
+    /// a bare `--` are not flags.
+    pub fn unsupported_flags(&self, command: &str) -> Vec<String> {
+        command
+            .split_whitespace()
+            .skip(1)
+            .take_while(|token| *token != "--")
+            .filter(|token| token.starts_with("--"))
+            .filter(|token| self.supports(token) == Some(false))
+            .map(|token| token.split_once('=').map_or(token, |(name, _)| name))
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Cached probe of one executable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    /// Modification time in seconds since the epoch
+    modified: u64,
+    capabilities: AgentCapabilities,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CapabilitiesCache {
+    /// Entries by canonical executable path
+    #[serde(default)]
+    agents: BTreeMap<String, CacheEntry>,
+}
+
+fn cache_file_path() -> Result<PathBuf> {
+    let dir = get_state_dir()?.join("workmux");
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create state directory: {}", dir.display()))?;
+    Ok(dir.join("agent-capabilities.json"))
+}
+
+fn load_cache() -> CapabilitiesCache {
+    // A missing, corrupted or newer cache only means probing again
+    cache_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| schema::parse(StateKind::AgentCapabilities, &contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &CapabilitiesCache) -> Result<()> {
+    let path = cache_file_path()?;
+    let json = schema::to_json(StateKind::AgentCapabilities, cache)
+        .context("Failed to serialize agent capabilities")?;
+    fs::write(&path, json)
+        .with_context(|| format!("Failed to write cache file: {}", path.display()))
+}
+
+/// An installed executable: the path to run, and its canonical path, size
+/// and modification time to key the cache.
+struct Executable {
+    path: PathBuf,
+    canonical: PathBuf,
+    size: u64,
+    modified: u64,
+}
+
+fn find_executable(token: &str) -> Option<Executable> {
+    let path = PathBuf::from(config::resolve_executable_path(token)?);
+    let canonical = fs::canonicalize(&path).ok()?;
+    let meta = fs::metadata(&canonical).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(Executable {
+        path,
+        canonical,
+        size: meta.len(),
+        modified,
+    })
+}
+
+/// Run `exe arg` and return stdout and stderr together, since some CLIs
+/// print their help on stderr.
+fn run_probe(exe: &Path, arg: &str) -> Option<String> {
+    let mut child = Command::new(exe)
+        .arg(arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    // Read on threads so long help text can't block on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let mut stderr = child.stderr.take()?;
+    let out_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stdout.read_to_string(&mut buf);
+        buf
+    });
+    let err_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    if child.wait_timeout(PROBE_TIMEOUT).ok()?.is_none() {
+        let _ = child.kill();
+        let _ = child.wait();
+        debug!(exe = %exe.display(), arg, "capabilities:probe timed out");
+        return None;
+    }
+    let mut output = out_reader.join().unwrap_or_default();
+    output.push('\n');
+    output.push_str(&err_reader.join().unwrap_or_default());
+    Some(output)
+}
+
+/// Long flags (`--name`) mentioned in help text.
+fn parse_flags(help: &str) -> BTreeSet<String> {
+    let re =
+        regex::Regex::new(r"(?:^|[\s,\[(|])(--[A-Za-z0-9][A-Za-z0-9-]*)").expect("valid regex");
+    re.captures_iter(help)
+        .map(|caps| caps[1].trim_end_matches('-').to_string())
+        .collect()
+}
+
+/// Capabilities of the agent `agent_command` starts, probing it on first use.
+///
+/// Returns None when the executable can't be found.
+pub fn probe(agent_command: &str) -> Option<AgentCapabilities> {
+    let (token, _) = config::split_first_token(agent_command)?;
+    let exe = find_executable(token)?;
+    let key = exe.canonical.to_string_lossy().into_owned();
+
+    let mut cache = load_cache();
+    if let Some(entry) = cache.agents.get(&key)
+        && entry.size == exe.size
+        && entry.modified == exe.modified
+    {
+        return Some(entry.capabilities.clone());
+    }
+
+    let capabilities = AgentCapabilities {
+        version: run_probe(&exe.path, "--version").and_then(|output| {
+            output
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(String::from)
+        }),
+        flags: run_probe(&exe.path, "--help")
+            .map(|help| parse_flags(&help))
+            .unwrap_or_default(),
+    };
+    debug!(
+        exe = %key,
+        version = ?capabilities.version,
+        flags = capabilities.flags.len(),
+        "capabilities:probed"
+    );
+
+    cache.agents.insert(
+        key,
+        CacheEntry {
+            size: exe.size,
+            modified: exe.modified,
+            capabilities: capabilities.clone(),
+        },
+    );
+    if let Err(e) = save_cache(&cache) {
+        debug!(error = %e, "capabilities:failed to save cache");
+    }
+    Some(capabilities)
+}
+
+/// Whether the agent `agent_command` starts accepts `flag`, or None when
+/// that is unknown.
+pub fn supports(agent_command: &str, flag: &str) -> Option<bool> {
+    probe(agent_command)?.supports(flag)
+}
+
+/// Problems with the configured agent commands: flags in them the installed
+/// agent doesn't accept, and flags workmux would inject that it lacks.
+pub fn lint(config: &Config) -> Vec<String> {
+    let Some(agent) = config.agent.as_deref() else {
+        return Vec::new();
+    };
+    let Some(capabilities) = probe(agent) else {
+        return Vec::new();
+    };
+
+    let mut commands = vec![agent.to_string()];
+    for pane in config.panes.as_deref().unwrap_or_default() {
+        if let Some(command) = pane.command.as_deref()
+            && config::is_agent_command(command, agent)
+        {
+            commands.push(command.replacen("<agent>", agent, 1));
+        }
+    }
+    commands.dedup();
+
+    let version = capabilities.version.as_deref().unwrap_or(agent);
+    let mut issues = Vec::new();
+    for command in &commands {
+        for flag in capabilities.unsupported_flags(command) {
+            issues.push(format!(
+                "'{}' passes {}, which {} does not accept",
+                command, flag, version
+            ));
+        }
+    }
+
+    let profile = super::agent::resolve_profile(Some(agent));
+    if config.sandbox.is_enabled()
+        && let Some(flag) = profile.skip_permissions_flag()
+        && capabilities.supports(flag) == Some(false)
+    {
+        issues.push(format!(
+            "{} does not accept {}; sandboxed agents will ask for permissions",
+            version, flag
+        ));
+    }
+    if let Some(argument) = profile.system_prompt_argument("")
+        && let Some((flag, _)) = config::split_first_token(&argument)
+        && capabilities.supports(flag) == Some(false)
+    {
+        issues.push(format!(
+            "{} does not accept {}; system instructions are sent with the prompt",
+            version, flag
+        ));
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP: &str = "\
+Usage: claude [options] [command] [prompt]
+
+Options:
+  -d, --debug [filter]              Enable debug mode
+  --verbose                         Override verbose mode setting
+  -p, --print                       Print response and exit
+  --output-format <format>          Output format (choices: \"text\", \"json\")
+  --append-system-prompt <prompt>   Append a system prompt
+  --model=<model>                   Model for the session
+  -h, --help                        Display help for command
+";
+
+    #[test]
+    fn long_flags_are_parsed_from_help() {
+        let flags = parse_flags(HELP);
+        for flag in [
+            "--debug",
+            "--verbose",
+            "--print",
+            "--output-format",
+            "--append-system-prompt",
+            "--model",
+            "--help",
+        ] {
+            assert!(flags.contains(flag), "missing {}", flag);
+        }
+        assert!(!flags.contains("-d"));
+        assert_eq!(flags.len(), 7);
+    }
+
+    #[test]
+    fn unknown_flags_are_reported() {
+        let capabilities = AgentCapabilities {
+            version: Some("1.0.0".to_string()),
+            flags: parse_flags(HELP),
+        };
+        assert_eq!(capabilities.supports("--verbose"), Some(true));
+        assert_eq!(capabilities.supports("--model=opus"), Some(true));
+        assert_eq!(
+            capabilities.supports("--dangerously-skip-permissions"),
+            Some(false)
+        );
+        assert_eq!(
+            capabilities.unsupported_flags("claude --verbose --yolo=1 -x -- --not-a-flag"),
+            vec!["--yolo".to_string()]
+        );
+
+        // A help without flags tells nothing
+        let unknown = AgentCapabilities::default();
+        assert_eq!(unknown.supports("--verbose"), None);
+        assert!(unknown.unsupported_flags("claude --yolo").is_empty());
+    }
+
+    #[test]
+    fn cache_round_trips_through_schema() {
+        let mut cache = CapabilitiesCache::default();
+        cache.agents.insert(
+            "/usr/bin/claude".to_string(),
+            CacheEntry {
+                size: 10,
+                modified: 20,
+                capabilities: AgentCapabilities {
+                    version: Some("1.0.0".to_string()),
+                    flags: BTreeSet::from(["--verbose".to_string()]),
+                },
+            },
+        );
+        let json = schema::to_json(StateKind::AgentCapabilities, &cache).unwrap();
+        let loaded: CapabilitiesCache = schema::parse(StateKind::AgentCapabilities, &json).unwrap();
+        let entry = &loaded.agents["/usr/bin/claude"];
+        assert_eq!(entry.size, 10);
+        assert_eq!(entry.capabilities.supports("--verbose"), Some(true));
+    }
+}
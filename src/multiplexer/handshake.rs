@@ -6,12 +6,17 @@
 use anyhow::{Context, Result, anyhow};
 #[cfg(unix)]
 use nix::sys::stat::Mode;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, trace, warn};
 
 use crate::cmd::Cmd;
+use crate::config::{Config, HandshakeConfig};
+use crate::shell::ShellKind;
+
+use super::Multiplexer;
 
 /// Trait for pane handshake mechanisms.
 ///
@@ -33,11 +38,440 @@ pub trait PaneHandshake: Send {
     }
 
     /// Waits for the handshake signal, consuming the handshake object.
-    fn wait(self: Box<Self>) -> Result<()>;
+    fn wait(self: Box<Self>, settings: &HandshakeSettings) -> Result<()>;
+}
+
+/// Timing and retry settings for pane handshakes and command injection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandshakeSettings {
+    /// How long to wait for the shell to signal readiness
+    pub timeout: Duration,
+    /// Delay between readiness checks (also used between injection checks)
+    pub poll_interval: Duration,
+    /// Number of re-sends when a command does not appear to have been received
+    pub retries: u32,
+    /// Whether agent input is checked for mangling before pressing Enter
+    pub verify_input: bool,
+}
+
+impl Default for HandshakeSettings {
+    fn default() -> Self {
+        Self::from_config(&HandshakeConfig::default())
+    }
+}
+
+impl HandshakeSettings {
+    pub fn from_config(config: &HandshakeConfig) -> Self {
+        Self {
+            timeout: Duration::from_secs(config.timeout()),
+            poll_interval: Duration::from_millis(config.poll_interval_ms()),
+            retries: config.retries(),
+            verify_input: config.verify_input(),
+        }
+    }
+}
+
+/// Wait between pasted chunks unless `agent_submit` sets one.
+const DEFAULT_PASTE_CHUNK_DELAY: Duration = Duration::from_millis(100);
+
+/// Pause between submit keys, so a double Enter arrives as two keystrokes.
+const SUBMIT_KEY_GAP: Duration = Duration::from_millis(50);
+
+/// How input is submitted to an agent: its profile's defaults overlaid with
+/// the `agent_submit` config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmitSettings {
+    /// Keys pressed to submit (tmux key names)
+    pub keys: Vec<String>,
+    /// Wait after typing or pasting before pressing the keys
+    pub delay: Duration,
+    /// Paste multi-line input in chunks of this many characters
+    pub paste_chunk_size: Option<usize>,
+    /// Wait between pasted chunks
+    pub paste_chunk_delay: Duration,
+}
+
+impl SubmitSettings {
+    /// Settings for `agent` (an agent command, resolved to its profile).
+    pub fn for_agent(config: &Config, agent: Option<&str>) -> Self {
+        let profile = super::agent::resolve_profile(agent);
+        let configured = config.agent_submit_for(profile.name());
+        Self {
+            keys: configured
+                .and_then(|c| c.keys.clone())
+                .filter(|keys| !keys.is_empty())
+                .unwrap_or_else(|| {
+                    profile
+                        .submit_keys()
+                        .iter()
+                        .map(|k| k.to_string())
+                        .collect()
+                }),
+            delay: configured
+                .and_then(|c| c.delay_ms)
+                .map_or_else(|| profile.submit_delay(), Duration::from_millis),
+            paste_chunk_size: configured
+                .and_then(|c| c.paste_chunk_size)
+                .filter(|&size| size > 0),
+            paste_chunk_delay: configured
+                .and_then(|c| c.paste_chunk_delay_ms)
+                .map_or(DEFAULT_PASTE_CHUNK_DELAY, Duration::from_millis),
+        }
+    }
 }
 
-/// Timeout for waiting for pane readiness (seconds)
-const HANDSHAKE_TIMEOUT_SECS: u64 = 5;
+/// Press the submit keys once the submit delay has passed.
+pub fn submit_input<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    submit: &SubmitSettings,
+) -> Result<()> {
+    thread::sleep(submit.delay);
+    for (i, key) in submit.keys.iter().enumerate() {
+        if i > 0 {
+            thread::sleep(SUBMIT_KEY_GAP);
+        }
+        mux.send_key(pane_id, key)?;
+    }
+    Ok(())
+}
+
+/// Paste multi-line input into an agent pane and submit it.
+///
+/// With `paste_chunk_size` set, long input is pasted in pieces so agents
+/// that drop part of a large paste receive all of it.
+pub fn paste_agent_input<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    content: &str,
+    submit: &SubmitSettings,
+) -> Result<()> {
+    for (i, chunk) in paste_chunks(content, submit.paste_chunk_size)
+        .into_iter()
+        .enumerate()
+    {
+        if i > 0 {
+            thread::sleep(submit.paste_chunk_delay);
+        }
+        mux.paste(pane_id, chunk)?;
+    }
+    submit_input(mux, pane_id, submit)
+}
+
+/// Split `content` into pieces of at most `size` characters, ending each
+/// piece after a line break when one falls within the limit.
+fn paste_chunks(content: &str, size: Option<usize>) -> Vec<&str> {
+    let Some(size) = size.filter(|&size| size > 0) else {
+        return vec![content];
+    };
+    let mut chunks = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let mut end = rest.char_indices().nth(size).map_or(rest.len(), |(i, _)| i);
+        if end < rest.len()
+            && let Some(newline) = rest[..end].rfind('\n')
+        {
+            end = newline + 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// How long to let a shell process a sent command before checking for it.
+const INJECTION_SETTLE: Duration = Duration::from_millis(300);
+
+/// Minimum number of characters of the command to look for in pane content.
+const INJECTION_SNIPPET_LEN: usize = 16;
+
+/// Send a command to a freshly spawned pane, verifying it reached the shell.
+///
+/// When `settings.retries` is 0 this is a plain `send_keys`. Otherwise, after
+/// sending, the pane is checked: the command is considered received if the
+/// foreground process is no longer the shell, or if the start of the command
+/// is visible in the captured pane content. If neither holds (typically
+/// because keystrokes were swallowed by a half-initialized shell), the
+/// command is sent again, up to `retries` times.
+pub fn send_verified<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    command: &str,
+    shell: &str,
+    settings: &HandshakeSettings,
+) -> Result<()> {
+    mux.send_keys(pane_id, command)?;
+    if settings.retries == 0 {
+        return Ok(());
+    }
+
+    for attempt in 1..=settings.retries {
+        thread::sleep(INJECTION_SETTLE.max(settings.poll_interval));
+        if command_received(mux, pane_id, command, shell) {
+            return Ok(());
+        }
+        warn!(
+            pane_id,
+            attempt,
+            retries = settings.retries,
+            "handshake:command not visible in pane, re-sending"
+        );
+        mux.send_keys(pane_id, command)?;
+    }
+
+    thread::sleep(INJECTION_SETTLE.max(settings.poll_interval));
+    if !command_received(mux, pane_id, command, shell) {
+        warn!(pane_id, "handshake:could not verify command injection");
+    }
+    Ok(())
+}
+
+/// Number of trailing pane lines searched for typed agent input.
+const INPUT_TAIL_LINES: usize = 40;
+
+/// Send a single line of input to an agent pane and submit it.
+///
+/// The text is typed and then submitted with `submit`'s keys. With
+/// `settings.verify_input` the pane is captured before and after typing, and
+/// the keys are only pressed once the text is visible intact in the lines
+/// typing changed. On a mismatch (slow
+/// agents can drop or reorder keystrokes while busy) the input is erased and
+/// typed again, up to `settings.retries` times (at least once). If it never
+/// arrives intact the input is erased and an error is returned instead of
+/// submitting garbled text.
+pub fn send_agent_input<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    text: &str,
+    agent: Option<&str>,
+    settings: &HandshakeSettings,
+    submit: &SubmitSettings,
+) -> Result<()> {
+    // Claude renders `!` as a mode switch rather than as part of the input
+    let bang = super::agent::resolve_profile(agent).needs_bang_delay() && text.starts_with('!');
+    let expected = if bang { &text[1..] } else { text };
+
+    if !settings.verify_input {
+        type_input(mux, pane_id, expected, bang)?;
+        return submit_input(mux, pane_id, submit);
+    }
+
+    let attempts = settings.retries.max(1) + 1;
+    for attempt in 1..=attempts {
+        let before = mux
+            .capture_pane(pane_id, INPUT_TAIL_LINES as u16)
+            .unwrap_or_default();
+        type_input(mux, pane_id, expected, bang)?;
+        thread::sleep(INJECTION_SETTLE.max(settings.poll_interval));
+
+        if mux
+            .capture_pane(pane_id, INPUT_TAIL_LINES as u16)
+            .is_some_and(|after| input_arrived(&before, &after, expected))
+        {
+            return submit_input(mux, pane_id, submit);
+        }
+
+        warn!(pane_id, attempt, attempts, "send:input not intact in pane");
+        erase_input(mux, pane_id, text)?;
+    }
+
+    Err(anyhow!(
+        "Input to pane {} did not arrive intact after {} attempts; nothing was submitted",
+        pane_id,
+        attempts
+    ))
+}
+
+/// Type `text`, after a separate `!` when `bang` is set.
+fn type_input<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    text: &str,
+    bang: bool,
+) -> Result<()> {
+    if bang {
+        mux.send_text(pane_id, "!")?;
+        thread::sleep(Duration::from_millis(50));
+    }
+    mux.send_text(pane_id, text)
+}
+
+/// Erase typed input by sending one backspace per character, plus slack for
+/// duplicated keystrokes.
+fn erase_input<M: Multiplexer + ?Sized>(mux: &M, pane_id: &str, text: &str) -> Result<()> {
+    let count = text.chars().count() * 2;
+    mux.send_text(pane_id, &"\x7f".repeat(count))?;
+    thread::sleep(INJECTION_SETTLE);
+    Ok(())
+}
+
+/// Whether `text` appears intact in the pane lines that changed between the
+/// captures taken `before` and `after` typing it.
+///
+/// Lines already on screen before typing are left out, so text that is
+/// visible in the agent's transcript (a repeated message, a short answer
+/// like `y`) doesn't count as arrived. Agent TUIs wrap long input and draw
+/// borders around the input box, so both sides are compared with whitespace
+/// and box-drawing characters removed.
+fn input_arrived(before: &str, after: &str, text: &str) -> bool {
+    fn normalize(s: &str) -> String {
+        s.chars()
+            .filter(|c| !c.is_whitespace() && !('\u{2500}'..='\u{257F}').contains(c))
+            .collect()
+    }
+
+    let before = strip_ansi_escapes::strip_str(before);
+    let mut unchanged: HashMap<&str, usize> = HashMap::new();
+    for line in before.lines() {
+        *unchanged.entry(line.trim_end()).or_default() += 1;
+    }
+
+    let after = strip_ansi_escapes::strip_str(after);
+    let lines: Vec<&str> = after.trim_end().lines().collect();
+    let changed: Vec<&str> = lines[lines.len().saturating_sub(INPUT_TAIL_LINES)..]
+        .iter()
+        .copied()
+        .filter(|line| match unchanged.get_mut(line.trim_end()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    let expected = normalize(text);
+    !expected.is_empty() && normalize(&changed.join("\n")).contains(&expected)
+}
+
+/// Check whether a sent command has reached the pane's shell.
+fn command_received<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    command: &str,
+    shell: &str,
+) -> bool {
+    if let Ok(Some(info)) = mux.get_live_pane_info(pane_id)
+        && !is_shell(&info.current_command, shell)
+    {
+        trace!(pane_id, current = %info.current_command, "handshake:foreground process changed");
+        return true;
+    }
+
+    mux.capture_pane(pane_id, 200)
+        .is_some_and(|content| content_shows_command(&content, command))
+}
+
+/// Whether a pane's foreground command is the shell (or unknown).
+pub(crate) fn is_shell(current_command: &str, shell: &str) -> bool {
+    let shell_name = shell.rsplit('/').next().unwrap_or(shell);
+    current_command.is_empty() || current_command.trim_start_matches('-') == shell_name
+}
+
+/// How long a freshly started agent gets to draw its input before it is typed
+/// into, and between warm-up lines.
+const AGENT_INPUT_SETTLE: Duration = Duration::from_millis(1500);
+
+/// Wait for an agent launched in a pane to take over from the shell.
+///
+/// Polls the pane's foreground process until it is no longer the shell, then
+/// gives the agent a moment to draw its input.
+pub fn wait_for_agent_start<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    shell: &str,
+    timeout: Duration,
+    settings: &HandshakeSettings,
+) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(Some(info)) = mux.get_live_pane_info(pane_id)
+            && !is_shell(&info.current_command, shell)
+        {
+            debug!(pane_id, current = %info.current_command, "agent_init:agent started");
+            thread::sleep(AGENT_INPUT_SETTLE);
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!(
+                "Agent in pane {} did not start within {}s; agent_init lines and prompt were not sent",
+                pane_id,
+                timeout.as_secs()
+            ));
+        }
+        thread::sleep(settings.poll_interval.max(Duration::from_millis(100)));
+    }
+}
+
+/// Type `agent_init` lines into a started agent, then its prompt.
+///
+/// Each line is submitted like `workmux send` input. A multi-line prompt is
+/// pasted. Lines are removed from `lines` as they are submitted, so after an
+/// error it holds only the ones still to send.
+pub fn send_agent_init<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    lines: &mut Vec<String>,
+    prompt: Option<&str>,
+    agent: Option<&str>,
+    settings: &HandshakeSettings,
+    submit: &SubmitSettings,
+) -> Result<()> {
+    send_in_order(
+        lines,
+        prompt,
+        |line| {
+            debug!(pane_id, line, "agent_init:send");
+            send_agent_input(mux, pane_id, line, agent, settings, submit)?;
+            thread::sleep(AGENT_INPUT_SETTLE);
+            Ok(())
+        },
+        |prompt| {
+            if prompt.contains('\n') {
+                paste_agent_input(mux, pane_id, prompt, submit)
+            } else {
+                send_agent_input(mux, pane_id, prompt, agent, settings, submit)
+            }
+        },
+    )
+}
+
+/// Send each of `lines`, then a non-empty `prompt`, dropping lines from
+/// `lines` once they are sent.
+fn send_in_order(
+    lines: &mut Vec<String>,
+    prompt: Option<&str>,
+    mut send_line: impl FnMut(&str) -> Result<()>,
+    send_prompt: impl FnOnce(&str) -> Result<()>,
+) -> Result<()> {
+    while let Some(line) = lines.first() {
+        send_line(line)?;
+        lines.remove(0);
+    }
+    match prompt {
+        Some(prompt) if !prompt.is_empty() => send_prompt(prompt),
+        _ => Ok(()),
+    }
+}
+
+/// Whether captured pane content contains the start of `command`.
+fn content_shows_command(content: &str, command: &str) -> bool {
+    let snippet: String = command.trim().chars().take(INJECTION_SNIPPET_LEN).collect();
+    !snippet.is_empty() && content.contains(&snippet)
+}
+
+/// End of a handshake script: replace the script's `sh` with the user's
+/// shell, as a login shell if it takes the usual flag.
+///
+/// The script always runs in `sh`, so the path is quoted for POSIX whatever
+/// the user's shell is.
+fn exec_shell(shell: &str) -> String {
+    let quoted = ShellKind::Posix.quote(shell);
+    match ShellKind::detect(shell).login_flag() {
+        Some(flag) => format!("exec {} {}", quoted, flag),
+        None => format!("exec {}", quoted),
+    }
+}
 
 /// Manages the tmux wait-for handshake protocol for pane synchronization.
 ///
@@ -89,17 +523,17 @@ impl PaneHandshake for TmuxHandshake {
     /// (`'\''`) doesn't work reliably when nushell parses the command before passing
     /// it to sh.
     fn wrapper_command(&self, shell: &str) -> String {
-        let escaped_shell = super::util::escape_for_sh_c_inner_single_quote(shell);
         format!(
-            "sh -c \"stty -echo 2>/dev/null; tmux wait-for -U {}; stty echo 2>/dev/null; exec '{}' -l\"",
-            self.channel, escaped_shell
+            "sh -c \"{}\"",
+            super::util::escape_for_double_quotes(&self.script_content(shell))
         )
     }
 
     fn script_content(&self, shell: &str) -> String {
         format!(
-            "stty -echo 2>/dev/null; tmux wait-for -U {}; stty echo 2>/dev/null; exec '{}' -l",
-            self.channel, shell
+            "stty -echo 2>/dev/null; tmux wait-for -U {}; stty echo 2>/dev/null; {}",
+            self.channel,
+            exec_shell(shell)
         )
     }
 
@@ -108,7 +542,7 @@ impl PaneHandshake for TmuxHandshake {
     /// This method consumes the handshake to ensure cleanup happens exactly once.
     /// Uses a polling loop with timeout to prevent indefinite hangs if the pane
     /// fails to start.
-    fn wait(self: Box<Self>) -> Result<()> {
+    fn wait(self: Box<Self>, settings: &HandshakeSettings) -> Result<()> {
         debug!(channel = %self.channel, "tmux:handshake start");
 
         let mut child = std::process::Command::new("tmux")
@@ -119,7 +553,7 @@ impl PaneHandshake for TmuxHandshake {
             .context("Failed to spawn tmux wait-for command")?;
 
         let start = Instant::now();
-        let timeout = Duration::from_secs(HANDSHAKE_TIMEOUT_SECS);
+        let timeout = settings.timeout;
 
         loop {
             match child.try_wait() {
@@ -155,12 +589,13 @@ impl PaneHandshake for TmuxHandshake {
 
                         warn!(
                             channel = %self.channel,
-                            timeout_secs = HANDSHAKE_TIMEOUT_SECS,
+                            timeout_secs = timeout.as_secs(),
                             "tmux:handshake timeout"
                         );
                         return Err(anyhow!(
-                            "Pane handshake timed out after {}s - shell may have failed to start",
-                            HANDSHAKE_TIMEOUT_SECS
+                            "Pane handshake timed out after {}s - shell may have failed to start \
+                             (increase handshake.timeout on slow machines)",
+                            timeout.as_secs()
                         ));
                     }
                     trace!(
@@ -168,7 +603,7 @@ impl PaneHandshake for TmuxHandshake {
                         elapsed_ms = start.elapsed().as_millis(),
                         "tmux:handshake waiting"
                     );
-                    thread::sleep(Duration::from_millis(50));
+                    thread::sleep(settings.poll_interval);
                 }
                 Err(e) => {
                     let _ = child.kill();
@@ -218,28 +653,24 @@ impl UnixPipeHandshake {
 #[cfg(unix)]
 impl PaneHandshake for UnixPipeHandshake {
     fn wrapper_command(&self, shell: &str) -> String {
-        let escaped_shell = super::util::escape_for_sh_c_inner_single_quote(shell);
         format!(
-            "sh -c 'echo ready > {}; exec '\\''{}'\\'' -l'",
-            self.pipe_path.display(),
-            escaped_shell
+            "sh -c {}",
+            ShellKind::Posix.quote(&self.script_content(shell))
         )
     }
 
     fn script_content(&self, shell: &str) -> String {
         format!(
-            "echo ready > {}; exec '{}' -l",
+            "echo ready > {}; {}",
             self.pipe_path.display(),
-            shell
+            exec_shell(shell)
         )
     }
 
-    fn wait(self: Box<Self>) -> Result<()> {
+    fn wait(self: Box<Self>, settings: &HandshakeSettings) -> Result<()> {
         use std::os::unix::fs::OpenOptionsExt;
         use std::os::unix::io::AsRawFd;
 
-        const POLL_INTERVAL_MS: u64 = 50;
-
         // Open pipe for reading (non-blocking)
         let file = std::fs::OpenOptions::new()
             .read(true)
@@ -249,7 +680,7 @@ impl PaneHandshake for UnixPipeHandshake {
 
         let fd = file.as_raw_fd();
         let start = Instant::now();
-        let timeout = Duration::from_secs(HANDSHAKE_TIMEOUT_SECS);
+        let timeout = settings.timeout;
 
         loop {
             // Check if data available via poll()
@@ -259,7 +690,7 @@ impl PaneHandshake for UnixPipeHandshake {
                 revents: 0,
             };
 
-            let poll_timeout_ms = POLL_INTERVAL_MS as i32;
+            let poll_timeout_ms = settings.poll_interval.as_millis() as i32;
             let ret = unsafe { libc::poll(&mut pollfd, 1, poll_timeout_ms) };
 
             if ret > 0 && (pollfd.revents & libc::POLLIN) != 0 {
@@ -280,8 +711,9 @@ impl PaneHandshake for UnixPipeHandshake {
             if start.elapsed() >= timeout {
                 let _ = std::fs::remove_file(&self.pipe_path);
                 return Err(anyhow!(
-                    "Pane handshake timed out after {}s - shell may have failed to start",
-                    HANDSHAKE_TIMEOUT_SECS
+                    "Pane handshake timed out after {}s - shell may have failed to start \
+                     (increase handshake.timeout on slow machines)",
+                    timeout.as_secs()
                 ));
             }
 
@@ -297,3 +729,206 @@ impl Drop for UnixPipeHandshake {
         let _ = std::fs::remove_file(&self.pipe_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_defaults() {
+        let settings = HandshakeSettings::default();
+        assert_eq!(settings.timeout, Duration::from_secs(5));
+        assert_eq!(settings.poll_interval, Duration::from_millis(50));
+        assert_eq!(settings.retries, 0);
+        assert!(!settings.verify_input);
+    }
+
+    #[test]
+    fn submit_settings_overlay_profile_defaults() {
+        let config = Config::default();
+        let settings = SubmitSettings::for_agent(&config, Some("claude"));
+        assert_eq!(settings.keys, ["Enter"]);
+        assert_eq!(settings.delay, Duration::ZERO);
+        assert_eq!(settings.paste_chunk_size, None);
+        let settings = SubmitSettings::for_agent(&config, Some("opencode"));
+        assert_eq!(settings.delay, Duration::from_millis(500));
+
+        let config: Config = serde_yaml::from_str(
+            "agent_submit:\n  opencode:\n    keys: [M-Enter]\n    paste_chunk_size: 0\n  default:\n    delay_ms: 200\n    paste_chunk_size: 1000\n",
+        )
+        .unwrap();
+        let settings = SubmitSettings::for_agent(&config, Some("opencode"));
+        assert_eq!(settings.keys, ["M-Enter"]);
+        assert_eq!(settings.delay, Duration::from_millis(500));
+        assert_eq!(settings.paste_chunk_size, None);
+        let settings = SubmitSettings::for_agent(&config, Some("codex"));
+        assert_eq!(settings.keys, ["Enter"]);
+        assert_eq!(settings.delay, Duration::from_millis(200));
+        assert_eq!(settings.paste_chunk_size, Some(1000));
+        assert_eq!(settings.paste_chunk_delay, DEFAULT_PASTE_CHUNK_DELAY);
+    }
+
+    #[test]
+    fn paste_chunks_prefer_line_breaks() {
+        assert_eq!(paste_chunks("abc\ndef", None), ["abc\ndef"]);
+        assert_eq!(paste_chunks("abc\ndef\ngh", Some(6)), ["abc\n", "def\ngh"]);
+        assert_eq!(
+            paste_chunks("ab\ncd\nefgh", Some(5)),
+            ["ab\n", "cd\n", "efgh"]
+        );
+        assert_eq!(paste_chunks("abcdefg", Some(3)), ["abc", "def", "g"]);
+        // Splits on characters, not bytes
+        assert_eq!(paste_chunks("ééé", Some(2)), ["éé", "é"]);
+        assert!(paste_chunks("", Some(3)).is_empty());
+    }
+
+    #[test]
+    fn settings_from_config_clamps_values() {
+        let config = HandshakeConfig {
+            timeout: Some(0),
+            poll_interval_ms: Some(5000),
+            retries: Some(2),
+            ..Default::default()
+        };
+        let settings = HandshakeSettings::from_config(&config);
+        assert_eq!(settings.timeout, Duration::from_secs(1));
+        assert_eq!(settings.poll_interval, Duration::from_millis(1000));
+        assert_eq!(settings.retries, 2);
+    }
+
+    #[test]
+    fn content_shows_command_matches_prefix() {
+        let content = "~/project $  claude --dangerously-skip-permissions \"$(cat\n";
+        assert!(content_shows_command(
+            content,
+            " claude --dangerously-skip-permissions \"$(cat /tmp/prompt.md)\""
+        ));
+    }
+
+    #[test]
+    fn content_shows_command_rejects_blank_pane() {
+        assert!(!content_shows_command("\n\n", "npm run dev"));
+        assert!(!content_shows_command("anything", "   "));
+    }
+
+    #[test]
+    fn input_arrived_handles_wrapped_boxed_input() {
+        let before = "\x1b[2m╭────────────────────╮\x1b[0m\n\
+                      │ >                  │\n\
+                      ╰────────────────────╯\n\n";
+        let after = "\x1b[2m╭────────────────────╮\x1b[0m\n\
+                     │ > please commit th │\n\
+                     │   e staged changes │\n\
+                     ╰────────────────────╯\n\n";
+        assert!(input_arrived(
+            before,
+            after,
+            "please commit the staged changes"
+        ));
+    }
+
+    #[test]
+    fn input_arrived_rejects_mangled_input() {
+        let after = "│ > please comit the staged changes │\n";
+        assert!(!input_arrived(
+            "",
+            after,
+            "please commit the staged changes"
+        ));
+        assert!(!input_arrived("", after, "  "));
+    }
+
+    #[test]
+    fn input_arrived_ignores_text_already_on_screen() {
+        let before = "> fix the bug\n⏺ Fixed.\n> /model opus\n⏺ Set model to opus\n> y\n\
+                      ╭──────╮\n│ >    │\n╰──────╯\n";
+        // Keystrokes dropped: the pane is unchanged
+        for text in ["fix the bug", "/model opus", "y"] {
+            assert!(!input_arrived(before, before, text), "{}", text);
+        }
+
+        let typed = |input: &str| before.replace("│ >    │", &format!("│ > {} │", input));
+        for text in ["fix the bug", "/model opus", "y"] {
+            assert!(input_arrived(before, &typed(text), text), "{}", text);
+        }
+        // A repeated plain prompt line counts once it is there twice
+        assert!(input_arrived("> y\n> \n", "> y\n> y\n", "y"));
+        assert!(!input_arrived("> y\n> \n", "> y\n> \n", "y"));
+    }
+
+    #[test]
+    fn send_in_order_keeps_only_unsent_lines() {
+        let mut lines = vec!["/model opus".to_string(), "/clear".to_string()];
+        let mut sent = Vec::new();
+        let result = send_in_order(
+            &mut lines,
+            Some("fix the bug"),
+            |line| {
+                sent.push(line.to_string());
+                Ok(())
+            },
+            |_| Err(anyhow!("prompt did not arrive")),
+        );
+        assert!(result.is_err());
+        assert_eq!(sent, ["/model opus", "/clear"]);
+        // A retry sends only the prompt
+        assert!(lines.is_empty());
+
+        let mut lines = vec!["/model opus".to_string(), "/clear".to_string()];
+        let result = send_in_order(
+            &mut lines,
+            Some("fix the bug"),
+            |line| match line {
+                "/clear" => Err(anyhow!("line did not arrive")),
+                _ => Ok(()),
+            },
+            |_| panic!("prompt sent before the init lines"),
+        );
+        assert!(result.is_err());
+        assert_eq!(lines, ["/clear"]);
+    }
+
+    fn tmux_handshake(channel: &str) -> TmuxHandshake {
+        TmuxHandshake {
+            channel: channel.to_string(),
+        }
+    }
+
+    #[test]
+    fn handshake_starts_each_shell_as_login_shell() {
+        let handshake = tmux_handshake("wm_ready_1");
+        let script = |shell| handshake.script_content(shell);
+        assert!(script("/bin/zsh").ends_with("; exec '/bin/zsh' -l"));
+        assert!(script("/usr/bin/fish").ends_with("; exec '/usr/bin/fish' -l"));
+        assert!(script("/opt/homebrew/bin/nu").ends_with("; exec '/opt/homebrew/bin/nu' -l"));
+        // Shells that may not accept -l are started plain
+        assert!(script("/usr/bin/elvish").ends_with("; exec '/usr/bin/elvish'"));
+    }
+
+    #[test]
+    fn handshake_quotes_shell_path_for_sh() {
+        let handshake = tmux_handshake("wm_ready_1");
+        assert!(
+            handshake
+                .script_content("/home/o'brien/bin/fish")
+                .ends_with(r"exec '/home/o'\''brien/bin/fish' -l")
+        );
+        // The tmux wrapper is double-quoted so nushell and fish pass it to sh as is
+        assert_eq!(
+            handshake.wrapper_command("/usr/bin/nu"),
+            "sh -c \"stty -echo 2>/dev/null; tmux wait-for -U wm_ready_1; \
+             stty echo 2>/dev/null; exec '/usr/bin/nu' -l\""
+        );
+    }
+
+    #[test]
+    fn is_shell_matches_login_shell_and_path() {
+        assert!(is_shell("zsh", "/bin/zsh"));
+        assert!(is_shell("-zsh", "/bin/zsh"));
+        assert!(is_shell("", "/bin/zsh"));
+        assert!(!is_shell("claude", "/bin/zsh"));
+        // tmux reports the binary name of fish and nushell panes
+        assert!(is_shell("fish", "/opt/homebrew/bin/fish"));
+        assert!(is_shell("nu", "/usr/bin/nu"));
+    }
+}
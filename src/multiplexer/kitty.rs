@@ -20,6 +20,7 @@ use crate::config::SplitDirection;
 
 use super::agent;
 use super::handshake::UnixPipeHandshake;
+use super::process;
 use super::types::*;
 use super::util;
 use super::{Multiplexer, PaneHandshake};
@@ -185,7 +186,6 @@ impl KittyBackend {
     }
 
     /// Set the tab title for a window.
-    #[allow(dead_code)]
     fn set_tab_title(&self, window_id: &str, title: &str) -> Result<()> {
         self.kitten_cmd()
             .args(&[
@@ -398,6 +398,10 @@ impl Multiplexer for KittyBackend {
         Ok(())
     }
 
+    fn rename_window(&self, pane_id: &str, new_name: &str) -> Result<()> {
+        self.set_tab_title(pane_id, new_name)
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let panes = self.list_panes()?;
         let scoped_panes = self.panes_in_current_scope(&panes);
@@ -650,6 +654,20 @@ impl Multiplexer for KittyBackend {
         Ok(())
     }
 
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        self.kitten_cmd()
+            .args(&[
+                "send-text",
+                "--match",
+                &format!("id:{}", pane_id),
+                "--stdin",
+            ])
+            .stdin(text)
+            .run()
+            .context("Failed to send text to pane")?;
+        Ok(())
+    }
+
     fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
         if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
             // Send ! first
@@ -662,73 +680,49 @@ impl Multiplexer for KittyBackend {
             thread::sleep(Duration::from_millis(50));
 
             // Send the rest of the command
-            self.kitten_cmd()
-                .args(&[
-                    "send-text",
-                    "--match",
-                    &format!("id:{}", pane_id),
-                    &command[1..],
-                ])
-                .run()
-                .context("Failed to send keys to pane")?;
-
-            // Send Enter
-            self.kitten_cmd()
-                .args(&["send-text", "--match", &format!("id:{}", pane_id), "\r"])
-                .run()
-                .context("Failed to send Enter key to pane")?;
-
-            Ok(())
+            self.send_text(pane_id, &command[1..])?;
         } else {
-            self.send_keys(pane_id, command)
+            self.send_text(pane_id, command)?;
         }
+
+        // Send Enter
+        self.kitten_cmd()
+            .args(&["send-text", "--match", &format!("id:{}", pane_id), "\r"])
+            .run()
+            .context("Failed to send Enter key to pane")?;
+
+        Ok(())
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
         // Translate tmux key names to ANSI escape sequences for kitty.
         // The dashboard sends tmux-style names like "BSpace", "Enter", etc.
-        let translated = match key {
-            "BSpace" => "\x7f",
-            "Enter" => "\r",
-            "Tab" => "\t",
-            "Up" => "\x1b[A",
-            "Down" => "\x1b[B",
-            "Right" => "\x1b[C",
-            "Left" => "\x1b[D",
-            "Escape" => "\x1b",
-            _ => key,
-        };
+        let translated = util::key_sequence(key);
         self.kitten_cmd()
             .args(&[
                 "send-text",
                 "--match",
                 &format!("id:{}", pane_id),
-                translated,
+                &translated,
             ])
             .run()
             .context("Failed to send key to pane")?;
         Ok(())
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
-        // Use bracketed paste mode
+    fn paste(&self, pane_id: &str, content: &str) -> Result<()> {
+        // Use bracketed paste mode, reading the content from stdin
         self.kitten_cmd()
             .args(&[
                 "send-text",
                 "--match",
                 &format!("id:{}", pane_id),
-                "--bracketed-paste",
-                content,
+                "--bracketed-paste=enable",
+                "--stdin",
             ])
+            .stdin(content)
             .run()
             .context("Failed to paste content to pane")?;
-
-        // Send Enter to submit
-        self.kitten_cmd()
-            .args(&["send-text", "--match", &format!("id:{}", pane_id), "\r"])
-            .run()
-            .context("Failed to send Enter after paste")?;
-
         Ok(())
     }
 
@@ -829,20 +823,25 @@ impl Multiplexer for KittyBackend {
         let pane = panes.into_iter().find(|p| p.window_id == pane_id_num);
 
         match pane {
-            Some(p) => Ok(Some(LivePaneInfo {
-                pid: p.foreground_pid.unwrap_or(p.pid),
-                current_command: p
-                    .foreground_command
-                    .unwrap_or_else(|| "unknown".to_string()),
-                working_dir: p.cwd,
-                title: if p.title.is_empty() {
-                    None
-                } else {
-                    Some(p.title)
-                },
-                session: Some(format!("os-window-{}", p.os_window_id)),
-                window: Some(p.tab_title),
-            })),
+            Some(p) => {
+                let mut info = LivePaneInfo {
+                    pid: p.foreground_pid.unwrap_or(p.pid),
+                    current_command: p
+                        .foreground_command
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    working_dir: p.cwd,
+                    title: if p.title.is_empty() {
+                        None
+                    } else {
+                        Some(p.title)
+                    },
+                    session: Some(format!("os-window-{}", p.os_window_id)),
+                    window: Some(p.tab_title),
+                    process: None,
+                };
+                process::sample([&mut info]);
+                Ok(Some(info))
+            }
             None => Ok(None),
         }
     }
@@ -868,10 +867,12 @@ impl Multiplexer for KittyBackend {
                     },
                     session: Some(format!("os-window-{}", p.os_window_id)),
                     window: Some(p.tab_title),
+                    process: None,
                 },
             );
         }
 
+        process::sample(result.values_mut());
         Ok(result)
     }
 
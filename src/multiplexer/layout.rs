@@ -0,0 +1,378 @@
+//! Pane geometry of a pane layout.
+//!
+//! Mirrors how tmux divides a pane on `split-window`: the new pane goes to
+//! the right of (horizontal) or below (vertical) the pane it splits and takes
+//! `size` cells or `percentage` percent of it (half by default), with one
+//! cell in between for the border. [`fit`] uses this to keep every pane at a
+//! usable size in the window it is created in, and `workmux layout preview`
+//! to draw a layout before creating anything.
+
+use anyhow::{Result, bail};
+
+use crate::config::{Config, PaneConfig, SplitDirection};
+
+/// Position and size of a pane, in cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    /// Cells across in the direction of a split.
+    fn extent(&self, direction: &SplitDirection) -> u16 {
+        match direction {
+            SplitDirection::Horizontal => self.width,
+            SplitDirection::Vertical => self.height,
+        }
+    }
+}
+
+/// Smallest size panes are kept at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl MinSize {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            width: config.min_pane_width(),
+            height: config.min_pane_height(),
+        }
+    }
+
+    fn along(&self, direction: &SplitDirection) -> u16 {
+        match direction {
+            SplitDirection::Horizontal => self.width,
+            SplitDirection::Vertical => self.height,
+        }
+    }
+}
+
+/// Name of the cells counted along a split, for messages.
+pub fn unit(direction: &SplitDirection) -> &'static str {
+    match direction {
+        SplitDirection::Horizontal => "columns",
+        SplitDirection::Vertical => "lines",
+    }
+}
+
+/// Cells `pane` asks for when it splits a pane `extent` cells across.
+fn requested(pane: &PaneConfig, extent: u16) -> u16 {
+    match (pane.size, pane.percentage) {
+        (Some(size), _) => size,
+        (None, Some(percentage)) => (u32::from(extent) * u32::from(percentage) / 100) as u16,
+        (None, None) => (extent.saturating_add(1) / 2).saturating_sub(1),
+    }
+}
+
+/// Split `target`, giving the new pane `size` cells (clamped like tmux does).
+/// Returns what is left of `target` and the new pane, or `None` when `target`
+/// is too small to split.
+fn split(target: Rect, direction: &SplitDirection, size: u16) -> Option<(Rect, Rect)> {
+    let extent = target.extent(direction);
+    if extent < 3 {
+        return None;
+    }
+    let size = size.clamp(1, extent - 2);
+    let kept = extent - size - 1;
+    Some(match direction {
+        SplitDirection::Horizontal => (
+            Rect {
+                width: kept,
+                ..target
+            },
+            Rect {
+                x: target.x + kept + 1,
+                width: size,
+                ..target
+            },
+        ),
+        SplitDirection::Vertical => (
+            Rect {
+                height: kept,
+                ..target
+            },
+            Rect {
+                y: target.y + kept + 1,
+                height: size,
+                ..target
+            },
+        ),
+    })
+}
+
+/// The pane that pane `index` splits.
+fn split_target(panes: &[PaneConfig], index: usize) -> Result<(&SplitDirection, usize)> {
+    let pane = &panes[index];
+    let Some(direction) = &pane.split else {
+        bail!("Pane {} must have a 'split' direction specified.", index);
+    };
+    let target = pane.target.unwrap_or(index - 1);
+    if target >= index {
+        bail!("Pane {} has invalid target {}.", index, target);
+    }
+    Ok((direction, target))
+}
+
+/// Rectangles of `panes`, in order, in a `width` x `height` window.
+pub fn compute(panes: &[PaneConfig], width: u16, height: u16) -> Result<Vec<Rect>> {
+    let mut rects = vec![Rect {
+        x: 0,
+        y: 0,
+        width,
+        height,
+    }];
+    for i in 1..panes.len() {
+        let (direction, target) = split_target(panes, i)?;
+        let extent = rects[target].extent(direction);
+        let Some((kept, new)) = split(rects[target], direction, requested(&panes[i], extent))
+        else {
+            bail!(
+                "Pane {} doesn't fit: pane {} is only {} {} across",
+                i,
+                target,
+                extent,
+                unit(direction)
+            );
+        };
+        rects[target] = kept;
+        rects.push(new);
+    }
+    Ok(rects)
+}
+
+/// A layout adjusted to the window it is created in.
+#[derive(Debug)]
+pub struct Fitted {
+    pub panes: Vec<PaneConfig>,
+    pub rects: Vec<Rect>,
+    /// Panes whose size was changed to keep panes at the minimum size
+    pub resized: Vec<usize>,
+    /// Panes that stay below the minimum size because the window is too small
+    pub cramped: Vec<usize>,
+}
+
+/// Adjust the split sizes of `panes` so that, in a `width` x `height`
+/// window, every split leaves both panes at least `min` cells. A pane that
+/// can't hold two panes of the minimum size is split evenly instead.
+pub fn fit(panes: &[PaneConfig], width: u16, height: u16, min: MinSize) -> Result<Fitted> {
+    let mut fitted = Fitted {
+        panes: panes.to_vec(),
+        rects: vec![Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }],
+        resized: Vec::new(),
+        cramped: Vec::new(),
+    };
+    for i in 1..panes.len() {
+        let (direction, target) = split_target(panes, i)?;
+        let rect = fitted.rects[target];
+        let extent = rect.extent(direction);
+        let wanted = requested(&panes[i], extent);
+        let least = min.along(direction);
+        let size = if extent > least.saturating_mul(2) {
+            wanted.clamp(least, extent - least - 1)
+        } else {
+            requested(&PaneConfig::default(), extent)
+        };
+        if size != wanted {
+            let pane = &mut fitted.panes[i];
+            pane.size = Some(size);
+            pane.percentage = None;
+            fitted.resized.push(i);
+        }
+        let Some((kept, new)) = split(rect, direction, size) else {
+            bail!(
+                "Pane {} doesn't fit: pane {} is only {} {} across",
+                i,
+                target,
+                extent,
+                unit(direction)
+            );
+        };
+        fitted.rects[target] = kept;
+        fitted.rects.push(new);
+    }
+    fitted.cramped = (0..fitted.rects.len())
+        .filter(|&i| fitted.rects[i].width < min.width || fitted.rects[i].height < min.height)
+        .collect();
+    Ok(fitted)
+}
+
+/// Draw panes as ASCII boxes in a `width` x `height` frame, with each pane's
+/// label lines in its top-left corner.
+pub fn render(rects: &[Rect], labels: &[Vec<String>], width: u16, height: u16) -> String {
+    let (width, height) = (usize::from(width), usize::from(height));
+    let mut covered = vec![vec![false; width]; height];
+    for rect in rects {
+        for row in covered
+            .iter_mut()
+            .skip(usize::from(rect.y))
+            .take(usize::from(rect.height))
+        {
+            for cell in row
+                .iter_mut()
+                .skip(usize::from(rect.x))
+                .take(usize::from(rect.width))
+            {
+                *cell = true;
+            }
+        }
+    }
+
+    // Cells outside every pane are borders, and so is the frame around them
+    let (w, h) = (width as isize, height as isize);
+    let border = |x: isize, y: isize| {
+        (-1..=w).contains(&x)
+            && (-1..=h).contains(&y)
+            && (x < 0 || y < 0 || x == w || y == h || !covered[y as usize][x as usize])
+    };
+    let mut canvas: Vec<Vec<char>> = (-1..=h)
+        .map(|y| {
+            (-1..=w)
+                .map(|x| {
+                    let vertical = border(x, y - 1) || border(x, y + 1);
+                    let horizontal = border(x - 1, y) || border(x + 1, y);
+                    match (border(x, y), vertical, horizontal) {
+                        (false, _, _) => ' ',
+                        (true, true, false) => '|',
+                        (true, false, true) => '-',
+                        (true, _, _) => '+',
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    for (rect, lines) in rects.iter().zip(labels) {
+        for (row, line) in lines.iter().take(usize::from(rect.height)).enumerate() {
+            let y = usize::from(rect.y) + row + 1;
+            for (col, ch) in line.chars().take(usize::from(rect.width)).enumerate() {
+                canvas[y][usize::from(rect.x) + col + 1] = ch;
+            }
+        }
+    }
+
+    canvas
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(split: Option<SplitDirection>) -> PaneConfig {
+        PaneConfig {
+            split,
+            ..Default::default()
+        }
+    }
+
+    const MIN: MinSize = MinSize {
+        width: 20,
+        height: 5,
+    };
+
+    #[test]
+    fn splits_like_tmux() {
+        let mut right = pane(Some(SplitDirection::Horizontal));
+        right.percentage = Some(30);
+        let mut bottom = pane(Some(SplitDirection::Vertical));
+        bottom.size = Some(5);
+        bottom.target = Some(0);
+        let rects = compute(&[pane(None), right, bottom], 100, 40).unwrap();
+
+        assert_eq!(
+            rects,
+            vec![
+                Rect {
+                    x: 0,
+                    y: 0,
+                    width: 69,
+                    height: 34
+                },
+                Rect {
+                    x: 70,
+                    y: 0,
+                    width: 30,
+                    height: 40
+                },
+                Rect {
+                    x: 0,
+                    y: 35,
+                    width: 69,
+                    height: 5
+                },
+            ]
+        );
+
+        // Without a size the new pane gets half, minus the border
+        let rects = compute(
+            &[pane(None), pane(Some(SplitDirection::Horizontal))],
+            80,
+            24,
+        );
+        assert_eq!(rects.unwrap()[1].width, 39);
+    }
+
+    #[test]
+    fn panes_that_cannot_be_split_are_reported() {
+        let panes = vec![pane(None), pane(Some(SplitDirection::Vertical))];
+        let err = compute(&panes, 80, 2).unwrap_err().to_string();
+        assert_eq!(err, "Pane 1 doesn't fit: pane 0 is only 2 lines across");
+    }
+
+    #[test]
+    fn fit_keeps_panes_at_the_minimum_size() {
+        let mut sidebar = pane(Some(SplitDirection::Horizontal));
+        sidebar.percentage = Some(10);
+        let mut wide = pane(Some(SplitDirection::Horizontal));
+        wide.size = Some(90);
+        wide.target = Some(0);
+        let panes = vec![pane(None), sidebar, wide];
+
+        let fitted = fit(&panes, 120, 40, MIN).unwrap();
+        assert_eq!(fitted.resized, vec![1, 2]);
+        assert_eq!(fitted.panes[1].size, Some(20));
+        assert_eq!(fitted.panes[1].percentage, None);
+        assert_eq!(fitted.panes[2].size, Some(78));
+        assert!(fitted.cramped.is_empty());
+        assert!(fitted.rects.iter().all(|r| r.width >= MIN.width));
+
+        // Sizes that already fit are left alone
+        let fitted = fit(&panes[..2], 400, 40, MIN).unwrap();
+        assert!(fitted.resized.is_empty());
+        assert_eq!(fitted.panes[1].percentage, Some(10));
+    }
+
+    #[test]
+    fn fit_splits_evenly_when_the_window_is_too_small() {
+        let mut small = pane(Some(SplitDirection::Vertical));
+        small.size = Some(2);
+        let fitted = fit(&[pane(None), small], 80, 7, MIN).unwrap();
+        assert_eq!(fitted.panes[1].size, Some(3));
+        assert_eq!(fitted.cramped, vec![0, 1]);
+    }
+
+    #[test]
+    fn render_draws_borders_and_labels() {
+        let panes = vec![pane(None), pane(Some(SplitDirection::Horizontal))];
+        let rects = compute(&panes, 9, 2).unwrap();
+        let labels = vec![vec!["0: vim".to_string()], vec!["1".to_string()]];
+        assert_eq!(
+            render(&rects, &labels, 9, 2),
+            "+----+----+\n|0: v|1   |\n|    |    |\n+----+----+"
+        );
+    }
+}
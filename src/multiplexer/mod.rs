@@ -4,27 +4,33 @@
 //! with different terminal multiplexers (tmux, WezTerm) interchangeably.
 
 pub mod agent;
+pub mod capabilities;
 pub mod handle;
 pub mod handshake;
 pub mod kitty;
+pub mod layout;
+pub mod process;
 pub mod tmux;
 pub mod types;
 pub mod util;
 pub mod wezterm;
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 pub use handle::MuxHandle;
-pub use handshake::PaneHandshake;
+pub use handshake::{HandshakeSettings, PaneHandshake};
 pub use tmux::TmuxBackend;
 pub use types::*;
 
 use crate::config::{Config, PaneConfig, SplitDirection};
 
+/// Seconds an agent pane gets to start before `agent_init` lines are given up on.
+const AGENT_START_TIMEOUT_SECS: u64 = 30;
+
 /// Main trait for terminal multiplexer backends.
 ///
 /// Implementations must be Send + Sync to allow sharing via Arc<dyn Multiplexer>.
@@ -45,6 +51,12 @@ pub trait Multiplexer: Send + Sync {
     /// where the env var may be stale or missing.
     fn active_pane_id(&self) -> Option<String>;
 
+    /// Whether this process runs in a popup over the client (such as the
+    /// dashboard's) rather than in a pane. Default: false.
+    fn in_popup(&self) -> bool {
+        false
+    }
+
     /// Get the working directory of the active pane in the current client's session
     fn get_client_active_pane_path(&self) -> Result<PathBuf>;
 
@@ -83,6 +95,33 @@ pub trait Multiplexer: Send + Sync {
     /// Kill a window by its full name (including prefix)
     fn kill_window(&self, full_name: &str) -> Result<()>;
 
+    /// Rename the window (tab) containing a pane.
+    ///
+    /// Targets a pane rather than a name since names may be ambiguous.
+    fn rename_window(&self, pane_id: &str, new_name: &str) -> Result<()>;
+
+    /// Rename a session by its full name. Default: unsupported.
+    fn rename_session(&self, _full_name: &str, _new_name: &str) -> Result<()> {
+        Err(anyhow!("{} does not support sessions", self.name()))
+    }
+
+    /// Move the window containing `pane_id` into a new session named
+    /// `session_name`, keeping its panes (and pane IDs). Default: unsupported.
+    fn move_window_to_new_session(
+        &self,
+        _pane_id: &str,
+        _session_name: &str,
+        _cwd: &Path,
+    ) -> Result<()> {
+        Err(anyhow!("{} does not support sessions", self.name()))
+    }
+
+    /// Move the window containing `pane_id` into the existing session
+    /// `session_name`. Default: unsupported.
+    fn move_window_to_session(&self, _pane_id: &str, _session_name: &str) -> Result<()> {
+        Err(anyhow!("{} does not support sessions", self.name()))
+    }
+
     /// Schedule a window to close after a delay
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()>;
 
@@ -170,19 +209,49 @@ pub trait Multiplexer: Send + Sync {
     /// Capture the content of a pane
     fn capture_pane(&self, pane_id: &str, lines: u16) -> Option<String>;
 
+    /// Capture only the scrollback added since a previous capture.
+    ///
+    /// `since` is the `history_size` returned by the previous capture of the
+    /// same pane. When it is `None`, or the backend cannot tell what changed,
+    /// up to `lines` lines of scrollback are returned with `reset` set.
+    fn capture_pane_incremental(
+        &self,
+        pane_id: &str,
+        _since: Option<usize>,
+        lines: u16,
+    ) -> Option<PaneCapture> {
+        let content = self.capture_pane(pane_id, lines)?;
+        Some(PaneCapture {
+            history: Vec::new(),
+            screen: content.lines().map(str::to_string).collect(),
+            reset: true,
+            history_size: None,
+        })
+    }
+
     // === Text I/O ===
 
     /// Send keys (command + Enter) to a pane
     fn send_keys(&self, pane_id: &str, command: &str) -> Result<()>;
 
+    /// Type literal text into a pane without pressing Enter
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()>;
+
     /// Send keys to an agent pane, with special handling for Claude's ! prefix
     fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()>;
 
     /// Send a single key to a pane
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()>;
 
-    /// Paste multiline content to a pane (using bracketed paste)
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()>;
+    /// Paste content to a pane using bracketed paste, without submitting it
+    fn paste(&self, pane_id: &str, content: &str) -> Result<()>;
+
+    /// Paste multiline content to a pane (using bracketed paste) and press
+    /// Enter
+    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
+        self.paste(pane_id, content)?;
+        self.send_key(pane_id, "Enter")
+    }
 
     // === Shell ===
 
@@ -206,6 +275,25 @@ pub trait Multiplexer: Send + Sync {
     /// Ensure the status format is configured (for backends that need it)
     fn ensure_status_format(&self, pane_id: &str) -> Result<()>;
 
+    /// Settings in the user's multiplexer config that keep status icons from
+    /// showing, for `workmux doctor`. Default: none.
+    fn status_format_issues(&self, _status_format: bool) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Set (or clear, with `None`) the aggregate status icon of a session.
+    ///
+    /// Used in session mode to surface the rollup of all agents in a worktree's
+    /// session. Default: no-op for backends without a session-level status bar.
+    fn set_session_status(&self, _session: &str, _icon: Option<&str>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Ensure the session status icon is shown next to the session name.
+    fn ensure_session_status_format(&self, _session: &str) -> Result<()> {
+        Ok(())
+    }
+
     // === Pane Setup ===
 
     /// Split a pane, returning the new pane ID.
@@ -219,6 +307,30 @@ pub trait Multiplexer: Send + Sync {
         command: Option<&str>,
     ) -> Result<String>;
 
+    /// Width and height of a pane in cells, or `None` when the backend
+    /// can't tell or can't size splits in cells.
+    fn pane_size(&self, _pane_id: &str) -> Result<Option<(u16, u16)>> {
+        Ok(None)
+    }
+
+    /// Close a single pane.
+    fn kill_pane(&self, _pane_id: &str) -> Result<()> {
+        Err(anyhow!("{} cannot close individual panes", self.name()))
+    }
+
+    /// Scrollback lines for panes created from now on in `pane_id`'s
+    /// session; `None` restores the default. Returns false when the backend
+    /// has no per-pane scrollback (it is set in the terminal's own config).
+    fn set_history_limit(&self, _pane_id: &str, _lines: Option<u32>) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Append everything `pane_id` prints from now on to `path`. Returns false
+    /// when the backend can't transcribe pane output.
+    fn log_pane_output(&self, _pane_id: &str, _path: &Path) -> Result<bool> {
+        Ok(false)
+    }
+
     /// Setup panes in a window according to configuration.
     ///
     /// Default implementation handles the full orchestration: command resolution,
@@ -237,13 +349,66 @@ pub trait Multiplexer: Send + Sync {
         if panes.is_empty() {
             return Ok(PaneSetupResult {
                 focus_pane_id: initial_pane_id.to_string(),
+                prompted_pane_ids: Vec::new(),
             });
         }
 
+        // Size the splits to the window so no pane ends up too small to use
+        let fitted;
+        let panes = match self.pane_size(initial_pane_id).ok().flatten() {
+            Some((width, height)) => {
+                let fit = layout::fit(panes, width, height, layout::MinSize::from_config(config))
+                    .with_context(|| format!("The window is only {}x{}", width, height))?;
+                if !fit.resized.is_empty() {
+                    tracing::debug!(resized = ?fit.resized, width, height, "setup_panes:fit");
+                }
+                if !fit.cramped.is_empty() {
+                    crate::progress::warn(format!(
+                        "The window ({}x{}) is too small to keep panes {:?} at \
+                         min_pane_width/min_pane_height",
+                        width, height, fit.cramped
+                    ));
+                }
+                fitted = fit.panes;
+                &fitted[..]
+            }
+            None => panes,
+        };
+
         let mut focus_pane_id: Option<String> = None;
         let mut pane_ids: Vec<String> = vec![initial_pane_id.to_string()];
+        let mut prompted_pane_ids = Vec::new();
         let effective_agent = task_agent.or(config.agent.as_deref());
         let shell = self.get_default_shell()?;
+        let handshake_settings = HandshakeSettings::from_config(&config.handshake);
+
+        // Host panes source the env they share with the sandboxed agent
+        let shared_env = match crate::sandbox::shared_env::sync(
+            &config.sandbox,
+            options.worktree_root.unwrap_or(working_dir),
+        ) {
+            Ok(env) => env,
+            Err(e) => {
+                eprintln!("Warning: failed to write sandbox.shared_env: {:#}", e);
+                None
+            }
+        };
+
+        // Transcribe a pane's output into the log directory
+        let log_pane = |pane_id: &str, index: usize| -> Result<()> {
+            let Some(dir) = options.log_dir else {
+                return Ok(());
+            };
+            let path = dir.join(format!("pane-{}.log", index));
+            if !self.log_pane_output(pane_id, &path)? {
+                eprintln!(
+                    "Warning: {} cannot transcribe pane output; ignoring log_output for pane {}",
+                    self.name(),
+                    index
+                );
+            }
+            Ok(())
+        };
 
         for (i, pane_config) in panes.iter().enumerate() {
             let is_first = i == 0;
@@ -253,6 +418,22 @@ pub trait Multiplexer: Send + Sync {
                 continue;
             }
 
+            // tmux sizes a pane's scrollback when the pane is created, so the
+            // limit is raised around creating it. The first pane already
+            // exists and is recreated under the raised limit below.
+            let raised_history = match pane_config.history_limit {
+                Some(lines) => self.set_history_limit(&pane_ids[0], Some(lines))?,
+                None => false,
+            };
+            if pane_config.history_limit.is_some() && !raised_history {
+                eprintln!(
+                    "Warning: {} sets scrollback in its own config (`scrollback_lines`); \
+                     ignoring history_limit for pane {}",
+                    self.name(),
+                    i
+                );
+            }
+
             // Resolve command: handle <agent> placeholder and prompt injection
             let adjusted_command = util::resolve_pane_command(
                 pane_config.command.as_deref(),
@@ -261,17 +442,61 @@ pub trait Multiplexer: Send + Sync {
                 working_dir,
                 effective_agent,
                 &shell,
+                options.vars,
             );
 
+            let has_command = adjusted_command.is_some();
             let pane_id = if let Some(resolved) = adjusted_command {
+                // Detect if this is an agent pane for sandbox targeting
+                let is_agent_pane = pane_config.command.as_deref().is_some_and(|cmd| {
+                    cmd == "<agent>"
+                        || agent::is_known_agent(cmd)
+                        || effective_agent.is_some_and(|a| crate::config::is_agent_command(cmd, a))
+                });
+
+                // Warm-up lines are typed once the agent runs, so the prompt is
+                // held back from the command line and sent after them. With
+                // queue_prompts the prompt is typed even without them
+                let init_lines = match &config.agent_init {
+                    Some(init) if is_agent_pane => init.lines_for(
+                        agent::resolve_profile(
+                            resolved.effective_agent.as_deref().or(effective_agent),
+                        )
+                        .name(),
+                    ),
+                    _ => &[],
+                };
+                let deferred_prompt = options.prompt_file_path.filter(|_| {
+                    resolved.prompt_injected
+                        && (!init_lines.is_empty() || config.handshake.queue_prompts())
+                });
+                let resolved = match deferred_prompt {
+                    Some(_) => util::resolve_pane_command(
+                        pane_config.command.as_deref(),
+                        options.run_commands,
+                        None,
+                        working_dir,
+                        effective_agent,
+                        &shell,
+                        options.vars,
+                    )
+                    .unwrap_or(resolved),
+                    None => resolved,
+                };
+
                 // Use per-pane agent if set, otherwise fall back to window-level agent
                 let pane_agent = resolved.effective_agent.as_deref().or(effective_agent);
 
                 // Spawn with handshake so we can send the command after shell is ready
                 let handshake = self.create_handshake()?;
-                let script = handshake.script_content(&shell);
+                let mut script = handshake.script_content(&shell);
+                if let Some(env) = &shared_env {
+                    script.insert_str(0, &env.source_prefix());
+                }
 
-                let spawned_id = if is_first {
+                let spawned_id = if is_first && raised_history {
+                    recreate_pane(self, &pane_ids[0], working_dir, Some(&script))?
+                } else if is_first {
                     self.respawn_pane(&pane_ids[0], working_dir, Some(&script))?
                 } else {
                     let direction = pane_config.split.as_ref().unwrap();
@@ -289,14 +514,12 @@ pub trait Multiplexer: Send + Sync {
                     )?
                 };
 
-                handshake.wait()?;
+                handshake.wait(&handshake_settings)?;
 
-                // Detect if this is an agent pane for sandbox targeting
-                let is_agent_pane = pane_config.command.as_deref().is_some_and(|cmd| {
-                    cmd == "<agent>"
-                        || agent::is_known_agent(cmd)
-                        || effective_agent.is_some_and(|a| crate::config::is_agent_command(cmd, a))
-                });
+                // Start the transcript before the command runs
+                if pane_config.log_output {
+                    log_pane(&spawned_id, i)?;
+                }
 
                 // Apply sandbox wrapping if enabled for this pane type
                 let final_command = if config.sandbox.is_enabled() {
@@ -308,18 +531,35 @@ pub trait Multiplexer: Send + Sync {
                         // Use worktree_root for mounting, working_dir for cwd
                         let wt_root = options.worktree_root.unwrap_or(working_dir);
 
+                        // Sandboxes run the command with sh, so start from its
+                        // POSIX form and adapt the result to the pane's shell
+                        let posix_command = util::unwrap_for_shell(&resolved.command, &shell);
+
                         // Inject skip-permissions flag for agent panes only
                         // (sandbox provides the security boundary, so permission
                         // prompts are unnecessary and break autonomous workflow)
                         let command_to_wrap = if is_agent_pane {
                             let profile = crate::multiplexer::agent::resolve_profile(pane_agent);
-                            if let Some(flag) = profile.skip_permissions_flag() {
-                                util::inject_skip_permissions_flag(&resolved.command, flag)
-                            } else {
-                                resolved.command.clone()
+                            match profile.skip_permissions_flag() {
+                                // Versions without the flag would refuse to start
+                                Some(flag)
+                                    if pane_agent.and_then(|a| capabilities::supports(a, flag))
+                                        == Some(false) =>
+                                {
+                                    crate::progress::warn(format!(
+                                        "The installed {} does not accept {}; it will ask for permissions",
+                                        profile.name(),
+                                        flag
+                                    ));
+                                    posix_command
+                                }
+                                Some(flag) => {
+                                    util::inject_skip_permissions_flag(&posix_command, flag)
+                                }
+                                None => posix_command,
                             }
                         } else {
-                            resolved.command.clone()
+                            posix_command
                         };
 
                         // Choose backend based on config
@@ -333,16 +573,21 @@ pub trait Multiplexer: Send + Sync {
                                 )
                             }
                             crate::config::SandboxBackend::Lima => {
-                                let vm_name = options.lima_vm_name.ok_or_else(|| {
-                                    anyhow!(
-                                        "Lima VM name missing despite sandbox wrap request. \
-                                         This is a bug in workmux."
-                                    )
-                                })?;
+                                let vm_name = match (options.lima_vm_name, options.lima_boot) {
+                                    (Some(vm_name), _) => vm_name.to_string(),
+                                    // Booting since before the window was created
+                                    (None, Some(boot)) => boot.wait()?,
+                                    (None, None) => {
+                                        return Err(anyhow!(
+                                            "Lima VM name missing despite sandbox wrap request. \
+                                             This is a bug in workmux."
+                                        ));
+                                    }
+                                };
                                 crate::sandbox::wrap_for_lima(
                                     &command_to_wrap,
                                     config,
-                                    vm_name,
+                                    &vm_name,
                                     working_dir,
                                 )
                             }
@@ -350,7 +595,19 @@ pub trait Multiplexer: Send + Sync {
 
                         // Fail closed: if sandbox is enabled but wrapping fails, don't fall back to unsandboxed
                         match wrap_result {
-                            Ok(wrapped) => wrapped,
+                            Ok(wrapped) => {
+                                if config.strict_quoting() {
+                                    let inner = command_to_wrap.strip_prefix(' ');
+                                    crate::shell::verify_embedded(
+                                        &wrapped,
+                                        inner.unwrap_or(&command_to_wrap),
+                                    )
+                                    .context(
+                                        "Strict quoting: sandbox wrapping broke the command",
+                                    )?;
+                                }
+                                util::wrap_for_shell(&wrapped, &shell)
+                            }
                             Err(e) => {
                                 return Err(anyhow!(
                                     "Sandbox is enabled but failed to wrap command: {}. \
@@ -366,20 +623,88 @@ pub trait Multiplexer: Send + Sync {
                     resolved.command.clone()
                 };
 
-                self.send_keys(&spawned_id, &final_command)?;
+                // Hold the command until the panes it depends on are ready.
+                // The wait runs on the host, outside any sandbox wrapping.
+                let checks: Vec<&crate::config::PaneWaitFor> = pane_config
+                    .depends_on
+                    .iter()
+                    .flatten()
+                    .filter_map(|&dep| panes.get(dep)?.wait_for.as_ref())
+                    .collect();
+                let final_command = if checks.is_empty() {
+                    final_command
+                } else {
+                    let exe = std::env::current_exe()
+                        .map(|p| p.to_string_lossy().into_owned())
+                        .unwrap_or_else(|_| "workmux".to_string());
+                    util::with_dependency_wait(&final_command, &checks, &exe, &shell)
+                };
+
+                if config.strict_quoting() {
+                    util::verify_pane_command(&final_command, &shell).with_context(|| {
+                        format!("Strict quoting: refusing to send the command of pane {}", i)
+                    })?;
+                }
+
+                handshake::send_verified(
+                    self,
+                    &spawned_id,
+                    &final_command,
+                    &shell,
+                    &handshake_settings,
+                )?;
+
+                let mut queued = false;
+                if !init_lines.is_empty() || deferred_prompt.is_some() {
+                    let mut delivery = crate::state::PromptDelivery {
+                        agent: pane_agent.map(str::to_string),
+                        init_lines: init_lines.to_vec(),
+                        prompt_file: deferred_prompt.map(|path| working_dir.join(path)),
+                    };
+                    // Dependency waits delay the agent's start
+                    let start_timeout = AGENT_START_TIMEOUT_SECS
+                        + checks.iter().map(|c| c.timeout()).max().unwrap_or(0);
+                    let delivered = handshake::wait_for_agent_start(
+                        self,
+                        &spawned_id,
+                        &shell,
+                        Duration::from_secs(start_timeout),
+                        &handshake_settings,
+                    )
+                    .and_then(|()| {
+                        crate::state::pending::deliver(self, &spawned_id, &mut delivery, config)
+                    });
+                    // Keep what didn't arrive and retry it in the background
+                    if let Err(e) = delivered {
+                        let worktree = options.worktree_root.unwrap_or(working_dir);
+                        crate::state::pending::enqueue(self, &spawned_id, worktree, delivery, &e)?;
+                        crate::progress::warn(format!(
+                            "{:#}. Queued the prompt for pane {}; it is retried in the \
+                             background, or run 'workmux send --flush {}'",
+                            e,
+                            spawned_id,
+                            worktree.file_name().unwrap_or_default().to_string_lossy()
+                        ));
+                        queued = true;
+                    }
+                }
 
                 // Set working status for agent panes with injected prompts
-                if resolved.prompt_injected
-                    && agent::resolve_profile(pane_agent).needs_auto_status()
-                {
+                let prompted = resolved.prompt_injected || deferred_prompt.is_some();
+                if prompted && !queued && agent::resolve_profile(pane_agent).needs_auto_status() {
                     let icon = config.status_icons.working();
                     if config.status_format.unwrap_or(true) {
                         let _ = self.ensure_status_format(&spawned_id);
                     }
                     let _ = self.set_status(&spawned_id, icon, false);
                 }
+                if prompted {
+                    prompted_pane_ids.push(spawned_id.clone());
+                }
 
                 spawned_id
+            } else if is_first && raised_history {
+                recreate_pane(self, &pane_ids[0], working_dir, None)?
             } else if is_first {
                 // No command for first pane - keep as-is
                 pane_ids[0].clone()
@@ -400,6 +725,13 @@ pub trait Multiplexer: Send + Sync {
                 )?
             };
 
+            if raised_history {
+                self.set_history_limit(&pane_id, None)?;
+            }
+            if pane_config.log_output && !has_command {
+                log_pane(&pane_id, i)?;
+            }
+
             if is_first {
                 pane_ids[0] = pane_id.clone();
             } else {
@@ -413,6 +745,7 @@ pub trait Multiplexer: Send + Sync {
 
         Ok(PaneSetupResult {
             focus_pane_id: focus_pane_id.unwrap_or_else(|| pane_ids[0].clone()),
+            prompted_pane_ids,
         })
     }
 
@@ -427,6 +760,11 @@ pub trait Multiplexer: Send + Sync {
         None // Default: can't determine
     }
 
+    /// Get the session the client was in before the current one, if any.
+    fn last_session(&self) -> Option<String> {
+        None
+    }
+
     /// Get all window names across ALL sessions/workspaces.
     ///
     /// Default implementation returns same as get_all_window_names() (single session).
@@ -460,6 +798,19 @@ pub trait Multiplexer: Send + Sync {
     fn get_all_live_pane_info(&self) -> Result<std::collections::HashMap<String, LivePaneInfo>>;
 }
 
+/// Replace `pane_id` with a new pane split from it, so settings that only
+/// apply to new panes (tmux `history-limit`) take effect for it.
+fn recreate_pane<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    cwd: &Path,
+    command: Option<&str>,
+) -> Result<String> {
+    let new_pane = mux.split_pane(pane_id, &SplitDirection::Vertical, cwd, None, None, command)?;
+    mux.kill_pane(pane_id)?;
+    Ok(new_pane)
+}
+
 /// Detect which backend to use based on environment.
 ///
 /// Checks `$WORKMUX_BACKEND` first for an explicit override, then auto-detects
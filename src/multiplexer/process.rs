@@ -0,0 +1,348 @@
+//! What a pane's foreground command is doing, from the process table.
+//!
+//! To the multiplexer, an agent idle at its prompt and one running a build
+//! look the same: the agent is the foreground command either way. The
+//! process table tells them apart, since the build runs as a child of the
+//! agent and uses CPU. CPU usage is measured between two queries of the same
+//! pane, so it is only known from the second query on in a process that
+//! queries repeatedly, like the dashboard.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::{LivePaneInfo, ProcessActivity};
+
+/// Shells agents run commands with, looked through to name the command.
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish", "dash"];
+
+/// Commands that do their work in a container or VM, out of sight of the
+/// process table.
+const OPAQUE: &[&str] = &["docker", "podman", "limactl", "ssh"];
+
+/// A previous query further back than this doesn't tell what the command is
+/// doing now.
+const MAX_SAMPLE_AGE: Duration = Duration::from_secs(60);
+
+/// Processes cut off when walking a process tree, in case of a cycle in a
+/// racy process listing.
+const MAX_TREE_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Process {
+    ppid: u32,
+    /// Foreground process group of the process's terminal
+    tpgid: Option<u32>,
+    name: String,
+    /// CPU time used so far
+    cpu: Duration,
+}
+
+/// CPU time of each process in a pane's tree at the previous query.
+struct Sample {
+    at: Instant,
+    cpu: HashMap<u32, Duration>,
+}
+
+/// Previous samples, by the PID of the pane's foreground command.
+static SAMPLES: LazyLock<Mutex<HashMap<u32, Sample>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Fill in `process` for each of `panes`, from one read of the process
+/// table. Left `None` when the table can't be read.
+pub fn sample<'a>(panes: impl IntoIterator<Item = &'a mut LivePaneInfo>) {
+    let Some(processes) = read_processes() else {
+        return;
+    };
+    let now = Instant::now();
+    let mut samples = SAMPLES.lock().unwrap_or_else(|e| e.into_inner());
+    for pane in panes {
+        if pane.pid == 0 {
+            continue;
+        }
+        let root = foreground(&processes, pane.pid);
+        let tree = tree_cpu(&processes, root);
+        let previous = samples
+            .get(&root)
+            .filter(|s| now.duration_since(s.at) <= MAX_SAMPLE_AGE);
+        pane.process = Some(activity(&processes, root, &tree, previous, now));
+        samples.insert(root, Sample { at: now, cpu: tree });
+    }
+    samples.retain(|pid, _| processes.contains_key(pid));
+}
+
+/// The process leading the foreground process group of `pid`'s terminal,
+/// or `pid` itself when that can't be told.
+fn foreground(processes: &HashMap<u32, Process>, pid: u32) -> u32 {
+    processes
+        .get(&pid)
+        .and_then(|p| p.tpgid)
+        .filter(|tpgid| processes.contains_key(tpgid))
+        .unwrap_or(pid)
+}
+
+/// CPU time of `root` and all its descendants.
+fn tree_cpu(processes: &HashMap<u32, Process>, root: u32) -> HashMap<u32, Duration> {
+    let mut tree = HashMap::new();
+    let mut pending = vec![root];
+    while let Some(pid) = pending.pop() {
+        let Some(process) = processes.get(&pid) else {
+            continue;
+        };
+        if tree.insert(pid, process.cpu).is_some() || tree.len() > MAX_TREE_SIZE {
+            continue;
+        }
+        pending.extend(children(processes, pid));
+    }
+    tree
+}
+
+fn children(processes: &HashMap<u32, Process>, pid: u32) -> impl Iterator<Item = u32> + '_ {
+    processes
+        .iter()
+        .filter(move |(child, p)| p.ppid == pid && **child != pid)
+        .map(|(child, _)| *child)
+}
+
+/// What the tree under `root` did since `previous`.
+fn activity(
+    processes: &HashMap<u32, Process>,
+    root: u32,
+    tree: &HashMap<u32, Duration>,
+    previous: Option<&Sample>,
+    now: Instant,
+) -> ProcessActivity {
+    let Some(previous) = previous else {
+        return ProcessActivity::default();
+    };
+    // Processes started since the previous query used all their CPU time since
+    let used = |pid: &u32| {
+        let before = previous.cpu.get(pid).copied().unwrap_or_default();
+        tree[pid].saturating_sub(before)
+    };
+    let subtree_used = |pid: u32| -> Duration { tree_cpu(processes, pid).keys().map(used).sum() };
+
+    // Follow the busiest child down, through shells wrapping the command
+    let mut busiest = root;
+    loop {
+        let next = children(processes, busiest)
+            .filter(|child| tree.contains_key(child))
+            .map(|child| (subtree_used(child), child))
+            .filter(|(used, _)| !used.is_zero())
+            .max();
+        let Some((_, child)) = next else {
+            break;
+        };
+        busiest = child;
+        if !SHELLS.contains(&processes[&child].name.as_str()) {
+            break;
+        }
+    }
+    let child = (busiest != root).then(|| processes[&busiest].name.clone());
+
+    let opaque = tree
+        .keys()
+        .any(|pid| OPAQUE.contains(&processes[pid].name.as_str()));
+    let elapsed = now.duration_since(previous.at);
+    let cpu_percent = (!opaque && !elapsed.is_zero()).then(|| {
+        let used: Duration = tree.keys().map(used).sum();
+        (used.as_secs_f64() / elapsed.as_secs_f64() * 100.0) as f32
+    });
+    ProcessActivity { child, cpu_percent }
+}
+
+#[cfg(target_os = "linux")]
+fn read_processes() -> Option<HashMap<u32, Process>> {
+    // SAFETY: sysconf only reads a configuration value
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    let ticks = u64::try_from(ticks).ok().filter(|&t| t > 0)?;
+    let mut processes = HashMap::new();
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        // Processes can exit while the table is read
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        if let Some(process) = parse_stat(&stat, ticks) {
+            processes.insert(pid, process);
+        }
+    }
+    Some(processes)
+}
+
+/// A process from `/proc/<pid>/stat`. The command name is in parentheses and
+/// may contain spaces, so fields are counted from the last `)`. CPU time
+/// includes children that exited, so short-lived compiler runs between two
+/// queries count too.
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat(stat: &str, ticks: u64) -> Option<Process> {
+    let name = stat.get(stat.find('(')? + 1..stat.rfind(')')?)?;
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let field = |i: usize| fields.get(i)?.parse::<i64>().ok();
+    let cpu_ticks: i64 = [11, 12, 13, 14]
+        .into_iter()
+        .map(field)
+        .sum::<Option<i64>>()?;
+    Some(Process {
+        ppid: u32::try_from(field(1)?).ok()?,
+        tpgid: u32::try_from(field(5)?).ok().filter(|&t| t > 0),
+        name: name.to_string(),
+        cpu: Duration::from_millis(u64::try_from(cpu_ticks).ok()? * 1000 / ticks),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_processes() -> Option<HashMap<u32, Process>> {
+    let output = crate::cmd::Cmd::new("ps")
+        .args(&["-A", "-o", "pid=,ppid=,tpgid=,time=,comm="])
+        .run_and_capture_stdout()
+        .ok()?;
+    Some(output.lines().filter_map(parse_ps_line).collect())
+}
+
+/// A `ps -o pid=,ppid=,tpgid=,time=,comm=` line. `comm` is the last column
+/// and may be a path containing spaces.
+#[cfg(any(not(target_os = "linux"), test))]
+fn parse_ps_line(line: &str) -> Option<(u32, Process)> {
+    let mut fields = line.split_whitespace();
+    let pid = fields.next()?.parse().ok()?;
+    let ppid = fields.next()?.parse().ok()?;
+    let tpgid = fields.next()?.parse::<i64>().ok()?;
+    let cpu = parse_cpu_time(fields.next()?)?;
+    let command = fields.collect::<Vec<_>>().join(" ");
+    let name = command.rsplit('/').next()?.to_string();
+    let process = Process {
+        ppid,
+        tpgid: u32::try_from(tpgid).ok().filter(|&t| t > 0),
+        name,
+        cpu,
+    };
+    Some((pid, process))
+}
+
+/// CPU time as printed by ps: `[dd-]hh:mm:ss` (Linux) or `mm:ss.cc` (macOS).
+#[cfg(any(not(target_os = "linux"), test))]
+fn parse_cpu_time(time: &str) -> Option<Duration> {
+    let (days, rest) = match time.split_once('-') {
+        Some((days, rest)) => (days.parse::<u64>().ok()?, rest),
+        None => (0, time),
+    };
+    let mut secs = 0.0;
+    for part in rest.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(Duration::from_secs_f64(days as f64 * 86400.0 + secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(ppid: u32, name: &str, cpu_ms: u64) -> Process {
+        Process {
+            ppid,
+            tpgid: None,
+            name: name.to_string(),
+            cpu: Duration::from_millis(cpu_ms),
+        }
+    }
+
+    fn sample(at: Instant, cpu: &[(u32, u64)]) -> Sample {
+        Sample {
+            at,
+            cpu: cpu
+                .iter()
+                .map(|&(pid, ms)| (pid, Duration::from_millis(ms)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn busy_children_are_named_through_shells() {
+        // zsh -> claude -> bash -> cargo, with an idle MCP server
+        let mut processes = HashMap::from([
+            (10, process(1, "zsh", 50)),
+            (20, process(10, "claude", 5000)),
+            (30, process(20, "node", 900)),
+            (40, process(20, "bash", 10)),
+            (50, process(40, "cargo", 3000)),
+        ]);
+        processes.get_mut(&10).unwrap().tpgid = Some(20);
+        let root = foreground(&processes, 10);
+        assert_eq!(root, 20);
+
+        let now = Instant::now();
+        let before = sample(
+            now - Duration::from_secs(5),
+            &[(20, 4900), (30, 900), (40, 10), (50, 500)],
+        );
+        let tree = tree_cpu(&processes, root);
+        assert_eq!(tree.len(), 4);
+        let busy = activity(&processes, root, &tree, Some(&before), now);
+        assert_eq!(busy.child.as_deref(), Some("cargo"));
+        // 100ms + 2500ms over 5s
+        assert!((busy.cpu_percent.unwrap() - 52.0).abs() < 0.1);
+        assert!(!busy.is_idle());
+
+        // At the prompt: nothing used CPU since
+        let before = sample(
+            now - Duration::from_secs(5),
+            &[(20, 5000), (30, 900), (40, 10), (50, 3000)],
+        );
+        let idle = activity(&processes, root, &tree, Some(&before), now);
+        assert_eq!(idle.child, None);
+        assert_eq!(idle.cpu_percent, Some(0.0));
+        assert!(idle.is_idle());
+
+        // Nothing to compare with on the first query
+        let first = activity(&processes, root, &tree, None, now);
+        assert_eq!(first, ProcessActivity::default());
+        assert!(!first.is_idle());
+    }
+
+    #[test]
+    fn cpu_is_unknown_for_work_in_a_container() {
+        let processes = HashMap::from([
+            (20, process(1, "workmux", 100)),
+            (30, process(20, "docker", 100)),
+        ]);
+        let now = Instant::now();
+        let before = sample(now - Duration::from_secs(5), &[(20, 100), (30, 100)]);
+        let tree = tree_cpu(&processes, 20);
+        let activity = activity(&processes, 20, &tree, Some(&before), now);
+        assert_eq!(activity.cpu_percent, None);
+        assert!(!activity.is_idle());
+    }
+
+    #[test]
+    fn process_table_lines_are_parsed() {
+        let stat = "4242 (tmux: server) S 17 4242 4242 34816 4300 4194560 \
+                    100 0 0 0 250 50 30 20 20 0 1 0";
+        let process = parse_stat(stat, 100).unwrap();
+        assert_eq!(process.name, "tmux: server");
+        assert_eq!(process.ppid, 17);
+        assert_eq!(process.tpgid, Some(4300));
+        assert_eq!(process.cpu, Duration::from_millis(3500));
+        assert_eq!(parse_stat("garbage", 100), None);
+
+        let (pid, process) =
+            parse_ps_line("  812   800   812   1:02.50 /Applications/My App/bin/node").unwrap();
+        assert_eq!(pid, 812);
+        assert_eq!(process.name, "node");
+        assert_eq!(process.cpu, Duration::from_millis(62500));
+        let (_, process) = parse_ps_line("  812   800    -1 00:00:01 sshd").unwrap();
+        assert_eq!(process.tpgid, None);
+        assert_eq!(process.cpu, Duration::from_secs(1));
+
+        assert_eq!(
+            parse_cpu_time("2-01:00:00"),
+            Some(Duration::from_secs(176400))
+        );
+        assert_eq!(parse_cpu_time("soon"), None);
+    }
+}
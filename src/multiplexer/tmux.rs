@@ -14,7 +14,7 @@ use crate::config::SplitDirection as ConfigSplitDirection;
 
 use super::handshake::TmuxHandshake;
 use super::types::*;
-use super::{Multiplexer, PaneHandshake, agent, util};
+use super::{Multiplexer, PaneHandshake, agent, process, util};
 
 /// tmux backend implementation.
 ///
@@ -46,6 +46,17 @@ impl TmuxBackend {
             .with_context(|| format!("tmux query failed: {:?}", args))
     }
 
+    /// Load `content` into a named tmux buffer via stdin. Returns the buffer name.
+    fn load_buffer(&self, content: &str) -> Result<String> {
+        let buffer = format!("workmux-{}", std::process::id());
+        Cmd::new("tmux")
+            .args(&["load-buffer", "-b", &buffer, "-"])
+            .stdin(content)
+            .run()
+            .context("Failed to load tmux buffer")?;
+        Ok(buffer)
+    }
+
     /// Get the default shell configured in tmux.
     fn get_default_shell_internal(&self) -> Result<String> {
         let output = self.tmux_query(&["show-option", "-gqv", "default-shell"])?;
@@ -62,9 +73,35 @@ impl TmuxBackend {
         self.tmux_cmd(&["run-shell", script])
     }
 
-    /// Clear the window status display (status bar icon).
+    /// Clear the window status display (status bar icon) and the pane's own status.
     fn clear_window_status_internal(&self, pane_id: &str) {
         let _ = self.tmux_cmd(&["set-option", "-uw", "-t", pane_id, "@workmux_status"]);
+        let _ = self.tmux_cmd(&["set-option", "-up", "-t", pane_id, "@workmux_pane_status"]);
+    }
+
+    /// Read a global option, keeping meaningful whitespace. None if unset or empty.
+    fn global_option(&self, option: &str) -> Option<String> {
+        Cmd::new("tmux")
+            .args(&["show-option", "-gqv", option])
+            .run()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|s| s.trim_end_matches('\n').to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Global options that decide how status icons can be shown.
+    fn status_options(&self) -> StatusOptions {
+        StatusOptions {
+            formats: USER_FORMAT_OPTIONS
+                .iter()
+                .filter_map(|option| Some((*option, self.global_option(option)?)))
+                .collect(),
+            status_bar_on: self.global_option("status").as_deref() != Some("off"),
+            pane_border_on: self
+                .global_option("pane-border-status")
+                .is_some_and(|v| v != "off"),
+        }
     }
 
     /// Updates a single tmux format option for the target window to include workmux status.
@@ -173,6 +210,11 @@ impl Multiplexer for TmuxBackend {
         std::env::var("TMUX_PANE").ok()
     }
 
+    fn in_popup(&self) -> bool {
+        // Popups get the server's TMUX but, not being panes, no TMUX_PANE
+        std::env::var_os("TMUX").is_some() && self.current_pane_id().is_none()
+    }
+
     fn active_pane_id(&self) -> Option<String> {
         self.tmux_query(&["display-message", "-p", "#{pane_id}"])
             .ok()
@@ -338,6 +380,49 @@ impl Multiplexer for TmuxBackend {
         self.tmux_cmd(&["kill-window", "-t", &target])
     }
 
+    fn rename_window(&self, pane_id: &str, new_name: &str) -> Result<()> {
+        self.tmux_cmd(&["rename-window", "-t", pane_id, new_name])
+    }
+
+    fn rename_session(&self, full_name: &str, new_name: &str) -> Result<()> {
+        let target = format!("={}", full_name);
+        self.tmux_cmd(&["rename-session", "-t", &target, new_name])
+    }
+
+    fn move_window_to_new_session(
+        &self,
+        pane_id: &str,
+        session_name: &str,
+        cwd: &Path,
+    ) -> Result<()> {
+        let working_dir_str = cwd
+            .to_str()
+            .ok_or_else(|| anyhow!("Working directory path contains non-UTF8 characters"))?;
+        // tmux sessions always start with a window; it is replaced by the
+        // moved window below
+        let placeholder = self
+            .tmux_query(&[
+                "new-session",
+                "-d",
+                "-s",
+                session_name,
+                "-c",
+                working_dir_str,
+                "-P",
+                "-F",
+                "#{window_id}",
+            ])
+            .context("Failed to create tmux session")?;
+        let target = format!("={}:", session_name);
+        self.tmux_cmd(&["move-window", "-s", pane_id, "-t", &target])?;
+        self.tmux_cmd(&["kill-window", "-t", placeholder.trim()])
+    }
+
+    fn move_window_to_session(&self, pane_id: &str, session_name: &str) -> Result<()> {
+        let target = format!("={}:", session_name);
+        self.tmux_cmd(&["move-window", "-s", pane_id, "-t", &target])
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let delay_secs = format!("{:.3}", delay.as_secs_f64());
         let target = format!("={}", full_name);
@@ -437,6 +522,13 @@ impl Multiplexer for TmuxBackend {
             .filter(|s| !s.is_empty())
     }
 
+    fn last_session(&self) -> Option<String> {
+        self.tmux_query(&["display-message", "-p", "#{client_last_session}"])
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
     fn get_all_window_names(&self) -> Result<HashSet<String>> {
         let windows = self
             .tmux_query(&["list-windows", "-F", "#{window_name}"])
@@ -594,6 +686,49 @@ impl Multiplexer for TmuxBackend {
             .ok()
     }
 
+    fn capture_pane_incremental(
+        &self,
+        pane_id: &str,
+        since: Option<usize>,
+        lines: u16,
+    ) -> Option<PaneCapture> {
+        let sizes = self
+            .tmux_query(&[
+                "display-message",
+                "-p",
+                "-t",
+                pane_id,
+                "#{history_size} #{history_limit} #{pane_height}",
+            ])
+            .ok()?;
+        let (history_size, history_limit, height) = parse_history_sizes(&sizes)?;
+
+        // Once the scrollback is full, old lines drop off as new ones arrive and
+        // history_size stops growing, so new lines can only be found by recapturing
+        let added = since
+            .filter(|&since| since <= history_size && history_size < history_limit)
+            .map(|since| history_size - since)
+            .filter(|&added| added <= lines as usize);
+        let start = format!("-{}", added.unwrap_or(lines as usize));
+
+        // Capture the raw output: trimming would drop blank lines and shift the
+        // boundary between scrollback and screen
+        let output = Cmd::new("tmux")
+            .args(&["capture-pane", "-p", "-e", "-S", &start, "-t", pane_id])
+            .run()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut history: Vec<String> = text.lines().map(str::to_string).collect();
+        let screen = history.split_off(history.len().saturating_sub(height));
+
+        Some(PaneCapture {
+            history,
+            screen,
+            reset: added.is_none(),
+            history_size: Some(history_size),
+        })
+    }
+
     // === Text I/O ===
 
     fn send_keys(&self, pane_id: &str, command: &str) -> Result<()> {
@@ -601,6 +736,13 @@ impl Multiplexer for TmuxBackend {
         self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
     }
 
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        // Typed via a buffer (without bracketed paste) so agent input stays
+        // out of argv and the command log
+        let buffer = self.load_buffer(text)?;
+        self.tmux_cmd(&["paste-buffer", "-b", &buffer, "-t", pane_id, "-d"])
+    }
+
     fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
         if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
             // Send ! first
@@ -610,43 +752,26 @@ impl Multiplexer for TmuxBackend {
             thread::sleep(Duration::from_millis(50));
 
             // Send the rest of the command
-            self.tmux_cmd(&["send-keys", "-t", pane_id, "-l", &command[1..]])?;
-
-            // Send Enter
-            self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
+            self.send_text(pane_id, &command[1..])?;
         } else {
-            self.send_keys(pane_id, command)
+            self.send_text(pane_id, command)?;
         }
-    }
 
-    fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
-        self.tmux_cmd(&["send-keys", "-t", pane_id, key])
+        // Send Enter
+        self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
-        use std::io::Write;
-
-        let mut child = std::process::Command::new("tmux")
-            .args(["load-buffer", "-"])
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn tmux load-buffer")?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin
-                .write_all(content.as_bytes())
-                .context("Failed to write to tmux buffer")?;
-        }
-
-        let status = child
-            .wait()
-            .context("Failed to wait for tmux load-buffer")?;
-        if !status.success() {
-            return Err(anyhow::anyhow!("tmux load-buffer failed"));
+    fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
+        // A trailing ';' would end the tmux command
+        match key.strip_suffix(';') {
+            Some(head) => self.tmux_cmd(&["send-keys", "-t", pane_id, &format!("{}\\;", head)]),
+            None => self.tmux_cmd(&["send-keys", "-t", pane_id, key]),
         }
+    }
 
-        self.tmux_cmd(&["paste-buffer", "-t", pane_id, "-p", "-d"])?;
-        self.tmux_cmd(&["send-keys", "-t", pane_id, "Enter"])
+    fn paste(&self, pane_id: &str, content: &str) -> Result<()> {
+        let buffer = self.load_buffer(content)?;
+        self.tmux_cmd(&["paste-buffer", "-b", &buffer, "-t", pane_id, "-p", "-d"])
     }
 
     // === Shell ===
@@ -669,14 +794,32 @@ impl Multiplexer for TmuxBackend {
         {
             eprintln!("workmux: failed to set window status: {}", e);
         }
+        // Pane-level copy for pane-border-format, which would otherwise show
+        // the window's icon on every pane of the window
+        let _ = self.tmux_cmd(&[
+            "set-option",
+            "-p",
+            "-t",
+            pane_id,
+            "@workmux_pane_status",
+            icon,
+        ]);
 
         // Set up hook to auto-clear status when window receives focus.
         // Used for "waiting" and "done" statuses so they clear once the user sees them.
+        // The icon is cleared right away; workmux clears the stored status so the
+        // dashboard stops showing it too.
         if auto_clear_on_focus {
+            let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("workmux"));
+            let clear_cmd = format!(
+                "{} set-window-status clear --pane {}",
+                crate::shell::shell_quote(&exe.to_string_lossy()),
+                pane_id
+            );
             // Only clear if status still matches this icon (avoids clearing a newer status)
             let hook_cmd = format!(
-                "if-shell -F \"#{{==:#{{@workmux_status}},{}}}\" \"set-option -uw @workmux_status\"",
-                icon
+                "if-shell -F \"#{{==:#{{@workmux_status}},{}}}\" \"set-option -uw @workmux_status ; set-option -up -t {} @workmux_pane_status ; run-shell -b '{}'\"",
+                icon, pane_id, clear_cmd
             );
             let _ = self.tmux_cmd(&["set-hook", "-w", "-t", pane_id, "pane-focus-in", &hook_cmd]);
         }
@@ -690,11 +833,58 @@ impl Multiplexer for TmuxBackend {
     }
 
     fn ensure_status_format(&self, pane_id: &str) -> Result<()> {
+        // Formats that already show the status belong to the user; injecting
+        // into the window list as well would show the icon twice
+        if self.status_options().user_status_option().is_some() {
+            return Ok(());
+        }
         self.update_format_option(pane_id, "window-status-format")?;
         self.update_format_option(pane_id, "window-status-current-format")?;
         Ok(())
     }
 
+    fn set_session_status(&self, session: &str, icon: Option<&str>) -> Result<()> {
+        // Session option mirrors the StateStore rollup for status-left display
+        let target = format!("={}", session);
+        let result = match icon {
+            Some(icon) => {
+                self.tmux_cmd(&["set-option", "-t", &target, "@workmux_session_status", icon])
+            }
+            None => self.tmux_cmd(&["set-option", "-u", "-t", &target, "@workmux_session_status"]),
+        };
+        if let Err(e) = result {
+            eprintln!("workmux: failed to set session status: {}", e);
+        }
+        Ok(())
+    }
+
+    fn ensure_session_status_format(&self, session: &str) -> Result<()> {
+        let target = format!("={}", session);
+        let read = |args: &[&str]| {
+            Cmd::new("tmux")
+                .args(args)
+                .run()
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|s| s.trim_end_matches('\n').to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let current = read(&["show-option", "-v", "-t", &target, "status-left"])
+            .or_else(|| read(&["show-option", "-gv", "status-left"]))
+            .unwrap_or_else(|| "[#S] ".to_string());
+
+        if !current.contains("@workmux_session_status")
+            && let Some(new_format) = inject_session_status_format(&current)
+        {
+            self.tmux_cmd(&["set-option", "-t", &target, "status-left", &new_format])?;
+        }
+        Ok(())
+    }
+
+    fn status_format_issues(&self, status_format: bool) -> Vec<String> {
+        self.status_options().issues(status_format)
+    }
+
     fn split_pane(
         &self,
         target_pane_id: &str,
@@ -707,6 +897,39 @@ impl Multiplexer for TmuxBackend {
         self.split_pane_internal(target_pane_id, direction, cwd, size, percentage, command)
     }
 
+    fn kill_pane(&self, pane_id: &str) -> Result<()> {
+        self.tmux_cmd(&["kill-pane", "-t", pane_id])
+    }
+
+    fn set_history_limit(&self, pane_id: &str, lines: Option<u32>) -> Result<bool> {
+        // history-limit is a session option that tmux reads when creating a
+        // pane; unsetting it falls back to the global value
+        match lines {
+            Some(lines) => self.tmux_cmd(&[
+                "set-option",
+                "-t",
+                pane_id,
+                "history-limit",
+                &lines.to_string(),
+            ])?,
+            None => self.tmux_cmd(&["set-option", "-u", "-t", pane_id, "history-limit"])?,
+        }
+        Ok(true)
+    }
+
+    fn log_pane_output(&self, pane_id: &str, path: &Path) -> Result<bool> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+        }
+        let command = format!(
+            "cat >> {}",
+            crate::shell::shell_quote(&path.to_string_lossy())
+        );
+        self.tmux_cmd(&["pipe-pane", "-o", "-t", pane_id, &command])?;
+        Ok(true)
+    }
+
     // === State Reconciliation ===
 
     fn instance_id(&self) -> String {
@@ -720,6 +943,20 @@ impl Multiplexer for TmuxBackend {
             .unwrap_or_else(|| "default".to_string())
     }
 
+    fn pane_size(&self, pane_id: &str) -> Result<Option<(u16, u16)>> {
+        let output = self.tmux_query(&[
+            "display-message",
+            "-t",
+            pane_id,
+            "-p",
+            "#{pane_width} #{pane_height}",
+        ])?;
+        Ok(output
+            .trim()
+            .split_once(' ')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?))))
+    }
+
     fn get_live_pane_info(&self, pane_id: &str) -> Result<Option<LivePaneInfo>> {
         let format = "#{pane_id}\t#{pane_pid}\t#{pane_current_command}\t#{pane_current_path}\t#{pane_title}\t#{session_name}\t#{window_name}";
 
@@ -741,7 +978,7 @@ impl Multiplexer for TmuxBackend {
             return Ok(None);
         }
 
-        Ok(Some(LivePaneInfo {
+        let mut info = LivePaneInfo {
             pid: parts[1].parse().unwrap_or(0),
             current_command: parts[2].to_string(),
             working_dir: PathBuf::from(parts[3]),
@@ -752,7 +989,10 @@ impl Multiplexer for TmuxBackend {
             },
             session: Some(parts[5].to_string()),
             window: Some(parts[6].to_string()),
-        }))
+            process: None,
+        };
+        process::sample([&mut info]);
+        Ok(Some(info))
     }
 
     fn get_all_live_pane_info(&self) -> Result<std::collections::HashMap<String, LivePaneInfo>> {
@@ -785,14 +1025,23 @@ impl Multiplexer for TmuxBackend {
                     },
                     session: Some(parts[5].to_string()),
                     window: Some(parts[6].to_string()),
+                    process: None,
                 },
             );
         }
 
+        process::sample(panes.values_mut());
         Ok(panes)
     }
 }
 
+/// Parse `#{history_size} #{history_limit} #{pane_height}` output.
+fn parse_history_sizes(output: &str) -> Option<(usize, usize, usize)> {
+    let mut fields = output.split_whitespace().map(|f| f.parse().ok());
+    let sizes = (fields.next()??, fields.next()??, fields.next()??);
+    Some(sizes)
+}
+
 /// Format string to inject into tmux window-status-format.
 const WORKMUX_STATUS_FORMAT: &str = "#{?@workmux_status, #{@workmux_status},}";
 
@@ -809,10 +1058,161 @@ fn inject_status_format(format: &str) -> String {
     }
 }
 
+/// Global format options users may reference the status variables from.
+const USER_FORMAT_OPTIONS: [&str; 5] = [
+    "window-status-format",
+    "window-status-current-format",
+    "pane-border-format",
+    "status-left",
+    "status-right",
+];
+
+/// The parts of the user's tmux configuration that status display depends on.
+#[derive(Debug, Default)]
+struct StatusOptions {
+    /// Global value of each option in `USER_FORMAT_OPTIONS` that is set
+    formats: Vec<(&'static str, String)>,
+    status_bar_on: bool,
+    pane_border_on: bool,
+}
+
+impl StatusOptions {
+    /// First format that already shows `@workmux_status` or `@workmux_pane_status`.
+    fn user_status_option(&self) -> Option<&'static str> {
+        self.formats
+            .iter()
+            .find(|(_, format)| {
+                format.contains("@workmux_status") || format.contains("@workmux_pane_status")
+            })
+            .map(|(option, _)| *option)
+    }
+
+    /// Settings that keep status icons from showing up.
+    fn issues(&self, status_format: bool) -> Vec<String> {
+        let mut issues = Vec::new();
+        if let Some(option) = self.user_status_option() {
+            if option == "pane-border-format" && !self.pane_border_on {
+                issues.push(
+                    "pane-border-format shows the agent status, but pane-border-status is off \
+                     (set it to top or bottom)"
+                        .to_string(),
+                );
+            }
+        } else if status_format && !self.status_bar_on {
+            issues.push(
+                "The tmux status bar is off, so status icons added to window-status-format \
+                 are hidden (reference #{@workmux_pane_status} in pane-border-format instead)"
+                    .to_string(),
+            );
+        } else if !status_format {
+            issues.push(
+                "status_format is false and no tmux format references #{@workmux_status}, \
+                 so status icons are not shown"
+                    .to_string(),
+            );
+        }
+        issues
+    }
+}
+
+/// Format string to inject after the session name in status-left.
+const WORKMUX_SESSION_STATUS_FORMAT: &str =
+    "#{?@workmux_session_status, #{@workmux_session_status},}";
+
+/// Injects the session status icon right after the session name.
+///
+/// Returns None if the format doesn't show the session name, so custom
+/// themes that omit it are left untouched.
+fn inject_session_status_format(format: &str) -> Option<String> {
+    let (pos, len) = ["#{session_name}", "#S"]
+        .iter()
+        .filter_map(|p| format.find(p).map(|pos| (pos, p.len())))
+        .min()?;
+    let (before, after) = format.split_at(pos + len);
+    Some(format!(
+        "{}{}{}",
+        before, WORKMUX_SESSION_STATUS_FORMAT, after
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn status_options(formats: &[(&'static str, &str)]) -> StatusOptions {
+        StatusOptions {
+            formats: formats.iter().map(|(o, f)| (*o, f.to_string())).collect(),
+            status_bar_on: true,
+            pane_border_on: false,
+        }
+    }
+
+    #[test]
+    fn parse_history_sizes_reads_three_numbers() {
+        assert_eq!(parse_history_sizes("120 2000 40"), Some((120, 2000, 40)));
+        assert_eq!(parse_history_sizes("120 2000"), None);
+        assert_eq!(parse_history_sizes("x 2000 40"), None);
+    }
+
+    #[test]
+    fn user_status_option_finds_composed_formats() {
+        let opts = status_options(&[
+            ("window-status-format", "#I:#W"),
+            (
+                "pane-border-format",
+                " #{pane_title} #{@workmux_pane_status} ",
+            ),
+        ]);
+        assert_eq!(opts.user_status_option(), Some("pane-border-format"));
+        assert_eq!(
+            status_options(&[("status-right", "%H:%M")]).user_status_option(),
+            None
+        );
+    }
+
+    #[test]
+    fn status_issues() {
+        let opts = status_options(&[("pane-border-format", "#{@workmux_status}")]);
+        let issues = opts.issues(true);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("pane-border-status is off"));
+
+        let opts = StatusOptions {
+            pane_border_on: true,
+            ..status_options(&[("pane-border-format", "#{@workmux_status}")])
+        };
+        assert!(opts.issues(false).is_empty());
+
+        let opts = StatusOptions {
+            status_bar_on: false,
+            ..status_options(&[])
+        };
+        assert!(opts.issues(true)[0].contains("status bar is off"));
+        assert!(status_options(&[]).issues(false)[0].contains("status_format is false"));
+        assert!(status_options(&[]).issues(true).is_empty());
+    }
+
+    #[test]
+    fn test_inject_session_status_format_default() {
+        assert_eq!(
+            inject_session_status_format("[#S] ").as_deref(),
+            Some("[#S#{?@workmux_session_status, #{@workmux_session_status},}] ")
+        );
+    }
+
+    #[test]
+    fn test_inject_session_status_format_long_name() {
+        assert_eq!(
+            inject_session_status_format("#{session_name} | ").as_deref(),
+            Some("#{session_name}#{?@workmux_session_status, #{@workmux_session_status},} | ")
+        );
+    }
+
+    #[test]
+    fn test_inject_session_status_format_without_session_name() {
+        assert_eq!(inject_session_status_format("#H "), None);
+    }
+
     #[test]
     fn test_inject_status_format_standard() {
         let input = "#I:#W#{?window_flags,#{window_flags}, }";
@@ -2,12 +2,15 @@
 //!
 //! These types are used by both the tmux and WezTerm backends.
 
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Agent status representing the current state of an agent.
 ///
 /// Stored as lowercase strings in JSON (e.g., "working", "waiting", "done").
+/// `unknown` is never reported by agents; the reconciler sets it when an
+/// agent stops sending heartbeats and its process is gone.
 /// Icons are resolved at display time from config.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -18,6 +21,225 @@ pub enum AgentStatus {
     Waiting,
     /// Agent has finished
     Done,
+    /// Agent stopped reporting without finishing (see `reconcile_liveness`)
+    Unknown,
+}
+
+impl AgentStatus {
+    /// Lowercase name, as stored in JSON (e.g. "waiting").
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentStatus::Working => "working",
+            AgentStatus::Waiting => "waiting",
+            AgentStatus::Done => "done",
+            AgentStatus::Unknown => "unknown",
+        }
+    }
+
+    /// Severity used for rollups: higher values need attention first.
+    fn severity(self) -> u8 {
+        match self {
+            AgentStatus::Waiting => 3,
+            AgentStatus::Unknown => 2,
+            AgentStatus::Working => 1,
+            AgentStatus::Done => 0,
+        }
+    }
+
+    /// Aggregate several agent statuses into one using worst-of semantics.
+    ///
+    /// Waiting beats Unknown, which beats Working, which beats Done. Returns None for an empty set.
+    pub fn rollup<I: IntoIterator<Item = AgentStatus>>(statuses: I) -> Option<AgentStatus> {
+        statuses.into_iter().max_by_key(|s| s.severity())
+    }
+}
+
+/// Fine-grained progress pulled from an agent's own API (OpenCode server mode).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentProgress {
+    /// Tool currently running, with its title if available (e.g. "bash: cargo test")
+    pub tool: Option<String>,
+    /// Tokens used by the latest assistant message
+    pub tokens: Option<u64>,
+    /// First line of the latest assistant text
+    pub last_message: Option<String>,
+    /// Unix timestamp when progress was fetched
+    pub updated_ts: u64,
+}
+
+impl AgentProgress {
+    /// One-line "currently doing X" summary for display.
+    pub fn summary(&self) -> Option<String> {
+        let activity = match (&self.tool, &self.last_message) {
+            (Some(tool), _) => format!("Running {}", tool),
+            (None, Some(message)) => message.clone(),
+            (None, None) => return None,
+        };
+        Some(match self.tokens {
+            Some(tokens) if tokens >= 1000 => {
+                format!("{} ({:.1}k tok)", activity, tokens as f64 / 1000.0)
+            }
+            Some(tokens) => format!("{} ({} tok)", activity, tokens),
+            None => activity,
+        })
+    }
+}
+
+/// Step-by-step progress reported by the agent itself with
+/// `workmux report-progress`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    /// Current step, from 0 to `total`
+    pub step: u32,
+    pub total: u32,
+    /// What the agent is working on in this step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Unix timestamp when progress was reported
+    pub updated_ts: u64,
+}
+
+impl TaskProgress {
+    /// Messages are cut to this many characters.
+    pub const MAX_MESSAGE_CHARS: usize = 120;
+
+    /// Validate a progress report. Reports can come from sandboxed agents,
+    /// so the message is reduced to its first line without control
+    /// characters and capped in length.
+    pub fn new(step: u32, total: u32, message: Option<&str>, updated_ts: u64) -> Result<Self> {
+        if total == 0 {
+            bail!("Total number of steps must be at least 1");
+        }
+        if step > total {
+            bail!("Step {} is past the total of {} steps", step, total);
+        }
+        let message = message
+            .and_then(|m| m.lines().next())
+            .map(|line| {
+                line.chars()
+                    .filter(|c| !c.is_control())
+                    .take(Self::MAX_MESSAGE_CHARS)
+                    .collect::<String>()
+            })
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty());
+        Ok(Self {
+            step,
+            total,
+            message,
+            updated_ts,
+        })
+    }
+
+    /// Progress bar `width` cells wide followed by the step count,
+    /// e.g. "▰▰▱▱▱ 2/5".
+    pub fn bar(&self, width: usize) -> String {
+        let total = self.total.max(1) as usize;
+        let filled = (self.step.min(self.total) as usize * width) / total;
+        format!(
+            "{}{} {}/{}",
+            "▰".repeat(filled),
+            "▱".repeat(width - filled),
+            self.step,
+            self.total
+        )
+    }
+}
+
+/// The latest command a sandboxed agent tried to run on the host without
+/// being allowed to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeniedExec {
+    /// Command name as the guest sent it, sanitized for display
+    pub command: String,
+    /// Number of denied requests from this agent
+    pub count: u32,
+    /// Unix timestamp of the latest one
+    pub ts: u64,
+}
+
+impl DeniedExec {
+    /// Command names are cut to this many characters.
+    pub const MAX_COMMAND_CHARS: usize = 40;
+
+    /// Reduce a command name from the guest to printable characters.
+    pub fn sanitize_command(command: &str) -> String {
+        command
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(Self::MAX_COMMAND_CHARS)
+            .collect()
+    }
+}
+
+/// Recent network traffic of a sandboxed agent, sampled by the sandbox
+/// supervisor.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetActivity {
+    /// Bytes per second received and sent, per sample, oldest first
+    pub rates: Vec<u64>,
+    /// Connections opened through the network proxy, per sample (only when
+    /// the sandbox restricts the network)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<u64>,
+    /// Unix timestamp of the latest sample
+    pub sampled_ts: u64,
+}
+
+impl NetActivity {
+    /// Seconds between samples.
+    pub const SAMPLE_SECS: u64 = 5;
+
+    /// Samples kept for the sparkline.
+    pub const MAX_SAMPLES: usize = 10;
+
+    /// Rates below this many bytes per second look idle in the sparkline.
+    const IDLE_RATE: u64 = 1024;
+
+    /// Add a sample, dropping the oldest beyond `MAX_SAMPLES`.
+    pub fn push(&mut self, rate: u64, connections: Option<u64>, now: u64) {
+        self.rates.push(rate);
+        if self.rates.len() > Self::MAX_SAMPLES {
+            self.rates.remove(0);
+        }
+        if let Some(connections) = connections {
+            self.connections.push(connections);
+            if self.connections.len() > Self::MAX_SAMPLES {
+                self.connections.remove(0);
+            }
+        }
+        self.sampled_ts = now;
+    }
+
+    /// Whether sampling stopped, e.g. because the sandbox is gone.
+    pub fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.sampled_ts) > 3 * Self::SAMPLE_SECS
+    }
+
+    /// Latest rate in bytes per second.
+    pub fn rate(&self) -> u64 {
+        self.rates.last().copied().unwrap_or(0)
+    }
+
+    /// The rates as block characters scaled to the busiest sample.
+    pub fn sparkline(&self) -> String {
+        const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = self
+            .rates
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(Self::IDLE_RATE);
+        self.rates
+            .iter()
+            .map(|&rate| {
+                let level = (u128::from(rate) * 7 + u128::from(max) / 2) / u128::from(max);
+                let level = level as usize;
+                BLOCKS[level.min(7)]
+            })
+            .collect()
+    }
 }
 
 /// Information about a specific pane running a workmux agent
@@ -37,6 +259,16 @@ pub struct AgentPane {
     pub status: Option<AgentStatus>,
     /// Unix timestamp when status was last set
     pub status_ts: Option<u64>,
+    /// Detailed progress, when the agent exposes it
+    pub progress: Option<AgentProgress>,
+    /// Progress the agent reported with `workmux report-progress`
+    pub task_progress: Option<TaskProgress>,
+    /// Latest host command the agent was denied (sandboxed agents)
+    pub denied_exec: Option<DeniedExec>,
+    /// Recent network traffic (sandboxed agents)
+    pub net_activity: Option<NetActivity>,
+    /// What the agent's process is running, from the process table
+    pub process: Option<ProcessActivity>,
 }
 
 /// Parameters for creating a new window/tab
@@ -81,6 +313,41 @@ pub struct CreateWindowInSessionParams<'a> {
 pub struct PaneSetupResult {
     /// The ID of the pane that should receive focus
     pub focus_pane_id: String,
+    /// Agent panes the prompt was sent to
+    pub prompted_pane_ids: Vec<String>,
+}
+
+/// Worktree values substituted for `{{name}}` placeholders in pane commands.
+#[derive(Debug, Clone, Default)]
+pub struct PaneVars {
+    /// Worktree handle (directory name)
+    pub handle: String,
+    pub branch: String,
+    /// Branch the worktree was created from
+    pub base: String,
+    /// Absolute path of the worktree
+    pub worktree: PathBuf,
+    /// Prompt file passed to the agent, if any
+    pub prompt_file: Option<PathBuf>,
+}
+
+impl PaneVars {
+    /// Value for a placeholder name, or None if the name is unknown.
+    pub fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "handle" => Some(self.handle.clone()),
+            "branch" => Some(self.branch.clone()),
+            "base" => Some(self.base.clone()),
+            "worktree" => Some(self.worktree.display().to_string()),
+            "prompt_file" => Some(
+                self.prompt_file
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
 }
 
 /// Options for pane setup
@@ -94,6 +361,13 @@ pub struct PaneSetupOptions<'a> {
     pub worktree_root: Option<&'a std::path::Path>,
     /// Pre-booted Lima VM name (if sandbox backend is Lima and VM was booted before window creation)
     pub lima_vm_name: Option<&'a str>,
+    /// Lima VM booting in the background, waited for before sending the
+    /// command of a pane that runs in it
+    pub lima_boot: Option<&'a crate::sandbox::VmBoot>,
+    /// Values for `{{name}}` placeholders in pane commands
+    pub vars: Option<&'a PaneVars>,
+    /// Directory for transcripts of panes with `log_output`
+    pub log_dir: Option<&'a std::path::Path>,
 }
 
 /// Backend type for multiplexer selection
@@ -131,6 +405,19 @@ impl std::str::FromStr for BackendType {
     }
 }
 
+/// Result of [`Multiplexer::capture_pane_incremental`](super::Multiplexer::capture_pane_incremental).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaneCapture {
+    /// Scrollback lines added since the previous capture, oldest first
+    pub history: Vec<String>,
+    /// Lines currently visible in the pane
+    pub screen: Vec<String>,
+    /// `history` replaces the caller's cached scrollback instead of extending it
+    pub reset: bool,
+    /// Scrollback size to pass as `since` on the next capture, if the backend tracks it
+    pub history_size: Option<usize>,
+}
+
 /// Live pane information from the multiplexer (used for reconciliation).
 ///
 /// Contains current state of a pane as queried from the multiplexer,
@@ -154,4 +441,144 @@ pub struct LivePaneInfo {
 
     /// Window name
     pub window: Option<String>,
+
+    /// What the foreground command is running (see `multiplexer::process`)
+    pub process: Option<ProcessActivity>,
+}
+
+/// What a pane's foreground command is doing, from the process table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessActivity {
+    /// Command the foreground command is busy running (e.g. "cargo" while an
+    /// agent runs a build), looking through shells it runs commands with
+    pub child: Option<String>,
+    /// CPU used by the foreground command and everything it runs since the
+    /// previous query, in percent of one core. `None` on the first query, and
+    /// when the work happens in a container or VM, where it can't be seen
+    pub cpu_percent: Option<f32>,
+}
+
+impl ProcessActivity {
+    /// CPU usage below this many percent of one core counts as idle.
+    pub const IDLE_CPU_PERCENT: f32 = 1.0;
+
+    /// Whether the command is known to be idle, e.g. an agent sitting at its
+    /// prompt rather than thinking or running a tool.
+    pub fn is_idle(&self) -> bool {
+        self.child.is_none()
+            && self
+                .cpu_percent
+                .is_some_and(|cpu| cpu < Self::IDLE_CPU_PERCENT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AgentProgress, AgentStatus, NetActivity, TaskProgress};
+
+    #[test]
+    fn rollup_prefers_waiting_over_working_over_done() {
+        use AgentStatus::*;
+        assert_eq!(AgentStatus::rollup([Done, Working, Waiting]), Some(Waiting));
+        assert_eq!(AgentStatus::rollup([Done, Working]), Some(Working));
+        assert_eq!(AgentStatus::rollup([Done, Done]), Some(Done));
+    }
+
+    #[test]
+    fn rollup_of_nothing_is_none() {
+        assert_eq!(AgentStatus::rollup(Vec::new()), None);
+    }
+
+    #[test]
+    fn progress_summary_prefers_running_tool() {
+        let progress = AgentProgress {
+            tool: Some("bash: cargo test".to_string()),
+            tokens: Some(12_345),
+            last_message: Some("Running the tests".to_string()),
+            updated_ts: 0,
+        };
+        assert_eq!(
+            progress.summary().as_deref(),
+            Some("Running bash: cargo test (12.3k tok)")
+        );
+
+        let idle = AgentProgress {
+            tool: None,
+            tokens: Some(42),
+            ..progress
+        };
+        assert_eq!(
+            idle.summary().as_deref(),
+            Some("Running the tests (42 tok)")
+        );
+        assert_eq!(AgentProgress::default().summary(), None);
+    }
+
+    #[test]
+    fn task_progress_rejects_impossible_steps() {
+        assert!(TaskProgress::new(1, 0, None, 0).is_err());
+        assert!(TaskProgress::new(4, 3, None, 0).is_err());
+        assert!(TaskProgress::new(0, 3, None, 0).is_ok());
+    }
+
+    #[test]
+    fn task_progress_sanitizes_message() {
+        let progress = TaskProgress::new(1, 2, Some("  \x1b[31mRed\x07 text\nsecond line"), 0);
+        assert_eq!(progress.unwrap().message.as_deref(), Some("[31mRed text"));
+
+        let long = "x".repeat(500);
+        let progress = TaskProgress::new(1, 2, Some(&long), 0).unwrap();
+        assert_eq!(
+            progress.message.unwrap().chars().count(),
+            TaskProgress::MAX_MESSAGE_CHARS
+        );
+        assert_eq!(
+            TaskProgress::new(1, 2, Some(" \n"), 0).unwrap().message,
+            None
+        );
+    }
+
+    #[test]
+    fn task_progress_bar() {
+        let progress = TaskProgress::new(2, 5, None, 0).unwrap();
+        assert_eq!(progress.bar(5), "▰▰▱▱▱ 2/5");
+        assert_eq!(
+            TaskProgress::new(1, 3, None, 0).unwrap().bar(5),
+            "▰▱▱▱▱ 1/3"
+        );
+        assert_eq!(
+            TaskProgress::new(3, 3, None, 0).unwrap().bar(5),
+            "▰▰▰▰▰ 3/3"
+        );
+    }
+
+    #[test]
+    fn net_activity_keeps_recent_samples() {
+        let mut activity = NetActivity::default();
+        for rate in 0..12 {
+            activity.push(rate * 1000, None, 100 + rate);
+        }
+        assert_eq!(activity.rates.len(), NetActivity::MAX_SAMPLES);
+        assert_eq!(activity.rate(), 11_000);
+        assert!(activity.connections.is_empty());
+        assert!(!activity.is_stale(111 + NetActivity::SAMPLE_SECS));
+        assert!(activity.is_stale(112 + 3 * NetActivity::SAMPLE_SECS));
+    }
+
+    #[test]
+    fn net_activity_sparkline() {
+        let activity = NetActivity {
+            rates: vec![0, 100, 50_000, 100_000],
+            connections: Vec::new(),
+            sampled_ts: 0,
+        };
+        assert_eq!(activity.sparkline(), "▁▁▅█");
+
+        // Light traffic isn't scaled up to full height
+        let idle = NetActivity {
+            rates: vec![0, 200, 0],
+            ..activity
+        };
+        assert_eq!(idle.sparkline(), "▁▂▁");
+    }
 }
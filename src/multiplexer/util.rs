@@ -5,6 +5,12 @@
 use std::borrow::Cow;
 use std::path::Path;
 
+use anyhow::{Result, bail};
+
+use crate::shell::{ShellKind, shell_quote};
+
+use super::types::PaneVars;
+
 /// Helper function to add prefix to window name.
 ///
 /// Used by all backends to construct full window names from prefix and base name.
@@ -17,11 +23,7 @@ pub fn prefixed(prefix: &str, window_name: &str) -> String {
 /// Used to determine whether agent commands need to be wrapped in `sh -c '...'`
 /// for shells like nushell or fish that don't support POSIX command substitution.
 pub fn is_posix_shell(shell: &str) -> bool {
-    let shell_name = Path::new(shell)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("sh");
-    matches!(shell_name, "bash" | "zsh" | "sh" | "dash" | "ksh" | "ash")
+    ShellKind::detect(shell).is_posix()
 }
 
 /// Rewrites an agent command to inject a prompt file's contents.
@@ -71,8 +73,12 @@ pub fn rewrite_agent_command(
         return None;
     }
 
-    let relative = prompt_file.strip_prefix(working_dir).unwrap_or(prompt_file);
-    let prompt_path = relative.to_string_lossy();
+    let relative_path = |path: &Path| {
+        path.strip_prefix(working_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    };
     let rest = pane_rest.trim_start();
 
     // Build the inner command step-by-step to ensure correct order:
@@ -85,20 +91,39 @@ pub fn rewrite_agent_command(
         inner_cmd.push_str(rest);
     }
 
-    // Add the prompt argument using agent profile
+    // Add the prompt argument using agent profile. Structured prompts have
+    // separate system/task parts next to the prompt file; agents with a system
+    // prompt option get them split, others get the combined prompt file.
     let profile = super::agent::resolve_profile(effective_agent);
+    let system_part = crate::prompt::system_part_path(prompt_file);
+    let task_part = crate::prompt::task_part_path(prompt_file);
+    let system_argument = if system_part.exists() && task_part.exists() {
+        profile
+            .system_prompt_argument(&relative_path(&system_part))
+            // Versions without the option get the combined prompt
+            .filter(|argument| {
+                crate::config::split_first_token(argument)
+                    .and_then(|(flag, _)| super::capabilities::supports(pane_token, flag))
+                    != Some(false)
+            })
+    } else {
+        None
+    };
     inner_cmd.push(' ');
-    inner_cmd.push_str(&profile.prompt_argument(&prompt_path));
+    match system_argument {
+        Some(system_argument) => {
+            inner_cmd.push_str(&system_argument);
+            inner_cmd.push(' ');
+            inner_cmd.push_str(&profile.prompt_argument(&relative_path(&task_part)));
+        }
+        None => inner_cmd.push_str(&profile.prompt_argument(&relative_path(prompt_file))),
+    }
 
     // For POSIX shells (bash, zsh, sh, etc.), use the command directly.
     // For non-POSIX shells (nushell, fish, pwsh), wrap in sh -c '...' to ensure
     // $(cat ...) command substitution works.
     // Prefix with space to prevent shell history entry.
-    if is_posix_shell(shell) {
-        Some(format!(" {}", inner_cmd))
-    } else {
-        Some(format!(" {}", wrap_for_non_posix_shell(&inner_cmd)))
-    }
+    Some(wrap_for_shell(&format!(" {}", inner_cmd), shell))
 }
 
 /// Resolve a pane's command: handle `<agent>` placeholder, auto-detect known
@@ -123,8 +148,13 @@ pub fn resolve_pane_command(
     working_dir: &Path,
     effective_agent: Option<&str>,
     shell: &str,
+    vars: Option<&PaneVars>,
 ) -> Option<ResolvedCommand> {
-    let raw_command = pane_command?;
+    let expanded = match vars {
+        Some(vars) => expand_placeholders(pane_command?, vars),
+        None => Cow::Borrowed(pane_command?),
+    };
+    let raw_command: &str = &expanded;
 
     let (command, pane_effective_agent) = if raw_command == "<agent>" {
         // Bare <agent> - use window-level effective agent
@@ -158,6 +188,33 @@ pub fn resolve_pane_command(
     })
 }
 
+/// Replace `{{name}}` placeholders with worktree values (see [`PaneVars`]).
+///
+/// Whitespace inside the braces is ignored. Unknown names are left as written,
+/// so other `{{...}}` syntax (e.g. `docker ps --format '{{.Names}}'`) passes
+/// through untouched.
+pub fn expand_placeholders<'a>(command: &'a str, vars: &PaneVars) -> Cow<'a, str> {
+    if !command.contains("{{") {
+        return Cow::Borrowed(command);
+    }
+    let mut out = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        match vars.get(after[..end].trim()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
 /// Adjust a command for execution, potentially rewriting it to inject prompts.
 ///
 /// This is a convenience wrapper around `rewrite_agent_command` that returns
@@ -217,6 +274,101 @@ pub fn wrap_for_non_posix_shell(command: &str) -> String {
     format!("sh -c '{}'", escaped)
 }
 
+/// Make a POSIX command line runnable in `shell`.
+///
+/// POSIX shells get it unchanged. Other shells get it wrapped in `sh -c`,
+/// quoted the way that shell parses quotes (see [`ShellKind::quote`]).
+/// Leading spaces (history prevention) stay outside the wrapper.
+pub fn wrap_for_shell(command: &str, shell: &str) -> String {
+    let kind = ShellKind::detect(shell);
+    if kind.is_posix() {
+        return command.to_string();
+    }
+    let trimmed = command.trim_start();
+    let leading_spaces = &command[..command.len() - trimmed.len()];
+    format!("{}sh -c {}", leading_spaces, kind.quote(trimmed))
+}
+
+/// Reverse [`wrap_for_shell`], returning the POSIX command line. Commands
+/// that aren't wrapped are returned unchanged.
+pub fn unwrap_for_shell(command: &str, shell: &str) -> String {
+    let kind = ShellKind::detect(shell);
+    let trimmed = command.trim_start();
+    let leading_spaces = &command[..command.len() - trimmed.len()];
+    match trimmed
+        .strip_prefix("sh -c ")
+        .filter(|_| !kind.is_posix())
+        .and_then(|quoted| kind.unquote(quoted))
+    {
+        Some(inner) => format!("{}{}", leading_spaces, inner),
+        None => command.to_string(),
+    }
+}
+
+/// Check that a pane command survives the quoting layers it goes through,
+/// for `strict_quoting`: it must be free of control characters (which tmux
+/// would type as key presses), unwrap to a POSIX command line that parses,
+/// and wrap back to itself.
+pub fn verify_pane_command(command: &str, shell: &str) -> Result<()> {
+    if let Some(c) = command.chars().find(|c| c.is_control()) {
+        bail!(
+            "Pane command contains the control character {:?}: {}",
+            c,
+            command
+        );
+    }
+    let posix = unwrap_for_shell(command, shell);
+    crate::shell::split_words(&posix)?;
+    if wrap_for_shell(&posix, shell) != command {
+        bail!(
+            "Pane command does not round-trip through {} quoting: {}",
+            shell,
+            command
+        );
+    }
+    Ok(())
+}
+
+/// Prefix a pane command with a wait for the readiness checks of the panes it
+/// depends on.
+///
+/// The wait runs in the pane itself via the hidden `workmux _pane-wait`
+/// command, so pane setup is not blocked and the user can watch (or cancel)
+/// the wait. The longest timeout among the checks applies. Non-POSIX shells
+/// get the whole chain wrapped in `sh -c '...'`.
+pub fn with_dependency_wait(
+    command: &str,
+    checks: &[&crate::config::PaneWaitFor],
+    exe: &str,
+    shell: &str,
+) -> String {
+    if checks.is_empty() {
+        return command.to_string();
+    }
+
+    // Chain on the POSIX form, an agent command may already be wrapped
+    let command = unwrap_for_shell(command, shell);
+    let trimmed = command.trim_start();
+    let leading_spaces = &command[..command.len() - trimmed.len()];
+
+    let mut wait = format!("{} _pane-wait", shell_quote(exe));
+    for check in checks {
+        if let Some(port) = check.port {
+            wait.push_str(&format!(" --port {}", port));
+        }
+        if let Some(file) = &check.file {
+            wait.push_str(&format!(" --file {}", shell_quote(file)));
+        }
+        if let Some(cmd) = &check.command {
+            wait.push_str(&format!(" --command {}", shell_quote(cmd)));
+        }
+    }
+    let timeout = checks.iter().map(|c| c.timeout()).max().unwrap_or_default();
+    wait.push_str(&format!(" --timeout {}", timeout));
+
+    wrap_for_shell(&format!("{}{} && {}", leading_spaces, wait, trimmed), shell)
+}
+
 /// Inject a permissions flag into an agent command string.
 ///
 /// Inserts the flag after the executable token but before any existing arguments.
@@ -256,11 +408,172 @@ fn inject_flag_after_executable(command: &str, flag: &str) -> String {
     }
 }
 
+/// Bytes a terminal sends for a tmux key name, for backends that can only
+/// send text. `C-`, `M-` and `S-` prefixes are encoded the way xterm does.
+/// Names without a translation are sent as they are.
+pub fn key_sequence(key: &str) -> Cow<'_, str> {
+    if let Some(seq) = plain_key_sequence(key) {
+        return Cow::Borrowed(seq);
+    }
+    let (modifiers, base) = split_modifiers(key);
+    if modifiers == 0 {
+        return Cow::Borrowed(key);
+    }
+    // xterm's modifier parameter: 1 + shift (1) + alt (2) + ctrl (4)
+    let param = 1 + modifiers;
+    if let Some(letter) = csi_letter(base) {
+        return Cow::Owned(format!("\x1b[1;{}{}", param, letter));
+    }
+    if let Some(code) = csi_tilde_code(base) {
+        return Cow::Owned(format!("\x1b[{};{}~", code, param));
+    }
+    let mut seq = String::new();
+    if modifiers & MOD_ALT != 0 {
+        seq.push('\x1b');
+    }
+    let mut chars = base.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers & MOD_CTRL != 0 => seq.push(control_char(c)),
+        _ => seq.push_str(plain_key_sequence(base).unwrap_or(base)),
+    }
+    Cow::Owned(seq)
+}
+
+const MOD_SHIFT: u8 = 1;
+const MOD_ALT: u8 = 2;
+const MOD_CTRL: u8 = 4;
+
+/// Split `C-M-x` into its modifier bits and the key name.
+fn split_modifiers(key: &str) -> (u8, &str) {
+    let mut modifiers = 0;
+    let mut rest = key;
+    // A bare "C-" is the character sequence, not a modifier
+    while rest.len() > 2 {
+        let bit = match &rest[..2] {
+            "C-" => MOD_CTRL,
+            "M-" => MOD_ALT,
+            "S-" => MOD_SHIFT,
+            _ => break,
+        };
+        modifiers |= bit;
+        rest = &rest[2..];
+    }
+    (modifiers, rest)
+}
+
+fn plain_key_sequence(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "BSpace" => "\x7f",
+        "Enter" => "\r",
+        "M-Enter" => "\x1b\r",
+        "C-j" => "\n",
+        "Tab" => "\t",
+        "BTab" => "\x1b[Z",
+        "Space" => " ",
+        "Escape" => "\x1b",
+        "Up" => "\x1b[A",
+        "Down" => "\x1b[B",
+        "Right" => "\x1b[C",
+        "Left" => "\x1b[D",
+        "Home" => "\x1b[H",
+        "End" => "\x1b[F",
+        "IC" => "\x1b[2~",
+        "DC" => "\x1b[3~",
+        "PPage" => "\x1b[5~",
+        "NPage" => "\x1b[6~",
+        "F1" => "\x1bOP",
+        "F2" => "\x1bOQ",
+        "F3" => "\x1bOR",
+        "F4" => "\x1bOS",
+        "F5" => "\x1b[15~",
+        "F6" => "\x1b[17~",
+        "F7" => "\x1b[18~",
+        "F8" => "\x1b[19~",
+        "F9" => "\x1b[20~",
+        "F10" => "\x1b[21~",
+        "F11" => "\x1b[23~",
+        "F12" => "\x1b[24~",
+        _ => return None,
+    })
+}
+
+/// Final byte of keys sent as `CSI 1 ; <modifiers> <letter>` when modified.
+fn csi_letter(key: &str) -> Option<char> {
+    Some(match key {
+        "Up" => 'A',
+        "Down" => 'B',
+        "Right" => 'C',
+        "Left" => 'D',
+        "End" => 'F',
+        "Home" => 'H',
+        "F1" => 'P',
+        "F2" => 'Q',
+        "F3" => 'R',
+        "F4" => 'S',
+        _ => return None,
+    })
+}
+
+/// Number of keys sent as `CSI <code> ; <modifiers> ~` when modified.
+fn csi_tilde_code(key: &str) -> Option<u8> {
+    Some(match key {
+        "IC" => 2,
+        "DC" => 3,
+        "PPage" => 5,
+        "NPage" => 6,
+        "F5" => 15,
+        "F6" => 17,
+        "F7" => 18,
+        "F8" => 19,
+        "F9" => 20,
+        "F10" => 21,
+        "F11" => 23,
+        "F12" => 24,
+        _ => return None,
+    })
+}
+
+/// The control character for Ctrl+`c`.
+fn control_char(c: char) -> char {
+    match c {
+        'a'..='z' | 'A'..='Z' => (c.to_ascii_lowercase() as u8 - b'a' + 1) as char,
+        '@' | ' ' | '2' => '\0',
+        '[' | '3' => '\x1b',
+        '\\' | '4' => '\x1c',
+        ']' | '5' => '\x1d',
+        '^' | '6' => '\x1e',
+        '_' | '/' | '7' => '\x1f',
+        '?' | '8' => '\x7f',
+        c => c,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn key_sequence_translates_tmux_names() {
+        assert_eq!(key_sequence("Enter"), "\r");
+        assert_eq!(key_sequence("M-Enter"), "\x1b\r");
+        assert_eq!(key_sequence("Escape"), "\x1b");
+        assert_eq!(key_sequence("y"), "y");
+    }
+
+    #[test]
+    fn key_sequence_encodes_modifiers() {
+        assert_eq!(key_sequence("C-c"), "\x03");
+        assert_eq!(key_sequence("C-]"), "\x1d");
+        assert_eq!(key_sequence("M-x"), "\x1bx");
+        assert_eq!(key_sequence("C-M-a"), "\x1b\x01");
+        assert_eq!(key_sequence("C-Up"), "\x1b[1;5A");
+        assert_eq!(key_sequence("S-F5"), "\x1b[15;2~");
+        assert_eq!(key_sequence("M-BSpace"), "\x1b\x7f");
+        // Too short to carry a modifier
+        assert_eq!(key_sequence("C-"), "C-");
+    }
+
     // --- prefixed tests ---
 
     #[test]
@@ -417,6 +730,45 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_rewrite_structured_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let prompt_file = dir.path().join("PROMPT.md");
+        for path in [
+            &prompt_file,
+            &crate::prompt::system_part_path(&prompt_file),
+            &crate::prompt::task_part_path(&prompt_file),
+        ] {
+            std::fs::write(path, "x").unwrap();
+        }
+
+        // Claude gets the system instructions via --append-system-prompt
+        let result = rewrite_agent_command(
+            "claude",
+            &prompt_file,
+            dir.path(),
+            Some("claude"),
+            "/bin/zsh",
+        );
+        assert_eq!(
+            result,
+            Some(
+                " claude --append-system-prompt \"$(cat PROMPT.system.md)\" -- \"$(cat PROMPT.task.md)\""
+                    .to_string()
+            )
+        );
+
+        // Agents without a system prompt option get the combined prompt
+        let result = rewrite_agent_command(
+            "gemini",
+            &prompt_file,
+            dir.path(),
+            Some("gemini"),
+            "/bin/zsh",
+        );
+        assert_eq!(result, Some(" gemini -i \"$(cat PROMPT.md)\"".to_string()));
+    }
+
     // --- escape_for_double_quotes tests ---
 
     #[test]
@@ -563,7 +915,8 @@ mod tests {
 
     #[test]
     fn test_resolve_pane_command_none_when_no_command() {
-        let result = resolve_pane_command(None, true, None, Path::new("/tmp"), None, "/bin/zsh");
+        let result =
+            resolve_pane_command(None, true, None, Path::new("/tmp"), None, "/bin/zsh", None);
         assert!(result.is_none());
     }
 
@@ -576,14 +929,22 @@ mod tests {
             Path::new("/tmp"),
             None,
             "/bin/zsh",
+            None,
         );
         assert!(result.is_none());
     }
 
     #[test]
     fn test_resolve_pane_command_returns_command_as_is() {
-        let result =
-            resolve_pane_command(Some("vim"), true, None, Path::new("/tmp"), None, "/bin/zsh");
+        let result = resolve_pane_command(
+            Some("vim"),
+            true,
+            None,
+            Path::new("/tmp"),
+            None,
+            "/bin/zsh",
+            None,
+        );
         let resolved = result.unwrap();
         assert_eq!(resolved.command, "vim");
         assert!(!resolved.prompt_injected);
@@ -598,6 +959,7 @@ mod tests {
             Path::new("/tmp"),
             Some("claude"),
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert_eq!(resolved.command, "claude");
@@ -613,6 +975,7 @@ mod tests {
             Path::new("/tmp"),
             None,
             "/bin/zsh",
+            None,
         );
         assert!(result.is_none());
     }
@@ -628,6 +991,7 @@ mod tests {
             &working_dir,
             Some("claude"),
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert!(resolved.prompt_injected);
@@ -645,6 +1009,7 @@ mod tests {
             &working_dir,
             Some("claude"),
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert!(!resolved.prompt_injected);
@@ -660,6 +1025,7 @@ mod tests {
             Path::new("/tmp"),
             Some("claude"),
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert_eq!(resolved.command, "claude");
@@ -675,6 +1041,7 @@ mod tests {
             Path::new("/tmp"),
             Some("claude"),
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert_eq!(resolved.command, "vim");
@@ -695,6 +1062,7 @@ mod tests {
             Path::new("/tmp"),
             Some("claude"), // window-level agent is claude
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert_eq!(resolved.command, "codex --yolo");
@@ -713,6 +1081,7 @@ mod tests {
             &working_dir,
             Some("claude"), // window-level is claude, pane is codex
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert!(resolved.prompt_injected);
@@ -732,6 +1101,7 @@ mod tests {
             &working_dir,
             None, // no window-level agent at all
             "/bin/zsh",
+            None,
         );
         let resolved = result.unwrap();
         assert!(resolved.prompt_injected);
@@ -739,4 +1109,186 @@ mod tests {
         assert!(resolved.command.contains("-i"));
         assert_eq!(resolved.effective_agent.as_deref(), Some("gemini"));
     }
+
+    // --- with_dependency_wait tests ---
+
+    #[test]
+    fn test_with_dependency_wait_prefixes_checks() {
+        let db = crate::config::PaneWaitFor {
+            port: Some(5432),
+            ..Default::default()
+        };
+        let migrations = crate::config::PaneWaitFor {
+            file: Some("tmp/migrated".to_string()),
+            command: Some("pg_isready -q".to_string()),
+            timeout: Some(120),
+            ..Default::default()
+        };
+        let result = with_dependency_wait(
+            " npm run dev",
+            &[&db, &migrations],
+            "/usr/bin/workmux",
+            "/bin/zsh",
+        );
+        assert_eq!(
+            result,
+            " /usr/bin/workmux _pane-wait --port 5432 --file tmp/migrated \
+             --command 'pg_isready -q' --timeout 120 && npm run dev"
+        );
+    }
+
+    #[test]
+    fn test_with_dependency_wait_non_posix_and_no_checks() {
+        let db = crate::config::PaneWaitFor {
+            port: Some(5432),
+            ..Default::default()
+        };
+        assert_eq!(
+            with_dependency_wait("npm run dev", &[&db], "workmux", "/usr/bin/nu"),
+            "sh -c 'workmux _pane-wait --port 5432 --timeout 60 && npm run dev'"
+        );
+        assert_eq!(
+            with_dependency_wait("npm run dev", &[], "workmux", "/bin/zsh"),
+            "npm run dev"
+        );
+    }
+
+    #[test]
+    fn test_with_dependency_wait_unwraps_wrapped_agent_command() {
+        let db = crate::config::PaneWaitFor {
+            port: Some(5432),
+            ..Default::default()
+        };
+        let agent = wrap_for_shell(" claude -- \"$(cat 'my prompt.md')\"", "/usr/bin/nu");
+        assert_eq!(
+            with_dependency_wait(&agent, &[&db], "workmux", "/usr/bin/nu"),
+            " sh -c r#'workmux _pane-wait --port 5432 --timeout 60 && \
+             claude -- \"$(cat 'my prompt.md')\"'#"
+        );
+    }
+
+    // --- wrap_for_shell tests ---
+
+    #[test]
+    fn test_wrap_for_shell_per_shell() {
+        let command = " echo 'it works' && echo $HOME";
+        assert_eq!(wrap_for_shell(command, "/bin/bash"), command);
+        assert_eq!(
+            wrap_for_shell(command, "/usr/bin/fish"),
+            r" sh -c 'echo \'it works\' && echo $HOME'"
+        );
+        assert_eq!(
+            wrap_for_shell(command, "/usr/bin/nu"),
+            " sh -c r#'echo 'it works' && echo $HOME'#"
+        );
+        assert_eq!(
+            wrap_for_shell(command, "/usr/bin/pwsh"),
+            r" sh -c 'echo '\''it works'\'' && echo $HOME'"
+        );
+        for shell in ["/bin/bash", "/usr/bin/fish", "/usr/bin/nu", "/usr/bin/pwsh"] {
+            assert_eq!(
+                unwrap_for_shell(&wrap_for_shell(command, shell), shell),
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_pane_command() {
+        let command = " claude -- \"$(cat 'my prompt.md')\"";
+        for shell in ["/bin/bash", "/usr/bin/fish", "/usr/bin/nu", "/usr/bin/pwsh"] {
+            assert!(verify_pane_command(&wrap_for_shell(command, shell), shell).is_ok());
+        }
+        assert!(verify_pane_command("echo 'unterminated", "/bin/zsh").is_err());
+        assert!(verify_pane_command("echo a\necho b", "/bin/zsh").is_err());
+        // A wrapper whose quoting broke no longer unwraps to the same command
+        assert!(verify_pane_command(" sh -c 'echo 'hi''", "/usr/bin/fish").is_err());
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_wrap_for_shell_round_trips(command in "[ -~]{0,60}") {
+            for shell in ["/bin/bash", "/usr/bin/fish", "/usr/bin/nu", "/usr/bin/pwsh"] {
+                proptest::prop_assert_eq!(
+                    unwrap_for_shell(&wrap_for_shell(&command, shell), shell),
+                    command.clone()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rewrite_command_with_quotes_fish() {
+        let result = rewrite_agent_command(
+            "claude --model 'opus'",
+            &PathBuf::from("/tmp/worktree/PROMPT.md"),
+            &PathBuf::from("/tmp/worktree"),
+            Some("claude"),
+            "/usr/bin/fish",
+        );
+        assert_eq!(
+            result.as_deref(),
+            Some(r#" sh -c 'claude --model \'opus\' -- "$(cat PROMPT.md)"'"#)
+        );
+    }
+
+    // --- expand_placeholders tests ---
+
+    fn pane_vars() -> PaneVars {
+        PaneVars {
+            handle: "fix-login".to_string(),
+            branch: "user/fix-login".to_string(),
+            base: "main".to_string(),
+            worktree: PathBuf::from("/repo__worktrees/fix-login"),
+            prompt_file: None,
+        }
+    }
+
+    #[test]
+    fn test_expand_placeholders_known_names() {
+        let vars = pane_vars();
+        assert_eq!(
+            expand_placeholders(
+                "printf '\\033]0;{{ branch }}\\007' && cd {{worktree}}",
+                &vars
+            ),
+            "printf '\\033]0;user/fix-login\\007' && cd /repo__worktrees/fix-login"
+        );
+        assert_eq!(
+            expand_placeholders("git log {{base}}..{{handle}}", &vars),
+            "git log main..fix-login"
+        );
+        assert_eq!(expand_placeholders("cat {{prompt_file}}", &vars), "cat ");
+    }
+
+    #[test]
+    fn test_expand_placeholders_leaves_other_braces() {
+        let vars = pane_vars();
+        assert!(matches!(
+            expand_placeholders("npm run dev", &vars),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            expand_placeholders("docker ps --format '{{.Names}}' {{port.web}}", &vars),
+            "docker ps --format '{{.Names}}' {{port.web}}"
+        );
+        assert_eq!(expand_placeholders("echo {{handle", &vars), "echo {{handle");
+    }
+
+    #[test]
+    fn test_resolve_pane_command_expands_placeholders() {
+        let vars = pane_vars();
+        let result = resolve_pane_command(
+            Some("npm run dev -- --name {{handle}}"),
+            true,
+            None,
+            Path::new("/tmp"),
+            None,
+            "/bin/zsh",
+            Some(&vars),
+        )
+        .unwrap();
+        assert_eq!(result.command, "npm run dev -- --name fix-login");
+        assert!(!result.prompt_injected);
+    }
 }
@@ -15,6 +15,7 @@ use crate::config::SplitDirection;
 
 use super::agent;
 use super::handshake::UnixPipeHandshake;
+use super::process;
 use super::types::*;
 use super::util;
 use super::{Multiplexer, PaneHandshake};
@@ -295,6 +296,10 @@ impl Multiplexer for WezTermBackend {
         Ok(())
     }
 
+    fn rename_window(&self, pane_id: &str, new_name: &str) -> Result<()> {
+        self.set_tab_title(pane_id, new_name)
+    }
+
     fn schedule_window_close(&self, full_name: &str, delay: Duration) -> Result<()> {
         let panes = self.list_panes()?;
         let current_ws = self.current_workspace();
@@ -662,6 +667,16 @@ impl Multiplexer for WezTermBackend {
         Ok(())
     }
 
+    fn send_text(&self, pane_id: &str, text: &str) -> Result<()> {
+        // Without a text argument, send-text reads stdin (keeps it out of argv)
+        self.wezterm_cmd()
+            .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste"])
+            .stdin(text)
+            .run()
+            .context("Failed to send text to pane")?;
+        Ok(())
+    }
+
     fn send_keys_to_agent(&self, pane_id: &str, command: &str, agent: Option<&str>) -> Result<()> {
         if agent::resolve_profile(agent).needs_bang_delay() && command.starts_with('!') {
             // Send ! first
@@ -674,49 +689,41 @@ impl Multiplexer for WezTermBackend {
             thread::sleep(Duration::from_millis(50));
 
             // Send the rest of the command (without the !)
-            self.wezterm_cmd()
-                .args(&[
-                    "cli",
-                    "send-text",
-                    "--pane-id",
-                    pane_id,
-                    "--no-paste",
-                    &command[1..],
-                ])
-                .run()
-                .context("Failed to send keys to pane")?;
-
-            // Send Enter
-            self.wezterm_cmd()
-                .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", "\r"])
-                .run()
-                .context("Failed to send Enter key to pane")?;
-
-            Ok(())
+            self.send_text(pane_id, &command[1..])?;
         } else {
-            self.send_keys(pane_id, command)
+            self.send_text(pane_id, command)?;
         }
+
+        // Send Enter
+        self.wezterm_cmd()
+            .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", "\r"])
+            .run()
+            .context("Failed to send Enter key to pane")?;
+
+        Ok(())
     }
 
     fn send_key(&self, pane_id: &str, key: &str) -> Result<()> {
         self.wezterm_cmd()
-            .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", key])
+            .args(&[
+                "cli",
+                "send-text",
+                "--pane-id",
+                pane_id,
+                "--no-paste",
+                &util::key_sequence(key),
+            ])
             .run()
             .context("Failed to send key to pane")?;
         Ok(())
     }
 
-    fn paste_multiline(&self, pane_id: &str, content: &str) -> Result<()> {
-        // Without --no-paste, WezTerm uses bracketed paste
-        self.wezterm_cmd()
-            .args(&["cli", "send-text", "--pane-id", pane_id, content])
-            .run()?;
-
-        // Send Enter to submit
+    fn paste(&self, pane_id: &str, content: &str) -> Result<()> {
+        // Without --no-paste, WezTerm uses bracketed paste (content read from stdin)
         self.wezterm_cmd()
-            .args(&["cli", "send-text", "--pane-id", pane_id, "--no-paste", "\r"])
+            .args(&["cli", "send-text", "--pane-id", pane_id])
+            .stdin(content)
             .run()?;
-
         Ok(())
     }
 
@@ -833,7 +840,7 @@ impl Multiplexer for WezTermBackend {
                     .filter(|s| !s.is_empty())
                     .unwrap_or_else(|| "unknown".to_string());
 
-                Ok(Some(LivePaneInfo {
+                let mut info = LivePaneInfo {
                     pid,
                     current_command,
                     working_dir: p.cwd_path(),
@@ -844,7 +851,10 @@ impl Multiplexer for WezTermBackend {
                     },
                     session: Some(p.workspace.clone()),
                     window: Some(p.tab_title.clone()),
-                }))
+                    process: None,
+                };
+                process::sample([&mut info]);
+                Ok(Some(info))
             }
             None => Ok(None),
         }
@@ -861,53 +871,32 @@ impl Multiplexer for WezTermBackend {
     fn get_all_live_pane_info(&self) -> Result<std::collections::HashMap<String, LivePaneInfo>> {
         use std::collections::HashMap;
 
+        let panes = self.list_panes()?;
+
+        // WezTerm doesn't expose PID or current command via CLI list. Rather
+        // than running ps for every pane, list foreground processes for all
+        // TTYs once and match them up by TTY name.
+        let foreground = Cmd::new("ps")
+            .args(&["-A", "-o", "tty=,pid=,stat=,comm="])
+            .run_and_capture_stdout()
+            .map(|output| parse_foreground_processes(&output))
+            .unwrap_or_default();
+
         let mut result = HashMap::new();
 
-        for p in self.list_panes()? {
-            let pane_id = p.pane_id.to_string();
-
-            // WezTerm doesn't expose PID or current command via CLI list.
-            // We extract both from the TTY using ps.
-            let tty_name = p.tty_name.as_ref().map(|t| t.trim_start_matches("/dev/"));
-
-            let pid = tty_name
-                .and_then(|tty| {
-                    Cmd::new("sh")
-                        .args(&[
-                            "-c",
-                            &format!(
-                                "ps -t {} -o pid=,stat= | grep '+' | head -1 | awk '{{print $1}}'",
-                                tty
-                            ),
-                        ])
-                        .run_and_capture_stdout()
-                        .ok()
-                })
-                .and_then(|output| output.trim().parse::<u32>().ok())
-                .unwrap_or(0);
-
-            let current_command = tty_name
-                .and_then(|tty| {
-                    Cmd::new("sh")
-                        .args(&[
-                            "-c",
-                            &format!(
-                                "ps -t {} -o stat=,comm= | grep '+' | head -1 | awk '{{print $2}}'",
-                                tty
-                            ),
-                        ])
-                        .run_and_capture_stdout()
-                        .ok()
-                })
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| "unknown".to_string());
+        for p in panes {
+            let process = p
+                .tty_name
+                .as_ref()
+                .and_then(|t| foreground.get(t.trim_start_matches("/dev/")));
 
             result.insert(
-                pane_id,
+                p.pane_id.to_string(),
                 LivePaneInfo {
-                    pid,
-                    current_command,
+                    pid: process.map(|(pid, _)| *pid).unwrap_or(0),
+                    current_command: process
+                        .map(|(_, command)| command.clone())
+                        .unwrap_or_else(|| "unknown".to_string()),
                     working_dir: p.cwd_path(),
                     title: if p.title.is_empty() {
                         None
@@ -916,10 +905,12 @@ impl Multiplexer for WezTermBackend {
                     },
                     session: Some(p.workspace.clone()),
                     window: Some(p.tab_title.clone()),
+                    process: None,
                 },
             );
         }
 
+        process::sample(result.values_mut());
         Ok(result)
     }
 
@@ -943,6 +934,32 @@ impl Multiplexer for WezTermBackend {
     }
 }
 
+/// Map each TTY to its first foreground process (PID, command).
+///
+/// Parses `ps -A -o tty=,pid=,stat=,comm=` output. Foreground processes have
+/// '+' in STAT. Only the first word of the command is kept, matching what
+/// `get_live_pane_info` reports for a single pane.
+fn parse_foreground_processes(output: &str) -> std::collections::HashMap<String, (u32, String)> {
+    let mut processes = std::collections::HashMap::new();
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(tty), Some(pid), Some(stat), Some(command)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if !stat.contains('+') {
+            continue;
+        }
+        if let Ok(pid) = pid.parse::<u32>() {
+            processes
+                .entry(tty.to_string())
+                .or_insert((pid, command.to_string()));
+        }
+    }
+    processes
+}
+
 /// Send escape sequence to trigger cross-workspace pane switch via WezTerm's user-var-changed event.
 ///
 /// This requires the user to have a Lua handler in their wezterm.lua.
@@ -1009,4 +1026,21 @@ mod tests {
 
         assert_eq!(pane.cwd_path(), PathBuf::from("/home/user/project"));
     }
+
+    #[test]
+    fn test_parse_foreground_processes() {
+        let output = "\
+?            1 Ss   init
+pts/3      100 Ss   zsh
+pts/3      200 S+   claude
+pts/3      201 S+   node
+pts/4      300 Ss+  zsh
+ttys005    400 R+   my agent
+";
+        let processes = parse_foreground_processes(output);
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes["pts/3"], (200, "claude".to_string()));
+        assert_eq!(processes["pts/4"], (300, "zsh".to_string()));
+        assert_eq!(processes["ttys005"], (400, "my".to_string()));
+    }
 }
@@ -35,6 +35,9 @@ pub fn derive_handle(
     };
 
     validate_handle(&handle)?;
+    if config.strict_quoting() {
+        crate::shell::check_name("Branch name", branch_name)?;
+    }
     Ok(handle)
 }
 
@@ -208,6 +211,17 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn derive_handle_strict_quoting_rejects_unsafe_branch() {
+        let config = Config {
+            strict_quoting: Some(true),
+            ..Config::default()
+        };
+        assert!(derive_handle("fix/it's-$HOME", None, &default_config()).is_ok());
+        assert!(derive_handle("fix/it's-$HOME", None, &config).is_err());
+        assert!(derive_handle("fix/login", None, &config).is_ok());
+    }
+
     #[test]
     fn validate_handle_empty_fails() {
         let result = validate_handle("");
@@ -10,6 +10,8 @@ use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
+use crate::config::IconTheme;
+
 /// Cached nerdfont setting to avoid repeated config lookups.
 static NERDFONT_ENABLED: OnceLock<bool> = OnceLock::new();
 
@@ -27,6 +29,7 @@ pub struct PrIcons {
 pub struct GitIcons {
     pub diff: &'static str,
     pub conflict: &'static str,
+    pub no_upstream: &'static str,
 }
 
 const NERDFONT_PR_ICONS: PrIcons = PrIcons {
@@ -64,13 +67,15 @@ const FALLBACK_CHECK_ICONS: CheckIcons = CheckIcons {
 };
 
 const NERDFONT_GIT_ICONS: GitIcons = GitIcons {
-    diff: "\u{f03eb}",     // nf-md-file_document_edit_outline
-    conflict: "\u{f002a}", // nf-md-alert
+    diff: "\u{f03eb}",        // nf-md-file_document_edit_outline
+    conflict: "\u{f002a}",    // nf-md-alert
+    no_upstream: "\u{f0164}", // nf-md-cloud_off_outline
 };
 
 const FALLBACK_GIT_ICONS: GitIcons = GitIcons {
     diff: "*",
     conflict: "!",
+    no_upstream: "~",
 };
 
 /// Git branch icon used in the setup prompt.
@@ -128,6 +133,9 @@ pub fn contains_pua(s: &str) -> bool {
 /// This indicates the user has nerdfonts configured.
 pub fn config_has_pua(config: &crate::config::Config) -> bool {
     // Check status_icons
+    if config.status_icons.theme == Some(IconTheme::Nerdfont) {
+        return true;
+    }
     if let Some(ref working) = config.status_icons.working
         && contains_pua(working)
     {
@@ -166,9 +174,10 @@ fn global_config_path() -> Option<PathBuf> {
     crate::config::global_config_path()
 }
 
-/// Prompt the user to indicate if they have nerdfonts installed.
+/// Prompt the user to indicate if they have nerdfonts installed, and pick the
+/// status icon theme from the answer when `pick_icon_theme` is set.
 /// Returns None if stdin is not a TTY (non-interactive) or in CI/test environments.
-pub fn prompt_setup() -> Result<Option<bool>> {
+pub fn prompt_setup(pick_icon_theme: bool) -> Result<Option<bool>> {
     // Skip prompt in CI or test environments
     if std::env::var("CI").is_ok() || std::env::var("WORKMUX_TEST").is_ok() {
         return Ok(None);
@@ -251,6 +260,23 @@ pub fn prompt_setup() -> Result<Option<bool>> {
             style("Set nerdfont: true in ~/.config/workmux/config.yaml to enable later").dim()
         );
     }
+    if pick_icon_theme {
+        let theme = detect_icon_theme(
+            enabled,
+            std::env::var("TERM").ok().as_deref(),
+            locale().as_deref(),
+        );
+        println!(
+            "{}",
+            style(format!("✔ Using {} status icons", theme.name())).green()
+        );
+        if let Err(e) = save_icon_theme(theme) {
+            println!(
+                "  {}",
+                style(format!("Could not save status icon theme: {}", e)).yellow()
+            );
+        }
+    }
     println!();
 
     Ok(Some(enabled))
@@ -293,6 +319,77 @@ fn save_nerdfont_preference(enabled: bool) -> Result<()> {
     Ok(())
 }
 
+/// Save the status icon theme to the global config file.
+fn save_icon_theme(theme: IconTheme) -> Result<()> {
+    let config_path = global_config_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = if config_path.exists() {
+        fs::read_to_string(&config_path)?
+    } else {
+        String::new()
+    };
+    fs::write(&config_path, with_icon_theme(&content, theme)?)?;
+    Ok(())
+}
+
+/// Set `status_icons.theme` in the config text, keeping the rest as is.
+fn with_icon_theme(content: &str, theme: IconTheme) -> Result<String> {
+    let line = format!("theme: {}", theme.name());
+    let section = regex::Regex::new(r"(?m)^status_icons:[ \t]*(#.*)?$")?;
+    let Some(header) = section.find(content) else {
+        if regex::Regex::new(r"(?m)^status_icons:")?.is_match(content) {
+            anyhow::bail!("status_icons is not a block mapping; add '{}' to it", line);
+        }
+        let mut content = content.to_string();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("status_icons:\n  {}\n", line));
+        return Ok(content);
+    };
+
+    // Match the indentation of the section's first entry
+    let rest = content[header.end()..].trim_start_matches(['\r', '\n']);
+    let indent: String = rest.chars().take_while(|c| *c == ' ').collect();
+    let indent = if indent.is_empty() { "  " } else { &indent };
+    Ok(format!(
+        "{}\n{}{}{}",
+        &content[..header.end()],
+        indent,
+        line,
+        &content[header.end()..]
+    ))
+}
+
+/// Status icon theme for the terminal: Nerd Font glyphs when the font has
+/// them, ASCII when the terminal or locale can't show emoji, emoji otherwise.
+pub fn detect_icon_theme(nerdfont: bool, term: Option<&str>, locale: Option<&str>) -> IconTheme {
+    if nerdfont {
+        return IconTheme::Nerdfont;
+    }
+    let basic_term = matches!(term, Some("linux" | "dumb" | "vt100" | "vt220"));
+    let utf8_locale = locale.is_none_or(|locale| {
+        let locale = locale.to_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    });
+    if basic_term || !utf8_locale {
+        IconTheme::Ascii
+    } else {
+        IconTheme::Emoji
+    }
+}
+
+/// Locale governing character encoding, by POSIX precedence.
+fn locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
 /// Run the nerdfont setup check.
 /// Returns the nerdfont setting (true/false) or None if not determined.
 pub fn check_and_prompt(config: &crate::config::Config) -> Result<Option<bool>> {
@@ -306,8 +403,14 @@ pub fn check_and_prompt(config: &crate::config::Config) -> Result<Option<bool>>
         return Ok(Some(true));
     }
 
-    // Otherwise, prompt the user
-    prompt_setup()
+    // Otherwise, prompt the user. Icon themes are picked along the way
+    // unless the status icons are configured already.
+    let icons = &config.status_icons;
+    let pick_icon_theme = icons.theme.is_none()
+        && icons.working.is_none()
+        && icons.waiting.is_none()
+        && icons.done.is_none();
+    prompt_setup(pick_icon_theme)
 }
 
 #[cfg(test)]
@@ -339,4 +442,47 @@ mod tests {
     fn contains_pua_handles_empty_string() {
         assert!(!contains_pua(""));
     }
+
+    #[test]
+    fn icon_theme_follows_font_terminal_and_locale() {
+        let utf8 = Some("en_US.UTF-8");
+        assert_eq!(
+            detect_icon_theme(true, Some("xterm-256color"), utf8),
+            IconTheme::Nerdfont
+        );
+        assert_eq!(
+            detect_icon_theme(false, Some("xterm-256color"), utf8),
+            IconTheme::Emoji
+        );
+        assert_eq!(detect_icon_theme(false, None, None), IconTheme::Emoji);
+        assert_eq!(
+            detect_icon_theme(false, Some("linux"), utf8),
+            IconTheme::Ascii
+        );
+        assert_eq!(
+            detect_icon_theme(false, Some("xterm"), Some("C")),
+            IconTheme::Ascii
+        );
+    }
+
+    #[test]
+    fn icon_theme_is_written_into_the_config() {
+        assert_eq!(
+            with_icon_theme("", IconTheme::Ascii).unwrap(),
+            "status_icons:\n  theme: ascii\n"
+        );
+        assert_eq!(
+            with_icon_theme("nerdfont: false", IconTheme::Ascii).unwrap(),
+            "nerdfont: false\nstatus_icons:\n  theme: ascii\n"
+        );
+        assert_eq!(
+            with_icon_theme(
+                "status_icons: # mine\n    done: \"ok\"\nagent: claude\n",
+                IconTheme::Emoji
+            )
+            .unwrap(),
+            "status_icons: # mine\n    theme: emoji\n    done: \"ok\"\nagent: claude\n"
+        );
+        assert!(with_icon_theme("status_icons: {done: ok}\n", IconTheme::Emoji).is_err());
+    }
 }
@@ -0,0 +1,197 @@
+//! Notifications when an agent starts waiting for input or finishes.
+//!
+//! Configured with the `notifications` section of the global config. Each
+//! status change is sent to every configured sink: a desktop notification, a
+//! webhook POST and a shell command. Webhooks and commands run in the
+//! background so a slow endpoint never holds up the agent's status hook.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::config::{Config, NotificationsConfig};
+use crate::forge::curl_quote;
+use crate::multiplexer::AgentStatus;
+
+/// An agent status change, as sent to the webhook.
+#[derive(Debug, Serialize)]
+pub struct Notification {
+    /// One-line summary, e.g. "auth is waiting for input"
+    pub text: String,
+    pub status: AgentStatus,
+    /// Name of the worktree the agent runs in
+    pub worktree: String,
+    pub path: PathBuf,
+    pub pane_id: String,
+    pub title: Option<String>,
+}
+
+impl Notification {
+    pub fn new(status: AgentStatus, path: &Path, pane_id: &str, title: Option<&str>) -> Self {
+        let worktree = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let text = match status {
+            AgentStatus::Waiting => format!("{} is waiting for input", worktree),
+            AgentStatus::Done => format!("{} is done", worktree),
+            AgentStatus::Working => format!("{} is working", worktree),
+            AgentStatus::Unknown => format!("{} stopped reporting its status", worktree),
+        };
+        Self {
+            text,
+            status,
+            worktree,
+            path: path.to_path_buf(),
+            pane_id: pane_id.to_string(),
+            title: title
+                .map(|t| t.strip_prefix("... ").unwrap_or(t).trim().to_string())
+                .filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Environment variables describing the change for the command sink.
+    fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("WORKMUX_STATUS", self.status.as_str().to_string()),
+            ("WORKMUX_WORKTREE", self.worktree.clone()),
+            ("WORKMUX_PATH", self.path.display().to_string()),
+            ("WORKMUX_PANE_ID", self.pane_id.clone()),
+            ("WORKMUX_TITLE", self.title.clone().unwrap_or_default()),
+            ("WORKMUX_MESSAGE", self.text.clone()),
+        ]
+    }
+}
+
+/// Notify that the agent in `pane_id`, working in `workdir`, changed to
+/// `status`. Best-effort: sinks that fail are logged and skipped.
+pub fn status_changed(pane_id: &str, workdir: &Path, status: AgentStatus, title: Option<&str>) {
+    let config = match Config::load(None) {
+        Ok(config) => config.notifications,
+        Err(e) => {
+            debug!(error = %e, "notify:no config");
+            return;
+        }
+    };
+    if !config.is_enabled() || !config.notifies(status) {
+        return;
+    }
+    // Name the worktree rather than the subdirectory the agent is in
+    let root = crate::vcs::detect_vcs()
+        .and_then(|vcs| vcs.get_repo_root_for(workdir))
+        .unwrap_or_else(|_| workdir.to_path_buf());
+    send(&config, &Notification::new(status, &root, pane_id, title));
+}
+
+/// Send `notification` to every sink in `config`.
+pub fn send(config: &NotificationsConfig, notification: &Notification) {
+    if config.desktop() {
+        let message = match &notification.title {
+            Some(title) => format!("{}\n{}", notification.text, title),
+            None => notification.text.clone(),
+        };
+        crate::util::show_notification(&message);
+    }
+    if let Some(url) = &config.webhook {
+        match serde_json::to_string(notification) {
+            Ok(body) => {
+                let mut cmd = Command::new("curl");
+                cmd.args(["-fsS", "--connect-timeout", "5", "--max-time", "10"]);
+                cmd.args(["--config", "-"]);
+                // The URL often embeds a secret, so it goes on stdin rather
+                // than the command line
+                spawn("webhook", cmd, Some(webhook_config(url, &body)));
+            }
+            Err(e) => warn!(error = %e, "notify:webhook payload"),
+        }
+    }
+    if let Some(command) = &config.command {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd.envs(notification.env());
+        spawn("command", cmd, None);
+    }
+}
+
+/// curl config POSTing `body` as JSON to `url`.
+fn webhook_config(url: &str, body: &str) -> String {
+    format!(
+        "url = \"{}\"\nheader = \"Content-Type: application/json\"\ndata-binary = \"{}\"\n",
+        curl_quote(url),
+        curl_quote(body)
+    )
+}
+
+/// Start `cmd` in the background, writing `input` to its stdin.
+fn spawn(sink: &'static str, mut cmd: Command, input: Option<String>) {
+    let stdin = if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    };
+    cmd.stdin(stdin).stdout(Stdio::null()).stderr(Stdio::null());
+    // Own process group, so it outlives a status hook that exits right away
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            warn!(sink, error = %e, "notify:failed to start");
+            return;
+        }
+    };
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take())
+        && let Err(e) = stdin.write_all(input.as_bytes())
+    {
+        warn!(sink, error = %e, "notify:failed to write input");
+    }
+    // Reap it in long-running callers such as the sandbox RPC host
+    thread::spawn(move || {
+        if let Ok(status) = child.wait()
+            && !status.success()
+        {
+            warn!(sink, %status, "notify:failed");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notification_describes_the_change() {
+        let n = Notification::new(
+            AgentStatus::Waiting,
+            Path::new("/code/web__worktrees/auth"),
+            "%3",
+            Some("... Fix login"),
+        );
+        assert_eq!(n.text, "auth is waiting for input");
+        assert_eq!(n.title.as_deref(), Some("Fix login"));
+
+        let json = serde_json::to_value(&n).unwrap();
+        assert_eq!(json["status"], "waiting");
+        assert_eq!(json["worktree"], "auth");
+        assert_eq!(json["pane_id"], "%3");
+
+        let env = n.env();
+        assert!(env.contains(&("WORKMUX_STATUS", "waiting".to_string())));
+        assert!(env.contains(&("WORKMUX_PATH", "/code/web__worktrees/auth".to_string())));
+
+        let config = webhook_config("https://example.com/hook?a=\"b\"", r#"{"text":"x"}"#);
+        assert_eq!(
+            config,
+            "url = \"https://example.com/hook?a=\\\"b\\\"\"\n\
+             header = \"Content-Type: application/json\"\n\
+             data-binary = \"{\\\"text\\\":\\\"x\\\"}\"\n"
+        );
+    }
+}
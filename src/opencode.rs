@@ -0,0 +1,277 @@
+//! Client for OpenCode's HTTP server.
+//!
+//! OpenCode runs a local HTTP server alongside its TUI. The workmux status
+//! plugin reports the server URL via `set-window-status --server-url`, and the
+//! dashboard polls it for fine-grained progress (running tool, token usage,
+//! latest assistant message) when `dashboard.opencode_server` is enabled.
+//!
+//! Only plain `http://` is supported since the server always listens locally.
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::multiplexer::AgentProgress;
+
+/// Per-request timeout. Kept short so an unresponsive server can't stall polling.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// Number of trailing messages to request (older servers ignore the limit).
+const MESSAGE_LIMIT: u32 = 5;
+
+/// Fetch current progress from the OpenCode server at `server_url`.
+///
+/// Uses the most recently updated session, which is the one the user is
+/// interacting with (or a subagent session it spawned).
+pub fn fetch_progress(server_url: &str) -> Result<AgentProgress> {
+    let sessions = get_json(server_url, "/session")?;
+    let session_id =
+        latest_session_id(&sessions).ok_or_else(|| anyhow!("OpenCode server has no sessions"))?;
+    let messages = get_json(
+        server_url,
+        &format!("/session/{}/message?limit={}", session_id, MESSAGE_LIMIT),
+    )?;
+    Ok(parse_progress(&messages))
+}
+
+/// Pick the session with the latest `time.updated`.
+fn latest_session_id(sessions: &Value) -> Option<String> {
+    sessions
+        .as_array()?
+        .iter()
+        .max_by_key(|s| {
+            s.pointer("/time/updated")
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        })
+        .and_then(|s| s.get("id")?.as_str())
+        .map(String::from)
+}
+
+/// Extract progress from a session's message list (`[{info, parts}]`).
+fn parse_progress(messages: &Value) -> AgentProgress {
+    let updated_ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut progress = AgentProgress {
+        updated_ts,
+        ..Default::default()
+    };
+
+    let Some(last) = messages.as_array().and_then(|messages| {
+        messages
+            .iter()
+            .rev()
+            .find(|m| m.pointer("/info/role").and_then(Value::as_str) == Some("assistant"))
+    }) else {
+        return progress;
+    };
+
+    if let Some(tokens) = last.pointer("/info/tokens") {
+        let total: u64 = ["/input", "/output", "/reasoning"]
+            .iter()
+            .filter_map(|p| tokens.pointer(p).and_then(Value::as_u64))
+            .sum();
+        progress.tokens = (total > 0).then_some(total);
+    }
+
+    let parts = last
+        .get("parts")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    progress.tool = parts
+        .iter()
+        .rev()
+        .filter(|p| p.get("type").and_then(Value::as_str) == Some("tool"))
+        .find(|p| {
+            matches!(
+                p.pointer("/state/status").and_then(Value::as_str),
+                Some("pending" | "running")
+            )
+        })
+        .and_then(|p| {
+            let tool = p.get("tool")?.as_str()?;
+            let title = p
+                .pointer("/state/title")
+                .and_then(Value::as_str)
+                .map(first_line)
+                .filter(|t| !t.is_empty());
+            Some(match title {
+                Some(title) => format!("{}: {}", tool, title),
+                None => tool.to_string(),
+            })
+        });
+
+    progress.last_message = parts
+        .iter()
+        .rev()
+        .filter(|p| p.get("type").and_then(Value::as_str) == Some("text"))
+        .filter_map(|p| p.get("text")?.as_str())
+        .map(first_line)
+        .find(|t| !t.is_empty())
+        .map(String::from);
+
+    progress
+}
+
+fn first_line(text: &str) -> &str {
+    text.trim().lines().next().unwrap_or("").trim()
+}
+
+/// Perform a GET request and parse the response body as JSON.
+fn get_json(server_url: &str, path: &str) -> Result<Value> {
+    let body = http_get(server_url, path)?;
+    serde_json::from_slice(&body).context("Invalid JSON from OpenCode server")
+}
+
+/// Minimal HTTP/1.1 GET over a plain TCP connection.
+fn http_get(server_url: &str, path: &str) -> Result<Vec<u8>> {
+    let authority = server_url
+        .strip_prefix("http://")
+        .ok_or_else(|| anyhow!("Unsupported OpenCode server URL: {}", server_url))?
+        .trim_end_matches('/');
+    let authority = authority.split('/').next().unwrap_or(authority);
+
+    let addr = authority
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid OpenCode server address: {}", authority))?
+        .next()
+        .ok_or_else(|| anyhow!("Could not resolve {}", authority))?;
+    let mut stream = TcpStream::connect_timeout(&addr, REQUEST_TIMEOUT)
+        .with_context(|| format!("Failed to connect to OpenCode server at {}", authority))?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        path, authority
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .context("Failed to read OpenCode server response")?;
+    parse_http_response(&response)
+}
+
+/// Split an HTTP response into status/headers and body, decoding chunked bodies.
+fn parse_http_response(response: &[u8]) -> Result<Vec<u8>> {
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| anyhow!("Malformed HTTP response"))?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| anyhow!("Malformed HTTP status line"))?;
+    if status != 200 {
+        bail!("OpenCode server returned HTTP {}", status);
+    }
+
+    let chunked = lines.any(|l| {
+        l.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(|| anyhow!("Malformed chunked body"))?;
+        let size_str = String::from_utf8_lossy(&body[..line_end]);
+        let size_str = size_str.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_str, 16)
+            .with_context(|| format!("Invalid chunk size '{}'", size_str))?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        if body.len() < size {
+            bail!("Truncated chunked body");
+        }
+        out.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn latest_session_id_picks_most_recently_updated() {
+        let sessions = json!([
+            {"id": "ses_a", "time": {"created": 1, "updated": 10}},
+            {"id": "ses_b", "time": {"created": 2, "updated": 30}},
+            {"id": "ses_c", "time": {"created": 3, "updated": 20}},
+        ]);
+        assert_eq!(latest_session_id(&sessions).as_deref(), Some("ses_b"));
+        assert_eq!(latest_session_id(&json!([])), None);
+    }
+
+    #[test]
+    fn parse_progress_reports_running_tool_tokens_and_text() {
+        let messages = json!([
+            {"info": {"role": "user"}, "parts": [{"type": "text", "text": "fix tests"}]},
+            {
+                "info": {"role": "assistant", "tokens": {"input": 1200, "output": 300, "reasoning": 0}},
+                "parts": [
+                    {"type": "text", "text": "Running the test suite.\nMore detail"},
+                    {"type": "tool", "tool": "read", "state": {"status": "completed"}},
+                    {"type": "tool", "tool": "bash", "state": {"status": "running", "title": "cargo test"}},
+                ]
+            }
+        ]);
+        let progress = parse_progress(&messages);
+        assert_eq!(progress.tool.as_deref(), Some("bash: cargo test"));
+        assert_eq!(progress.tokens, Some(1500));
+        assert_eq!(
+            progress.last_message.as_deref(),
+            Some("Running the test suite.")
+        );
+    }
+
+    #[test]
+    fn parse_progress_without_assistant_message_is_empty() {
+        let messages = json!([{"info": {"role": "user"}, "parts": []}]);
+        let progress = parse_progress(&messages);
+        assert_eq!(progress.tool, None);
+        assert_eq!(progress.tokens, None);
+        assert_eq!(progress.last_message, None);
+    }
+
+    #[test]
+    fn parse_http_response_plain_and_chunked() {
+        let plain = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n[]";
+        assert_eq!(parse_http_response(plain).unwrap(), b"[]");
+
+        let chunked =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n[1,\r\n2\r\n2]\r\n0\r\n\r\n";
+        assert_eq!(parse_http_response(chunked).unwrap(), b"[1,2]");
+
+        let not_found = b"HTTP/1.1 404 Not Found\r\n\r\n";
+        assert!(parse_http_response(not_found).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! External plugins notified of worktree lifecycle events.
+//!
+//! Every executable in `~/.config/workmux/plugins/` is run once per event with
+//! the event as a single JSON object on stdin. Each line a plugin prints to
+//! stdout may be a JSON directive that annotates the worktree or, for
+//! `remove_requested`, vetoes the removal. Plugins run in name order and are
+//! killed if they take longer than [`PLUGIN_TIMEOUT`].
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use wait_timeout::ChildExt;
+
+use crate::multiplexer::AgentStatus;
+use crate::vcs::Vcs;
+
+/// How long a plugin may run before it is killed.
+pub const PLUGIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Workspace metadata key holding the note set by plugins.
+pub const NOTE_META_KEY: &str = "note";
+/// Workspace metadata key holding comma-separated tags set by plugins.
+pub const TAGS_META_KEY: &str = "tags";
+
+/// Lifecycle event sent to plugins on stdin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PluginEvent {
+    /// A worktree was created and its post-create hooks have run
+    WorktreeCreated {
+        handle: String,
+        branch: String,
+        worktree_path: PathBuf,
+    },
+    /// An agent in the worktree reported a new status
+    StatusChanged {
+        handle: String,
+        worktree_path: PathBuf,
+        pane_id: String,
+        status: AgentStatus,
+    },
+    /// A branch was merged; the worktree has not been cleaned up yet
+    MergeCompleted {
+        handle: String,
+        branch: String,
+        target: String,
+    },
+    /// `workmux remove` is about to delete the worktree
+    RemoveRequested {
+        handle: String,
+        branch: String,
+        worktree_path: PathBuf,
+    },
+}
+
+impl PluginEvent {
+    fn handle(&self) -> &str {
+        match self {
+            Self::WorktreeCreated { handle, .. }
+            | Self::StatusChanged { handle, .. }
+            | Self::MergeCompleted { handle, .. }
+            | Self::RemoveRequested { handle, .. } => handle,
+        }
+    }
+}
+
+/// Directive a plugin prints to stdout, one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "directive", rename_all = "snake_case")]
+pub enum Directive {
+    /// Replace the worktree's note (shown by `workmux list`)
+    Note { text: String },
+    /// Add a tag to the worktree
+    Tag { tag: String },
+    /// Refuse a `remove_requested` event
+    Veto { reason: String },
+}
+
+/// Directory scanned for plugins.
+pub fn plugins_dir() -> Option<PathBuf> {
+    home::home_dir().map(|home| home.join(".config/workmux/plugins"))
+}
+
+/// Executables in `dir`, sorted by file name.
+fn find_plugins(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut plugins: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_executable(path))
+        .collect();
+    plugins.sort();
+    plugins
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Send `event` to every plugin and apply the directives they print.
+///
+/// Plugins that fail, time out or print invalid lines are logged and skipped.
+/// Returns an error only when a plugin vetoes a `remove_requested` event.
+pub fn dispatch(event: &PluginEvent, vcs: &dyn Vcs) -> Result<()> {
+    let Some(dir) = plugins_dir() else {
+        return Ok(());
+    };
+    let plugins = find_plugins(&dir);
+    if plugins.is_empty() {
+        return Ok(());
+    }
+    let payload = serde_json::to_string(event)?;
+
+    for plugin in plugins {
+        let name = plugin
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let stdout = match run_plugin(&plugin, &payload) {
+            Ok(stdout) => stdout,
+            Err(e) => {
+                warn!(plugin = %name, error = %e, "plugin:failed");
+                continue;
+            }
+        };
+        for directive in parse_directives(&name, &stdout) {
+            debug!(plugin = %name, ?directive, "plugin:directive");
+            match directive {
+                Directive::Note { text } => {
+                    if let Err(e) = vcs.set_workspace_meta(event.handle(), NOTE_META_KEY, &text) {
+                        warn!(plugin = %name, error = %e, "plugin:failed to save note");
+                    }
+                }
+                Directive::Tag { tag } => {
+                    let existing = vcs.get_workspace_meta(event.handle(), TAGS_META_KEY);
+                    let tags = add_tag(existing.as_deref(), &tag);
+                    if let Err(e) = vcs.set_workspace_meta(event.handle(), TAGS_META_KEY, &tags) {
+                        warn!(plugin = %name, error = %e, "plugin:failed to save tag");
+                    }
+                }
+                Directive::Veto { reason } => {
+                    if matches!(event, PluginEvent::RemoveRequested { .. }) {
+                        bail!(
+                            "Removal of '{}' vetoed by plugin '{}': {}",
+                            event.handle(),
+                            name,
+                            reason
+                        );
+                    }
+                    warn!(plugin = %name, "plugin:veto ignored, event cannot be vetoed");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run one plugin with `payload` on stdin and return its stdout.
+fn run_plugin(plugin: &Path, payload: &str) -> Result<String> {
+    let mut child = Command::new(plugin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", plugin.display()))?;
+
+    // A plugin that exits without reading stdin closes the pipe; that's fine
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+        let _ = stdin.write_all(b"\n");
+    }
+
+    // Read output on threads so a chatty plugin can't block on a full pipe
+    let mut stdout = child.stdout.take().context("Plugin stdout unavailable")?;
+    let mut stderr = child.stderr.take().context("Plugin stderr unavailable")?;
+    let out_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stdout.read_to_string(&mut buf);
+        buf
+    });
+    let err_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    let Some(status) = child.wait_timeout(PLUGIN_TIMEOUT)? else {
+        let _ = child.kill();
+        let _ = child.wait();
+        bail!("timed out after {}s", PLUGIN_TIMEOUT.as_secs());
+    };
+
+    let output = out_reader.join().unwrap_or_default();
+    let errors = err_reader.join().unwrap_or_default();
+    if !errors.trim().is_empty() {
+        debug!(plugin = %plugin.display(), stderr = %errors.trim(), "plugin:stderr");
+    }
+    if !status.success() {
+        bail!("exited with {}", status);
+    }
+    Ok(output)
+}
+
+/// Parse the directive lines in a plugin's output, skipping blank lines.
+fn parse_directives(plugin: &str, output: &str) -> Vec<Directive> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(directive) => Some(directive),
+            Err(e) => {
+                warn!(plugin, line, error = %e, "plugin:invalid directive");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Split a stored comma-separated tag list.
+pub fn split_tags(tags: &str) -> Vec<&str> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Check a tag given on the command line: short, no separators or spaces.
+pub fn validate_tag(tag: &str) -> Result<()> {
+    let valid = !tag.is_empty()
+        && tag.len() <= 64
+        && !tag.starts_with('-')
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':'));
+    if !valid {
+        bail!(
+            "Invalid tag '{}': use up to 64 letters, digits, '-', '_', '.', '/' or ':'",
+            tag
+        );
+    }
+    Ok(())
+}
+
+/// Append `tag` to a stored tag list unless it is already present.
+pub fn add_tag(existing: Option<&str>, tag: &str) -> String {
+    let mut tags = existing.map(split_tags).unwrap_or_default();
+    let tag = tag.trim();
+    if !tag.is_empty() && !tags.contains(&tag) {
+        tags.push(tag);
+    }
+    tags.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_serializes_with_tag() {
+        let event = PluginEvent::MergeCompleted {
+            handle: "feature".to_string(),
+            branch: "user/feature".to_string(),
+            target: "main".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"merge_completed","handle":"feature","branch":"user/feature","target":"main"}"#
+        );
+    }
+
+    #[test]
+    fn parse_directives_skips_invalid_lines() {
+        let output = "\n{\"directive\":\"note\",\"text\":\"needs review\"}\nnot json\n\
+                      {\"directive\":\"tag\",\"tag\":\"ci\"}\n{\"directive\":\"bogus\"}\n";
+        assert_eq!(
+            parse_directives("p", output),
+            [
+                Directive::Note {
+                    text: "needs review".to_string()
+                },
+                Directive::Tag {
+                    tag: "ci".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn add_tag_deduplicates() {
+        assert_eq!(add_tag(None, "ci"), "ci");
+        assert_eq!(add_tag(Some("ci"), "review"), "ci,review");
+        assert_eq!(add_tag(Some("ci,review"), "ci"), "ci,review");
+        assert_eq!(add_tag(Some(""), " "), "");
+    }
+
+    #[test]
+    fn validate_tag_rejects_separators() {
+        assert!(validate_tag("team:infra").is_ok());
+        assert!(validate_tag("a,b").is_err());
+        assert!(validate_tag("has space").is_err());
+        assert!(validate_tag("--force").is_err());
+        assert!(validate_tag("").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_plugins_only_returns_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        for (name, mode) in [("b-plugin", 0o755), ("a-plugin", 0o700), ("README", 0o644)] {
+            let path = tmp.path().join(name);
+            fs::write(&path, "").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        let names: Vec<String> = find_plugins(tmp.path())
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["a-plugin", "b-plugin"]);
+    }
+}
@@ -0,0 +1,370 @@
+//! Progress reporting for long-running workflows.
+//!
+//! Workflows report steps, notes and warnings through this module instead of
+//! printing directly, so their output adapts to where it ends up:
+//!
+//! - [`Mode::Tty`]: spinners on stderr, messages on stdout
+//! - [`Mode::Plain`]: one line per event, no cursor movement. Used when
+//!   stderr is not a terminal, e.g. when run from a keybinding or a script
+//! - [`Mode::Json`]: one JSON object per line on stdout (`--json`)
+//!
+//! Workflows report through the [`Reporter`] in their `WorkflowContext`,
+//! which is [`Terminal`] for the CLI. Embedders can pass their own to capture
+//! or silence the events.
+
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// How progress is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Tty,
+    Plain,
+    Json,
+}
+
+const UNSET: u8 = 0;
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+impl Mode {
+    fn to_u8(self) -> u8 {
+        match self {
+            Mode::Tty => 1,
+            Mode::Plain => 2,
+            Mode::Json => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Mode::Tty),
+            2 => Some(Mode::Plain),
+            3 => Some(Mode::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Override the detected mode for the rest of the process.
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode.to_u8(), Ordering::Relaxed);
+}
+
+/// Current mode. Defaults to [`Mode::Tty`] when stderr is a terminal and
+/// [`Mode::Plain`] otherwise.
+pub fn mode() -> Mode {
+    Mode::from_u8(MODE.load(Ordering::Relaxed)).unwrap_or_else(|| {
+        if std::io::stderr().is_terminal() {
+            Mode::Tty
+        } else {
+            Mode::Plain
+        }
+    })
+}
+
+/// Status of a step in JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Started,
+    Ok,
+    Failed,
+}
+
+/// A single line of progress output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event<'a> {
+    Step {
+        message: &'a str,
+        status: StepStatus,
+    },
+    Info {
+        message: &'a str,
+    },
+    Success {
+        message: &'a str,
+    },
+    Warning {
+        message: &'a str,
+    },
+    /// Final machine-readable outcome of a command, only emitted in JSON mode
+    Result {
+        data: serde_json::Value,
+    },
+}
+
+impl Event<'_> {
+    /// Line printed in plain mode, or `None` for events it doesn't show.
+    pub fn plain(&self) -> Option<String> {
+        match self {
+            Event::Step {
+                message,
+                status: StepStatus::Started,
+            } => Some(format!("{}...", message)),
+            Event::Step {
+                message,
+                status: StepStatus::Ok,
+            } => Some(format!("✔ {}", message)),
+            Event::Step {
+                message,
+                status: StepStatus::Failed,
+            } => Some(format!("✘ {}", message)),
+            Event::Info { message } => Some(message.to_string()),
+            Event::Success { message } => Some(format!("✓ {}", message)),
+            Event::Warning { message } => Some(format!("Warning: {}", message)),
+            Event::Result { .. } => None,
+        }
+    }
+
+    /// Whether plain and TTY modes print this event to stderr.
+    fn is_diagnostic(&self) -> bool {
+        matches!(self, Event::Step { .. } | Event::Warning { .. })
+    }
+}
+
+fn emit(event: &Event) {
+    match mode() {
+        Mode::Json => {
+            if let Ok(line) = serde_json::to_string(&event) {
+                let mut stdout = std::io::stdout().lock();
+                let _ = writeln!(stdout, "{}", line);
+                let _ = stdout.flush();
+            }
+        }
+        Mode::Tty | Mode::Plain => {
+            let Some(line) = event.plain() else { return };
+            if event.is_diagnostic() {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Destination of progress events.
+pub trait Reporter: Send + Sync {
+    fn report(&self, event: &Event);
+
+    /// Whether steps run under a spinner instead of reporting start and
+    /// finish events.
+    fn spinners(&self) -> bool {
+        false
+    }
+
+    /// Whether `Result` events are wanted.
+    fn wants_result(&self) -> bool {
+        true
+    }
+}
+
+/// Reports to the terminal, or as JSON lines, following [`mode`].
+pub struct Terminal;
+
+impl Reporter for Terminal {
+    fn report(&self, event: &Event) {
+        emit(event);
+    }
+
+    fn spinners(&self) -> bool {
+        mode() == Mode::Tty
+    }
+
+    fn wants_result(&self) -> bool {
+        mode() == Mode::Json
+    }
+}
+
+impl dyn Reporter + '_ {
+    /// Run `op` as a named step: a spinner on a terminal, start and finish
+    /// events otherwise.
+    pub fn step<T, F>(&self, message: &str, op: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        if self.spinners() {
+            return crate::spinner::with_spinner(message, op);
+        }
+
+        self.report(&Event::Step {
+            message,
+            status: StepStatus::Started,
+        });
+        let result = op();
+        let status = if result.is_ok() {
+            StepStatus::Ok
+        } else {
+            StepStatus::Failed
+        };
+        self.report(&Event::Step { message, status });
+        result
+    }
+
+    /// Report a neutral status message.
+    pub fn info(&self, message: impl AsRef<str>) {
+        self.report(&Event::Info {
+            message: message.as_ref(),
+        });
+    }
+
+    /// Report a completed action.
+    pub fn success(&self, message: impl AsRef<str>) {
+        self.report(&Event::Success {
+            message: message.as_ref(),
+        });
+    }
+
+    /// Report a problem that doesn't stop the workflow.
+    pub fn warn(&self, message: impl AsRef<str>) {
+        self.report(&Event::Warning {
+            message: message.as_ref(),
+        });
+    }
+
+    /// Report the outcome of a command for scripts.
+    pub fn result(&self, data: &impl Serialize) {
+        if !self.wants_result() {
+            return;
+        }
+        if let Ok(data) = serde_json::to_value(data) {
+            self.report(&Event::Result { data });
+        }
+    }
+}
+
+/// [`Terminal`] as a reporter, for code that runs outside a workflow.
+fn terminal() -> &'static dyn Reporter {
+    &Terminal
+}
+
+/// Run `op` as a named step on the terminal. See [`Reporter`].
+pub fn step<T, F>(message: &str, op: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    terminal().step(message, op)
+}
+
+/// Report a neutral status message.
+pub fn info(message: impl AsRef<str>) {
+    terminal().info(message);
+}
+
+/// Report a completed action.
+pub fn success(message: impl AsRef<str>) {
+    terminal().success(message);
+}
+
+/// Report a problem that doesn't stop the workflow.
+pub fn warn(message: impl AsRef<str>) {
+    terminal().warn(message);
+}
+
+/// Report the outcome of a command for scripts. Ignored outside JSON mode.
+pub fn result(data: &impl Serialize) {
+    terminal().result(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_lines() {
+        let step = |status| Event::Step {
+            message: "Fetching",
+            status,
+        };
+        assert_eq!(
+            step(StepStatus::Started).plain().as_deref(),
+            Some("Fetching...")
+        );
+        assert_eq!(step(StepStatus::Ok).plain().as_deref(), Some("✔ Fetching"));
+        assert_eq!(
+            step(StepStatus::Failed).plain().as_deref(),
+            Some("✘ Fetching")
+        );
+        assert_eq!(
+            Event::Warning {
+                message: "no remote"
+            }
+            .plain()
+            .as_deref(),
+            Some("Warning: no remote")
+        );
+        assert_eq!(
+            Event::Result {
+                data: serde_json::json!({})
+            }
+            .plain(),
+            None
+        );
+    }
+
+    /// Collects the plain lines of the events it receives.
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<String>>);
+
+    impl Reporter for Recorder {
+        fn report(&self, event: &Event) {
+            let line = match event {
+                Event::Result { data } => data.to_string(),
+                _ => event.plain().unwrap_or_default(),
+            };
+            self.0.lock().unwrap().push(line);
+        }
+    }
+
+    #[test]
+    fn custom_reporters_receive_every_event() {
+        let recorder = Recorder::default();
+        let reporter: &dyn Reporter = &recorder;
+        reporter.step("Fetching", || Ok(())).unwrap();
+        assert!(
+            reporter
+                .step("Pushing", || Err::<(), _>(anyhow::anyhow!("rejected")))
+                .is_err()
+        );
+        reporter.info("PR #7");
+        reporter.warn("no remote");
+        reporter.result(&serde_json::json!({"kept": true}));
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "Fetching...",
+                "✔ Fetching",
+                "Pushing...",
+                "✘ Pushing",
+                "PR #7",
+                "Warning: no remote",
+                r#"{"kept":true}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn json_events() {
+        let json = |event: Event| serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json(Event::Step {
+                message: "Fetching",
+                status: StepStatus::Failed
+            }),
+            serde_json::json!({"event": "step", "message": "Fetching", "status": "failed"})
+        );
+        assert_eq!(
+            json(Event::Success { message: "Merged" }),
+            serde_json::json!({"event": "success", "message": "Merged"})
+        );
+        assert_eq!(
+            json(Event::Result {
+                data: serde_json::json!({"branch": "feature"})
+            }),
+            serde_json::json!({"event": "result", "data": {"branch": "feature"}})
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
 
 #[derive(Debug, Clone)]
 pub enum Prompt {
@@ -27,6 +29,40 @@ impl Prompt {
 pub struct PromptMetadata {
     #[serde(default)]
     pub foreach: Option<BTreeMap<String, Vec<String>>>,
+
+    /// System instructions, passed separately from the task to agents that
+    /// support it (e.g. Claude's `--append-system-prompt`)
+    #[serde(default)]
+    pub system: Option<String>,
+
+    /// Files (relative to the worktree) whose contents are added to the
+    /// system instructions
+    #[serde(default)]
+    pub context: Option<Vec<String>>,
+}
+
+/// Frontmatter kept in the written prompt file so the system/task split
+/// survives until the agent command is built.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct PromptStructure {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<String>>,
+}
+
+/// A prompt split into system instructions and the task itself.
+#[derive(Debug, PartialEq)]
+pub struct StructuredPrompt {
+    pub system: String,
+    pub task: String,
+}
+
+impl StructuredPrompt {
+    /// Single prompt for agents without a separate system prompt option.
+    pub fn combined(&self) -> String {
+        format!("{}\n\n{}", self.system.trim_end(), self.task)
+    }
 }
 
 #[derive(Debug)]
@@ -37,7 +73,7 @@ pub struct PromptDocument {
 
 /// Split frontmatter from markdown content.
 /// Returns (Some(frontmatter_yaml), body) if frontmatter exists, or (None, content) if not.
-fn split_frontmatter(content: &str) -> (Option<String>, &str) {
+pub(crate) fn split_frontmatter(content: &str) -> (Option<String>, &str) {
     let lines: Vec<&str> = content.lines().collect();
 
     // Check if content starts with "---"
@@ -99,6 +135,83 @@ pub fn parse_prompt_document(prompt: &Prompt) -> Result<PromptDocument> {
     })
 }
 
+/// Re-attach the `system`/`context` frontmatter to a rendered prompt body.
+///
+/// Returns the body unchanged when the prompt has no structure.
+pub fn with_structure(
+    system: Option<&str>,
+    context: Option<&[String]>,
+    body: &str,
+) -> Result<String> {
+    if system.is_none() && context.is_none_or(|c| c.is_empty()) {
+        return Ok(body.to_string());
+    }
+    let structure = PromptStructure {
+        system: system.map(str::to_string),
+        context: context.map(<[String]>::to_vec),
+    };
+    let yaml =
+        serde_yaml::to_string(&structure).context("Failed to serialize prompt frontmatter")?;
+    Ok(format!("---\n{}---\n{}", yaml, body))
+}
+
+/// Split a prompt with `system`/`context` frontmatter into its parts.
+///
+/// Context files are resolved against `base_dir` and appended to the system
+/// instructions. Returns `None` for prompts without such frontmatter, which
+/// are passed to the agent as-is.
+pub fn split_structured(content: &str, base_dir: &Path) -> Result<Option<StructuredPrompt>> {
+    let (Some(yaml), body) = split_frontmatter(content) else {
+        return Ok(None);
+    };
+    // Frontmatter that isn't ours (or isn't YAML) is left for the agent to see
+    let Ok(structure) = serde_yaml::from_str::<PromptStructure>(&yaml) else {
+        return Ok(None);
+    };
+    let context = structure.context.unwrap_or_default();
+    if structure.system.is_none() && context.is_empty() {
+        return Ok(None);
+    }
+
+    let mut system = structure.system.unwrap_or_default().trim_end().to_string();
+    for file in &context {
+        let path = base_dir.join(file);
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read prompt context file '{}'", path.display()))?;
+        if !system.is_empty() {
+            system.push_str("\n\n");
+        }
+        system.push_str(&format!(
+            "<context file=\"{}\">\n{}\n</context>",
+            file,
+            text.trim_end()
+        ));
+    }
+
+    Ok(Some(StructuredPrompt {
+        system,
+        task: body.to_string(),
+    }))
+}
+
+/// Path of the system part written next to a structured prompt file.
+pub fn system_part_path(prompt_file: &Path) -> PathBuf {
+    part_path(prompt_file, "system")
+}
+
+/// Path of the task part written next to a structured prompt file.
+pub fn task_part_path(prompt_file: &Path) -> PathBuf {
+    part_path(prompt_file, "task")
+}
+
+fn part_path(prompt_file: &Path, part: &str) -> PathBuf {
+    let stem = prompt_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    prompt_file.with_file_name(format!("{}.{}.md", stem, part))
+}
+
 /// Convert frontmatter foreach (BTreeMap<String, Vec<String>>) to matrix rows.
 /// Validates that all value lists have equal length (zip constraint).
 pub fn foreach_from_frontmatter(
@@ -144,6 +257,130 @@ pub fn foreach_from_frontmatter(
     Ok(rows)
 }
 
+/// Reject prompts larger than `max_kb` with an actionable error.
+///
+/// Agents receive the prompt as a single command-line argument, so oversized
+/// prompts would otherwise fail later with an opaque OS or tmux error.
+pub fn ensure_size(content: &str, max_kb: u64) -> Result<()> {
+    let size_kb = content.len().div_ceil(1024) as u64;
+    if size_kb > max_kb {
+        return Err(anyhow::anyhow!(
+            "Prompt is {} KB, which exceeds the {} KB limit. \
+             Shorten it or raise 'max_prompt_kb' in your config.",
+            size_kb,
+            max_kb
+        ));
+    }
+    Ok(())
+}
+
+/// Append references to attached files (paths relative to the worktree) to
+/// a prompt, in the syntax `agent` reads files with.
+pub fn with_attachments(content: &str, paths: &[String], agent: Option<&str>) -> String {
+    let profile = crate::multiplexer::agent::resolve_profile(agent);
+    let mut out = content.trim_end().to_string();
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str("Attached files:");
+    for path in paths {
+        out.push_str("\n- ");
+        out.push_str(&profile.file_reference(path));
+    }
+    out
+}
+
+/// Longest title derived from a prompt, in characters.
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Short title for the agent working on a prompt: the first line of text
+/// after any frontmatter, without Markdown heading marks, cut at a word
+/// boundary. `None` when the prompt has no text.
+pub fn title_summary(content: &str) -> Option<String> {
+    let (_, body) = split_frontmatter(content);
+    let line = body
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())?;
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let line = words.join(" ");
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return Some(line);
+    }
+    // Whole words that fit next to the ellipsis; a single long word is cut
+    let mut title = String::new();
+    for word in &words {
+        let len = title.chars().count() + usize::from(!title.is_empty()) + word.chars().count();
+        if len >= MAX_TITLE_CHARS {
+            break;
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    if title.is_empty() {
+        title = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+    }
+    title.push('…');
+    Some(title)
+}
+
+/// Project text wrapped around every prompt: conventions and rules from
+/// `prompt_prefix` and `prompt_suffix` in config.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Guardrails<'a> {
+    prefix: Option<&'a str>,
+    suffix: Option<&'a str>,
+}
+
+impl<'a> Guardrails<'a> {
+    pub fn from_config(config: &'a Config) -> Self {
+        let text = |s: &'a Option<String>| s.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        Self {
+            prefix: text(&config.prompt_prefix),
+            suffix: text(&config.prompt_suffix),
+        }
+    }
+
+    /// `task` between the prefix and the suffix, separated by blank lines.
+    pub fn wrap(&self, task: &str) -> String {
+        let parts: Vec<&str> = [self.prefix, Some(task.trim()), self.suffix]
+            .into_iter()
+            .flatten()
+            .collect();
+        parts.join("\n\n")
+    }
+
+    /// `text` without the prefix and suffix `wrap` added.
+    pub fn unwrap<'t>(&self, text: &'t str) -> &'t str {
+        let mut text = text.trim();
+        if let Some(rest) = self.prefix.and_then(|p| text.strip_prefix(p)) {
+            text = rest.trim_start();
+        }
+        if let Some(rest) = self.suffix.and_then(|s| text.strip_suffix(s)) {
+            text = rest.trim_end();
+        }
+        text
+    }
+}
+
+/// Representation of a prompt for log messages.
+///
+/// When `redact` is set only the size is logged; otherwise the text is
+/// truncated to keep log lines readable.
+pub fn log_repr(content: &str, redact: bool) -> String {
+    const MAX_LOGGED_CHARS: usize = 200;
+    if redact {
+        return format!("<redacted, {} bytes>", content.len());
+    }
+    let mut text: String = content.chars().take(MAX_LOGGED_CHARS).collect();
+    if content.chars().nth(MAX_LOGGED_CHARS).is_some() {
+        text.push('…');
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,6 +396,29 @@ mod tests {
         assert_eq!(body, "\nBody content here");
     }
 
+    #[test]
+    fn title_summary_uses_first_line_of_text() {
+        assert_eq!(
+            title_summary("---\nsystem: Be terse.\n---\n\n# Fix  the login\n\nDetails").as_deref(),
+            Some("Fix the login")
+        );
+        assert_eq!(title_summary("\n  \n"), None);
+
+        let long = "Refactor the authentication module so that sessions expire after inactivity";
+        let title = title_summary(long).unwrap();
+        assert_eq!(
+            title,
+            "Refactor the authentication module so that sessions expire…"
+        );
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+
+        let word = "x".repeat(100);
+        assert_eq!(
+            title_summary(&word).unwrap().chars().count(),
+            MAX_TITLE_CHARS
+        );
+    }
+
     #[test]
     fn split_frontmatter_handles_no_frontmatter() {
         let content = "Just body content";
@@ -242,6 +502,67 @@ mod tests {
         assert!(doc.meta.foreach.is_none());
     }
 
+    #[test]
+    fn parse_prompt_document_reads_system_and_context() {
+        let prompt = Prompt::Inline(
+            "---\nsystem: Be terse.\ncontext:\n  - docs/api.md\n---\nFix the bug".to_string(),
+        );
+        let doc = parse_prompt_document(&prompt).unwrap();
+        assert_eq!(doc.meta.system.as_deref(), Some("Be terse."));
+        assert_eq!(doc.meta.context, Some(vec!["docs/api.md".to_string()]));
+        assert_eq!(doc.body, "Fix the bug");
+    }
+
+    #[test]
+    fn with_structure_roundtrips_through_split_structured() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notes.md"), "API is v2\n").unwrap();
+        let context = vec!["notes.md".to_string()];
+
+        let content = with_structure(Some("Be terse."), Some(&context), "Fix the bug").unwrap();
+        let parts = split_structured(&content, dir.path()).unwrap().unwrap();
+
+        assert_eq!(
+            parts.system,
+            "Be terse.\n\n<context file=\"notes.md\">\nAPI is v2\n</context>"
+        );
+        assert_eq!(parts.task, "Fix the bug");
+        assert!(parts.combined().ends_with("</context>\n\nFix the bug"));
+    }
+
+    #[test]
+    fn unstructured_prompts_pass_through() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(with_structure(None, None, "plain").unwrap(), "plain");
+        assert_eq!(split_structured("plain", dir.path()).unwrap(), None);
+        // Frontmatter without system/context is left for the agent
+        assert_eq!(
+            split_structured("---\ntitle: x\n---\nbody", dir.path()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn split_structured_reports_missing_context_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "---\ncontext: [missing.md]\n---\ntask";
+        let err = split_structured(content, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("missing.md"));
+    }
+
+    #[test]
+    fn part_paths_sit_next_to_prompt_file() {
+        let prompt = Path::new("/wt/.workmux/PROMPT-feature.md");
+        assert_eq!(
+            system_part_path(prompt),
+            Path::new("/wt/.workmux/PROMPT-feature.system.md")
+        );
+        assert_eq!(
+            task_part_path(prompt),
+            Path::new("/wt/.workmux/PROMPT-feature.task.md")
+        );
+    }
+
     #[test]
     fn foreach_from_frontmatter_creates_rows() {
         let mut map = BTreeMap::new();
@@ -298,4 +619,56 @@ mod tests {
                 .contains("at least one value")
         );
     }
+
+    #[test]
+    fn ensure_size_rejects_oversized_prompts() {
+        assert!(ensure_size("short prompt", 1).is_ok());
+        assert!(ensure_size(&"x".repeat(1024), 1).is_ok());
+        let err = ensure_size(&"x".repeat(1025), 1).unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1 KB limit"));
+    }
+
+    #[test]
+    fn with_attachments_references_files_for_the_agent() {
+        let paths = vec![".workmux/attachments/spec.pdf".to_string()];
+        assert_eq!(
+            with_attachments("Implement the spec\n", &paths, Some("claude")),
+            "Implement the spec\n\nAttached files:\n- @.workmux/attachments/spec.pdf"
+        );
+        assert_eq!(
+            with_attachments("", &paths, Some("codex")),
+            "Attached files:\n- .workmux/attachments/spec.pdf"
+        );
+    }
+
+    #[test]
+    fn guardrails_wrap_and_unwrap_the_task() {
+        let config = Config {
+            prompt_prefix: Some("Don't edit generated files.\n".to_string()),
+            prompt_suffix: Some("  ".to_string()),
+            ..Config::default()
+        };
+        let guardrails = Guardrails::from_config(&config);
+        let wrapped = guardrails.wrap("\nFix the login\n");
+        assert_eq!(wrapped, "Don't edit generated files.\n\nFix the login");
+        assert_eq!(guardrails.unwrap(&wrapped), "Fix the login");
+
+        let config = Config {
+            prompt_suffix: Some("Use conventional commits.".to_string()),
+            ..Config::default()
+        };
+        let guardrails = Guardrails::from_config(&config);
+        let wrapped = guardrails.wrap("Fix the login");
+        assert_eq!(wrapped, "Fix the login\n\nUse conventional commits.");
+        assert_eq!(guardrails.unwrap(&wrapped), "Fix the login");
+        assert_eq!(Guardrails::default().wrap(" task "), "task");
+    }
+
+    #[test]
+    fn log_repr_redacts_or_truncates() {
+        assert_eq!(log_repr("secret plan", true), "<redacted, 11 bytes>");
+        assert_eq!(log_repr("secret plan", false), "secret plan");
+        let long = "a".repeat(250);
+        assert_eq!(log_repr(&long, false).chars().count(), 201);
+    }
 }
@@ -13,6 +13,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::SandboxRuntime;
 use crate::sandbox::DEFAULT_IMAGE_REGISTRY;
+use crate::state::schema::{self, StateKind};
 
 /// How long to cache freshness check results (24 hours in seconds).
 const CACHE_TTL_SECONDS: u64 = 24 * 60 * 60;
@@ -62,8 +63,9 @@ fn load_cache(image: &str) -> Option<FreshnessCache> {
         return None;
     }
 
+    // A cache written by a newer version is a miss, like a corrupted one
     let contents = fs::read_to_string(&cache_path).ok()?;
-    let cache: FreshnessCache = serde_json::from_str(&contents).ok()?;
+    let cache: FreshnessCache = schema::parse(StateKind::ImageFreshness, &contents).ok()?;
 
     // Check if cache is for the same image
     if cache.image != image {
@@ -94,7 +96,8 @@ fn save_cache(image: &str, is_fresh: bool, local_image_id: Option<String>) -> Re
         local_image_id,
     };
 
-    let json = serde_json::to_string_pretty(&cache).context("Failed to serialize cache")?;
+    let json =
+        schema::to_json(StateKind::ImageFreshness, &cache).context("Failed to serialize cache")?;
 
     fs::write(&cache_path, json)
         .with_context(|| format!("Failed to write cache file: {}", cache_path.display()))?;
@@ -180,8 +183,24 @@ fn get_remote_digest(image: &str) -> Result<String> {
     anyhow::bail!("Could not find Digest in imagetools output");
 }
 
-/// Perform the freshness check and print hint if stale.
-fn check_freshness(image: &str, runtime: SandboxRuntime) -> Result<bool> {
+/// Tell the user about a newer image, or with `prefetch_agent` set, pull it
+/// in the background.
+fn report_stale(prefetch_agent: Option<&str>) {
+    if let Some(agent) = prefetch_agent {
+        match crate::sandbox::preheat::spawn_in_background(&[agent]) {
+            Ok(_) => return,
+            Err(e) => tracing::debug!(error = %e, "freshness:background pull failed to start"),
+        }
+    }
+    eprintln!("hint: a newer sandbox image is available (run `workmux sandbox pull` to update)");
+}
+
+/// Perform the freshness check and report if stale.
+fn check_freshness(
+    image: &str,
+    runtime: SandboxRuntime,
+    prefetch_agent: Option<&str>,
+) -> Result<bool> {
     let runtime_bin = match runtime {
         SandboxRuntime::Docker => "docker",
         SandboxRuntime::Podman => "podman",
@@ -198,9 +217,7 @@ fn check_freshness(image: &str, runtime: SandboxRuntime) -> Result<bool> {
     let is_fresh = local_digests.iter().any(|d| d.contains(&remote_digest));
 
     if !is_fresh {
-        eprintln!(
-            "hint: a newer sandbox image is available (run `workmux sandbox pull` to update)"
-        );
+        report_stale(prefetch_agent);
     }
 
     Ok(is_fresh)
@@ -225,11 +242,16 @@ pub fn mark_fresh(image: &str, runtime: SandboxRuntime) {
 /// 1. Checks if image is from official registry (returns early if not)
 /// 2. Checks cache (returns early if recently checked)
 /// 3. Compares local vs remote digests
-/// 4. Prints hint to stderr if stale
+/// 4. Prints hint to stderr if stale, or with `prefetch_agent` set, starts
+///    a background pull of that agent's image
 /// 5. Updates cache with result
 ///
 /// Silent on any failure (network issues, missing commands, etc.)
-pub fn check_in_background(image: String, runtime: SandboxRuntime) {
+pub fn check_in_background(
+    image: String,
+    runtime: SandboxRuntime,
+    prefetch_agent: Option<&'static str>,
+) {
     std::thread::spawn(move || {
         // Only check official images from our registry
         if !image.starts_with(DEFAULT_IMAGE_REGISTRY) {
@@ -253,9 +275,7 @@ pub fn check_in_background(image: String, runtime: SandboxRuntime) {
                 && cache.local_image_id.as_deref() == Some(&current_id)
             {
                 // Same local image, still stale
-                eprintln!(
-                    "hint: a newer sandbox image is available (run `workmux sandbox pull` to update)"
-                );
+                report_stale(prefetch_agent);
                 return;
             }
             // Local image changed or couldn't be checked - fall through to re-check
@@ -263,7 +283,7 @@ pub fn check_in_background(image: String, runtime: SandboxRuntime) {
 
         // Perform freshness check
         let local_id = get_local_image_id(runtime_bin, &image).ok();
-        match check_freshness(&image, runtime) {
+        match check_freshness(&image, runtime, prefetch_agent) {
             Ok(is_fresh) => {
                 // Save result to cache (ignore errors)
                 let _ = save_cache(&image, is_fresh, local_id);
@@ -0,0 +1,270 @@
+//! Read-only git queries a sandbox guest can ask the host to answer.
+//!
+//! Guests only see their own worktree, so they can't look at the branch they
+//! were created from or at other refs. The `GitQuery` RPC request runs one of
+//! a fixed set of queries against the host repository instead. Refs and paths
+//! are validated before git sees them, and git runs with hooks, fsmonitor,
+//! external diff drivers and textconv filters disabled, since the guest can
+//! write to the repository's `.git` directory.
+
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::cmd::Cmd;
+use crate::config::Config;
+use crate::git;
+
+/// Commits listed by `base_log` unless the guest asks for a number.
+const DEFAULT_LOG_LIMIT: u32 = 20;
+
+/// Most commits `base_log` lists.
+const MAX_LOG_LIMIT: u32 = 200;
+
+/// Longest ref name accepted.
+const MAX_REF_LEN: usize = 200;
+
+/// Longest path accepted.
+const MAX_PATH_LEN: usize = 4096;
+
+/// Output beyond this many bytes is cut off.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// A read-only question about the host repository.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "query", rename_all = "snake_case")]
+pub enum GitQuery {
+    /// One line per commit of the worktree's base branch, newest first
+    BaseLog {
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    /// `git diff --stat` of the worktree's commits against its base branch
+    DiffStat,
+    /// Content of a file at a ref
+    Show { git_ref: String, path: String },
+}
+
+/// Answer `query` for the worktree at `worktree_path`.
+pub fn run(query: &GitQuery, worktree_path: &Path) -> Result<String> {
+    let output = match query {
+        GitQuery::BaseLog { limit } => {
+            let base = base_branch(worktree_path)?;
+            let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+            git_output(
+                worktree_path,
+                &[
+                    "log",
+                    "--no-show-signature",
+                    "--format=%h %ad %an %s",
+                    "--date=short",
+                    &format!("--max-count={}", limit),
+                    &base,
+                    "--",
+                ],
+            )?
+        }
+        GitQuery::DiffStat => {
+            let base = base_branch(worktree_path)?;
+            git_output(
+                worktree_path,
+                &[
+                    "diff",
+                    "--no-ext-diff",
+                    "--no-textconv",
+                    "--stat",
+                    &format!("{}...HEAD", base),
+                    "--",
+                ],
+            )?
+        }
+        GitQuery::Show { git_ref, path } => {
+            validate_ref(git_ref)?;
+            validate_path(path)?;
+            ensure_commit(worktree_path, git_ref)?;
+            git_output(
+                worktree_path,
+                &["show", "--no-textconv", &format!("{}:{}", git_ref, path)],
+            )?
+        }
+    };
+    Ok(truncate_output(output))
+}
+
+/// Check that `git_ref` is a plain ref name or revision: no options, ranges,
+/// reflog selectors or path separators git would read as something else.
+pub fn validate_ref(git_ref: &str) -> Result<()> {
+    if git_ref.is_empty() || git_ref.len() > MAX_REF_LEN {
+        bail!("Invalid ref: must be 1 to {} characters", MAX_REF_LEN);
+    }
+    if git_ref.starts_with(['-', '/']) {
+        bail!("Invalid ref '{}': must not start with '-' or '/'", git_ref);
+    }
+    if git_ref.contains("..") || git_ref.contains("//") {
+        bail!("Invalid ref '{}': ranges are not supported", git_ref);
+    }
+    if let Some(c) = git_ref
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || "._/-~^".contains(*c)))
+    {
+        bail!("Invalid ref '{}': '{}' is not allowed", git_ref, c);
+    }
+    Ok(())
+}
+
+/// Check that `path` is a relative path inside the repository.
+pub fn validate_path(path: &str) -> Result<()> {
+    if path.is_empty() || path.len() > MAX_PATH_LEN {
+        bail!("Invalid path: must be 1 to {} bytes", MAX_PATH_LEN);
+    }
+    if path.chars().any(char::is_control) {
+        bail!("Invalid path: control characters are not allowed");
+    }
+    if !Path::new(path)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        bail!(
+            "Invalid path '{}': must be relative, without '.' or '..'",
+            path
+        );
+    }
+    Ok(())
+}
+
+/// Base branch of the worktree's branch: the one recorded when it was
+/// created, then `base_branches`, then the default branch.
+fn base_branch(worktree_path: &Path) -> Result<String> {
+    let branch = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&["branch", "--show-current"])
+        .run_and_capture_stdout()
+        .context("Failed to read the worktree's branch")?;
+    let config = Config::load(None).unwrap_or_default();
+    let base = git::get_branch_base_in(&branch, Some(worktree_path))
+        .ok()
+        .or_else(|| config.base_for_branch(&branch).map(str::to_string))
+        .map_or_else(|| git::get_default_branch_in(Some(worktree_path)), Ok)?;
+    // The base comes from repository config the guest can write to
+    validate_ref(&base).with_context(|| format!("Base branch of '{}'", branch))?;
+    ensure_commit(worktree_path, &base)?;
+    Ok(base)
+}
+
+/// Fail unless `git_ref` names a commit in the repository.
+fn ensure_commit(worktree_path: &Path, git_ref: &str) -> Result<()> {
+    let found = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", git_ref),
+        ])
+        .run_as_check()?;
+    if !found {
+        return Err(anyhow!("Unknown ref '{}'", git_ref));
+    }
+    Ok(())
+}
+
+/// Run git read-only in the worktree, without anything the repository's
+/// config could make it execute.
+fn git_output(worktree_path: &Path, args: &[&str]) -> Result<String> {
+    let mut full_args = vec![
+        "-c",
+        "core.hooksPath=/dev/null",
+        "-c",
+        "core.fsmonitor=false",
+        "--no-pager",
+    ];
+    full_args.extend_from_slice(args);
+    let output = Cmd::new("git")
+        .workdir(worktree_path)
+        .args(&full_args)
+        .run()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Cut `output` off at `MAX_OUTPUT_BYTES`, on a character boundary.
+fn truncate_output(mut output: String) -> String {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output;
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    output.truncate(end);
+    output.push_str("\n[output truncated]\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refs_are_validated() {
+        for ok in [
+            "main",
+            "origin/main",
+            "v1.2.0",
+            "HEAD~3",
+            "feature/x^",
+            "a1b2c3d",
+        ] {
+            assert!(validate_ref(ok).is_ok(), "{}", ok);
+        }
+        for bad in [
+            "",
+            "-n",
+            "--output=/tmp/x",
+            "/etc",
+            "main..feature",
+            "main@{1}",
+            "main:secret",
+            "a b",
+            "main\n",
+            "$(id)",
+        ] {
+            assert!(validate_ref(bad).is_err(), "{:?}", bad);
+        }
+        assert!(validate_ref(&"a".repeat(MAX_REF_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn paths_are_validated() {
+        for ok in ["README.md", "src/main.rs", "docs/a b.md", "-odd-name"] {
+            assert!(validate_path(ok).is_ok(), "{}", ok);
+        }
+        for bad in ["", "/etc/passwd", "../secret", "src/../../x", "./a", "a\nb"] {
+            assert!(validate_path(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn queries_round_trip_through_json() {
+        let query: GitQuery =
+            serde_json::from_str(r#"{"query":"show","git_ref":"main","path":"a.rs"}"#).unwrap();
+        assert_eq!(
+            query,
+            GitQuery::Show {
+                git_ref: "main".to_string(),
+                path: "a.rs".to_string()
+            }
+        );
+        let query: GitQuery = serde_json::from_str(r#"{"query":"base_log"}"#).unwrap();
+        assert_eq!(query, GitQuery::BaseLog { limit: None });
+        assert!(serde_json::from_str::<GitQuery>(r#"{"query":"push"}"#).is_err());
+    }
+
+    #[test]
+    fn long_output_is_truncated_on_a_char_boundary() {
+        let output = truncate_output("é".repeat(MAX_OUTPUT_BYTES));
+        assert!(output.ends_with("[output truncated]\n"));
+        assert!(output.len() <= MAX_OUTPUT_BYTES + 20);
+        assert_eq!(truncate_output("short".to_string()), "short");
+    }
+}
@@ -0,0 +1,93 @@
+//! Booting a Lima VM in the background while a worktree is set up.
+//!
+//! Creating or starting a VM takes much longer than creating a worktree, so
+//! `workmux add` starts the boot first and only the panes that run inside
+//! the VM wait for it, right before their command is sent.
+
+use anyhow::{Result, anyhow};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::thread::JoinHandle;
+use tracing::debug;
+
+use crate::config::Config;
+
+/// A Lima VM boot running on a background thread.
+#[derive(Debug)]
+pub struct VmBoot {
+    handle: Mutex<Option<JoinHandle<Result<String>>>>,
+    /// VM name, or the boot error, once the boot has been waited for
+    outcome: OnceLock<std::result::Result<String, String>>,
+}
+
+impl VmBoot {
+    /// Start booting the VM for the project `repo_root` belongs to. VM
+    /// mounts cover the whole project, so any of its worktrees will do.
+    pub fn start(config: &Config, repo_root: &Path) -> Self {
+        let config = config.clone();
+        let repo_root = repo_root.to_path_buf();
+        debug!(repo_root = %repo_root.display(), "starting Lima VM boot in the background");
+        let handle =
+            std::thread::spawn(move || super::ensure_vm_running_quietly(&config, &repo_root));
+        Self {
+            handle: Mutex::new(Some(handle)),
+            outcome: OnceLock::new(),
+        }
+    }
+
+    /// Wait for the boot to finish and return the VM name. The first caller
+    /// shows a spinner while the VM is still booting; later callers get the
+    /// same outcome right away.
+    pub fn wait(&self) -> Result<String> {
+        self.outcome
+            .get_or_init(|| {
+                let handle = self.handle.lock().ok().and_then(|mut h| h.take());
+                let Some(handle) = handle else {
+                    return Err("Lima VM boot was not started".to_string());
+                };
+                let finished = handle.is_finished();
+                let join = || {
+                    handle
+                        .join()
+                        .map_err(|_| anyhow!("Lima VM boot thread panicked"))?
+                };
+                let result = if finished {
+                    join()
+                } else {
+                    crate::spinner::with_spinner("Waiting for the Lima VM to boot", join)
+                };
+                result.map_err(|e| format!("{:#}", e))
+            })
+            .clone()
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(result: Result<String>) -> VmBoot {
+        VmBoot {
+            handle: Mutex::new(Some(std::thread::spawn(move || result))),
+            outcome: OnceLock::new(),
+        }
+    }
+
+    #[test]
+    fn every_waiter_gets_the_outcome() {
+        let boot = finished(Ok("wm-project-1234".to_string()));
+        assert_eq!(boot.wait().unwrap(), "wm-project-1234");
+        assert_eq!(boot.wait().unwrap(), "wm-project-1234");
+
+        let boot = finished(Err(anyhow!("limactl start failed")));
+        for _ in 0..2 {
+            assert!(
+                boot.wait()
+                    .unwrap_err()
+                    .to_string()
+                    .contains("limactl start failed")
+            );
+        }
+    }
+}
@@ -147,6 +147,15 @@ pub fn generate_lima_config(
                 );
             }
 
+            // Lima's default 9p cache modes assume the host is the only
+            // writer, which serves stale files from NFS/SMB mounts. Only
+            // applies to QEMU, VZ uses virtiofs.
+            if m.network {
+                let mut nine_p = serde_yaml::Mapping::new();
+                nine_p.insert("cache".into(), "none".into());
+                mount_config.insert("9p".into(), nine_p.into());
+            }
+
             Value::Mapping(mount_config)
         })
         .collect();
@@ -244,6 +253,7 @@ mod tests {
                 host_path: PathBuf::from("/Users/test/.claude"),
                 guest_path: PathBuf::from("/root/.claude"),
                 read_only: false,
+                network: false,
             },
         ];
 
@@ -432,6 +442,7 @@ mod tests {
                 host_path: PathBuf::from("/tmp/notes"),
                 guest_path: PathBuf::from("/mnt/notes"),
                 read_only: true,
+                network: false,
             },
         ];
 
@@ -456,6 +467,22 @@ mod tests {
         assert_eq!(m1["mountPoint"].as_str().unwrap(), "/mnt/notes");
     }
 
+    #[test]
+    fn test_generate_lima_config_network_mount_disables_cache() {
+        let mut network = Mount::rw(PathBuf::from("/mnt/nfs/project"));
+        network.network = true;
+        let mounts = vec![Mount::rw(PathBuf::from("/tmp/project")), network];
+
+        let sandbox_config = SandboxConfig::default();
+        let yaml =
+            generate_lima_config("test-vm", &mounts, &sandbox_config, "claude", true).unwrap();
+
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let mount_list = parsed["mounts"].as_sequence().unwrap();
+        assert!(mount_list[0]["9p"].is_null());
+        assert_eq!(mount_list[1]["9p"]["cache"].as_str().unwrap(), "none");
+    }
+
     #[test]
     fn test_generate_lima_config_codex_agent() {
         let mounts = vec![Mount::rw(PathBuf::from("/tmp/test"))];
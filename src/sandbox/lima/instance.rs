@@ -114,6 +114,16 @@ impl LimaInstance {
 ///
 /// Returns the VM name for use by `wrap_for_lima()`.
 pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String> {
+    boot_vm(config, worktree_path, false)
+}
+
+/// Like `ensure_vm_running`, but without terminal output, for booting on a
+/// background thread while other output is going on (see `VmBoot`).
+pub fn ensure_vm_running_quietly(config: &Config, worktree_path: &Path) -> Result<String> {
+    boot_vm(config, worktree_path, true)
+}
+
+fn boot_vm(config: &Config, worktree_path: &Path, quiet: bool) -> Result<String> {
     if !LimaInstance::is_lima_available() {
         bail!(
             "Lima backend is enabled but limactl is not installed.\n\
@@ -144,18 +154,15 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
             let mut cmd = Command::new("limactl");
             cmd.args(["start", "--tty=false", "--progress", &vm_name]);
 
-            let start = std::time::Instant::now();
-            match crate::spinner::with_streaming_command_formatted(&msg, cmd, move |line| {
-                super::log_format::format_lima_log_line(line, &start)
-            }) {
+            match run_limactl_start(&msg, cmd, quiet) {
                 Ok(()) => {}
-                Err(_) => {
+                Err(e) => {
                     // Race condition: another process may have started the VM.
                     // Re-check state before failing.
                     if matches!(check_vm_state(&vm_name)?, VmState::Running) {
                         return Ok(vm_name);
                     }
-                    bail!("Failed to start Lima VM '{}'", vm_name);
+                    return Err(e.context(format!("Failed to start Lima VM '{}'", vm_name)));
                 }
             }
         }
@@ -167,17 +174,21 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
             // Only generate config and mounts when we need to create a new VM
             let mounts = super::generate_mounts(worktree_path, isolation, config, &vm_name, agent)?;
 
-            eprintln!("  Mounts:");
-            for m in &mounts {
-                if m.host_path == m.guest_path {
-                    eprintln!("    {} (rw)", m.host_path.display());
-                } else {
-                    eprintln!(
-                        "    {} -> {} ({})",
-                        m.host_path.display(),
-                        m.guest_path.display(),
-                        if m.read_only { "ro" } else { "rw" }
-                    );
+            if quiet {
+                debug!(vm_name = %vm_name, mounts = ?mounts, "Lima VM mounts");
+            } else {
+                eprintln!("  Mounts:");
+                for m in &mounts {
+                    if m.host_path == m.guest_path {
+                        eprintln!("    {} (rw)", m.host_path.display());
+                    } else {
+                        eprintln!(
+                            "    {} -> {} ({})",
+                            m.host_path.display(),
+                            m.guest_path.display(),
+                            if m.read_only { "ro" } else { "rw" }
+                        );
+                    }
                 }
             }
 
@@ -208,18 +219,15 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
                 &config_path.to_string_lossy(),
             ]);
 
-            let start = std::time::Instant::now();
-            match crate::spinner::with_streaming_command_formatted(&msg, cmd, move |line| {
-                super::log_format::format_lima_log_line(line, &start)
-            }) {
+            match run_limactl_start(&msg, cmd, quiet) {
                 Ok(()) => {}
-                Err(_) => {
+                Err(e) => {
                     // Race condition: another process may have created the VM.
                     // Re-check state before failing.
                     if matches!(check_vm_state(&vm_name)?, VmState::Running) {
                         return Ok(vm_name);
                     }
-                    bail!("Failed to create Lima VM '{}'", vm_name);
+                    return Err(e.context(format!("Failed to create Lima VM '{}'", vm_name)));
                 }
             }
         }
@@ -228,3 +236,116 @@ pub fn ensure_vm_running(config: &Config, worktree_path: &Path) -> Result<String
     info!(vm_name = %vm_name, "Lima VM ready");
     Ok(vm_name)
 }
+
+/// Run `limactl start`, streaming its progress under a spinner, or silently
+/// when `quiet`, in which case its last lines of output go into the error.
+fn run_limactl_start(msg: &str, mut cmd: Command, quiet: bool) -> Result<()> {
+    if !quiet {
+        let start = std::time::Instant::now();
+        return crate::spinner::with_streaming_command_formatted(msg, cmd, move |line| {
+            super::log_format::format_lima_log_line(line, &start)
+        });
+    }
+    debug!(msg, "running limactl start in the background");
+    let output = cmd.output().context("Failed to run limactl")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+        bail!(
+            "limactl start failed:\n{}",
+            lines[lines.len().saturating_sub(5)..].join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Subset of a Lima instance config (`lima.yaml`) needed to inspect mounts.
+#[derive(Debug, Deserialize)]
+struct LimaMountsConfig {
+    #[serde(default)]
+    mounts: Vec<LimaMountEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LimaMountEntry {
+    location: String,
+}
+
+/// Check whether any mount in a Lima instance config covers `path`.
+fn mounts_cover(lima_yaml: &str, path: &Path) -> Result<bool> {
+    let config: LimaMountsConfig =
+        serde_yaml::from_str(lima_yaml).context("Failed to parse Lima instance config")?;
+    let home = home::home_dir();
+    Ok(config.mounts.iter().any(|m| {
+        let location = match (m.location.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => Path::new(&m.location).to_path_buf(),
+        };
+        path.starts_with(&location)
+    }))
+}
+
+/// Make sure an existing VM mounts `path`, adding a writable mount if needed.
+///
+/// Mounts are fixed when a VM boots, so an uncovered path means stopping the
+/// VM and editing its config; the next sandboxed launch starts it again.
+/// Returns true if the VM config was changed. No-op if the VM doesn't exist.
+pub fn ensure_vm_mounts_path(vm_name: &str, path: &Path) -> Result<bool> {
+    let Some(info) = LimaInstance::list()?
+        .into_iter()
+        .find(|i| i.name == vm_name)
+    else {
+        return Ok(false);
+    };
+    let Some(dir) = info.dir.as_deref() else {
+        return Ok(false);
+    };
+
+    let lima_yaml = std::fs::read_to_string(Path::new(dir).join("lima.yaml"))
+        .with_context(|| format!("Failed to read config of Lima VM '{}'", vm_name))?;
+    if mounts_cover(&lima_yaml, path)? {
+        return Ok(false);
+    }
+
+    if info.is_running() {
+        info!(vm_name = %vm_name, "stopping Lima VM to add mount");
+        LimaInstance::stop_by_name(vm_name)?;
+    }
+
+    let location = serde_json::to_string(&path.to_string_lossy())?;
+    let expr = format!(
+        ".mounts += [{{\"location\": {}, \"writable\": true}}]",
+        location
+    );
+    let output = Command::new("limactl")
+        .args(["edit", "--tty=false", "--set", &expr, vm_name])
+        .output()
+        .with_context(|| format!("Failed to execute limactl edit for '{}'", vm_name))?;
+    if !output.status.success() {
+        bail!(
+            "Failed to add mount to Lima VM '{}': {}",
+            vm_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    debug!(vm_name = %vm_name, path = %path.display(), "added Lima mount");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mounts_cover_nested_path() {
+        let yaml = "mounts:\n  - location: /Users/me/code\n    writable: true\n";
+        assert!(mounts_cover(yaml, Path::new("/Users/me/code/proj__worktrees/feat")).unwrap());
+        assert!(!mounts_cover(yaml, Path::new("/Volumes/ext/feat")).unwrap());
+    }
+
+    #[test]
+    fn mounts_cover_handles_missing_mounts() {
+        assert!(!mounts_cover("cpus: 4\n", Path::new("/tmp/x")).unwrap());
+    }
+}
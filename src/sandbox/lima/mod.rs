@@ -2,14 +2,19 @@
 //!
 //! Provides VM-based sandboxing using Lima (Linux Machines) with configurable isolation levels.
 
+mod boot;
 mod config;
 mod instance;
 pub(crate) mod log_format;
 pub(crate) mod mounts;
 mod wrap;
 
+pub use boot::VmBoot;
 pub use config::generate_lima_config;
-pub use instance::{LimaInstance, LimaInstanceInfo, ensure_vm_running, parse_lima_instances};
+pub use instance::{
+    LimaInstance, LimaInstanceInfo, ensure_vm_mounts_path, ensure_vm_running,
+    ensure_vm_running_quietly, parse_lima_instances,
+};
 pub use mounts::{determine_project_root, generate_mounts};
 pub use wrap::wrap_for_lima;
 
@@ -5,6 +5,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::{Config, IsolationLevel};
+use crate::filesystem;
 
 /// A mount point configuration for Lima.
 #[derive(Debug, Clone)]
@@ -15,6 +16,9 @@ pub struct Mount {
     pub guest_path: PathBuf,
     /// Whether the mount is read-only
     pub read_only: bool,
+    /// Whether the host path is on a network filesystem, where the guest
+    /// must not cache file contents
+    pub network: bool,
 }
 
 impl Mount {
@@ -24,6 +28,7 @@ impl Mount {
             guest_path: path.clone(),
             host_path: path,
             read_only: false,
+            network: false,
         }
     }
 
@@ -34,6 +39,7 @@ impl Mount {
             guest_path: path.clone(),
             host_path: path,
             read_only: true,
+            network: false,
         }
     }
 
@@ -246,6 +252,7 @@ pub fn generate_mounts(
             host_path: auth_dir,
             guest_path,
             read_only: false,
+            network: false,
         });
     }
 
@@ -258,6 +265,7 @@ pub fn generate_mounts(
             host_path: state_dir,
             guest_path,
             read_only: false,
+            network: false,
         });
     }
 
@@ -268,9 +276,14 @@ pub fn generate_mounts(
             host_path,
             guest_path,
             read_only,
+            network: false,
         });
     }
 
+    for mount in &mut mounts {
+        mount.network = filesystem::is_network(&mount.host_path);
+    }
+
     Ok(mounts)
 }
 
@@ -2,11 +2,16 @@
 
 mod container;
 pub mod freshness;
+pub mod git_query;
 pub mod guest;
 pub(crate) mod host_exec_sandbox;
 pub mod lima;
+pub mod net_stats;
 pub mod network_proxy;
+pub mod preheat;
 pub mod rpc;
+pub(crate) mod secrets;
+pub mod shared_env;
 pub(crate) mod shims;
 pub(crate) mod toolchain;
 
@@ -20,5 +25,6 @@ pub(crate) use container::ensure_sandbox_config_dirs;
 pub use container::pull_image;
 pub use container::stop_containers_for_handle;
 pub use container::wrap_for_container;
+pub use lima::VmBoot;
 pub use lima::ensure_vm_running as ensure_lima_vm;
 pub use lima::wrap_for_lima;
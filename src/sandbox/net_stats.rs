@@ -0,0 +1,149 @@
+//! Network activity sampling for sandboxed agents.
+//!
+//! Reads the byte counters of the sandbox's network interfaces from
+//! `/proc/net/dev` inside the container or Lima guest every few seconds and
+//! records the transfer rate in the state store, where the dashboard draws it
+//! as a sparkline. Lima VMs can be shared by several worktrees, so their
+//! rates cover the whole guest. When the network proxy is running, the
+//! number of tunnels it opened is recorded too.
+
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use tracing::debug;
+
+use crate::multiplexer::{self, NetActivity};
+
+/// Where the counters are read from.
+#[derive(Debug, Clone)]
+pub enum Source {
+    Container { runtime: &'static str, name: String },
+    Lima { vm: String },
+}
+
+impl Source {
+    /// Bytes received and sent by the sandbox so far.
+    fn read(&self) -> Result<u64> {
+        let mut cmd = match self {
+            Source::Container { runtime, name } => {
+                let mut cmd = Command::new(runtime);
+                cmd.args(["exec", name]);
+                cmd
+            }
+            Source::Lima { vm } => {
+                let mut cmd = Command::new("limactl");
+                cmd.args(["shell", vm]);
+                cmd
+            }
+        };
+        let output = cmd
+            .args(["cat", "/proc/net/dev"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .context("Failed to read network counters")?;
+        if !output.status.success() {
+            bail!("Reading network counters exited with {}", output.status);
+        }
+        let (rx, tx) = parse_net_dev(&String::from_utf8_lossy(&output.stdout))
+            .context("No network interfaces in /proc/net/dev")?;
+        Ok(rx + tx)
+    }
+}
+
+/// Total bytes received and sent over every interface except loopback.
+pub fn parse_net_dev(content: &str) -> Option<(u64, u64)> {
+    let mut total = None;
+    // Two header lines, then "iface: rx_bytes rx_packets ... tx_bytes ..."
+    for line in content.lines().skip(2) {
+        let Some((iface, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if iface.trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = counters
+            .split_whitespace()
+            .filter_map(|f| f.parse().ok())
+            .collect();
+        if fields.len() < 16 {
+            continue;
+        }
+        let (rx, tx) = total.get_or_insert((0, 0));
+        *rx += fields[0];
+        *tx += fields[8];
+    }
+    total
+}
+
+/// Sample the network activity of `source` in a background thread for as
+/// long as the supervisor runs, recording it for the agent in `pane_id`.
+pub fn spawn_sampler(source: Source, pane_id: String, tunnels: Option<Arc<AtomicU64>>) {
+    if pane_id.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mux = multiplexer::create_backend(multiplexer::detect_backend());
+        let interval = Duration::from_secs(NetActivity::SAMPLE_SECS);
+        let mut last: Option<(u64, Instant)> = None;
+        let mut last_tunnels = 0;
+        loop {
+            std::thread::sleep(interval);
+            let bytes = match source.read() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    // The container may still be starting
+                    debug!(error = %e, "net_stats:sample failed");
+                    continue;
+                }
+            };
+            let now = Instant::now();
+            let rate = match last {
+                Some((prev, at)) => {
+                    let secs = now.duration_since(at).as_secs().max(1);
+                    bytes.saturating_sub(prev) / secs
+                }
+                None => {
+                    last = Some((bytes, now));
+                    continue;
+                }
+            };
+            last = Some((bytes, now));
+            let connections = tunnels.as_ref().map(|t| {
+                let total = t.load(Ordering::Relaxed);
+                let opened = total.saturating_sub(last_tunnels);
+                last_tunnels = total;
+                opened
+            });
+            crate::state::record_net_activity(mux.as_ref(), &pane_id, rate, connections);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:   12345      10    0    0    0     0          0         0    12345      10    0    0    0     0       0          0
+  eth0: 1048576     800    0    0    0     0          0         0     4096      40    0    0    0     0       0          0
+  eth1:     100       1    0    0    0     0          0         0      200       2    0    0    0     0       0          0
+";
+
+    #[test]
+    fn parse_net_dev_sums_interfaces_except_loopback() {
+        assert_eq!(parse_net_dev(NET_DEV), Some((1048676, 4296)));
+    }
+
+    #[test]
+    fn parse_net_dev_without_interfaces() {
+        let only_lo = NET_DEV.lines().take(3).collect::<Vec<_>>().join("\n");
+        assert_eq!(parse_net_dev(&only_lo), None);
+        assert_eq!(parse_net_dev(""), None);
+    }
+}
@@ -13,7 +13,7 @@ use anyhow::{Context, Result};
 use std::io::{BufRead, BufReader, Write};
 use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::Duration;
 use tracing::{debug, warn};
@@ -42,6 +42,7 @@ pub struct NetworkProxy {
     port: u16,
     token: String,
     allowed_domains: Vec<String>,
+    tunnels: Arc<AtomicU64>,
 }
 
 /// Handle to a running proxy server thread.
@@ -62,6 +63,7 @@ impl NetworkProxy {
             port,
             token,
             allowed_domains: allowed_domains.to_vec(),
+            tunnels: Arc::new(AtomicU64::new(0)),
         })
     }
 
@@ -75,11 +77,17 @@ impl NetworkProxy {
         &self.token
     }
 
+    /// Number of tunnels opened so far, counted while the proxy runs.
+    pub fn tunnels(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.tunnels)
+    }
+
     /// Spawn the proxy accept loop in a background thread.
     pub fn spawn(self) -> ProxyHandle {
         let ctx = Arc::new(ProxyContext {
             token: self.token,
             allowed_domains: self.allowed_domains,
+            tunnels: self.tunnels,
         });
         let active = Arc::new(AtomicUsize::new(0));
 
@@ -119,6 +127,7 @@ impl NetworkProxy {
 struct ProxyContext {
     token: String,
     allowed_domains: Vec<String>,
+    tunnels: Arc<AtomicU64>,
 }
 
 /// Check if a domain matches a pattern (case-insensitive).
@@ -322,6 +331,7 @@ fn handle_proxy_connection(stream: TcpStream, ctx: &ProxyContext) -> Result<()>
     };
 
     debug!(hostname, addr = %target_addr, "tunnel established");
+    ctx.tunnels.fetch_add(1, Ordering::Relaxed);
 
     // Send 200 Connection Established
     writer.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
@@ -0,0 +1,162 @@
+//! Pulling container sandbox images before the first agent needs them.
+//!
+//! Without this, the first sandboxed agent start pulls the image while the
+//! pane sits at a blank prompt for minutes. `workmux sandbox preheat` pulls
+//! the official per-agent images (which share the base layers) up front;
+//! `workmux setup --sandbox` and `sandbox.prefetch` run it in the background.
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::{Config, SandboxConfig};
+use crate::sandbox::{DEFAULT_IMAGE_REGISTRY, container, freshness};
+
+/// Log file of background preheats, under the workmux state directory.
+const PREHEAT_LOG: &str = "workmux/sandbox-preheat.log";
+
+/// An image that is ready locally after preheating.
+pub struct PreheatedImage {
+    pub image: String,
+    /// Whether it had to be pulled (false when it was already present)
+    pub pulled: bool,
+    /// Size on disk as reported by the container runtime
+    pub size_bytes: Option<u64>,
+}
+
+/// Canonical names of the agents whose images to preheat: `agents` when
+/// given, else the configured agent.
+pub fn agents_to_preheat(config: &Config, agents: &[String]) -> Vec<&'static str> {
+    if agents.is_empty() {
+        return vec![crate::multiplexer::agent::resolve_profile(config.agent.as_deref()).name()];
+    }
+    let mut names = Vec::new();
+    for agent in agents {
+        let name = crate::multiplexer::agent::resolve_profile(Some(agent)).name();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Make the images of `agents` available locally, printing progress.
+///
+/// Official images are pulled (updating ones that are already present).
+/// A custom `sandbox.image` can't be pulled on the user's behalf, so it only
+/// has to exist.
+pub fn preheat(config: &SandboxConfig, agents: &[&str]) -> Result<Vec<PreheatedImage>> {
+    let runtime = runtime_bin(config);
+    let mut images: Vec<String> = Vec::new();
+    for agent in agents {
+        let image = config.resolved_image(agent);
+        if !images.contains(&image) {
+            images.push(image);
+        }
+    }
+
+    let mut ready = Vec::new();
+    for (i, image) in images.iter().enumerate() {
+        println!("[{}/{}] {}", i + 1, images.len(), image);
+        let pulled = if image.starts_with(DEFAULT_IMAGE_REGISTRY) {
+            container::pull_image(config, image)?;
+            freshness::mark_fresh(image, config.runtime());
+            true
+        } else if image_size(runtime, image).is_some() {
+            println!("Image '{}' is present locally.", image);
+            false
+        } else {
+            bail!(
+                "Image '{}' is not available locally. Build it first (see `workmux sandbox init-dockerfile`).",
+                image
+            );
+        };
+        ready.push(PreheatedImage {
+            image: image.clone(),
+            pulled,
+            size_bytes: image_size(runtime, image),
+        });
+    }
+    Ok(ready)
+}
+
+/// Start `workmux sandbox preheat` for `agents` as a detached process and
+/// return the log file it writes to.
+pub fn spawn_in_background(agents: &[&str]) -> Result<PathBuf> {
+    let log_path = crate::state::store::get_state_dir()?.join(PREHEAT_LOG);
+    if let Some(dir) = log_path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let log = File::create(&log_path)
+        .with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("workmux"));
+    let mut cmd = Command::new(exe);
+    cmd.args(["sandbox", "preheat"]);
+    for agent in agents {
+        cmd.arg(format!("--agent={}", agent));
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // Own process group, so closing the terminal doesn't stop the pull
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd.spawn().context("Failed to start background preheat")?;
+    Ok(log_path)
+}
+
+fn runtime_bin(config: &SandboxConfig) -> &'static str {
+    match config.runtime() {
+        crate::config::SandboxRuntime::Podman => "podman",
+        crate::config::SandboxRuntime::Docker => "docker",
+    }
+}
+
+/// Size of a local image in bytes, or `None` when it isn't present.
+fn image_size(runtime: &str, image: &str) -> Option<u64> {
+    let output = Command::new(runtime)
+        .args(["image", "inspect", "--format", "{{.Size}}", image])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preheats_the_configured_agent_by_default() {
+        let config = Config {
+            agent: Some("codex --yolo".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(agents_to_preheat(&config, &[]), ["codex"]);
+        assert_eq!(
+            agents_to_preheat(
+                &config,
+                &[
+                    "claude".to_string(),
+                    "codex".to_string(),
+                    "claude".to_string()
+                ]
+            ),
+            ["claude", "codex"]
+        );
+        assert_eq!(
+            agents_to_preheat(&config, &["gemini".to_string(), "opencode".to_string()]),
+            ["gemini", "opencode"]
+        );
+    }
+}
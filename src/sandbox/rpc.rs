@@ -8,14 +8,18 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use tracing::{debug, info, warn};
 
-use crate::config::Config;
-use crate::multiplexer::{AgentStatus, Multiplexer};
+use crate::config::{Config, ConflictStrategy};
+use crate::multiplexer::{AgentStatus, DeniedExec, Multiplexer, TaskProgress};
+use crate::sandbox::git_query::{self, GitQuery};
+use crate::state::events::{self, Event, ExecDecided, ExecDenied};
+use crate::state::{ExecRequest, PaneKey, StateStore};
+use crate::workflow::MergeRemoteSync;
 
 // ── Protocol types ──────────────────────────────────────────────────────
 
@@ -30,15 +34,36 @@ pub enum RpcRequest {
         title: String,
     },
     Heartbeat,
+    /// Task progress of the agent. A `total` of 0 clears it.
+    ReportProgress {
+        step: u32,
+        total: u32,
+        #[serde(default)]
+        message: Option<String>,
+    },
     SpawnAgent {
         prompt: String,
         branch_name: Option<String>,
         background: Option<bool>,
+        #[serde(default)]
+        base: Option<String>,
+        #[serde(default)]
+        layout: Option<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        agent: Option<String>,
     },
     Exec {
         command: String,
         args: Vec<String>,
     },
+    /// Run a command that isn't in `host_commands` once, after the user
+    /// approves it in the dashboard
+    RequestExec {
+        command: String,
+        args: Vec<String>,
+    },
     Merge {
         name: String,
         into: Option<String>,
@@ -49,9 +74,49 @@ pub enum RpcRequest {
         no_verify: bool,
         no_hooks: bool,
         notification: bool,
+        #[serde(default)]
+        push: bool,
+        #[serde(default)]
+        delete_remote: bool,
+        #[serde(default)]
+        push_rebased: bool,
+        #[serde(default)]
+        on_conflict: Option<ConflictStrategy>,
+    },
+    /// Read-only git query against the host repository
+    GitQuery {
+        query: GitQuery,
     },
 }
 
+impl RpcRequest {
+    /// Debug representation for logging, with prompts passed through
+    /// `prompt::log_repr`.
+    fn log_repr(&self, redact_prompts: bool) -> String {
+        match self {
+            RpcRequest::SpawnAgent {
+                prompt,
+                branch_name,
+                background,
+                base,
+                layout,
+                tags,
+                agent,
+            } => format!(
+                "SpawnAgent {{ prompt: {:?}, branch_name: {:?}, background: {:?}, base: {:?}, layout: {:?}, tags: {:?}, agent: {:?} }}",
+                crate::prompt::log_repr(prompt, redact_prompts),
+                branch_name,
+                background,
+                base,
+                layout,
+                tags,
+                agent
+            ),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
 /// RPC response sent from host to guest.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -62,6 +127,7 @@ pub enum RpcResponse {
     ExecOutput { data: String },
     ExecError { data: String },
     ExecExit { code: i32 },
+    QueryOutput { data: String },
 }
 
 // ── Server ──────────────────────────────────────────────────────────────
@@ -82,6 +148,15 @@ pub struct RpcContext {
     pub detected_toolchain: crate::sandbox::toolchain::DetectedToolchain,
     /// Whether to allow host-exec without bwrap on Linux.
     pub allow_unsandboxed_host_exec: bool,
+    /// Whether denied host-exec requests are recorded in the event log and
+    /// the agent's state.
+    pub audit_denied: bool,
+    /// Whether denied host-exec requests raise a desktop notification.
+    pub notify_denied: bool,
+    /// Secret values injected into the guest, redacted from request logs.
+    pub redact_values: Vec<String>,
+    /// Whether spawn-agent prompts are redacted from request logs.
+    pub redact_prompts: bool,
 }
 
 /// TCP RPC server that accepts guest connections.
@@ -267,7 +342,10 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
         let request: RpcRequest = serde_json::from_str(line.trim())
             .with_context(|| format!("Failed to parse RPC request: {}", line.trim()))?;
 
-        info!(?request, "RPC request received");
+        info!(
+            request = %crate::sandbox::secrets::redact(&request.log_repr(ctx.redact_prompts), &ctx.redact_values),
+            "RPC request received"
+        );
 
         // Exec and Merge require streaming multiple responses, handle separately
         if let RpcRequest::Exec {
@@ -279,6 +357,15 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
             continue;
         }
 
+        if let RpcRequest::RequestExec {
+            ref command,
+            ref args,
+        } = request
+        {
+            handle_request_exec(command, args, ctx, &mut writer)?;
+            continue;
+        }
+
         if let RpcRequest::Merge {
             ref name,
             ref into,
@@ -289,6 +376,10 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
             no_verify: _,
             no_hooks: _,
             notification,
+            push,
+            delete_remote,
+            push_rebased,
+            on_conflict,
         } = request
         {
             // SECURITY: Force --no-verify --no-hooks regardless of guest request.
@@ -304,6 +395,12 @@ fn handle_connection(stream: TcpStream, ctx: &RpcContext) -> Result<()> {
                 ignore_uncommitted,
                 keep,
                 notification,
+                MergeRemoteSync {
+                    push,
+                    delete_remote,
+                    push_rebased,
+                },
+                on_conflict,
                 &ctx.worktree_path,
                 &mut writer,
             )?;
@@ -331,20 +428,39 @@ fn write_response(writer: &mut impl Write, response: &RpcResponse) -> Result<()>
 
 fn dispatch_request(request: &RpcRequest, ctx: &RpcContext) -> RpcResponse {
     match request {
-        RpcRequest::Heartbeat => RpcResponse::Ok,
+        RpcRequest::Heartbeat => {
+            crate::state::record_heartbeat(&*ctx.mux, &ctx.pane_id);
+            RpcResponse::Ok
+        }
         RpcRequest::SetStatus { status } => handle_set_status(status, ctx),
         RpcRequest::SetTitle { title } => handle_set_title(title, ctx),
+        RpcRequest::ReportProgress {
+            step,
+            total,
+            message,
+        } => handle_report_progress(*step, *total, message.as_deref(), ctx),
         RpcRequest::SpawnAgent {
             prompt,
             branch_name,
             background,
+            base,
+            layout,
+            tags,
+            agent,
         } => handle_spawn_agent(
             prompt,
             branch_name.as_deref(),
             *background,
+            &SpawnSelection {
+                base: base.as_deref(),
+                layout: layout.as_deref(),
+                tags,
+                agent: agent.as_deref(),
+            },
             &ctx.worktree_path,
         ),
-        RpcRequest::Exec { .. } => {
+        RpcRequest::GitQuery { query } => handle_git_query(query, &ctx.worktree_path),
+        RpcRequest::Exec { .. } | RpcRequest::RequestExec { .. } => {
             // Handled in handle_connection before dispatch
             unreachable!("Exec is handled directly in handle_connection")
         }
@@ -390,6 +506,9 @@ fn handle_set_status(status: &str, ctx: &RpcContext) -> RpcResponse {
                     message: format!("Failed to clear status: {}", e),
                 };
             }
+            if crate::state::clear_agent_status(&*ctx.mux, &ctx.pane_id, false) {
+                crate::state::refresh_session_status(&*ctx.mux, &ctx.pane_id, &config);
+            }
             return RpcResponse::Ok;
         }
         _ => {
@@ -407,12 +526,14 @@ fn handle_set_status(status: &str, ctx: &RpcContext) -> RpcResponse {
         Ok(()) => {
             // Persist agent state to StateStore so the dashboard sees this agent
             if let Some(agent_status) = agent_status {
-                crate::state::persist_agent_update(
+                let status_changed = crate::state::persist_agent_update(
                     &*ctx.mux,
                     &ctx.pane_id,
                     Some(agent_status),
                     None,
                 );
+                crate::state::refresh_session_status(&*ctx.mux, &ctx.pane_id, &config);
+                crate::state::preempt::apply(&*ctx.mux, &ctx.pane_id, status_changed, &config);
             }
             RpcResponse::Ok
         }
@@ -446,6 +567,31 @@ fn handle_set_title(title: &str, ctx: &RpcContext) -> RpcResponse {
     }
 }
 
+fn handle_report_progress(
+    step: u32,
+    total: u32,
+    message: Option<&str>,
+    ctx: &RpcContext,
+) -> RpcResponse {
+    if total == 0 {
+        crate::state::set_agent_task_progress(&*ctx.mux, &ctx.pane_id, None);
+        return RpcResponse::Ok;
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    match TaskProgress::new(step, total, message, now) {
+        Ok(progress) => {
+            crate::state::set_agent_task_progress(&*ctx.mux, &ctx.pane_id, Some(progress));
+            RpcResponse::Ok
+        }
+        Err(e) => RpcResponse::Error {
+            message: format!("Invalid progress: {}", e),
+        },
+    }
+}
+
 /// Disable git native hooks for a spawned command and all its children.
 ///
 /// Sets `core.hooksPath=/dev/null` via git's `GIT_CONFIG_*` environment
@@ -464,14 +610,102 @@ fn disable_git_hooks(cmd: &mut std::process::Command) {
         .env("GIT_CONFIG_VALUE_0", "/dev/null");
 }
 
+fn write_temp_prompt(prompt: &str) -> std::io::Result<tempfile::NamedTempFile> {
+    use std::io::Write;
+
+    let mut file = tempfile::Builder::new()
+        .prefix("workmux-prompt-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(prompt.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// Environment a guest selected for a spawned agent.
+#[derive(Default)]
+struct SpawnSelection<'a> {
+    base: Option<&'a str>,
+    layout: Option<&'a str>,
+    tags: &'a [String],
+    agent: Option<&'a str>,
+}
+
+impl SpawnSelection<'_> {
+    /// Check the selection against the host's `sandbox.spawn` policy.
+    fn check(&self, config: &Config) -> Result<(), String> {
+        let policy = &config.sandbox.spawn;
+        if let Some(agent) = self.agent
+            && !policy.allows_agent(agent, config.agent.as_deref())
+        {
+            return Err(format!(
+                "agent '{}' is not allowed by the host's sandbox.spawn.agents",
+                agent
+            ));
+        }
+        if let Some(layout) = self.layout
+            && !policy.allows_layout(layout)
+        {
+            return Err(format!(
+                "layout '{}' is not allowed by the host's sandbox.spawn.layouts",
+                layout
+            ));
+        }
+        if let Some(base) = self.base
+            && !policy.allows_base(base)
+        {
+            return Err(format!(
+                "base '{}' is not allowed by the host's sandbox.spawn.bases",
+                base
+            ));
+        }
+        for tag in self.tags {
+            crate::plugin::validate_tag(tag).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// `workmux add` flags for the selection. Values are attached with `=`
+    /// so a guest can't smuggle in flags of its own.
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(base) = self.base {
+            args.push(format!("--base={}", base));
+        }
+        if let Some(layout) = self.layout {
+            args.push(format!("--layout={}", layout));
+        }
+        for tag in self.tags {
+            args.push(format!("--tag={}", tag));
+        }
+        if let Some(agent) = self.agent {
+            args.push(format!("--agent={}", agent));
+        }
+        args
+    }
+}
+
 fn handle_spawn_agent(
     prompt: &str,
     branch_name: Option<&str>,
     background: Option<bool>,
+    selection: &SpawnSelection,
     worktree_path: &PathBuf,
 ) -> RpcResponse {
     use std::process::Command;
 
+    let config = match Config::load(None) {
+        Ok(c) => c,
+        Err(e) => {
+            return RpcResponse::Error {
+                message: format!("Failed to load config: {}", e),
+            };
+        }
+    };
+    if let Err(message) = selection.check(&config) {
+        return RpcResponse::Error { message };
+    }
+
     let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("workmux"));
     let mut cmd = Command::new(exe);
     cmd.arg("add");
@@ -482,13 +716,28 @@ fn handle_spawn_agent(
         cmd.arg("--auto-name");
     }
 
-    if !prompt.is_empty() {
-        cmd.args(["--prompt", prompt]);
+    // Pass the prompt via a file so it doesn't show up in the process list.
+    // The file must outlive the child, which reads it before exiting.
+    let prompt_file = if prompt.is_empty() {
+        None
+    } else {
+        match write_temp_prompt(prompt) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                return RpcResponse::Error {
+                    message: format!("Failed to write prompt file: {}", e),
+                };
+            }
+        }
+    };
+    if let Some(file) = &prompt_file {
+        cmd.arg("--prompt-file").arg(file.path());
     }
 
     if background.unwrap_or(false) {
         cmd.arg("--background");
     }
+    cmd.args(selection.args());
 
     // SECURITY: Skip workmux hooks AND git native hooks when triggered via RPC.
     // Workmux hooks are arbitrary shell commands from config that run unsandboxed
@@ -514,6 +763,15 @@ fn handle_spawn_agent(
     }
 }
 
+fn handle_git_query(query: &GitQuery, worktree_path: &Path) -> RpcResponse {
+    match git_query::run(query, worktree_path) {
+        Ok(data) => RpcResponse::QueryOutput { data },
+        Err(e) => RpcResponse::Error {
+            message: format!("Git query failed: {}", e),
+        },
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn handle_merge(
     name: &str,
@@ -523,6 +781,8 @@ fn handle_merge(
     ignore_uncommitted: bool,
     keep: bool,
     notification: bool,
+    remote_sync: MergeRemoteSync,
+    on_conflict: Option<ConflictStrategy>,
     worktree_path: &PathBuf,
     writer: &mut impl Write,
 ) -> Result<()> {
@@ -551,6 +811,18 @@ fn handle_merge(
     if notification {
         cmd.arg("--notification");
     }
+    if remote_sync.push {
+        cmd.arg("--push");
+    }
+    if remote_sync.delete_remote {
+        cmd.arg("--delete-remote");
+    }
+    if remote_sync.push_rebased {
+        cmd.arg("--push-rebased");
+    }
+    if let Some(strategy) = on_conflict {
+        cmd.args(["--on-conflict", strategy.as_str()]);
+    }
 
     // SECURITY: Skip workmux hooks AND git native hooks when triggered via RPC.
     // --no-verify/--no-hooks skip workmux's own pre_merge hooks (arbitrary shell
@@ -693,16 +965,77 @@ fn sanitized_env() -> std::collections::HashMap<String, String> {
     envs
 }
 
+/// Characters of a denied command line shown in its notification.
+const DENIED_NOTIFICATION_CHARS: usize = 80;
+
+/// Make a refused host-exec request visible instead of only returning 127 to
+/// the guest: it goes to the event log, shows as a badge on the agent in the
+/// dashboard, and raises a desktop notification when `notify_denied` is set.
+fn report_denied(command: &str, args: &[String], reason: &str, ctx: &RpcContext) {
+    let event = denied_event(command, args, reason, ctx);
+    warn!(
+        command = %event.command,
+        args = ?event.args,
+        reason,
+        "host-exec denied"
+    );
+    if !ctx.audit_denied {
+        return;
+    }
+    if let Err(e) = events::append(&Event::ExecDenied(event.clone())) {
+        warn!(error = %e, "failed to record denied host-exec");
+    }
+    crate::state::record_denied_exec(&*ctx.mux, &ctx.pane_id, command);
+    if ctx.notify_denied {
+        let attempted: String = std::iter::once(event.command.as_str())
+            .chain(event.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(DENIED_NOTIFICATION_CHARS)
+            .collect();
+        crate::util::show_notification(&format!(
+            "Agent in {} tried to run {}",
+            event.handle, attempted
+        ));
+    }
+}
+
+/// Event log entry for a refused request, with secrets redacted.
+fn denied_event(command: &str, args: &[String], reason: &str, ctx: &RpcContext) -> ExecDenied {
+    ExecDenied {
+        handle: ctx
+            .worktree_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        worktree_path: ctx.worktree_path.clone(),
+        pane_id: ctx.pane_id.clone(),
+        command: DeniedExec::sanitize_command(command),
+        args: args
+            .iter()
+            .map(|arg| crate::sandbox::secrets::redact(arg, &ctx.redact_values))
+            .collect(),
+        reason: reason.to_string(),
+    }
+}
+
 fn handle_exec(
     command: &str,
     args: &[String],
     ctx: &RpcContext,
     writer: &mut impl Write,
 ) -> Result<()> {
-    info!(command, ?args, "host-exec request");
+    info!(
+        command,
+        args = %crate::sandbox::secrets::redact(&format!("{:?}", args), &ctx.redact_values),
+        "host-exec request"
+    );
 
     // Validate command name format (strict alphanumeric + dash/underscore/dot)
     if !crate::sandbox::shims::validate_command_name(command) {
+        report_denied(command, args, "invalid command name", ctx);
         let resp = RpcResponse::ExecExit { code: 127 };
         write_response(writer, &resp)?;
         return Ok(());
@@ -710,6 +1043,7 @@ fn handle_exec(
 
     // Validate command is in allowlist
     if !ctx.allowed_commands.contains(command) {
+        report_denied(command, args, "not in host_commands", ctx);
         let resp = RpcResponse::ExecExit { code: 127 };
         write_response(writer, &resp)?;
         return Ok(());
@@ -751,7 +1085,7 @@ fn handle_exec(
         ctx.allow_unsandboxed_host_exec,
     );
 
-    let mut child = match spawn_result {
+    let child = match spawn_result {
         Ok(child) => child,
         Err(e) => {
             warn!(command, error = %e, "failed to spawn command");
@@ -766,6 +1100,14 @@ fn handle_exec(
         }
     };
 
+    let code = stream_child(child, writer)?;
+    info!(command, code, "host-exec finished");
+    Ok(())
+}
+
+/// Stream the output of `child` to the guest, then its exit code. Returns
+/// the exit code.
+fn stream_child(mut child: std::process::Child, writer: &mut impl Write) -> Result<i32> {
     let mut stdout = child.stdout.take().unwrap();
     let mut stderr = child.stderr.take().unwrap();
 
@@ -815,10 +1157,10 @@ fn handle_exec(
         Ok(())
     })();
 
-    if stream_result.is_err() {
+    if let Err(e) = stream_result {
         let _ = child.kill();
         let _ = child.wait();
-        return stream_result;
+        return Err(e);
     }
 
     stdout_thread.join().ok();
@@ -826,12 +1168,189 @@ fn handle_exec(
 
     let status = child.wait()?;
     let code = status.code().unwrap_or(1);
-    info!(command, code, "host-exec finished");
 
     write_response(writer, &RpcResponse::ExecExit { code })?;
+    Ok(code)
+}
+
+/// How long a host command request waits for the user's decision. Matches
+/// the longest timeout agents allow a shell command.
+const APPROVAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// How often a host command request checks for the user's decision.
+const APPROVAL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Exit code of a host command request that wasn't approved.
+const NOT_APPROVED_EXIT_CODE: i32 = 126;
+
+/// Ask the user to approve a command that isn't in `host_commands`, and run
+/// it once on the host if they do.
+///
+/// The request shows in the dashboard with its exact argv until the user
+/// approves or denies it, the guest disconnects, or `APPROVAL_TIMEOUT`
+/// passes. Approved commands run outside the host-exec sandbox, since the
+/// user vouched for them (e.g. `gh` needs its credentials), with the same
+/// sanitized environment. Every decision goes to the event log.
+fn handle_request_exec(
+    command: &str,
+    args: &[String],
+    ctx: &RpcContext,
+    writer: &mut TcpStream,
+) -> Result<()> {
+    if !crate::sandbox::shims::validate_command_name(command) {
+        report_denied(command, args, "invalid command name", ctx);
+        write_response(writer, &RpcResponse::ExecExit { code: 127 })?;
+        return Ok(());
+    }
+
+    // Allowed commands need no approval
+    if ctx.allowed_commands.contains(command) {
+        return handle_exec(command, args, ctx, writer);
+    }
+
+    // Same fields, and redaction, as a refused request
+    let event = denied_event(command, args, "", ctx);
+    let mut id = [0u8; 8];
+    getrandom::fill(&mut id).expect("failed to get random bytes");
+    let request = ExecRequest {
+        pane_key: PaneKey {
+            backend: ctx.mux.name().to_string(),
+            instance: ctx.mux.instance_id(),
+            pane_id: ctx.pane_id.clone(),
+        },
+        worktree: ctx.worktree_path.clone(),
+        id: u64::from_le_bytes(id),
+        command: event.command.clone(),
+        args: event.args.clone(),
+        requested_ts: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        approved: None,
+    };
+    let store = match StateStore::new().and_then(|store| {
+        store.save_exec_request(&request)?;
+        Ok(store)
+    }) {
+        Ok(store) => store,
+        Err(e) => {
+            write_response(
+                writer,
+                &RpcResponse::Error {
+                    message: format!("Failed to save the request: {e:#}"),
+                },
+            )?;
+            return Ok(());
+        }
+    };
+    info!(command = %request.command_line(), "host command waiting for approval");
+    crate::util::show_notification(&format!(
+        "Agent in {} asks to run {}",
+        event.handle,
+        request
+            .command_line()
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(DENIED_NOTIFICATION_CHARS)
+            .collect::<String>()
+    ));
+    write_response(
+        writer,
+        &RpcResponse::Output {
+            message: "Waiting for approval in the workmux dashboard".to_string(),
+        },
+    )?;
+
+    let started = std::time::Instant::now();
+    let approved = loop {
+        match store.get_exec_request(&request.pane_key) {
+            Ok(Some(current)) if current.id == request.id => {
+                if let Some(approved) = current.approved {
+                    break approved;
+                }
+            }
+            // Replaced by another request of the pane, or removed
+            Ok(_) => break false,
+            Err(e) => warn!(error = %e, "failed to read host command request"),
+        }
+        if started.elapsed() >= APPROVAL_TIMEOUT || guest_disconnected(writer) {
+            break false;
+        }
+        thread::sleep(APPROVAL_POLL_INTERVAL);
+    };
+    if let Err(e) = store.delete_exec_request(&request.pane_key, request.id) {
+        warn!(error = %e, "failed to delete host command request");
+    }
+
+    info!(command = %request.command_line(), approved, "host command request decided");
+    let decided = ExecDecided {
+        handle: event.handle,
+        worktree_path: event.worktree_path,
+        pane_id: event.pane_id,
+        command: event.command,
+        args: event.args,
+        approved,
+    };
+    if let Err(e) = events::append(&Event::ExecDecided(decided)) {
+        warn!(error = %e, "failed to record host command decision");
+    }
+
+    if !approved {
+        write_response(
+            writer,
+            &RpcResponse::ExecError {
+                data: format!("{command}: not approved\n"),
+            },
+        )?;
+        write_response(
+            writer,
+            &RpcResponse::ExecExit {
+                code: NOT_APPROVED_EXIT_CODE,
+            },
+        )?;
+        return Ok(());
+    }
+
+    let spawn_result = std::process::Command::new(command)
+        .args(args)
+        .current_dir(&ctx.worktree_path)
+        .env_clear()
+        .envs(sanitized_env())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let child = match spawn_result {
+        Ok(child) => child,
+        Err(e) => {
+            warn!(command, error = %e, "failed to spawn approved command");
+            write_response(
+                writer,
+                &RpcResponse::ExecError {
+                    data: format!("host-exec spawn failed: {e}\n"),
+                },
+            )?;
+            write_response(writer, &RpcResponse::ExecExit { code: 126 })?;
+            return Ok(());
+        }
+    };
+    let code = stream_child(child, writer)?;
+    info!(command, code, "approved host command finished");
     Ok(())
 }
 
+/// Whether the guest closed its connection, e.g. because the agent gave up
+/// on the command.
+fn guest_disconnected(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return false;
+    }
+    let mut buf = [0u8; 1];
+    let closed = matches!(stream.peek(&mut buf), Ok(0));
+    let _ = stream.set_nonblocking(false);
+    closed
+}
+
 // ── Client ──────────────────────────────────────────────────────────────
 
 /// RPC client for guest-side use. Connects to the host supervisor.
@@ -924,12 +1443,40 @@ mod tests {
         assert!(json.contains("\"status\":\"working\""));
     }
 
+    #[test]
+    fn test_request_serialization_report_progress() {
+        let req = RpcRequest::ReportProgress {
+            step: 2,
+            total: 5,
+            message: Some("Writing tests".to_string()),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"type\":\"ReportProgress\""));
+        assert!(json.contains("\"step\":2"));
+
+        // The message is optional
+        let parsed: RpcRequest =
+            serde_json::from_str(r#"{"type":"ReportProgress","step":1,"total":3}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            RpcRequest::ReportProgress {
+                step: 1,
+                total: 3,
+                message: None
+            }
+        ));
+    }
+
     #[test]
     fn test_request_serialization_spawn_agent() {
         let req = RpcRequest::SpawnAgent {
             prompt: "fix the bug".to_string(),
             branch_name: Some("fix-bug".to_string()),
             background: Some(true),
+            base: Some("main".to_string()),
+            layout: None,
+            tags: vec!["review".to_string()],
+            agent: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let parsed: RpcRequest = serde_json::from_str(&json).unwrap();
@@ -938,10 +1485,15 @@ mod tests {
                 prompt,
                 branch_name,
                 background,
+                base,
+                tags,
+                ..
             } => {
                 assert_eq!(prompt, "fix the bug");
                 assert_eq!(branch_name.as_deref(), Some("fix-bug"));
                 assert_eq!(background, Some(true));
+                assert_eq!(base.as_deref(), Some("main"));
+                assert_eq!(tags, ["review"]);
             }
             _ => panic!("Wrong variant"),
         }
@@ -967,6 +1519,7 @@ mod tests {
             r#"{"type":"Heartbeat"}"#,
             r#"{"type":"SetStatus","status":"working"}"#,
             r#"{"type":"SetTitle","title":"my agent"}"#,
+            r#"{"type":"ReportProgress","step":1,"total":4,"message":"Planning"}"#,
             r#"{"type":"SpawnAgent","prompt":"do stuff","branch_name":null,"background":null}"#,
             r#"{"type":"Exec","command":"cargo","args":["build","--release"]}"#,
             r#"{"type":"Merge","name":"feat","into":null,"rebase":true,"squash":false,"ignore_uncommitted":false,"keep":false,"no_verify":false,"no_hooks":false,"notification":false}"#,
@@ -1008,6 +1561,10 @@ mod tests {
             allowed_commands: std::collections::HashSet::new(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: false,
+            audit_denied: false,
+            notify_denied: false,
+            redact_values: Vec::new(),
+            redact_prompts: true,
         });
 
         let _handle = server.spawn(ctx);
@@ -1146,6 +1703,10 @@ mod tests {
             allowed_commands: std::collections::HashSet::new(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: false,
+            audit_denied: false,
+            notify_denied: false,
+            redact_values: Vec::new(),
+            redact_prompts: true,
         });
 
         let _handle = server.spawn(ctx);
@@ -1186,6 +1747,10 @@ mod tests {
             allowed_commands: allowed.iter().map(|s| s.to_string()).collect(),
             detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
             allow_unsandboxed_host_exec: allow_unsandboxed,
+            audit_denied: false,
+            notify_denied: false,
+            redact_values: Vec::new(),
+            redact_prompts: true,
         });
 
         let handle = server.spawn(ctx);
@@ -1245,6 +1810,34 @@ mod tests {
         assert_eq!(code, 127);
     }
 
+    #[test]
+    fn test_denied_event_redacts_secrets() {
+        let ctx = RpcContext {
+            pane_id: "%3".to_string(),
+            worktree_path: PathBuf::from("/code/api__worktrees/feature-x"),
+            mux: multiplexer::create_backend(multiplexer::BackendType::Tmux),
+            token: generate_token(),
+            allowed_commands: std::collections::HashSet::new(),
+            detected_toolchain: crate::sandbox::toolchain::DetectedToolchain::None,
+            allow_unsandboxed_host_exec: false,
+            audit_denied: false,
+            notify_denied: false,
+            redact_values: vec!["ghp_supersecrettoken".to_string()],
+            redact_prompts: true,
+        };
+        let args = [
+            "auth".to_string(),
+            "--with-token=ghp_supersecrettoken".to_string(),
+        ];
+        let event = denied_event("gh\u{1b}", &args, "not in host_commands", &ctx);
+        assert_eq!(event.handle, "feature-x");
+        assert_eq!(event.pane_id, "%3");
+        assert_eq!(event.command, "gh");
+        assert_eq!(event.args[0], "auth");
+        assert!(!event.args[1].contains("ghp_supersecrettoken"));
+        assert_eq!(event.reason, "not in host_commands");
+    }
+
     #[test]
     fn test_exec_shell_metacharacters_in_args_not_interpreted() {
         let (mut client, _tmp, _handle) = start_exec_server(&["echo"], true);
@@ -1400,6 +1993,10 @@ mod tests {
             no_verify: false,
             no_hooks: true,
             notification: true,
+            push: true,
+            delete_remote: false,
+            push_rebased: false,
+            on_conflict: Some(ConflictStrategy::Agent),
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"type\":\"Merge\""));
@@ -1422,6 +2019,10 @@ mod tests {
                 no_verify,
                 no_hooks,
                 notification,
+                push,
+                delete_remote,
+                push_rebased,
+                on_conflict,
             } => {
                 assert_eq!(name, "feature-x");
                 assert_eq!(into.as_deref(), Some("main"));
@@ -1432,6 +2033,29 @@ mod tests {
                 assert!(!no_verify);
                 assert!(no_hooks);
                 assert!(notification);
+                assert!(push);
+                assert!(!delete_remote);
+                assert!(!push_rebased);
+                assert_eq!(on_conflict, Some(ConflictStrategy::Agent));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_request_merge_without_remote_sync_fields() {
+        // Guests running an older workmux don't send the remote sync fields
+        let json = r#"{"type":"Merge","name":"f","into":null,"rebase":false,"squash":false,"ignore_uncommitted":false,"keep":false,"no_verify":false,"no_hooks":false,"notification":false}"#;
+        match serde_json::from_str::<RpcRequest>(json).unwrap() {
+            RpcRequest::Merge {
+                push,
+                delete_remote,
+                push_rebased,
+                on_conflict,
+                ..
+            } => {
+                assert!(!push && !delete_remote && !push_rebased);
+                assert_eq!(on_conflict, None);
             }
             _ => panic!("Wrong variant"),
         }
@@ -1458,7 +2082,13 @@ mod tests {
         // When prompt is empty, handle_spawn_agent should not pass --prompt
         // This prevents creating blank prompt files on the host
         let tmp = tempfile::tempdir().unwrap();
-        let resp = handle_spawn_agent("", Some("test-branch"), None, &tmp.path().to_path_buf());
+        let resp = handle_spawn_agent(
+            "",
+            Some("test-branch"),
+            None,
+            &SpawnSelection::default(),
+            &tmp.path().to_path_buf(),
+        );
         // The handler will try to run workmux add, which will fail because
         // we're not in a real environment, but the key assertion is that it
         // doesn't hang or crash with empty prompt
@@ -1477,6 +2107,7 @@ mod tests {
             "do stuff",
             Some("bg-branch"),
             Some(true),
+            &SpawnSelection::default(),
             &tmp.path().to_path_buf(),
         );
         // The handler will fail to run workmux add, but we're testing that
@@ -1492,7 +2123,13 @@ mod tests {
     fn test_spawn_agent_auto_name_when_branch_is_none() {
         // When branch_name is None, handler should pass --auto-name
         let tmp = tempfile::tempdir().unwrap();
-        let resp = handle_spawn_agent("fix bug", None, None, &tmp.path().to_path_buf());
+        let resp = handle_spawn_agent(
+            "fix bug",
+            None,
+            None,
+            &SpawnSelection::default(),
+            &tmp.path().to_path_buf(),
+        );
         match resp {
             RpcResponse::Error { .. } => {} // Expected
             RpcResponse::Ok => {}
@@ -1507,6 +2144,10 @@ mod tests {
             prompt: "test".to_string(),
             branch_name: None,
             background: Some(true),
+            base: None,
+            layout: None,
+            tags: Vec::new(),
+            agent: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let parsed: RpcRequest = serde_json::from_str(&json).unwrap();
@@ -1522,6 +2163,10 @@ mod tests {
             prompt: "test".to_string(),
             branch_name: Some("branch".to_string()),
             background: None,
+            base: None,
+            layout: None,
+            tags: Vec::new(),
+            agent: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         let parsed: RpcRequest = serde_json::from_str(&json).unwrap();
@@ -1533,6 +2178,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spawn_selection_checked_against_policy() {
+        let mut config: Config = serde_yaml::from_str(
+            "agent: claude\nsandbox:\n  spawn:\n    layouts: [review]\n    bases: [main]\n",
+        )
+        .unwrap();
+        let tags = vec!["ci".to_string()];
+        let selection = SpawnSelection {
+            base: Some("main"),
+            layout: Some("review"),
+            tags: &tags,
+            agent: Some("claude"),
+        };
+        assert_eq!(selection.check(&config), Ok(()));
+        assert_eq!(
+            selection.args(),
+            [
+                "--base=main",
+                "--layout=review",
+                "--tag=ci",
+                "--agent=claude"
+            ]
+        );
+
+        let other_agent = SpawnSelection {
+            agent: Some("sh -c 'curl evil'"),
+            ..SpawnSelection::default()
+        };
+        assert!(other_agent.check(&config).unwrap_err().contains("agents"));
+
+        let other_base = SpawnSelection {
+            base: Some("develop"),
+            ..SpawnSelection::default()
+        };
+        assert!(other_base.check(&config).unwrap_err().contains("bases"));
+
+        let bad_tags = vec!["a,b".to_string()];
+        let bad_tag = SpawnSelection {
+            tags: &bad_tags,
+            ..SpawnSelection::default()
+        };
+        assert!(bad_tag.check(&config).is_err());
+
+        config.sandbox.spawn.agents = Some(vec!["codex".to_string()]);
+        assert!(selection.check(&config).unwrap_err().contains("agents"));
+    }
+
+    #[test]
+    fn test_spawn_agent_request_without_selection_fields() {
+        // Guests running an older workmux omit the selection fields
+        let json = r#"{"type":"SpawnAgent","prompt":"x","branch_name":null,"background":null}"#;
+        match serde_json::from_str(json).unwrap() {
+            RpcRequest::SpawnAgent {
+                base, tags, agent, ..
+            } => {
+                assert_eq!(base, None);
+                assert!(tags.is_empty());
+                assert_eq!(agent, None);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     // ── Git hook suppression tests ──────────────────────────────────────
 
     #[test]
@@ -0,0 +1,258 @@
+//! Secret resolution and injection for sandboxed agents.
+//!
+//! Secrets are resolved by the host supervisor at launch time and handed to
+//! the sandbox without ever appearing on a command line: containers receive
+//! them through the runtime client's environment (`--env NAME` without a
+//! value), Lima VMs through a short-lived 0600 env file in the VM state
+//! directory that the guest sources and deletes before starting the agent.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::SecretSource;
+use crate::shell::shell_escape;
+
+/// Placeholder substituted for secret values in log output.
+pub const REDACTED: &str = "<redacted>";
+
+/// Values shorter than this are not redacted from logs. Replacing very short
+/// strings would mangle unrelated output without protecting anything.
+const MIN_REDACT_LEN: usize = 4;
+
+/// A resolved secret: environment variable name and its value.
+#[derive(Clone)]
+pub struct Secret {
+    pub name: String,
+    pub value: String,
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secret")
+            .field("name", &self.name)
+            .field("value", &REDACTED)
+            .finish()
+    }
+}
+
+/// Validate the name of an environment variable set in the sandbox (a
+/// secret or a `shared_env` variable).
+///
+/// Accepts `[A-Za-z_][A-Za-z0-9_]*` and rejects names reserved for
+/// workmux's own guest plumbing (`WM_*`) and `PATH`/`HOME`.
+pub fn validate_env_name(name: &str) -> Result<()> {
+    let mut bytes = name.bytes();
+    let valid = match bytes.next() {
+        Some(first) => {
+            (first.is_ascii_alphabetic() || first == b'_')
+                && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+        }
+        None => false,
+    };
+    if !valid {
+        bail!("invalid environment variable name '{}'", name);
+    }
+    if name.starts_with("WM_") || matches!(name, "PATH" | "HOME") {
+        bail!("'{}' is reserved by workmux", name);
+    }
+    Ok(())
+}
+
+/// Resolve all configured secrets. Fails on the first secret that cannot be
+/// resolved so the agent never starts with a partial credential set.
+pub fn resolve_all(secrets: &BTreeMap<String, SecretSource>) -> Result<Vec<Secret>> {
+    secrets
+        .iter()
+        .map(|(name, source)| {
+            validate_env_name(name).map_err(|e| anyhow!("secrets: {}", e))?;
+            let value =
+                resolve(source).with_context(|| format!("Failed to resolve secret '{}'", name))?;
+            Ok(Secret {
+                name: name.clone(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Resolve a single secret source to its value.
+pub fn resolve(source: &SecretSource) -> Result<String> {
+    let mut cmd = match source {
+        SecretSource::Command { command } => {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        }
+        SecretSource::Keychain { keychain, account } => {
+            let mut cmd = Command::new("security");
+            cmd.args(["find-generic-password", "-s", keychain]);
+            if let Some(account) = account {
+                cmd.args(["-a", account]);
+            }
+            cmd.arg("-w");
+            cmd
+        }
+        SecretSource::Pass { pass } => {
+            let mut cmd = Command::new("pass");
+            cmd.args(["show", pass]);
+            cmd
+        }
+    };
+
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run '{}'", program))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "'{}' exited with {}: {}",
+            program,
+            output.status,
+            stderr.trim()
+        ));
+    }
+
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("'{}' produced non-UTF-8 output", program))?;
+    let value = match source {
+        // pass stores metadata after the first line
+        SecretSource::Pass { .. } => stdout.lines().next().unwrap_or("").to_string(),
+        _ => stdout.trim_end_matches(['\n', '\r']).to_string(),
+    };
+    if value.is_empty() {
+        bail!("'{}' returned an empty value", program);
+    }
+    Ok(value)
+}
+
+/// Replace every occurrence of a secret value in `text` with `<redacted>`.
+pub fn redact(text: &str, values: &[String]) -> String {
+    let mut out = text.to_string();
+    for value in values {
+        if value.len() >= MIN_REDACT_LEN && out.contains(value.as_str()) {
+            out = out.replace(value.as_str(), REDACTED);
+        }
+    }
+    out
+}
+
+/// Write secrets as shell `export` statements to a private file in `dir`.
+///
+/// The file is created with mode 0600 and a unique name so concurrent
+/// supervisors for the same VM do not clobber each other. Returns the host
+/// path; the caller maps it to the guest path and removes it after launch.
+pub fn write_env_file(dir: &Path, secrets: &[Secret]) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("secrets-{}.env", std::process::id()));
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    for secret in secrets {
+        writeln!(
+            file,
+            "export {}='{}'",
+            secret.name,
+            shell_escape(&secret.value)
+        )?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_env_name_accepts_common_names() {
+        assert!(validate_env_name("ANTHROPIC_API_KEY").is_ok());
+        assert!(validate_env_name("_private").is_ok());
+        assert!(validate_env_name("token2").is_ok());
+    }
+
+    #[test]
+    fn validate_env_name_rejects_invalid_and_reserved() {
+        for name in ["", "2FA", "MY-KEY", "A B", "WM_RPC_TOKEN", "PATH", "HOME"] {
+            assert!(
+                validate_env_name(name).is_err(),
+                "{name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_command_source_strips_trailing_newline() {
+        let source = SecretSource::Command {
+            command: "printf 'sk-test\\n'".to_string(),
+        };
+        assert_eq!(resolve(&source).unwrap(), "sk-test");
+    }
+
+    #[test]
+    fn resolve_command_source_failure_is_error() {
+        let source = SecretSource::Command {
+            command: "exit 3".to_string(),
+        };
+        assert!(resolve(&source).is_err());
+    }
+
+    #[test]
+    fn resolve_command_source_empty_is_error() {
+        let source = SecretSource::Command {
+            command: "true".to_string(),
+        };
+        assert!(resolve(&source).is_err());
+    }
+
+    #[test]
+    fn redact_replaces_all_occurrences() {
+        let values = vec!["sk-secret-123".to_string()];
+        let text = r#"Exec { args: ["sk-secret-123", "--key=sk-secret-123"] }"#;
+        let redacted = redact(text, &values);
+        assert!(!redacted.contains("sk-secret-123"));
+        assert_eq!(redacted.matches(REDACTED).count(), 2);
+    }
+
+    #[test]
+    fn redact_ignores_short_values() {
+        let values = vec!["ab".to_string()];
+        assert_eq!(redact("abc", &values), "abc");
+    }
+
+    #[test]
+    fn secret_debug_hides_value() {
+        let secret = Secret {
+            name: "KEY".to_string(),
+            value: "hunter22".to_string(),
+        };
+        assert!(!format!("{:?}", secret).contains("hunter22"));
+    }
+
+    #[test]
+    fn write_env_file_is_private_and_quoted() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = vec![Secret {
+            name: "KEY".to_string(),
+            value: "it's".to_string(),
+        }];
+        let path = write_env_file(tmp.path(), &secrets).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "export KEY='it'\\''s'\n"
+        );
+    }
+}
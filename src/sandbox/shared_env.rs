@@ -0,0 +1,136 @@
+//! Environment shared by the sandboxed agent and the host panes of a worktree.
+//!
+//! With `sandbox.target: agent` only the agent runs in the sandbox; helper
+//! panes (dev servers, watchers) run on the host. `sandbox.shared_env` lists
+//! variables both sides need to agree on. They are written to one env file
+//! per worktree, regenerated from the config whenever panes or the sandbox
+//! start: host panes source it before their command runs, and the sandbox
+//! supervisor starts the container or VM with the same values.
+
+use anyhow::{Context, Result, anyhow};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::config::SandboxConfig;
+use crate::shell::shell_escape;
+use crate::state::store::get_state_dir;
+
+use super::secrets::validate_env_name;
+
+/// The shared variables of one worktree and the file holding them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedEnv {
+    pub path: PathBuf,
+    pub vars: BTreeMap<String, String>,
+}
+
+impl SharedEnv {
+    /// POSIX shell prefix exporting the variables from the env file, for the
+    /// start script of host panes.
+    pub fn source_prefix(&self) -> String {
+        format!(
+            "set -a; . '{}'; set +a; ",
+            shell_escape(&self.path.to_string_lossy())
+        )
+    }
+}
+
+/// Write the shared env file of the worktree at `worktree_root` from the
+/// config, if it changed. Returns `None`, and removes a file left from an
+/// earlier config, when sandboxing is off or no variables are configured.
+pub fn sync(config: &SandboxConfig, worktree_root: &Path) -> Result<Option<SharedEnv>> {
+    let path = env_file_path(worktree_root)?;
+    let vars = match config.shared_env() {
+        Some(vars) if config.is_enabled() => vars,
+        _ => {
+            if path.exists() {
+                fs::remove_file(&path).context("Failed to remove shared env file")?;
+            }
+            return Ok(None);
+        }
+    };
+    for name in vars.keys() {
+        validate_env_name(name).map_err(|e| anyhow!("sandbox.shared_env: {}", e))?;
+    }
+
+    let content = render(vars);
+    if fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create shared env directory")?;
+        }
+        fs::write(&path, &content).context("Failed to write shared env file")?;
+        debug!(path = %path.display(), "sandbox:shared env file updated");
+    }
+    Ok(Some(SharedEnv {
+        path,
+        vars: vars.clone(),
+    }))
+}
+
+/// Env file of a worktree: `$XDG_STATE_HOME/workmux/env/<worktree>.env`.
+fn env_file_path(worktree_root: &Path) -> Result<PathBuf> {
+    let name = utf8_percent_encode(&worktree_root.to_string_lossy(), NON_ALPHANUMERIC).to_string();
+    Ok(get_state_dir()?
+        .join("workmux")
+        .join("env")
+        .join(format!("{}.env", name)))
+}
+
+fn render(vars: &BTreeMap<String, String>) -> String {
+    let mut content =
+        String::from("# Generated by workmux from sandbox.shared_env; edits are overwritten.\n");
+    for (name, value) in vars {
+        content.push_str(&format!("{}='{}'\n", name, shell_escape(value)));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> SandboxConfig {
+        SandboxConfig {
+            enabled: Some(true),
+            shared_env: Some(
+                vars.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..SandboxConfig::default()
+        }
+    }
+
+    #[test]
+    fn values_are_quoted_for_sourcing() {
+        let vars = config(&[("API_PORT", "4000"), ("GREETING", "it's on")])
+            .shared_env
+            .unwrap();
+        assert_eq!(
+            render(&vars).lines().skip(1).collect::<Vec<_>>(),
+            ["API_PORT='4000'", "GREETING='it'\\''s on'"]
+        );
+    }
+
+    #[test]
+    fn reserved_names_are_rejected() {
+        let worktree = Path::new("/src/app__worktrees/login");
+        assert!(sync(&config(&[("WM_RPC_PORT", "1")]), worktree).is_err());
+        assert!(sync(&config(&[("PATH", "/bin")]), worktree).is_err());
+    }
+
+    #[test]
+    fn source_prefix_exports_the_file() {
+        let env = SharedEnv {
+            path: PathBuf::from("/state/env/x.env"),
+            vars: BTreeMap::new(),
+        };
+        assert_eq!(
+            env.source_prefix(),
+            "set -a; . '/state/env/x.env'; set +a; "
+        );
+    }
+}
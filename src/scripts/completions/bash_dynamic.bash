@@ -9,6 +9,11 @@ _workmux_git_branches() {
     workmux _complete-git-branches 2>/dev/null
 }
 
+# Dynamic base branch completion for add --base (includes remote branches)
+_workmux_base_branches() {
+    workmux _complete-base-branches 2>/dev/null
+}
+
 # Wrapper that adds dynamic completion
 _workmux_dynamic() {
     local cur prev words cword
@@ -50,8 +55,8 @@ _workmux_dynamic() {
             add)
                 # Handle flags that take specific argument types
                 case "$prev" in
-                    --base|-b)
-                        COMPREPLY=($(compgen -W "$(_workmux_git_branches)" -- "$cur"))
+                    --base)
+                        COMPREPLY=($(compgen -W "$(_workmux_base_branches)" -- "$cur"))
                         return
                         ;;
                     --prompt-file|-P)
@@ -9,8 +9,15 @@ function __workmux_git_branches
     workmux _complete-git-branches 2>/dev/null
 end
 
+# Dynamic base branch completion for add --base (includes remote branches)
+function __workmux_base_branches
+    workmux _complete-base-branches 2>/dev/null
+end
+
 # Add dynamic completions for commands that take worktree handles or branch names
 # (handles are the primary identifier shown in completions)
 complete -c workmux -n '__fish_seen_subcommand_from open remove rm path merge close send capture status wait run' -f -a '(__workmux_handles)'
 # Add dynamic completions for add command (uses git branches)
 complete -c workmux -n '__fish_seen_subcommand_from add' -f -a '(__workmux_git_branches)'
+# Add dynamic completions for add --base (local and remote branches)
+complete -c workmux -n '__fish_seen_subcommand_from add' -l base -x -a '(__workmux_base_branches)'
@@ -1,4 +1,10 @@
 //! Shell escaping utilities.
+//!
+//! Besides quoting, this holds the checks behind `strict_quoting`: a POSIX
+//! word splitter to parse generated commands back, round-trip checks for
+//! quoted arguments, and a check for names that would need quoting at all.
+
+use anyhow::{Result, anyhow, bail};
 
 /// Escape single quotes within a string for use inside a single-quoted shell argument.
 ///
@@ -17,18 +23,216 @@ pub fn shell_quote(s: &str) -> String {
     if s.is_empty() {
         return "''".to_string();
     }
-    if s.chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '/')
-    {
+    if s.chars().all(is_safe_char) {
         s.to_string()
     } else {
         format!("'{}'", shell_escape(s))
     }
 }
 
+/// Characters that never need quoting.
+fn is_safe_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '/'
+}
+
+/// Split a POSIX command line into words the way `sh` quote removal does.
+///
+/// Handles single quotes, double quotes (where `\` escapes `$`, `` ` ``,
+/// `"`, `\` and newline) and backslashes outside quotes. Expansions and
+/// operators are not interpreted, so `a && b` is three words. Fails on an
+/// unterminated quote or a trailing backslash.
+pub fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated single quote in: {}", line),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => bail!("Unterminated double quote in: {}", line),
+                        },
+                        Some(c) => word.push(c),
+                        None => bail!("Unterminated double quote in: {}", line),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => bail!("Trailing backslash in: {}", line),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Check that the POSIX command line `line` parses and passes `arg` as one
+/// of its words, as wrappers that embed a quoted command must.
+pub fn verify_embedded(line: &str, arg: &str) -> Result<()> {
+    if !split_words(line)?.iter().any(|word| word == arg) {
+        bail!(
+            "The command {:?} does not survive quoting in: {}",
+            arg,
+            line
+        );
+    }
+    Ok(())
+}
+
+/// Reject a name (`what` says which, e.g. "Branch name") that would need
+/// quoting in a shell command, for `strict_quoting`.
+pub fn check_name(what: &str, name: &str) -> Result<()> {
+    match name.chars().find(|&c| !is_safe_char(c)) {
+        Some(c) => Err(anyhow!(
+            "{} '{}' contains {:?}, which needs shell quoting. With strict_quoting, \
+             names may only use ASCII letters, digits, '-', '_', '.' and '/'",
+            what,
+            name.escape_debug(),
+            c
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Family of an interactive shell, which decides how commands typed into it
+/// are quoted and how it is started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    /// sh, bash, zsh, dash, ksh, ash
+    Posix,
+    /// fish: single quotes support `\'` and `\\` escapes
+    Fish,
+    /// nushell: single quotes are literal, raw strings hold the rest
+    Nushell,
+    /// Any other shell, quoted like POSIX
+    Other,
+}
+
+impl ShellKind {
+    /// Detect the kind from a shell path or name (`/usr/bin/fish`, `-zsh`).
+    pub fn detect(shell: &str) -> Self {
+        let name = shell.rsplit('/').next().unwrap_or(shell);
+        match name.trim_start_matches('-') {
+            "bash" | "zsh" | "sh" | "dash" | "ksh" | "ash" => ShellKind::Posix,
+            "fish" => ShellKind::Fish,
+            "nu" => ShellKind::Nushell,
+            _ => ShellKind::Other,
+        }
+    }
+
+    pub fn is_posix(self) -> bool {
+        self == ShellKind::Posix
+    }
+
+    /// Flag that starts the shell as a login shell. Unknown shells are
+    /// started without one since not all of them accept `-l` (e.g. elvish).
+    pub fn login_flag(self) -> Option<&'static str> {
+        match self {
+            ShellKind::Posix | ShellKind::Fish | ShellKind::Nushell => Some("-l"),
+            ShellKind::Other => None,
+        }
+    }
+
+    /// Quote `s` as a single argument for this shell.
+    pub fn quote(self, s: &str) -> String {
+        match self {
+            ShellKind::Posix | ShellKind::Other => format!("'{}'", shell_escape(s)),
+            ShellKind::Fish => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+            ShellKind::Nushell if !s.contains('\'') => format!("'{}'", s),
+            ShellKind::Nushell => {
+                // A raw string ends at a quote followed by as many `#` as it
+                // was opened with, so use one more than any run in `s`
+                let mut hashes = String::from("#");
+                while s.contains(&format!("'{}", hashes)) {
+                    hashes.push('#');
+                }
+                format!("r{}'{}'{}", hashes, s, hashes)
+            }
+        }
+    }
+
+    /// Reverse [`quote`](Self::quote). Returns `None` if `quoted` isn't a
+    /// single argument quoted that way.
+    pub fn unquote(self, quoted: &str) -> Option<String> {
+        match self {
+            ShellKind::Posix | ShellKind::Other => {
+                let inner = quoted.strip_prefix('\'')?.strip_suffix('\'')?;
+                Some(inner.replace("'\\''", "'"))
+            }
+            ShellKind::Fish => {
+                let inner = quoted.strip_prefix('\'')?.strip_suffix('\'')?;
+                let mut out = String::with_capacity(inner.len());
+                let mut chars = inner.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        c => out.push(c),
+                    }
+                }
+                Some(out)
+            }
+            ShellKind::Nushell => {
+                if let Some(raw) = quoted.strip_prefix('r') {
+                    let fence = "#".repeat(raw.len() - raw.trim_start_matches('#').len());
+                    let inner = raw
+                        .strip_prefix(&format!("{}'", fence))?
+                        .strip_suffix(&format!("'{}", fence))?;
+                    return Some(inner.to_string());
+                }
+                let inner = quoted.strip_prefix('\'')?.strip_suffix('\'')?;
+                Some(inner.to_string())
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// Check that `quoted` is read back by a `kind` shell as the single
+    /// argument `original`.
+    fn verify_quoted(kind: ShellKind, original: &str, quoted: &str) -> Result<()> {
+        let parsed = match kind {
+            ShellKind::Posix | ShellKind::Other => match split_words(quoted)?.as_slice() {
+                [word] => Some(word.clone()),
+                _ => None,
+            },
+            ShellKind::Fish | ShellKind::Nushell => kind.unquote(quoted),
+        };
+        if parsed.as_deref() != Some(original) {
+            bail!(
+                "Quoting {:?} produced {}, which does not read back as the same argument",
+                original,
+                quoted
+            );
+        }
+        Ok(())
+    }
 
     #[test]
     fn test_shell_escape_simple() {
@@ -74,4 +278,112 @@ mod tests {
     fn test_shell_quote_empty_string() {
         assert_eq!(shell_quote(""), "''");
     }
+
+    #[test]
+    fn detects_shell_kinds() {
+        assert_eq!(ShellKind::detect("/bin/zsh"), ShellKind::Posix);
+        assert_eq!(ShellKind::detect("-bash"), ShellKind::Posix);
+        assert_eq!(ShellKind::detect("/opt/homebrew/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::detect("/usr/bin/nu"), ShellKind::Nushell);
+        assert_eq!(ShellKind::detect("/usr/bin/elvish"), ShellKind::Other);
+        assert_eq!(ShellKind::Other.login_flag(), None);
+    }
+
+    #[test]
+    fn fish_quoting_escapes_quotes_and_backslashes() {
+        let fish = ShellKind::Fish;
+        assert_eq!(fish.quote("echo $HOME"), "'echo $HOME'");
+        assert_eq!(fish.quote(r"it's a\b"), r"'it\'s a\\b'");
+        assert_eq!(fish.unquote(r"'it\'s a\\b'").unwrap(), r"it's a\b");
+    }
+
+    #[test]
+    fn nushell_quoting_uses_raw_strings_for_quotes() {
+        let nu = ShellKind::Nushell;
+        assert_eq!(
+            nu.quote(r#"claude -- "$(cat P.md)""#),
+            r#"'claude -- "$(cat P.md)"'"#
+        );
+        assert_eq!(nu.quote("echo 'hi'"), "r#'echo 'hi''#");
+        assert_eq!(nu.quote("a '# b"), "r##'a '# b'##");
+        for s in ["plain", "echo 'hi'", "a '# b", r"back\slash"] {
+            assert_eq!(nu.unquote(&nu.quote(s)).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn posix_unquote_reverses_quote() {
+        let quoted = ShellKind::Posix.quote("echo 'quoted'");
+        assert_eq!(ShellKind::Posix.unquote(&quoted).unwrap(), "echo 'quoted'");
+    }
+
+    #[test]
+    fn split_words_removes_quotes() {
+        assert_eq!(
+            split_words(r#"echo 'a b' "c \"d\" \$e \x" f\ g ''"#).unwrap(),
+            ["echo", "a b", r#"c "d" $e \x"#, "f g", ""]
+        );
+        assert_eq!(split_words("  a\tb\n").unwrap(), ["a", "b"]);
+        assert_eq!(split_words("a\\\nb").unwrap(), ["ab"]);
+        assert!(split_words("echo 'open").is_err());
+        assert!(split_words("echo \"open").is_err());
+        assert!(split_words("echo \\").is_err());
+    }
+
+    #[test]
+    fn verify_quoted_catches_broken_quoting() {
+        assert!(verify_quoted(ShellKind::Posix, "it's", &shell_quote("it's")).is_ok());
+        assert!(verify_quoted(ShellKind::Posix, "it's", "'it's'").is_err());
+        assert!(verify_quoted(ShellKind::Posix, "a b", "a b").is_err());
+        assert!(verify_quoted(ShellKind::Fish, r"a\b", r"'a\b'").is_err());
+    }
+
+    #[test]
+    fn verify_embedded_finds_the_command() {
+        let line = format!(
+            "workmux sandbox run '/tmp' -- '{}'",
+            shell_escape("echo 'hi'")
+        );
+        assert!(verify_embedded(&line, "echo 'hi'").is_ok());
+        assert!(verify_embedded("workmux sandbox run -- 'echo 'hi''", "echo 'hi'").is_err());
+    }
+
+    #[test]
+    fn check_name_rejects_characters_that_need_quoting() {
+        assert!(check_name("Branch name", "feature/login-v2_1.0").is_ok());
+        let err = check_name("Branch name", "fix-$HOME").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Branch name 'fix-$HOME' contains '$'")
+        );
+        assert!(check_name("Branch name", "a\nb").is_err());
+        assert!(check_name("Branch name", "naïve").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn posix_quoting_round_trips(s in any::<String>()) {
+            prop_assert_eq!(split_words(&shell_quote(&s)).unwrap(), vec![s.clone()]);
+            prop_assert!(verify_quoted(ShellKind::Posix, &s, &ShellKind::Posix.quote(&s)).is_ok());
+        }
+
+        #[test]
+        fn quoted_arguments_split_back_apart(args in prop::collection::vec(any::<String>(), 0..6)) {
+            let line = args.iter().map(|a| shell_quote(a)).collect::<Vec<_>>().join(" ");
+            prop_assert_eq!(split_words(&line).unwrap(), args);
+        }
+
+        #[test]
+        fn fish_and_nushell_quoting_round_trip(s in any::<String>()) {
+            for kind in [ShellKind::Fish, ShellKind::Nushell] {
+                prop_assert!(verify_quoted(kind, &s, &kind.quote(&s)).is_ok());
+            }
+        }
+
+        #[test]
+        fn safe_names_need_no_quoting(s in "[a-zA-Z0-9/._-]{1,40}") {
+            prop_assert!(check_name("Name", &s).is_ok());
+            prop_assert_eq!(shell_quote(&s), s);
+        }
+    }
 }
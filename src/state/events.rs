@@ -0,0 +1,137 @@
+//! Append-only log of notable workmux events.
+//!
+//! Each line of `<state dir>/workmux/events.jsonl` is one JSON event with the
+//! Unix time it happened, so scripts can follow what workmux did in the
+//! background (e.g. `tail -f` piped through `jq`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::store::get_state_dir;
+
+/// A worktree created with `workmux add --background` is ready.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorktreeReady {
+    pub handle: String,
+    pub branch: String,
+    /// Window (or session) name in the multiplexer
+    pub window: String,
+    pub worktree_path: PathBuf,
+    /// Lima VM the sandboxed panes run in
+    pub sandbox_vm: Option<String>,
+    /// Number of post-create hooks that ran (all succeeded)
+    pub post_create_hooks: usize,
+    /// First lines of output from the focused (agent) pane
+    pub agent_output: Vec<String>,
+}
+
+/// A sandboxed agent asked the host to run a command it isn't allowed to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecDenied {
+    pub handle: String,
+    pub worktree_path: PathBuf,
+    /// Pane of the sandboxed agent
+    pub pane_id: String,
+    pub command: String,
+    /// Arguments, with injected secrets redacted
+    pub args: Vec<String>,
+    /// Why it was refused
+    pub reason: String,
+}
+
+/// The user decided on a host command a sandboxed agent asked to run
+/// outside its allowlist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecDecided {
+    pub handle: String,
+    pub worktree_path: PathBuf,
+    /// Pane of the sandboxed agent
+    pub pane_id: String,
+    pub command: String,
+    /// Arguments, with injected secrets redacted
+    pub args: Vec<String>,
+    /// False when denied, or when nobody answered in time
+    pub approved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    WorktreeReady(WorktreeReady),
+    ExecDenied(ExecDenied),
+    ExecDecided(ExecDecided),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    ts: u64,
+    #[serde(flatten)]
+    event: Event,
+}
+
+/// Path of the event log.
+pub fn events_path() -> Result<PathBuf> {
+    Ok(get_state_dir()?.join("workmux").join("events.jsonl"))
+}
+
+/// Append an event to the event log.
+pub fn append(event: &Event) -> Result<()> {
+    append_to(&events_path()?, event)
+}
+
+fn append_to(path: &Path, event: &Event) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create state directory")?;
+    }
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut line = serde_json::to_string(&Record {
+        ts,
+        event: event.clone(),
+    })?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .context("Failed to write event log")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_writes_one_tagged_line_per_event() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("workmux/events.jsonl");
+        let event = Event::WorktreeReady(WorktreeReady {
+            handle: "auth".to_string(),
+            branch: "feature/auth".to_string(),
+            window: "wm-auth".to_string(),
+            worktree_path: PathBuf::from("/code/api__worktrees/auth"),
+            sandbox_vm: None,
+            post_create_hooks: 2,
+            agent_output: vec!["Reading the codebase".to_string()],
+        });
+        append_to(&path, &event).unwrap();
+        append_to(&path, &event).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let value: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(value["event"], "worktree_ready");
+        assert_eq!(value["window"], "wm-auth");
+        assert!(value["ts"].as_u64().is_some());
+        let record: Record = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(record.event, event);
+    }
+}
@@ -0,0 +1,148 @@
+//! Status history of an agent: the statuses it changed to and for how long.
+//!
+//! Every status change is appended to the agent's state file, so the
+//! dashboard can show how an agent spent its time, spot one that keeps
+//! bouncing between working and waiting, and measure how long it took to
+//! first come back with an answer.
+
+use crate::multiplexer::AgentStatus;
+
+use super::types::StatusChange;
+
+/// Status changes kept per agent. Older ones are dropped first.
+pub const HISTORY_LIMIT: usize = 200;
+
+/// Append a change to `status` at `ts`, unless the agent already has it.
+pub fn record(history: &mut Vec<StatusChange>, status: AgentStatus, ts: u64) {
+    if history.last().is_some_and(|last| last.status == status) {
+        return;
+    }
+    history.push(StatusChange { status, ts });
+    if history.len() > HISTORY_LIMIT {
+        history.drain(..history.len() - HISTORY_LIMIT);
+    }
+}
+
+/// One entry of the timeline: a status and how long the agent had it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub status: AgentStatus,
+    pub start: u64,
+    pub secs: u64,
+}
+
+/// The periods of `history`, the last one lasting until `now`.
+pub fn periods(history: &[StatusChange], now: u64) -> Vec<Period> {
+    history
+        .iter()
+        .enumerate()
+        .map(|(i, change)| {
+            let end = history.get(i + 1).map_or(now, |next| next.ts);
+            Period {
+                status: change.status,
+                start: change.ts,
+                secs: end.saturating_sub(change.ts),
+            }
+        })
+        .collect()
+}
+
+/// Totals over an agent's history.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub working_secs: u64,
+    pub waiting_secs: u64,
+    pub done_secs: u64,
+    pub unknown_secs: u64,
+    /// Times the agent stopped to wait for input
+    pub waits: usize,
+    /// Seconds from first starting work to first waiting or finishing
+    pub first_response_secs: Option<u64>,
+}
+
+pub fn summarize(history: &[StatusChange], now: u64) -> Summary {
+    let mut summary = Summary::default();
+    for period in periods(history, now) {
+        match period.status {
+            AgentStatus::Working => summary.working_secs += period.secs,
+            AgentStatus::Waiting => {
+                summary.waiting_secs += period.secs;
+                summary.waits += 1;
+            }
+            AgentStatus::Done => summary.done_secs += period.secs,
+            AgentStatus::Unknown => summary.unknown_secs += period.secs,
+        }
+    }
+    let started = history
+        .iter()
+        .position(|c| c.status == AgentStatus::Working);
+    summary.first_response_secs = started.and_then(|i| {
+        history[i..]
+            .iter()
+            .find(|c| matches!(c.status, AgentStatus::Waiting | AgentStatus::Done))
+            .map(|c| c.ts - history[i].ts)
+    });
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(status: AgentStatus, ts: u64) -> StatusChange {
+        StatusChange { status, ts }
+    }
+
+    #[test]
+    fn record_skips_repeats_and_drops_the_oldest() {
+        let mut history = Vec::new();
+        record(&mut history, AgentStatus::Working, 10);
+        record(&mut history, AgentStatus::Working, 20);
+        record(&mut history, AgentStatus::Waiting, 30);
+        assert_eq!(
+            history,
+            [
+                change(AgentStatus::Working, 10),
+                change(AgentStatus::Waiting, 30)
+            ]
+        );
+
+        for ts in 0..HISTORY_LIMIT as u64 {
+            let status = if ts % 2 == 0 {
+                AgentStatus::Working
+            } else {
+                AgentStatus::Waiting
+            };
+            record(&mut history, status, 100 + ts);
+        }
+        assert_eq!(history.len(), HISTORY_LIMIT);
+        assert_eq!(history[0].ts, 100);
+    }
+
+    #[test]
+    fn summary_totals_time_per_status_and_first_response() {
+        let history = [
+            change(AgentStatus::Waiting, 0),
+            change(AgentStatus::Working, 100),
+            change(AgentStatus::Waiting, 145),
+            change(AgentStatus::Working, 200),
+            change(AgentStatus::Done, 500),
+        ];
+        let periods = periods(&history, 560);
+        assert_eq!(periods[1].secs, 45);
+        assert_eq!(periods[4].secs, 60);
+
+        assert_eq!(
+            summarize(&history, 560),
+            Summary {
+                working_secs: 345,
+                waiting_secs: 155,
+                done_secs: 60,
+                unknown_secs: 0,
+                waits: 2,
+                first_response_secs: Some(45),
+            }
+        );
+        assert_eq!(summarize(&[], 10), Summary::default());
+    }
+}
@@ -0,0 +1,225 @@
+//! Liveness checks for agents that stop reporting.
+//!
+//! Status comes from agent hooks, so an agent that dies without firing its
+//! final hook stays "working" forever. Two signals tell a live agent from a
+//! dead one:
+//!
+//! - **Heartbeat**: every status report, and `workmux set-window-status
+//!   heartbeat` (sent by the hooks, or over RPC from a sandbox), refreshes
+//!   the agent's `heartbeat_ts`.
+//! - **Process**: the pane's process that reported the status (the agent, or
+//!   the sandbox supervisor) is recorded as `agent_pid` and checked with
+//!   `kill(pid, 0)`.
+//!
+//! Long tool calls send no heartbeats, so only an agent failing both checks
+//! counts as dead. The reconciler then downgrades it to `unknown`.
+//!
+//! A working agent whose hook never fired is still running but sits idle at
+//! its prompt. The process table (see `multiplexer::process`) tells that
+//! apart from a long tool call, so a quiet working agent that uses no CPU and
+//! runs nothing is downgraded too.
+
+use crate::multiplexer::{AgentStatus, ProcessActivity};
+
+use super::AgentState;
+
+/// Whether `state` describes a working or waiting agent that has gone
+/// quiet for longer than `timeout_secs` and whose process has exited.
+pub fn is_dead(state: &AgentState, now: u64, timeout_secs: u64) -> bool {
+    is_quiet(state, now, timeout_secs) && process_gone(state)
+}
+
+/// Whether `state` describes a working agent that has gone quiet for longer
+/// than `timeout_secs` while its process sits idle, per `process`.
+pub fn is_idle(
+    state: &AgentState,
+    process: Option<&ProcessActivity>,
+    now: u64,
+    timeout_secs: u64,
+) -> bool {
+    state.status == Some(AgentStatus::Working)
+        && is_quiet(state, now, timeout_secs)
+        && process.is_some_and(ProcessActivity::is_idle)
+}
+
+fn is_quiet(state: &AgentState, now: u64, timeout_secs: u64) -> bool {
+    let last_heartbeat = state.heartbeat_ts.unwrap_or(state.updated_ts);
+    now.saturating_sub(last_heartbeat) > timeout_secs
+}
+
+/// Whether `state` describes a working or waiting agent whose recorded
+/// process has exited.
+pub fn process_gone(state: &AgentState) -> bool {
+    if !matches!(
+        state.status,
+        Some(AgentStatus::Working | AgentStatus::Waiting)
+    ) {
+        return false;
+    }
+    // Without a recorded process there is nothing to confirm the death with
+    state.agent_pid.is_some_and(|pid| !process_alive(pid))
+}
+
+/// Whether a process with `pid` exists.
+pub fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks whether the process can be signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    // The process exists but belongs to someone else
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// The ancestor of the current process that is a direct child of the pane's
+/// shell (`pane_pid`): the agent a hook was run by, or the sandbox
+/// supervisor. `None` when the current process doesn't run in the pane,
+/// e.g. the dashboard.
+pub fn pane_child_of_current(pane_pid: u32) -> Option<u32> {
+    let mut pid = std::process::id();
+    // Bounded in case of a cycle in a racy process listing
+    for _ in 0..32 {
+        let parent = parent_pid(pid)?;
+        if parent == pane_pid {
+            return Some(pid);
+        }
+        if parent <= 1 {
+            return None;
+        }
+        pid = parent;
+    }
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat_ppid(&stat)
+}
+
+/// Parent PID from `/proc/<pid>/stat`. The command name is in parentheses
+/// and may contain spaces, so fields are counted from the last `)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    stat.get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn parent_pid(pid: u32) -> Option<u32> {
+    crate::cmd::Cmd::new("ps")
+        .args(&["-o", "ppid=", "-p", &pid.to_string()])
+        .run_and_capture_stdout()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PaneKey;
+    use std::path::PathBuf;
+
+    fn state(status: AgentStatus, heartbeat_ts: u64, agent_pid: u32) -> AgentState {
+        AgentState {
+            pane_key: PaneKey {
+                backend: "tmux".to_string(),
+                instance: "default".to_string(),
+                pane_id: "%1".to_string(),
+            },
+            workdir: PathBuf::from("/tmp"),
+            status: Some(status),
+            status_ts: Some(0),
+            pane_title: None,
+            pane_pid: 1,
+            command: "node".to_string(),
+            updated_ts: 0,
+            server_url: None,
+            progress: None,
+            task_progress: None,
+            priority: None,
+            paused_by: None,
+            denied_exec: None,
+            net_activity: None,
+            heartbeat_ts: Some(heartbeat_ts),
+            agent_pid: Some(agent_pid),
+            history: Vec::new(),
+        }
+    }
+
+    /// PID of a process that has exited and been reaped.
+    fn exited_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn agents_are_dead_only_when_both_checks_fail() {
+        let dead = exited_pid();
+        let alive = std::process::id();
+
+        assert!(is_dead(&state(AgentStatus::Working, 100, dead), 500, 300));
+        assert!(is_dead(&state(AgentStatus::Waiting, 100, dead), 500, 300));
+        // Fresh heartbeat
+        assert!(!is_dead(&state(AgentStatus::Working, 300, dead), 500, 300));
+        // Quiet but still running, e.g. a long build
+        assert!(!is_dead(&state(AgentStatus::Working, 100, alive), 500, 300));
+        // Finished agents are left alone
+        assert!(!is_dead(&state(AgentStatus::Done, 100, dead), 500, 300));
+
+        let mut unrecorded = state(AgentStatus::Working, 100, dead);
+        unrecorded.agent_pid = None;
+        assert!(!is_dead(&unrecorded, 500, 300));
+    }
+
+    #[test]
+    fn quiet_working_agents_are_idle_only_without_activity() {
+        let alive = std::process::id();
+        let idle = ProcessActivity {
+            child: None,
+            cpu_percent: Some(0.2),
+        };
+        let building = ProcessActivity {
+            child: Some("cargo".to_string()),
+            cpu_percent: Some(180.0),
+        };
+        let working = state(AgentStatus::Working, 100, alive);
+
+        assert!(is_idle(&working, Some(&idle), 500, 300));
+        assert!(!is_idle(&working, Some(&building), 500, 300));
+        // Not sampled yet
+        let unsampled = ProcessActivity::default();
+        assert!(!is_idle(&working, Some(&unsampled), 500, 300));
+        assert!(!is_idle(&working, None, 500, 300));
+        // Fresh heartbeat
+        assert!(!is_idle(&working, Some(&idle), 350, 300));
+        // Waiting agents are idle at their prompt by design
+        let waiting = state(AgentStatus::Waiting, 100, alive);
+        assert!(!is_idle(&waiting, Some(&idle), 500, 300));
+    }
+
+    #[test]
+    fn parent_pid_is_read_after_the_command_name() {
+        assert_eq!(
+            parse_stat_ppid("4242 (tmux: server) S 17 4242 4242 0 -1"),
+            Some(17)
+        );
+        assert_eq!(parse_stat_ppid("garbage"), None);
+    }
+
+    #[test]
+    fn pane_child_is_found_among_ancestors() {
+        let parent = parent_pid(std::process::id()).unwrap();
+        assert_eq!(pane_child_of_current(parent), Some(std::process::id()));
+        assert_eq!(pane_child_of_current(u32::MAX), None);
+    }
+}
@@ -3,18 +3,30 @@
 //! This module provides persistent state storage that works across all
 //! terminal multiplexer backends (tmux, WezTerm, Zellij).
 
+pub mod events;
+pub mod history;
+pub mod liveness;
+pub mod pending;
+pub mod preempt;
 pub mod run;
+pub mod schema;
 pub(crate) mod store;
 mod types;
+pub mod watch;
 
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use tracing::warn;
 
-use crate::multiplexer::{AgentStatus, Multiplexer};
+use crate::config::Config;
+use crate::multiplexer::{AgentStatus, DeniedExec, Multiplexer, NetActivity, TaskProgress};
 
-pub use store::StateStore;
-pub use types::{AgentState, PaneKey};
+pub use store::{StateLock, StateStore};
+pub use types::{
+    AgentState, CommentDrafts, ExecRequest, MergeCheckpoint, MergeStep, PaneKey, PendingPrompt,
+    PromptDelivery, SessionState, StatusChange,
+};
 
 /// Persist an agent state update to the StateStore.
 ///
@@ -23,13 +35,14 @@ pub use types::{AgentState, PaneKey};
 /// - If `title_override` is Some, uses it. If None, preserves existing stored title,
 ///   falling back to the live pane title.
 ///
-/// Logs warnings on failure without propagating errors (best-effort persistence).
+/// Returns whether the agent's status changed. Logs warnings on failure
+/// without propagating errors (best-effort persistence).
 pub fn persist_agent_update(
     mux: &dyn Multiplexer,
     pane_id: &str,
     status: Option<AgentStatus>,
     title_override: Option<String>,
-) {
+) -> bool {
     let pane_key = PaneKey {
         backend: mux.name().to_string(),
         instance: mux.instance_id(),
@@ -40,11 +53,11 @@ pub fn persist_agent_update(
         Ok(Some(info)) => info,
         Ok(None) => {
             warn!(%pane_id, "pane not found, skipping state persist");
-            return;
+            return false;
         }
         Err(e) => {
             warn!(error = %e, "failed to get live pane info, skipping state persist");
-            return;
+            return false;
         }
     };
 
@@ -53,21 +66,77 @@ pub fn persist_agent_update(
         .map(|d| d.as_secs())
         .unwrap_or(0);
 
+    let store = match StateStore::new() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!(error = %e, "failed to open state store, skipping state persist");
+            return false;
+        }
+    };
+    // Hooks for several panes can fire at once; keep the merge below atomic
+    let lock = store.lock();
+    if let Err(e) = &lock {
+        warn!(error = %e, "failed to lock state store, persisting without it");
+    }
+
     // Load existing state to merge with
-    let existing = StateStore::new()
-        .ok()
-        .and_then(|store| store.get_agent(&pane_key).ok().flatten());
+    let existing = store.get_agent(&pane_key).ok().flatten();
 
     // Resolve status: explicit update wins, otherwise preserve existing
     let final_status = status.or(existing.as_ref().and_then(|e| e.status));
 
+    let status_changed = final_status != existing.as_ref().and_then(|e| e.status);
+
     // Preserve existing status_ts if status hasn't changed (avoids resetting timer)
-    let status_ts = if final_status == existing.as_ref().and_then(|e| e.status) {
+    let status_ts = if !status_changed {
         existing.as_ref().and_then(|e| e.status_ts).unwrap_or(now)
     } else {
         now
     };
 
+    // Server URL, progress, preemption state, denied commands and network
+    // activity are maintained separately; carry them over
+    let (server_url, progress, priority, paused_by, denied_exec, net_activity) = existing
+        .as_ref()
+        .map(|e| {
+            (
+                e.server_url.clone(),
+                e.progress.clone(),
+                e.priority,
+                e.paused_by.clone(),
+                e.denied_exec.clone(),
+                e.net_activity.clone(),
+            )
+        })
+        .unwrap_or_default();
+
+    // Reported task progress belongs to the previous prompt once a finished
+    // agent starts working again
+    let restarted = existing.as_ref().and_then(|e| e.status) == Some(AgentStatus::Done)
+        && final_status == Some(AgentStatus::Working);
+    let task_progress = existing
+        .as_ref()
+        .and_then(|e| e.task_progress.clone())
+        .filter(|_| !restarted);
+
+    // Every report doubles as a heartbeat. The reporting process is only
+    // found when it runs in the pane; otherwise keep the one recorded for
+    // the same pane shell
+    let agent_pid = liveness::pane_child_of_current(live_info.pid).or(existing
+        .as_ref()
+        .filter(|e| e.pane_pid == live_info.pid)
+        .and_then(|e| e.agent_pid));
+
+    // A new shell in the pane means a new agent, whose history starts over
+    let mut history = existing
+        .as_ref()
+        .filter(|e| e.pane_pid == live_info.pid)
+        .map(|e| e.history.clone())
+        .unwrap_or_default();
+    if status_changed && let Some(status) = final_status {
+        history::record(&mut history, status, now);
+    }
+
     // Resolve title: explicit override wins, then existing stored title, then live
     let pane_title = title_override
         .or(existing.and_then(|e| e.pane_title))
@@ -82,11 +151,328 @@ pub fn persist_agent_update(
         pane_pid: live_info.pid,
         command: live_info.current_command,
         updated_ts: now,
+        server_url,
+        progress,
+        task_progress,
+        priority,
+        paused_by,
+        denied_exec,
+        net_activity,
+        heartbeat_ts: Some(now),
+        agent_pid,
+        history,
     };
 
-    if let Ok(store) = StateStore::new()
-        && let Err(e) = store.upsert_agent(&state)
-    {
+    if let Err(e) = store.upsert_agent(&state) {
         warn!(error = %e, "failed to persist agent state");
     }
+    drop(lock);
+
+    if status_changed && let Some(status) = final_status {
+        notify_status_plugins(pane_id, &state.workdir, status);
+        crate::notify::status_changed(pane_id, &state.workdir, status, state.pane_title.as_deref());
+    }
+    status_changed
+}
+
+/// Tell plugins that the agent in a worktree changed status.
+///
+/// Panes outside a linked worktree are ignored. Best-effort like the rest of
+/// status persistence.
+fn notify_status_plugins(pane_id: &str, workdir: &Path, status: AgentStatus) {
+    let Ok(vcs) = crate::vcs::detect_vcs() else {
+        return;
+    };
+    let Ok(root) = vcs.get_repo_root_for(workdir) else {
+        return;
+    };
+    if vcs
+        .get_main_workspace_root()
+        .is_ok_and(|main| crate::util::canon_or_self(&main) == crate::util::canon_or_self(&root))
+    {
+        return;
+    }
+    let Some(handle) = root.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+        return;
+    };
+    let event = crate::plugin::PluginEvent::StatusChanged {
+        handle,
+        worktree_path: root,
+        pane_id: pane_id.to_string(),
+        status,
+    };
+    if let Err(e) = crate::plugin::dispatch(&event, vcs.as_ref()) {
+        warn!(error = %e, "plugin dispatch failed");
+    }
+}
+
+/// Record a heartbeat from the agent in `pane_id` without changing its
+/// status. Panes without agent state are ignored.
+///
+/// Best-effort: logs warnings on failure without propagating errors.
+pub fn record_heartbeat(mux: &dyn Multiplexer, pane_id: &str) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let agent_pid = match mux.get_live_pane_info(pane_id) {
+        Ok(Some(info)) => liveness::pane_child_of_current(info.pid),
+        _ => None,
+    };
+    let result = StateStore::new().and_then(|store| {
+        store.update_agent(&pane_key, |state| {
+            state.heartbeat_ts = Some(now);
+            if agent_pid.is_some() {
+                state.agent_pid = agent_pid;
+            }
+        })
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist agent heartbeat");
+    }
+}
+
+/// Clear the stored status of the agent in `pane_id`. With `only_seen`,
+/// only the statuses that clear once the user has looked at them (waiting,
+/// done and unknown) are cleared, so a focus hook can't wipe a newer "working".
+///
+/// Returns whether a status was cleared. Best-effort: logs warnings on
+/// failure without propagating errors.
+pub fn clear_agent_status(mux: &dyn Multiplexer, pane_id: &str, only_seen: bool) -> bool {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut cleared = false;
+    let result = StateStore::new().and_then(|store| {
+        store.update_agent(&pane_key, |state| {
+            let clear = match state.status {
+                Some(AgentStatus::Working) => !only_seen,
+                Some(_) => true,
+                None => false,
+            };
+            if clear {
+                state.status = None;
+                state.status_ts = Some(now);
+                state.updated_ts = now;
+                cleared = true;
+            }
+        })
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to clear agent status");
+    }
+    cleared
+}
+
+/// Record the HTTP server URL reported by an agent (OpenCode server mode).
+///
+/// Best-effort: logs warnings on failure without propagating errors.
+pub fn set_agent_server_url(mux: &dyn Multiplexer, pane_id: &str, url: String) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let result = StateStore::new().and_then(|store| {
+        store.update_agent(&pane_key, |state| {
+            if state.server_url.as_deref() != Some(url.as_str()) {
+                // Progress from a previous server instance no longer applies
+                state.progress = None;
+            }
+            state.server_url = Some(url);
+        })
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist agent server url");
+    }
+}
+
+/// Store progress reported by the agent in `pane_id`, or clear it with `None`.
+///
+/// A pane without agent state is registered first, so progress shows up in
+/// the dashboard even before the agent reports a status. Best-effort like
+/// the rest of status persistence.
+pub fn set_agent_task_progress(
+    mux: &dyn Multiplexer,
+    pane_id: &str,
+    task_progress: Option<TaskProgress>,
+) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let update = |store: &StateStore| {
+        store.update_agent(&pane_key, |state| {
+            state.task_progress = task_progress.clone();
+        })
+    };
+    let result = StateStore::new().and_then(|store| {
+        if !update(&store)? && task_progress.is_some() {
+            persist_agent_update(mux, pane_id, None, None);
+            update(&store)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist agent task progress");
+    }
+}
+
+/// Record that the sandbox refused to run `command` on the host for the
+/// agent in `pane_id`, for the dashboard's security badge.
+///
+/// A pane without agent state is registered first, like task progress.
+/// Best-effort like the rest of status persistence.
+pub fn record_denied_exec(mux: &dyn Multiplexer, pane_id: &str, command: &str) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let command = DeniedExec::sanitize_command(command);
+    let update = |store: &StateStore| {
+        store.update_agent(&pane_key, |state| {
+            let count = state.denied_exec.as_ref().map_or(0, |d| d.count);
+            state.denied_exec = Some(DeniedExec {
+                command: command.clone(),
+                count: count.saturating_add(1),
+                ts: now,
+            });
+        })
+    };
+    let result = StateStore::new().and_then(|store| {
+        if !update(&store)? {
+            persist_agent_update(mux, pane_id, None, None);
+            update(&store)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist denied command");
+    }
+}
+
+/// Add a network traffic sample for the sandboxed agent in `pane_id`, for
+/// the dashboard's activity sparkline.
+///
+/// A pane without agent state is registered first, like task progress.
+/// Best-effort like the rest of status persistence.
+pub fn record_net_activity(
+    mux: &dyn Multiplexer,
+    pane_id: &str,
+    rate: u64,
+    connections: Option<u64>,
+) {
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let update = |store: &StateStore| {
+        store.update_agent(&pane_key, |state| {
+            state
+                .net_activity
+                .get_or_insert_with(NetActivity::default)
+                .push(rate, connections, now);
+        })
+    };
+    let result = StateStore::new().and_then(|store| {
+        if !update(&store)? {
+            persist_agent_update(mux, pane_id, None, None);
+            update(&store)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist network activity");
+    }
+}
+
+/// Recompute the rolled-up status of the session containing `pane_id`.
+///
+/// Only applies to workmux-managed sessions (session mode), identified by the
+/// configured window prefix. The rollup uses worst-of semantics across all
+/// live agents in the session, is persisted to the StateStore, and mirrored
+/// to the backend's session status icon.
+///
+/// Best-effort: logs warnings on failure without propagating errors.
+pub fn refresh_session_status(mux: &dyn Multiplexer, pane_id: &str, config: &Config) {
+    let session = match mux.get_live_pane_info(pane_id) {
+        Ok(Some(info)) => info.session,
+        _ => None,
+    };
+    let Some(session) = session.filter(|s| s.starts_with(config.window_prefix())) else {
+        return;
+    };
+
+    let Ok(store) = StateStore::new() else {
+        return;
+    };
+    let agents = match store.load_reconciled_agents(mux) {
+        Ok(agents) => agents,
+        Err(e) => {
+            warn!(error = %e, "failed to load agents for session rollup");
+            return;
+        }
+    };
+    let statuses: Vec<AgentStatus> = agents
+        .iter()
+        .filter(|a| a.session == session)
+        .filter_map(|a| a.status)
+        .collect();
+    let status = AgentStatus::rollup(statuses.iter().copied());
+
+    let backend = mux.name();
+    let instance = mux.instance_id();
+    let result = match status {
+        Some(_) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            store.upsert_session(&SessionState {
+                backend: backend.to_string(),
+                instance,
+                session: session.clone(),
+                status,
+                agent_count: statuses.len(),
+                updated_ts: now,
+            })
+        }
+        None => store.delete_session(backend, &instance, &session),
+    };
+    if let Err(e) = result {
+        warn!(error = %e, "failed to persist session status");
+    }
+
+    let icon = status.map(|s| match s {
+        AgentStatus::Working => config.status_icons.working(),
+        AgentStatus::Waiting => config.status_icons.waiting(),
+        AgentStatus::Done => config.status_icons.done(),
+        AgentStatus::Unknown => config.status_icons.unknown(),
+    });
+    if icon.is_some() && config.status_format.unwrap_or(true) {
+        let _ = mux.ensure_session_status_format(&session);
+    }
+    let _ = mux.set_session_status(&session, icon);
 }
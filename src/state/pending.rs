@@ -0,0 +1,286 @@
+//! Prompts queued until their agent is ready for input.
+//!
+//! Prompts typed into an agent (`agent_init`, `handshake.queue_prompts`) can
+//! fail to arrive: the agent may not start in time, may sit on a login or
+//! MFA screen, or may mangle the input. Such prompts are saved in the state
+//! store and retried in the background with growing delays, and
+//! `workmux send --flush` delivers them on demand.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use tracing::{debug, info, warn};
+
+use super::{PaneKey, PendingPrompt, PromptDelivery, StateStore};
+use crate::config::Config;
+use crate::multiplexer::{HandshakeSettings, Multiplexer, agent, handshake};
+
+/// Delay before the first automatic retry, doubled after each failure.
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Longest delay between automatic retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Number of trailing pane lines searched for a login screen.
+const LOGIN_SCREEN_LINES: u16 = 60;
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Wait before the next attempt after `attempts` failed ones.
+pub fn retry_delay(attempts: u32) -> Duration {
+    FIRST_RETRY_DELAY
+        .saturating_mul(1 << attempts.min(16))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Type a delivery into the agent in `pane_id`, unless the agent shows
+/// that it isn't ready for input.
+///
+/// `agent_init` lines are removed from `delivery` as they are submitted, so
+/// after an error it holds only what is still to be sent.
+pub fn deliver<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    delivery: &mut PromptDelivery,
+    config: &Config,
+) -> Result<()> {
+    let agent = delivery.agent.as_deref();
+    if let Some(text) = mux
+        .capture_pane(pane_id, LOGIN_SCREEN_LINES)
+        .and_then(|content| not_ready(&content, agent, config))
+    {
+        bail!("Agent is not ready for input (shows \"{}\")", text);
+    }
+    let prompt = delivery
+        .prompt_file
+        .as_ref()
+        .map(std::fs::read_to_string)
+        .transpose()
+        .context("Failed to read prompt file")?;
+    handshake::send_agent_init(
+        mux,
+        pane_id,
+        &mut delivery.init_lines,
+        prompt.as_deref().map(str::trim_end),
+        agent,
+        &HandshakeSettings::from_config(&config.handshake),
+        &handshake::SubmitSettings::for_agent(config, agent),
+    )
+}
+
+/// The configured or known not-ready text visible in captured pane content.
+fn not_ready(content: &str, agent: Option<&str>, config: &Config) -> Option<String> {
+    let content = strip_ansi_escapes::strip_str(content);
+    match &config.handshake.not_ready_text {
+        Some(texts) => texts
+            .iter()
+            .find(|text| !text.is_empty() && content.contains(text.as_str()))
+            .cloned(),
+        None => agent::resolve_profile(agent)
+            .login_screens()
+            .iter()
+            .find(|text| content.contains(*text))
+            .map(|text| text.to_string()),
+    }
+}
+
+/// Queue a delivery that failed with `error`, and start retrying it in the
+/// background.
+pub fn enqueue<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    worktree: &Path,
+    delivery: PromptDelivery,
+    error: &anyhow::Error,
+) -> Result<()> {
+    let now = now();
+    let pending = PendingPrompt {
+        pane_key: PaneKey {
+            backend: mux.name().to_string(),
+            instance: mux.instance_id(),
+            pane_id: pane_id.to_string(),
+        },
+        worktree: worktree.to_path_buf(),
+        delivery,
+        attempts: 0,
+        last_error: format!("{:#}", error),
+        queued_ts: now,
+        next_attempt_ts: now + retry_delay(0).as_secs(),
+    };
+    StateStore::new()?.save_pending_prompt(&pending)?;
+    info!(pane_id, error = %pending.last_error, "pending:queued prompt");
+    spawn_retry(worktree)
+}
+
+/// Outcome of a flush.
+#[derive(Debug, Default)]
+pub struct Flushed {
+    pub delivered: usize,
+    /// Prompts still queued after the flush
+    pub waiting: Vec<PendingPrompt>,
+}
+
+/// Try to deliver the prompts queued for agents in `worktree`. Without
+/// `force`, prompts whose next attempt isn't due yet are left alone.
+/// Prompts for panes that are gone are dropped.
+pub fn flush<M: Multiplexer + ?Sized>(
+    mux: &M,
+    worktree: &Path,
+    config: &Config,
+    force: bool,
+) -> Result<Flushed> {
+    let store = StateStore::new()?;
+    let shell = mux.get_default_shell()?;
+    let mut flushed = Flushed::default();
+    for mut queued in store.list_pending_prompts(worktree)? {
+        let key = &queued.pane_key.clone();
+        if key.backend != mux.name() || key.instance != mux.instance_id() {
+            continue;
+        }
+        if !force && queued.next_attempt_ts > now() {
+            flushed.waiting.push(queued);
+            continue;
+        }
+        let Some(info) = mux.get_live_pane_info(&key.pane_id)? else {
+            debug!(pane_id = %key.pane_id, "pending:pane gone, dropping prompt");
+            store.delete_pending_prompt(key)?;
+            continue;
+        };
+        // Another flush may have delivered it while this one was busy
+        if store.get_pending_prompt(key)?.is_none() {
+            continue;
+        }
+
+        let result = if handshake::is_shell(&info.current_command, &shell) {
+            Err(anyhow!("Agent is not running in pane {}", key.pane_id))
+        } else {
+            deliver(mux, &key.pane_id, &mut queued.delivery, config)
+        };
+        match result {
+            Ok(()) => {
+                store.delete_pending_prompt(key)?;
+                mark_working(mux, &key.pane_id, &queued.delivery, config);
+                info!(pane_id = %key.pane_id, "pending:delivered prompt");
+                flushed.delivered += 1;
+            }
+            Err(e) => {
+                let attempts = queued.attempts + 1;
+                let retried = PendingPrompt {
+                    attempts,
+                    last_error: format!("{:#}", e),
+                    next_attempt_ts: now() + retry_delay(attempts).as_secs(),
+                    ..queued
+                };
+                warn!(pane_id = %key.pane_id, attempts, error = %retried.last_error, "pending:delivery failed");
+                store.save_pending_prompt(&retried)?;
+                flushed.waiting.push(retried);
+            }
+        }
+    }
+    Ok(flushed)
+}
+
+/// Flush the queue of `worktree` whenever an attempt is due, until every
+/// prompt is delivered, dropped, or out of automatic retries.
+pub fn retry_until_delivered<M: Multiplexer + ?Sized>(
+    mux: &M,
+    worktree: &Path,
+    config: &Config,
+) -> Result<()> {
+    let retries = config.handshake.prompt_retries();
+    loop {
+        let flushed = flush(mux, worktree, config, false)?;
+        let next = flushed
+            .waiting
+            .iter()
+            .filter(|p| p.attempts < retries)
+            .map(|p| p.next_attempt_ts)
+            .min();
+        let Some(next) = next else {
+            return Ok(());
+        };
+        std::thread::sleep(Duration::from_secs(next.saturating_sub(now()).max(1)));
+    }
+}
+
+/// Show the working status a prompted agent gets when it starts.
+fn mark_working<M: Multiplexer + ?Sized>(
+    mux: &M,
+    pane_id: &str,
+    delivery: &PromptDelivery,
+    config: &Config,
+) {
+    if delivery.prompt_file.is_none()
+        || !agent::resolve_profile(delivery.agent.as_deref()).needs_auto_status()
+    {
+        return;
+    }
+    if config.status_format.unwrap_or(true) {
+        let _ = mux.ensure_status_format(pane_id);
+    }
+    let _ = mux.set_status(pane_id, config.status_icons.working(), false);
+}
+
+/// Start `workmux send --flush --retry` for `worktree` as a detached process.
+fn spawn_retry(worktree: &Path) -> Result<()> {
+    let name = worktree
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("Invalid worktree path '{}'", worktree.display()))?;
+    let exe = std::env::current_exe().unwrap_or_else(|_| "workmux".into());
+    let mut cmd = Command::new(exe);
+    cmd.args(["send", "--flush", "--retry", name])
+        .current_dir(worktree)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Own process group, so closing the terminal doesn't stop the retries
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd.spawn()
+        .context("Failed to start retrying the queued prompt")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(0), Duration::from_secs(5));
+        assert_eq!(retry_delay(1), Duration::from_secs(10));
+        assert_eq!(retry_delay(3), Duration::from_secs(40));
+        assert_eq!(retry_delay(7), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn login_screens_hold_prompts_back() {
+        let mut config = Config::default();
+        let screen = "\x1b[1mSelect login method:\x1b[0m\n 1. Claude account";
+        assert_eq!(
+            not_ready(screen, Some("claude"), &config).as_deref(),
+            Some("Select login method")
+        );
+        assert_eq!(not_ready("> ", Some("claude"), &config), None);
+        assert_eq!(not_ready(screen, Some("my-agent"), &config), None);
+
+        config.handshake.not_ready_text = Some(vec!["Enter MFA code".to_string()]);
+        assert_eq!(not_ready(screen, Some("claude"), &config), None);
+        assert_eq!(
+            not_ready("Enter MFA code: ", None, &config).as_deref(),
+            Some("Enter MFA code")
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Priority preemption: pause lower-priority agents while a higher-priority
+//! agent works, and resume them once nothing outranks them.
+//!
+//! Runs after every status update when `preemption.enabled` is set. Each
+//! agent's priority is recorded in its state when it reports a status, so
+//! agents in other repositories can be compared without visiting their
+//! worktrees.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use super::{AgentState, PaneKey, StateStore};
+use crate::config::{Config, Priority};
+use crate::multiplexer::{AgentStatus, Multiplexer, agent};
+
+/// Tag prefix that sets a worktree's priority (`priority:high`).
+pub const PRIORITY_TAG_PREFIX: &str = "priority:";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    /// Interrupt the agent in `pane_id`, which the agent in `by` outranks
+    Pause {
+        pane_id: String,
+        by: String,
+    },
+    Resume {
+        pane_id: String,
+    },
+}
+
+fn priority_of(state: &AgentState) -> Priority {
+    state.priority.unwrap_or_default()
+}
+
+/// Whether the agent is working and not paused.
+fn is_running(state: &AgentState) -> bool {
+    state.status == Some(AgentStatus::Working) && state.paused_by.is_none()
+}
+
+/// Decide which agents to pause and resume. `started` is the pane of an
+/// agent that just started working, if any.
+fn plan(agents: &[AgentState], started: Option<&str>) -> Vec<Action> {
+    let mut actions = Vec::new();
+
+    // Resume paused agents that no running agent outranks anymore
+    for paused in agents.iter().filter(|a| a.paused_by.is_some()) {
+        let outranked = agents.iter().any(|other| {
+            other.pane_key.pane_id != paused.pane_key.pane_id
+                && is_running(other)
+                && priority_of(other) > priority_of(paused)
+        });
+        if !outranked {
+            actions.push(Action::Resume {
+                pane_id: paused.pane_key.pane_id.clone(),
+            });
+        }
+    }
+
+    // An agent that starts working pauses the running agents it outranks
+    let Some(started) = started
+        .and_then(|pane_id| agents.iter().find(|a| a.pane_key.pane_id == pane_id))
+        .filter(|a| is_running(a))
+    else {
+        return actions;
+    };
+    for other in agents
+        .iter()
+        .filter(|a| is_running(a) && priority_of(a) < priority_of(started))
+    {
+        actions.push(Action::Pause {
+            pane_id: other.pane_key.pane_id.clone(),
+            by: started.pane_key.pane_id.clone(),
+        });
+    }
+    actions
+}
+
+/// Priority set by a `priority:<level>` tag in a stored tag list.
+fn priority_from_tags(tags: &str) -> Option<Priority> {
+    crate::plugin::split_tags(tags).into_iter().find_map(|tag| {
+        tag.strip_prefix(PRIORITY_TAG_PREFIX)
+            .and_then(Priority::parse)
+    })
+}
+
+/// Priority of the worktree at `workdir`: its `priority:<level>` tag, then
+/// the `priority` config option.
+fn worktree_priority(config: &Config, workdir: &Path) -> Priority {
+    let tagged = crate::vcs::detect_vcs().ok().and_then(|vcs| {
+        let root = vcs.get_repo_root_for(workdir).ok()?;
+        let handle = root.file_name()?.to_string_lossy().into_owned();
+        let tags = vcs.get_workspace_meta(&handle, crate::plugin::TAGS_META_KEY)?;
+        priority_from_tags(&tags)
+    });
+    tagged.or(config.priority).unwrap_or_default()
+}
+
+/// Pause and resume agents after the agent in `pane_id` reported a status.
+/// `status_changed` tells whether the report changed the agent's status.
+///
+/// Best-effort: logs warnings on failure without propagating errors.
+pub fn apply(mux: &dyn Multiplexer, pane_id: &str, status_changed: bool, config: &Config) {
+    if !config.preemption.enabled() {
+        return;
+    }
+    if let Err(e) = try_apply(mux, pane_id, status_changed, config) {
+        warn!(error = %e, "failed to apply agent preemption");
+    }
+}
+
+fn try_apply(
+    mux: &dyn Multiplexer,
+    pane_id: &str,
+    status_changed: bool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let store = StateStore::new()?;
+    let pane_key = PaneKey {
+        backend: mux.name().to_string(),
+        instance: mux.instance_id(),
+        pane_id: pane_id.to_string(),
+    };
+    let Some(state) = store.get_agent(&pane_key)? else {
+        return Ok(());
+    };
+
+    let priority = worktree_priority(config, &state.workdir);
+    let working = state.status == Some(AgentStatus::Working);
+    store.update_agent(&pane_key, |state| {
+        state.priority = Some(priority);
+        // Working again after a pause: resumed, by us or by hand
+        if working {
+            state.paused_by = None;
+        }
+    })?;
+
+    // Reconciling drops agents whose panes are gone
+    let live: HashSet<String> = store
+        .load_reconciled_agents(mux)?
+        .into_iter()
+        .map(|a| a.pane_id)
+        .collect();
+    let agents: Vec<AgentState> = store
+        .list_all_agents()?
+        .into_iter()
+        .filter(|a| {
+            a.pane_key.backend == pane_key.backend
+                && a.pane_key.instance == pane_key.instance
+                && live.contains(&a.pane_key.pane_id)
+        })
+        .collect();
+
+    let started = (working && status_changed).then_some(pane_id);
+    for action in plan(&agents, started) {
+        let pane_id = match &action {
+            Action::Pause { pane_id, .. } | Action::Resume { pane_id } => pane_id,
+        };
+        let Some(target) = agents.iter().find(|a| &a.pane_key.pane_id == pane_id) else {
+            continue;
+        };
+        // Match the agent by its process, falling back to the configured agent
+        let mut profile = agent::resolve_profile(Some(&target.command));
+        if profile.name() == "default" {
+            profile = agent::resolve_profile(config.agent.as_deref());
+        }
+        let keys = config.preemption.keys_for(profile.name());
+
+        match action {
+            Action::Pause { pane_id, by } => {
+                let pause: Vec<String> = keys.and_then(|k| k.pause.clone()).unwrap_or_else(|| {
+                    profile.pause_keys().iter().map(|k| k.to_string()).collect()
+                });
+                if pause.is_empty() {
+                    continue;
+                }
+                for key in &pause {
+                    mux.send_key(&pane_id, key)?;
+                }
+                info!(%pane_id, %by, "preempt:paused");
+                store.update_agent(&target.pane_key, |state| state.paused_by = Some(by))?;
+            }
+            Action::Resume { pane_id } => {
+                let resume = keys
+                    .and_then(|k| k.resume.clone())
+                    .or_else(|| profile.resume_input().map(str::to_string));
+                if let Some(resume) = resume {
+                    mux.send_keys_to_agent(&pane_id, &resume, Some(profile.name()))?;
+                }
+                info!(%pane_id, "preempt:resumed");
+                store.update_agent(&target.pane_key, |state| state.paused_by = None)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn agent(pane_id: &str, status: AgentStatus, priority: Priority) -> AgentState {
+        AgentState {
+            pane_key: PaneKey {
+                backend: "tmux".to_string(),
+                instance: "default".to_string(),
+                pane_id: pane_id.to_string(),
+            },
+            workdir: PathBuf::from("/tmp"),
+            status: Some(status),
+            status_ts: None,
+            pane_title: None,
+            pane_pid: 1,
+            command: "claude".to_string(),
+            updated_ts: 0,
+            server_url: None,
+            progress: None,
+            task_progress: None,
+            priority: Some(priority),
+            paused_by: None,
+            denied_exec: None,
+            net_activity: None,
+            heartbeat_ts: None,
+            agent_pid: None,
+            history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn starting_agent_pauses_lower_priority_running_agents() {
+        let agents = vec![
+            agent("%1", AgentStatus::Working, Priority::High),
+            agent("%2", AgentStatus::Working, Priority::Low),
+            agent("%3", AgentStatus::Waiting, Priority::Low),
+            agent("%4", AgentStatus::Working, Priority::High),
+        ];
+        assert_eq!(
+            plan(&agents, Some("%1")),
+            vec![Action::Pause {
+                pane_id: "%2".to_string(),
+                by: "%1".to_string()
+            }]
+        );
+        // Still working, not starting: nothing happens
+        assert_eq!(plan(&agents, None), vec![]);
+    }
+
+    #[test]
+    fn paused_agents_resume_once_nothing_outranks_them() {
+        let mut paused = agent("%2", AgentStatus::Working, Priority::Normal);
+        paused.paused_by = Some("%1".to_string());
+        let mut agents = vec![
+            agent("%1", AgentStatus::Done, Priority::High),
+            paused,
+            agent("%3", AgentStatus::Working, Priority::High),
+        ];
+        // Another high-priority agent is still working
+        assert_eq!(plan(&agents, None), vec![]);
+
+        agents[2].status = Some(AgentStatus::Waiting);
+        assert_eq!(
+            plan(&agents, None),
+            vec![Action::Resume {
+                pane_id: "%2".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn priority_tag_is_parsed() {
+        assert_eq!(
+            priority_from_tags("review, priority:high"),
+            Some(Priority::High)
+        );
+        assert_eq!(priority_from_tags("priority:urgent"), None);
+        assert_eq!(priority_from_tags("review"), None);
+    }
+}
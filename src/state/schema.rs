@@ -0,0 +1,213 @@
+//! Schema versions of the JSON files workmux keeps in its state directory.
+//!
+//! Every file records the version of its format in a `schema_version` field.
+//! Files written before versioning have no field and count as version 0.
+//! Loading a file upgrades older versions in memory through its kind's
+//! migrations; a file written by a newer workmux is an error, since this
+//! version can't know what changed in it.
+//!
+//! [`StateStore::new`](super::StateStore::new) also rewrites its files once
+//! after an upgrade, keeping backups of the originals.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Field holding a file's schema version.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Upgrades a file's JSON from one version to the next.
+type Migration = fn(&mut Value);
+
+/// Version 1 only adds the version field; fields added before it all have
+/// serde defaults.
+const ADD_VERSION: &[Migration] = &[|_| {}];
+
+/// The kinds of versioned state files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    /// `agents/*.json`
+    Agent,
+    /// `sessions/*.json`
+    Session,
+    /// `settings.json`
+    Settings,
+    /// `setup.json`: agents whose status tracking setup was declined
+    Setup,
+    /// `image-freshness.json`: cached result of the sandbox image check
+    ImageFreshness,
+    /// `agent-capabilities.json`: flags detected in installed agent CLIs
+    AgentCapabilities,
+    /// `merges/*.json`: checkpoints of merges in progress. Short-lived, so
+    /// upgraded as they are read rather than by the store migration.
+    Merge,
+    /// `drafts/*.json`: unsent dashboard comments, upgraded as they are read
+    CommentDrafts,
+    /// `pending/*.json`: prompts waiting for their agent, upgraded as they
+    /// are read
+    PendingPrompt,
+    /// `requests/*.json`: host commands waiting for the user's approval,
+    /// upgraded as they are read
+    ExecRequest,
+}
+
+impl StateKind {
+    /// The kinds of files in the [`StateStore`](super::StateStore).
+    pub const STORE: [StateKind; 3] = [StateKind::Agent, StateKind::Session, StateKind::Settings];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StateKind::Agent => "agent state",
+            StateKind::Session => "session state",
+            StateKind::Settings => "dashboard settings",
+            StateKind::Setup => "setup state",
+            StateKind::ImageFreshness => "image freshness cache",
+            StateKind::AgentCapabilities => "agent capabilities cache",
+            StateKind::Merge => "merge checkpoint",
+            StateKind::CommentDrafts => "comment drafts",
+            StateKind::PendingPrompt => "pending prompt",
+            StateKind::ExecRequest => "host command request",
+        }
+    }
+
+    /// Steps that upgrade this kind's format: `migrations()[n]` turns
+    /// version `n` into version `n + 1`.
+    fn migrations(self) -> &'static [Migration] {
+        match self {
+            StateKind::Agent => ADD_VERSION,
+            StateKind::Session => ADD_VERSION,
+            StateKind::Settings => ADD_VERSION,
+            StateKind::Setup => ADD_VERSION,
+            StateKind::ImageFreshness => ADD_VERSION,
+            StateKind::AgentCapabilities => ADD_VERSION,
+            StateKind::Merge => ADD_VERSION,
+            StateKind::CommentDrafts => ADD_VERSION,
+            StateKind::PendingPrompt => ADD_VERSION,
+            StateKind::ExecRequest => ADD_VERSION,
+        }
+    }
+
+    /// Version of the format this workmux writes.
+    pub fn current_version(self) -> u64 {
+        self.migrations().len() as u64
+    }
+}
+
+/// Version of the store as a whole, recorded once its files are migrated.
+/// Grows whenever the format of one of its file kinds does.
+pub fn store_version() -> u64 {
+    StateKind::STORE.iter().map(|k| k.current_version()).sum()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaError {
+    #[error(
+        "{kind} was written by a newer version of workmux (schema version {found}, \
+        this version supports {supported}). Update workmux to use it."
+    )]
+    Newer {
+        kind: &'static str,
+        found: u64,
+        supported: u64,
+    },
+    #[error("invalid {kind}: {source}")]
+    Invalid {
+        kind: &'static str,
+        source: serde_json::Error,
+    },
+}
+
+/// Schema version a file's JSON was written with.
+pub fn version_of(value: &Value) -> u64 {
+    value
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Upgrade a file's JSON to the current version. Returns whether anything
+/// had to be migrated.
+pub fn upgrade(kind: StateKind, value: &mut Value) -> Result<bool, SchemaError> {
+    let found = version_of(value);
+    let supported = kind.current_version();
+    if found > supported {
+        return Err(SchemaError::Newer {
+            kind: kind.name(),
+            found,
+            supported,
+        });
+    }
+    if found == supported {
+        return Ok(false);
+    }
+    for migration in &kind.migrations()[found as usize..] {
+        migration(value);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert(SCHEMA_VERSION_KEY.to_string(), supported.into());
+    }
+    Ok(true)
+}
+
+/// Parse a state file, upgrading it from older versions.
+pub fn parse<T: DeserializeOwned>(kind: StateKind, contents: &str) -> Result<T, SchemaError> {
+    let invalid = |source| SchemaError::Invalid {
+        kind: kind.name(),
+        source,
+    };
+    let mut value: Value = serde_json::from_str(contents).map_err(invalid)?;
+    upgrade(kind, &mut value)?;
+    serde_json::from_value(value).map_err(invalid)
+}
+
+/// Serialize a state file with its current schema version.
+pub fn to_json<T: Serialize>(kind: StateKind, state: &T) -> serde_json::Result<String> {
+    let mut value = serde_json::to_value(state)?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            SCHEMA_VERSION_KEY.to_string(),
+            kind.current_version().into(),
+        );
+    }
+    serde_json::to_string_pretty(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Example {
+        name: String,
+    }
+
+    #[test]
+    fn unversioned_files_are_upgraded() {
+        let mut value = serde_json::json!({"name": "a"});
+        assert_eq!(version_of(&value), 0);
+        assert!(upgrade(StateKind::Agent, &mut value).unwrap());
+        assert_eq!(version_of(&value), StateKind::Agent.current_version());
+        assert!(!upgrade(StateKind::Agent, &mut value).unwrap());
+
+        let parsed: Example = parse(StateKind::Session, r#"{"name": "a"}"#).unwrap();
+        assert_eq!(parsed.name, "a");
+    }
+
+    #[test]
+    fn files_from_newer_versions_are_rejected() {
+        let contents = r#"{"name": "a", "schema_version": 99}"#;
+        let err = parse::<Example>(StateKind::Settings, contents).unwrap_err();
+        assert!(matches!(err, SchemaError::Newer { found: 99, .. }));
+        assert!(err.to_string().contains("newer version of workmux"));
+    }
+
+    #[test]
+    fn written_files_carry_the_version() {
+        let json = to_json(StateKind::Setup, &Example { name: "a".into() }).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(version_of(&value), StateKind::Setup.current_version());
+        let parsed: Example = parse(StateKind::Setup, &json).unwrap();
+        assert_eq!(parsed.name, "a");
+    }
+}
@@ -1,12 +1,22 @@
 //! Filesystem-based state persistence for agent state.
 
 use anyhow::{Context, Result};
+use nix::fcntl::{Flock, FlockArg};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use tracing::warn;
-
-use super::types::{AgentState, GlobalSettings, PaneKey};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+use super::liveness;
+use super::schema::{self, SchemaError, StateKind};
+use super::types::{
+    AgentState, CommentDrafts, ExecRequest, GlobalSettings, MergeCheckpoint, PaneKey,
+    PendingPrompt, SessionState, drafts_filename, merge_filename, session_filename,
+};
+use super::watch::StateWatcher;
+use crate::config::Config;
+use crate::multiplexer::AgentStatus;
 
 /// Manages filesystem-based state persistence for workmux agents.
 ///
@@ -14,19 +24,51 @@ use super::types::{AgentState, GlobalSettings, PaneKey};
 /// ```text
 /// $XDG_STATE_HOME/workmux/           # ~/.local/state/workmux/
 /// ├── settings.json                   # Global dashboard settings
-/// └── agents/
-///     ├── tmux__default__%1.json     # {backend}__{instance}__{pane_id}.json
-///     └── wezterm__main__3.json
+/// ├── state.lock                      # Serializes read-modify-write updates
+/// ├── schema_version                  # Store version its files were migrated to
+/// ├── backups/
+/// │   └── schema-v0/                  # Files as they were before a migration
+/// ├── agents/
+/// │   ├── tmux__default__%1.json     # {backend}__{instance}__{pane_id}.json
+/// │   └── wezterm__main__3.json
+/// ├── sessions/
+/// │   └── tmux__default__wm-foo.json # {backend}__{instance}__{session}.json
+/// ├── merges/
+/// │   └── %2Fsrc%2Fapp__feature.json # {repo}__{branch}.json, merges in progress
+/// ├── drafts/
+/// │   └── %2Fsrc%2Fapp__wt.json      # {worktree}.json, unsent dashboard comments
+/// ├── pending/
+/// │   └── tmux__default__%3.json     # {backend}__{instance}__{pane_id}.json, queued prompts
+/// └── requests/
+///     └── tmux__default__%4.json     # {backend}__{instance}__{pane_id}.json, host commands to approve
 /// ```
 pub struct StateStore {
     base_path: PathBuf,
 }
 
+/// Exclusive lock on the state store, released on drop.
+///
+/// Locks are per open file, so taking a second one in the same process
+/// blocks just like one held by another process.
+pub struct StateLock {
+    _lock: Flock<fs::File>,
+}
+
 impl StateStore {
     /// Create a new StateStore using XDG_STATE_HOME.
     ///
-    /// Creates the base directory and agents subdirectory if they don't exist.
+    /// Creates the base directory and agents subdirectory if they don't exist,
+    /// and migrates the files after an upgrade. Fails if the state was written
+    /// by a newer version of workmux.
     pub fn new() -> Result<Self> {
+        let store = Self::without_migration()?;
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// Open the store without migrating its files, for checks that must not
+    /// change anything.
+    pub fn without_migration() -> Result<Self> {
         let base = get_state_dir()?.join("workmux");
         fs::create_dir_all(&base).context("Failed to create state directory")?;
         fs::create_dir_all(base.join("agents")).context("Failed to create agents directory")?;
@@ -46,6 +88,31 @@ impl StateStore {
         self.base_path.join("agents")
     }
 
+    /// Path to sessions directory (session-mode status rollups).
+    fn sessions_dir(&self) -> PathBuf {
+        self.base_path.join("sessions")
+    }
+
+    /// Path to merges directory (checkpoints of merges in progress).
+    fn merges_dir(&self) -> PathBuf {
+        self.base_path.join("merges")
+    }
+
+    /// Path to drafts directory (unsent dashboard comments).
+    fn drafts_dir(&self) -> PathBuf {
+        self.base_path.join("drafts")
+    }
+
+    /// Path to pending directory (prompts waiting for their agent).
+    fn pending_dir(&self) -> PathBuf {
+        self.base_path.join("pending")
+    }
+
+    /// Path to requests directory (host commands waiting for approval).
+    fn requests_dir(&self) -> PathBuf {
+        self.base_path.join("requests")
+    }
+
     /// Path to containers directory.
     fn containers_dir(&self) -> PathBuf {
         self.base_path.join("containers")
@@ -56,24 +123,56 @@ impl StateStore {
         self.base_path.join("settings.json")
     }
 
+    /// Path to the file recording the store's schema version.
+    fn schema_version_path(&self) -> PathBuf {
+        self.base_path.join("schema_version")
+    }
+
     /// Path to a specific agent's state file.
     fn agent_path(&self, key: &PaneKey) -> PathBuf {
         self.agents_dir().join(key.to_filename())
     }
 
+    /// Take the store's exclusive lock, waiting for other processes to
+    /// release it.
+    ///
+    /// Single writes are atomic on their own; hold the lock across a read and
+    /// the write based on it so concurrent updates aren't lost.
+    pub fn lock(&self) -> Result<StateLock> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.base_path.join("state.lock"))
+            .context("Failed to open state lock file")?;
+        let lock = Flock::lock(file, FlockArg::LockExclusive)
+            .map_err(|(_, errno)| errno)
+            .context("Failed to lock state store")?;
+        Ok(StateLock { _lock: lock })
+    }
+
+    /// Watch agent and session state, and host command requests, for
+    /// changes made by any process.
+    pub fn watch(&self) -> Result<StateWatcher> {
+        let sessions = self.sessions_dir();
+        fs::create_dir_all(&sessions).context("Failed to create sessions directory")?;
+        let requests = self.requests_dir();
+        fs::create_dir_all(&requests).context("Failed to create requests directory")?;
+        StateWatcher::new(&[&self.agents_dir(), &sessions, &requests])
+    }
+
     /// Create or update agent state.
     ///
     /// Uses atomic write (temp file + rename) for crash safety.
     pub fn upsert_agent(&self, state: &AgentState) -> Result<()> {
         let path = self.agent_path(&state.pane_key);
-        let content = serde_json::to_string_pretty(state)?;
+        let content = schema::to_json(StateKind::Agent, state)?;
         write_atomic(&path, content.as_bytes())
     }
 
     /// Read agent state by pane key.
     ///
     /// Returns None if the agent doesn't exist or the file is corrupted.
-    #[allow(dead_code)] // Used in tests, may be used in future features
     pub fn get_agent(&self, key: &PaneKey) -> Result<Option<AgentState>> {
         read_agent_file(&self.agent_path(key))
     }
@@ -104,6 +203,25 @@ impl StateStore {
         Ok(agents)
     }
 
+    /// Apply an in-place update to an existing agent's state.
+    ///
+    /// Holds the store lock for the whole update. Returns false (without
+    /// writing) if the agent has no state file. Updates that change nothing
+    /// are not written, so watchers aren't woken for them.
+    pub fn update_agent(&self, key: &PaneKey, f: impl FnOnce(&mut AgentState)) -> Result<bool> {
+        let _lock = self.lock()?;
+        let Some(mut state) = self.get_agent(key)? else {
+            return Ok(false);
+        };
+        let before = schema::to_json(StateKind::Agent, &state)?;
+        f(&mut state);
+        let after = schema::to_json(StateKind::Agent, &state)?;
+        if after != before {
+            write_atomic(&self.agent_path(key), after.as_bytes())?;
+        }
+        Ok(true)
+    }
+
     /// Delete agent state.
     ///
     /// No-op if the file doesn't exist.
@@ -116,14 +234,84 @@ impl StateStore {
         }
     }
 
+    /// Rewrite agent working directories after a worktree has moved.
+    ///
+    /// Every agent whose workdir is `from` or inside it is re-pointed at the
+    /// same relative location under `to`. Returns the number of agents updated.
+    pub fn relocate_workdirs(&self, from: &Path, to: &Path) -> Result<usize> {
+        let _lock = self.lock()?;
+        let mut updated = 0;
+        for mut state in self.list_all_agents()? {
+            let Ok(rel) = state.workdir.strip_prefix(from) else {
+                continue;
+            };
+            state.workdir = to.join(rel);
+            self.upsert_agent(&state)?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// Create or update a session's rolled-up status.
+    pub fn upsert_session(&self, state: &SessionState) -> Result<()> {
+        let dir = self.sessions_dir();
+        fs::create_dir_all(&dir).context("Failed to create sessions directory")?;
+        let content = schema::to_json(StateKind::Session, state)?;
+        write_atomic(&dir.join(state.to_filename()), content.as_bytes())
+    }
+
+    /// Read a session's rolled-up status.
+    ///
+    /// Returns None if no rollup exists or the file is corrupted, and an
+    /// error if it was written by a newer version of workmux.
+    pub fn get_session(
+        &self,
+        backend: &str,
+        instance: &str,
+        session: &str,
+    ) -> Result<Option<SessionState>> {
+        let path = self
+            .sessions_dir()
+            .join(session_filename(backend, instance, session));
+        match fs::read_to_string(&path) {
+            Ok(content) => match schema::parse(StateKind::Session, &content) {
+                Ok(state) => Ok(Some(state)),
+                Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
+                Err(e) => {
+                    warn!(?path, error = %e, "corrupted session state file, deleting");
+                    let _ = fs::remove_file(&path);
+                    Ok(None)
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context("Failed to read session state"),
+        }
+    }
+
+    /// Delete a session's rolled-up status.
+    ///
+    /// No-op if the file doesn't exist.
+    pub fn delete_session(&self, backend: &str, instance: &str, session: &str) -> Result<()> {
+        let path = self
+            .sessions_dir()
+            .join(session_filename(backend, instance, session));
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Failed to delete session state"),
+        }
+    }
+
     /// Load global settings.
     ///
-    /// Returns defaults if the file is missing or corrupted.
+    /// Returns defaults if the file is missing or corrupted, and an error if
+    /// it was written by a newer version of workmux.
     pub fn load_settings(&self) -> Result<GlobalSettings> {
         let path = self.settings_path();
         match fs::read_to_string(&path) {
-            Ok(content) => match serde_json::from_str(&content) {
+            Ok(content) => match schema::parse(StateKind::Settings, &content) {
                 Ok(settings) => Ok(settings),
+                Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
                 Err(e) => {
                     warn!(?path, error = %e, "corrupted settings file, using defaults");
                     Ok(GlobalSettings::default())
@@ -139,10 +327,382 @@ impl StateStore {
     /// Uses atomic write for crash safety.
     pub fn save_settings(&self, settings: &GlobalSettings) -> Result<()> {
         let path = self.settings_path();
-        let content = serde_json::to_string_pretty(settings)?;
+        let content = schema::to_json(StateKind::Settings, settings)?;
+        write_atomic(&path, content.as_bytes())
+    }
+
+    /// Every versioned file in the store, with its kind.
+    fn state_files(&self) -> Result<Vec<(StateKind, PathBuf)>> {
+        fn json_files(dir: &Path) -> Result<Vec<PathBuf>> {
+            if !dir.exists() {
+                return Ok(Vec::new());
+            }
+            let mut files = Vec::new();
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|e| e == "json") {
+                    files.push(path);
+                }
+            }
+            Ok(files)
+        }
+
+        let mut files = Vec::new();
+        for path in json_files(&self.agents_dir())? {
+            files.push((StateKind::Agent, path));
+        }
+        for path in json_files(&self.sessions_dir())? {
+            files.push((StateKind::Session, path));
+        }
+        if self.settings_path().exists() {
+            files.push((StateKind::Settings, self.settings_path()));
+        }
+        Ok(files)
+    }
+
+    /// Store version the files were last migrated to, if recorded.
+    fn recorded_version(&self) -> Option<u64> {
+        let content = fs::read_to_string(self.schema_version_path()).ok()?;
+        content.trim().parse().ok()
+    }
+
+    /// Upgrade all files to the current schema versions.
+    ///
+    /// Runs once per upgrade; afterwards the store version is recorded and
+    /// older files written by a still-running old version are upgraded as
+    /// they are read. Originals are copied to `backups/schema-v<old>/`.
+    fn migrate(&self) -> Result<()> {
+        let current = schema::store_version();
+        let recorded = self.recorded_version();
+        match recorded {
+            Some(version) if version == current => return Ok(()),
+            Some(version) if version > current => anyhow::bail!(
+                "The state in {} was written by a newer version of workmux (schema version {}, \
+                this version supports {}). Update workmux, or move the directory aside to start \
+                with fresh state.",
+                self.base_path.display(),
+                version,
+                current
+            ),
+            _ => {}
+        }
+
+        let _lock = self.lock()?;
+        let backup_dir = self
+            .base_path
+            .join("backups")
+            .join(format!("schema-v{}", recorded.unwrap_or(0)));
+        for (kind, path) in self.state_files()? {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            // Corrupted files are left to the readers, which delete them
+            let Ok(mut value) = serde_json::from_str(&content) else {
+                continue;
+            };
+            if !schema::upgrade(kind, &mut value)? {
+                continue;
+            }
+            let relative = path.strip_prefix(&self.base_path).unwrap_or(&path);
+            let backup = backup_dir.join(relative);
+            if let Some(parent) = backup.parent() {
+                fs::create_dir_all(parent).context("Failed to create state backup directory")?;
+            }
+            fs::write(&backup, &content).context("Failed to back up state file")?;
+            write_atomic(&path, serde_json::to_string_pretty(&value)?.as_bytes())?;
+        }
+        info!(from = ?recorded, to = current, "state:migrated");
+        write_atomic(&self.schema_version_path(), current.to_string().as_bytes())
+    }
+
+    /// Check that every state file can be read by this version.
+    ///
+    /// Unlike the readers above, this leaves unreadable files in place.
+    /// Returns each file that can't be read with the reason.
+    pub fn incompatible_files(&self) -> Result<Vec<(PathBuf, String)>> {
+        let mut failures = Vec::new();
+        if let Some(version) = self.recorded_version()
+            && version > schema::store_version()
+        {
+            failures.push((
+                self.schema_version_path(),
+                format!(
+                    "store schema version {} is newer than {}",
+                    version,
+                    schema::store_version()
+                ),
+            ));
+        }
+        for (kind, path) in self.state_files()? {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    match kind {
+                        StateKind::Agent => schema::parse::<AgentState>(kind, &content).map(drop),
+                        StateKind::Session => {
+                            schema::parse::<SessionState>(kind, &content).map(drop)
+                        }
+                        StateKind::Settings => {
+                            schema::parse::<GlobalSettings>(kind, &content).map(drop)
+                        }
+                        // Not kept in the store
+                        StateKind::Setup
+                        | StateKind::ImageFreshness
+                        | StateKind::AgentCapabilities
+                        | StateKind::Merge
+                        | StateKind::CommentDrafts
+                        | StateKind::PendingPrompt
+                        | StateKind::ExecRequest => Ok(()),
+                    }
+                    .map_err(|e| e.to_string())
+                });
+            if let Err(e) = parsed {
+                failures.push((path, e));
+            }
+        }
+        Ok(failures)
+    }
+
+    // ── Merge checkpoints ───────────────────────────────────────────────────
+
+    /// Create or update the checkpoint of a merge.
+    pub fn save_merge(&self, checkpoint: &MergeCheckpoint) -> Result<()> {
+        let dir = self.merges_dir();
+        fs::create_dir_all(&dir).context("Failed to create merges directory")?;
+        let content = schema::to_json(StateKind::Merge, checkpoint)?;
+        write_atomic(&dir.join(checkpoint.to_filename()), content.as_bytes())
+    }
+
+    /// Checkpoints of the merges in progress in the repository at `repo`.
+    ///
+    /// Corrupted files are deleted; a file written by a newer version of
+    /// workmux is an error.
+    pub fn list_merges(&self, repo: &Path) -> Result<Vec<MergeCheckpoint>> {
+        let dir = self.merges_dir();
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read merges directory"),
+        };
+        let mut merges = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            match schema::parse::<MergeCheckpoint>(StateKind::Merge, &content) {
+                Ok(checkpoint) if checkpoint.repo == repo => merges.push(checkpoint),
+                Ok(_) => {}
+                Err(e @ SchemaError::Newer { .. }) => return Err(e.into()),
+                Err(e) => {
+                    warn!(?path, error = %e, "corrupted merge checkpoint, deleting");
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+        Ok(merges)
+    }
+
+    /// Delete the checkpoint of a merge.
+    ///
+    /// No-op if the file doesn't exist.
+    pub fn delete_merge(&self, repo: &Path, branch: &str) -> Result<()> {
+        let path = self.merges_dir().join(merge_filename(repo, branch));
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Failed to delete merge checkpoint"),
+        }
+    }
+
+    // ── Comment drafts ──────────────────────────────────────────────────────
+
+    /// Unsent dashboard comments on the hunks of `worktree`.
+    ///
+    /// Empty if there are none; a corrupted file is deleted.
+    pub fn load_comment_drafts(&self, worktree: &Path) -> Result<CommentDrafts> {
+        let empty = CommentDrafts {
+            worktree: worktree.to_path_buf(),
+            ..Default::default()
+        };
+        let path = self.drafts_dir().join(drafts_filename(worktree));
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(empty),
+            Err(e) => return Err(e).context("Failed to read comment drafts"),
+        };
+        match schema::parse::<CommentDrafts>(StateKind::CommentDrafts, &content) {
+            Ok(drafts) => Ok(drafts),
+            Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
+            Err(e) => {
+                warn!(?path, error = %e, "corrupted comment drafts, deleting");
+                let _ = fs::remove_file(&path);
+                Ok(empty)
+            }
+        }
+    }
+
+    /// Save a worktree's comment drafts, deleting the file once none are left.
+    pub fn save_comment_drafts(&self, drafts: &CommentDrafts) -> Result<()> {
+        let path = self.drafts_dir().join(drafts.to_filename());
+        if drafts.drafts.is_empty() {
+            return match fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e).context("Failed to delete comment drafts"),
+            };
+        }
+        fs::create_dir_all(self.drafts_dir()).context("Failed to create drafts directory")?;
+        let content = schema::to_json(StateKind::CommentDrafts, drafts)?;
         write_atomic(&path, content.as_bytes())
     }
 
+    // ── Pending prompts ─────────────────────────────────────────────────────
+
+    /// Queue a prompt, or update a queued one.
+    pub fn save_pending_prompt(&self, pending: &PendingPrompt) -> Result<()> {
+        let dir = self.pending_dir();
+        fs::create_dir_all(&dir).context("Failed to create pending directory")?;
+        let content = schema::to_json(StateKind::PendingPrompt, pending)?;
+        write_atomic(&dir.join(pending.to_filename()), content.as_bytes())
+    }
+
+    /// The prompt queued for a pane, if any.
+    pub fn get_pending_prompt(&self, key: &PaneKey) -> Result<Option<PendingPrompt>> {
+        let path = self.pending_dir().join(key.to_filename());
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("Failed to read pending prompt"),
+        };
+        match schema::parse::<PendingPrompt>(StateKind::PendingPrompt, &content) {
+            Ok(pending) => Ok(Some(pending)),
+            Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
+            Err(e) => {
+                warn!(?path, error = %e, "corrupted pending prompt, deleting");
+                let _ = fs::remove_file(&path);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Prompts queued for agents in the worktree at `worktree`.
+    ///
+    /// Corrupted files are deleted; a file written by a newer version of
+    /// workmux is an error.
+    pub fn list_pending_prompts(&self, worktree: &Path) -> Result<Vec<PendingPrompt>> {
+        let entries = match fs::read_dir(self.pending_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read pending directory"),
+        };
+        let mut pending = Vec::new();
+        for entry in entries.flatten() {
+            let filename = entry.file_name();
+            let Some(key) = filename.to_str().and_then(PaneKey::from_filename) else {
+                continue;
+            };
+            if let Some(prompt) = self.get_pending_prompt(&key)?
+                && prompt.worktree == worktree
+            {
+                pending.push(prompt);
+            }
+        }
+        pending.sort_by(|a, b| a.pane_key.pane_id.cmp(&b.pane_key.pane_id));
+        Ok(pending)
+    }
+
+    /// Drop the prompt queued for a pane.
+    ///
+    /// No-op if the file doesn't exist.
+    pub fn delete_pending_prompt(&self, key: &PaneKey) -> Result<()> {
+        match fs::remove_file(self.pending_dir().join(key.to_filename())) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Failed to delete pending prompt"),
+        }
+    }
+
+    // ── Host command requests ───────────────────────────────────────────────
+
+    /// Save a host command request, replacing an earlier one of the pane.
+    pub fn save_exec_request(&self, request: &ExecRequest) -> Result<()> {
+        let dir = self.requests_dir();
+        fs::create_dir_all(&dir).context("Failed to create requests directory")?;
+        let content = schema::to_json(StateKind::ExecRequest, request)?;
+        write_atomic(&dir.join(request.to_filename()), content.as_bytes())
+    }
+
+    /// The host command request of a pane, if any.
+    pub fn get_exec_request(&self, key: &PaneKey) -> Result<Option<ExecRequest>> {
+        let path = self.requests_dir().join(key.to_filename());
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("Failed to read host command request"),
+        };
+        match schema::parse::<ExecRequest>(StateKind::ExecRequest, &content) {
+            Ok(request) => Ok(Some(request)),
+            Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
+            Err(e) => {
+                warn!(?path, error = %e, "corrupted host command request, deleting");
+                let _ = fs::remove_file(&path);
+                Ok(None)
+            }
+        }
+    }
+
+    /// All host command requests, oldest first.
+    pub fn list_exec_requests(&self) -> Result<Vec<ExecRequest>> {
+        let entries = match fs::read_dir(self.requests_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read requests directory"),
+        };
+        let mut requests = Vec::new();
+        for entry in entries.flatten() {
+            let filename = entry.file_name();
+            let Some(key) = filename.to_str().and_then(PaneKey::from_filename) else {
+                continue;
+            };
+            if let Some(request) = self.get_exec_request(&key)? {
+                requests.push(request);
+            }
+        }
+        requests.sort_by_key(|r| r.requested_ts);
+        Ok(requests)
+    }
+
+    /// Record the user's decision on request `id` of a pane. Returns false
+    /// when that request is gone or was already decided.
+    pub fn decide_exec_request(&self, key: &PaneKey, id: u64, approved: bool) -> Result<bool> {
+        let _lock = self.lock()?;
+        match self.get_exec_request(key)? {
+            Some(mut request) if request.id == id && request.approved.is_none() => {
+                request.approved = Some(approved);
+                self.save_exec_request(&request)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Drop request `id` of a pane, unless a later request replaced it.
+    pub fn delete_exec_request(&self, key: &PaneKey, id: u64) -> Result<()> {
+        let _lock = self.lock()?;
+        if self.get_exec_request(key)?.is_some_and(|r| r.id == id) {
+            match fs::remove_file(self.requests_dir().join(key.to_filename())) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).context("Failed to delete host command request"),
+            }
+        }
+        Ok(())
+    }
+
     // ── Container state management ──────────────────────────────────────────
 
     /// Register a running container for a worktree handle.
@@ -192,7 +752,10 @@ impl StateStore {
     /// - **PID validation**: Pane was closed and recycled (stored PID != live PID)
     /// - **Command comparison**: Agent exited within pane (foreground command changed)
     ///
-    /// Returns only valid agents; removes stale state files.
+    /// Returns only valid agents; removes stale state files. Working and
+    /// waiting agents that failed both liveness checks (see `liveness`) are
+    /// downgraded to unknown. Agents recorded while their shell was still
+    /// starting them (see `setup_environment`) take on the agent's command.
     pub fn load_reconciled_agents(
         &self,
         mux: &dyn crate::multiplexer::Multiplexer,
@@ -205,8 +768,12 @@ impl StateStore {
         let mut valid_agents = Vec::new();
         let backend = mux.name();
         let instance = mux.instance_id();
+        // Only needed for agents that look dead, which is rare
+        let mut config: Option<Config> = None;
+        // Only needed for agents recorded before they started
+        let mut shell: Option<String> = None;
 
-        for state in all_agents {
+        for mut state in all_agents {
             // Skip agents from other backends/instances
             if state.pane_key.backend != backend || state.pane_key.instance != instance {
                 continue;
@@ -227,6 +794,27 @@ impl StateStore {
                     // Clear stale window status icon from status bar
                     let _ = mux.clear_status(&state.pane_key.pane_id);
                 }
+                Some(live)
+                    if live.current_command != state.command
+                        && recorded_before_start(
+                            &state,
+                            shell
+                                .get_or_insert_with(|| mux.get_default_shell().unwrap_or_default()),
+                        ) =>
+                {
+                    // Recorded at creation before the agent took over the shell
+                    let command = live.current_command.clone();
+                    self.update_agent(&state.pane_key, |stored| {
+                        stored.command = command.clone();
+                    })?;
+                    state.command = command;
+                    let mut agent_pane = state.to_agent_pane(
+                        live.session.clone().unwrap_or_default(),
+                        live.window.clone().unwrap_or_default(),
+                    );
+                    agent_pane.process = live.process.clone();
+                    valid_agents.push(agent_pane);
+                }
                 Some(live) if live.current_command != state.command => {
                     // Command changed - agent exited (e.g., "node" -> "zsh")
                     self.delete_agent(&state.pane_key)?;
@@ -234,11 +822,18 @@ impl StateStore {
                     let _ = mux.clear_status(&state.pane_key.pane_id);
                 }
                 Some(live) => {
+                    let idle = live.process.as_ref().is_some_and(|p| p.is_idle());
+                    if liveness::process_gone(&state) || idle {
+                        let config =
+                            config.get_or_insert_with(|| Config::load(None).unwrap_or_default());
+                        self.downgrade_if_dead(&mut state, live.process.as_ref(), mux, config)?;
+                    }
                     // Valid - include in dashboard
-                    let agent_pane = state.to_agent_pane(
+                    let mut agent_pane = state.to_agent_pane(
                         live.session.clone().unwrap_or_default(),
                         live.window.clone().unwrap_or_default(),
                     );
+                    agent_pane.process = live.process.clone();
                     valid_agents.push(agent_pane);
                 }
             }
@@ -246,13 +841,80 @@ impl StateStore {
 
         Ok(valid_agents)
     }
+
+    /// Mark the agent unknown if it has also stopped sending heartbeats, or
+    /// sits idle at its prompt while working, and show that in the
+    /// multiplexer.
+    fn downgrade_if_dead(
+        &self,
+        state: &mut AgentState,
+        process: Option<&crate::multiplexer::ProcessActivity>,
+        mux: &dyn crate::multiplexer::Multiplexer,
+        config: &Config,
+    ) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let Some(timeout) = config.heartbeat_timeout() else {
+            return Ok(());
+        };
+        if !liveness::is_dead(state, now, timeout)
+            && !liveness::is_idle(state, process, now, timeout)
+        {
+            return Ok(());
+        }
+        debug!(pane_id = %state.pane_key.pane_id, "agent stopped reporting, marking unknown");
+        state.status = Some(AgentStatus::Unknown);
+        state.status_ts = Some(now);
+        self.update_agent(&state.pane_key, |stored| {
+            stored.status = Some(AgentStatus::Unknown);
+            stored.status_ts = Some(now);
+            super::history::record(&mut stored.history, AgentStatus::Unknown, now);
+        })?;
+        let _ = mux.set_status(&state.pane_key.pane_id, config.status_icons.unknown(), true);
+        Ok(())
+    }
+
+    /// Agents of `mux` whose pane is gone or no longer runs the agent, by the
+    /// same checks as `load_reconciled_agents`, without deleting anything.
+    pub fn stale_agents(
+        &self,
+        mux: &dyn crate::multiplexer::Multiplexer,
+    ) -> Result<Vec<AgentState>> {
+        let live_panes = mux.get_all_live_pane_info()?;
+        let backend = mux.name();
+        let instance = mux.instance_id();
+        let shell = mux.get_default_shell().unwrap_or_default();
+        Ok(self
+            .list_all_agents()?
+            .into_iter()
+            .filter(|state| {
+                state.pane_key.backend == backend && state.pane_key.instance == instance
+            })
+            .filter(|state| {
+                live_panes.get(&state.pane_key.pane_id).is_none_or(|live| {
+                    live.pid != state.pane_pid
+                        || (live.current_command != state.command
+                            && !recorded_before_start(state, &shell))
+                })
+            })
+            .collect())
+    }
+}
+
+/// Whether `state` was recorded while the pane's shell was still starting
+/// the agent, so a different foreground command is the agent, not its exit.
+fn recorded_before_start(state: &AgentState, shell: &str) -> bool {
+    state.status.is_none() && crate::multiplexer::handshake::is_shell(&state.command, shell)
 }
 
 /// Write content atomically using temp file + rename.
 ///
-/// This ensures the target file is never partially written.
+/// This ensures the target file is never partially written. The temp file
+/// is named per process so concurrent writers don't clobber each other's.
 fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
-    let tmp = path.with_extension("json.tmp");
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
     fs::write(&tmp, content).context("Failed to write temp file")?;
     fs::rename(&tmp, path).context("Failed to rename temp file")?;
     Ok(())
@@ -277,10 +939,12 @@ pub(crate) fn get_state_dir() -> Result<PathBuf> {
 ///
 /// Returns None if file doesn't exist.
 /// Deletes corrupted files and returns None (recoverable error).
+/// Fails on files written by a newer version of workmux.
 fn read_agent_file(path: &Path) -> Result<Option<AgentState>> {
     match fs::read_to_string(path) {
-        Ok(content) => match serde_json::from_str(&content) {
+        Ok(content) => match schema::parse(StateKind::Agent, &content) {
             Ok(state) => Ok(Some(state)),
+            Err(e @ SchemaError::Newer { .. }) => Err(e.into()),
             Err(e) => {
                 warn!(?path, error = %e, "corrupted state file, deleting");
                 let _ = fs::remove_file(path);
@@ -296,6 +960,8 @@ fn read_agent_file(path: &Path) -> Result<Option<AgentState>> {
 mod tests {
     use super::*;
     use crate::multiplexer::AgentStatus;
+    use crate::state::types::{MergeStep, PromptDelivery};
+    use std::collections::BTreeMap;
     use tempfile::TempDir;
 
     fn test_store() -> (StateStore, TempDir) {
@@ -322,6 +988,16 @@ mod tests {
             pane_pid: 12345,
             command: "node".to_string(),
             updated_ts: 1234567890,
+            server_url: None,
+            progress: None,
+            task_progress: None,
+            priority: None,
+            paused_by: None,
+            denied_exec: None,
+            net_activity: None,
+            heartbeat_ts: None,
+            agent_pid: None,
+            history: Vec::new(),
         }
     }
 
@@ -436,6 +1112,7 @@ mod tests {
             hide_stale: true,
             preview_size: Some(30),
             last_pane_id: Some("%5".to_string()),
+            pinned_panes: BTreeMap::from([("tmux:default".to_string(), vec!["%3".to_string()])]),
         };
 
         store.save_settings(&settings).unwrap();
@@ -445,6 +1122,7 @@ mod tests {
         assert_eq!(loaded.hide_stale, settings.hide_stale);
         assert_eq!(loaded.preview_size, settings.preview_size);
         assert_eq!(loaded.last_pane_id, settings.last_pane_id);
+        assert_eq!(loaded.pinned_panes, settings.pinned_panes);
     }
 
     #[test]
@@ -484,4 +1162,354 @@ mod tests {
         let agents = store.list_all_agents().unwrap();
         assert_eq!(agents.len(), 1);
     }
+
+    #[test]
+    fn test_session_roundtrip_and_delete() {
+        let (store, _dir) = test_store();
+        let state = SessionState {
+            backend: "tmux".to_string(),
+            instance: "default".to_string(),
+            session: "wm-feature/auth".to_string(),
+            status: Some(AgentStatus::Waiting),
+            agent_count: 2,
+            updated_ts: 1234567890,
+        };
+
+        store.upsert_session(&state).unwrap();
+        let loaded = store
+            .get_session("tmux", "default", "wm-feature/auth")
+            .unwrap();
+        assert_eq!(loaded, Some(state));
+
+        store
+            .delete_session("tmux", "default", "wm-feature/auth")
+            .unwrap();
+        assert!(
+            store
+                .get_session("tmux", "default", "wm-feature/auth")
+                .unwrap()
+                .is_none()
+        );
+        // Deleting again is a no-op
+        store
+            .delete_session("tmux", "default", "wm-feature/auth")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_merge_checkpoints_are_listed_per_repo() {
+        let (store, _dir) = test_store();
+        let checkpoint = MergeCheckpoint {
+            repo: PathBuf::from("/src/app"),
+            handle: "login".to_string(),
+            branch: "feature/login".to_string(),
+            target: "main".to_string(),
+            worktree: PathBuf::from("/src/app__worktrees/login"),
+            target_worktree: PathBuf::from("/src/app"),
+            target_head: "abc123".to_string(),
+            step: MergeStep::Merging,
+            pid: 42,
+            started_ts: 1,
+        };
+        store.save_merge(&checkpoint).unwrap();
+        store
+            .save_merge(&MergeCheckpoint {
+                repo: PathBuf::from("/src/other"),
+                ..checkpoint.clone()
+            })
+            .unwrap();
+
+        let updated = MergeCheckpoint {
+            step: MergeStep::Merged,
+            ..checkpoint.clone()
+        };
+        store.save_merge(&updated).unwrap();
+        assert_eq!(
+            store.list_merges(Path::new("/src/app")).unwrap(),
+            vec![updated]
+        );
+
+        store
+            .delete_merge(Path::new("/src/app"), "feature/login")
+            .unwrap();
+        assert!(store.list_merges(Path::new("/src/app")).unwrap().is_empty());
+        assert_eq!(store.list_merges(Path::new("/src/other")).unwrap().len(), 1);
+        // Deleting again is a no-op
+        store
+            .delete_merge(Path::new("/src/app"), "feature/login")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_comment_drafts_roundtrip() {
+        let (store, dir) = test_store();
+        let worktree = Path::new("/src/app__worktrees/login");
+        let mut drafts = store.load_comment_drafts(worktree).unwrap();
+        assert!(drafts.drafts.is_empty());
+
+        drafts.drafts.insert(
+            "src/lib.rs:abc".to_string(),
+            "line one\nline two".to_string(),
+        );
+        store.save_comment_drafts(&drafts).unwrap();
+        assert_eq!(store.load_comment_drafts(worktree).unwrap(), drafts);
+
+        // The file goes away with the last draft
+        drafts.drafts.clear();
+        store.save_comment_drafts(&drafts).unwrap();
+        assert_eq!(fs::read_dir(dir.path().join("drafts")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_pending_prompts_are_listed_per_worktree() {
+        let (store, _dir) = test_store();
+        let pending = PendingPrompt {
+            pane_key: test_pane_key(),
+            worktree: PathBuf::from("/src/app__worktrees/login"),
+            delivery: PromptDelivery {
+                agent: Some("claude".to_string()),
+                init_lines: vec!["/model opus".to_string()],
+                prompt_file: Some(PathBuf::from("/src/app__worktrees/login/PROMPT.md")),
+            },
+            attempts: 0,
+            last_error: "Agent shows a login screen".to_string(),
+            queued_ts: 1,
+            next_attempt_ts: 6,
+        };
+        store.save_pending_prompt(&pending).unwrap();
+        let mut other_key = test_pane_key();
+        other_key.pane_id = "%9".to_string();
+        store
+            .save_pending_prompt(&PendingPrompt {
+                pane_key: other_key,
+                worktree: PathBuf::from("/src/app__worktrees/other"),
+                ..pending.clone()
+            })
+            .unwrap();
+
+        let worktree = Path::new("/src/app__worktrees/login");
+        assert_eq!(store.list_pending_prompts(worktree).unwrap(), vec![pending]);
+
+        store.delete_pending_prompt(&test_pane_key()).unwrap();
+        assert!(store.list_pending_prompts(worktree).unwrap().is_empty());
+        assert!(
+            store
+                .get_pending_prompt(&test_pane_key())
+                .unwrap()
+                .is_none()
+        );
+        // Deleting again is a no-op
+        store.delete_pending_prompt(&test_pane_key()).unwrap();
+    }
+
+    #[test]
+    fn test_exec_request_decisions_apply_to_their_request() {
+        let (store, _dir) = test_store();
+        let request = ExecRequest {
+            pane_key: test_pane_key(),
+            worktree: PathBuf::from("/src/app__worktrees/login"),
+            id: 7,
+            command: "gh".to_string(),
+            args: vec!["pr".to_string(), "create".to_string()],
+            requested_ts: 1,
+            approved: None,
+        };
+        store.save_exec_request(&request).unwrap();
+        assert_eq!(store.list_exec_requests().unwrap(), vec![request.clone()]);
+
+        // A decision for another request of the pane is ignored
+        assert!(
+            !store
+                .decide_exec_request(&test_pane_key(), 8, true)
+                .unwrap()
+        );
+        assert!(
+            store
+                .decide_exec_request(&test_pane_key(), 7, false)
+                .unwrap()
+        );
+        assert!(
+            !store
+                .decide_exec_request(&test_pane_key(), 7, true)
+                .unwrap()
+        );
+        let decided = store.get_exec_request(&test_pane_key()).unwrap().unwrap();
+        assert_eq!(decided.approved, Some(false));
+
+        store.delete_exec_request(&test_pane_key(), 8).unwrap();
+        assert!(store.get_exec_request(&test_pane_key()).unwrap().is_some());
+        store.delete_exec_request(&test_pane_key(), 7).unwrap();
+        assert!(store.list_exec_requests().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_relocate_workdirs() {
+        let (store, _dir) = test_store();
+        let mut inside = test_agent_state(test_pane_key());
+        inside.workdir = PathBuf::from("/old/wt/sub");
+        store.upsert_agent(&inside).unwrap();
+
+        let mut other_key = test_pane_key();
+        other_key.pane_id = "%2".to_string();
+        let mut outside = test_agent_state(other_key.clone());
+        outside.workdir = PathBuf::from("/old/wt-other");
+        store.upsert_agent(&outside).unwrap();
+
+        let n = store
+            .relocate_workdirs(Path::new("/old/wt"), Path::new("/new/wt"))
+            .unwrap();
+        assert_eq!(n, 1);
+        assert_eq!(
+            store.get_agent(&test_pane_key()).unwrap().unwrap().workdir,
+            PathBuf::from("/new/wt/sub")
+        );
+        assert_eq!(
+            store.get_agent(&other_key).unwrap().unwrap().workdir,
+            PathBuf::from("/old/wt-other")
+        );
+    }
+
+    #[test]
+    fn test_update_agent() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+        assert!(!store.update_agent(&key, |_| {}).unwrap());
+
+        store.upsert_agent(&test_agent_state(key.clone())).unwrap();
+        let updated = store
+            .update_agent(&key, |s| {
+                s.server_url = Some("http://127.0.0.1:4096".to_string())
+            })
+            .unwrap();
+        assert!(updated);
+
+        let state = store.get_agent(&key).unwrap().unwrap();
+        assert_eq!(state.server_url.as_deref(), Some("http://127.0.0.1:4096"));
+        assert_eq!(state.status, Some(AgentStatus::Working));
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+        let mut state = test_agent_state(key.clone());
+        state.updated_ts = 0;
+        store.upsert_agent(&state).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        store.update_agent(&key, |s| s.updated_ts += 1).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(store.get_agent(&key).unwrap().unwrap().updated_ts, 40);
+    }
+
+    #[test]
+    fn test_watch_reports_agent_writes() {
+        let (store, _dir) = test_store();
+        let watcher = store.watch().unwrap();
+        assert!(!watcher.has_changed());
+
+        store
+            .upsert_agent(&test_agent_state(test_pane_key()))
+            .unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !watcher.has_changed() {
+            assert!(std::time::Instant::now() < deadline, "no change reported");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_agent_file_without_server_fields_loads() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+        let mut json = serde_json::to_value(test_agent_state(key.clone())).unwrap();
+        json.as_object_mut().unwrap().remove("server_url");
+        json.as_object_mut().unwrap().remove("progress");
+        fs::create_dir_all(store.agents_dir()).unwrap();
+        fs::write(store.agent_path(&key), json.to_string()).unwrap();
+
+        let state = store.get_agent(&key).unwrap().unwrap();
+        assert_eq!(state.server_url, None);
+        assert_eq!(state.progress, None);
+    }
+
+    #[test]
+    fn test_incompatible_files_are_reported_and_kept() {
+        let (store, _dir) = test_store();
+        store
+            .upsert_agent(&test_agent_state(test_pane_key()))
+            .unwrap();
+        let bad = store.agents_dir().join("tmux__default__%2.json");
+        fs::write(&bad, r#"{"pane_key": 42}"#).unwrap();
+        fs::write(store.settings_path(), "not json").unwrap();
+
+        let failures = store.incompatible_files().unwrap();
+        let paths: Vec<&PathBuf> = failures.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&&bad));
+        assert!(paths.contains(&&store.settings_path()));
+        assert!(bad.exists());
+    }
+
+    #[test]
+    fn test_migrate_upgrades_and_backs_up_unversioned_files() {
+        let (store, dir) = test_store();
+        let key = test_pane_key();
+        let mut json = serde_json::to_value(test_agent_state(key.clone())).unwrap();
+        json.as_object_mut().unwrap().remove("schema_version");
+        let original = json.to_string();
+        fs::write(store.agent_path(&key), &original).unwrap();
+
+        store.migrate().unwrap();
+        let migrated: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(store.agent_path(&key)).unwrap()).unwrap();
+        assert_eq!(
+            schema::version_of(&migrated),
+            StateKind::Agent.current_version()
+        );
+        let backup = dir
+            .path()
+            .join("backups/schema-v0/agents")
+            .join(key.to_filename());
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+        assert_eq!(store.recorded_version(), Some(schema::store_version()));
+        assert!(store.get_agent(&key).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_state_from_newer_version_is_an_error() {
+        let (store, _dir) = test_store();
+        let key = test_pane_key();
+        let mut json = serde_json::to_value(test_agent_state(key.clone())).unwrap();
+        json["schema_version"] = 99.into();
+        fs::write(store.agent_path(&key), json.to_string()).unwrap();
+
+        let err = store.get_agent(&key).unwrap_err();
+        assert!(err.to_string().contains("newer version of workmux"));
+        // Kept for the version that wrote it
+        assert!(store.agent_path(&key).exists());
+
+        let newer = schema::store_version() + 1;
+        fs::write(store.schema_version_path(), newer.to_string()).unwrap();
+        let err = store.migrate().unwrap_err();
+        assert!(err.to_string().contains("newer version of workmux"));
+    }
+
+    #[test]
+    fn agents_recorded_from_the_shell_await_their_start() {
+        let mut state = test_agent_state(test_pane_key());
+        state.status = None;
+        state.command = "zsh".to_string();
+        assert!(recorded_before_start(&state, "/bin/zsh"));
+        // Reporting agents and agents recorded while running are settled
+        assert!(!recorded_before_start(&state, "/bin/bash"));
+        state.status = Some(AgentStatus::Working);
+        assert!(!recorded_before_start(&state, "/bin/zsh"));
+    }
 }
@@ -2,13 +2,17 @@
 
 use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Characters that need encoding in filenames (beyond control chars).
 /// Includes path separators and other filesystem-unsafe characters.
 const FILENAME_ENCODE_SET: &AsciiSet = &CONTROLS.add(b'/').add(b'\\').add(b':').add(b'%');
 
-use crate::multiplexer::types::{AgentPane, AgentStatus};
+use crate::config::Priority;
+use crate::multiplexer::types::{
+    AgentPane, AgentProgress, AgentStatus, DeniedExec, NetActivity, TaskProgress,
+};
 
 /// Composite pane identifier for unique state file naming.
 ///
@@ -42,7 +46,6 @@ impl PaneKey {
     /// Parse a PaneKey from a filename.
     ///
     /// Returns None if the filename doesn't match the expected format.
-    #[allow(dead_code)] // Used in tests, may be used in future features
     pub fn from_filename(filename: &str) -> Option<Self> {
         let stem = filename.strip_suffix(".json")?;
         let parts: Vec<&str> = stem.splitn(3, "__").collect();
@@ -95,6 +98,55 @@ pub struct AgentState {
     /// Note: This is NOT a heartbeat - only updated when status changes.
     /// Used for staleness detection and recency sorting.
     pub updated_ts: u64,
+
+    /// URL of the agent's HTTP server (OpenCode server mode), if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_url: Option<String>,
+
+    /// Latest progress pulled from the agent's server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<AgentProgress>,
+
+    /// Latest progress the agent reported with `workmux report-progress`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_progress: Option<TaskProgress>,
+
+    /// Priority of the agent's worktree, recorded while preemption is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+
+    /// Pane ID of the higher-priority agent this one was paused for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused_by: Option<String>,
+
+    /// Latest host command the sandbox refused to run for this agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denied_exec: Option<DeniedExec>,
+
+    /// Recent network traffic, sampled by the sandbox supervisor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_activity: Option<NetActivity>,
+
+    /// Unix timestamp of the agent's latest status report or heartbeat
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_ts: Option<u64>,
+
+    /// PID of the pane process that reported the status: the agent itself,
+    /// or the sandbox supervisor (see `liveness`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_pid: Option<u32>,
+
+    /// Statuses the agent changed to, oldest first (see `history`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<StatusChange>,
+}
+
+/// A status an agent changed to, and when.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct StatusChange {
+    pub status: AgentStatus,
+    /// Unix timestamp of the change
+    pub ts: u64,
 }
 
 impl AgentState {
@@ -110,10 +162,241 @@ impl AgentState {
             pane_title: self.pane_title.clone(),
             status: self.status,
             status_ts: self.status_ts,
+            progress: self.progress.clone(),
+            task_progress: self.task_progress.clone(),
+            denied_exec: self.denied_exec.clone(),
+            net_activity: self.net_activity.clone(),
+            process: None,
         }
     }
 }
 
+/// Aggregate status for a session-mode worktree (one file per session).
+///
+/// Derived from the statuses of all agent panes in the session using
+/// worst-of semantics (see `AgentStatus::rollup`).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SessionState {
+    /// Backend type: "tmux", "wezterm", "zellij"
+    pub backend: String,
+
+    /// Backend instance identifier
+    pub instance: String,
+
+    /// Full (prefixed) session name
+    pub session: String,
+
+    /// Rolled-up status of all agents in the session
+    pub status: Option<AgentStatus>,
+
+    /// Number of agent panes contributing to the rollup
+    pub agent_count: usize,
+
+    /// Unix timestamp of last rollup update
+    pub updated_ts: u64,
+}
+
+impl SessionState {
+    /// Generate filename for this session's state file.
+    ///
+    /// Format: `{backend}__{instance}__{session}.json`, encoded like `PaneKey`.
+    pub fn to_filename(&self) -> String {
+        session_filename(&self.backend, &self.instance, &self.session)
+    }
+}
+
+/// Filename for a session state file, shared by writers and lookups.
+pub fn session_filename(backend: &str, instance: &str, session: &str) -> String {
+    let safe_instance = utf8_percent_encode(instance, FILENAME_ENCODE_SET).to_string();
+    let safe_session = utf8_percent_encode(session, FILENAME_ENCODE_SET).to_string();
+    format!("{}__{}__{}.json", backend, safe_instance, safe_session)
+}
+
+/// Progress of a `workmux merge`, recorded while it runs so a merge cut
+/// short by a crash, a sleeping machine or a killed tmux server can be
+/// resumed or aborted by the next run.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MergeCheckpoint {
+    /// Main worktree of the repository
+    pub repo: PathBuf,
+
+    /// Worktree handle of the branch being merged
+    pub handle: String,
+
+    pub branch: String,
+
+    /// Branch it is merged into
+    pub target: String,
+
+    /// Worktree of the branch being merged
+    pub worktree: PathBuf,
+
+    /// Worktree the merge into the target happens in
+    pub target_worktree: PathBuf,
+
+    /// Commit the target pointed at before the merge
+    pub target_head: String,
+
+    /// Last completed step
+    pub step: MergeStep,
+
+    /// Process running the merge
+    pub pid: u32,
+
+    /// Unix timestamp the merge started at
+    pub started_ts: u64,
+}
+
+impl MergeCheckpoint {
+    /// Generate filename for this merge's checkpoint file.
+    pub fn to_filename(&self) -> String {
+        merge_filename(&self.repo, &self.branch)
+    }
+}
+
+/// Filename for a merge checkpoint: `{repo}__{branch}.json`, encoded like
+/// `PaneKey`.
+pub fn merge_filename(repo: &Path, branch: &str) -> String {
+    let safe_repo = utf8_percent_encode(&repo.to_string_lossy(), FILENAME_ENCODE_SET).to_string();
+    let safe_branch = utf8_percent_encode(branch, FILENAME_ENCODE_SET).to_string();
+    format!("{}__{}.json", safe_repo, safe_branch)
+}
+
+/// Steps of a merge, in order.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStep {
+    /// Merging into the target; it may be left mid-merge
+    Merging,
+    /// The branch has landed on the target
+    Merged,
+    /// Remote sync is done; only cleanup is left
+    Synced,
+}
+
+impl MergeStep {
+    pub fn describe(self) -> &'static str {
+        match self {
+            MergeStep::Merging => "while merging",
+            MergeStep::Merged => "after merging, before syncing the remote",
+            MergeStep::Synced => "during cleanup",
+        }
+    }
+}
+
+/// Comments typed in the dashboard's patch mode but not sent yet, for the
+/// hunks of one worktree. Saved as they are typed, so closing the editor or
+/// the dashboard doesn't lose them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CommentDrafts {
+    pub worktree: PathBuf,
+
+    /// Draft text by hunk key (file and hunk content, see `DiffHunk::draft_key`)
+    #[serde(default)]
+    pub drafts: BTreeMap<String, String>,
+}
+
+impl CommentDrafts {
+    /// Generate filename for this worktree's drafts file.
+    pub fn to_filename(&self) -> String {
+        drafts_filename(&self.worktree)
+    }
+}
+
+/// Filename for a worktree's comment drafts: `{worktree}.json`, encoded like
+/// `PaneKey`.
+pub fn drafts_filename(worktree: &Path) -> String {
+    let safe = utf8_percent_encode(&worktree.to_string_lossy(), FILENAME_ENCODE_SET).to_string();
+    format!("{}.json", safe)
+}
+
+/// What to type into an agent once it is ready: its `agent_init` lines,
+/// then its prompt.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct PromptDelivery {
+    /// Agent command, for its profile and submit keys
+    pub agent: Option<String>,
+
+    #[serde(default)]
+    pub init_lines: Vec<String>,
+
+    /// Absolute path of the prompt file
+    pub prompt_file: Option<PathBuf>,
+}
+
+/// A prompt that couldn't be delivered when its pane was created (the
+/// agent didn't start, showed a login screen, or the input didn't arrive
+/// intact), kept until a retry delivers it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PendingPrompt {
+    pub pane_key: PaneKey,
+
+    /// Worktree the agent runs in
+    pub worktree: PathBuf,
+
+    pub delivery: PromptDelivery,
+
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+
+    /// Why the last attempt failed
+    pub last_error: String,
+
+    /// Unix timestamp the prompt was queued at
+    pub queued_ts: u64,
+
+    /// Unix timestamp of the next automatic attempt
+    pub next_attempt_ts: u64,
+}
+
+impl PendingPrompt {
+    /// Generate filename for this prompt's queue file.
+    pub fn to_filename(&self) -> String {
+        self.pane_key.to_filename()
+    }
+}
+
+/// A host command a sandboxed agent asked to run although it isn't in
+/// `host_commands`, waiting for the user's decision in the dashboard.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExecRequest {
+    pub pane_key: PaneKey,
+
+    /// Worktree the agent runs in
+    pub worktree: PathBuf,
+
+    /// Random id, so a decision can't apply to a later request of the pane
+    pub id: u64,
+
+    pub command: String,
+
+    /// Arguments, with injected secrets redacted
+    pub args: Vec<String>,
+
+    /// Unix timestamp the request was made at
+    pub requested_ts: u64,
+
+    /// The user's decision, once made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
+}
+
+impl ExecRequest {
+    /// Generate filename for this request's file.
+    pub fn to_filename(&self) -> String {
+        self.pane_key.to_filename()
+    }
+
+    /// The command line, quoted where needed.
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.command)
+            .chain(&self.args)
+            .map(|arg| crate::shell::shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 /// Dashboard preferences stored globally.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GlobalSettings {
@@ -128,12 +411,25 @@ pub struct GlobalSettings {
 
     /// Last visited agent pane_id (for quick toggle)
     pub last_pane_id: Option<String>,
+
+    /// Pinned agent pane_ids, by `<backend>:<instance>` (pane ids are only
+    /// unique within one multiplexer server)
+    #[serde(default)]
+    pub pinned_panes: BTreeMap<String, Vec<String>>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_merge_filename_encodes_paths() {
+        assert_eq!(
+            merge_filename(Path::new("/src/app"), "feature/login"),
+            "%2Fsrc%2Fapp__feature%2Flogin.json"
+        );
+    }
+
     #[test]
     fn test_pane_key_to_filename() {
         let key = PaneKey {
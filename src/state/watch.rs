@@ -0,0 +1,90 @@
+//! Change notifications for the state store.
+//!
+//! Hooks, the RPC supervisor and other workmux processes write agent state
+//! while the dashboard is open. Watching the state directories (inotify on
+//! Linux, FSEvents on macOS) lets the dashboard refresh as soon as a status
+//! changes instead of waiting for its next poll.
+
+use std::path::Path;
+use std::sync::mpsc::{Receiver, channel};
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Watches state directories for writes from any process.
+pub struct StateWatcher {
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<notify::Event>>,
+}
+
+impl StateWatcher {
+    /// Start watching `dirs` (non-recursively). The directories must exist.
+    pub fn new(dirs: &[&Path]) -> Result<Self> {
+        let (tx, rx) = channel();
+        let mut watcher =
+            notify::recommended_watcher(tx).context("Failed to start state watcher")?;
+        for dir in dirs {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {}", dir.display()))?;
+        }
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+        })
+    }
+
+    /// Whether state changed since the last call. Never blocks.
+    pub fn has_changed(&self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.rx.try_recv() {
+            // Errors mean events may have been lost, so assume a change
+            changed |= event.is_err() || event.as_ref().is_ok_and(is_state_change);
+        }
+        changed
+    }
+}
+
+/// Whether an event is a write to a state file, ignoring temp files that are
+/// about to be renamed into place.
+fn is_state_change(event: &notify::Event) -> bool {
+    let writes = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+    writes
+        && event
+            .paths
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+    use std::path::PathBuf;
+
+    fn event(kind: EventKind, path: &str) -> notify::Event {
+        notify::Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn only_json_writes_count_as_changes() {
+        let create = EventKind::Create(CreateKind::File);
+        assert!(is_state_change(&event(
+            create,
+            "/s/agents/tmux__default__%1.json"
+        )));
+        assert!(is_state_change(&event(
+            EventKind::Modify(ModifyKind::Any),
+            "/s/sessions/tmux__default__wm-foo.json"
+        )));
+        assert!(!is_state_change(&event(create, "/s/agents/a.json.123.tmp")));
+        assert!(!is_state_change(&event(
+            EventKind::Access(notify::event::AccessKind::Any),
+            "/s/agents/a.json"
+        )));
+    }
+}
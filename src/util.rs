@@ -1,11 +1,50 @@
+use anyhow::{Context, Result};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use crate::cmd::Cmd;
+
 /// Canonicalize a path, falling back to the original if canonicalization fails.
 pub fn canon_or_self(p: &Path) -> PathBuf {
     p.canonicalize().unwrap_or_else(|_| p.to_path_buf())
 }
 
+/// Check whether `a` and `b` live on the same filesystem.
+///
+/// Either path may not exist yet; the nearest existing ancestor is used.
+pub fn same_filesystem(a: &Path, b: &Path) -> bool {
+    let dev = |p: &Path| {
+        p.ancestors()
+            .find_map(|dir| std::fs::metadata(dir).ok())
+            .map(|m| m.dev())
+    };
+    matches!((dev(a), dev(b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Move a directory, copying across filesystems when a rename is impossible.
+///
+/// The destination must not exist. Its parent directories are created.
+pub fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    if same_filesystem(from, to) {
+        return std::fs::rename(from, to)
+            .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()));
+    }
+
+    let from_str = from.to_string_lossy();
+    let to_str = to.to_string_lossy();
+    Cmd::new("cp")
+        .args(&["-a", &from_str, &to_str])
+        .run()
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+    std::fs::remove_dir_all(from)
+        .with_context(|| format!("Failed to remove {} after copying", from.display()))
+}
+
 /// Format a duration as a human-readable elapsed time string.
 /// Used by `status` and `wait` commands.
 pub fn format_elapsed_secs(secs: u64) -> String {
@@ -41,6 +80,68 @@ pub fn format_elapsed_duration(d: Duration) -> String {
     }
 }
 
+/// Levenshtein edit distance between two strings (counted in chars).
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// Pick up to `limit` candidates close to `input`, nearest first.
+///
+/// The allowed distance scales with input length so short names don't match
+/// everything and long names still tolerate a couple of typos.
+pub fn closest_matches<'a>(input: &str, candidates: &'a [String], limit: usize) -> Vec<&'a str> {
+    let max_distance = (input.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|c| (levenshtein(input, c), c.as_str()))
+        .filter(|(d, _)| *d <= max_distance)
+        .collect();
+    scored.sort();
+    scored.into_iter().take(limit).map(|(_, c)| c).collect()
+}
+
+/// Shows a system notification on macOS or Linux
+pub fn show_notification(message: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        use mac_notification_sys::{Notification, set_application};
+        // Set application to Terminal to use its icon
+        if let Err(e) = set_application("com.apple.Terminal") {
+            tracing::debug!("Failed to set notification application: {:?}", e);
+        }
+        if let Err(e) = Notification::default()
+            .title("workmux")
+            .message(message)
+            .send()
+        {
+            tracing::debug!("Failed to send notification: {:?}", e);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if let Err(e) = notify_rust::Notification::new()
+            .summary("workmux")
+            .body(message)
+            .show()
+        {
+            tracing::debug!("Failed to send notification: {:?}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +194,58 @@ mod tests {
         assert_eq!(format_elapsed_duration(Duration::from_secs(3661)), "1h 01m");
         assert_eq!(format_elapsed_duration(Duration::from_secs(7260)), "2h 01m");
     }
+
+    #[test]
+    fn move_dir_creates_parents_and_moves_contents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("wt");
+        std::fs::create_dir_all(from.join("src")).unwrap();
+        std::fs::write(from.join("src/main.rs"), "fn main() {}").unwrap();
+
+        let to = tmp.path().join("elsewhere/nested/wt");
+        move_dir(&from, &to).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(
+            std::fs::read_to_string(to.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+    }
+
+    #[test]
+    fn same_filesystem_uses_existing_ancestor() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(same_filesystem(
+            tmp.path(),
+            &tmp.path().join("missing/child")
+        ));
+    }
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("main", "main"), 0);
+        assert_eq!(levenshtein("mian", "main"), 2);
+        assert_eq!(levenshtein("develop", "devel"), 2);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn closest_matches_orders_by_distance_and_limits() {
+        let candidates: Vec<String> = ["main", "master", "origin/main", "feature/foo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(closest_matches("mian", &candidates, 3), vec!["main"]);
+        assert_eq!(
+            closest_matches("origin/mian", &candidates, 3),
+            vec!["origin/main"]
+        );
+        assert_eq!(
+            closest_matches("feature/fo", &candidates, 3),
+            vec!["feature/foo"]
+        );
+        assert!(closest_matches("zzzzzz", &candidates, 3).is_empty());
+    }
 }
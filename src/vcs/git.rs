@@ -11,6 +11,7 @@ use super::{Vcs, VcsStatus};
 /// Git implementation of the Vcs trait.
 ///
 /// Delegates to the existing `git::*` module functions.
+#[derive(Default)]
 pub struct GitVcs;
 
 impl GitVcs {
@@ -46,6 +47,11 @@ impl Vcs for GitVcs {
         git::get_main_worktree_root()
     }
 
+    fn is_main_workspace(&self, dir: &Path) -> Result<bool> {
+        // Linked worktrees have a `.git` file pointing at the common dir
+        Ok(git::get_repo_root_for(dir)?.join(".git").is_dir())
+    }
+
     fn get_shared_dir(&self) -> Result<PathBuf> {
         git::get_git_common_dir()
     }
@@ -87,6 +93,22 @@ impl Vcs for GitVcs {
         git::prune_worktrees_in(shared_dir)
     }
 
+    fn list_locked_workspaces(&self) -> HashMap<PathBuf, String> {
+        git::list_locked_worktrees().unwrap_or_default()
+    }
+
+    fn lock_workspace(&self, path: &Path, reason: Option<&str>) -> Result<()> {
+        git::lock_worktree(path, reason)
+    }
+
+    fn unlock_workspace(&self, path: &Path) -> Result<()> {
+        git::unlock_worktree(path)
+    }
+
+    fn move_workspace(&self, from: &Path, to: &Path) -> Result<()> {
+        git::move_worktree(from, to)
+    }
+
     // ── Workspace metadata ───────────────────────────────────────────
 
     fn set_workspace_meta(&self, handle: &str, key: &str, value: &str) -> Result<()> {
@@ -97,6 +119,14 @@ impl Vcs for GitVcs {
         git::get_worktree_meta(handle, key)
     }
 
+    fn set_repo_meta(&self, key: &str, value: &str) -> Result<()> {
+        git::set_repo_meta(key, value)
+    }
+
+    fn get_repo_meta(&self, key: &str) -> Option<String> {
+        git::get_repo_meta(key)
+    }
+
     fn get_workspace_mode(&self, handle: &str) -> MuxMode {
         git::get_worktree_mode(handle)
     }
@@ -109,6 +139,10 @@ impl Vcs for GitVcs {
         git::remove_worktree_meta(handle)
     }
 
+    fn unset_workspace_meta(&self, handle: &str, key: &str) -> Result<()> {
+        git::unset_worktree_meta(handle, key)
+    }
+
     // ── Branch/bookmark operations ───────────────────────────────────
 
     fn get_default_branch(&self) -> Result<String> {
@@ -135,6 +169,28 @@ impl Vcs for GitVcs {
         git::list_checkout_branches()
     }
 
+    fn list_base_branches(&self) -> Result<Vec<String>> {
+        git::list_all_branches()
+    }
+
+    fn revision_exists(&self, rev: &str) -> Result<bool> {
+        git::branch_exists(&format!("{}^{{commit}}", rev))
+    }
+
+    fn resolve_commit(&self, rev: &str) -> Result<String> {
+        git::resolve_commit(rev)
+    }
+
+    fn split_remote_ref(&self, rev: &str) -> Option<(String, String)> {
+        git::parse_remote_branch_spec(rev)
+            .ok()
+            .map(|spec| (spec.remote, spec.branch))
+    }
+
+    fn remote_ref(&self, remote: &str, branch: &str) -> String {
+        format!("{}/{}", remote, branch)
+    }
+
     fn delete_branch(&self, name: &str, force: bool, shared_dir: &Path) -> Result<()> {
         git::delete_branch_in(name, force, shared_dir)
     }
@@ -151,6 +207,28 @@ impl Vcs for GitVcs {
         git::get_gone_branches()
     }
 
+    fn get_last_commit_time(&self, branch: &str) -> Result<u64> {
+        git::get_last_commit_time(branch)
+    }
+
+    fn get_branch_summary(&self, branch: &str, base: &str) -> Result<git::BranchSummary> {
+        git::get_branch_summary(branch, base)
+    }
+
+    fn bundle_branch(
+        &self,
+        branch: &str,
+        base: &str,
+        path: &Path,
+        shared_dir: &Path,
+    ) -> Result<bool> {
+        git::bundle_branch_in(branch, base, path, shared_dir)
+    }
+
+    fn restore_branch_bundle(&self, path: &Path, branch: &str, shared_dir: &Path) -> Result<()> {
+        git::restore_branch_bundle_in(path, branch, shared_dir)
+    }
+
     // ── Base branch tracking ─────────────────────────────────────────
 
     fn set_branch_base(&self, branch: &str, base: &str) -> Result<()> {
@@ -161,6 +239,10 @@ impl Vcs for GitVcs {
         git::get_branch_base(branch)
     }
 
+    fn unset_branch_base(&self, branch: &str) -> Result<()> {
+        git::unset_branch_base(branch)
+    }
+
     fn get_branch_base_in(&self, branch: &str, workdir: Option<&Path>) -> Result<String> {
         git::get_branch_base_in(branch, workdir)
     }
@@ -193,12 +275,16 @@ impl Vcs for GitVcs {
 
     // ── Merge operations ─────────────────────────────────────────────
 
-    fn commit_with_editor(&self, worktree: &Path) -> Result<()> {
-        git::commit_with_editor(worktree)
+    fn commit_with_editor(&self, worktree: &Path, no_verify: bool) -> Result<()> {
+        git::commit_with_editor(worktree, no_verify)
     }
 
-    fn merge_in_workspace(&self, worktree: &Path, branch: &str) -> Result<()> {
-        git::merge_in_worktree(worktree, branch)
+    fn append_commit_template(&self, worktree: &Path, text: &str) -> Result<()> {
+        git::append_squash_message(worktree, text)
+    }
+
+    fn merge_in_workspace(&self, worktree: &Path, branch: &str, no_verify: bool) -> Result<()> {
+        git::merge_in_worktree(worktree, branch, no_verify)
     }
 
     fn rebase_onto_base(&self, worktree: &Path, base: &str) -> Result<()> {
@@ -229,6 +315,18 @@ impl Vcs for GitVcs {
         git::abort_merge_in_worktree(worktree)
     }
 
+    fn merge_in_progress(&self, worktree: &Path) -> bool {
+        git::merge_in_progress(worktree)
+    }
+
+    fn holds_merge_of(&self, worktree: &Path, branch: &str) -> Result<bool> {
+        git::holds_merge_of(worktree, branch)
+    }
+
+    fn apply_patch(&self, worktree: &Path, patch: &Path) -> Result<()> {
+        git::apply_patch_file(worktree, patch).map(|_| ())
+    }
+
     // ── Remotes ──────────────────────────────────────────────────────
 
     fn list_remotes(&self) -> Result<Vec<String>> {
@@ -243,10 +341,40 @@ impl Vcs for GitVcs {
         git::fetch_remote(remote)
     }
 
+    fn fetch_remote_branch(&self, remote: &str, branch: &str) -> Result<()> {
+        git::fetch_remote_branch(remote, branch)
+    }
+
     fn fetch_prune(&self) -> Result<()> {
         git::fetch_prune()
     }
 
+    fn get_branch_remote(&self, branch: &str) -> Option<String> {
+        git::get_branch_remote(branch)
+    }
+
+    fn push_branch(
+        &self,
+        remote: &str,
+        branch: &str,
+        force_with_lease: bool,
+        no_verify: bool,
+    ) -> Result<()> {
+        git::push_branch(remote, branch, force_with_lease, no_verify)
+    }
+
+    fn set_branch_upstream(&self, branch: &str, remote: &str) -> Result<()> {
+        git::set_branch_upstream(branch, remote)
+    }
+
+    fn get_remote_default_branch(&self, remote: &str) -> Result<String> {
+        git::get_remote_default_branch(remote)
+    }
+
+    fn delete_remote_branch(&self, remote: &str, branch: &str) -> Result<()> {
+        git::delete_remote_branch(remote, branch)
+    }
+
     fn add_remote(&self, name: &str, url: &str) -> Result<()> {
         git::add_remote(name, url)
     }
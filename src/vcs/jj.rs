@@ -4,12 +4,15 @@ use std::path::{Path, PathBuf};
 use tracing::debug;
 
 use crate::cmd::Cmd;
-use crate::config::MuxMode;
+use crate::config::{ForgeProvider, MuxMode};
+use crate::forge::{self, RemoteUrl};
+use crate::git::{self, BranchSummary};
 use crate::shell::shell_quote;
 
 use super::{Vcs, VcsStatus, WorkspaceNotFound};
 
 /// Jujutsu (jj) implementation of the Vcs trait.
+#[derive(Default)]
 pub struct JjVcs;
 
 impl JjVcs {
@@ -71,46 +74,15 @@ fn parse_workspace_list(output: &str) -> Vec<String> {
 }
 
 /// Parse repository owner from a git remote URL.
-/// Supports both HTTPS and SSH formats.
-fn parse_owner_from_url(url: &str) -> Option<&str> {
-    if let Some(https_part) = url
-        .strip_prefix("https://")
-        .or_else(|| url.strip_prefix("http://"))
-    {
-        https_part.split('/').nth(1)
-    } else if url.starts_with("git@") {
-        url.split(':')
-            .nth(1)
-            .and_then(|path| path.split('/').next())
-    } else {
-        None
-    }
+/// Supports both HTTPS and SSH formats; GitLab owners are the full namespace.
+fn parse_owner_from_url(url: &str, provider: ForgeProvider) -> Option<&str> {
+    RemoteUrl::parse(url)?.owner(provider)
 }
 
-/// Construct a fork URL by replacing the owner in an origin URL.
-fn construct_fork_url(origin_url: &str, fork_owner: &str) -> Result<String> {
-    use git_url_parse::GitUrl;
-    use git_url_parse::types::provider::GenericProvider;
-
-    let parsed_url = GitUrl::parse(origin_url).with_context(|| {
-        format!("Failed to parse origin URL for fork remote: {}", origin_url)
-    })?;
-
-    let host = parsed_url.host().unwrap_or("github.com");
-    let scheme = parsed_url.scheme().unwrap_or("ssh");
-
-    let provider: GenericProvider = parsed_url
-        .provider_info()
-        .context("Failed to extract provider info from origin URL")?;
-    let repo_name = provider.repo();
-
-    let fork_url = match scheme {
-        "https" => format!("https://{}/{}/{}.git", host, fork_owner, repo_name),
-        "http" => format!("http://{}/{}/{}.git", host, fork_owner, repo_name),
-        _ => format!("git@{}:{}/{}.git", host, fork_owner, repo_name),
-    };
-
-    Ok(fork_url)
+/// Provider of the repository in the current directory with origin `origin_url`.
+fn current_provider(origin_url: &str) -> ForgeProvider {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    forge::resolve_provider(&cwd, Some(origin_url))
 }
 
 /// Read metadata directly from .jj/repo/config.toml (for batch operations).
@@ -156,6 +128,11 @@ impl Vcs for JjVcs {
         find_jj_root()
     }
 
+    fn is_main_workspace(&self, dir: &Path) -> Result<bool> {
+        // Secondary workspaces have a `.jj/repo` file pointing at the main one
+        Ok(find_jj_root_for(dir)?.join(".jj/repo").is_dir())
+    }
+
     fn get_shared_dir(&self) -> Result<PathBuf> {
         // For jj, the shared directory is the repo root (where .jj/ lives).
         // This is used for running cleanup commands from a stable directory.
@@ -405,6 +382,54 @@ impl Vcs for JjVcs {
         Ok(())
     }
 
+    fn list_locked_workspaces(&self) -> HashMap<PathBuf, String> {
+        HashMap::new()
+    }
+
+    fn lock_workspace(&self, _path: &Path, _reason: Option<&str>) -> Result<()> {
+        Err(jj_todo("lock_workspace"))
+    }
+
+    fn unlock_workspace(&self, _path: &Path) -> Result<()> {
+        Err(jj_todo("unlock_workspace"))
+    }
+
+    fn move_workspace(&self, from: &Path, to: &Path) -> Result<()> {
+        let handle = from
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid workspace path: no directory name"))?
+            .to_string_lossy()
+            .to_string();
+        let to_str = to
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid workspace path"))?;
+
+        // Secondary workspaces locate the shared store through the `.jj/repo`
+        // file, which may hold a relative path. Resolve it before moving so it
+        // can be pinned to an absolute path afterwards.
+        let pointer = from.join(".jj").join("repo");
+        let store = if pointer.is_file() {
+            let target = std::fs::read_to_string(&pointer)
+                .context("Failed to read workspace repo pointer")?;
+            Some(crate::util::canon_or_self(
+                &from.join(".jj").join(target.trim()),
+            ))
+        } else {
+            None
+        };
+
+        crate::util::move_dir(from, to)?;
+
+        if let Some(store) = store {
+            std::fs::write(
+                to.join(".jj").join("repo"),
+                store.to_string_lossy().as_bytes(),
+            )
+            .context("Failed to update workspace repo pointer")?;
+        }
+        self.set_workspace_meta(&handle, "path", to_str)
+    }
+
     // ── Workspace metadata ───────────────────────────────────────────
 
     fn set_workspace_meta(&self, handle: &str, key: &str, value: &str) -> Result<()> {
@@ -427,6 +452,38 @@ impl Vcs for JjVcs {
             .filter(|s| !s.is_empty())
     }
 
+    fn unset_workspace_meta(&self, handle: &str, key: &str) -> Result<()> {
+        if self.get_workspace_meta(handle, key).is_none() {
+            return Ok(());
+        }
+        let root = find_jj_root()?;
+        let config_key = format!("workmux.worktree.{}.{}", handle, key);
+        jj_cmd(Some(&root))
+            .args(&["config", "unset", "--repo", &config_key])
+            .run()
+            .with_context(|| format!("Failed to unset jj config {}", config_key))?;
+        Ok(())
+    }
+
+    fn set_repo_meta(&self, key: &str, value: &str) -> Result<()> {
+        let root = find_jj_root()?;
+        let config_key = format!("workmux.{}", key);
+        jj_cmd(Some(&root))
+            .args(&["config", "set", "--repo", &config_key, value])
+            .run()
+            .with_context(|| format!("Failed to set jj config {}", config_key))?;
+        Ok(())
+    }
+
+    fn get_repo_meta(&self, key: &str) -> Option<String> {
+        let root = find_jj_root().ok()?;
+        jj_cmd(Some(&root))
+            .args(&["config", "get", &format!("workmux.{}", key)])
+            .run_and_capture_stdout()
+            .ok()
+            .filter(|s| !s.is_empty())
+    }
+
     fn get_workspace_mode(&self, handle: &str) -> MuxMode {
         match self.get_workspace_meta(handle, "mode") {
             Some(mode) if mode == "session" => MuxMode::Session,
@@ -615,6 +672,64 @@ impl Vcs for JjVcs {
             .collect())
     }
 
+    fn list_base_branches(&self) -> Result<Vec<String>> {
+        let output = jj_cmd(None)
+            .args(&[
+                "bookmark",
+                "list",
+                "--all",
+                "-T",
+                "if(remote, name ++ \"@\" ++ remote, name) ++ \"\\n\"",
+            ])
+            .run_and_capture_stdout()
+            .context("Failed to list jj bookmarks")?;
+
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|s| !s.is_empty() && !s.ends_with("@git"))
+            .map(String::from)
+            .collect())
+    }
+
+    fn revision_exists(&self, rev: &str) -> Result<bool> {
+        jj_cmd(None)
+            .args(&["log", "-r", rev, "--no-graph", "--limit", "1", "-T", "\"\""])
+            .run_as_check()
+    }
+
+    fn resolve_commit(&self, rev: &str) -> Result<String> {
+        let id = jj_cmd(None)
+            .args(&[
+                "log",
+                "-r",
+                rev,
+                "--no-graph",
+                "--limit",
+                "1",
+                "-T",
+                "commit_id",
+            ])
+            .run_and_capture_stdout()
+            .with_context(|| format!("Failed to resolve '{}'", rev))?;
+        if id.is_empty() {
+            return Err(anyhow!("Revision '{}' not found", rev));
+        }
+        Ok(id)
+    }
+
+    fn split_remote_ref(&self, rev: &str) -> Option<(String, String)> {
+        let (branch, remote) = rev.rsplit_once('@')?;
+        if branch.is_empty() || remote.is_empty() {
+            return None;
+        }
+        Some((remote.to_string(), branch.to_string()))
+    }
+
+    fn remote_ref(&self, remote: &str, branch: &str) -> String {
+        format!("{}@{}", branch, remote)
+    }
+
     fn delete_branch(&self, name: &str, _force: bool, shared_dir: &Path) -> Result<()> {
         // jj bookmark delete has no force distinction
         jj_cmd(Some(shared_dir))
@@ -682,6 +797,63 @@ impl Vcs for JjVcs {
         Ok(HashSet::new())
     }
 
+    fn get_last_commit_time(&self, branch: &str) -> Result<u64> {
+        let output = jj_cmd(None)
+            .args(&[
+                "log",
+                "-r",
+                branch,
+                "--no-graph",
+                "-T",
+                "committer.timestamp().format(\"%s\")",
+            ])
+            .run_and_capture_stdout()?;
+        output
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid commit time for bookmark '{}'", branch))
+    }
+
+    fn get_branch_summary(&self, branch: &str, base: &str) -> Result<BranchSummary> {
+        let count = |revset: String| -> Result<usize> {
+            let output = jj_cmd(None)
+                .args(&["log", "-r", &revset, "--no-graph", "-T", "\"x\""])
+                .run_and_capture_stdout()?;
+            Ok(output.trim().len())
+        };
+        let tip = jj_cmd(None)
+            .args(&[
+                "log",
+                "-r",
+                branch,
+                "--no-graph",
+                "-T",
+                "committer.timestamp().format(\"%s\") ++ \"\\t\" ++ description.first_line()",
+            ])
+            .run_and_capture_stdout()?;
+        let (time, subject) = tip.split_once('\t').unwrap_or((tip.as_str(), ""));
+        Ok(BranchSummary {
+            ahead: count(format!("{}..{}", base, branch))?,
+            behind: count(format!("{}..{}", branch, base))?,
+            last_commit_time: time.trim().parse().unwrap_or(0),
+            subject: subject.trim().to_string(),
+        })
+    }
+
+    fn bundle_branch(
+        &self,
+        _branch: &str,
+        _base: &str,
+        _path: &Path,
+        _shared_dir: &Path,
+    ) -> Result<bool> {
+        Err(jj_todo("bundle_branch"))
+    }
+
+    fn restore_branch_bundle(&self, _path: &Path, _branch: &str, _shared_dir: &Path) -> Result<()> {
+        Err(jj_todo("restore_branch_bundle"))
+    }
+
     // ── Base branch tracking ─────────────────────────────────────────
 
     fn set_branch_base(&self, branch: &str, base: &str) -> Result<()> {
@@ -698,6 +870,19 @@ impl Vcs for JjVcs {
         self.get_branch_base_in(branch, None)
     }
 
+    fn unset_branch_base(&self, branch: &str) -> Result<()> {
+        if self.get_branch_base(branch).is_err() {
+            return Ok(());
+        }
+        let root = find_jj_root()?;
+        let config_key = format!("workmux.base.{}", branch);
+        jj_cmd(Some(&root))
+            .args(&["config", "unset", "--repo", &config_key])
+            .run()
+            .context("Failed to unset workmux base config")?;
+        Ok(())
+    }
+
     fn get_branch_base_in(&self, branch: &str, workdir: Option<&Path>) -> Result<String> {
         let root = match workdir {
             Some(d) => find_jj_root_for(d).unwrap_or_else(|_| find_jj_root().unwrap_or_default()),
@@ -789,9 +974,20 @@ impl Vcs for JjVcs {
             .map(|s| parse_jj_diff_stat_totals(&s))
             .unwrap_or((0, 0));
 
+        // jj has no upstream tracking here, so count against the base bookmark
+        let count = |revset: String| {
+            jj_cmd(Some(worktree))
+                .args(&["log", "-r", &revset, "--no-graph", "-T", "\"x\""])
+                .run_and_capture_stdout()
+                .map(|s| s.trim().len())
+                .unwrap_or(0)
+        };
+        let ahead = count(format!("{}..{}", base_branch, branch_ref));
+        let behind = count(format!("{}..{}", branch_ref, base_branch));
+
         VcsStatus {
-            ahead: 0, // jj doesn't have ahead/behind in the same way
-            behind: 0,
+            ahead,
+            behind,
             has_conflict,
             is_dirty,
             lines_added,
@@ -837,7 +1033,7 @@ impl Vcs for JjVcs {
 
     // ── Merge operations ─────────────────────────────────────────────
 
-    fn commit_with_editor(&self, worktree: &Path) -> Result<()> {
+    fn commit_with_editor(&self, worktree: &Path, _no_verify: bool) -> Result<()> {
         // `jj commit` creates a new change on top of the current one,
         // prompting for a description via the editor.
         let status = std::process::Command::new("jj")
@@ -853,7 +1049,24 @@ impl Vcs for JjVcs {
         Ok(())
     }
 
-    fn merge_in_workspace(&self, worktree: &Path, branch: &str) -> Result<()> {
+    fn append_commit_template(&self, worktree: &Path, text: &str) -> Result<()> {
+        // `jj commit` opens the editor on the current change's description
+        let mut description = jj_cmd(Some(worktree))
+            .args(&["log", "-r", "@", "--no-graph", "-T", "description"])
+            .run_and_capture_stdout()
+            .context("Failed to read the change description")?;
+        if !description.trim().is_empty() {
+            description = format!("{}\n\n", description.trim_end());
+        }
+        description.push_str(text);
+        jj_cmd(Some(worktree))
+            .args(&["describe", "-m", &description])
+            .run()
+            .context("Failed to set the change description")?;
+        Ok(())
+    }
+
+    fn merge_in_workspace(&self, worktree: &Path, branch: &str, _no_verify: bool) -> Result<()> {
         // In jj, merge creates a new change with multiple parents:
         // `jj new @ <branch>` creates a merge commit
         jj_cmd(Some(worktree))
@@ -920,6 +1133,37 @@ impl Vcs for JjVcs {
         Ok(())
     }
 
+    fn merge_in_progress(&self, _worktree: &Path) -> bool {
+        // jj records conflicts in the merge change itself, so a failed
+        // `jj new` never leaves a merge part-way
+        false
+    }
+
+    fn holds_merge_of(&self, _worktree: &Path, _branch: &str) -> Result<bool> {
+        // A jj merge is a change of its own rather than pending working copy
+        // state, so there is nothing to roll back by restoring
+        Ok(false)
+    }
+
+    fn apply_patch(&self, worktree: &Path, patch: &Path) -> Result<()> {
+        // Commits from mailboxes and bundles would need jj's git import;
+        // a plain diff lands in the working-copy change like any edit
+        let format = git::detect_patch_format(patch)?;
+        if format != git::PatchFormat::Diff {
+            return Err(anyhow!(
+                "Only plain diffs can be applied in jj repositories (got a {})",
+                format.label()
+            ));
+        }
+        let patch = std::path::absolute(patch).context("Invalid patch path")?;
+        Cmd::new("git")
+            .workdir(worktree)
+            .args(&["apply", "--whitespace=nowarn", &patch.to_string_lossy()])
+            .run()
+            .context("Failed to apply the diff")?;
+        Ok(())
+    }
+
     // ── Remotes ──────────────────────────────────────────────────────
 
     fn list_remotes(&self) -> Result<Vec<String>> {
@@ -949,6 +1193,14 @@ impl Vcs for JjVcs {
         Ok(())
     }
 
+    fn fetch_remote_branch(&self, remote: &str, branch: &str) -> Result<()> {
+        jj_cmd(None)
+            .args(&["git", "fetch", "--remote", remote, "--branch", branch])
+            .run()
+            .with_context(|| format!("Failed to fetch '{}' from remote '{}'", branch, remote))?;
+        Ok(())
+    }
+
     fn fetch_prune(&self) -> Result<()> {
         // jj git fetch auto-prunes deleted remote branches
         jj_cmd(None)
@@ -958,6 +1210,46 @@ impl Vcs for JjVcs {
         Ok(())
     }
 
+    fn get_branch_remote(&self, _branch: &str) -> Option<String> {
+        // jj tracks bookmarks per remote rather than per branch config
+        None
+    }
+
+    fn push_branch(
+        &self,
+        remote: &str,
+        branch: &str,
+        _force_with_lease: bool,
+        _no_verify: bool,
+    ) -> Result<()> {
+        // jj git push always checks that the remote bookmark is where jj last
+        // saw it, which is the same safety as --force-with-lease
+        jj_cmd(None)
+            .args(&["git", "push", "--remote", remote, "--bookmark", branch])
+            .run()
+            .with_context(|| format!("Failed to push '{}' to remote '{}'", branch, remote))?;
+        Ok(())
+    }
+
+    fn set_branch_upstream(&self, branch: &str, remote: &str) -> Result<()> {
+        jj_cmd(None)
+            .args(&["bookmark", "track", &format!("{}@{}", branch, remote)])
+            .run()
+            .with_context(|| format!("Failed to track '{}@{}'", branch, remote))?;
+        Ok(())
+    }
+
+    fn get_remote_default_branch(&self, _remote: &str) -> Result<String> {
+        // jj doesn't record a remote's HEAD
+        Err(jj_todo("get_remote_default_branch"))
+    }
+
+    fn delete_remote_branch(&self, _remote: &str, _branch: &str) -> Result<()> {
+        // jj deletes remote bookmarks by pushing a deleted local bookmark, but
+        // the local bookmark is only removed later by the merge cleanup
+        Err(jj_todo("delete_remote_branch"))
+    }
+
     fn add_remote(&self, name: &str, url: &str) -> Result<()> {
         jj_cmd(None)
             .args(&["git", "remote", "add", name, url])
@@ -994,16 +1286,17 @@ impl Vcs for JjVcs {
 
     fn ensure_fork_remote(&self, fork_owner: &str) -> Result<String> {
         // Reuse same logic as git: check if fork owner matches origin owner
-        let current_owner = self.get_repo_owner().unwrap_or_default();
+        let origin_url = self.get_remote_url("origin")?;
+        let provider = current_provider(&origin_url);
+        let current_owner = parse_owner_from_url(&origin_url, provider).unwrap_or_default();
         if !current_owner.is_empty() && fork_owner == current_owner {
             return Ok("origin".to_string());
         }
 
-        let remote_name = format!("fork-{}", fork_owner);
-        let origin_url = self.get_remote_url("origin")?;
+        let remote_name = format!("fork-{}", fork_owner.replace('/', "-"));
 
         // Construct fork URL based on origin URL format
-        let fork_url = construct_fork_url(&origin_url, fork_owner)?;
+        let fork_url = forge::fork_url(provider, &origin_url, fork_owner)?;
 
         if self.remote_exists(&remote_name)? {
             let current_url = self.get_remote_url(&remote_name)?;
@@ -1019,7 +1312,7 @@ impl Vcs for JjVcs {
 
     fn get_repo_owner(&self) -> Result<String> {
         let url = self.get_remote_url("origin")?;
-        parse_owner_from_url(&url)
+        parse_owner_from_url(&url, current_provider(&url))
             .ok_or_else(|| anyhow!("Could not parse repository owner from origin URL: {}", url))
             .map(|s| s.to_string())
     }
@@ -1188,7 +1481,7 @@ mod tests {
     #[test]
     fn test_parse_owner_https() {
         assert_eq!(
-            parse_owner_from_url("https://github.com/owner/repo.git"),
+            parse_owner_from_url("https://github.com/owner/repo.git", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -1196,7 +1489,7 @@ mod tests {
     #[test]
     fn test_parse_owner_ssh() {
         assert_eq!(
-            parse_owner_from_url("git@github.com:owner/repo.git"),
+            parse_owner_from_url("git@github.com:owner/repo.git", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -1204,7 +1497,7 @@ mod tests {
     #[test]
     fn test_parse_owner_http() {
         assert_eq!(
-            parse_owner_from_url("http://github.com/owner/repo"),
+            parse_owner_from_url("http://github.com/owner/repo", ForgeProvider::Github),
             Some("owner")
         );
     }
@@ -1212,7 +1505,10 @@ mod tests {
     #[test]
     fn test_parse_owner_enterprise_https() {
         assert_eq!(
-            parse_owner_from_url("https://github.enterprise.com/org/project.git"),
+            parse_owner_from_url(
+                "https://github.enterprise.com/org/project.git",
+                ForgeProvider::Github
+            ),
             Some("org")
         );
     }
@@ -1220,19 +1516,28 @@ mod tests {
     #[test]
     fn test_parse_owner_enterprise_ssh() {
         assert_eq!(
-            parse_owner_from_url("git@github.enterprise.net:team/project.git"),
+            parse_owner_from_url(
+                "git@github.enterprise.net:team/project.git",
+                ForgeProvider::Github
+            ),
             Some("team")
         );
     }
 
     #[test]
     fn test_parse_owner_invalid() {
-        assert_eq!(parse_owner_from_url("not-a-valid-url"), None);
+        assert_eq!(
+            parse_owner_from_url("not-a-valid-url", ForgeProvider::Github),
+            None
+        );
     }
 
     #[test]
     fn test_parse_owner_local_path() {
-        assert_eq!(parse_owner_from_url("/local/path/to/repo"), None);
+        assert_eq!(
+            parse_owner_from_url("/local/path/to/repo", ForgeProvider::Github),
+            None
+        );
     }
 
     // ── Cleanup commands ─────────────────────────────────────────────
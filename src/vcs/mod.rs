@@ -15,6 +15,8 @@ pub use self::jj::JjVcs;
 /// VCS-agnostic status information for a workspace
 pub type VcsStatus = crate::git::GitStatus;
 
+pub use crate::git::BranchSummary;
+
 /// Custom error type for workspace not found
 #[derive(Debug, thiserror::Error)]
 #[error("Workspace not found: {0}")]
@@ -44,6 +46,10 @@ pub trait Vcs: Send + Sync {
     /// Get the main workspace root (primary worktree or bare repo path)
     fn get_main_workspace_root(&self) -> Result<PathBuf>;
 
+    /// Check if the given path lies in the main workspace of its repository
+    /// (as opposed to a linked worktree / secondary workspace)
+    fn is_main_workspace(&self, dir: &Path) -> Result<bool>;
+
     /// Get the shared directory (git-common-dir or jj repo dir)
     fn get_shared_dir(&self) -> Result<PathBuf>;
 
@@ -78,6 +84,20 @@ pub trait Vcs: Send + Sync {
     /// Prune stale workspace metadata
     fn prune_workspaces(&self, shared_dir: &Path) -> Result<()>;
 
+    /// Locked workspaces with their lock reason (empty when none was given).
+    /// Locked workspaces survive pruning while their directory is missing.
+    fn list_locked_workspaces(&self) -> HashMap<PathBuf, String>;
+
+    /// Lock a workspace so it isn't pruned or removed
+    fn lock_workspace(&self, path: &Path, reason: Option<&str>) -> Result<()>;
+
+    /// Unlock a workspace locked with `lock_workspace`
+    fn unlock_workspace(&self, path: &Path) -> Result<()>;
+
+    /// Relocate a workspace directory, keeping VCS bookkeeping consistent.
+    /// The destination must not exist; it may be on another filesystem.
+    fn move_workspace(&self, from: &Path, to: &Path) -> Result<()>;
+
     // ── Workspace metadata ───────────────────────────────────────────
 
     /// Store per-workspace metadata
@@ -95,6 +115,15 @@ pub trait Vcs: Send + Sync {
     /// Remove all metadata for a workspace handle
     fn remove_workspace_meta(&self, handle: &str) -> Result<()>;
 
+    /// Remove one metadata key of a workspace handle (no-op if unset)
+    fn unset_workspace_meta(&self, handle: &str, key: &str) -> Result<()>;
+
+    /// Store repository-wide metadata (shared by all workspaces)
+    fn set_repo_meta(&self, key: &str, value: &str) -> Result<()>;
+
+    /// Retrieve repository-wide metadata. Returns None if key doesn't exist.
+    fn get_repo_meta(&self, key: &str) -> Option<String>;
+
     // ── Branch/bookmark operations ───────────────────────────────────
 
     /// Get the default branch (main/master)
@@ -115,6 +144,25 @@ pub trait Vcs: Send + Sync {
     /// List branches available for checkout (excluding those already checked out)
     fn list_checkout_branches(&self) -> Result<Vec<String>>;
 
+    /// List all branches usable as a base, local and remote-tracking,
+    /// including those checked out in a worktree
+    fn list_base_branches(&self) -> Result<Vec<String>>;
+
+    /// Check if a revision (branch, tag, commit or revset) resolves
+    fn revision_exists(&self, rev: &str) -> Result<bool>;
+
+    /// Full commit id a revision resolves to
+    fn resolve_commit(&self, rev: &str) -> Result<String>;
+
+    /// Split a remote branch reference into (remote, branch) if it uses this
+    /// VCS's remote syntax (`origin/feature` for git, `feature@origin` for jj).
+    /// Does not check that the remote exists.
+    fn split_remote_ref(&self, rev: &str) -> Option<(String, String)>;
+
+    /// Reference to `branch` on `remote` in this VCS's remote syntax; the
+    /// inverse of `split_remote_ref`.
+    fn remote_ref(&self, remote: &str, branch: &str) -> String;
+
     /// Delete a branch
     fn delete_branch(&self, name: &str, force: bool, shared_dir: &Path) -> Result<()>;
 
@@ -127,6 +175,26 @@ pub trait Vcs: Send + Sync {
     /// Get branches whose upstream tracking branch has been deleted
     fn get_gone_branches(&self) -> Result<HashSet<String>>;
 
+    /// Get the time (Unix seconds) of the latest commit on a branch
+    fn get_last_commit_time(&self, branch: &str) -> Result<u64>;
+
+    /// Ahead/behind counts against `base` and the tip commit of a local or
+    /// remote branch
+    fn get_branch_summary(&self, branch: &str, base: &str) -> Result<BranchSummary>;
+
+    /// Save the commits on a branch that aren't on `base` to a bundle file.
+    /// Returns false if there was nothing to save.
+    fn bundle_branch(
+        &self,
+        branch: &str,
+        base: &str,
+        path: &Path,
+        shared_dir: &Path,
+    ) -> Result<bool>;
+
+    /// Recreate a branch from a bundle written by `bundle_branch`
+    fn restore_branch_bundle(&self, path: &Path, branch: &str, shared_dir: &Path) -> Result<()>;
+
     // ── Base branch tracking (metadata) ──────────────────────────────
 
     /// Store the base branch that a branch was created from
@@ -135,6 +203,9 @@ pub trait Vcs: Send + Sync {
     /// Retrieve the base branch that a branch was created from
     fn get_branch_base(&self, branch: &str) -> Result<String>;
 
+    /// Forget the base branch recorded for a branch (no-op if unset)
+    fn unset_branch_base(&self, branch: &str) -> Result<()>;
+
     /// Get the base branch for a given branch in a specific workdir
     fn get_branch_base_in(&self, branch: &str, workdir: Option<&Path>) -> Result<String>;
 
@@ -160,11 +231,17 @@ pub trait Vcs: Send + Sync {
 
     // ── Merge operations ─────────────────────────────────────────────
 
-    /// Commit staged changes using the user's editor
-    fn commit_with_editor(&self, worktree: &Path) -> Result<()>;
+    /// Commit staged changes using the user's editor, skipping the
+    /// repository's commit hooks with `no_verify`
+    fn commit_with_editor(&self, worktree: &Path, no_verify: bool) -> Result<()>;
 
-    /// Merge a branch into the current branch in a workspace
-    fn merge_in_workspace(&self, worktree: &Path, branch: &str) -> Result<()>;
+    /// Append text to the message `commit_with_editor` offers after a
+    /// squash merge
+    fn append_commit_template(&self, worktree: &Path, text: &str) -> Result<()>;
+
+    /// Merge a branch into the current branch in a workspace, skipping the
+    /// repository's merge commit hooks with `no_verify`
+    fn merge_in_workspace(&self, worktree: &Path, branch: &str, no_verify: bool) -> Result<()>;
 
     /// Rebase the current branch onto a base branch
     fn rebase_onto_base(&self, worktree: &Path, base: &str) -> Result<()>;
@@ -187,6 +264,17 @@ pub trait Vcs: Send + Sync {
     /// Abort a merge in progress
     fn abort_merge(&self, worktree: &Path) -> Result<()>;
 
+    /// Whether a merge stopped part-way in a workspace, leaving conflicts
+    /// to resolve
+    fn merge_in_progress(&self, worktree: &Path) -> bool;
+
+    /// Whether a workspace holds nothing but an unfinished merge of `branch`,
+    /// so `reset_hard` only rolls that merge back
+    fn holds_merge_of(&self, worktree: &Path, branch: &str) -> Result<bool>;
+
+    /// Apply a diff, `git format-patch` mailbox or git bundle to a workspace
+    fn apply_patch(&self, worktree: &Path, patch: &Path) -> Result<()>;
+
     // ── Remotes ──────────────────────────────────────────────────────
 
     /// List configured remotes
@@ -198,9 +286,34 @@ pub trait Vcs: Send + Sync {
     /// Fetch updates from a remote
     fn fetch_remote(&self, remote: &str) -> Result<()>;
 
+    /// Fetch a single branch from a remote, updating its remote-tracking ref
+    fn fetch_remote_branch(&self, remote: &str, branch: &str) -> Result<()>;
+
     /// Fetch from remote with prune
     fn fetch_prune(&self) -> Result<()>;
 
+    /// Get the remote a branch tracks, if any
+    fn get_branch_remote(&self, branch: &str) -> Option<String>;
+
+    /// Push a branch to a remote, optionally with a lease-protected force push.
+    /// With `no_verify`, the repository's pre-push hook is skipped.
+    fn push_branch(
+        &self,
+        remote: &str,
+        branch: &str,
+        force_with_lease: bool,
+        no_verify: bool,
+    ) -> Result<()>;
+
+    /// Make a local branch track the branch of the same name on `remote`
+    fn set_branch_upstream(&self, branch: &str, remote: &str) -> Result<()>;
+
+    /// Default branch of a remote (e.g. `main` for `upstream/HEAD`)
+    fn get_remote_default_branch(&self, remote: &str) -> Result<String>;
+
+    /// Delete a branch on a remote
+    fn delete_remote_branch(&self, remote: &str, branch: &str) -> Result<()>;
+
     /// Add a remote
     fn add_remote(&self, name: &str, url: &str) -> Result<()>;
 
@@ -261,3 +374,13 @@ pub fn detect_vcs() -> Result<Arc<dyn Vcs>> {
 pub fn try_detect_vcs() -> Option<Arc<dyn Vcs>> {
     detect_vcs().ok()
 }
+
+/// A lock reason from `list_locked_workspaces` formatted to follow "locked",
+/// e.g. " (on usb-drive)".
+pub fn describe_lock(reason: &str) -> String {
+    if reason.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", reason)
+    }
+}
@@ -1,6 +1,7 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use std::path::{Path, PathBuf};
 
+use crate::config::Config;
 use crate::multiplexer::{AgentPane, Multiplexer};
 use crate::state::StateStore;
 use crate::util::canon_or_self;
@@ -46,6 +47,27 @@ pub fn resolve_worktree_agent(name: &str, mux: &dyn Multiplexer, vcs: &dyn Vcs)
     Ok((path, agent))
 }
 
+/// Refuse to send input to an agent in the main worktree when `protect_main`
+/// is enabled, unless explicitly allowed.
+pub fn ensure_not_protected_main(
+    config: &Config,
+    vcs: &dyn Vcs,
+    path: &Path,
+    allow_main: bool,
+) -> Result<()> {
+    if allow_main || !config.protect_main() {
+        return Ok(());
+    }
+    if vcs.is_main_workspace(path).unwrap_or(false) {
+        bail!(
+            "Refusing to send to an agent in the main worktree ({}) because protect_main is enabled. \
+             Pass --allow-main to override.",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
 /// Match agents to a worktree path from a pre-loaded agent list.
 ///
 /// Used by `status` and `wait` commands that load agents once and match
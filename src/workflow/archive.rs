@@ -0,0 +1,411 @@
+//! Snapshots of worktrees taken before removal.
+//!
+//! `workmux remove --archive` writes a `.tar.gz` under
+//! `<archive_dir>/<project>/` before anything is deleted, so work that was
+//! never merged can still be audited or picked up later. An archive holds:
+//!
+//! - `manifest.json`: the worktree, branch and commit that were archived
+//! - `diff.patch`: changes against the base branch, including uncommitted
+//!   changes to tracked files
+//! - `worktree/`: the worktree's files (tracked and untracked, not ignored),
+//!   with `.workmux/` and so the prompts and attachments
+//! - `logs/`: the pane transcripts
+//!
+//! `workmux archive list|extract` finds and unpacks them again.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::cmd::Cmd;
+use crate::{config, git};
+
+use super::artifacts::ARTIFACTS_DIR;
+use super::context::WorkflowContext;
+
+/// Name of the manifest at the root of each archive.
+const MANIFEST: &str = "manifest.json";
+
+/// Name of the diff against the base branch.
+const DIFF: &str = "diff.patch";
+
+/// Directory holding the worktree's files.
+const WORKTREE: &str = "worktree";
+
+/// Directory holding the pane transcripts.
+const LOGS: &str = "logs";
+
+const EXTENSION: &str = ".tar.gz";
+
+/// What an archive was taken of.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub handle: String,
+    pub branch: String,
+    /// Branch the diff is against, when one could be found
+    pub base: Option<String>,
+    /// Commit the branch was at
+    pub head: Option<String>,
+    pub worktree_path: PathBuf,
+    /// Unix seconds when the archive was written
+    pub created: u64,
+    /// Number of files in `worktree/`
+    pub files: usize,
+}
+
+/// An archive file, as found in the archive directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeArchive {
+    pub handle: String,
+    /// Unix seconds when the archive was written
+    pub created: u64,
+    pub path: PathBuf,
+}
+
+/// Directory holding the archives for the project at `main_worktree_root`.
+pub fn archive_dir(config: &config::Config, main_worktree_root: &Path) -> Result<PathBuf> {
+    Ok(config
+        .archive_dir()?
+        .join(config::project_name(main_worktree_root)))
+}
+
+/// Archive the worktree of `branch` at `worktree_path`. Returns the path of
+/// the archive.
+pub fn archive_worktree(
+    context: &WorkflowContext,
+    handle: &str,
+    branch: &str,
+    worktree_path: &Path,
+) -> Result<PathBuf> {
+    if !worktree_path.is_dir() {
+        bail!(
+            "Nothing to archive: '{}' doesn't exist",
+            worktree_path.display()
+        );
+    }
+    let dir = archive_dir(&context.config, &context.main_worktree_root)?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create archive directory '{}'", dir.display()))?;
+    let staging = tempfile::Builder::new()
+        .prefix(".staging-")
+        .tempdir_in(&dir)
+        .context("Failed to create archive staging directory")?;
+    let root = staging.path();
+
+    let files = copy_worktree(worktree_path, &root.join(WORKTREE))?;
+    let base = git::get_branch_base_in(branch, Some(worktree_path))
+        .unwrap_or_else(|_| context.main_branch.clone());
+    let diff = diff_against(worktree_path, &base);
+    if let Some(diff) = &diff {
+        fs::write(root.join(DIFF), diff).context("Failed to write archive diff")?;
+    }
+    let logs = super::setup::pane_log_dir(&context.main_worktree_root, handle)?;
+    if logs.is_dir() {
+        fs_extra::dir::copy(
+            &logs,
+            root.join(LOGS),
+            &fs_extra::dir::CopyOptions::new().content_only(true),
+        )
+        .context("Failed to copy pane transcripts")?;
+    }
+
+    let manifest = Manifest {
+        handle: handle.to_string(),
+        branch: branch.to_string(),
+        base: diff.is_some().then_some(base),
+        head: Cmd::new("git")
+            .args(&["rev-parse", "HEAD"])
+            .workdir(worktree_path)
+            .run_and_capture_stdout()
+            .ok(),
+        worktree_path: worktree_path.to_path_buf(),
+        created: now_secs(),
+        files,
+    };
+    fs::write(
+        root.join(MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )
+    .context("Failed to write archive manifest")?;
+
+    // Write under a temporary name, so a failed write never lists as an archive
+    let path = dir.join(file_name(handle, manifest.created));
+    let partial = path.with_extension("gz.partial");
+    let mut entries = vec![MANIFEST, WORKTREE];
+    entries.extend([DIFF, LOGS].into_iter().filter(|e| root.join(e).exists()));
+    let partial_arg = partial.to_string_lossy();
+    let mut args = vec!["-czf", partial_arg.as_ref()];
+    args.extend(&entries);
+    Cmd::new("tar")
+        .args(&args)
+        .workdir(root)
+        .run()
+        .context("Failed to write archive")?;
+    fs::rename(&partial, &path).context("Failed to write archive")?;
+    info!(handle, path = %path.display(), files, "archive:written");
+    Ok(path)
+}
+
+/// Copy the worktree's tracked and untracked (not ignored) files and its
+/// artifacts directory to `dest`. Returns the number of files copied.
+fn copy_worktree(worktree: &Path, dest: &Path) -> Result<usize> {
+    let output = Cmd::new("git")
+        .args(&[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .workdir(worktree)
+        .run()
+        .context("Failed to list worktree files")?;
+    let mut count = 0;
+    for rel in output.stdout.split(|b| *b == 0).filter(|p| !p.is_empty()) {
+        let rel = git_path(rel);
+        if copy_entry(&worktree.join(&rel), &dest.join(&rel))? {
+            count += 1;
+        }
+    }
+
+    // The artifacts directory is excluded from git, so ls-files skips it
+    let artifacts = worktree.join(ARTIFACTS_DIR);
+    if artifacts.is_dir() {
+        let mut pending = vec![artifacts];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir)?.flatten() {
+                let path = entry.path();
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    pending.push(path);
+                } else if let Ok(rel) = path.strip_prefix(worktree)
+                    && copy_entry(&path, &dest.join(rel))?
+                {
+                    count += 1;
+                }
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// A path from git's `-z` output. Paths are bytes to git, so names that
+/// aren't valid UTF-8 are kept as they are.
+#[cfg(unix)]
+fn git_path(raw: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(raw))
+}
+
+#[cfg(not(unix))]
+fn git_path(raw: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(raw).into_owned())
+}
+
+/// Copy a file, or recreate a symlink, at `dest`. Returns false for paths
+/// that are gone (deleted but still tracked) or aren't files.
+fn copy_entry(src: &Path, dest: &Path) -> Result<bool> {
+    let Ok(meta) = fs::symlink_metadata(src) else {
+        return Ok(false);
+    };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    #[cfg(unix)]
+    if meta.file_type().is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(src)?, dest)
+            .with_context(|| format!("Failed to copy '{}'", src.display()))?;
+        return Ok(true);
+    }
+    if !meta.is_file() {
+        return Ok(false);
+    }
+    fs::copy(src, dest).with_context(|| format!("Failed to copy '{}'", src.display()))?;
+    Ok(true)
+}
+
+/// Diff of the worktree, uncommitted changes included, against where the
+/// branch forked from `base`. `None` when git can't produce one.
+///
+/// Kept as raw bytes: text hunks needn't be UTF-8, and the patch must apply.
+fn diff_against(worktree: &Path, base: &str) -> Option<Vec<u8>> {
+    let fork = Cmd::new("git")
+        .args(&["merge-base", base, "HEAD"])
+        .workdir(worktree)
+        .run_and_capture_stdout();
+    let result = fork.and_then(|fork| {
+        Cmd::new("git")
+            .args(&["diff", "--binary", &fork])
+            .workdir(worktree)
+            .run()
+    });
+    match result {
+        Ok(output) => Some(output.stdout),
+        Err(e) => {
+            debug!(base, error = %e, "archive:no diff against base");
+            None
+        }
+    }
+}
+
+/// All archives in `dir`, newest first.
+pub fn list_archives(dir: &Path) -> Vec<WorktreeArchive> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut archives: Vec<WorktreeArchive> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (handle, created) = parse_file_name(path.file_name()?.to_str()?)?;
+            Some(WorktreeArchive {
+                handle,
+                created,
+                path,
+            })
+        })
+        .collect();
+    archives.sort_by(|a, b| b.created.cmp(&a.created).then(a.handle.cmp(&b.handle)));
+    archives
+}
+
+/// The archive `name` refers to: a path to an archive, an archive's file
+/// name in `dir`, or a handle (its newest archive).
+pub fn find_archive(dir: &Path, name: &str) -> Result<PathBuf> {
+    let path = Path::new(name);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    list_archives(dir)
+        .into_iter()
+        .find(|a| a.handle == name || a.path.file_name().is_some_and(|f| f == name))
+        .map(|a| a.path)
+        .ok_or_else(|| {
+            anyhow!(
+                "No archive found for '{}'. Run 'workmux archive list' to list archives.",
+                name
+            )
+        })
+}
+
+/// Read the manifest of the archive at `path`.
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let output = Cmd::new("tar")
+        .args(&["-xzOf", &path.to_string_lossy(), MANIFEST])
+        .run()
+        .with_context(|| format!("Failed to read '{}'", path.display()))?;
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Invalid manifest in '{}'", path.display()))
+}
+
+/// Unpack the archive at `path` into `dest`, which must not exist yet.
+pub fn extract(path: &Path, dest: &Path) -> Result<()> {
+    if dest.exists() {
+        bail!("'{}' already exists", dest.display());
+    }
+    fs::create_dir_all(dest).with_context(|| format!("Failed to create '{}'", dest.display()))?;
+    let result = Cmd::new("tar")
+        .args(&["-xzf", &path.to_string_lossy()])
+        .workdir(dest)
+        .run();
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(dest);
+        return Err(e).with_context(|| format!("Failed to extract '{}'", path.display()));
+    }
+    Ok(())
+}
+
+/// `<handle>@<created>.tar.gz`.
+fn file_name(handle: &str, created: u64) -> String {
+    format!("{}@{}{}", handle, created, EXTENSION)
+}
+
+fn parse_file_name(name: &str) -> Option<(String, u64)> {
+    let (handle, created) = name.strip_suffix(EXTENSION)?.rsplit_once('@')?;
+    Some((handle.to_string(), created.parse().ok()?))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Cmd::new("git").args(args).workdir(dir).run().unwrap();
+    }
+
+    #[test]
+    fn file_name_round_trips_handles() {
+        for handle in ["feature", "fix-login", "a@b"] {
+            let name = file_name(handle, 1_700_000_000);
+            assert_eq!(
+                parse_file_name(&name),
+                Some((handle.to_string(), 1_700_000_000))
+            );
+        }
+        assert_eq!(parse_file_name("feature@123.tar.gz.partial"), None);
+        assert_eq!(parse_file_name("feature.tar.gz"), None);
+    }
+
+    #[test]
+    fn worktree_copy_skips_ignored_files_but_keeps_artifacts() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        fs::create_dir_all(repo.join("src")).unwrap();
+        git(&repo, &["init", "-q"]);
+        fs::write(repo.join(".gitignore"), "target/\n").unwrap();
+        fs::write(repo.join("src/lib.rs"), "fn main() {}").unwrap();
+        git(&repo, &["add", "."]);
+        fs::write(repo.join("notes.txt"), "untracked").unwrap();
+        fs::create_dir_all(repo.join("target")).unwrap();
+        fs::write(repo.join("target/out"), "ignored").unwrap();
+        fs::create_dir_all(repo.join(".workmux/prompts")).unwrap();
+        fs::write(repo.join(".workmux/prompts/PROMPT-x.md"), "task").unwrap();
+        fs::write(repo.join(".git/info/exclude"), ".workmux/\n").unwrap();
+
+        let dest = temp.path().join("copy");
+        assert_eq!(copy_worktree(&repo, &dest).unwrap(), 4);
+        assert!(dest.join("src/lib.rs").is_file());
+        assert!(dest.join("notes.txt").is_file());
+        assert!(dest.join(".workmux/prompts/PROMPT-x.md").is_file());
+        assert!(!dest.join("target").exists());
+    }
+
+    // macOS filesystems reject file names that aren't UTF-8
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn archive_keeps_non_utf8_names_and_diffs() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "t"]);
+        git(&repo, &["config", "user.email", "t@t"]);
+        fs::write(repo.join("readme.txt"), b"caf\xe9\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+
+        let name = std::ffi::OsStr::from_bytes(b"r\xe9sum\xe9.txt");
+        fs::write(repo.join(name), "cv").unwrap();
+        fs::write(repo.join("readme.txt"), b"caf\xe9 cr\xe8me\n").unwrap();
+
+        let dest = temp.path().join("copy");
+        assert_eq!(copy_worktree(&repo, &dest).unwrap(), 2);
+        assert!(dest.join(name).is_file());
+
+        let diff = diff_against(&repo, "main").unwrap();
+        assert!(diff.windows(3).any(|w| w == b"\xe8me"));
+    }
+}
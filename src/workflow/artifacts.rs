@@ -0,0 +1,219 @@
+//! The `.workmux/` directory workmux manages in each worktree.
+//!
+//! Files workmux writes for a worktree (prompt files for its agents, files
+//! attached to their prompts) live here rather than loose in the checkout. The directory is listed in the
+//! repository's `.git/info/exclude`, so agents don't see it as untracked
+//! changes and commit it. Hooks and scripts can keep their own scratch files
+//! in it for the same reason.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::debug;
+
+/// Name of the artifacts directory at the root of each worktree.
+pub const ARTIFACTS_DIR: &str = ".workmux";
+
+/// Line excluding the artifacts directory.
+const EXCLUDE_ENTRY: &str = ".workmux/";
+
+/// Create the worktree's artifacts directory and exclude it from git.
+/// Returns its path.
+pub fn ensure_dir(worktree: &Path) -> Result<PathBuf> {
+    let dir = worktree.join(ARTIFACTS_DIR);
+    fs::create_dir_all(&dir).with_context(|| {
+        format!(
+            "Failed to create {} directory in '{}'",
+            ARTIFACTS_DIR,
+            worktree.display()
+        )
+    })?;
+    // Best effort: a failed exclude only leaves the directory visible
+    if let Some(exclude_path) = resolve_git_exclude_path(worktree)
+        && let Err(e) = ensure_excluded(&exclude_path)
+    {
+        debug!(path = %exclude_path.display(), error = %e, "artifacts:failed to update exclude");
+    }
+    Ok(dir)
+}
+
+/// Directory for prompt files, inside the artifacts directory.
+pub fn prompts_dir(worktree: &Path) -> Result<PathBuf> {
+    let dir = ensure_dir(worktree)?.join("prompts");
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create prompts directory '{}'", dir.display()))?;
+    Ok(dir)
+}
+
+/// Directory for files attached to prompts, inside the artifacts directory.
+pub fn attachments_dir(worktree: &Path) -> Result<PathBuf> {
+    let dir = ensure_dir(worktree)?.join("attachments");
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create attachments directory '{}'", dir.display()))?;
+    Ok(dir)
+}
+
+/// Copy `files` into the attachments directory of `worktree`. Returns their
+/// paths relative to the worktree. A file whose name is taken by a different
+/// attachment is copied under a numbered name instead.
+pub fn attach(worktree: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+    let dir = attachments_dir(worktree)?;
+    let mut attached = Vec::new();
+    for file in files {
+        let content = fs::read(file)
+            .with_context(|| format!("Failed to read attachment '{}'", file.display()))?;
+        let name = file
+            .file_name()
+            .with_context(|| format!("Invalid attachment path '{}'", file.display()))?;
+        let name = Path::new(name);
+        let mut target = dir.join(name);
+        let mut n = 1;
+        while target.exists() && fs::read(&target).ok().as_ref() != Some(&content) {
+            let mut numbered = name.file_stem().unwrap_or_default().to_os_string();
+            numbered.push(format!("-{}", n));
+            if let Some(ext) = name.extension() {
+                numbered.push(".");
+                numbered.push(ext);
+            }
+            target = dir.join(numbered);
+            n += 1;
+        }
+        fs::write(&target, &content)
+            .with_context(|| format!("Failed to write attachment '{}'", target.display()))?;
+        let relative = target.strip_prefix(worktree).unwrap_or(&target);
+        attached.push(relative.to_string_lossy().into_owned());
+    }
+    Ok(attached)
+}
+
+/// Add the artifacts directory to an exclude file, creating it if needed.
+fn ensure_excluded(exclude_path: &Path) -> std::io::Result<()> {
+    let content = match fs::read_to_string(exclude_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    if content.lines().any(|line| line.trim() == EXCLUDE_ENTRY) {
+        return Ok(());
+    }
+    if let Some(parent) = exclude_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(exclude_path)?;
+    let separator = if content.is_empty() || content.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    writeln!(file, "{separator}# workmux artifacts\n{EXCLUDE_ENTRY}")
+}
+
+/// Resolve the path to .git/info/exclude, handling worktrees correctly.
+/// In a worktree, .git is a file containing "gitdir: /path/to/.git/worktrees/name",
+/// so we need to find the actual git directory.
+fn resolve_git_exclude_path(dir: &Path) -> Option<PathBuf> {
+    let git_path = dir.join(".git");
+
+    if git_path.is_dir() {
+        // Regular git repo: .git is a directory
+        Some(git_path.join("info/exclude"))
+    } else if git_path.is_file() {
+        // Git worktree: .git is a file pointing to the real git dir
+        // Format: "gitdir: /path/to/main/.git/worktrees/name"
+        let content = fs::read_to_string(&git_path).ok()?;
+        let gitdir = content.strip_prefix("gitdir: ")?.trim();
+        // Go up two levels from worktrees/<name> to get to .git/
+        let main_git = Path::new(gitdir).ancestors().nth(2)?;
+        Some(main_git.join("info/exclude"))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn artifacts_dir_is_excluded_once() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        let worktree = temp.path().join("wt");
+        fs::create_dir_all(repo.join(".git/worktrees/wt")).unwrap();
+        fs::create_dir_all(&worktree).unwrap();
+        let gitdir = repo.join(".git/worktrees/wt");
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", gitdir.display()),
+        )
+        .unwrap();
+
+        // No info/exclude yet: it is created
+        let dir = ensure_dir(&worktree).unwrap();
+        assert_eq!(dir, worktree.join(".workmux"));
+        assert!(dir.is_dir());
+        let exclude = repo.join(".git/info/exclude");
+        assert_eq!(
+            fs::read_to_string(&exclude).unwrap(),
+            "# workmux artifacts\n.workmux/\n"
+        );
+
+        prompts_dir(&worktree).unwrap();
+        assert!(worktree.join(".workmux/prompts").is_dir());
+        assert_eq!(
+            fs::read_to_string(&exclude)
+                .unwrap()
+                .matches(".workmux/")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn attachments_are_copied_without_overwriting() {
+        let temp = TempDir::new().unwrap();
+        let worktree = temp.path().join("wt");
+        let a = temp.path().join("a");
+        let b = temp.path().join("b");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("spec.md"), "first").unwrap();
+        fs::write(b.join("spec.md"), "second").unwrap();
+
+        let attached = attach(&worktree, &[a.join("spec.md"), b.join("spec.md")]).unwrap();
+        assert_eq!(
+            attached,
+            vec![
+                ".workmux/attachments/spec.md",
+                ".workmux/attachments/spec-1.md"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(worktree.join(&attached[1])).unwrap(),
+            "second"
+        );
+
+        // Attaching the same file again reuses its copy
+        let again = attach(&worktree, &[b.join("spec.md")]).unwrap();
+        assert_eq!(again, vec![".workmux/attachments/spec-1.md"]);
+    }
+
+    #[test]
+    fn exclude_entry_is_appended_after_existing_patterns() {
+        let temp = TempDir::new().unwrap();
+        let exclude = temp.path().join("exclude");
+        fs::write(&exclude, "*.log").unwrap();
+        ensure_excluded(&exclude).unwrap();
+        assert_eq!(
+            fs::read_to_string(&exclude).unwrap(),
+            "*.log\n# workmux artifacts\n.workmux/\n"
+        );
+    }
+}
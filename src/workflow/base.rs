@@ -0,0 +1,93 @@
+//! Base branches: validation of `--base` revisions before a worktree is
+//! created, and the base an existing branch is compared against.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail};
+
+use crate::config::Config;
+use crate::progress::Reporter;
+use crate::util::closest_matches;
+use crate::vcs::Vcs;
+
+/// Make sure `base` resolves, fetching it first if it names a remote branch
+/// that hasn't been fetched yet.
+///
+/// Runs before anything is created so a typo fails fast with suggestions
+/// instead of leaving a half-created worktree behind.
+pub fn ensure_base_exists(vcs: &dyn Vcs, progress: &dyn Reporter, base: &str) -> Result<()> {
+    if vcs.revision_exists(base)? {
+        return Ok(());
+    }
+
+    if let Some((remote, branch)) = vcs.split_remote_ref(base)
+        && vcs.list_remotes()?.contains(&remote)
+    {
+        let msg = format!("Fetching '{}' from {}", branch, remote);
+        // A missing branch makes the fetch fail; fall through to suggestions
+        let fetched = progress
+            .step(&msg, || vcs.fetch_remote_branch(&remote, &branch))
+            .is_ok();
+        if fetched && vcs.revision_exists(base)? {
+            return Ok(());
+        }
+    }
+
+    let branches = vcs.list_base_branches().unwrap_or_default();
+    let suggestions = closest_matches(base, &branches, 3);
+    if suggestions.is_empty() {
+        bail!("Base '{}' not found", base);
+    }
+    bail!(
+        "Base '{}' not found. Did you mean:\n{}",
+        base,
+        suggestions
+            .iter()
+            .map(|s| format!("  {}", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
+/// Base `branch` is compared against: the base recorded when it was created,
+/// then the one `base_branches` configures for its name, then
+/// `default_branch`.
+pub fn branch_base(vcs: &dyn Vcs, config: &Config, branch: &str, default_branch: &str) -> String {
+    vcs.get_branch_base(branch)
+        .ok()
+        .or_else(|| config.base_for_branch(branch).map(str::to_string))
+        .unwrap_or_else(|| default_branch.to_string())
+}
+
+/// The `branches` that have commits not merged into their own base (see
+/// [`branch_base`]). Checks each distinct base once; branches whose base
+/// can't be resolved count as merged.
+pub fn unmerged_branches<'a>(
+    vcs: &dyn Vcs,
+    config: &Config,
+    branches: impl IntoIterator<Item = &'a str>,
+    default_branch: &str,
+) -> HashSet<String> {
+    let mut by_base: HashMap<String, Vec<&str>> = HashMap::new();
+    for branch in branches {
+        let base = branch_base(vcs, config, branch, default_branch);
+        by_base.entry(base).or_default().push(branch);
+    }
+
+    let mut unmerged = HashSet::new();
+    for (base, branches) in by_base {
+        let Ok(not_in_base) = vcs
+            .get_merge_base(&base)
+            .and_then(|merge_base| vcs.get_unmerged_branches(&merge_base))
+        else {
+            continue;
+        };
+        unmerged.extend(
+            branches
+                .into_iter()
+                .filter(|b| not_in_base.contains(*b))
+                .map(str::to_string),
+        );
+    }
+    unmerged
+}
@@ -0,0 +1,183 @@
+//! Bundle backups of branches deleted by cleanup.
+//!
+//! With `branch_backup.enabled`, cleanup writes the commits a branch has beyond
+//! the main branch to a bundle under `<state dir>/workmux/branch-backups/<project>/`
+//! before deleting it. `workmux restore-branch` brings the branch back, which
+//! covers force-removing a branch whose work turned out not to be merged.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use tracing::{debug, warn};
+
+use crate::config;
+use crate::state::store::get_state_dir;
+
+use super::context::WorkflowContext;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A bundle written before a branch was deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchBackup {
+    pub branch: String,
+    /// Unix seconds when the bundle was written
+    pub created: u64,
+    pub path: PathBuf,
+}
+
+/// Directory holding the bundles for the project at `main_worktree_root`.
+pub fn backup_dir(main_worktree_root: &Path) -> Result<PathBuf> {
+    Ok(get_state_dir()?
+        .join("workmux/branch-backups")
+        .join(config::project_name(main_worktree_root)))
+}
+
+/// Bundle the branch's commits that aren't on the main branch.
+///
+/// Prunes expired bundles first. Returns None if the branch had nothing the
+/// main branch doesn't already contain.
+pub fn backup_branch(context: &WorkflowContext, branch: &str) -> Result<Option<PathBuf>> {
+    let dir = backup_dir(&context.main_worktree_root)?;
+    fs::create_dir_all(&dir).context("Failed to create branch backup directory")?;
+    let now = now_secs();
+    prune(&dir, context.config.branch_backup.keep_days(), now);
+
+    let base = context
+        .vcs
+        .get_merge_base(&context.main_branch)
+        .unwrap_or_else(|_| context.main_branch.clone());
+    let path = dir.join(file_name(branch, now));
+    if context
+        .vcs
+        .bundle_branch(branch, &base, &path, &context.shared_dir)?
+    {
+        Ok(Some(path))
+    } else {
+        debug!(branch, "branch_backup:no commits beyond main, skipping");
+        Ok(None)
+    }
+}
+
+/// Recreate `branch` from its newest bundle.
+pub fn restore_branch(context: &WorkflowContext, branch: &str) -> Result<BranchBackup> {
+    if context.vcs.branch_exists(branch)? {
+        bail!("Branch '{}' already exists", branch);
+    }
+    let dir = backup_dir(&context.main_worktree_root)?;
+    let backup = list_backups(&dir)
+        .into_iter()
+        .find(|b| b.branch == branch)
+        .ok_or_else(|| {
+            anyhow!(
+                "No backup found for '{}'. Run 'workmux restore-branch' to list backups.",
+                branch
+            )
+        })?;
+    context
+        .vcs
+        .restore_branch_bundle(&backup.path, branch, &context.shared_dir)?;
+    Ok(backup)
+}
+
+/// All bundles in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Vec<BranchBackup> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BranchBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (branch, created) = parse_file_name(path.file_name()?.to_str()?)?;
+            Some(BranchBackup {
+                branch,
+                created,
+                path,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then(a.branch.cmp(&b.branch)));
+    backups
+}
+
+/// Delete bundles older than `keep_days`. Returns how many were removed.
+fn prune(dir: &Path, keep_days: u64, now: u64) -> usize {
+    let cutoff = now.saturating_sub(keep_days * SECS_PER_DAY);
+    let mut removed = 0;
+    for backup in list_backups(dir) {
+        if backup.created >= cutoff {
+            continue;
+        }
+        match fs::remove_file(&backup.path) {
+            Ok(()) => removed += 1,
+            Err(e) => {
+                warn!(path = %backup.path.display(), error = %e, "branch_backup:failed to prune")
+            }
+        }
+    }
+    removed
+}
+
+/// `<branch>@<created>.bundle`, with `/` in the branch name escaped.
+fn file_name(branch: &str, created: u64) -> String {
+    let escaped = branch.replace('%', "%25").replace('/', "%2F");
+    format!("{}@{}.bundle", escaped, created)
+}
+
+fn parse_file_name(name: &str) -> Option<(String, u64)> {
+    let (escaped, created) = name.strip_suffix(".bundle")?.rsplit_once('@')?;
+    let branch = escaped.replace("%2F", "/").replace("%25", "%");
+    Some((branch, created.parse().ok()?))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_round_trips_branch_names() {
+        for branch in ["feature", "user/fix-login", "odd%2Fname", "a@b"] {
+            let name = file_name(branch, 1_700_000_000);
+            assert!(!name.contains('/'));
+            assert_eq!(
+                parse_file_name(&name),
+                Some((branch.to_string(), 1_700_000_000))
+            );
+        }
+        assert_eq!(parse_file_name("notes.txt"), None);
+        assert_eq!(parse_file_name("feature@soon.bundle"), None);
+    }
+
+    #[test]
+    fn list_backups_newest_first_and_prune() {
+        let tmp = tempfile::tempdir().unwrap();
+        let day = SECS_PER_DAY;
+        for (branch, created) in [("old", 1), ("feature", 40 * day), ("feature", 45 * day)] {
+            fs::write(tmp.path().join(file_name(branch, created)), "").unwrap();
+        }
+        fs::write(tmp.path().join("README"), "").unwrap();
+
+        let backups = list_backups(tmp.path());
+        let order: Vec<(&str, u64)> = backups
+            .iter()
+            .map(|b| (b.branch.as_str(), b.created))
+            .collect();
+        assert_eq!(
+            order,
+            [("feature", 45 * day), ("feature", 40 * day), ("old", 1)]
+        );
+
+        assert_eq!(prune(tmp.path(), 30, 50 * day), 1);
+        assert_eq!(list_backups(tmp.path()).len(), 2);
+    }
+}
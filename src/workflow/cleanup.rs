@@ -10,6 +10,7 @@ use crate::shell::shell_quote;
 use crate::cmd;
 use tracing::{debug, info, warn};
 
+use super::branch_backup;
 use super::context::WorkflowContext;
 use super::types::{CleanupResult, DeferredCleanup};
 
@@ -113,6 +114,39 @@ pub fn cleanup(
     // is run from within the worktree being deleted.
     context.chdir_to_main_worktree()?;
 
+    // Back up the branch before anything can delete it. Fail closed: a backup
+    // that was asked for but couldn't be written shouldn't cost the branch.
+    // jj can't write bundles yet, so the backup is skipped there instead of
+    // blocking every merge and remove.
+    let backup_enabled = !keep_branch && context.config.branch_backup.enabled();
+    if backup_enabled && context.vcs.name() != "git" {
+        warn!(
+            branch = branch_name,
+            vcs = context.vcs.name(),
+            "cleanup:branch backup skipped"
+        );
+        context.progress.warn(format!(
+            "Branch backups aren't supported for {} repositories; deleting '{}' without one",
+            context.vcs.name(),
+            branch_name
+        ));
+    } else if backup_enabled {
+        let backup = branch_backup::backup_branch(context, branch_name).with_context(|| {
+            format!(
+                "Failed to back up branch '{}' before deleting it. \
+                 Set 'branch_backup.enabled: false' in config to skip the backup.",
+                branch_name
+            )
+        })?;
+        if let Some(path) = backup {
+            info!(branch = branch_name, path = %path.display(), "cleanup:branch backed up");
+            context.progress.info(format!(
+                "Saved a backup of '{}' (restore with: workmux restore-branch {})",
+                branch_name, branch_name
+            ));
+        }
+    }
+
     let mux_running = context.mux.is_running().unwrap_or(false);
 
     // Check if we're running inside ANY matching target (original or duplicate)
@@ -0,0 +1,128 @@
+//! Reconciling `workmux add` with a window that already has the target name.
+//!
+//! A window can outlive its worktree, e.g. when workmux or the terminal
+//! crashed halfway through `add` or `remove`. Before acting on the
+//! `on_collision` policy, the existing window is inspected to tell such
+//! leftovers apart from a window that is in use.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::MuxMode;
+use crate::multiplexer::LivePaneInfo;
+
+/// How an existing window relates to the worktree `add` is about to use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExistingTarget {
+    /// No live panes, or all of them are in directories that no longer exist
+    Stale,
+    /// Its panes are in the branch's worktree
+    Matches,
+    /// Its panes are in another directory
+    Elsewhere(PathBuf),
+}
+
+impl ExistingTarget {
+    /// Short explanation for error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Stale => {
+                "it looks stale: its panes are gone or in deleted directories".to_string()
+            }
+            Self::Matches => "it is open on this branch's worktree".to_string(),
+            Self::Elsewhere(path) => format!("its panes are in {}", path.display()),
+        }
+    }
+}
+
+/// Classify the window or session named `full_name` by where its panes are.
+///
+/// `worktree` is the branch's existing worktree, if it has one.
+pub fn inspect(
+    panes: &HashMap<String, LivePaneInfo>,
+    mode: MuxMode,
+    full_name: &str,
+    worktree: Option<&Path>,
+) -> ExistingTarget {
+    let mut dirs: Vec<&Path> = panes
+        .values()
+        .filter(|info| {
+            let name = match mode {
+                MuxMode::Window => info.window.as_deref(),
+                MuxMode::Session => info.session.as_deref(),
+            };
+            name == Some(full_name)
+        })
+        .map(|info| info.working_dir.as_path())
+        .filter(|dir| dir.exists())
+        .collect();
+    dirs.sort();
+
+    if let Some(worktree) = worktree
+        && dirs.iter().any(|dir| dir.starts_with(worktree))
+    {
+        return ExistingTarget::Matches;
+    }
+    match dirs.first() {
+        Some(dir) => ExistingTarget::Elsewhere(dir.to_path_buf()),
+        None => ExistingTarget::Stale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(window: &str, dir: &Path) -> LivePaneInfo {
+        LivePaneInfo {
+            pid: 1,
+            current_command: "zsh".to_string(),
+            working_dir: dir.to_path_buf(),
+            title: None,
+            session: Some("main".to_string()),
+            window: Some(window.to_string()),
+            process: None,
+        }
+    }
+
+    #[test]
+    fn classifies_window_by_pane_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        let worktree = tmp.path().join("feature");
+        let other = tmp.path().join("other");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+
+        let panes = HashMap::from([
+            ("%1".to_string(), pane("wm-feature", &worktree.join("src"))),
+            ("%2".to_string(), pane("wm-other", &other)),
+            ("%3".to_string(), pane("wm-gone", &tmp.path().join("gone"))),
+        ]);
+        let window = |name| inspect(&panes, MuxMode::Window, name, Some(&worktree));
+
+        assert_eq!(window("wm-feature"), ExistingTarget::Matches);
+        assert_eq!(window("wm-other"), ExistingTarget::Elsewhere(other.clone()));
+        assert_eq!(window("wm-gone"), ExistingTarget::Stale);
+        assert_eq!(window("wm-missing"), ExistingTarget::Stale);
+
+        // Without a worktree for the branch, a live window is never a match
+        assert_eq!(
+            inspect(&panes, MuxMode::Window, "wm-feature", None),
+            ExistingTarget::Elsewhere(worktree.join("src"))
+        );
+    }
+
+    #[test]
+    fn session_mode_matches_by_session_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let panes = HashMap::from([("%1".to_string(), pane("editor", tmp.path()))]);
+        assert_eq!(
+            inspect(&panes, MuxMode::Session, "main", Some(tmp.path())),
+            ExistingTarget::Matches
+        );
+        assert_eq!(
+            inspect(&panes, MuxMode::Session, "editor", Some(tmp.path())),
+            ExistingTarget::Stale
+        );
+    }
+}
@@ -2,15 +2,19 @@ use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::multiplexer::Multiplexer;
+use crate::config::{self, MuxMode};
+use crate::multiplexer::{Multiplexer, MuxHandle};
+use crate::progress::{self, Reporter};
+use crate::util::canon_or_self;
 use crate::vcs::{self, Vcs};
-use crate::config;
-use tracing::debug;
+use tracing::{debug, info};
 
 /// Shared context for workflow operations
 ///
 /// This struct centralizes pre-flight checks and holds essential data
-/// needed by workflow modules, reducing code duplication.
+/// needed by workflow modules, reducing code duplication. Workflows reach the
+/// repository, the multiplexer and the user only through it, so tests and
+/// library consumers can drive them with their own backends.
 pub struct WorkflowContext {
     pub main_worktree_root: PathBuf,
     pub shared_dir: PathBuf,
@@ -19,6 +23,8 @@ pub struct WorkflowContext {
     pub config: config::Config,
     pub mux: Arc<dyn Multiplexer>,
     pub vcs: Arc<dyn Vcs>,
+    /// Where workflows report progress (the terminal for the CLI)
+    pub progress: Arc<dyn Reporter>,
     /// Relative path from repo root to config directory.
     /// Empty if config is at repo root or using defaults.
     pub config_rel_dir: PathBuf,
@@ -28,7 +34,8 @@ pub struct WorkflowContext {
 }
 
 impl WorkflowContext {
-    /// Create a new workflow context
+    /// Create a new workflow context for the repository in the current
+    /// directory
     ///
     /// Performs the VCS repository check and gathers all commonly needed data.
     /// Does NOT check if multiplexer is running or change the current directory - those
@@ -39,7 +46,18 @@ impl WorkflowContext {
         config_location: Option<config::ConfigLocation>,
     ) -> Result<Self> {
         let vcs = vcs::detect_vcs()?;
+        Self::with_backends(config, mux, vcs, config_location)
+    }
 
+    /// Create a workflow context on the given backends, without detecting
+    /// anything. Progress goes to the terminal; replace `progress` to
+    /// capture it.
+    pub fn with_backends(
+        config: config::Config,
+        mux: Arc<dyn Multiplexer>,
+        vcs: Arc<dyn Vcs>,
+        config_location: Option<config::ConfigLocation>,
+    ) -> Result<Self> {
         let main_worktree_root =
             vcs.get_main_workspace_root().context("Could not find the main worktree")?;
 
@@ -52,7 +70,7 @@ impl WorkflowContext {
             vcs.get_default_branch().context("Failed to determine the main branch")?
         };
 
-        let prefix = config.window_prefix().to_string();
+        let prefix = config.window_prefix_for(vcs.as_ref());
 
         let (config_rel_dir, config_source_dir) = match config_location {
             Some(loc) => (loc.rel_dir, loc.config_dir),
@@ -79,6 +97,7 @@ impl WorkflowContext {
             config,
             mux,
             vcs,
+            progress: Arc::new(progress::Terminal),
             config_rel_dir,
             config_source_dir,
         })
@@ -115,4 +134,94 @@ impl WorkflowContext {
             )
         })
     }
+
+    /// Directory new worktrees are created in: `worktree_dir` from config, or
+    /// `<project>__worktrees` next to the main worktree.
+    ///
+    /// Always based on the main worktree root (not the current repo root) so
+    /// paths are the same when running from inside another worktree.
+    pub fn worktree_base_dir(&self) -> Result<PathBuf> {
+        if let Some(worktree_dir) = &self.config.worktree_dir {
+            // Relative paths are resolved from the main worktree root
+            return Ok(self.main_worktree_root.join(worktree_dir));
+        }
+        let project_name = self
+            .main_worktree_root
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Could not determine project name"))?;
+        Ok(self
+            .main_worktree_root
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine parent directory"))?
+            .join(format!("{}__worktrees", project_name)))
+    }
+
+    /// Canonical roots of the main worktree and every linked worktree.
+    pub fn project_roots(&self) -> Vec<PathBuf> {
+        let mut roots = vec![canon_or_self(&self.main_worktree_root)];
+        if let Ok(workspaces) = self.vcs.list_workspaces() {
+            roots.extend(workspaces.iter().map(|(path, _)| canon_or_self(path)));
+        }
+        roots
+    }
+
+    /// Qualify the window prefix with the project name when the window (or
+    /// session) name for `handle` is already taken by another project.
+    ///
+    /// A target belongs to another project when none of its panes are inside
+    /// this project's worktrees. The qualification is recorded in repository
+    /// metadata so later commands resolve the same names (see
+    /// `Config::window_prefix_for`). Returns the colliding name if qualified.
+    pub fn qualify_prefix_on_collision(
+        &mut self,
+        handle: &str,
+        mode: MuxMode,
+    ) -> Result<Option<String>> {
+        let project = config::project_name(&self.main_worktree_root);
+        if config::unqualified_prefix(&self.prefix, &project).is_some()
+            || !self.mux.is_running().unwrap_or(false)
+        {
+            return Ok(None);
+        }
+
+        let target = MuxHandle::new(self.mux.as_ref(), mode, &self.prefix, handle);
+        if !target.exists()? {
+            return Ok(None);
+        }
+
+        let full_name = target.full_name();
+        let roots = self.project_roots();
+        let owner_dirs: Vec<PathBuf> = self
+            .mux
+            .get_all_live_pane_info()?
+            .into_values()
+            .filter(|info| {
+                let owner = match mode {
+                    MuxMode::Session => info.session.as_deref(),
+                    MuxMode::Window => info.window.as_deref(),
+                };
+                owner == Some(full_name.as_str())
+            })
+            .map(|info| canon_or_self(&info.working_dir))
+            .collect();
+        let foreign = !owner_dirs.is_empty()
+            && !owner_dirs
+                .iter()
+                .any(|dir| roots.iter().any(|root| dir.starts_with(root)));
+        if !foreign {
+            return Ok(None);
+        }
+
+        let qualified = config::qualified_prefix(&self.prefix, &project);
+        info!(
+            collision = %full_name,
+            prefix = %qualified,
+            "workflow_context:qualifying window prefix"
+        );
+        self.vcs
+            .set_repo_meta(config::QUALIFY_PREFIX_META, "true")?;
+        self.prefix = qualified;
+        Ok(Some(full_name))
+    }
 }
@@ -1,9 +1,13 @@
 use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 
-use crate::config::MuxMode;
+use crate::config::{MuxMode, WindowCollision};
+use crate::filesystem::{self, FsKind};
+use crate::git;
 use crate::multiplexer::MuxHandle;
-use crate::{git, spinner};
+use crate::plugin::{self, PluginEvent};
+use crate::progress::Reporter;
+use crate::prompt::Guardrails;
 use crate::vcs::Vcs;
 use tracing::{debug, info, warn};
 
@@ -32,11 +36,32 @@ fn is_registered_workspace(vcs: &dyn Vcs, path: &Path) -> Result<bool> {
 }
 
 use super::cleanup;
+use super::collision::{self, ExistingTarget};
 use super::context::WorkflowContext;
 use super::setup;
 use super::types::{CreateArgs, CreateResult, SetupOptions};
 
 /// Create a new worktree with tmux window and panes
+/// Warn when worktrees are created on a filesystem that makes them slow or
+/// broken.
+fn warn_unsupported_filesystem(progress: &dyn Reporter, base_dir: &Path) {
+    match filesystem::detect(base_dir) {
+        Some(FsKind::Network(name)) => progress.warn(format!(
+            "worktree directory {} is on a network filesystem ({}); status checks \
+             will be slower and the dashboard refreshes its git status less often",
+            base_dir.display(),
+            name
+        )),
+        Some(FsKind::Unsupported(name)) => progress.warn(format!(
+            "worktree directory {} is on a {} filesystem, which lacks symlinks and \
+             executable permissions; git hooks and symlinked files may not work",
+            base_dir.display(),
+            name
+        )),
+        Some(FsKind::Local) | None => {}
+    }
+}
+
 pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResult> {
     let CreateArgs {
         branch_name,
@@ -46,6 +71,7 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         prompt,
         options,
         agent,
+        apply,
     } = args;
 
     info!(
@@ -113,20 +139,134 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
             working_dir: options.working_dir.clone(),
             config_root: options.config_root.clone(),
             open_if_exists: false,
+            on_collision: WindowCollision::Fail,
             mode: options.mode,
+            vm_boot: None,
+            guardrails: options.guardrails,
+            attachments: options.attachments.clone(),
         };
 
         return super::open::open(branch_name, context, open_options, false);
     }
 
-    // Check target using handle (the display name)
+    // Reconcile a window or session that already has the target name (the
+    // display name), e.g. one left behind by a crash
+    let mut window_handle = handle.to_string();
+    let mut after_window = None;
     if target_exists {
-        return Err(anyhow!(
-            "A {} {} named '{}' already exists",
-            context.mux.name(),
-            target.kind(),
-            full_target_name
-        ));
+        let branch_worktree = context
+            .vcs
+            .list_workspaces()?
+            .into_iter()
+            .find(|(_, branch)| branch == branch_name)
+            .map(|(path, _)| path);
+        let panes = context.mux.get_all_live_pane_info()?;
+        let existing = collision::inspect(
+            &panes,
+            options.mode,
+            &full_target_name,
+            branch_worktree.as_deref(),
+        );
+        debug!(
+            handle = handle,
+            existing = ?existing,
+            policy = ?options.on_collision,
+            "create:target already exists"
+        );
+
+        match options.on_collision {
+            WindowCollision::Fail => {
+                return Err(anyhow!(
+                    "A {} {} named '{}' already exists ({}).\n\
+                     Hint: Use --on-collision reuse, replace or suffix, or --name to pick another name.",
+                    context.mux.name(),
+                    target.kind(),
+                    full_target_name,
+                    existing.describe()
+                ));
+            }
+            WindowCollision::Reuse => {
+                let Some(worktree_path) =
+                    branch_worktree.filter(|_| existing == ExistingTarget::Matches)
+                else {
+                    return Err(anyhow!(
+                        "Cannot reuse the {} '{}': {}",
+                        target.kind(),
+                        full_target_name,
+                        existing.describe()
+                    ));
+                };
+                if options.focus_window {
+                    target.select()?;
+                }
+                info!(
+                    branch = branch_name,
+                    handle = handle,
+                    kind = target.kind(),
+                    "create:reused existing target"
+                );
+                return Ok(CreateResult {
+                    worktree_path,
+                    branch_name: branch_name.to_string(),
+                    handle: handle.to_string(),
+                    post_create_hooks_run: 0,
+                    base_branch: None,
+                    did_switch: true,
+                    focus_pane_id: None,
+                    sandbox_vm: None,
+                });
+            }
+            WindowCollision::Replace => {
+                if let ExistingTarget::Elsewhere(_) = existing {
+                    return Err(anyhow!(
+                        "Refusing to replace the {} '{}': {}.\n\
+                         Close it first, or use --on-collision suffix.",
+                        target.kind(),
+                        full_target_name,
+                        existing.describe()
+                    ));
+                }
+                if target.current_name()?.as_deref() == Some(full_target_name.as_str()) {
+                    return Err(anyhow!(
+                        "Cannot replace the {} '{}' from inside it",
+                        target.kind(),
+                        full_target_name
+                    ));
+                }
+                MuxHandle::kill_full(context.mux.as_ref(), options.mode, &full_target_name)?;
+                info!(
+                    handle = handle,
+                    kind = target.kind(),
+                    "create:closed existing target"
+                );
+            }
+            WindowCollision::Suffix => {
+                if target.is_session() {
+                    return Err(anyhow!(
+                        "--on-collision suffix is not supported in session mode. \
+                         Each worktree can only have one session."
+                    ));
+                }
+                window_handle = super::open::resolve_unique_handle(context, handle)?;
+                after_window = context
+                    .mux
+                    .find_last_window_with_base_handle(&context.prefix, handle)
+                    .unwrap_or(None);
+            }
+        }
+
+        // The branch already has a worktree: open a window on it rather than
+        // failing below
+        if worktree_exists {
+            let new_window = options.on_collision == WindowCollision::Suffix;
+            let open_options = SetupOptions {
+                run_hooks: false,
+                run_file_ops: false,
+                open_if_exists: false,
+                ..options
+            };
+            return super::open::open(branch_name, context, open_options, new_window);
+        }
     }
 
     // Check if branch already has a worktree
@@ -165,10 +305,12 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         }
         let vcs = context.vcs.clone();
         let remote_name = spec.remote.clone();
-        spinner::with_spinner(&format!("Fetching from '{}'", spec.remote), move || {
-            vcs.fetch_remote(&remote_name)
-        })
-        .with_context(|| format!("Failed to fetch from remote '{}'", spec.remote))?;
+        context
+            .progress
+            .step(&format!("Fetching from '{}'", spec.remote), move || {
+                vcs.fetch_remote(&remote_name)
+            })
+            .with_context(|| format!("Failed to fetch from remote '{}'", spec.remote))?;
         let remote_ref = format!("{}/{}", spec.remote, spec.branch);
         if !context.vcs.branch_exists(&remote_ref)? {
             return Err(anyhow!(
@@ -182,6 +324,10 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         if let Some(base) = base_branch {
             // Use the explicitly provided base branch/commit/tag
             Some(base.to_string())
+        } else if let Some(base) = context.config.base_for_branch(branch_name) {
+            // A `base_branches` rule matches the branch name
+            super::ensure_base_exists(context.vcs.as_ref(), context.progress.as_ref(), base)?;
+            Some(base.to_string())
         } else {
             // Default to the current branch when no explicit base was provided
             let current_branch = context.vcs.get_current_branch()
@@ -201,31 +347,9 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         None
     };
 
-    // Determine worktree path: use config.worktree_dir or default to <project>__worktrees pattern
-    // Always use main_worktree_root (not repo_root) to ensure consistent paths even when
-    // running from inside an existing worktree.
-    let base_dir = if let Some(ref worktree_dir) = context.config.worktree_dir {
-        let path = Path::new(worktree_dir);
-        if path.is_absolute() {
-            // Use absolute path as-is
-            path.to_path_buf()
-        } else {
-            // Relative path: resolve from main worktree root
-            context.main_worktree_root.join(path)
-        }
-    } else {
-        // Default behavior: <main_worktree_root>/../<project_name>__worktrees
-        let project_name = context
-            .main_worktree_root
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| anyhow!("Could not determine project name"))?;
-        context
-            .main_worktree_root
-            .parent()
-            .ok_or_else(|| anyhow!("Could not determine parent directory"))?
-            .join(format!("{}__worktrees", project_name))
-    };
+    let base_dir = context.worktree_base_dir()?;
+    warn_unsupported_filesystem(context.progress.as_ref(), &base_dir);
+
     // Use handle for the worktree directory name (not branch_name)
     let worktree_path = base_dir.join(handle);
 
@@ -270,6 +394,10 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         })?;
     }
 
+    // Boot the sandbox VM while the worktree is created and its hooks run;
+    // only the panes that run in the VM wait for it
+    let vm_boot = setup::start_vm_boot(context, &options, agent)?;
+
     // Create worktree
     info!(
         branch = branch_name,
@@ -288,6 +416,9 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
     )
     .context("Failed to create workspace")?;
 
+    // Keep workmux's own files out of git status from the start
+    super::artifacts::ensure_dir(&worktree_path)?;
+
     // Store the base branch in git config for future reference (used during removal checks)
     if let Some(ref base) = base_branch_for_creation {
         context.vcs.set_branch_base(branch_name, base).with_context(|| {
@@ -315,12 +446,50 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         );
     }
 
+    // Apply the patch before hooks and the agent see the worktree
+    if let Some(patch) = apply {
+        info!(patch = %patch.display(), "create:applying patch");
+        if let Err(e) = context.vcs.apply_patch(&worktree_path, patch) {
+            warn!(error = %e, "create:patch failed, removing worktree");
+            if let Err(cleanup_err) = cleanup::cleanup(
+                context,
+                branch_name,
+                handle,
+                &worktree_path,
+                true,        // force
+                !create_new, // keep_branch: only delete a branch we created
+                true,        // no_hooks: nothing was set up yet
+            ) {
+                warn!(error = %cleanup_err, "create:cleanup after failed patch failed");
+            }
+            return Err(e.context(format!(
+                "Failed to apply '{}'. The new worktree has been removed.",
+                patch.display()
+            )));
+        }
+        context
+            .progress
+            .info(format!("Applied '{}'", patch.display()));
+    }
+
     // Setup the rest of the environment (tmux, files, hooks)
     let prompt_file_path = if let Some(p) = prompt {
+        let guardrails = if options.guardrails {
+            Guardrails::from_config(&context.config)
+        } else {
+            Guardrails::default()
+        };
+        let p = setup::attach_files(
+            &worktree_path,
+            p,
+            &options.attachments,
+            context.config.agent.as_deref(),
+        )?;
         Some(setup::write_prompt_file(
             Some(&worktree_path),
             branch_name,
-            p,
+            &p,
+            guardrails,
         )?)
     } else {
         None
@@ -355,17 +524,17 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         prompt_file_path,
         working_dir,
         config_root,
+        vm_boot,
         ..options
     };
     let mut result = setup::setup_environment(
-        context.mux.as_ref(),
+        context,
         branch_name,
-        handle,
+        &window_handle,
         &worktree_path,
-        &context.config,
         &options_with_prompt,
         agent,
-        None,
+        after_window,
     )?;
     result.base_branch = base_branch_for_creation.clone();
     info!(
@@ -374,6 +543,14 @@ pub fn create(context: &WorkflowContext, args: CreateArgs) -> Result<CreateResul
         hooks_run = result.post_create_hooks_run,
         "create:completed"
     );
+    let event = PluginEvent::WorktreeCreated {
+        handle: handle.to_string(),
+        branch: branch_name.to_string(),
+        worktree_path: result.worktree_path.clone(),
+    };
+    if let Err(e) = plugin::dispatch(&event, context.vcs.as_ref()) {
+        warn!(error = %e, "create:plugin dispatch failed");
+    }
     Ok(result)
 }
 
@@ -434,6 +611,7 @@ pub fn create_with_changes(
             prompt: None,
             options,
             agent: None,
+            apply: None,
         },
     ) {
         Ok(result) => result,
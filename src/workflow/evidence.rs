@@ -0,0 +1,93 @@
+//! Agent pane output saved as evidence for a merge (test runs, builds).
+//!
+//! The dashboard appends blocks to `.workmux/evidence.md` in the worktree;
+//! `workmux merge --squash` adds them to the commit message template.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::artifacts;
+
+/// File the evidence is collected in, inside the artifacts directory.
+const EVIDENCE_FILE: &str = "evidence.md";
+
+pub fn path(worktree: &Path) -> PathBuf {
+    worktree.join(artifacts::ARTIFACTS_DIR).join(EVIDENCE_FILE)
+}
+
+/// Block for the last `lines` non-blank-trailing lines of `output`, or
+/// `None` when there is nothing to save. Lines are indented so they read as
+/// a code block in Markdown and aren't taken for comments (`#`) by git.
+pub fn format_block(source: &str, output: &str, lines: usize) -> Option<String> {
+    let all: Vec<&str> = output.lines().map(str::trim_end).collect();
+    let end = all.iter().rposition(|l| !l.is_empty())? + 1;
+    let start = end.saturating_sub(lines);
+    let body: Vec<String> = all[start..end]
+        .iter()
+        .map(|l| {
+            if l.is_empty() {
+                String::new()
+            } else {
+                format!("    {}", l)
+            }
+        })
+        .collect();
+    Some(format!(
+        "Output of {} (last {} lines):\n\n{}\n",
+        source,
+        end - start,
+        body.join("\n")
+    ))
+}
+
+/// Append a block to the worktree's evidence file.
+pub fn append(worktree: &Path, block: &str) -> Result<()> {
+    artifacts::ensure_dir(worktree)?;
+    let path = path(worktree);
+    let separator = if path.metadata().is_ok_and(|m| m.len() > 0) {
+        "\n"
+    } else {
+        ""
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open '{}'", path.display()))?;
+    write!(file, "{}{}", separator, block)
+        .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// The evidence collected in a worktree, if any.
+pub fn read(worktree: &Path) -> Option<String> {
+    std::fs::read_to_string(path(worktree))
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_keeps_the_last_lines_indented() {
+        let output = "old\n# cargo test\ntest a ... ok\n\ntest result: ok   \n\n\n";
+        assert_eq!(
+            format_block("wm-feature", output, 4).unwrap(),
+            "Output of wm-feature (last 4 lines):\n\n    \
+             # cargo test\n    test a ... ok\n\n    test result: ok\n"
+        );
+        assert_eq!(format_block("wm-feature", " \n\n", 4), None);
+    }
+
+    #[test]
+    fn blocks_accumulate() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(read(temp.path()), None);
+        append(temp.path(), "first\n").unwrap();
+        append(temp.path(), "second\n").unwrap();
+        assert_eq!(read(temp.path()).unwrap(), "first\n\nsecond\n");
+    }
+}
@@ -0,0 +1,202 @@
+//! Named groups of worktrees that are opened, closed and monitored together.
+//!
+//! Groups come from the `groups` config option and from `workmux group add`,
+//! which stores them in repository metadata. A group defined in both places
+//! has the members of both.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+
+use crate::config::Config;
+use crate::vcs::Vcs;
+
+/// Repository metadata key holding groups added with `workmux group add`,
+/// as a JSON object of group name to member handles.
+pub const GROUPS_META_KEY: &str = "groups";
+
+/// A named set of worktree handles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub members: Vec<String>,
+    /// Whether the group is (also) defined in config, so it can't be
+    /// removed with `workmux group remove`
+    pub in_config: bool,
+}
+
+/// A group member with the worktree it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub handle: String,
+    /// None if no worktree exists for the handle
+    pub path: Option<PathBuf>,
+}
+
+fn stored_groups(vcs: &dyn Vcs) -> BTreeMap<String, Vec<String>> {
+    vcs.get_repo_meta(GROUPS_META_KEY)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_groups(vcs: &dyn Vcs, groups: &BTreeMap<String, Vec<String>>) -> Result<()> {
+    vcs.set_repo_meta(GROUPS_META_KEY, &serde_json::to_string(groups)?)
+}
+
+/// Combine groups from config with stored ones.
+fn combine(
+    configured: Option<&BTreeMap<String, Vec<String>>>,
+    stored: BTreeMap<String, Vec<String>>,
+) -> Vec<Group> {
+    let mut groups: BTreeMap<String, Group> = configured
+        .into_iter()
+        .flatten()
+        .map(|(name, members)| {
+            let group = Group {
+                name: name.clone(),
+                members: members.clone(),
+                in_config: true,
+            };
+            (name.clone(), group)
+        })
+        .collect();
+    for (name, members) in stored {
+        let group = groups.entry(name.clone()).or_insert_with(|| Group {
+            name,
+            members: Vec::new(),
+            in_config: false,
+        });
+        for member in members {
+            if !group.members.contains(&member) {
+                group.members.push(member);
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// All groups of the current repository, sorted by name.
+pub fn list(config: &Config, vcs: &dyn Vcs) -> Vec<Group> {
+    combine(config.groups.as_ref(), stored_groups(vcs))
+}
+
+/// Look up a group by name.
+pub fn find(config: &Config, vcs: &dyn Vcs, name: &str) -> Result<Group> {
+    let groups = list(config, vcs);
+    if let Some(group) = groups.iter().find(|g| g.name == name) {
+        return Ok(group.clone());
+    }
+    if groups.is_empty() {
+        bail!(
+            "Unknown group '{}': no groups are defined. Create one with 'workmux group add'",
+            name
+        );
+    }
+    let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+    bail!(
+        "Unknown group '{}'. Available groups: {}",
+        name,
+        names.join(", ")
+    )
+}
+
+/// Add members to a stored group, creating it if needed. Returns the
+/// handles that weren't members yet.
+pub fn add_members(vcs: &dyn Vcs, name: &str, handles: &[String]) -> Result<Vec<String>> {
+    let mut groups = stored_groups(vcs);
+    let members = groups.entry(name.to_string()).or_default();
+    let mut added = Vec::new();
+    for handle in handles {
+        if !members.contains(handle) {
+            members.push(handle.clone());
+            added.push(handle.clone());
+        }
+    }
+    save_groups(vcs, &groups)?;
+    Ok(added)
+}
+
+/// Remove members from a stored group, or the whole group if `handles` is
+/// empty. Groups left without members are deleted.
+pub fn remove_members(
+    config: &Config,
+    vcs: &dyn Vcs,
+    name: &str,
+    handles: &[String],
+) -> Result<()> {
+    let mut groups = stored_groups(vcs);
+    let configured = config.groups.as_ref().and_then(|groups| groups.get(name));
+
+    if let Some(members) = configured {
+        if handles.is_empty() {
+            bail!(
+                "Group '{}' is defined in config; remove it from the 'groups' option",
+                name
+            );
+        }
+        if let Some(handle) = handles.iter().find(|h| members.contains(h)) {
+            bail!(
+                "'{}' is a member of group '{}' in config; remove it from the 'groups' option",
+                handle,
+                name
+            );
+        }
+    }
+
+    let Some(members) = groups.get_mut(name) else {
+        if configured.is_some() {
+            bail!("None of these are members of group '{}'", name);
+        }
+        bail!("Unknown group '{}'", name);
+    };
+    if let Some(missing) = handles.iter().find(|h| !members.contains(h)) {
+        bail!("'{}' is not a member of group '{}'", missing, name);
+    }
+    members.retain(|m| !handles.contains(m));
+    if handles.is_empty() || members.is_empty() {
+        groups.remove(name);
+    }
+    save_groups(vcs, &groups)
+}
+
+/// Resolve each member of a group to its worktree.
+pub fn resolve_members(vcs: &dyn Vcs, group: &Group) -> Vec<Member> {
+    group
+        .members
+        .iter()
+        .map(|handle| Member {
+            handle: handle.clone(),
+            path: vcs.find_workspace(handle).ok().map(|(path, _)| path),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, members)| {
+                let members = members.iter().map(|m| m.to_string()).collect();
+                (name.to_string(), members)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn config_and_stored_groups_are_combined() {
+        let configured = map(&[("stack", &["frontend", "backend"])]);
+        let stored = map(&[("stack", &["backend", "infra"]), ("docs", &["site"])]);
+        let groups = combine(Some(&configured), stored);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "docs");
+        assert!(!groups[0].in_config);
+        assert_eq!(groups[1].name, "stack");
+        assert_eq!(groups[1].members, ["frontend", "backend", "infra"]);
+        assert!(groups[1].in_config);
+    }
+}
@@ -3,11 +3,12 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 
 use crate::config::MuxMode;
-use crate::multiplexer::{Multiplexer, util};
+use crate::forge::Forge;
+use crate::multiplexer::{AgentStatus, Multiplexer, util};
 use crate::state::StateStore;
 use crate::util::canon_or_self;
 use crate::vcs::Vcs;
-use crate::{config, github, spinner};
+use crate::{config, progress};
 
 use super::types::{AgentStatusSummary, WorktreeInfo};
 
@@ -92,32 +93,43 @@ pub fn list(
     // Get the main branch for unmerged checks
     let main_branch = vcs.get_default_branch().ok();
 
-    // Get all unmerged branches in one go for efficiency
-    // Prefer checking against remote tracking branch for more accurate results
-    let unmerged_branches = main_branch
-        .as_deref()
-        .and_then(|main| vcs.get_merge_base(main).ok())
-        .and_then(|base| vcs.get_unmerged_branches(&base).ok())
-        .unwrap_or_default(); // Use an empty set on failure
+    // Get all unmerged branches in one go for efficiency, checking each
+    // branch against its own base (empty on failure)
+    let unmerged_branches = match main_branch.as_deref() {
+        Some(main) => super::unmerged_branches(
+            vcs,
+            config,
+            worktrees_data
+                .iter()
+                .map(|(_, branch)| branch.as_str())
+                .filter(|branch| *branch != main && *branch != "(detached)"),
+            main,
+        ),
+        None => HashSet::new(),
+    };
 
     // Batch fetch all PRs if requested (single API call)
     let pr_map = if fetch_pr_status {
-        spinner::with_spinner("Fetching PR status", || {
-            Ok(github::list_prs().unwrap_or_default())
+        progress::step("Fetching PR status", || {
+            Ok(Forge::current().list_prs().unwrap_or_default())
         })?
     } else {
         std::collections::HashMap::new()
     };
 
+    let locked = vcs.list_locked_workspaces();
+
     // Load reconciled agent states (only if multiplexer is running)
+    let store = StateStore::new().ok();
     let agent_panes = if mux_running {
-        StateStore::new()
-            .ok()
+        store
+            .as_ref()
             .and_then(|store| store.load_reconciled_agents(mux).ok())
             .unwrap_or_default()
     } else {
         Vec::new()
     };
+    let instance = mux.instance_id();
 
     // Pre-calculate canonical paths for agents to avoid repeated syscalls
     let agent_panes_canon: Vec<_> = agent_panes
@@ -128,7 +140,7 @@ pub fn list(
     // Batch-load all workspace modes in a single VCS config call
     let worktree_modes = vcs.get_all_workspace_modes();
 
-    let prefix = config.window_prefix();
+    let prefix = &config.window_prefix_for(vcs);
     let worktrees: Vec<WorktreeInfo> = worktrees_data
         .into_iter()
         .map(|(path, branch)| {
@@ -180,11 +192,29 @@ pub fn list(
             let agent_status = if matching_statuses.is_empty() {
                 None
             } else {
+                // Session mode: prefer the stored rollup unless agents have come
+                // or gone since it was written, then recompute from live state.
+                let session_status = (mode == MuxMode::Session).then(|| {
+                    store
+                        .as_ref()
+                        .and_then(|store| {
+                            store
+                                .get_session(mux.name(), &instance, &prefixed_name)
+                                .ok()
+                                .flatten()
+                        })
+                        .filter(|s| s.agent_count == matching_statuses.len())
+                        .and_then(|s| s.status)
+                        .or_else(|| AgentStatus::rollup(matching_statuses.iter().copied()))
+                });
                 Some(AgentStatusSummary {
                     statuses: matching_statuses,
+                    session_status: session_status.flatten(),
                 })
             };
 
+            let locked = locked.get(&path).cloned();
+
             WorktreeInfo {
                 branch,
                 path,
@@ -192,6 +222,7 @@ pub fn list(
                 has_unmerged,
                 pr_info,
                 agent_status,
+                locked,
             }
         })
         .collect();
@@ -1,11 +1,19 @@
+use std::path::Path;
+
 use anyhow::{Context, Result, anyhow};
 
 use crate::cmd;
-use tracing::{debug, info};
+use crate::config::{ConflictStrategy, GitHookOp};
+use crate::git::GitHookFailed;
+use crate::multiplexer::{HandshakeSettings, handshake};
+use crate::plugin::{self, PluginEvent};
+use crate::state::{MergeCheckpoint, MergeStep};
+use tracing::{debug, info, warn};
 
 use super::cleanup;
 use super::context::WorkflowContext;
-use super::types::MergeResult;
+use super::merge_checkpoint::{self, Checkpoint};
+use super::types::{MergeRemoteSync, MergeResult, UpstreamResult};
 
 /// Merge a branch into the target branch and clean up
 #[allow(clippy::too_many_arguments)]
@@ -19,6 +27,9 @@ pub fn merge(
     no_verify: bool,
     no_hooks: bool,
     notification: bool,
+    remote_sync: MergeRemoteSync,
+    on_conflict: ConflictStrategy,
+    resume: bool,
     context: &WorkflowContext,
 ) -> Result<MergeResult> {
     info!(
@@ -30,6 +41,9 @@ pub fn merge(
         keep,
         no_verify,
         no_hooks,
+        ?remote_sync,
+        ?on_conflict,
+        resume,
         "merge:start"
     );
 
@@ -37,6 +51,32 @@ pub fn merge(
     // the worktree that is about to be deleted.
     context.chdir_to_main_worktree()?;
 
+    // A merge that died midway must be resumed (or aborted) explicitly
+    let resumed = match merge_checkpoint::find_interrupted(context, name)? {
+        Some(cp) if resume => Some(cp),
+        Some(cp) => return Err(merge_checkpoint::interrupted_error(&cp)),
+        None if resume => return Err(anyhow!("No interrupted merge of '{}' to resume", name)),
+        None => None,
+    };
+
+    // Cleanup already removed the worktree; nothing is left to do
+    if let Some(cp) = &resumed
+        && cp.step >= MergeStep::Merged
+        && !cp.worktree.exists()
+    {
+        merge_checkpoint::clear(cp)?;
+        context.progress.info(format!(
+            "The worktree of '{}' is already removed; nothing left to resume",
+            cp.branch
+        ));
+        return Ok(MergeResult {
+            branch_merged: cp.branch.clone(),
+            main_branch: cp.target.clone(),
+            had_staged_changes: false,
+            worktree_path: cp.worktree.clone(),
+        });
+    }
+
     // Smart resolution: try handle first, then branch name
     let (worktree_path, branch_to_merge) = context.vcs.find_workspace(name)
         .with_context(|| format!("No workspace found with name '{}'", name))?;
@@ -55,6 +95,10 @@ pub fn merge(
     // Capture mode BEFORE cleanup (cleanup removes the metadata)
     let mode = context.vcs.get_workspace_mode(handle);
 
+    // Repository git hooks configured to be bypassed with --no-verify
+    let skip_commit_hooks = context.config.git_hooks.skips(GitHookOp::Commit);
+    let skip_merge_hooks = context.config.git_hooks.skips(GitHookOp::Merge);
+
     debug!(
         name = name,
         handle = handle,
@@ -66,7 +110,8 @@ pub fn merge(
     // Determine the target branch:
     // 1. Use explicit --into if provided
     // 2. Otherwise, check if branch has a stored base (from workmux add)
-    // 3. Fall back to main_branch
+    // 3. Then a `base_branches` rule matching the branch name
+    // 4. Fall back to main_branch
     let detected_base: Option<String> = if into_branch.is_some() {
         None // User explicitly specified target, no auto-detection needed
     } else {
@@ -89,18 +134,30 @@ pub fn merge(
                     None
                 }
             }
-            Err(_) => {
-                debug!(
-                    branch = %branch_to_merge,
-                    "merge:no base config found, defaulting to main"
-                );
-                None
-            }
+            Err(_) => match context.config.base_for_branch(&branch_to_merge) {
+                Some(base) if context.vcs.branch_exists(base)? => {
+                    info!(
+                        branch = %branch_to_merge,
+                        base = %base,
+                        "merge:base from base_branches config"
+                    );
+                    Some(base.to_string())
+                }
+                _ => {
+                    debug!(
+                        branch = %branch_to_merge,
+                        "merge:no base config found, defaulting to main"
+                    );
+                    None
+                }
+            },
         }
     };
 
-    let target_branch = into_branch
-        .map(|s| s.to_string())
+    let target_branch = resumed
+        .as_ref()
+        .map(|cp| cp.target.clone())
+        .or_else(|| into_branch.map(|s| s.to_string()))
         .or(detected_base)
         .unwrap_or_else(|| context.main_branch.clone());
     let target_branch = target_branch.as_str();
@@ -138,6 +195,21 @@ pub fn merge(
         }
     };
 
+    // A locked worktree is kept until unlocked, so merging can't clean it up
+    if !keep
+        && context
+            .vcs
+            .list_locked_workspaces()
+            .contains_key(&worktree_path)
+    {
+        return Err(anyhow!(
+            "Worktree for '{}' is locked. Use --keep to merge without removing it, or unlock it \
+            with 'workmux unlock {}' first.",
+            branch_to_merge,
+            handle
+        ));
+    }
+
     // Handle changes in the source worktree
     // Only check for unstaged/untracked when worktree will be deleted (!keep)
     // With --keep, the worktree persists so no data loss risk
@@ -163,7 +235,10 @@ pub fn merge(
     if had_staged_changes && !ignore_uncommitted {
         // Commit using the user's editor
         info!(path = %worktree_path.display(), "merge:committing staged changes");
-        context.vcs.commit_with_editor(&worktree_path).context("Failed to commit staged changes")?;
+        context
+            .vcs
+            .commit_with_editor(&worktree_path, skip_commit_hooks)
+            .context("Failed to commit staged changes")?;
     }
 
     if branch_to_merge == target_branch {
@@ -178,6 +253,39 @@ pub fn merge(
         "merge:target branch resolved"
     );
 
+    // Where the interrupted merge left off. A merge that landed before the
+    // interruption counts as merged once the branch is fully in the target;
+    // one that didn't is rolled back and done again.
+    let mut resume_step = None;
+    if let Some(cp) = &resumed {
+        let mut step = cp.step;
+        if step == MergeStep::Merging {
+            let target_moved =
+                context.vcs.resolve_commit(target_branch).ok().as_ref() != Some(&cp.target_head);
+            if target_moved
+                && !context
+                    .vcs
+                    .get_unmerged_branches(target_branch)?
+                    .contains(&branch_to_merge)
+            {
+                step = MergeStep::Merged;
+            } else if !target_moved
+                && context
+                    .vcs
+                    .holds_merge_of(&target_worktree_path, &branch_to_merge)?
+            {
+                context.progress.info(format!(
+                    "Rolling back the interrupted merge in {}",
+                    target_worktree_path.display()
+                ));
+                context.vcs.reset_hard(&target_worktree_path)?;
+            }
+        }
+        info!(branch = %branch_to_merge, ?step, "merge:resuming");
+        resume_step = Some(step);
+    }
+    let already_merged = resume_step.is_some_and(|step| step >= MergeStep::Merged);
+
     // Safety check: Abort if the target worktree has uncommitted tracked changes.
     // Untracked files are allowed; git will fail safely if they collide with merged files.
     if context.vcs.has_tracked_changes(&target_worktree_path)? {
@@ -196,6 +304,7 @@ pub fn merge(
     // Skip hooks if --no-verify or --no-hooks flag is passed.
     if !no_verify
         && !no_hooks
+        && !already_merged
         && let Some(hooks) = &context.config.pre_merge
         && !hooks.is_empty()
     {
@@ -226,13 +335,31 @@ pub fn merge(
         }
     }
 
-    // Helper closure to generate the error message for merge conflicts
-    let conflict_err = |branch: &str| -> anyhow::Error {
-        let retry_cmd = if into_branch.is_some() {
+    let retry_cmd = |branch: &str| -> String {
+        if into_branch.is_some() {
             format!("workmux merge {} --into {}", branch, target_branch)
         } else {
             format!("workmux merge {}", branch)
+        }
+    };
+
+    // Helper closure leaving conflicts in the worktree (keep and agent strategies)
+    let kept_conflicts = |branch: &str, rebased: bool| -> anyhow::Error {
+        let conflicts = KeptConflicts {
+            handle,
+            branch,
+            target: target_branch,
+            worktree: &worktree_path,
+            retry_cmd: retry_cmd(branch),
         };
+        keep_conflicts(context, &conflicts, on_conflict, rebased, skip_merge_hooks)
+    };
+
+    // Helper closure to generate the error message for merge conflicts
+    let conflict_err = |branch: &str| -> anyhow::Error {
+        if on_conflict != ConflictStrategy::Abort {
+            return kept_conflicts(branch, false);
+        }
         anyhow!(
             "Merge failed due to conflicts. Target worktree kept clean.\n\n\
             To resolve, update your branch in worktree at {}:\n\
@@ -243,33 +370,69 @@ pub fn merge(
             worktree_path.display(),
             target_branch,
             target_branch,
-            retry_cmd
+            retry_cmd(branch)
         )
     };
 
-    if rebase {
+    let target_head = match &resumed {
+        Some(cp) => cp.target_head.clone(),
+        None => context
+            .vcs
+            .resolve_commit(target_branch)
+            .unwrap_or_default(),
+    };
+    let mut checkpoint = Checkpoint::begin(
+        context,
+        MergeCheckpoint {
+            repo: context.main_worktree_root.clone(),
+            handle: handle.to_string(),
+            branch: branch_to_merge.clone(),
+            target: target_branch.to_string(),
+            worktree: worktree_path.clone(),
+            target_worktree: target_worktree_path.clone(),
+            target_head,
+            step: resume_step.unwrap_or(MergeStep::Merging),
+            pid: 0,
+            started_ts: 0,
+        },
+    );
+
+    if already_merged {
+        info!(branch = %branch_to_merge, "merge:already merged, skipping to remote sync and cleanup");
+    } else if rebase {
         // Rebase the feature branch on top of target inside its own worktree.
         // This is where conflicts will be detected.
-        println!(
+        context.progress.info(format!(
             "Rebasing '{}' onto '{}'...",
             &branch_to_merge, target_branch
-        );
+        ));
         info!(
             branch = %branch_to_merge,
             base = target_branch,
             "merge:rebase start"
         );
-        context.vcs.rebase_onto_base(&worktree_path, target_branch).with_context(|| {
-            format!(
+        if let Err(e) = context.vcs.rebase_onto_base(&worktree_path, target_branch) {
+            // The stopped rebase already left the conflicts in the worktree
+            if on_conflict != ConflictStrategy::Abort {
+                info!(branch = %branch_to_merge, error = %e, "merge:rebase stopped, keeping conflicts");
+                return Err(kept_conflicts(&branch_to_merge, true));
+            }
+            return Err(e.context(format!(
                 "Rebase failed, likely due to conflicts.\n\n\
                 Please resolve them manually inside the worktree at '{}'.\n\
                 Then, run 'git rebase --continue' to proceed or 'git rebase --abort' to cancel.",
                 worktree_path.display()
-            )
-        })?;
+            )));
+        }
+
+        if remote_sync.push_rebased {
+            push_rebased_branch(context, &branch_to_merge)?;
+        }
 
         // After a successful rebase, merge into target. This will be a fast-forward.
-        context.vcs.merge_in_workspace(&target_worktree_path, &branch_to_merge)
+        context
+            .vcs
+            .merge_in_workspace(&target_worktree_path, &branch_to_merge, skip_merge_hooks)
             .context("Failed to merge rebased branch. This should have been a fast-forward.")?;
         info!(branch = %branch_to_merge, "merge:fast-forward complete");
     } else if squash {
@@ -281,26 +444,63 @@ pub fn merge(
             return Err(conflict_err(&branch_to_merge));
         }
 
+        // Offer the evidence saved from the dashboard in the commit message
+        if let Some(evidence) = super::evidence::read(&worktree_path)
+            && let Err(e) = context
+                .vcs
+                .append_commit_template(&target_worktree_path, &evidence)
+        {
+            warn!(error = %e, "merge:failed to add evidence to the commit message");
+        }
+
         // Prompt the user to provide a commit message for the squashed changes.
-        println!("Staged squashed changes. Please provide a commit message in your editor.");
-        context.vcs.commit_with_editor(&target_worktree_path)
+        context
+            .progress
+            .info("Staged squashed changes. Please provide a commit message in your editor.");
+        context
+            .vcs
+            .commit_with_editor(&target_worktree_path, skip_commit_hooks)
             .context("Failed to commit squashed changes. You may need to commit them manually.")?;
         info!(branch = %branch_to_merge, "merge:squash merge committed");
     } else {
         // Default merge commit workflow
-        if let Err(e) = context.vcs.merge_in_workspace(&target_worktree_path, &branch_to_merge) {
+        if let Err(e) = context.vcs.merge_in_workspace(
+            &target_worktree_path,
+            &branch_to_merge,
+            skip_merge_hooks,
+        ) {
             info!(branch = %branch_to_merge, error = %e, "merge:standard merge failed, aborting merge in target worktree");
             // Best effort to abort; ignore failure as the user message is the priority.
             let _ = context.vcs.abort_merge(&target_worktree_path);
+            // A hook refusing the merge commit is not a conflict; say so
+            if e.is::<GitHookFailed>() {
+                return Err(e.context("Merge aborted, target worktree kept clean"));
+            }
             return Err(conflict_err(&branch_to_merge));
         }
         info!(branch = %branch_to_merge, "merge:standard merge complete");
     }
+    checkpoint.advance(context, MergeStep::Merged);
+
+    // Remote operations must happen before cleanup, which may kill this process
+    if checkpoint.step() < MergeStep::Synced {
+        sync_remote(context, &branch_to_merge, target_branch, remote_sync);
+        checkpoint.advance(context, MergeStep::Synced);
+    }
+
+    let event = PluginEvent::MergeCompleted {
+        handle: handle.to_string(),
+        branch: branch_to_merge.clone(),
+        target: target_branch.to_string(),
+    };
+    if let Err(e) = plugin::dispatch(&event, context.vcs.as_ref()) {
+        warn!(error = %e, "merge:plugin dispatch failed");
+    }
 
     // Show notification before cleanup or early return (--keep),
     // since cleanup may kill the window and terminate this process
     if notification {
-        show_notification(&format!(
+        crate::util::show_notification(&format!(
             "Merged '{}' into '{}'",
             branch_to_merge, target_branch
         ));
@@ -309,10 +509,12 @@ pub fn merge(
     // Skip cleanup if --keep flag is used
     if keep {
         info!(branch = %branch_to_merge, "merge:skipping cleanup (--keep)");
+        checkpoint.finish();
         return Ok(MergeResult {
             branch_merged: branch_to_merge,
             main_branch: target_branch.to_string(),
             had_staged_changes,
+            worktree_path,
         });
     }
 
@@ -327,6 +529,7 @@ pub fn merge(
         false, // keep_branch: always delete when merging
         no_hooks,
     )?;
+    checkpoint.finish();
 
     // Navigate to the target branch window/session and close the source
     cleanup::navigate_to_target_and_close(
@@ -342,35 +545,452 @@ pub fn merge(
         branch_merged: branch_to_merge,
         main_branch: target_branch.to_string(),
         had_staged_changes,
+        worktree_path,
     })
 }
 
-/// Shows a system notification on macOS or Linux
-fn show_notification(message: &str) {
-    #[cfg(target_os = "macos")]
-    {
-        use mac_notification_sys::{Notification, set_application};
-        // Set application to Terminal to use its icon
-        if let Err(e) = set_application("com.apple.Terminal") {
-            tracing::debug!("Failed to set notification application: {:?}", e);
-        }
-        if let Err(e) = Notification::default()
-            .title("workmux")
-            .message(message)
-            .send()
+/// A merge whose conflicts are left in the branch's worktree.
+struct KeptConflicts<'a> {
+    handle: &'a str,
+    branch: &'a str,
+    target: &'a str,
+    worktree: &'a Path,
+    retry_cmd: String,
+}
+
+/// Leave a failed merge's conflicts in the branch's worktree for the keep and
+/// agent strategies, and return the error reporting where they are.
+///
+/// The target worktree has already been cleaned up, so the conflicts are
+/// recreated by merging the target into the branch in its own worktree
+/// (a failed rebase is already stopped there). With the agent strategy, the
+/// worktree's agent is asked to resolve them and run the merge again.
+fn keep_conflicts(
+    context: &WorkflowContext,
+    conflicts: &KeptConflicts,
+    on_conflict: ConflictStrategy,
+    rebased: bool,
+    skip_merge_hooks: bool,
+) -> anyhow::Error {
+    let worktree = conflicts.worktree;
+    if !rebased {
+        match context
+            .vcs
+            .merge_in_workspace(worktree, conflicts.target, skip_merge_hooks)
         {
-            tracing::debug!("Failed to send notification: {:?}", e);
+            Ok(()) => {
+                return anyhow!(
+                    "Merge failed, but '{}' merged into '{}' cleanly in the worktree at {}.\n\n\
+                    Retry: {}",
+                    conflicts.target,
+                    conflicts.branch,
+                    worktree.display(),
+                    conflicts.retry_cmd
+                );
+            }
+            Err(e) if e.is::<GitHookFailed>() => {
+                let _ = context.vcs.abort_merge(worktree);
+                return e.context("Merge aborted, target worktree kept clean");
+            }
+            Err(e) if !context.vcs.merge_in_progress(worktree) => {
+                return e.context(format!(
+                    "Failed to recreate the conflicts in the worktree at {}",
+                    worktree.display()
+                ));
+            }
+            Err(e) => {
+                info!(branch = conflicts.branch, error = %e, "merge:conflicts kept in worktree");
+            }
         }
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        if let Err(e) = notify_rust::Notification::new()
-            .summary("workmux")
-            .body(message)
-            .show()
-        {
-            tracing::debug!("Failed to send notification: {:?}", e);
+    let is_git = context.vcs.name() == "git";
+    let finish = match (is_git, rebased) {
+        (true, true) => "git rebase --continue",
+        (true, false) => "git commit",
+        (false, _) => "committing the resolution",
+    };
+    let operation = if rebased { "rebase onto" } else { "merge of" };
+
+    let mut asked_agent = false;
+    if on_conflict == ConflictStrategy::Agent {
+        let prompt = format!(
+            "Merging '{}' into '{}' stopped on conflicts. A {} '{}' is in progress in this worktree. \
+            Resolve the conflicts, finish with {}, then run: {}",
+            conflicts.branch,
+            conflicts.target,
+            operation,
+            conflicts.target,
+            finish,
+            conflicts.retry_cmd
+        );
+        let settings = HandshakeSettings::from_config(&context.config.handshake);
+        let submit =
+            handshake::SubmitSettings::for_agent(&context.config, context.config.agent.as_deref());
+        let sent = super::resolve_worktree_agent(
+            conflicts.handle,
+            context.mux.as_ref(),
+            context.vcs.as_ref(),
+        )
+        .and_then(|(_, agent)| {
+            handshake::send_agent_input(
+                context.mux.as_ref(),
+                &agent.pane_id,
+                &prompt,
+                context.config.agent.as_deref(),
+                &settings,
+                &submit,
+            )
+        });
+        match sent {
+            Ok(()) => asked_agent = true,
+            Err(e) => context
+                .progress
+                .warn(format!("Could not hand the conflicts to an agent: {}", e)),
+        }
+    }
+
+    crate::util::show_notification(&format!(
+        "Merging '{}' into '{}' has conflicts to resolve",
+        conflicts.branch, conflicts.target
+    ));
+
+    if asked_agent {
+        anyhow!(
+            "Merge has conflicts. The {} '{}' was left in progress in the worktree at {}, \
+            and its agent was asked to resolve them and run: {}",
+            operation,
+            conflicts.target,
+            worktree.display(),
+            conflicts.retry_cmd
+        )
+    } else {
+        anyhow!(
+            "Merge has conflicts. The {} '{}' was left in progress in the worktree at {}.\n\n\
+            Resolve them, finish with {}, then retry: {}",
+            operation,
+            conflicts.target,
+            worktree.display(),
+            finish,
+            conflicts.retry_cmd
+        )
+    }
+}
+
+/// Rebase a branch onto the default branch of the upstream remote and push it
+/// to the fork, ready for a pull request against the canonical repository.
+///
+/// Nothing is merged locally, so the worktree is kept for review follow-ups.
+pub fn merge_to_upstream(
+    name: &str,
+    into_branch: Option<&str>,
+    context: &WorkflowContext,
+) -> Result<UpstreamResult> {
+    info!(name, into = into_branch, "merge:to upstream start");
+    context.chdir_to_main_worktree()?;
+
+    let (worktree_path, branch) = context
+        .vcs
+        .find_workspace(name)
+        .with_context(|| format!("No workspace found with name '{}'", name))?;
+
+    let remotes = context.vcs.list_remotes()?;
+    let upstream = match upstream_remote(context, &remotes) {
+        Some(upstream) if remotes.contains(&upstream) => upstream,
+        Some(upstream) => {
+            return Err(anyhow!(
+                "Remote '{}' (upstream_remote) does not exist",
+                upstream
+            ));
+        }
+        None => {
+            return Err(anyhow!(
+                "No upstream remote. Add the canonical repository with \
+                'git remote add upstream <url>', or set upstream_remote in .workmux.yaml"
+            ));
+        }
+    };
+    let fork = pick_remote(
+        context.vcs.get_branch_remote(&branch),
+        &remotes,
+        Some(&upstream),
+    )
+    .ok_or_else(|| {
+        anyhow!(
+            "No fork remote to push '{}' to besides '{}'",
+            branch,
+            upstream
+        )
+    })?;
+
+    // Rebasing needs a clean worktree; commit what is staged like a merge does
+    if context.vcs.has_staged_changes(&worktree_path)? {
+        info!(path = %worktree_path.display(), "merge:committing staged changes");
+        context
+            .vcs
+            .commit_with_editor(
+                &worktree_path,
+                context.config.git_hooks.skips(GitHookOp::Commit),
+            )
+            .context("Failed to commit staged changes")?;
+    }
+    if context.vcs.has_tracked_changes(&worktree_path)? {
+        return Err(anyhow!(
+            "Worktree for '{}' has uncommitted changes. Commit or stash them before \
+            rebasing onto '{}'.",
+            branch,
+            upstream
+        ));
+    }
+
+    context.progress.info(format!("Fetching '{}'...", upstream));
+    context.vcs.fetch_remote(&upstream)?;
+    let target = match into_branch {
+        Some(target) => target.to_string(),
+        None => context.vcs.get_remote_default_branch(&upstream)?,
+    };
+    let base = context.vcs.remote_ref(&upstream, &target);
+
+    context
+        .progress
+        .info(format!("Rebasing '{}' onto '{}'...", branch, base));
+    context
+        .vcs
+        .rebase_onto_base(&worktree_path, &base)
+        .map_err(|e| {
+            e.context(format!(
+                "Rebase failed, likely due to conflicts.\n\n\
+                Please resolve them manually inside the worktree at '{}'.\n\
+                Then, run 'git rebase --continue' and 'workmux merge --to-upstream' again, \
+                or 'git rebase --abort' to cancel.",
+                worktree_path.display()
+            ))
+        })?;
+
+    context
+        .progress
+        .info(format!("Pushing '{}' to '{}'...", branch, fork));
+    context
+        .vcs
+        .push_branch(
+            &fork,
+            &branch,
+            true,
+            context.config.git_hooks.skips(GitHookOp::Push),
+        )
+        .with_context(|| {
+            format!(
+                "Failed to push '{}' to '{}'. The branch is rebased locally.",
+                branch, fork
+            )
+        })?;
+    context.vcs.set_branch_upstream(&branch, &fork)?;
+    info!(
+        branch = %branch,
+        upstream = %upstream,
+        target = %target,
+        fork = %fork,
+        "merge:pushed to fork"
+    );
+
+    Ok(UpstreamResult {
+        branch,
+        upstream,
+        target,
+        fork,
+        worktree_path,
+    })
+}
+
+/// Force-push (with lease) the freshly rebased branch so an open pull request
+/// contains exactly the commits about to land on the target branch.
+fn push_rebased_branch(context: &WorkflowContext, branch: &str) -> Result<()> {
+    let Some(remote) = branch_remote(context, branch) else {
+        context.progress.info(format!(
+            "No remote for '{}'; skipping push of rebased branch",
+            branch
+        ));
+        return Ok(());
+    };
+    context
+        .progress
+        .info(format!("Pushing rebased '{}' to '{}'...", branch, remote));
+    context
+        .vcs
+        .push_branch(
+            &remote,
+            branch,
+            true,
+            context.config.git_hooks.skips(GitHookOp::Push),
+        )
+        .with_context(|| {
+            format!(
+                "Failed to push rebased '{}'. Nothing was merged; the branch is rebased locally.",
+                branch
+            )
+        })?;
+    info!(branch, remote = %remote, "merge:pushed rebased branch");
+    Ok(())
+}
+
+/// Push the target branch and delete the merged remote branch.
+///
+/// The local merge has already succeeded at this point, so failures are
+/// reported as warnings rather than aborting cleanup.
+fn sync_remote(context: &WorkflowContext, branch: &str, target: &str, sync: MergeRemoteSync) {
+    let skip_push_hooks = context.config.git_hooks.skips(GitHookOp::Push);
+    let mut pushed = true;
+    if sync.push {
+        match branch_remote(context, target) {
+            Some(remote) => {
+                context
+                    .progress
+                    .info(format!("Pushing '{}' to '{}'...", target, remote));
+                match context
+                    .vcs
+                    .push_branch(&remote, target, false, skip_push_hooks)
+                {
+                    Ok(()) => {
+                        info!(target, remote = %remote, "merge:pushed target branch");
+                        context.progress.success(format!("Pushed '{}'", target));
+                    }
+                    Err(e) => {
+                        pushed = false;
+                        context.progress.warn(format!("{:#}", e));
+                    }
+                }
+            }
+            None => {
+                pushed = false;
+                context
+                    .progress
+                    .warn(format!("no remote found for '{}'; skipping push", target));
+            }
         }
     }
+
+    if sync.delete_remote {
+        let Some(remote) = branch_remote(context, branch) else {
+            context.progress.warn(format!(
+                "no remote found for '{}'; skipping remote delete",
+                branch
+            ));
+            return;
+        };
+        if !pushed {
+            // The remote branch may be the only published copy of these commits
+            context.progress.warn(format!(
+                "keeping '{}' on '{}' since '{}' was not pushed",
+                branch, remote, target
+            ));
+            return;
+        }
+        match context.vcs.delete_remote_branch(&remote, branch) {
+            Ok(()) => {
+                info!(branch, remote = %remote, "merge:deleted remote branch");
+                context
+                    .progress
+                    .success(format!("Deleted '{}' on '{}'", branch, remote));
+            }
+            Err(e) => context.progress.warn(format!("{:#}", e)),
+        }
+    }
+}
+
+/// The remote a branch should be pushed to or deleted from.
+///
+/// Only an explicitly configured `upstream_remote` is avoided; a remote that
+/// is merely named `upstream` is used like any other.
+pub(super) fn branch_remote(context: &WorkflowContext, branch: &str) -> Option<String> {
+    let remotes = context.vcs.list_remotes().unwrap_or_default();
+    let upstream = context.config.upstream_remote.as_deref();
+    pick_remote(context.vcs.get_branch_remote(branch), &remotes, upstream)
+}
+
+/// The remote of the canonical repository when `origin` is a fork: the
+/// configured `upstream_remote`, or a remote named `upstream`.
+fn upstream_remote(context: &WorkflowContext, remotes: &[String]) -> Option<String> {
+    context
+        .config
+        .upstream_remote
+        .clone()
+        .or_else(|| remotes.iter().find(|r| *r == "upstream").cloned())
+}
+
+/// Prefer the branch's tracked remote, then `origin`, then the only remote.
+/// The `upstream` remote, if given, is never picked: branches are pushed to
+/// the fork.
+fn pick_remote(
+    tracked: Option<String>,
+    remotes: &[String],
+    upstream: Option<&str>,
+) -> Option<String> {
+    let is_upstream = |r: &str| upstream == Some(r);
+    if let Some(tracked) = tracked.filter(|r| !is_upstream(r)) {
+        return Some(tracked);
+    }
+    let remotes: Vec<&String> = remotes.iter().filter(|r| !is_upstream(r)).collect();
+    if remotes.iter().any(|r| *r == "origin") {
+        return Some("origin".to_string());
+    }
+    match remotes[..] {
+        [only] => Some(only.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remotes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn pick_remote_prefers_tracked_remote() {
+        assert_eq!(
+            pick_remote(
+                Some("fork".to_string()),
+                &remotes(&["origin", "fork"]),
+                None
+            ),
+            Some("fork".to_string())
+        );
+    }
+
+    #[test]
+    fn pick_remote_falls_back_to_origin_then_single_remote() {
+        assert_eq!(
+            pick_remote(None, &remotes(&["upstream", "origin"]), None),
+            Some("origin".to_string())
+        );
+        assert_eq!(
+            pick_remote(None, &remotes(&["upstream"]), None),
+            Some("upstream".to_string())
+        );
+        assert_eq!(pick_remote(None, &remotes(&["a", "b"]), None), None);
+        assert_eq!(pick_remote(None, &[], None), None);
+    }
+
+    #[test]
+    fn pick_remote_never_picks_the_upstream_remote() {
+        // main tracking upstream/main pushes to the fork instead
+        assert_eq!(
+            pick_remote(
+                Some("upstream".to_string()),
+                &remotes(&["upstream", "origin"]),
+                Some("upstream")
+            ),
+            Some("origin".to_string())
+        );
+        assert_eq!(
+            pick_remote(None, &remotes(&["canonical", "mine"]), Some("canonical")),
+            Some("mine".to_string())
+        );
+        assert_eq!(
+            pick_remote(None, &remotes(&["upstream"]), Some("upstream")),
+            None
+        );
+    }
 }
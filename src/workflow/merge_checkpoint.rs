@@ -0,0 +1,181 @@
+//! Checkpoints that let an interrupted `workmux merge` be resumed or aborted.
+//!
+//! A merge records its progress in the state store while it runs. The
+//! checkpoint is removed when the merge finishes or fails on its own, so one
+//! that is left behind means the process died midway (closed terminal, killed
+//! editor, crash). Running `workmux merge` for the same branch again finds it
+//! and picks up after the last completed step, or rolls the target back.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow, bail};
+use tracing::{info, warn};
+
+use crate::state::{MergeCheckpoint, MergeStep, StateStore};
+
+use super::context::WorkflowContext;
+
+/// Progress of the running merge, kept in the state store.
+///
+/// Dropping it while the merge is still `Merging` deletes the checkpoint:
+/// a merge that failed there has already cleaned up the target. Once the
+/// branch has landed the checkpoint stays until `finish`, so a failed
+/// cleanup can be resumed without merging again.
+pub struct Checkpoint {
+    store: Option<StateStore>,
+    state: MergeCheckpoint,
+}
+
+impl Checkpoint {
+    /// Record that a merge of `state.branch` is starting (or resuming).
+    ///
+    /// A merge must not fail because its checkpoint can't be written, so
+    /// errors are only warned about.
+    pub fn begin(context: &WorkflowContext, mut state: MergeCheckpoint) -> Self {
+        state.pid = std::process::id();
+        state.started_ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let store = match StateStore::new() {
+            Ok(store) => Some(store),
+            Err(e) => {
+                warn!(error = %e, "merge:checkpoint store unavailable");
+                None
+            }
+        };
+        let checkpoint = Checkpoint { store, state };
+        checkpoint.save(context);
+        checkpoint
+    }
+
+    pub fn step(&self) -> MergeStep {
+        self.state.step
+    }
+
+    /// Record that `step` has completed. Steps a resumed merge skipped
+    /// are already recorded.
+    pub fn advance(&mut self, context: &WorkflowContext, step: MergeStep) {
+        if step > self.state.step {
+            self.state.step = step;
+            self.save(context);
+        }
+    }
+
+    /// The merge is complete; forget it.
+    pub fn finish(mut self) {
+        self.delete();
+    }
+
+    fn save(&self, context: &WorkflowContext) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(e) = store.save_merge(&self.state) {
+            warn!(error = %e, "merge:failed to save checkpoint");
+            context.progress.warn(format!(
+                "could not save merge progress, an interrupted merge can't be resumed: {:#}",
+                e
+            ));
+        }
+    }
+
+    fn delete(&mut self) {
+        if let Some(store) = self.store.take()
+            && let Err(e) = store.delete_merge(&self.state.repo, &self.state.branch)
+        {
+            warn!(error = %e, "merge:failed to delete checkpoint");
+        }
+    }
+}
+
+impl Drop for Checkpoint {
+    fn drop(&mut self) {
+        if self.state.step == MergeStep::Merging {
+            self.delete();
+        }
+    }
+}
+
+/// The interrupted merge of the worktree or branch `name`, if any.
+///
+/// Fails if the merge is still running in another process.
+pub fn find_interrupted(context: &WorkflowContext, name: &str) -> Result<Option<MergeCheckpoint>> {
+    let store = StateStore::new()?;
+    let found = store
+        .list_merges(&context.main_worktree_root)?
+        .into_iter()
+        .find(|cp| cp.handle == name || cp.branch == name);
+    match found {
+        Some(cp) if cp.pid != std::process::id() && is_running(cp.pid) => bail!(
+            "'{}' is already being merged into '{}' by another workmux process (pid {})",
+            cp.branch,
+            cp.target,
+            cp.pid
+        ),
+        found => Ok(found),
+    }
+}
+
+/// Roll back an interrupted merge and forget it.
+///
+/// Only a merge interrupted while merging has anything to roll back: the
+/// target worktree is reset to its last commit. After that step the branch
+/// has landed and aborting only drops the checkpoint.
+pub fn abort(context: &WorkflowContext, checkpoint: &MergeCheckpoint) -> Result<()> {
+    if checkpoint.step == MergeStep::Merging
+        && checkpoint.target_worktree.exists()
+        && context
+            .vcs
+            .has_tracked_changes(&checkpoint.target_worktree)?
+    {
+        info!(path = %checkpoint.target_worktree.display(), "merge:resetting interrupted merge");
+        context.vcs.reset_hard(&checkpoint.target_worktree)?;
+    }
+    clear(checkpoint)
+}
+
+/// Forget an interrupted merge.
+pub fn clear(checkpoint: &MergeCheckpoint) -> Result<()> {
+    StateStore::new()?.delete_merge(&checkpoint.repo, &checkpoint.branch)
+}
+
+/// Error for a merge that was interrupted and needs `--resume` or `--abort`.
+pub fn interrupted_error(checkpoint: &MergeCheckpoint) -> anyhow::Error {
+    anyhow!(
+        "A previous merge of '{}' into '{}' was interrupted {}.\n\n\
+        Continue it: workmux merge {} --resume\n\
+        Or roll it back: workmux merge {} --abort",
+        checkpoint.branch,
+        checkpoint.target,
+        checkpoint.step.describe(),
+        checkpoint.handle,
+        checkpoint.handle
+    )
+}
+
+/// Whether a process with this pid exists.
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // 0 would address the whole process group
+    if pid == 0 {
+        return false;
+    }
+    // Signal 0 only checks for existence; EPERM means it exists but belongs
+    // to another user
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn own_process_is_running() {
+        assert!(is_running(std::process::id()));
+        assert!(!is_running(u32::MAX));
+    }
+}
@@ -1,26 +1,40 @@
 // Module declarations
 mod agent_resolve;
+pub mod archive;
+pub mod artifacts;
+mod base;
+pub mod branch_backup;
 mod cleanup;
+mod collision;
 mod context;
 mod create;
+pub mod evidence;
+pub mod group;
 mod list;
 mod merge;
+pub mod merge_checkpoint;
 mod open;
+pub mod orphans;
+pub mod pane;
 pub mod pr;
+pub mod preflight;
 pub mod prompt_loader;
 mod remove;
 mod setup;
 pub mod types;
 
 // Public API re-exports
-pub use agent_resolve::{match_agents_to_worktree, resolve_worktree_agent};
+pub use agent_resolve::{
+    ensure_not_protected_main, match_agents_to_worktree, resolve_worktree_agent,
+};
+pub use base::{branch_base, ensure_base_exists, unmerged_branches};
 pub use create::{create, create_with_changes};
 pub use list::list;
-pub use merge::merge;
+pub use merge::{merge, merge_to_upstream};
 pub use open::open;
 pub use remove::remove;
-pub use setup::write_prompt_file;
+pub use setup::{attach_files, pane_logs_root, write_prompt_file};
 
 // Re-export commonly used types for convenience
 pub use context::WorkflowContext;
-pub use types::{CreateArgs, SetupOptions};
+pub use types::{CreateArgs, MergeRemoteSync, SetupOptions};
@@ -82,9 +82,12 @@ pub fn open(
         return Ok(CreateResult {
             worktree_path,
             branch_name,
+            handle: base_handle,
             post_create_hooks_run: 0,
             base_branch: None,
             did_switch: true,
+            focus_pane_id: None,
+            sandbox_vm: None,
         });
     }
 
@@ -136,11 +139,10 @@ pub fn open(
 
     // Setup the environment
     let result = setup::setup_environment(
-        context.mux.as_ref(),
+        context,
         &branch_name,
         &handle,
         &worktree_path,
-        &context.config,
         &options_with_workdir,
         None,
         after_window,
@@ -163,8 +165,12 @@ pub fn open(
 ///
 /// This returns "my-feature-3".
 ///
-/// Note: Only called in window mode (session mode rejects --new).
-fn resolve_unique_handle(context: &WorkflowContext, base_handle: &str) -> Result<String> {
+/// Note: Only called in window mode (session mode rejects --new and
+/// `--on-collision suffix`).
+pub(super) fn resolve_unique_handle(
+    context: &WorkflowContext,
+    base_handle: &str,
+) -> Result<String> {
     let all_names = context.mux.get_all_window_names()?;
     let prefix = &context.prefix;
     let full_base = prefixed(prefix, base_handle);
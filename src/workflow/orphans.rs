@@ -0,0 +1,143 @@
+//! Windows (or sessions) left running in a worktree that was deleted
+//! behind workmux's back, e.g. with `git worktree remove`.
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::config::MuxMode;
+use crate::multiplexer::LivePaneInfo;
+use crate::naming;
+use crate::util::canon_or_self;
+
+use super::WorkflowContext;
+
+/// A window or session whose worktree no longer exists.
+#[derive(Debug, Clone)]
+pub struct OrphanTarget {
+    /// Full window or session name
+    pub name: String,
+    /// Handle of the deleted worktree
+    pub handle: String,
+    /// Path the worktree was at
+    pub path: PathBuf,
+    pub mode: MuxMode,
+    pub pane_ids: Vec<String>,
+}
+
+/// Whether a pane working directory no longer exists.
+pub fn is_missing_dir(path: &Path) -> bool {
+    !path.as_os_str().is_empty() && !strip_deleted(path).exists()
+}
+
+/// Linux reports the cwd of a process whose directory was removed as
+/// `<path> (deleted)`.
+fn strip_deleted(path: &Path) -> &Path {
+    path.to_str()
+        .and_then(|s| s.strip_suffix(" (deleted)"))
+        .map_or(path, Path::new)
+}
+
+/// This project's windows and sessions with panes in a deleted worktree.
+pub fn find(
+    context: &WorkflowContext,
+    panes: &HashMap<String, LivePaneInfo>,
+) -> Result<Vec<OrphanTarget>> {
+    let base_dir = context.worktree_base_dir()?;
+    let bases = [canon_or_self(&base_dir), base_dir];
+    let mut found: BTreeMap<String, OrphanTarget> = BTreeMap::new();
+    for (pane_id, info) in panes {
+        if !is_missing_dir(&info.working_dir) {
+            continue;
+        }
+        let Some((handle, path)) = deleted_worktree(&info.working_dir, &bases) else {
+            continue;
+        };
+        let mode = context.vcs.get_workspace_mode(&handle);
+        let name = match mode {
+            MuxMode::Window => info.window.as_deref(),
+            MuxMode::Session => info.session.as_deref(),
+        };
+        let Some(name) = name.filter(|n| n.starts_with(&context.prefix)) else {
+            continue;
+        };
+        found
+            .entry(name.to_string())
+            .or_insert_with(|| OrphanTarget {
+                name: name.to_string(),
+                handle,
+                path,
+                mode,
+                pane_ids: Vec::new(),
+            })
+            .pane_ids
+            .push(pane_id.clone());
+    }
+    let mut orphans: Vec<OrphanTarget> = found.into_values().collect();
+    for orphan in &mut orphans {
+        orphan.pane_ids.sort();
+    }
+    Ok(orphans)
+}
+
+/// Handle and path of the worktree under one of `bases` that `cwd` was in,
+/// if that worktree is gone (not just a subdirectory of it).
+fn deleted_worktree(cwd: &Path, bases: &[PathBuf]) -> Option<(String, PathBuf)> {
+    let cwd = strip_deleted(cwd);
+    bases.iter().find_map(|base| {
+        let handle = cwd.strip_prefix(base).ok()?.components().next()?;
+        let handle = handle.as_os_str().to_str()?.to_string();
+        let path = base.join(&handle);
+        (!path.exists()).then_some((handle, path))
+    })
+}
+
+/// The branch the worktree `handle` had checked out: a branch of that name,
+/// or one whose derived handle is `handle`.
+pub fn branch_for(context: &WorkflowContext, handle: &str) -> Result<Option<String>> {
+    if context.vcs.branch_exists(handle)? {
+        return Ok(Some(handle.to_string()));
+    }
+    Ok(context
+        .vcs
+        .list_checkout_branches()?
+        .into_iter()
+        .find(|branch| {
+            naming::derive_handle(branch, None, &context.config).is_ok_and(|h| h == handle)
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleted_worktrees_are_found_from_any_depth() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let base = temp.path().join("api__worktrees");
+        std::fs::create_dir_all(base.join("kept/src")).unwrap();
+        let bases = [base.clone()];
+
+        let gone = base.join("gone");
+        assert_eq!(
+            deleted_worktree(&gone.join("src"), &bases),
+            Some(("gone".to_string(), gone.clone()))
+        );
+        let deleted = PathBuf::from(format!("{} (deleted)", gone.display()));
+        assert!(is_missing_dir(&deleted));
+        assert_eq!(
+            deleted_worktree(&deleted, &bases),
+            Some(("gone".to_string(), gone))
+        );
+
+        // A removed subdirectory of a worktree that still exists
+        assert_eq!(deleted_worktree(&base.join("kept/build"), &bases), None);
+        // Outside the worktree directory
+        assert_eq!(
+            deleted_worktree(&temp.path().join("elsewhere"), &bases),
+            None
+        );
+        assert!(!is_missing_dir(&base.join("kept/src")));
+        assert!(!is_missing_dir(Path::new("")));
+    }
+}
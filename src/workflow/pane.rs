@@ -0,0 +1,243 @@
+//! Adding panes to a worktree's window after it was created.
+//!
+//! The new pane goes through the same path as the panes `add` and `open`
+//! create: handshake, `<agent>` and placeholder resolution, sandbox wrapping
+//! and readiness waits. It is set up as the last pane of a layout whose
+//! earlier entries stand for the panes the window already has, so
+//! `depends_on` indices and transcript names line up with the configured
+//! layout.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use tracing::info;
+
+use crate::config::{MuxMode, PaneConfig};
+use crate::multiplexer::{LivePaneInfo, MuxHandle, PaneSetupOptions};
+
+use super::context::WorkflowContext;
+use super::setup;
+use super::types::SetupOptions;
+
+/// Where the added pane ended up.
+pub struct AddedPane {
+    pub pane_id: String,
+    /// Full name of the window (or session) it was added to
+    pub target_name: String,
+}
+
+/// Add `pane` to the open window of worktree `name`.
+///
+/// Splits `target` (a pane id in the window), else the pane this runs in
+/// when it belongs to the window, else the window's first pane. With
+/// `focus`, switches to the new pane.
+pub fn add_pane(
+    name: &str,
+    context: &WorkflowContext,
+    pane: PaneConfig,
+    target: Option<&str>,
+    focus: bool,
+) -> Result<AddedPane> {
+    context.ensure_mux_running()?;
+    if pane.split.is_none() {
+        bail!("The pane needs a split direction; pass --split horizontal or --split vertical");
+    }
+
+    let (worktree_path, branch_name) = context.vcs.find_workspace(name).with_context(|| {
+        format!(
+            "No worktree found with name '{}'. Use 'workmux list' to see available worktrees.",
+            name
+        )
+    })?;
+    let handle = worktree_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid worktree path: no directory name"))?
+        .to_string_lossy()
+        .to_string();
+
+    let mode = context.vcs.get_workspace_mode(&handle);
+    let mux = context.mux.as_ref();
+    let mux_handle = MuxHandle::new(mux, mode, &context.prefix, &handle);
+    if !mux_handle.exists()? {
+        bail!(
+            "No {} is open for '{}'. Open it with `workmux open {}` first.",
+            mux_handle.kind(),
+            handle,
+            handle
+        );
+    }
+    let target_name = mux_handle.full_name();
+
+    let live_panes = mux.get_all_live_pane_info()?;
+    let window_panes = panes_in(&live_panes, mode, &target_name);
+    let current = mux.current_pane_id();
+    let target_pane = choose_target(&window_panes, target, current.as_deref())?;
+
+    // Same working directory as `open` uses for the window
+    let working_dir = Some(worktree_path.join(&context.config_rel_dir))
+        .filter(|dir| !context.config_rel_dir.as_os_str().is_empty() && dir.exists())
+        .unwrap_or_else(|| worktree_path.clone());
+
+    // Panes the window already has come first, keeping configured readiness
+    // checks so the new pane's `depends_on` can wait on them
+    let configured = context.config.panes.as_deref().unwrap_or_default();
+    let index = window_panes.len().max(configured.len());
+    let mut panes: Vec<PaneConfig> = (0..index)
+        .map(|i| PaneConfig {
+            wait_for: configured.get(i).and_then(|p| p.wait_for.clone()),
+            ..PaneConfig::default()
+        })
+        .collect();
+    panes.push(PaneConfig {
+        target: Some(0),
+        focus: true,
+        ..pane
+    });
+
+    let options = SetupOptions::new(false, false, true);
+    let lima_vm_name = setup::pre_boot_lima_vm(
+        mux,
+        &context.config,
+        &panes[index..],
+        &working_dir,
+        &worktree_path,
+        &options,
+        None,
+    )?;
+    let log_dir = if panes[index].log_output {
+        Some(setup::pane_log_dir(&context.main_worktree_root, &handle)?)
+    } else {
+        None
+    };
+    let vars = setup::pane_vars(&branch_name, &handle, &worktree_path, None);
+
+    let result = mux
+        .setup_panes(
+            &target_pane,
+            &panes,
+            &working_dir,
+            PaneSetupOptions {
+                run_commands: true,
+                prompt_file_path: None,
+                worktree_root: Some(&worktree_path),
+                lima_vm_name: lima_vm_name.as_deref(),
+                lima_boot: None,
+                vars: Some(&vars),
+                log_dir: log_dir.as_deref(),
+            },
+            &context.config,
+            None,
+        )
+        .context("Failed to add pane")?;
+    info!(
+        handle = %handle,
+        target = %target_pane,
+        pane_id = %result.focus_pane_id,
+        "pane:added"
+    );
+
+    if focus {
+        mux.select_pane(&result.focus_pane_id)?;
+        mux_handle.select()?;
+    }
+
+    Ok(AddedPane {
+        pane_id: result.focus_pane_id,
+        target_name,
+    })
+}
+
+/// Ids of the panes in the window (or session) `full_name`, in creation
+/// order.
+fn panes_in(
+    live_panes: &HashMap<String, LivePaneInfo>,
+    mode: MuxMode,
+    full_name: &str,
+) -> Vec<String> {
+    let mut ids: Vec<&String> = live_panes
+        .iter()
+        .filter(|(_, info)| {
+            let owner = match mode {
+                MuxMode::Window => info.window.as_deref(),
+                MuxMode::Session => info.session.as_deref(),
+            };
+            owner == Some(full_name)
+        })
+        .map(|(id, _)| id)
+        .collect();
+    // Ids count up as panes are created (`%3` before `%12`)
+    ids.sort_by_key(|id| {
+        let digits: String = id.chars().filter(char::is_ascii_digit).collect();
+        (digits.parse::<u64>().unwrap_or(u64::MAX), id.to_string())
+    });
+    ids.into_iter().cloned().collect()
+}
+
+/// The pane to split: the requested one, the current one when it is in the
+/// window, or the window's first pane.
+fn choose_target(
+    window_panes: &[String],
+    requested: Option<&str>,
+    current: Option<&str>,
+) -> Result<String> {
+    if let Some(requested) = requested {
+        return window_panes
+            .iter()
+            .find(|id| id.as_str() == requested)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Pane '{}' is not in this window (its panes: {})",
+                    requested,
+                    window_panes.join(", ")
+                )
+            });
+    }
+    current
+        .and_then(|current| window_panes.iter().find(|id| id.as_str() == current))
+        .or_else(|| window_panes.first())
+        .cloned()
+        .ok_or_else(|| anyhow!("The window has no panes to split"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn pane(window: &str) -> LivePaneInfo {
+        LivePaneInfo {
+            pid: 1,
+            current_command: "zsh".to_string(),
+            working_dir: PathBuf::from("/tmp"),
+            title: None,
+            session: Some("main".to_string()),
+            window: Some(window.to_string()),
+            process: None,
+        }
+    }
+
+    #[test]
+    fn window_panes_are_listed_in_creation_order() {
+        let live = HashMap::from([
+            ("%12".to_string(), pane("wm-feature")),
+            ("%3".to_string(), pane("wm-feature")),
+            ("%5".to_string(), pane("wm-other")),
+        ]);
+        assert_eq!(
+            panes_in(&live, MuxMode::Window, "wm-feature"),
+            vec!["%3".to_string(), "%12".to_string()]
+        );
+        assert!(panes_in(&live, MuxMode::Session, "wm-feature").is_empty());
+    }
+
+    #[test]
+    fn target_prefers_request_then_current_pane() {
+        let panes = vec!["%3".to_string(), "%12".to_string()];
+        assert_eq!(choose_target(&panes, Some("%12"), None).unwrap(), "%12");
+        assert!(choose_target(&panes, Some("%99"), None).is_err());
+        assert_eq!(choose_target(&panes, None, Some("%12")).unwrap(), "%12");
+        assert_eq!(choose_target(&panes, None, Some("%7")).unwrap(), "%3");
+        assert!(choose_target(&[], None, None).is_err());
+    }
+}
@@ -1,11 +1,21 @@
 //! PR and fork branch resolution logic.
 //!
 //! This module extracts domain logic for resolving pull requests and fork branches
-//! from the command layer, making it reusable and testable.
+//! from the command layer, making it reusable and testable. It also opens pull
+//! requests for worktree branches, described from the prompt the agent got.
 
-use crate::{git, github, spinner};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::GitHookOp;
+use crate::forge::{Forge, NewPullRequest, RemoteUrl};
+use crate::prompt::Guardrails;
+use crate::{git, progress};
 use anyhow::{Context, Result, anyhow};
 
+use super::WorkflowContext;
+use super::artifacts::ARTIFACTS_DIR;
+
 /// Abstraction for git operations used in remote detection
 trait RemoteDetectionContext {
     fn list_remotes(&self) -> Result<Vec<String>>;
@@ -49,25 +59,26 @@ pub fn resolve_pr_ref(
     pr_number: u32,
     custom_branch_name: Option<&str>,
 ) -> Result<PrCheckoutResult> {
-    let pr_details = spinner::with_spinner(&format!("Fetching PR #{}", pr_number), || {
-        github::get_pr_details(pr_number)
+    let forge = Forge::current();
+    let pr_details = progress::step(&format!("Fetching PR #{}", pr_number), || {
+        forge.get_pr_details(pr_number)
     })
     .with_context(|| format!("Failed to fetch details for PR #{}", pr_number))?;
 
     // Display PR information
-    println!("PR #{}: {}", pr_number, pr_details.title);
-    println!("Author: {}", pr_details.author.login);
-    println!("Branch: {}", pr_details.head_ref_name);
+    progress::info(format!("PR #{}: {}", pr_number, pr_details.title));
+    progress::info(format!("Author: {}", pr_details.author.login));
+    progress::info(format!("Branch: {}", pr_details.head_ref_name));
 
     // Warn about PR state
     if pr_details.state != "OPEN" {
-        eprintln!(
-            "⚠️  Warning: PR #{} is {}. Proceeding with checkout...",
+        progress::warn(format!(
+            "PR #{} is {}. Proceeding with checkout...",
             pr_number, pr_details.state
-        );
+        ));
     }
     if pr_details.is_draft {
-        eprintln!("⚠️  Warning: PR #{} is a DRAFT.", pr_number);
+        progress::warn(format!("PR #{} is a DRAFT.", pr_number));
     }
 
     // Determine local branch name (match gh pr checkout behavior)
@@ -107,7 +118,8 @@ pub struct ForkBranchResult {
 /// Sets up the fork remote and optionally displays associated PR info.
 pub fn resolve_fork_branch(fork_spec: &git::ForkBranchSpec) -> Result<ForkBranchResult> {
     // Try to find an associated PR and display info (optional, non-blocking)
-    if let Ok(Some(pr)) = github::find_pr_by_head_ref(&fork_spec.owner, &fork_spec.branch) {
+    if let Ok(Some(pr)) = Forge::current().find_pr_by_head_ref(&fork_spec.owner, &fork_spec.branch)
+    {
         let state_suffix = match pr.state.as_str() {
             "OPEN" if pr.is_draft => " (draft)",
             "OPEN" => "",
@@ -115,7 +127,7 @@ pub fn resolve_fork_branch(fork_spec: &git::ForkBranchSpec) -> Result<ForkBranch
             "CLOSED" => " (closed)",
             _ => "",
         };
-        println!("PR #{}: {}{}", pr.number, pr.title, state_suffix);
+        progress::info(format!("PR #{}: {}{}", pr.number, pr.title, state_suffix));
     }
 
     // Ensure the fork remote exists
@@ -133,7 +145,7 @@ pub fn resolve_fork_branch(fork_spec: &git::ForkBranchSpec) -> Result<ForkBranch
 
 /// Detect if a branch name refers to a remote branch and extract the base name.
 ///
-/// Handles both "remote/branch" format and "owner:branch" (GitHub fork) format.
+/// Handles both "remote/branch" format and "owner:branch" (fork) format.
 /// Returns (remote_branch, template_base_name).
 pub fn detect_remote_branch(
     branch_name: &str,
@@ -191,7 +203,7 @@ fn detect_remote_branch_internal(
         let remote_ref = format!("refs/remotes/{}", branch_name);
         if !ctx.branch_exists(&remote_ref)? {
             // Remote branch not found locally - try fetching to see if it exists on the server
-            spinner::with_spinner(
+            progress::step(
                 &format!(
                     "Branch prefix matches remote '{}', verifying if it exists there...",
                     remote_name
@@ -209,10 +221,10 @@ fn detect_remote_branch_internal(
             // Check again after fetch
             if !ctx.branch_exists(&remote_ref)? {
                 // Branch doesn't exist on the server either - user wants a local branch with this name
-                eprintln!(
+                progress::info(format!(
                     "Not found on '{}', creating local branch '{}'",
                     remote_name, branch_name
-                );
+                ));
                 return Ok((None, branch_name.to_string()));
             }
 
@@ -225,6 +237,159 @@ fn detect_remote_branch_internal(
     }
 }
 
+/// Options for opening a pull request with [`create_pull_request`].
+#[derive(Debug, Default)]
+pub struct PrCreateOptions<'a> {
+    /// Branch to merge into, defaults to the main branch
+    pub base: Option<&'a str>,
+    /// Title, defaults to the first line of the worktree's prompt
+    pub title: Option<&'a str>,
+    pub draft: bool,
+}
+
+/// A pull request opened for a worktree's branch.
+#[derive(Debug)]
+pub struct PrCreateResult {
+    pub branch: String,
+    /// Remote the branch was pushed to
+    pub remote: String,
+    pub base: String,
+    pub url: String,
+    /// Whether the branch already had an open pull request, so none was opened
+    pub existing: bool,
+}
+
+/// Push the branch of worktree `name` and open a pull request for it.
+///
+/// The title and description come from the newest prompt file in the
+/// worktree, unless a title is given. When the branch already has an open
+/// pull request, the push updates it and no new one is opened.
+pub fn create_pull_request(
+    name: &str,
+    options: &PrCreateOptions,
+    context: &WorkflowContext,
+) -> Result<PrCreateResult> {
+    context.chdir_to_main_worktree()?;
+    let (worktree_path, branch) = context
+        .vcs
+        .find_workspace(name)
+        .with_context(|| format!("No workspace found with name '{}'", name))?;
+    if branch == context.main_branch {
+        return Err(anyhow!(
+            "'{}' is the main branch; open pull requests from a feature branch",
+            branch
+        ));
+    }
+    if context.vcs.has_tracked_changes(&worktree_path)? {
+        context.progress.warn(format!(
+            "Worktree for '{}' has uncommitted changes; they are not part of the pull request",
+            branch
+        ));
+    }
+
+    let remote = super::merge::branch_remote(context, &branch)
+        .ok_or_else(|| anyhow!("No remote to push '{}' to", branch))?;
+    context
+        .progress
+        .info(format!("Pushing '{}' to '{}'...", branch, remote));
+    context
+        .vcs
+        .push_branch(
+            &remote,
+            &branch,
+            false,
+            context.config.git_hooks.skips(GitHookOp::Push),
+        )
+        .with_context(|| format!("Failed to push '{}' to '{}'", branch, remote))?;
+    context.vcs.set_branch_upstream(&branch, &remote)?;
+
+    let forge = Forge::for_repo(&context.main_worktree_root);
+    let base = options.base.unwrap_or(&context.main_branch).to_string();
+    let remote_owner = git::get_remote_url(&remote).ok().and_then(|url| {
+        RemoteUrl::parse(&url)
+            .and_then(|u| u.owner(forge.provider))
+            .map(str::to_string)
+    });
+    // Only a pull request from the repository the branch was pushed to is ours
+    let open = remote_owner
+        .as_deref()
+        .and_then(|owner| forge.find_pr_by_head_ref(owner, &branch).ok().flatten())
+        .filter(|pr| pr.state == "OPEN");
+    if let Some(url) = open.and_then(|pr| pr.url) {
+        return Ok(PrCreateResult {
+            branch,
+            remote,
+            base,
+            url,
+            existing: true,
+        });
+    }
+
+    let guardrails = Guardrails::from_config(&context.config);
+    let (prompt_title, body) = description_from_prompt(&worktree_path, guardrails)
+        .unwrap_or_else(|| (branch.clone(), String::new()));
+    let title = options.title.map_or(prompt_title, str::to_string);
+    // Branches pushed to a fork remote are offered from the fork
+    let head_owner = remote_owner.filter(|_| remote != "origin");
+    let url = progress::step("Opening pull request", || {
+        forge.create_pull_request(&NewPullRequest {
+            head: &branch,
+            head_owner: head_owner.as_deref(),
+            base: &base,
+            title: &title,
+            body: &body,
+            draft: options.draft,
+        })
+    })?;
+    Ok(PrCreateResult {
+        branch,
+        remote,
+        base,
+        url,
+        existing: false,
+    })
+}
+
+/// Title and description for a pull request from the newest prompt given to
+/// an agent in `worktree`. None when there is no prompt file.
+pub fn description_from_prompt(
+    worktree: &Path,
+    guardrails: Guardrails,
+) -> Option<(String, String)> {
+    let prompt_file = latest_prompt_file(&worktree.join(ARTIFACTS_DIR).join("prompts"))?;
+    // Structured prompts keep the task apart from the system instructions
+    let task_file = crate::prompt::task_part_path(&prompt_file);
+    let path = if task_file.exists() {
+        task_file
+    } else {
+        prompt_file
+    };
+    let content = fs::read_to_string(path).ok()?;
+    let content = guardrails.unwrap(&content);
+    let title = crate::prompt::title_summary(content)?;
+    let (_, body) = crate::prompt::split_frontmatter(content);
+    Some((title, body.trim().to_string()))
+}
+
+/// The most recently written prompt file in `dir`, skipping the system and
+/// task parts of structured prompts.
+fn latest_prompt_file(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_prompt_file(path))
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+}
+
+fn is_prompt_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("PROMPT-"))
+        .and_then(|name| name.strip_suffix(".md"))
+        .is_some_and(|stem| !stem.ends_with(".system") && !stem.ends_with(".task"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,4 +643,33 @@ mod tests {
         assert!(err.to_string().contains("Failed to fetch"));
         assert!(err.to_string().contains("origin"));
     }
+
+    #[test]
+    fn description_comes_from_the_task_of_the_prompt() {
+        let worktree = tempfile::tempdir().unwrap();
+        let prompts = worktree.path().join(".workmux/prompts");
+        assert!(description_from_prompt(worktree.path(), Guardrails::default()).is_none());
+
+        fs::create_dir_all(&prompts).unwrap();
+        fs::write(
+            prompts.join("PROMPT-auth.md"),
+            "You are careful.\n\n# Fix login\n\nUsers get logged out.\n",
+        )
+        .unwrap();
+        fs::write(
+            prompts.join("PROMPT-auth.task.md"),
+            "# Fix login\n\nUsers get logged out.\n",
+        )
+        .unwrap();
+        fs::write(prompts.join("PROMPT-auth.system.md"), "You are careful.\n").unwrap();
+
+        let (title, body) =
+            description_from_prompt(worktree.path(), Guardrails::default()).unwrap();
+        assert_eq!(title, "Fix login");
+        assert_eq!(body, "# Fix login\n\nUsers get logged out.");
+
+        assert!(is_prompt_file(Path::new("PROMPT-feature.md")));
+        assert!(!is_prompt_file(Path::new("PROMPT-feature.task.md")));
+        assert!(!is_prompt_file(Path::new("notes.md")));
+    }
 }
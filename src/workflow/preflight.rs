@@ -0,0 +1,220 @@
+//! Repository checks run before the first `workmux add` in a repository.
+//!
+//! A fresh repository often lacks something `add` needs (a commit to branch
+//! from, a discoverable default branch, a remote). Checking up front reports
+//! every problem at once, with a fix for each, instead of failing halfway
+//! through creating a worktree.
+
+use anyhow::{Result, bail};
+
+use crate::config::{self, Config};
+use crate::progress;
+use crate::vcs::Vcs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// `add` cannot succeed until this is fixed
+    Error,
+    /// Some features won't work, but `add` can proceed
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightIssue {
+    pub severity: Severity,
+    pub problem: String,
+    pub fix: String,
+}
+
+/// What the checks need to know about the repository.
+struct RepoFacts {
+    is_git: bool,
+    has_commits: bool,
+    /// `main_branch` from config, with whether it exists
+    configured_main: Option<(String, bool)>,
+    default_branch_found: bool,
+    has_remotes: bool,
+}
+
+/// Inspect the repository and return every problem found.
+///
+/// `needs_remote` turns a missing remote into an error (e.g. for `--pr`).
+pub fn check(vcs: &dyn Vcs, config: &Config, needs_remote: bool) -> Vec<PreflightIssue> {
+    let has_commits = vcs.has_commits().unwrap_or(false);
+    let facts = RepoFacts {
+        is_git: vcs.name() == "git",
+        has_commits,
+        configured_main: config.main_branch.as_ref().map(|b| {
+            (
+                b.clone(),
+                has_commits && vcs.branch_exists(b).unwrap_or(false),
+            )
+        }),
+        default_branch_found: vcs.get_default_branch().is_ok(),
+        has_remotes: vcs.list_remotes().is_ok_and(|r| !r.is_empty()),
+    };
+    collect_issues(&facts, needs_remote)
+}
+
+fn collect_issues(facts: &RepoFacts, needs_remote: bool) -> Vec<PreflightIssue> {
+    let mut issues = Vec::new();
+    let error = |problem: String, fix: String| PreflightIssue {
+        severity: Severity::Error,
+        problem,
+        fix,
+    };
+
+    if !facts.has_commits {
+        issues.push(error(
+            "The repository has no commits yet".to_string(),
+            "git commit --allow-empty -m \"Initial commit\"".to_string(),
+        ));
+    } else {
+        match &facts.configured_main {
+            Some((branch, false)) => issues.push(error(
+                format!("main_branch '{}' from config does not exist", branch),
+                "Create the branch or correct 'main_branch' in .workmux.yaml".to_string(),
+            )),
+            Some((_, true)) => {}
+            None if !facts.default_branch_found => {
+                let mut fix = "Set 'main_branch' in .workmux.yaml".to_string();
+                if facts.is_git && facts.has_remotes {
+                    fix.push_str(", or run: git remote set-head origin --auto");
+                }
+                issues.push(error(
+                    "Could not determine the default branch".to_string(),
+                    fix,
+                ));
+            }
+            None => {}
+        }
+    }
+
+    if !facts.has_remotes {
+        let fix = if facts.is_git {
+            "git remote add origin <url>"
+        } else {
+            "jj git remote add origin <url>"
+        };
+        issues.push(PreflightIssue {
+            severity: if needs_remote {
+                Severity::Error
+            } else {
+                Severity::Warning
+            },
+            problem: "No remotes configured (needed for --pr and merge --push)".to_string(),
+            fix: fix.to_string(),
+        });
+    }
+
+    issues
+}
+
+/// Format issues as a list with the fix under each problem.
+pub fn format_issues(issues: &[PreflightIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| {
+            let marker = match issue.severity {
+                Severity::Error => "✗",
+                Severity::Warning => "!",
+            };
+            format!("  {} {}\n    Fix: {}", marker, issue.problem, issue.fix)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Run the checks once per repository before `add` changes anything.
+///
+/// Errors abort with the full list; warnings are printed and `add` goes on.
+/// Once the checks pass, the result is recorded in repository metadata so
+/// later runs skip them. A missing remote is checked every time `needs_remote`
+/// is set, since only those runs depend on it.
+pub fn ensure_ready(vcs: &dyn Vcs, config: &Config, needs_remote: bool) -> Result<()> {
+    let checked = vcs.get_repo_meta(config::PREFLIGHT_META).as_deref() == Some("ok");
+    if checked && !needs_remote {
+        return Ok(());
+    }
+
+    let issues = check(vcs, config, needs_remote);
+    if issues.iter().any(|i| i.severity == Severity::Error) {
+        bail!(
+            "This repository isn't ready for workmux:\n{}",
+            format_issues(&issues)
+        );
+    }
+    if !checked {
+        if !issues.is_empty() {
+            progress::warn(format_issues(&issues));
+        }
+        vcs.set_repo_meta(config::PREFLIGHT_META, "ok")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> RepoFacts {
+        RepoFacts {
+            is_git: true,
+            has_commits: true,
+            configured_main: None,
+            default_branch_found: true,
+            has_remotes: true,
+        }
+    }
+
+    #[test]
+    fn collect_issues_healthy_repo() {
+        assert!(collect_issues(&healthy(), true).is_empty());
+    }
+
+    #[test]
+    fn collect_issues_empty_repo_reports_all_problems() {
+        let facts = RepoFacts {
+            has_commits: false,
+            default_branch_found: false,
+            has_remotes: false,
+            ..healthy()
+        };
+        let issues = collect_issues(&facts, false);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0].problem.contains("no commits"));
+        // The default branch can't exist without commits; only one error
+        assert_eq!(issues[1].severity, Severity::Warning);
+        assert!(issues[1].problem.contains("No remotes"));
+    }
+
+    #[test]
+    fn collect_issues_default_branch_and_remote() {
+        let facts = RepoFacts {
+            default_branch_found: false,
+            ..healthy()
+        };
+        let issues = collect_issues(&facts, false);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].fix.contains("git remote set-head origin --auto"));
+
+        let facts = RepoFacts {
+            has_remotes: false,
+            ..healthy()
+        };
+        assert_eq!(collect_issues(&facts, true)[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn collect_issues_missing_configured_main_branch() {
+        let facts = RepoFacts {
+            configured_main: Some(("trunk".to_string(), false)),
+            default_branch_found: false,
+            ..healthy()
+        };
+        let issues = collect_issues(&facts, false);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].problem.contains("'trunk'"));
+    }
+}
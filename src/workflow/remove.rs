@@ -1,8 +1,10 @@
 use anyhow::{Context, Result, anyhow};
 
+use crate::plugin::{self, PluginEvent};
 use crate::sandbox;
 use tracing::{debug, info};
 
+use super::archive;
 use super::cleanup;
 use super::context::WorkflowContext;
 use super::types::RemoveResult;
@@ -12,9 +14,10 @@ pub fn remove(
     handle: &str,
     force: bool,
     keep_branch: bool,
+    archive: bool,
     context: &WorkflowContext,
 ) -> Result<RemoveResult> {
-    info!(handle = handle, force, keep_branch, "remove:start");
+    info!(handle = handle, force, keep_branch, archive, "remove:start");
 
     // Get worktree path and branch - this also validates that the worktree exists
     // Smart resolution: try handle first, then branch name
@@ -73,12 +76,51 @@ pub fn remove(
         ));
     }
 
+    // Locked worktrees are often on a drive that is unplugged right now;
+    // removing them would lose the branch's only checkout
+    if let Some(reason) = context.vcs.list_locked_workspaces().remove(&worktree_path) {
+        if !force {
+            return Err(anyhow!(
+                "Worktree '{}' is locked{}. Unlock it with 'workmux unlock {}' or use --force.",
+                actual_handle,
+                crate::vcs::describe_lock(&reason),
+                actual_handle
+            ));
+        }
+        context.vcs.unlock_workspace(&worktree_path)?;
+    }
+
     if worktree_path.exists() && context.vcs.has_uncommitted_changes(&worktree_path)? && !force {
         return Err(anyhow!(
             "Worktree has uncommitted changes. Use --force to delete anyway."
         ));
     }
 
+    // Plugins may veto the removal
+    plugin::dispatch(
+        &PluginEvent::RemoveRequested {
+            handle: actual_handle.to_string(),
+            branch: branch_name.clone(),
+            worktree_path: worktree_path.clone(),
+        },
+        context.vcs.as_ref(),
+    )?;
+
+    // Snapshot the worktree before anything is deleted. Fail closed: an
+    // archive that was asked for but couldn't be written shouldn't cost the work.
+    let archive_path = if archive {
+        let path = archive::archive_worktree(context, actual_handle, &branch_name, &worktree_path)
+            .with_context(|| format!("Failed to archive worktree '{}'", actual_handle))?;
+        context.progress.info(format!(
+            "Archived '{}' to {}",
+            actual_handle,
+            path.display()
+        ));
+        Some(path)
+    } else {
+        None
+    };
+
     // Note: Unmerged branch check removed - git branch -d/D handles this natively
     // The CLI provides a user-friendly confirmation prompt before calling this function
 
@@ -111,5 +153,6 @@ pub fn remove(
 
     Ok(RemoveResult {
         branch_removed: branch_name.to_string(),
+        archive: archive_path,
     })
 }
@@ -1,39 +1,41 @@
 use anyhow::{Context, Result, anyhow};
 use std::fs;
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
-use crate::config::{MuxMode, WindowConfig};
+use crate::config::{HookLocation, MuxMode, PostCreateHook, WindowConfig};
 use crate::multiplexer::{
-    CreateSessionParams, CreateWindowInSessionParams, CreateWindowParams, Multiplexer,
-    PaneSetupOptions,
+    CreateSessionParams, CreateWindowInSessionParams, CreateWindowParams, PaneSetupOptions,
+    PaneVars,
 };
-use crate::{cmd, config, git, prompt::Prompt};
+use crate::prompt::{Guardrails, Prompt};
+use crate::sandbox::VmBoot;
+use crate::shell::shell_escape;
+use crate::{cmd, config, git};
 use tracing::{debug, info};
 
 use fs_extra::dir as fs_dir;
 use fs_extra::file as fs_file;
 
+use super::context::WorkflowContext;
 use super::types::CreateResult;
 
 /// Sets up the terminal window, files, and hooks for a worktree.
 /// This is the shared logic between `create` and `open`.
 ///
 /// # Arguments
-/// * `mux` - The terminal multiplexer backend
+/// * `context` - Multiplexer, configuration and main worktree of the repository
 /// * `branch_name` - The git branch name (for logging/reference)
 /// * `handle` - The display name used for window naming
 /// * `worktree_path` - Path to the worktree directory
-/// * `config` - Configuration settings
 /// * `options` - Setup options (hooks, file ops, etc.)
 /// * `agent` - Optional agent override
 /// * `after_window` - Optional window ID to insert after (for grouping duplicates)
-#[allow(clippy::too_many_arguments)]
 pub fn setup_environment(
-    mux: &dyn Multiplexer,
+    context: &WorkflowContext,
     branch_name: &str,
     handle: &str,
     worktree_path: &Path,
-    config: &config::Config,
     options: &super::types::SetupOptions,
     agent: Option<&str>,
     after_window: Option<String>,
@@ -46,15 +48,17 @@ pub fn setup_environment(
         run_file_ops = options.run_file_ops,
         "setup_environment:start"
     );
-    let prefix = config.window_prefix();
+    let mux = context.mux.as_ref();
+    let config = &context.config;
+    let prefix = context.prefix.as_str();
     // Use main worktree root for file operations since source files live there
-    let repo_root = git::get_main_worktree_root()?;
+    let repo_root = &context.main_worktree_root;
 
     // Determine effective working directory (config-relative or worktree root)
     let effective_working_dir = options.working_dir.as_deref().unwrap_or(worktree_path);
 
     // Determine source root for file operations
-    let file_ops_source = options.config_root.as_deref().unwrap_or(&repo_root);
+    let file_ops_source = options.config_root.as_deref().unwrap_or(repo_root);
 
     // Perform file operations (copy and symlink) if requested
     if options.run_file_ops {
@@ -68,16 +72,15 @@ pub fn setup_environment(
 
     // Auto-symlink CLAUDE.local.md from main worktree if it exists and is gitignored
     if options.run_file_ops {
-        symlink_claude_local_md(&repo_root, effective_working_dir)
+        symlink_claude_local_md(repo_root, effective_working_dir)
             .context("Failed to auto-symlink CLAUDE.local.md")?;
     }
 
     // Run post-create hooks before opening tmux so the new window appears "ready"
     let mut hooks_run = 0;
-    if options.run_hooks
-        && let Some(post_create) = &config.post_create
-        && !post_create.is_empty()
-    {
+    let mut hook_vm_name: Option<String> = None;
+    let post_create = post_create_hooks(config, worktree_path);
+    if options.run_hooks && !post_create.is_empty() {
         hooks_run = post_create.len();
         // Resolve absolute paths for environment variables.
         // canonicalize() ensures symlinks are resolved and paths are absolute.
@@ -100,11 +103,23 @@ pub fn setup_environment(
             ("WM_PROJECT_ROOT", project_root_str.as_ref()),
             ("WM_CONFIG_DIR", config_dir_str.as_ref()),
         ];
-        for (idx, command) in post_create.iter().enumerate() {
+        for (idx, hook) in post_create.iter().enumerate() {
+            let command = hook.command();
             info!(branch = branch_name, step = idx + 1, total = hooks_run, command = %command, "setup_environment:hook start");
             info!(command = %command, "Running post-create hook {}/{}", idx + 1, hooks_run);
-            cmd::shell_command_with_env(command, effective_working_dir, &hook_env)
-                .with_context(|| format!("Failed to run post-create command: '{}'", command))?;
+            if hook.location() == HookLocation::Guest && uses_lima_sandbox(config) {
+                // Guest hooks set up the VM the agent will run in, so boot it now
+                if hook_vm_name.is_none() {
+                    hook_vm_name = Some(match &options.vm_boot {
+                        Some(boot) => boot.wait()?,
+                        None => crate::sandbox::ensure_lima_vm(config, worktree_path)?,
+                    });
+                }
+                run_guest_hook(command, effective_working_dir, &hook_env)
+            } else {
+                cmd::shell_command_with_env(command, effective_working_dir, &hook_env)
+            }
+            .with_context(|| format!("Failed to run post-create command: '{}'", command))?;
             info!(branch = branch_name, step = idx + 1, total = hooks_run, command = %command, "setup_environment:hook complete");
         }
         info!(
@@ -142,25 +157,47 @@ pub fn setup_environment(
     // Pre-boot Lima VM if needed BEFORE creating the tmux window.
     // This ensures the user sees VM boot progress in their terminal
     // and the window only appears once the VM is ready.
-    let lima_vm_name = pre_boot_lima_vm(
-        mux,
-        config,
-        &all_resolved_panes,
-        effective_working_dir,
-        worktree_path,
-        options,
-        agent,
-    )?;
+    let lima_vm_name = match hook_vm_name {
+        Some(vm_name) => Some(vm_name),
+        // Already booting; the panes that run in the VM wait for it
+        None if options.vm_boot.is_some() => None,
+        None => pre_boot_lima_vm(
+            mux,
+            config,
+            &all_resolved_panes,
+            effective_working_dir,
+            worktree_path,
+            options,
+            agent,
+        )?,
+    };
 
+    let pane_vars = pane_vars(
+        branch_name,
+        handle,
+        worktree_path,
+        options.prompt_file_path.clone(),
+    );
     let pane_setup_options = PaneSetupOptions {
         run_commands: options.run_pane_commands,
         prompt_file_path: options.prompt_file_path.as_deref(),
         worktree_root: Some(worktree_path),
         lima_vm_name: lima_vm_name.as_deref(),
+        lima_boot: options.vm_boot.as_deref(),
+        vars: Some(&pane_vars),
+        log_dir: None,
+    };
+
+    // Transcripts of panes with `log_output`, kept after the worktree is removed
+    let log_dir = if all_panes.iter().any(|p| p.log_output) {
+        Some(pane_log_dir(repo_root, handle)?)
+    } else {
+        None
     };
 
     // Track the focus pane across all windows
     let mut focus_pane_id: Option<String> = None;
+    let mut prompted_pane_ids: Vec<String> = Vec::new();
 
     match options.mode {
         MuxMode::Window => {
@@ -191,13 +228,17 @@ pub fn setup_environment(
                     &initial_pane_id,
                     &resolved_panes,
                     effective_working_dir,
-                    pane_setup_options,
+                    PaneSetupOptions {
+                        log_dir: log_dir.as_deref(),
+                        ..pane_setup_options
+                    },
                     config,
                     agent,
                 )
                 .context("Failed to setup panes")?;
 
             focus_pane_id = Some(result.focus_pane_id);
+            prompted_pane_ids = result.prompted_pane_ids;
         }
         MuxMode::Session => {
             let session_full_name = crate::multiplexer::util::prefixed(prefix, handle);
@@ -244,16 +285,25 @@ pub fn setup_environment(
                     pane_id
                 };
 
+                // One directory per window, as pane indices restart in each
+                let window_log_dir = log_dir.as_ref().map(|dir| match &window_plan.name {
+                    Some(name) => dir.join(name),
+                    None => dir.join(format!("window-{}", i)),
+                });
                 let result = mux
                     .setup_panes(
                         &initial_pane_id,
                         &resolved_panes,
                         effective_working_dir,
-                        pane_setup_options.clone(),
+                        PaneSetupOptions {
+                            log_dir: window_log_dir.as_deref(),
+                            ..pane_setup_options.clone()
+                        },
                         config,
                         agent,
                     )
                     .context("Failed to setup panes")?;
+                prompted_pane_ids.extend(result.prompted_pane_ids);
 
                 // Track focus: last window with a focus: true pane wins.
                 // If no pane has focus: true, use the first window's default.
@@ -272,6 +322,18 @@ pub fn setup_environment(
         "setup_environment:panes configured"
     );
 
+    // Agents that never set a title show what they were asked to do
+    if let Some(prompt_file) = &options.prompt_file_path
+        && !prompted_pane_ids.is_empty()
+    {
+        record_prompt_title(
+            mux,
+            &prompted_pane_ids,
+            &effective_working_dir.join(prompt_file),
+            Guardrails::from_config(config),
+        );
+    }
+
     // Focus the configured pane and optionally switch to the window/session.
     // select_pane automatically selects the containing window in tmux.
     if options.focus_window {
@@ -289,19 +351,134 @@ pub fn setup_environment(
     Ok(CreateResult {
         worktree_path: worktree_path.to_path_buf(),
         branch_name: branch_name.to_string(),
+        handle: handle.to_string(),
         post_create_hooks_run: hooks_run,
         base_branch: None,
         did_switch: false,
+        focus_pane_id: Some(focus_pane_id),
+        // A background boot has been waited for by the panes by now
+        sandbox_vm: lima_vm_name.or_else(|| options.vm_boot.as_ref()?.wait().ok()),
     })
 }
 
+/// Store a summary of the prompt as the title of the agents in `pane_ids`.
+/// Best-effort: a missing prompt file only means no title.
+fn record_prompt_title(
+    mux: &dyn crate::multiplexer::Multiplexer,
+    pane_ids: &[String],
+    prompt_file: &Path,
+    guardrails: Guardrails,
+) {
+    // Structured prompts keep the task apart from the system instructions
+    let task_file = crate::prompt::task_part_path(prompt_file);
+    let path = if task_file.exists() {
+        &task_file
+    } else {
+        prompt_file
+    };
+    let Some(title) = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| crate::prompt::title_summary(guardrails.unwrap(&content)))
+    else {
+        return;
+    };
+    debug!(title = %title, "setup_environment:recording prompt title");
+    for pane_id in pane_ids {
+        crate::state::persist_agent_update(mux, pane_id, None, Some(title.clone()));
+    }
+}
+
+/// Configured post-create hooks, preceded by the commands that install the
+/// repository's git hook dependencies when `git_hooks.install` is set.
+fn post_create_hooks(config: &config::Config, worktree_path: &Path) -> Vec<PostCreateHook> {
+    let mut hooks: Vec<PostCreateHook> = Vec::new();
+    if config.git_hooks.install() {
+        hooks.extend(
+            git::hook_install_commands(worktree_path)
+                .into_iter()
+                .map(PostCreateHook::Command),
+        );
+    }
+    hooks.extend(config.post_create.iter().flatten().cloned());
+    hooks
+}
+
+/// Root of the pane transcripts: `<state>/workmux/logs`.
+pub fn pane_logs_root() -> Result<PathBuf> {
+    Ok(crate::state::store::get_state_dir()?
+        .join("workmux")
+        .join("logs"))
+}
+
+/// Values for `{{name}}` placeholders in the worktree's pane commands.
+pub(super) fn pane_vars(
+    branch_name: &str,
+    handle: &str,
+    worktree_path: &Path,
+    prompt_file: Option<PathBuf>,
+) -> PaneVars {
+    PaneVars {
+        handle: handle.to_string(),
+        branch: branch_name.to_string(),
+        base: git::get_branch_base_in(branch_name, Some(worktree_path))
+            .or_else(|_| git::get_default_branch_in(Some(worktree_path)))
+            .unwrap_or_default(),
+        worktree: worktree_path
+            .canonicalize()
+            .unwrap_or_else(|_| worktree_path.to_path_buf()),
+        prompt_file,
+    }
+}
+
+/// Directory for pane transcripts: `<state>/workmux/logs/<project>/<handle>`.
+pub(super) fn pane_log_dir(repo_root: &Path, handle: &str) -> Result<PathBuf> {
+    Ok(pane_logs_root()?
+        .join(config::project_name(repo_root))
+        .join(handle))
+}
+
+fn uses_lima_sandbox(config: &config::Config) -> bool {
+    config.sandbox.is_enabled()
+        && matches!(
+            config.sandbox.backend(),
+            crate::config::SandboxBackend::Lima
+        )
+}
+
+/// Run a `where: guest` hook inside the Lima VM through the sandbox
+/// supervisor, which streams the hook's output to this terminal.
+fn run_guest_hook(command: &str, working_dir: &Path, env_vars: &[(&str, &str)]) -> Result<()> {
+    // The supervisor only forwards its own variables into the VM
+    let exports: String = env_vars
+        .iter()
+        .map(|(key, value)| format!("export {}='{}'; ", key, shell_escape(value)))
+        .collect();
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("workmux"));
+    let status = std::process::Command::new(exe)
+        .args(["sandbox", "run"])
+        .arg(working_dir)
+        .arg("--")
+        .arg(format!("{}{}", exports, command))
+        .current_dir(working_dir)
+        .status()
+        .with_context(|| format!("Failed to run guest hook: {}", command))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "Guest hook failed with exit code {}: {}",
+            status.code().unwrap_or(-1),
+            command
+        ));
+    }
+    Ok(())
+}
+
 /// Pre-boot a Lima VM if sandbox is enabled with the Lima backend and any
 /// pane requires sandboxing. Must be called BEFORE creating the tmux window
 /// so the user sees VM boot progress in their terminal.
 ///
 /// Returns the VM name if booted, None otherwise.
 #[allow(clippy::too_many_arguments)]
-fn pre_boot_lima_vm(
+pub(super) fn pre_boot_lima_vm(
     mux: &dyn crate::multiplexer::Multiplexer,
     config: &config::Config,
     panes: &[config::PaneConfig],
@@ -310,21 +487,69 @@ fn pre_boot_lima_vm(
     options: &super::types::SetupOptions,
     agent: Option<&str>,
 ) -> Result<Option<String>> {
-    if !config.sandbox.is_enabled()
-        || !matches!(
-            config.sandbox.backend(),
-            crate::config::SandboxBackend::Lima
-        )
+    if !uses_lima_sandbox(config)
+        || !panes_need_lima(mux, config, panes, working_dir, options, agent)?
     {
         return Ok(None);
     }
 
+    info!("pre-booting Lima VM before window creation");
+    let vm_name = crate::sandbox::ensure_lima_vm(config, worktree_path)?;
+    Ok(Some(vm_name))
+}
+
+/// Start booting the Lima VM in the background when a pane of a new
+/// worktree will run in it, so the boot overlaps creating the worktree and
+/// running its hooks. The main worktree stands in for the new one, which
+/// doesn't exist yet.
+pub(super) fn start_vm_boot(
+    context: &WorkflowContext,
+    options: &super::types::SetupOptions,
+    agent: Option<&str>,
+) -> Result<Option<Arc<VmBoot>>> {
+    let config = &context.config;
+    if !uses_lima_sandbox(config) {
+        return Ok(None);
+    }
+    let panes: Vec<config::PaneConfig> = match &config.windows {
+        Some(windows) => windows
+            .iter()
+            .flat_map(|w| w.panes.as_deref().unwrap_or(&[]).iter().cloned())
+            .collect(),
+        None => config.panes.clone().unwrap_or_default(),
+    };
+    let panes = resolve_pane_configuration(&panes, agent);
+    if !panes_need_lima(
+        context.mux.as_ref(),
+        config,
+        &panes,
+        &context.main_worktree_root,
+        options,
+        agent,
+    )? {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(VmBoot::start(
+        config,
+        &context.main_worktree_root,
+    ))))
+}
+
+/// Whether any of `panes` will run its command in the Lima VM.
+fn panes_need_lima(
+    mux: &dyn crate::multiplexer::Multiplexer,
+    config: &config::Config,
+    panes: &[config::PaneConfig],
+    working_dir: &Path,
+    options: &super::types::SetupOptions,
+    agent: Option<&str>,
+) -> Result<bool> {
     let effective_agent = agent.or(config.agent.as_deref());
     let shell = mux.get_default_shell()?;
 
     // Check if any pane will actually need Lima wrapping by resolving
     // commands the same way setup_panes does (respects run_commands flag).
-    let any_pane_needs_lima = panes.iter().any(|pane_config| {
+    Ok(panes.iter().any(|pane_config| {
         let resolved = crate::multiplexer::util::resolve_pane_command(
             pane_config.command.as_deref(),
             options.run_pane_commands,
@@ -332,6 +557,7 @@ fn pre_boot_lima_vm(
             working_dir,
             effective_agent,
             &shell,
+            None,
         );
         if resolved.is_none() {
             return false;
@@ -345,15 +571,7 @@ fn pre_boot_lima_vm(
             crate::config::SandboxTarget::All => true,
             crate::config::SandboxTarget::Agent => is_agent_pane,
         }
-    });
-
-    if !any_pane_needs_lima {
-        return Ok(None);
-    }
-
-    info!("pre-booting Lima VM before window creation");
-    let vm_name = crate::sandbox::ensure_lima_vm(config, worktree_path)?;
-    Ok(Some(vm_name))
+    }))
 }
 
 pub fn resolve_pane_configuration(
@@ -391,6 +609,10 @@ pub fn resolve_pane_configuration(
         size: None,
         percentage: None,
         target: None,
+        depends_on: None,
+        wait_for: None,
+        history_limit: None,
+        log_output: false,
     }]
 }
 
@@ -574,18 +796,42 @@ pub fn handle_file_operations(
     Ok(())
 }
 
+/// Copy `files` into the attachments directory of `worktree` (see
+/// [`artifacts::attach`](super::artifacts::attach)) and reference them at
+/// the end of `prompt`, in the syntax of `agent`.
+pub fn attach_files(
+    worktree: &Path,
+    prompt: &Prompt,
+    files: &[PathBuf],
+    agent: Option<&str>,
+) -> Result<Prompt> {
+    if files.is_empty() {
+        return Ok(prompt.clone());
+    }
+    let paths = super::artifacts::attach(worktree, files)?;
+    Ok(Prompt::Inline(crate::prompt::with_attachments(
+        &prompt.read_content()?,
+        &paths,
+        agent,
+    )))
+}
+
 /// Write a prompt file for agent consumption.
 ///
-/// When `working_dir` is provided, writes to `<working_dir>/.workmux/PROMPT-<branch>.md`
-/// so the prompt is accessible inside container sandboxes. Also adds `.workmux/` to
-/// `.git/info/exclude` to avoid polluting git status.
+/// When `working_dir` is provided, writes to `<working_dir>/.workmux/prompts/PROMPT-<branch>.md`
+/// (see [`artifacts`](super::artifacts)) so the prompt is accessible inside container
+/// sandboxes without showing up in git status.
+///
+/// When `working_dir` is None, writes to a temp directory (for an `open` of a worktree
+/// whose path can't be resolved up front).
 ///
-/// When `working_dir` is None, writes to a temp directory (legacy behavior for open command
-/// which doesn't know the worktree path at prompt write time).
+/// The task is wrapped in `guardrails`, so the file holds the exact text the
+/// agent receives.
 pub fn write_prompt_file(
     working_dir: Option<&Path>,
     branch_name: &str,
     prompt: &Prompt,
+    guardrails: Guardrails,
 ) -> Result<PathBuf> {
     let content = match prompt {
         Prompt::Inline(text) => text.clone(),
@@ -599,56 +845,44 @@ pub fn write_prompt_file(
 
     let prompt_path = if let Some(dir) = working_dir {
         // Write to .workmux/ inside the worktree so it's accessible in container sandbox
-        let workmux_dir = dir.join(".workmux");
-        fs::create_dir_all(&workmux_dir).with_context(|| {
-            format!("Failed to create .workmux directory in '{}'", dir.display())
-        })?;
-
-        // Add .workmux/ to git exclude to avoid polluting git status
-        // In worktrees, .git is a file pointing to the real git dir, so we need to resolve it
-        if let Some(exclude_path) = resolve_git_exclude_path(dir)
-            && exclude_path.exists()
-            && let Ok(content) = fs::read_to_string(&exclude_path)
-            && !content.lines().any(|line| line.trim() == ".workmux/")
-            && let Ok(mut file) = fs::OpenOptions::new().append(true).open(&exclude_path)
-        {
-            use std::io::Write;
-            let _ = writeln!(file, "\n# workmux prompt files\n.workmux/");
-        }
-
         let prompt_filename = format!("PROMPT-{}.md", safe_branch_name);
-        workmux_dir.join(prompt_filename)
+        super::artifacts::prompts_dir(dir)?.join(prompt_filename)
     } else {
-        // Legacy: write to temp directory for open command
+        // No worktree to write into: use the temp directory
         let prompt_filename = format!("workmux-prompt-{}.md", safe_branch_name);
         std::env::temp_dir().join(prompt_filename)
     };
 
-    fs::write(&prompt_path, content)
-        .with_context(|| format!("Failed to write prompt file '{}'", prompt_path.display()))?;
+    // Structured prompts are also written as separate system/task parts for
+    // agents that accept a system prompt (see `rewrite_agent_command`)
+    let system_path = crate::prompt::system_part_path(&prompt_path);
+    let task_path = crate::prompt::task_part_path(&prompt_path);
+    let context_dir = match working_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let content = match crate::prompt::split_structured(&content, &context_dir)? {
+        Some(mut parts) => {
+            parts.task = guardrails.wrap(&parts.task);
+            write_file(&system_path, &parts.system)?;
+            write_file(&task_path, &parts.task)?;
+            parts.combined()
+        }
+        None => {
+            // Drop parts left over from an earlier structured prompt
+            let _ = fs::remove_file(&system_path);
+            let _ = fs::remove_file(&task_path);
+            guardrails.wrap(&content)
+        }
+    };
+
+    write_file(&prompt_path, &content)?;
     Ok(prompt_path)
 }
 
-/// Resolve the path to .git/info/exclude, handling worktrees correctly.
-/// In a worktree, .git is a file containing "gitdir: /path/to/.git/worktrees/name",
-/// so we need to find the actual git directory.
-fn resolve_git_exclude_path(dir: &Path) -> Option<PathBuf> {
-    let git_path = dir.join(".git");
-
-    if git_path.is_dir() {
-        // Regular git repo: .git is a directory
-        Some(git_path.join("info/exclude"))
-    } else if git_path.is_file() {
-        // Git worktree: .git is a file pointing to the real git dir
-        // Format: "gitdir: /path/to/main/.git/worktrees/name"
-        let content = fs::read_to_string(&git_path).ok()?;
-        let gitdir = content.strip_prefix("gitdir: ")?.trim();
-        // Go up two levels from worktrees/<name> to get to .git/
-        let main_git = Path::new(gitdir).ancestors().nth(2)?;
-        Some(main_git.join("info/exclude"))
-    } else {
-        None
-    }
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write prompt file '{}'", path.display()))
 }
 
 #[cfg(test)]
@@ -664,6 +898,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
 
         let result = resolve_pane_configuration(&original_panes, None);
@@ -680,6 +918,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
 
         let result = resolve_pane_configuration(&original_panes, Some("claude"));
@@ -697,6 +939,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
             config::PaneConfig {
                 command: Some("npm run dev".to_string()),
@@ -705,6 +951,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
         ];
 
@@ -722,6 +972,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
 
         let result = resolve_pane_configuration(&original_panes, Some("claude"));
@@ -755,7 +1009,11 @@ mod tests {
             working_dir: None,
             config_root: None,
             open_if_exists: false,
+            on_collision: crate::config::WindowCollision::default(),
             mode: crate::config::MuxMode::default(),
+            vm_boot: None,
+            guardrails: true,
+            attachments: Vec::new(),
         }
     }
 
@@ -768,6 +1026,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(false); // pane commands disabled
@@ -791,6 +1053,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(None); // no agent
         let options = make_options_with_prompt(true);
@@ -815,6 +1081,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
             config::PaneConfig {
                 command: Some("clear".to_string()),
@@ -823,6 +1093,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
         ];
         let config = make_config_with_agent(Some("claude"));
@@ -844,6 +1118,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(true);
@@ -861,6 +1139,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(Some("claude"));
         let options = make_options_with_prompt(true);
@@ -878,6 +1160,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(Some("claude")); // config says claude
         let options = make_options_with_prompt(true);
@@ -902,6 +1188,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
             config::PaneConfig {
                 command: Some("claude --verbose".to_string()), // matches
@@ -910,6 +1200,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
         ];
         let config = make_config_with_agent(Some("claude"));
@@ -928,6 +1222,10 @@ mod tests {
             size: None,
             percentage: None,
             target: None,
+            depends_on: None,
+            wait_for: None,
+            history_limit: None,
+            log_output: false,
         }];
         let config = make_config_with_agent(None); // no global agent
         let options = make_options_with_prompt(true);
@@ -947,6 +1245,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
             config::PaneConfig {
                 command: Some("codex --yolo".to_string()),
@@ -955,6 +1257,10 @@ mod tests {
                 size: None,
                 percentage: None,
                 target: None,
+                depends_on: None,
+                wait_for: None,
+                history_limit: None,
+                log_output: false,
             },
         ];
 
@@ -975,7 +1281,7 @@ mod tests {
         let prompt = Prompt::Inline("test prompt content".to_string());
 
         // Test legacy mode (None working_dir)
-        let path = super::write_prompt_file(None, branch_name, &prompt)
+        let path = super::write_prompt_file(None, branch_name, &prompt, Guardrails::default())
             .expect("Should create prompt file");
 
         // Verify filename does not contain slashes
@@ -1007,11 +1313,16 @@ mod tests {
         let branch_name = "feature/test";
         let prompt = Prompt::Inline("test prompt".to_string());
 
-        let path = super::write_prompt_file(Some(temp.path()), branch_name, &prompt)
-            .expect("Should create prompt file");
+        let path = super::write_prompt_file(
+            Some(temp.path()),
+            branch_name,
+            &prompt,
+            Guardrails::default(),
+        )
+        .expect("Should create prompt file");
 
-        // Verify it's in .workmux/ directory
-        assert!(path.starts_with(temp.path().join(".workmux")));
+        // Verify it's in the .workmux/prompts/ directory
+        assert!(path.starts_with(temp.path().join(".workmux/prompts")));
         assert!(
             path.file_name()
                 .unwrap()
@@ -1024,6 +1335,60 @@ mod tests {
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(content, "test prompt");
     }
+
+    #[test]
+    fn write_prompt_file_splits_structured_prompt() {
+        use crate::prompt::{Prompt, system_part_path, task_part_path};
+        use tempfile::TempDir;
+
+        let temp = TempDir::new().unwrap();
+        let prompt = Prompt::Inline("---\nsystem: Be terse.\n---\nFix it".to_string());
+
+        let path =
+            super::write_prompt_file(Some(temp.path()), "feature", &prompt, Guardrails::default())
+                .expect("Should create prompt file");
+
+        let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+        assert_eq!(read(&path), "Be terse.\n\nFix it");
+        assert_eq!(read(&system_part_path(&path)), "Be terse.");
+        assert_eq!(read(&task_part_path(&path)), "Fix it");
+
+        // A later plain prompt for the same branch removes the stale parts
+        let prompt = Prompt::Inline("plain".to_string());
+        super::write_prompt_file(Some(temp.path()), "feature", &prompt, Guardrails::default())
+            .unwrap();
+        assert!(!system_part_path(&path).exists());
+        assert!(!task_part_path(&path).exists());
+    }
+
+    #[test]
+    fn write_prompt_file_wraps_the_task_in_guardrails() {
+        use crate::prompt::{Prompt, task_part_path};
+        use tempfile::TempDir;
+
+        let temp = TempDir::new().unwrap();
+        let config = config::Config {
+            prompt_prefix: Some("Don't edit gen/.".to_string()),
+            prompt_suffix: Some("Run the tests.".to_string()),
+            ..Default::default()
+        };
+        let guardrails = Guardrails::from_config(&config);
+        let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+
+        let prompt = Prompt::Inline("Fix it".to_string());
+        let path =
+            super::write_prompt_file(Some(temp.path()), "feature", &prompt, guardrails).unwrap();
+        assert_eq!(read(&path), "Don't edit gen/.\n\nFix it\n\nRun the tests.");
+
+        let prompt = Prompt::Inline("---\nsystem: Be terse.\n---\nFix it".to_string());
+        let path =
+            super::write_prompt_file(Some(temp.path()), "feature", &prompt, guardrails).unwrap();
+        assert_eq!(
+            read(&task_part_path(&path)),
+            "Don't edit gen/.\n\nFix it\n\nRun the tests."
+        );
+        assert!(read(&path).starts_with("Be terse.\n\nDon't edit gen/."));
+    }
 }
 
 /// Symlink CLAUDE.local.md from main worktree if it exists and is gitignored.
@@ -1,9 +1,11 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::config::MuxMode;
+use crate::config::{MuxMode, WindowCollision};
 use crate::github::PrSummary;
 use crate::multiplexer::AgentStatus;
 use crate::prompt::Prompt;
+use crate::sandbox::VmBoot;
 
 /// Arguments for creating a worktree
 pub struct CreateArgs<'a> {
@@ -14,16 +16,35 @@ pub struct CreateArgs<'a> {
     pub prompt: Option<&'a Prompt>,
     pub options: SetupOptions,
     pub agent: Option<&'a str>,
+    /// Patch, mailbox or bundle to apply before anything runs in the worktree
+    pub apply: Option<&'a Path>,
 }
 
 /// Result of creating a worktree
 pub struct CreateResult {
     pub worktree_path: PathBuf,
     pub branch_name: String,
+    /// Handle the window or session is named after (may carry a `-N` suffix)
+    pub handle: String,
     pub post_create_hooks_run: usize,
     pub base_branch: Option<String>,
     /// True if we switched to an existing window instead of creating a new one
     pub did_switch: bool,
+    /// Pane focused once setup finished (None when switching to an existing window)
+    pub focus_pane_id: Option<String>,
+    /// Lima VM booted for sandboxed panes
+    pub sandbox_vm: Option<String>,
+}
+
+/// Remote operations to run as part of a merge
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeRemoteSync {
+    /// Push the target branch after merging
+    pub push: bool,
+    /// Delete the merged branch on its remote
+    pub delete_remote: bool,
+    /// Force-push (with lease) the feature branch after rebasing it
+    pub push_rebased: bool,
 }
 
 /// Result of merging a worktree
@@ -31,11 +52,27 @@ pub struct MergeResult {
     pub branch_merged: String,
     pub main_branch: String,
     pub had_staged_changes: bool,
+    /// Worktree of the merged branch (removed unless kept)
+    pub worktree_path: PathBuf,
+}
+
+/// Result of `workmux merge --to-upstream`
+pub struct UpstreamResult {
+    pub branch: String,
+    /// Remote of the canonical repository the branch was rebased onto
+    pub upstream: String,
+    /// Branch of `upstream` the branch was rebased onto
+    pub target: String,
+    /// Remote the branch was pushed to
+    pub fork: String,
+    pub worktree_path: PathBuf,
 }
 
 /// Result of removing a worktree
 pub struct RemoveResult {
     pub branch_removed: String,
+    /// Archive written before the removal (`--archive`)
+    pub archive: Option<PathBuf>,
 }
 
 /// Deferred cleanup operations to run after window close.
@@ -80,8 +117,17 @@ pub struct SetupOptions {
     pub config_root: Option<PathBuf>,
     /// If true, open existing worktree instead of failing when it already exists.
     pub open_if_exists: bool,
+    /// What to do when a window or session with the target name already exists.
+    pub on_collision: WindowCollision,
     /// Mode for tmux operations: window (default) or session
     pub mode: MuxMode,
+    /// Lima VM already booting in the background, waited for by the panes
+    /// that run in it
+    pub vm_boot: Option<Arc<VmBoot>>,
+    /// Wrap the prompt in the configured `prompt_prefix`/`prompt_suffix`
+    pub guardrails: bool,
+    /// Files copied into the worktree and referenced at the end of the prompt
+    pub attachments: Vec<PathBuf>,
 }
 
 impl SetupOptions {
@@ -97,7 +143,11 @@ impl SetupOptions {
             working_dir: None,
             config_root: None,
             open_if_exists: false,
+            on_collision: WindowCollision::default(),
             mode: MuxMode::default(),
+            vm_boot: None,
+            guardrails: true,
+            attachments: Vec::new(),
         }
     }
 
@@ -112,7 +162,11 @@ impl SetupOptions {
             working_dir: None,
             config_root: None,
             open_if_exists: false,
+            on_collision: WindowCollision::default(),
             mode: MuxMode::default(),
+            vm_boot: None,
+            guardrails: true,
+            attachments: Vec::new(),
         }
     }
 
@@ -133,7 +187,11 @@ impl SetupOptions {
             working_dir: None,
             config_root: None,
             open_if_exists: false,
+            on_collision: WindowCollision::default(),
             mode: MuxMode::default(),
+            vm_boot: None,
+            guardrails: true,
+            attachments: Vec::new(),
         }
     }
 }
@@ -141,6 +199,8 @@ impl SetupOptions {
 /// Summary of agent statuses for a worktree (may have multiple agents)
 pub struct AgentStatusSummary {
     pub statuses: Vec<AgentStatus>,
+    /// Worst-of rollup for session-mode worktrees (None in window mode)
+    pub session_status: Option<AgentStatus>,
 }
 
 /// List all worktrees with their status
@@ -151,4 +211,7 @@ pub struct WorktreeInfo {
     pub has_unmerged: bool,
     pub pr_info: Option<PrSummary>,
     pub agent_status: Option<AgentStatusSummary>,
+    /// Lock reason (empty when none was given) for worktrees locked with
+    /// `workmux lock`
+    pub locked: Option<String>,
 }
@@ -67,6 +67,35 @@ def test_send_error_text_and_file_conflict(
     assert result.exit_code != 0
 
 
+def test_send_refuses_main_worktree_when_protected(
+    mux_server: MuxEnvironment, workmux_exe_path: Path, mux_repo_path: Path
+):
+    """Send refuses the main worktree with protect_main unless --allow-main."""
+    env = mux_server
+    write_workmux_config(mux_repo_path)
+    config_path = mux_repo_path / ".workmux.yaml"
+    config_path.write_text(config_path.read_text() + "protect_main: true\n")
+
+    result = run_workmux_command(
+        env,
+        workmux_exe_path,
+        mux_repo_path,
+        "send main hello",
+        expect_fail=True,
+    )
+    assert "protect_main" in result.stderr
+
+    # With --allow-main the guard is skipped; it then fails for lack of an agent
+    result = run_workmux_command(
+        env,
+        workmux_exe_path,
+        mux_repo_path,
+        "send main hello --allow-main",
+        expect_fail=True,
+    )
+    assert "No agent running" in result.stderr
+
+
 def test_send_inline_text_to_agent(
     mux_server: MuxEnvironment, workmux_exe_path: Path, mux_repo_path: Path
 ):